@@ -2,6 +2,7 @@ use core::fmt;
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     ops::Range,
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -10,18 +11,22 @@ use macaddr::MacAddr;
 use secrecy::{CloneableSecret, SecretBox, SerializableSecret, zeroize::Zeroize};
 use serde::{Deserialize, Serialize};
 use veloce::{
+    common::geo_area::{Circle, GeoArea, GeoPosition, Shape},
     security::{
-        permission::Permission,
+        permission::{AID, Permission},
         privacy::PrivacyStrategy,
+        signer_policy::{SignerIdentifierPolicy, SignerPolicy},
         ssp::{
             SspTrait,
             cam::{CamPermission, CamSsp},
             denm::{DenmPermission, DenmSsp, DenmSspV1, DenmSspV2},
         },
     },
-    types::Power,
+    time::{Duration, LeapSeconds, LeapSecondsError},
+    types::{Angle, Distance, Power, degree, meter},
     wire::{EthernetAddress, StationType},
 };
+use veloce_gnss::{CsvColumns, ReplayFormat};
 use veloce_nxp_phy::{NxpChannel, NxpConfig, NxpRadio, NxpWirelessChannel};
 
 use crate::utils::{UtilError, load_file};
@@ -42,6 +47,10 @@ pub enum ConfigError {
     InvalidStationType,
     /// No replay file provided.
     NoGnssReplayFile,
+    /// Invalid replay playback rate.
+    InvalidGnssReplayRate,
+    /// No serial device provided.
+    NoGnssSerialDevice,
     /// Invalid NXP slot.
     InvalidNxpSlot,
     /// Invalid NXP wireless channel number.
@@ -55,6 +64,10 @@ pub enum ConfigError {
     /// No TunTap interface name provided.
     #[cfg(any(target_os = "linux", target_os = "android"))]
     NoTunTapName,
+    /// Error while reading the leap seconds file.
+    LeapSecondsFileLoad(UtilError),
+    /// Invalid leap seconds file content.
+    InvalidLeapSeconds(LeapSecondsError),
 }
 
 impl fmt::Display for ConfigError {
@@ -68,6 +81,10 @@ impl fmt::Display for ConfigError {
             ConfigError::UnsupportedLLAddress => write!(f, "Unsupported LL address format"),
             ConfigError::InvalidStationType => write!(f, "Invalid station type"),
             ConfigError::NoGnssReplayFile => write!(f, "No GNSS replay file provided"),
+            ConfigError::InvalidGnssReplayRate => {
+                write!(f, "Invalid GNSS replay rate. Should be strictly positive")
+            }
+            ConfigError::NoGnssSerialDevice => write!(f, "No GNSS serial device provided"),
             ConfigError::InvalidNxpSlot => write!(f, "Invalid NXP channel slot. Should be 0 or 1"),
             ConfigError::InvalidNxpWirelessChannel => {
                 write!(f, "Invalid NXP wireless channel number")
@@ -77,6 +94,10 @@ impl fmt::Display for ConfigError {
             ConfigError::NoUdpPeerAddress => write!(f, "No peer UDP address provided"),
             #[cfg(any(target_os = "linux", target_os = "android"))]
             ConfigError::NoTunTapName => write!(f, "No TunTap interface name provided"),
+            ConfigError::LeapSecondsFileLoad(e) => {
+                write!(f, "Leap seconds file load error: {}", e)
+            }
+            ConfigError::InvalidLeapSeconds(e) => write!(f, "Invalid leap seconds file: {}", e),
         }
     }
 }
@@ -201,22 +222,50 @@ pub enum FileGnssConfigMode {
     Fixed,
     Gpsd,
     Replay,
+    Serial,
+    Ubx,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub enum FileGnssReplayFormat {
+    Auto,
+    Nmea,
+    Gpsd,
+    Gpx,
+    Csv,
 }
 
 /// Configuration values for the GNSS client.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct FileGnssConfig {
-    /// GNSS client driver mode. Either "fixed", "gpsd" or "replay".
+    /// GNSS client driver mode. Either "fixed", "gpsd", "replay", "serial" or "ubx".
     /// Default is "gpsd".
     /// Use "replay" to replay previously recorded GPS positions in `replay_file`.
     /// Use "fixed" to use a fixed position with `latitude`, `longitude` and `altitude`
     /// values below.
+    /// Use "serial" to read NMEA sentences from the `serial_device` receiver.
+    /// Use "ubx" to read u-blox UBX messages from the `serial_device` receiver.
     pub mode: Option<FileGnssConfigMode>,
     /// GPSD server address and port. Default is "127.0.0.1:2947".
     pub gpsd_address: Option<SocketAddr>,
-    /// Path of the replay file containing NMEA sentences.
+    /// Path of the replay file containing recorded GPS positions.
     pub replay_file: Option<String>,
+    /// Format of the replay file. Either "auto", "nmea", "gpsd", "gpx" or "csv".
+    /// Default is "auto", ie: detected from the file content.
+    pub replay_format: Option<FileGnssReplayFormat>,
+    /// Playback rate of the replay file, ie: 2.0 plays twice as fast as recorded.
+    /// Default is 1.0.
+    pub replay_rate: Option<f64>,
+    /// Offset in seconds in the replay file at which playing starts, and rewinds to.
+    /// Default is 0.
+    pub replay_start_offset: Option<u64>,
+    /// Path of the GNSS receiver serial device, ie: "/dev/ttyACM0".
+    pub serial_device: Option<String>,
+    /// Baud rate of the GNSS receiver serial device. Default is 9600.
+    pub serial_baud_rate: Option<u32>,
     /// Latitude in degrees of the fixed position. Default is 0.0°
     pub fixed_position_latitude: Option<f64>,
     /// Longitude in degrees of the fixed position. Default is 0.0°
@@ -275,6 +324,55 @@ pub enum FilePrivacyStrategy {
     No,
     Threshold,
     C2c,
+    Silent,
+    #[serde(rename = "mix_zone")]
+    MixZone,
+    Daily,
+    Random,
+}
+
+/// Configuration values parsed from the TOML config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub enum FileSignerPolicy {
+    C2c,
+    Certificate,
+    Digest,
+}
+
+/// Configuration values parsed from the TOML config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub enum FileSignerIdentifierPolicy {
+    Certificate,
+    Digest,
+    Periodic,
+}
+
+/// Configuration values for a per application signer identifier rule in the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileSignerRule {
+    /// ITS-AID of the application.
+    pub aid: i64,
+    /// Signer identifier policy of the application.
+    pub policy: FileSignerIdentifierPolicy,
+    /// Certificate inclusion interval in milliseconds, for 'periodic' policy. Default is 1000.
+    pub interval: Option<u64>,
+}
+
+/// Configuration values for a mix zone in the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileMixZone {
+    /// Latitude in degrees of the mix zone center.
+    pub latitude: f64,
+    /// Longitude in degrees of the mix zone center.
+    pub longitude: f64,
+    /// Radius in meters of the mix zone.
+    pub radius: f64,
 }
 
 /// Configuration values for the security layer.
@@ -288,6 +386,32 @@ pub struct FileSecurityConfig {
     pub privacy: Option<FilePrivacyStrategy>,
     /// Threshold value for threshold privacy strategy. Default is 2_000_000.
     pub privacy_threshold: Option<u32>,
+    /// Certificate usage duration in seconds, for 'silent' and 'daily' privacy strategies.
+    /// Default is 300 seconds for 'silent', 3600 seconds for 'daily'.
+    pub privacy_interval: Option<u64>,
+    /// Minimum silent period duration in seconds, for 'silent' privacy strategy. Default is 5.
+    pub privacy_min_silence: Option<u64>,
+    /// Maximum silent period duration in seconds, for 'silent' privacy strategy. Default is 15.
+    pub privacy_max_silence: Option<u64>,
+    /// Minimum certificate usage duration in seconds, for 'mix_zone' and 'random' privacy
+    /// strategies. Default is 60 seconds for 'mix_zone', 120 seconds for 'random'.
+    pub privacy_min_interval: Option<u64>,
+    /// Maximum certificate usage duration in seconds, for 'mix_zone' and 'random' privacy
+    /// strategies. Default is 900 seconds for 'mix_zone', 600 seconds for 'random'.
+    pub privacy_max_interval: Option<u64>,
+    /// Mix zones, ie: circular areas where the certificate is changed, for 'mix_zone' privacy
+    /// strategy. Default is none.
+    pub privacy_mix_zones: Option<Vec<FileMixZone>>,
+    /// Defer pseudonym changes while a locally originated DENM event is active. Default is false.
+    #[serde(default)]
+    pub defer_pseudonym_change: bool,
+    /// Time accuracy in milliseconds required to sign messages. Messages are not sent when the
+    /// local time accuracy is unknown or above this value. Default is disabled.
+    pub required_time_accuracy: Option<u64>,
+    /// Signer identifier policy. Default is 'c2c'.
+    pub signer: Option<FileSignerPolicy>,
+    /// Per application signer identifier rules, overriding the signer identifier policy.
+    pub signer_rules: Option<Vec<FileSignerRule>>,
     /// Canonical identifier. Default is empty string.
     pub canonical_identifier: Option<String>,
     /// Service Specific Permissions (SSP) to include into certificates requests.
@@ -300,6 +424,11 @@ pub struct FileSecurityConfig {
     pub ea_url: Option<String>,
     /// Authentication Authority URL. Default is empty string.
     pub aa_url: Option<String>,
+    /// Misbehavior Authority URL, where the misbehavior reports are submitted. If not set, the
+    /// Misbehavior Authority advertised by the ECTL is used, if any.
+    pub ma_url: Option<String>,
+    /// Misbehavior Authority certificate file path, COER encoded. Required with `ma_url`.
+    pub ma_certificate: Option<String>,
     /// Request timeout in seconds. Default is 5 seconds.
     pub timeout: Option<u32>,
     /// Secure storage path.
@@ -308,6 +437,16 @@ pub struct FileSecurityConfig {
     pub secret: Option<SecretBox<Secret>>,
 }
 
+/// Configuration values parsed from the TOML config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub enum FileDenmShutdownPolicy {
+    Keep,
+    Cancel,
+    Negate,
+}
+
 /// Configuration values parsed from the TOML config file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -344,13 +483,27 @@ pub struct FileConfig {
     pub ipc_publisher_port: Option<u16>,
     /// TCP port binded by the IPC replier. Default port is 45557.
     pub ipc_replier_port: Option<u16>,
+    /// Local TCP port of the Prometheus metrics HTTP endpoint. Metrics are not exported
+    /// over HTTP unless this option is set.
+    pub metrics_port: Option<u16>,
     /// Path of the UNIX domain socket file to send commands to the Veloce stack.
     /// Default is "/var/run/veloceCommand.sock"
     pub command_socket: Option<String>,
     /// Absolute path of the PID file.
     pub pid_file_path: Option<String>,
+    /// Directory of the lock file preventing several Veloce instances on the same PHY.
+    /// Default is the system temporary directory.
+    pub lock_dir: Option<String>,
+    /// Action on the active DENMs when Veloce stops. Either "keep", "cancel" or "negate".
+    /// Default is "keep".
+    pub shutdown_denm: Option<FileDenmShutdownPolicy>,
+    /// Path of the file persisting the DENMs originated by the station across restarts.
+    /// DENMs are not persisted unless this option is set.
+    pub denm_table_file: Option<String>,
     /// Log level. Default is "info".
     pub log_level: Option<String>,
+    /// Path of the IERS `leap-seconds.list` file. Default is the built-in TAI - UTC offset.
+    pub leap_seconds_file: Option<String>,
 }
 
 impl FileConfig {
@@ -405,21 +558,43 @@ impl ConfigBuilder {
             |st| Self::parse_station_type(st.to_owned()),
         )?;
 
+        let interface = Self::parse_interface_config(&toml)?;
+        let lock_file_path = toml
+            .lock_dir
+            .as_ref()
+            .map_or_else(std::env::temp_dir, PathBuf::from)
+            .join(format!("veloce-{}.lock", interface.id()))
+            .to_string_lossy()
+            .into_owned();
+
         let inner = Config {
             ll_address,
             pseudonym: toml.geonet.pseudonym.unwrap_or_else(|| rng.rand_u32()),
             station_type,
-            interface: Self::parse_interface_config(&toml)?,
+            interface,
             gnss: Self::parse_gnss_config(&toml.gnss)?,
             security: Self::parse_security_config(&toml.security)?,
             ipc_publisher_port: toml.ipc_publisher_port.unwrap_or(45556),
             ipc_replier_port: toml.ipc_replier_port.unwrap_or(45557),
+            metrics_port: toml.metrics_port,
             command_socket: toml
                 .command_socket
                 .clone()
                 .unwrap_or("/var/run/veloceCommand.sock".to_string()),
             pid_file_path: toml.pid_file_path.clone(),
+            lock_file_path,
+            denm_shutdown: match toml.shutdown_denm.unwrap_or(FileDenmShutdownPolicy::Keep) {
+                FileDenmShutdownPolicy::Keep => DenmShutdownPolicy::Keep,
+                FileDenmShutdownPolicy::Cancel => DenmShutdownPolicy::Cancel,
+                FileDenmShutdownPolicy::Negate => DenmShutdownPolicy::Negate,
+            },
+            denm_table_file: toml.denm_table_file.clone(),
             log_level: toml.log_level.clone().unwrap_or("info".to_string()),
+            leap_seconds: toml
+                .leap_seconds_file
+                .as_ref()
+                .map(|path| Self::parse_leap_seconds(path))
+                .transpose()?,
         };
 
         Ok(ConfigBuilder { toml, inner })
@@ -485,10 +660,40 @@ impl ConfigBuilder {
                 GnssConfig::Gpsd(addr)
             }
             FileGnssConfigMode::Replay => {
-                if let Some(path) = &toml.replay_file {
-                    GnssConfig::Replay(path.clone())
-                } else {
+                let Some(file) = toml.replay_file.clone() else {
                     return Err(ConfigError::NoGnssReplayFile);
+                };
+
+                let format = match toml.replay_format.unwrap_or(FileGnssReplayFormat::Auto) {
+                    FileGnssReplayFormat::Auto => None,
+                    FileGnssReplayFormat::Nmea => Some(ReplayFormat::Nmea),
+                    FileGnssReplayFormat::Gpsd => Some(ReplayFormat::GpsdJson),
+                    FileGnssReplayFormat::Gpx => Some(ReplayFormat::Gpx),
+                    FileGnssReplayFormat::Csv => Some(ReplayFormat::Csv(CsvColumns::default())),
+                };
+
+                let rate = toml.replay_rate.unwrap_or(1.0);
+                if !(rate.is_finite() && rate > 0.0) {
+                    return Err(ConfigError::InvalidGnssReplayRate);
+                }
+
+                GnssConfig::Replay {
+                    file,
+                    format,
+                    rate,
+                    start_offset: Duration::from_secs(toml.replay_start_offset.unwrap_or(0)),
+                }
+            }
+            FileGnssConfigMode::Serial | FileGnssConfigMode::Ubx => {
+                let Some(device) = toml.serial_device.clone() else {
+                    return Err(ConfigError::NoGnssSerialDevice);
+                };
+                let baud_rate = toml.serial_baud_rate.unwrap_or(9600);
+
+                if mode == FileGnssConfigMode::Serial {
+                    GnssConfig::Serial { device, baud_rate }
+                } else {
+                    GnssConfig::Ubx { device, baud_rate }
                 }
             }
         };
@@ -573,6 +778,12 @@ impl ConfigBuilder {
         Ok(res)
     }
 
+    /// Load and parse the leap seconds file at `path`.
+    fn parse_leap_seconds(path: &str) -> ConfigResult<LeapSeconds> {
+        let data = load_file(path).map_err(ConfigError::LeapSecondsFileLoad)?;
+        LeapSeconds::parse(&data).map_err(ConfigError::InvalidLeapSeconds)
+    }
+
     fn parse_security_config(toml: &FileSecurityConfig) -> ConfigResult<SecurityConfig> {
         let res = SecurityConfig {
             enable: toml.enable,
@@ -584,8 +795,33 @@ impl ConfigBuilder {
                         PrivacyStrategy::Threshold(toml.privacy_threshold.unwrap_or(2_000_000))
                     }
                     FilePrivacyStrategy::C2c => PrivacyStrategy::Car2Car(rand::random()),
+                    FilePrivacyStrategy::Silent => PrivacyStrategy::SilentPeriod {
+                        interval: Duration::from_secs(toml.privacy_interval.unwrap_or(300)),
+                        min_silence: Duration::from_secs(toml.privacy_min_silence.unwrap_or(5)),
+                        max_silence: Duration::from_secs(toml.privacy_max_silence.unwrap_or(15)),
+                        seed: rand::random(),
+                    },
+                    FilePrivacyStrategy::MixZone => PrivacyStrategy::MixZone {
+                        min_interval: Duration::from_secs(toml.privacy_min_interval.unwrap_or(60)),
+                        max_interval: Duration::from_secs(toml.privacy_max_interval.unwrap_or(900)),
+                    },
+                    FilePrivacyStrategy::Daily => PrivacyStrategy::DailyUsage(Duration::from_secs(
+                        toml.privacy_interval.unwrap_or(3600),
+                    )),
+                    FilePrivacyStrategy::Random => PrivacyStrategy::RandomInterval {
+                        min: Duration::from_secs(toml.privacy_min_interval.unwrap_or(120)),
+                        max: Duration::from_secs(toml.privacy_max_interval.unwrap_or(600)),
+                        seed: rand::random(),
+                    },
                 },
             ),
+            mix_zones: toml
+                .privacy_mix_zones
+                .as_deref()
+                .map_or_else(Vec::new, Self::parse_mix_zones),
+            defer_pseudonym_change: toml.defer_pseudonym_change,
+            required_time_accuracy: toml.required_time_accuracy.map(Duration::from_millis),
+            signer_policy: Self::parse_signer_policy(toml),
             canonical_identifier: toml.canonical_identifier.clone().unwrap_or("".to_string()),
             root_cert_id: toml.root_cert_id.clone().unwrap_or("".to_string()),
             permissions: toml
@@ -598,6 +834,8 @@ impl ConfigBuilder {
                 .unwrap_or("https://cpoc.jrc.ec.europa.eu/L0/".to_string()),
             ea_url: toml.ea_url.clone(),
             aa_url: toml.aa_url.clone(),
+            ma_url: toml.ma_url.clone(),
+            ma_certificate: toml.ma_certificate.clone(),
             timeout: toml.timeout.unwrap_or(5),
             storage_path: toml.storage_path.clone(),
             secret: toml
@@ -609,6 +847,46 @@ impl ConfigBuilder {
         Ok(res)
     }
 
+    /// Parse the mix zones of the mix zone privacy strategy.
+    fn parse_mix_zones(zones: &[FileMixZone]) -> Vec<GeoArea> {
+        zones
+            .iter()
+            .map(|zone| GeoArea {
+                shape: Shape::Circle(Circle {
+                    radius: Distance::new::<meter>(zone.radius),
+                }),
+                position: GeoPosition {
+                    latitude: Angle::new::<degree>(zone.latitude),
+                    longitude: Angle::new::<degree>(zone.longitude),
+                },
+                angle: Angle::new::<degree>(0.0),
+            })
+            .collect()
+    }
+
+    /// Parse the signer identifier policy.
+    fn parse_signer_policy(toml: &FileSecurityConfig) -> SignerPolicy {
+        let mut res = match toml.signer.unwrap_or(FileSignerPolicy::C2c) {
+            FileSignerPolicy::C2c => SignerPolicy::car2car(),
+            FileSignerPolicy::Certificate => SignerPolicy::new(SignerIdentifierPolicy::Certificate),
+            FileSignerPolicy::Digest => SignerPolicy::digest_only(),
+        };
+
+        for rule in toml.signer_rules.iter().flatten() {
+            let policy = match rule.policy {
+                FileSignerIdentifierPolicy::Certificate => SignerIdentifierPolicy::Certificate,
+                FileSignerIdentifierPolicy::Digest => SignerIdentifierPolicy::Digest,
+                FileSignerIdentifierPolicy::Periodic => SignerIdentifierPolicy::Periodic(
+                    Duration::from_millis(rule.interval.unwrap_or(1000)),
+                ),
+            };
+
+            res.set(AID::from(rule.aid), policy);
+        }
+
+        res
+    }
+
     /// Parse the SSP permissions.
     fn parse_ssp(ssp_vec: &Vec<FileSSPConfig>) -> Vec<Permission> {
         let mut res = Vec::new();
@@ -771,6 +1049,39 @@ pub enum InterfaceConfig {
     TunTap(String),
 }
 
+impl InterfaceConfig {
+    /// Returns an identifier of the PHY, usable in a file name.
+    pub fn id(&self) -> String {
+        match self {
+            InterfaceConfig::Nxp(nxp) => {
+                let radio = match nxp.config.radio() {
+                    NxpRadio::A => "a",
+                    NxpRadio::B => "b",
+                };
+                format!("{}-{}", nxp.name, radio)
+            }
+            InterfaceConfig::Ethernet(name) => name.clone(),
+            InterfaceConfig::Udp(udp) => {
+                format!("udp-{}-{}", udp.local_addr.ip(), udp.local_addr.port())
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            InterfaceConfig::TunTap(name) => name.clone(),
+        }
+    }
+}
+
+/// Action on the active DENMs when Veloce stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenmShutdownPolicy {
+    /// Stop transmitting the DENMs. Receivers keep them until they expire.
+    Keep,
+    /// Cancel the DENMs originated by the station.
+    Cancel,
+    /// Cancel the DENMs originated by the station, and negate the DENMs it originated
+    /// in a previous run, which are no longer in its originating table.
+    Negate,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum GnssConfig {
     FixedPosition {
@@ -779,7 +1090,20 @@ pub enum GnssConfig {
         altitude: f64,
     },
     Gpsd(SocketAddr),
-    Replay(String),
+    Replay {
+        file: String,
+        format: Option<ReplayFormat>,
+        rate: f64,
+        start_offset: Duration,
+    },
+    Serial {
+        device: String,
+        baud_rate: u32,
+    },
+    Ubx {
+        device: String,
+        baud_rate: u32,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -787,12 +1111,18 @@ pub enum GnssConfig {
 pub struct SecurityConfig {
     pub enable: bool,
     pub privacy_strategy: PrivacyStrategy,
+    pub mix_zones: Vec<GeoArea>,
+    pub defer_pseudonym_change: bool,
+    pub required_time_accuracy: Option<Duration>,
+    pub signer_policy: SignerPolicy,
     pub canonical_identifier: String,
     pub root_cert_id: String,
     pub permissions: Vec<Permission>,
     pub cpoc_url: String,
     pub ea_url: Option<String>,
     pub aa_url: Option<String>,
+    pub ma_url: Option<String>,
+    pub ma_certificate: Option<String>,
     pub timeout: u32,
     pub storage_path: Option<String>,
     pub secret: SecretBox<Secret>,
@@ -813,9 +1143,14 @@ pub struct Config {
     pub security: SecurityConfig,
     pub ipc_publisher_port: u16,
     pub ipc_replier_port: u16,
+    pub metrics_port: Option<u16>,
     pub command_socket: String,
     pub pid_file_path: Option<String>,
+    pub lock_file_path: String,
+    pub denm_shutdown: DenmShutdownPolicy,
+    pub denm_table_file: Option<String>,
     pub log_level: String,
+    pub leap_seconds: Option<LeapSeconds>,
 }
 
 impl Config {
@@ -4,7 +4,7 @@ use std::{io, path::Path};
 use log::info;
 use mio::{Registry, Token};
 use veloce::time::{Duration, Instant};
-use veloce_gnss::{Fixed, FixedError, Gpsd, Replay, ReplayError};
+use veloce_gnss::{Fixed, FixedError, Gpsd, Replay, ReplayConfig, ReplayError, Serial, Ubx};
 
 use crate::config::{Config, GnssConfig};
 
@@ -19,6 +19,8 @@ pub enum GnssSourceError {
     Gpsd(io::Error),
     /// Replay error.
     Replay(ReplayError),
+    /// Serial device error.
+    Serial(io::Error),
 }
 
 impl fmt::Display for GnssSourceError {
@@ -27,6 +29,7 @@ impl fmt::Display for GnssSourceError {
             GnssSourceError::Fixed(e) => write!(f, "Fixed position error: {e}"),
             GnssSourceError::Gpsd(e) => write!(f, "GPSD error: {e}"),
             GnssSourceError::Replay(e) => write!(f, "Replay error: {e}"),
+            GnssSourceError::Serial(e) => write!(f, "Serial device error: {e}"),
         }
     }
 }
@@ -39,8 +42,12 @@ pub enum GnssSource {
     Fixed(Fixed),
     /// GPSD server source.
     Gpsd(Gpsd),
-    /// Replay from an NMEA file source.
+    /// Replay from a recorded file source.
     Replay(Box<Replay>),
+    /// NMEA serial receiver source.
+    Serial(Serial),
+    /// u-blox UBX serial receiver source.
+    Ubx(Ubx),
 }
 
 impl GnssSource {
@@ -62,13 +69,48 @@ impl GnssSource {
                     .map_err(GnssSourceError::Gpsd)?;
                 GnssSource::Gpsd(gpsd)
             }
-            GnssConfig::Replay(file) => {
+            GnssConfig::Replay {
+                file,
+                format,
+                rate,
+                start_offset,
+            } => {
                 info!("Using replay file at: {}", file);
                 let path = Path::new(&file).to_owned();
+                let replay_config = ReplayConfig {
+                    format: *format,
+                    rewind_delay: Duration::from_secs(1),
+                    rate: *rate,
+                    start_offset: *start_offset,
+                };
                 let replay =
-                    Replay::new(path, Duration::from_secs(1)).map_err(GnssSourceError::Replay)?;
+                    Replay::with_config(path, replay_config).map_err(GnssSourceError::Replay)?;
                 GnssSource::Replay(replay.into())
             }
+            GnssConfig::Serial { device, baud_rate } => {
+                info!("Using NMEA serial receiver at: {}", device);
+                let serial = Serial::new(
+                    device.into(),
+                    *baud_rate,
+                    poll_registry,
+                    token,
+                    Instant::now(),
+                )
+                .map_err(GnssSourceError::Serial)?;
+                GnssSource::Serial(serial)
+            }
+            GnssConfig::Ubx { device, baud_rate } => {
+                info!("Using UBX serial receiver at: {}", device);
+                let ubx = Ubx::new(
+                    device.into(),
+                    *baud_rate,
+                    poll_registry,
+                    token,
+                    Instant::now(),
+                )
+                .map_err(GnssSourceError::Serial)?;
+                GnssSource::Ubx(ubx)
+            }
         };
 
         Ok(res)
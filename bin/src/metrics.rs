@@ -0,0 +1,707 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    io::{self, Read, Write},
+    net::{Ipv4Addr, SocketAddr},
+};
+
+use log::{debug, error};
+use mio::{
+    Interest, Registry, Token,
+    net::{TcpListener, TcpStream},
+};
+use veloce::{
+    iface::{Interface, InterfaceStats, SocketHandle, SocketSet},
+    network::GnCore,
+    security::service::DecapStats,
+    socket::{self, MessageStats},
+    time::{Duration, Instant},
+};
+use veloce_ipc::{IpcEvent, IpcEventType, stats};
+
+/// Delay after which a metrics HTTP connection is closed, answered or not.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of simultaneous metrics HTTP connections.
+const HTTP_MAX_CONNECTIONS: usize = 8;
+
+/// Maximum size of a metrics HTTP request.
+const HTTP_MAX_REQUEST_LEN: usize = 4096;
+
+/// Snapshot of the stack statistics.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Interface statistics.
+    iface: InterfaceStats,
+    /// Security decapsulation statistics. [None] if security is disabled.
+    security: Option<DecapStats>,
+    /// CAM socket statistics.
+    cam: MessageStats,
+    /// DENM socket statistics.
+    denm: MessageStats,
+}
+
+impl Snapshot {
+    /// Collect the statistics of the stack components.
+    pub fn collect(
+        iface: &Interface,
+        router: &GnCore,
+        sockets: &SocketSet,
+        cam_handle: SocketHandle,
+        denm_handle: SocketHandle,
+    ) -> Snapshot {
+        Snapshot {
+            iface: iface.stats(),
+            security: router.security().map(|sec| sec.decap_stats().clone()),
+            cam: sockets.get::<socket::cam::Socket>(cam_handle).stats(),
+            denm: sockets.get::<socket::denm::Socket>(denm_handle).stats(),
+        }
+    }
+
+    /// Render the snapshot in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        // Writing into a String never fails.
+        self.write_prometheus(&mut out).ok();
+        out
+    }
+
+    fn write_prometheus(&self, out: &mut String) -> fmt::Result {
+        let iface = &self.iface;
+        let lt = &iface.location_table;
+
+        write_family(
+            out,
+            "veloce_gn_rx_packets_total",
+            "Received Geonetworking packets.",
+            "type",
+            iface.rx.iter(),
+        )?;
+        write_family(
+            out,
+            "veloce_gn_tx_packets_total",
+            "Transmitted Geonetworking packets.",
+            "type",
+            iface.tx.iter(),
+        )?;
+        write_family(
+            out,
+            "veloce_gn_dropped_packets_total",
+            "Discarded received Geonetworking packets.",
+            "reason",
+            iface.dropped.iter(),
+        )?;
+        write_metric(
+            out,
+            "veloce_gn_forwarded_packets_total",
+            "counter",
+            "Transmitted packets originated by another station.",
+            iface.forwarded,
+        )?;
+        write_metric(
+            out,
+            "veloce_gn_duplicate_packets_total",
+            "counter",
+            "Received duplicate Geonetworking packets.",
+            iface.duplicates,
+        )?;
+        write_metric(
+            out,
+            "veloce_dcc_enqueued_packets_total",
+            "counter",
+            "Packets which had to wait in a DCC queue before transmission.",
+            iface.dcc.enqueued,
+        )?;
+        write_metric(
+            out,
+            "veloce_dcc_dropped_packets_total",
+            "counter",
+            "Packets dropped by the DCC.",
+            iface.dcc.dropped,
+        )?;
+        write_metric(
+            out,
+            "veloce_location_table_inserted_total",
+            "counter",
+            "Entries inserted in the Location Table.",
+            lt.inserted,
+        )?;
+        write_metric(
+            out,
+            "veloce_location_table_removed_total",
+            "counter",
+            "Entries removed from the Location Table.",
+            lt.removed,
+        )?;
+        write_metric(
+            out,
+            "veloce_location_table_entries",
+            "gauge",
+            "Current number of entries in the Location Table.",
+            lt.entries as u64,
+        )?;
+
+        if let Some(sec) = &self.security {
+            write_metric(
+                out,
+                "veloce_security_decap_succeeded_total",
+                "counter",
+                "Secured packets successfully decapsulated.",
+                sec.succeeded,
+            )?;
+            write_family(
+                out,
+                "veloce_security_decap_failed_total",
+                "Secured packets rejected, per error kind.",
+                "kind",
+                sec.failed.iter().map(|(k, v)| (k, *v)),
+            )?;
+        }
+
+        let messages = [("cam", self.cam), ("denm", self.denm)];
+        write_family(
+            out,
+            "veloce_messages_tx_total",
+            "Transmitted facilities messages.",
+            "message",
+            messages.iter().map(|(m, s)| (m, s.tx)),
+        )?;
+        write_family(
+            out,
+            "veloce_messages_rx_total",
+            "Received facilities messages.",
+            "message",
+            messages.iter().map(|(m, s)| (m, s.rx)),
+        )?;
+        write_family(
+            out,
+            "veloce_messages_rx_discarded_total",
+            "Discarded received facilities messages.",
+            "message",
+            messages.iter().map(|(m, s)| (m, s.rx_discarded)),
+        )
+    }
+
+    /// Convert the snapshot into its IPC representation, answering the request `id`.
+    pub fn to_proto(&self, id: u32) -> stats::Snapshot {
+        let iface = &self.iface;
+        let messages = |s: &MessageStats| stats::Messages {
+            tx: s.tx,
+            rx: s.rx,
+            rx_discarded: s.rx_discarded,
+        };
+
+        stats::Snapshot {
+            id,
+            gn_rx: iface.rx.iter().map(|(t, v)| (t.to_string(), v)).collect(),
+            gn_tx: iface.tx.iter().map(|(t, v)| (t.to_string(), v)).collect(),
+            gn_dropped: iface
+                .dropped
+                .iter()
+                .map(|(r, v)| (r.to_string(), v))
+                .collect(),
+            gn_forwarded: iface.forwarded,
+            gn_duplicates: iface.duplicates,
+            dcc_enqueued: iface.dcc.enqueued,
+            dcc_dropped: iface.dcc.dropped,
+            location_table: Some(stats::LocationTable {
+                inserted: iface.location_table.inserted,
+                removed: iface.location_table.removed,
+                entries: iface.location_table.entries as u64,
+            }),
+            security: self.security.as_ref().map(|sec| stats::Security {
+                decap_succeeded: sec.succeeded,
+                decap_failed: sec
+                    .failed
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), *v))
+                    .collect(),
+            }),
+            cam: Some(messages(&self.cam)),
+            denm: Some(messages(&self.denm)),
+        }
+    }
+
+    /// Build the IPC reply to the stats `request`.
+    pub fn reply(&self, request: &stats::Request) -> IpcEvent {
+        IpcEvent::new(IpcEventType::Stats(self.to_proto(request.id)))
+    }
+}
+
+/// Write the unlabelled `name` metric, of type `ty`.
+fn write_metric(out: &mut String, name: &str, ty: &str, help: &str, value: u64) -> fmt::Result {
+    writeln!(out, "# HELP {name} {help}")?;
+    writeln!(out, "# TYPE {name} {ty}")?;
+    writeln!(out, "{name} {value}")
+}
+
+/// Write the `name` counter, with one sample per `label` value.
+fn write_family<L: fmt::Display>(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    label: &str,
+    samples: impl Iterator<Item = (L, u64)>,
+) -> fmt::Result {
+    writeln!(out, "# HELP {name} {help}")?;
+    writeln!(out, "# TYPE {name} counter")?;
+    for (label_value, value) in samples {
+        writeln!(out, "{name}{{{label}=\"{label_value}\"}} {value}")?;
+    }
+    Ok(())
+}
+
+/// Metrics HTTP connection, answered once its request is fully received.
+#[derive(Debug)]
+struct Connection {
+    /// Connection socket, in non-blocking mode.
+    stream: TcpStream,
+    /// Request bytes received so far.
+    request: Vec<u8>,
+    /// Response to write, once the request is received.
+    response: Option<Vec<u8>>,
+    /// Number of response bytes already written.
+    written: usize,
+    /// Instant at which the connection is closed, whatever its state.
+    expires_at: Instant,
+}
+
+impl Connection {
+    /// Advance the connection state machine, reading the request and writing the response
+    /// until the socket would block. Returns `true` once the response is fully written.
+    fn advance(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        render: &impl Fn() -> String,
+    ) -> io::Result<bool> {
+        if self.response.is_none() {
+            let mut buf = [0u8; 512];
+            loop {
+                match self.stream.read(&mut buf) {
+                    // Peer closed its writing half, answer what we have.
+                    Ok(0) => break,
+                    Ok(n) => {
+                        self.request.extend_from_slice(&buf[..n]);
+                        if request_complete(&self.request) {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            self.response = Some(respond(&self.request, render));
+            registry.reregister(&mut self.stream, token, Interest::WRITABLE)?;
+        }
+
+        let response = self.response.as_deref().unwrap_or_default();
+        while self.written < response.len() {
+            match self.stream.write(&response[self.written..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => self.written += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Returns whether the HTTP `request` headers are fully received, or the request is too long.
+fn request_complete(request: &[u8]) -> bool {
+    request.len() >= HTTP_MAX_REQUEST_LEN || request.windows(4).any(|w| w == b"\r\n\r\n")
+}
+
+/// Build the response to the HTTP `request`, with the metrics returned by `render`.
+fn respond(request: &[u8], render: &impl Fn() -> String) -> Vec<u8> {
+    let request = String::from_utf8_lossy(request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+
+    let response = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = render();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        (Some("GET"), Some(_)) => {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        }
+        _ => "HTTP/1.1 405 Method Not Allowed\r\nAllow: GET\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+
+    response.into_bytes()
+}
+
+/// Prometheus metrics HTTP endpoint, listening on the loopback interface.
+///
+/// Only `GET /metrics` requests are served, each connection is closed after the response.
+/// Connections are non-blocking and registered in the router poll registry, so a slow client
+/// never stalls the stack. The listening socket uses the token given at registration, the
+/// connections use the tokens above it.
+#[derive(Debug)]
+pub struct MetricsServer {
+    /// Listening socket, in non-blocking mode.
+    listener: TcpListener,
+    /// Token of the listening socket.
+    token: Token,
+    /// Open connections, per token.
+    connections: BTreeMap<Token, Connection>,
+    /// Next token to try for a new connection.
+    next_token: usize,
+}
+
+impl MetricsServer {
+    /// Bind the metrics endpoint on the loopback interface, at `port`.
+    pub fn new(port: u16) -> io::Result<MetricsServer> {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))?;
+
+        debug!("Metrics endpoint listening on {}", listener.local_addr()?);
+
+        Ok(MetricsServer {
+            listener,
+            token: Token(0),
+            connections: BTreeMap::new(),
+            next_token: 1,
+        })
+    }
+
+    /// Register the listening socket in the `registry`, with `token`.
+    /// Tokens above `token` are reserved for the metrics connections.
+    pub fn register(&mut self, registry: &Registry, token: Token) -> io::Result<()> {
+        registry.register(&mut self.listener, token, Interest::READABLE)?;
+        self.token = token;
+        self.next_token = token.0 + 1;
+        Ok(())
+    }
+
+    /// Returns whether `token` belongs to the metrics endpoint.
+    pub fn owns(&self, token: Token) -> bool {
+        token.0 >= self.token.0
+    }
+
+    /// Process a readiness event on `token`, serving the metrics returned by `render`.
+    pub fn ready(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        now: Instant,
+        render: impl Fn() -> String,
+    ) {
+        if token == self.token {
+            self.accept(registry, now);
+            return;
+        }
+
+        // Events of an already closed connection are ignored.
+        let Some(mut conn) = self.connections.remove(&token) else {
+            return;
+        };
+
+        match conn.advance(registry, token, &render) {
+            Ok(false) => {
+                self.connections.insert(token, conn);
+            }
+            Ok(true) => {
+                registry.deregister(&mut conn.stream).ok();
+            }
+            Err(e) => {
+                debug!("Metrics request failed: {}", e);
+                registry.deregister(&mut conn.stream).ok();
+            }
+        }
+    }
+
+    /// Close the connections which did not complete before their deadline.
+    pub fn poll(&mut self, registry: &Registry, now: Instant) {
+        self.connections.retain(|_, conn| {
+            if conn.expires_at > now {
+                return true;
+            }
+
+            debug!("Metrics connection timed out");
+            registry.deregister(&mut conn.stream).ok();
+            false
+        });
+    }
+
+    /// Returns the delay until the next connection deadline, if any.
+    pub fn poll_delay(&self, now: Instant) -> Option<Duration> {
+        self.connections
+            .values()
+            .map(|conn| {
+                if conn.expires_at > now {
+                    conn.expires_at - now
+                } else {
+                    Duration::ZERO
+                }
+            })
+            .min()
+    }
+
+    /// Accept and register the pending connections.
+    fn accept(&mut self, registry: &Registry, now: Instant) {
+        loop {
+            match self.listener.accept() {
+                Ok((mut stream, _)) => {
+                    if self.connections.len() >= HTTP_MAX_CONNECTIONS {
+                        debug!("Too many metrics connections, closing the new one");
+                        continue;
+                    }
+
+                    let token = self.allocate_token();
+                    if let Err(e) = registry.register(&mut stream, token, Interest::READABLE) {
+                        error!("Cannot register metrics connection: {}", e);
+                        continue;
+                    }
+
+                    self.connections.insert(
+                        token,
+                        Connection {
+                            stream,
+                            request: Vec::new(),
+                            response: None,
+                            written: 0,
+                            expires_at: now + HTTP_TIMEOUT,
+                        },
+                    );
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    error!("Cannot accept metrics connection: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns a token above the listener one, not used by an open connection.
+    fn allocate_token(&mut self) -> Token {
+        loop {
+            let token = Token(self.next_token);
+            self.next_token = self.next_token.checked_add(1).unwrap_or(self.token.0 + 1);
+            if !self.connections.contains_key(&token) {
+                return token;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpStream as StdTcpStream;
+
+    use mio::{Events, Poll};
+
+    use super::*;
+
+    const LISTENER_TOKEN: Token = Token(4);
+
+    fn snapshot() -> Snapshot {
+        let mut iface = InterfaceStats::default();
+        iface.rx.geo_broadcast = 3;
+        iface.rx.beacon = 7;
+        iface.tx.single_hop_broadcast = 2;
+        iface.forwarded = 1;
+        iface.dcc.enqueued = 4;
+        iface.location_table.inserted = 5;
+        iface.location_table.entries = 2;
+
+        let mut security = DecapStats::default();
+        security.succeeded = 9;
+        security.failed.insert("signature", 2);
+
+        Snapshot {
+            iface,
+            security: Some(security),
+            cam: MessageStats {
+                tx: 10,
+                rx: 11,
+                rx_discarded: 1,
+            },
+            denm: MessageStats::default(),
+        }
+    }
+
+    /// Run the `server` event loop until a connection is served.
+    fn run(server: &mut MetricsServer, poll: &mut Poll, now: Instant) {
+        let mut events = Events::with_capacity(16);
+        let mut served = false;
+        while !served {
+            poll.poll(&mut events, Some(core::time::Duration::from_millis(100)))
+                .unwrap();
+            for event in events.iter() {
+                let token = event.token();
+                assert!(server.owns(token));
+                server.ready(poll.registry(), token, now, || "metrics".to_string());
+                served |= token != LISTENER_TOKEN && !server.connections.contains_key(&token);
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_prometheus() {
+        let text = snapshot().to_prometheus();
+        let lines: Vec<_> = text.lines().collect();
+
+        assert!(lines.contains(&"# TYPE veloce_gn_rx_packets_total counter"));
+        assert!(lines.contains(&"veloce_gn_rx_packets_total{type=\"geo_broadcast\"} 3"));
+        assert!(lines.contains(&"veloce_gn_rx_packets_total{type=\"beacon\"} 7"));
+        assert!(lines.contains(&"veloce_gn_tx_packets_total{type=\"single_hop_broadcast\"} 2"));
+        assert!(lines.contains(&"veloce_gn_dropped_packets_total{reason=\"malformed\"} 0"));
+        assert!(lines.contains(&"veloce_gn_forwarded_packets_total 1"));
+        assert!(lines.contains(&"veloce_dcc_enqueued_packets_total 4"));
+        assert!(lines.contains(&"# TYPE veloce_location_table_entries gauge"));
+        assert!(lines.contains(&"veloce_location_table_entries 2"));
+        assert!(lines.contains(&"veloce_security_decap_succeeded_total 9"));
+        assert!(lines.contains(&"veloce_security_decap_failed_total{kind=\"signature\"} 2"));
+        assert!(lines.contains(&"veloce_messages_tx_total{message=\"cam\"} 10"));
+        assert!(lines.contains(&"veloce_messages_rx_discarded_total{message=\"denm\"} 0"));
+
+        // Each sample belongs to the family described by the preceding HELP and TYPE lines.
+        let mut family = "";
+        for line in lines {
+            if let Some(help) = line.strip_prefix("# HELP ") {
+                family = help.split_whitespace().next().unwrap();
+            } else if let Some(ty) = line.strip_prefix("# TYPE ") {
+                assert!(ty.starts_with(family));
+                assert!(ty.ends_with(" counter") || ty.ends_with(" gauge"));
+            } else {
+                let (name, value) = line.rsplit_once(' ').unwrap();
+                assert_eq!(name.split('{').next().unwrap(), family);
+                value.parse::<u64>().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_prometheus_without_security() {
+        let mut snapshot = snapshot();
+        snapshot.security = None;
+
+        assert!(!snapshot.to_prometheus().contains("veloce_security_"));
+    }
+
+    #[test]
+    fn test_stats_reply() {
+        let snapshot = snapshot();
+        let reply = snapshot.reply(&stats::Request { id: 42 });
+
+        let decoded = IpcEvent::deserialize(&reply.serialize_to_vec()).unwrap();
+        let Some(IpcEventType::Stats(stats)) = decoded.event_type else {
+            panic!("Expected a stats reply");
+        };
+
+        assert_eq!(stats.id, 42);
+        assert_eq!(stats.gn_rx["geo_broadcast"], 3);
+        assert_eq!(stats.gn_tx["single_hop_broadcast"], 2);
+        assert_eq!(stats.gn_dropped["buffer_full"], 0);
+        assert_eq!(stats.gn_forwarded, 1);
+        assert_eq!(stats.dcc_enqueued, 4);
+        assert_eq!(stats.location_table.unwrap().inserted, 5);
+        let security = stats.security.unwrap();
+        assert_eq!(security.decap_succeeded, 9);
+        assert_eq!(security.decap_failed["signature"], 2);
+        assert_eq!(stats.cam.unwrap().rx, 11);
+        assert_eq!(stats.denm.unwrap().tx, 0);
+    }
+
+    #[test]
+    fn test_respond() {
+        let render = || "metrics".to_string();
+
+        let ok = String::from_utf8(respond(b"GET /metrics HTTP/1.1\r\n\r\n", &render)).unwrap();
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(ok.contains("Content-Length: 7\r\n"));
+        assert!(ok.ends_with("\r\n\r\nmetrics"));
+
+        let not_found = String::from_utf8(respond(b"GET / HTTP/1.1\r\n\r\n", &render)).unwrap();
+        assert!(not_found.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let not_allowed =
+            String::from_utf8(respond(b"POST /metrics HTTP/1.1\r\n\r\n", &render)).unwrap();
+        assert!(not_allowed.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    }
+
+    #[test]
+    fn test_serve() {
+        let mut poll = Poll::new().unwrap();
+        let mut server = MetricsServer::new(0).unwrap();
+        server.register(poll.registry(), LISTENER_TOKEN).unwrap();
+        let addr = server.listener.local_addr().unwrap();
+
+        let mut client = StdTcpStream::connect(addr).unwrap();
+        client
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+
+        run(&mut server, &mut poll, Instant::now());
+        assert!(server.connections.is_empty());
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nmetrics"));
+    }
+
+    #[test]
+    fn test_serve_slow_client() {
+        let mut poll = Poll::new().unwrap();
+        let mut events = Events::with_capacity(16);
+        let mut server = MetricsServer::new(0).unwrap();
+        server.register(poll.registry(), LISTENER_TOKEN).unwrap();
+        let addr = server.listener.local_addr().unwrap();
+
+        // A client sending an incomplete request does not block the server.
+        let mut slow = StdTcpStream::connect(addr).unwrap();
+        slow.write_all(b"GET /metr").unwrap();
+
+        let now = Instant::now();
+        while server.connections.values().all(|c| c.request.is_empty()) {
+            poll.poll(&mut events, Some(core::time::Duration::from_millis(100)))
+                .unwrap();
+            for event in events.iter() {
+                server.ready(poll.registry(), event.token(), now, || unreachable!());
+            }
+        }
+        assert_eq!(server.connections.len(), 1);
+        assert_eq!(server.poll_delay(now), Some(HTTP_TIMEOUT));
+
+        // Other clients are served meanwhile.
+        let mut client = StdTcpStream::connect(addr).unwrap();
+        client.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        run(&mut server, &mut poll, now);
+        assert_eq!(server.connections.len(), 1);
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.ends_with("\r\n\r\nmetrics"));
+
+        // The slow connection is closed at its deadline.
+        server.poll(
+            poll.registry(),
+            now + HTTP_TIMEOUT - Duration::from_millis(1),
+        );
+        assert_eq!(server.connections.len(), 1);
+        server.poll(poll.registry(), now + HTTP_TIMEOUT);
+        assert!(server.connections.is_empty());
+        assert_eq!(server.poll_delay(now), None);
+
+        let mut rest = Vec::new();
+        slow.read_to_end(&mut rest).unwrap();
+        assert!(rest.is_empty());
+    }
+}
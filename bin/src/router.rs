@@ -1,7 +1,7 @@
 use core::fmt;
 use std::{io, rc::Rc};
 
-use log::{debug, error};
+use log::{debug, error, info, warn};
 use mio::{Events, Interest, Poll, Registry, Token, event::Source};
 use veloce::{
     iface::{Config as RouterIfaceConfig, CongestionControl, Interface, SocketHandle, SocketSet},
@@ -15,21 +15,33 @@ use veloce::{
         DirectoryStorage, SecurityServicePollEvent, SecurityStorageMetadata, storage::StorageTrait,
     },
     socket,
-    time::Instant,
+    time::{Duration, Instant},
     types::Pseudonym,
 };
 use veloce_ipc::{IpcEvent, IpcEventType, prelude::zmq};
 
 use crate::{
-    config::Config,
+    config::{Config, DenmShutdownPolicy},
     device::AnyDevice,
     gnss::{GnssSource, GnssSourceError},
     ipc::Ipc,
+    metrics::{MetricsServer, Snapshot},
+    security::MisbehaviorReporter,
+    signal::{Signal, Signals},
+    systemd::Notifier,
+    utils::{load_denm_table, reload_logging, store_denm_table},
 };
 
 const PHY_TOKEN: Token = Token(0);
 const GNSS_TOKEN: Token = Token(1);
 const IPC_REP_TOKEN: Token = Token(2);
+const SIGNAL_TOKEN: Token = Token(3);
+/// Token of the metrics listening socket. Must be the last one, the tokens above
+/// it are used by the metrics connections.
+const METRICS_TOKEN: Token = Token(4);
+
+/// Maximum duration of the DENM terminations transmission when the router stops.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub type RouterResult<T> = core::result::Result<T, RouterError>;
 
@@ -50,6 +62,10 @@ pub enum RouterError {
     IpcRegister(io::Error),
     /// GNSS setup error.
     GnssCreate(GnssSourceError),
+    /// Error while installing the signal handlers.
+    SignalsSetup(io::Error),
+    /// Error while setting up the metrics endpoint.
+    MetricsSetup(io::Error),
 }
 
 impl fmt::Display for RouterError {
@@ -64,6 +80,8 @@ impl fmt::Display for RouterError {
             }
             RouterError::IpcRegister(e) => write!(f, "Failed to register IPC interface: {e}"),
             RouterError::GnssCreate(e) => write!(f, "Failed to setup GNSS source: {e}"),
+            RouterError::SignalsSetup(e) => write!(f, "Failed to setup signal handlers: {e}"),
+            RouterError::MetricsSetup(e) => write!(f, "Failed to setup metrics endpoint: {e}"),
         }
     }
 }
@@ -98,12 +116,27 @@ pub struct Router<'a> {
     poll_errors_num: u32,
     /// Max poll errors limit.
     max_poll_errors_num: u32,
+    /// Unix signals.
+    signals: Signals,
+    /// Service manager notifier.
+    notifier: Notifier,
+    /// Prometheus metrics endpoint, if enabled.
+    metrics: Option<MetricsServer>,
+    /// Misbehavior reporting to the MA, if enabled.
+    misbehavior_reporter: Option<MisbehaviorReporter>,
+    /// Path of the configuration file, loaded again on reload.
+    config_path: String,
+    /// Action on the active DENMs when stopping.
+    denm_shutdown: DenmShutdownPolicy,
+    /// Path of the file persisting the originated DENMs, if enabled.
+    denm_table_file: Option<String>,
 }
 
 impl<'a> Router<'a> {
-    /// Constructs a new [Router] with parameters from the [Config].
+    /// Constructs a new [Router] with parameters from the [Config], loaded from `config_path`.
     pub fn new(
         config: &Config,
+        config_path: &str,
         mut device: AnyDevice,
         security_config_storage: Option<(
             RouterSecurityConfig,
@@ -125,7 +158,43 @@ impl<'a> Router<'a> {
             router_config.addr_config_mode = GnAddrConfigMode::Managed(addr);
         }
 
-        let router = GnCore::new(router_config, Instant::now());
+        let now = Instant::now();
+        let mut router = GnCore::new(router_config, now);
+
+        if let Some(leap_seconds) = &config.leap_seconds {
+            router
+                .clock_mut()
+                .set_leap_seconds(leap_seconds.clone(), now);
+        }
+
+        // Restore the remote certificates learned over the air before the last shutdown.
+        if let (Some(sec), Some((storage, _))) = (router.security_mut(), &storage_meta) {
+            match sec.restore_remote_certificates(storage.as_ref(), now) {
+                Ok(n) => debug!("restored {} remote certificates", n),
+                Err(e) => error!("Failed to restore remote certificates: {}", e),
+            }
+        }
+
+        // Mix zones of the configuration are static, they never expire.
+        if let Some(sec) = router.security_mut() {
+            for area in &config.security.mix_zones {
+                sec.notify_mix_zone(*area, Instant::from_micros_const(i64::MAX));
+            }
+        }
+
+        // Misbehavior reporting, if a Misbehavior Authority is configured or advertised.
+        let misbehavior_reporter = match (router.security(), &storage_meta) {
+            (Some(sec), Some((storage, _))) => {
+                MisbehaviorReporter::setup(config, sec, storage.as_ref(), now)
+                    .inspect_err(|e| {
+                        error!("Failed to setup misbehavior reporting: {}", e);
+                    })
+                    .ok()
+                    .flatten()
+            }
+            _ => None,
+        };
+
         let ll_addr = router.address().mac_addr();
 
         // Configure interface
@@ -162,6 +231,18 @@ impl<'a> Router<'a> {
         let mut cam_socket = socket::cam::Socket::new();
         let mut denm_socket = socket::denm::Socket::new(vec![], vec![]);
 
+        // Restore the DENMs originated before the last stop.
+        if let Some(path) = &config.denm_table_file {
+            match load_denm_table(path) {
+                Ok(Some(table)) => {
+                    let n = denm_socket.restore_originating_table(&router, table);
+                    info!("Restored {} originated DENMs", n);
+                }
+                Ok(None) => {}
+                Err(e) => error!("Failed to restore originated DENMs: {}", e),
+            }
+        }
+
         // Register the CAM tx callback.
         let ipc_cam_tx = ipc.publisher();
         cam_socket.register_send_callback(move |uper, _| {
@@ -229,6 +310,23 @@ impl<'a> Router<'a> {
         let gnss =
             GnssSource::new(config, gnss_registry, GNSS_TOKEN).map_err(RouterError::GnssCreate)?;
 
+        // Signals.
+        let mut signals = Signals::new().map_err(RouterError::SignalsSetup)?;
+        signals
+            .register(poll.registry(), SIGNAL_TOKEN)
+            .map_err(RouterError::SignalsSetup)?;
+
+        // Metrics endpoint.
+        let metrics = config
+            .metrics_port
+            .map(|port| -> io::Result<MetricsServer> {
+                let mut server = MetricsServer::new(port)?;
+                server.register(poll.registry(), METRICS_TOKEN)?;
+                Ok(server)
+            })
+            .transpose()
+            .map_err(RouterError::MetricsSetup)?;
+
         Ok(Router {
             storage_meta,
             poll,
@@ -243,19 +341,30 @@ impl<'a> Router<'a> {
             denm_socket_handle,
             poll_errors_num: 0,
             max_poll_errors_num: 10000,
+            signals,
+            notifier: Notifier::from_env(),
+            metrics,
+            misbehavior_reporter,
+            config_path: config_path.to_owned(),
+            denm_shutdown: config.denm_shutdown,
+            denm_table_file: config.denm_table_file.clone(),
         })
     }
 
+    /// Run the router until a SIGTERM or SIGINT signal is received.
     pub fn run(&mut self) {
         let mut events = Events::with_capacity(128);
         debug!("running the geonetworking router");
+        self.notifier.ready();
 
+        let mut running = true;
         loop {
             self.check_for_poll_errors();
 
             // Update timestamp.
             let now = Instant::now();
             self.router.set_timestamp(now);
+            self.notifier.watchdog(now);
 
             // Process each event.
             for event in events.iter() {
@@ -295,6 +404,27 @@ impl<'a> Router<'a> {
                                     .ok();
                             });
                         }
+                        GnssSource::Serial(serial) => {
+                            serial.ready(event, now).then(|| {
+                                serial
+                                    .fetch_position()
+                                    .try_into()
+                                    .map(|fix| {
+                                        self.router.set_position(fix, now).ok();
+                                    })
+                                    .ok();
+                            });
+                        }
+                        GnssSource::Ubx(ubx) => {
+                            ubx.ready(event, now).then(|| {
+                                ubx.fetch_position()
+                                    .try_into()
+                                    .map(|fix| {
+                                        self.router.set_position(fix, now).ok();
+                                    })
+                                    .ok();
+                            });
+                        }
                         _ => panic!("Unexpected GNSS source"),
                     },
                     IPC_REP_TOKEN => loop {
@@ -302,6 +432,15 @@ impl<'a> Router<'a> {
                         match rep.events() {
                             Ok(evts) if evts.contains(zmq::POLLIN) => match rep.recv() {
                                 Ok(data) => match IpcEvent::deserialize(&data) {
+                                    Ok(IpcEvent {
+                                        event_type: Some(IpcEventType::StatsRequest(req)),
+                                        ..
+                                    }) => {
+                                        let resp = self.snapshot().reply(&req);
+                                        rep.send(&resp.serialize_to_vec()).inspect_err(|e|{
+                                            error!("Failed to send IPC stats response on IPC replier: {}", e);
+                                        }).ok();
+                                    }
                                     Ok(evt) => {
                                         let Ok(resp) = self.ipc_dispatcher.dispatch(
                                             evt,
@@ -327,6 +466,22 @@ impl<'a> Router<'a> {
                             Err(e) => error!("Cannot query ZMQ events on IPC replier: {}", e),
                         }
                     },
+                    SIGNAL_TOKEN => {
+                        for signal in self.signals.pending() {
+                            match signal {
+                                Signal::Terminate => running = false,
+                                Signal::Reload => self.reload(now),
+                            }
+                        }
+                    }
+                    token if self.metrics.as_ref().is_some_and(|m| m.owns(token)) => {
+                        if let Some(mut metrics) = self.metrics.take() {
+                            metrics.ready(self.poll.registry(), token, now, || {
+                                self.snapshot().to_prometheus()
+                            });
+                            self.metrics = Some(metrics);
+                        }
+                    }
                     // We don't expect any events with tokens other than those we provided.
                     _ => unreachable!(),
                 }
@@ -337,6 +492,12 @@ impl<'a> Router<'a> {
                 GnssSource::Gpsd(gpsd) => {
                     gpsd.poll(now).ok();
                 }
+                GnssSource::Serial(serial) => {
+                    serial.poll(now).ok();
+                }
+                GnssSource::Ubx(ubx) => {
+                    ubx.poll(now).ok();
+                }
                 GnssSource::Replay(replay) => {
                     if let Ok(true) = replay.poll(now) {
                         if let Ok(fix) = replay.fetch_position().try_into() {
@@ -355,14 +516,27 @@ impl<'a> Router<'a> {
                 }
             }
 
+            // Close the stalled metrics connections.
+            if let Some(metrics) = &mut self.metrics {
+                metrics.poll(self.poll.registry(), now);
+            }
+
             // Poll the router core for internal processing.
             match self.router.poll(&mut self.iface, now) {
                 GnCorePollEvent::None => {}
                 GnCorePollEvent::SecurityService(evt) => match evt {
                     SecurityServicePollEvent::PrivacyATCertificateRotation(i, _)
                     | SecurityServicePollEvent::ATCertificateExpiration(i, _) => {
+                        let usages = self
+                            .router
+                            .security_mut()
+                            .map(|sec| sec.at_daily_usages(now))
+                            .unwrap_or_default();
                         self.storage_meta.as_mut().map(|(storage, meta)| {
                             meta.increment_elections_stats(i);
+                            for (index, usage) in usages {
+                                meta.set_daily_usage(index, usage);
+                            }
                             storage
                                 .store_metadata(meta.to_owned())
                                 .inspect_err(|e| {
@@ -374,29 +548,39 @@ impl<'a> Router<'a> {
                 },
             }
 
+            // Report the misbehavior detections to the MA.
+            if let (Some(sec), Some(reporter)) =
+                (self.router.security_mut(), &mut self.misbehavior_reporter)
+            {
+                reporter.poll(sec, now);
+            }
+
+            // Persist the remote certificates learned over the air.
+            if let (Some(sec), Some((storage, _))) =
+                (self.router.security_mut(), &self.storage_meta)
+            {
+                sec.persist_remote_certificates(storage.as_ref(), now)
+                    .inspect_err(|e| {
+                        error!("Failed to persist remote certificates: {}", e);
+                    })
+                    .ok();
+            }
+
             // Poll the stack for egress or internal processing.
-            match &mut self.device {
-                AnyDevice::NxpLlc(d) => {
-                    self.iface
-                        .poll_egress(&mut self.router, d, &mut self.sockets)
-                }
-                AnyDevice::NxpUsb(d) => {
-                    self.iface
-                        .poll_egress(&mut self.router, d, &mut self.sockets)
-                }
-                AnyDevice::RawEthernet(d) => {
-                    self.iface
-                        .poll_egress(&mut self.router, d, &mut self.sockets)
-                }
-                AnyDevice::Udp(d) => self
-                    .iface
-                    .poll_egress(&mut self.router, d, &mut self.sockets),
-            };
+            self.poll_egress();
 
             let denm_socket = self
                 .sockets
                 .get_mut::<socket::denm::Socket>(self.denm_socket_handle);
-            denm_socket.poll(now);
+
+            // Persist the originated DENMs on each trigger, update or termination.
+            if denm_socket.poll(now).poll_out_evt().is_some() {
+                self.persist_denm_table(now);
+            }
+
+            if !running {
+                break;
+            }
 
             // Poll Mio for events, blocking until we get an event or a timeout.
             let timeout = self.compute_timeout(now);
@@ -412,6 +596,176 @@ impl<'a> Router<'a> {
                 }
             }
         }
+
+        self.shutdown(Instant::now());
+    }
+
+    /// Stop the router: terminate the active DENMs according to the shutdown policy,
+    /// persist the originated DENMs and flush the storage.
+    fn shutdown(&mut self, now: Instant) {
+        info!("Stopping the geonetworking router");
+        self.notifier.stopping();
+        self.router.set_timestamp(now);
+
+        let denm_socket = self
+            .sockets
+            .get_mut::<socket::denm::Socket>(self.denm_socket_handle);
+        let terminated = match self.denm_shutdown {
+            DenmShutdownPolicy::Keep => 0,
+            DenmShutdownPolicy::Cancel => denm_socket.cancel_all(&self.router),
+            DenmShutdownPolicy::Negate => {
+                denm_socket.cancel_all(&self.router) + denm_socket.negate_all(&self.router)
+            }
+        };
+
+        if terminated > 0 {
+            debug!("Sending {} DENM terminations", terminated);
+            self.drain(terminated as u64, now + SHUTDOWN_DRAIN_TIMEOUT);
+        }
+
+        let now = Instant::now();
+        self.persist_denm_table(now);
+
+        let usages = self
+            .router
+            .security_mut()
+            .map(|sec| sec.at_daily_usages(now))
+            .unwrap_or_default();
+        if let Some((storage, meta)) = &mut self.storage_meta {
+            for (index, usage) in usages {
+                meta.set_daily_usage(index, usage);
+            }
+            storage
+                .store_metadata(meta.to_owned())
+                .inspect_err(|e| {
+                    error!("Failed to store AT certificates metadata: {}", e);
+                })
+                .ok();
+        }
+
+        if let (Some(sec), Some((storage, _))) = (self.router.security_mut(), &self.storage_meta) {
+            sec.persist_remote_certificates(storage.as_ref(), now)
+                .inspect_err(|e| {
+                    error!("Failed to persist remote certificates: {}", e);
+                })
+                .ok();
+        }
+    }
+
+    /// Poll the stack until the DENM socket has transmitted `pending` more messages and
+    /// the DCC queues are empty, or until `deadline`.
+    fn drain(&mut self, pending: u64, deadline: Instant) {
+        let handle = self.denm_socket_handle;
+        let denm_tx = |sockets: &SocketSet| sockets.get::<socket::denm::Socket>(handle).stats().tx;
+        let target = denm_tx(&self.sockets) + pending;
+
+        loop {
+            // Terminations may wait in the DCC queues, let the clock run so they are released.
+            let now = Instant::now();
+            self.router.set_timestamp(now);
+            self.poll_egress();
+
+            let sent = denm_tx(&self.sockets);
+            let backlog = self.iface.congestion_total_backlog();
+            if sent >= target && backlog == 0 {
+                break;
+            }
+
+            if now >= deadline {
+                warn!(
+                    "Stopping with {} DENM terminations not sent and {} packets in DCC queues",
+                    target.saturating_sub(sent),
+                    backlog
+                );
+                break;
+            }
+
+            let remaining: std::time::Duration = (deadline - now).into();
+            let delay = self
+                .compute_timeout(now)
+                .map_or(remaining, |d| d.min(remaining));
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// Reload the configuration file and apply its reloadable values, ie: log level,
+    /// leap seconds, station type, signer policy, required time accuracy and DENM shutdown
+    /// policy. Other values require a restart to be applied.
+    fn reload(&mut self, now: Instant) {
+        info!("Reloading configuration from {}", self.config_path);
+        self.notifier.reloading();
+
+        match Config::load(&self.config_path) {
+            Ok(config) => {
+                reload_logging(&config.log_level);
+
+                if let Some(leap_seconds) = config.leap_seconds {
+                    self.router.clock_mut().set_leap_seconds(leap_seconds, now);
+                }
+
+                self.router.set_station_type(config.station_type);
+
+                if let Some(sec) = self.router.security_mut() {
+                    sec.set_signer_policy(config.security.signer_policy);
+                    sec.set_required_time_accuracy(config.security.required_time_accuracy);
+                } else if config.security.enable {
+                    warn!("Security cannot be enabled on reload, restart to apply");
+                }
+
+                self.denm_shutdown = config.denm_shutdown;
+            }
+            Err(e) => error!("Failed to reload configuration, keeping current one: {}", e),
+        }
+
+        self.notifier.ready();
+    }
+
+    /// Persist the DENMs originated by the station, if enabled.
+    fn persist_denm_table(&self, now: Instant) {
+        let Some(path) = &self.denm_table_file else {
+            return;
+        };
+
+        let table = self
+            .sockets
+            .get::<socket::denm::Socket>(self.denm_socket_handle)
+            .originating_table(now);
+
+        store_denm_table(path, table)
+            .inspect_err(|e| {
+                error!("Failed to persist originated DENMs: {}", e);
+            })
+            .ok();
+    }
+
+    /// Collect a snapshot of the stack statistics.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot::collect(
+            &self.iface,
+            &self.router,
+            &self.sockets,
+            self.cam_socket_handle,
+            self.denm_socket_handle,
+        )
+    }
+
+    /// Poll the stack for egress or internal processing.
+    fn poll_egress(&mut self) {
+        match &mut self.device {
+            AnyDevice::NxpLlc(d) => self
+                .iface
+                .poll_egress(&mut self.router, d, &mut self.sockets),
+            AnyDevice::NxpUsb(d) => self
+                .iface
+                .poll_egress(&mut self.router, d, &mut self.sockets),
+            AnyDevice::RawEthernet(d) => {
+                self.iface
+                    .poll_egress(&mut self.router, d, &mut self.sockets)
+            }
+            AnyDevice::Udp(d) => self
+                .iface
+                .poll_egress(&mut self.router, d, &mut self.sockets),
+        };
     }
 
     /// Compute the timeout to use for the next poll.
@@ -424,11 +778,20 @@ impl<'a> Router<'a> {
             None
         };
 
-        [iface_timeout, router_timeout, gnss_timeout]
-            .into_iter()
-            .flatten()
-            .min()
-            .map(Into::into)
+        let watchdog_timeout = self.notifier.poll_delay(now);
+        let metrics_timeout = self.metrics.as_ref().and_then(|m| m.poll_delay(now));
+
+        [
+            iface_timeout,
+            router_timeout,
+            gnss_timeout,
+            watchdog_timeout,
+            metrics_timeout,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .map(Into::into)
     }
 
     /// Check for polling errors.
@@ -1,29 +1,43 @@
 use core::fmt;
-use std::{collections::BTreeMap, rc::Rc};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    rc::Rc,
+};
 
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use veloce::{
     network::core::SecurityConfig as RouterSecurityConfig,
     pki::{
-        message::crl::{CertificateRevocationList, CertificateRevocationListError},
-        service::PkiClientService,
+        message::{
+            crl::{CertificateRevocationList, CertificateRevocationListError},
+            ctl::{CertificateTrustListError, CtlCommands},
+            ma::{MisbehaviorAuthority, MisbehaviorAuthorityError},
+        },
+        service::{PkiClientService, client::misbehavior::MisbehaviorReportQueue},
     },
     security::{
-        ATContainer, DirectoryStorage, EcdsaKey, SecurityBackend, SecurityStorageMetadata,
-        TrustChain,
+        ATContainer, DirectoryStorage, EcdsaKey, HashedId8, SecurityBackend, SecurityService,
+        SecurityStorageMetadata, TrustChain,
         backend::{BackendError, BackendTrait, PkiBackendTrait},
         certificate::{
             AuthorizationAuthorityCertificate, AuthorizationTicketCertificate, CertificateError,
             CertificateWithHashContainer, EnrollmentAuthorityCertificate,
             EnrollmentCredentialCertificate, ExplicitCertificate, RootCertificate,
+            TrustListManagerCertificate,
         },
         storage::{StorageError, StorageTrait},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use crate::{config::Config, utils};
 
+/// Delay before a new misbehavior reports submission attempt, after a failed one.
+const MBR_RETRY_DELAY: Duration = Duration::from_secs(30);
+
 pub type SecurityResult<T> = core::result::Result<T, SecurityError>;
 
 #[derive(Debug)]
@@ -42,10 +56,22 @@ pub enum SecurityError {
     CRLLoad(StorageError),
     /// CRL format error.
     CRL(CertificateRevocationListError),
+    /// Failed to load ECTL.
+    ECTLLoad(StorageError),
+    /// ECTL format error.
+    ECTL(CertificateTrustListError),
+    /// ECTL is not signed by the stored TLM certificate.
+    UntrustedECTL,
     /// False signature.
     FalseSignature,
     /// Crypto or storage backend error.
     CryptoStorage(utils::UtilError),
+    /// Misbehavior Authority URL is set without its certificate.
+    NoMACertificate,
+    /// Failed to load the Misbehavior Authority certificate.
+    MACertificateLoad(io::Error),
+    /// Misbehavior Authority certificate is invalid or untrusted.
+    MACertificate(MisbehaviorAuthorityError),
 }
 
 impl fmt::Display for SecurityError {
@@ -60,8 +86,14 @@ impl fmt::Display for SecurityError {
             SecurityError::CertificateHash(e) => write!(f, "cannot compute certificate hash: {e}"),
             SecurityError::CRLLoad(e) => write!(f, "failed to load CRL: {e}"),
             SecurityError::CRL(e) => write!(f, "failed to parse CRL: {e}"),
+            SecurityError::ECTLLoad(e) => write!(f, "failed to load ECTL: {e}"),
+            SecurityError::ECTL(e) => write!(f, "failed to parse ECTL: {e}"),
+            SecurityError::UntrustedECTL => write!(f, "ECTL is not signed by the TLM"),
             SecurityError::FalseSignature => write!(f, "false signature"),
             SecurityError::CryptoStorage(e) => write!(f, "failed to setup storage and crypto: {e}"),
+            SecurityError::NoMACertificate => write!(f, "no MA certificate configured"),
+            SecurityError::MACertificateLoad(e) => write!(f, "cannot load MA certificate: {e}"),
+            SecurityError::MACertificate(e) => write!(f, "invalid MA certificate: {e}"),
         }
     }
 }
@@ -86,10 +118,24 @@ pub fn setup_security(
         info!("Loading certificates and setting up the trust chain");
         let (trust_chain, meta) = setup_trust_chain(storage.as_ref(), &backend)?;
 
+        info!("Loading root certificates trusted by the ECTL");
+        let trusted_roots = load_trusted_roots(storage.as_ref(), Instant::now(), &backend)
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to load ECTL, no remote root certificate is trusted: {}",
+                    e
+                );
+                Vec::new()
+            });
+
         let security_config = RouterSecurityConfig {
             security_backend: SecurityBackend::Openssl(backend),
             own_trust_chain: trust_chain,
+            trusted_roots,
             privacy_strategy: config.security.privacy_strategy,
+            signer_policy: config.security.signer_policy.clone(),
+            defer_pseudonym_change: config.security.defer_pseudonym_change,
+            required_time_accuracy: config.security.required_time_accuracy,
         };
 
         Some((security_config, storage, meta))
@@ -100,6 +146,169 @@ pub fn setup_security(
     Ok(res)
 }
 
+/// Misbehavior reporting to the Misbehavior Authority (MA). The misbehavior detections of
+/// the security service are reported with the current AT certificate, then submitted to
+/// the MA over HTTP.
+#[derive(Debug)]
+pub struct MisbehaviorReporter {
+    /// PKI client, emitting the reports.
+    client: PkiClientService,
+    /// Misbehavior Authority the reports are submitted to.
+    ma: MisbehaviorAuthority,
+    /// Reports waiting for their submission.
+    queue: MisbehaviorReportQueue,
+    /// Submission request timeout.
+    timeout: Duration,
+    /// Instant of the next submission attempt, after a failed one.
+    retry_at: Instant,
+}
+
+impl MisbehaviorReporter {
+    /// Setup the misbehavior reporter with the Misbehavior Authority set in the configuration,
+    /// or else with the one advertised by the ECTL of the `storage`. The MA certificate is
+    /// checked at `timestamp` against the roots of the `sec` trust store.
+    /// Returns None if misbehavior reporting is disabled, ie: no MA is set nor advertised.
+    pub fn setup<S: StorageTrait>(
+        config: &Config,
+        sec: &SecurityService,
+        storage: &S,
+        timestamp: Instant,
+    ) -> SecurityResult<Option<Self>> {
+        let SecurityBackend::Openssl(backend) = sec.backend();
+
+        let ma = if let Some(ma_url) = &config.security.ma_url {
+            let path = config
+                .security
+                .ma_certificate
+                .as_ref()
+                .ok_or(SecurityError::NoMACertificate)?;
+            let bytes = fs::read(path).map_err(SecurityError::MACertificateLoad)?;
+            MisbehaviorAuthority::from_bytes(
+                &bytes,
+                ma_url.clone(),
+                sec.store(),
+                timestamp,
+                backend,
+            )
+        } else {
+            // A missing or invalid ECTL is already reported with the trusted roots loading.
+            let Some(entry) = load_ectl_commands(storage, timestamp, backend)
+                .ok()
+                .and_then(|commands| commands.add.ma.into_iter().next())
+            else {
+                return Ok(None);
+            };
+            MisbehaviorAuthority::from_ctl_entry(entry, sec.store(), timestamp, backend)
+        }
+        .map_err(SecurityError::MACertificate)?;
+
+        Ok(Some(Self::new(
+            PkiClientService::new(config.security.canonical_identifier.clone()),
+            ma,
+            Duration::from_secs(config.security.timeout.into()),
+        )))
+    }
+
+    /// Constructs a [MisbehaviorReporter], submitting the reports emitted by `client`
+    /// to `ma`, with a request `timeout`.
+    pub fn new(client: PkiClientService, ma: MisbehaviorAuthority, timeout: Duration) -> Self {
+        Self {
+            client,
+            ma,
+            queue: MisbehaviorReportQueue::new(),
+            timeout,
+            retry_at: Instant::ZERO,
+        }
+    }
+
+    /// Report the misbehavior detections taken from `sec`, then submit the waiting reports to
+    /// the MA. Submission blocks the caller up to the request timeout per report, and is
+    /// attempted again after [MBR_RETRY_DELAY] on failure.
+    pub fn poll(&mut self, sec: &mut SecurityService, now: Instant) {
+        let detections = sec.take_misbehavior_detections();
+        if !detections.is_empty() {
+            let own_chain = sec.store().own_chain();
+            match (own_chain.at_cert(), own_chain.at_cert_index()) {
+                (Some(at), Some(index)) => {
+                    let SecurityBackend::Openssl(backend) = sec.backend();
+                    let enqueued = self.client.enqueue_misbehavior_reports(
+                        detections,
+                        &mut self.queue,
+                        at.at_container(),
+                        index,
+                        &self.ma,
+                        now,
+                        backend,
+                    );
+                    debug!("Enqueued {} misbehavior reports", enqueued);
+                }
+                _ => warn!(
+                    "Cannot report {} misbehavior detections: no AT certificate",
+                    detections.len()
+                ),
+            }
+        }
+
+        if self.queue.is_empty() || now < self.retry_at {
+            return;
+        }
+
+        let (url, timeout) = (self.ma.access_point(), self.timeout);
+        match self
+            .queue
+            .submit(now, |report| http_post(url, report, timeout))
+        {
+            Ok(0) => {}
+            Ok(submitted) => info!("Submitted {} misbehavior reports to the MA", submitted),
+            Err(e) => {
+                warn!("Failed to submit misbehavior reports to the MA: {}", e);
+                self.retry_at = now + MBR_RETRY_DELAY;
+            }
+        }
+    }
+}
+
+/// Send `body` to `url` in an HTTP POST request, as a PKI message of ETSI TS 102 941.
+/// Only plain HTTP URLs are supported. Succeeds if the server answers with a 2xx status.
+fn http_post(url: &str, body: &[u8], timeout: Duration) -> io::Result<()> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(io::ErrorKind::Unsupported, format!("unsupported URL {url}"))
+    })?;
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let path = if path.is_empty() { "/" } else { path };
+
+    let addr = if authority.contains(':') {
+        authority.to_socket_addrs()
+    } else {
+        (authority, 80).to_socket_addrs()
+    }?
+    .next()
+    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("cannot resolve {url}")))?;
+
+    let timeout: std::time::Duration = timeout.into();
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/x-its-request\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+
+    // Only the status line is checked.
+    let mut status = String::new();
+    BufReader::new(stream).read_line(&mut status)?;
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(io::Error::other(format!(
+            "unexpected response: {}",
+            status.trim_end()
+        ))),
+    }
+}
+
 /// Setup the trust chain from the storage.
 /// This function will load the root certificate, the AA certificate if any, the EA certificate if any, the EC certificate if any
 /// and the AT certificates if any.
@@ -161,7 +370,10 @@ pub(crate) fn setup_trust_chain<B: PkiBackendTrait, S: StorageTrait>(
                                 at_metadata.set_elections_stats(*i, 0);
                                 0
                             });
-                            let container = ATContainer::new(c, elected);
+                            let mut container = ATContainer::new(c, elected);
+                            if let Some(usage) = at_metadata.daily_usage(*i) {
+                                container.set_daily_usage(usage);
+                            }
                             (*i, container)
                         })
                  })
@@ -356,3 +568,224 @@ fn load_and_check_crl<B: BackendTrait, S: StorageTrait>(
     PkiClientService::parse_and_check_crl(&crl_bytes, &root_cert, timestamp, backend)
         .map_err(SecurityError::CRL)
 }
+
+/// Load the ECTL from the storage and return the identifiers of the root certificates it lists.
+/// The ECTL should be signed by the TLM certificate of the storage.
+fn load_trusted_roots<B: PkiBackendTrait, S: StorageTrait>(
+    storage: &S,
+    timestamp: Instant,
+    backend: &B,
+) -> SecurityResult<Vec<HashedId8>> {
+    let commands = load_ectl_commands(storage, timestamp, backend)?;
+    commands
+        .add
+        .root
+        .into_iter()
+        .map(|entry| {
+            RootCertificate::from_etsi_cert(entry.certificate, backend)
+                .and_then(|c| c.hashed_id8(backend))
+                .map_err(SecurityError::Certificate)
+        })
+        .collect()
+}
+
+/// Load the ECTL from the storage and return its commands.
+/// The ECTL should be signed by the TLM certificate of the storage.
+fn load_ectl_commands<B: PkiBackendTrait, S: StorageTrait>(
+    storage: &S,
+    timestamp: Instant,
+    backend: &B,
+) -> SecurityResult<CtlCommands> {
+    let tlm_cert_bytes = storage
+        .load_tlm_certificate()
+        .map_err(SecurityError::CertificateLoad)?;
+    let tlm_cert = load_and_check_cert::<
+        B,
+        TrustListManagerCertificate,
+        TrustListManagerCertificate,
+    >(tlm_cert_bytes, timestamp, None, backend)?;
+
+    let ectl_bytes = storage.load_ectl().map_err(SecurityError::ECTLLoad)?;
+    let (ectl, signer) = PkiClientService::parse_and_check_ectl(&ectl_bytes, timestamp, backend)
+        .map_err(SecurityError::ECTL)?;
+
+    let signer_hash = signer
+        .hashed_id8(backend)
+        .map_err(SecurityError::CertificateHash)?;
+    if signer_hash != tlm_cert.hashed_id8() {
+        return Err(SecurityError::UntrustedECTL);
+    }
+
+    ectl.commands().map_err(SecurityError::ECTL)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use veloce::{
+        common::PotiPosition,
+        security::{
+            DirectoryStorageConfig, OpensslBackend, OpensslBackendConfig,
+            certificate::CertificateTrait,
+            misbehavior::{MisbehaviorCheck, MisbehaviorReaction, MisbehaviorVerdict},
+            permission::Permission,
+            privacy::PrivacyStrategy,
+            ssp::cam::CamSsp,
+        },
+        types::{Latitude, Longitude, degree},
+    };
+
+    use super::*;
+
+    /// Setup a [SecurityService] with the security test assets of the veloce crate,
+    /// signing with the first AT.
+    fn setup_security_service() -> SecurityService {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../veloce/src/security/tests");
+        let storage_config = DirectoryStorageConfig::new(Some(path.to_string()));
+        let storage = Rc::new(DirectoryStorage::new(storage_config).unwrap());
+        let crypto_config = OpensslBackendConfig::new("test1234".to_string().into());
+        let mut backend = OpensslBackend::new(crypto_config, storage.clone()).unwrap();
+        backend.set_at_key_index(0).unwrap();
+
+        let root_cert =
+            RootCertificate::from_bytes(&storage.load_root_certificate().unwrap(), &backend)
+                .and_then(|c| c.into_with_hash_container(&backend))
+                .unwrap();
+        let aa_cert = AuthorizationAuthorityCertificate::from_bytes(
+            &storage.load_aa_certificate().unwrap(),
+            &backend,
+        )
+        .and_then(|c| c.into_with_hash_container(&backend))
+        .unwrap();
+        let at_cert = AuthorizationTicketCertificate::from_bytes(
+            &storage.load_at_certificate(0).unwrap(),
+            &backend,
+        )
+        .and_then(|c| c.into_with_hash_container(&backend))
+        .unwrap();
+
+        let mut own_chain = TrustChain::new(root_cert);
+        own_chain.set_aa_cert(aa_cert);
+        own_chain.add_at_cert(0, ATContainer::new(at_cert, 0));
+        own_chain.set_at_cert_index(0).unwrap();
+
+        SecurityService::new(
+            own_chain,
+            SecurityBackend::Openssl(backend),
+            PrivacyStrategy::NoStrategy,
+        )
+    }
+
+    /// Serve a single HTTP request on `listener`, answered with `status`.
+    /// Returns the request head and body.
+    fn serve(listener: TcpListener, status: &'static str) -> JoinHandle<(String, Vec<u8>)> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            while !head.ends_with("\r\n\r\n") {
+                reader.read_line(&mut head).unwrap();
+            }
+
+            let len = head
+                .lines()
+                .find_map(|l| l.strip_prefix("Content-Length: "))
+                .and_then(|l| l.parse().ok())
+                .unwrap();
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n"
+            )
+            .unwrap();
+            (head, body)
+        })
+    }
+
+    #[test]
+    fn test_misbehavior_report_submission() {
+        let timestamp = Instant::from_secs(1_716_674_400);
+        let position = PotiPosition {
+            latitude: Some(Latitude::new::<degree>(48.2764384)),
+            longitude: Some(Longitude::new::<degree>(-3.5519532)),
+            altitude: None,
+        };
+
+        // Receive a CAM and detect a misbehavior on it.
+        let mut sec = setup_security_service();
+        let gn_cam = vec![0x20, 0x50, 0x02, 0x00, 0x00, 0x1e, 0x01, 0x00];
+        let secured = sec
+            .encap_packet(
+                gn_cam,
+                Permission::CAM(CamSsp::new_v1().into()),
+                timestamp,
+                position,
+            )
+            .unwrap();
+        let rx_at = timestamp + Duration::from_millis(50);
+        let (confirm, _) = sec.decap_packet(&secured, rx_at).unwrap();
+
+        let verdict = MisbehaviorVerdict {
+            detections: vec![MisbehaviorCheck::PositionJump],
+            reaction: MisbehaviorReaction::Flag,
+        };
+        sec.record_misbehavior(&confirm, &verdict, position.as_3d_location(), rx_at);
+
+        // The MA certificate is issued by a dedicated test root, which has to be trusted.
+        let assets = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../veloce/src/security/tests/assets"
+        );
+        let raw_ma_cert = fs::read(format!("{assets}/MA.cert")).unwrap();
+        let raw_ma_root_cert = fs::read(format!("{assets}/MA_RCA.cert")).unwrap();
+        let ma_root = RootCertificate::from_bytes(&raw_ma_root_cert, sec.backend().inner())
+            .and_then(|c| c.into_with_hash_container(sec.backend().inner()))
+            .unwrap();
+        sec.store_mut()
+            .set_trusted_roots(vec![ma_root.hashed_id8()]);
+        assert!(sec.store_mut().add_remote_root(ma_root));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mbr", listener.local_addr().unwrap());
+        let ma = MisbehaviorAuthority::from_bytes(
+            &raw_ma_cert,
+            url,
+            sec.store(),
+            rx_at,
+            sec.backend().inner(),
+        )
+        .unwrap();
+        let mut reporter = MisbehaviorReporter::new(
+            PkiClientService::new("veloce".to_string()),
+            ma,
+            Duration::from_secs(5),
+        );
+
+        // MA is failing, the report is kept until the retry delay elapsed.
+        let server = serve(listener.try_clone().unwrap(), "500 Internal Server Error");
+        reporter.poll(&mut sec, rx_at);
+        server.join().unwrap();
+        assert_eq!(reporter.queue.len(), 1);
+        assert!(sec.take_misbehavior_detections().is_empty());
+
+        reporter.poll(&mut sec, rx_at + Duration::from_secs(1));
+        assert_eq!(reporter.queue.len(), 1);
+
+        let server = serve(listener, "200 OK");
+        reporter.poll(&mut sec, rx_at + MBR_RETRY_DELAY);
+        let (head, body) = server.join().unwrap();
+        assert!(reporter.queue.is_empty());
+
+        assert!(head.starts_with("POST /mbr HTTP/1.1\r\n"));
+        assert!(head.contains("Content-Type: application/x-its-request\r\n"));
+        assert!(!body.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    io::{self, Read},
+    mem,
+    os::fd::AsRawFd,
+    ptr,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use mio::{
+    Interest, Registry, Token,
+    unix::pipe::{self, Receiver, Sender},
+};
+
+/// Write end of the signal pipe, used by the signal handler.
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Signal handler, forwarding the signal number on the signal pipe.
+extern "C" fn on_signal(signum: libc::c_int) {
+    let fd = SIGNAL_PIPE.load(Ordering::Relaxed);
+    if fd < 0 {
+        return;
+    }
+
+    // write(2) may change errno, which must be preserved for the interrupted code.
+    let errno = unsafe { *libc::__errno_location() };
+
+    // write(2) is async-signal-safe. If the pipe is full, the signal is dropped, which
+    // is harmless as the same signals are already waiting to be processed.
+    let byte = signum as u8;
+    unsafe { libc::write(fd, &byte as *const u8 as *const libc::c_void, 1) };
+
+    unsafe { *libc::__errno_location() = errno };
+}
+
+/// Signal received by the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGTERM or SIGINT. Process should stop gracefully.
+    Terminate,
+    /// SIGHUP. Configuration should be reloaded.
+    Reload,
+}
+
+/// Unix signals, delivered as mio events using a self-pipe.
+#[derive(Debug)]
+pub struct Signals {
+    /// Write end of the pipe. Kept open for the signal handler.
+    _sender: Sender,
+    /// Read end of the pipe.
+    receiver: Receiver,
+}
+
+impl Signals {
+    /// Install the handlers for SIGTERM, SIGINT and SIGHUP.
+    pub fn new() -> io::Result<Signals> {
+        let (sender, receiver) = pipe::new()?;
+        let fd = sender.as_raw_fd();
+        SIGNAL_PIPE.store(fd, Ordering::Relaxed);
+
+        for signum in [libc::SIGTERM, libc::SIGINT, libc::SIGHUP] {
+            let mut action: libc::sigaction = unsafe { mem::zeroed() };
+            action.sa_sigaction = on_signal as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+
+            let res = unsafe {
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signum, &action, ptr::null_mut())
+            };
+
+            if res != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(Signals {
+            _sender: sender,
+            receiver,
+        })
+    }
+
+    /// Register the signals source in the `registry`, with `token`.
+    pub fn register(&mut self, registry: &Registry, token: Token) -> io::Result<()> {
+        registry.register(&mut self.receiver, token, Interest::READABLE)
+    }
+
+    /// Returns the signals received since the last call.
+    pub fn pending(&mut self) -> Vec<Signal> {
+        let mut res = Vec::new();
+        let mut buf = [0u8; 16];
+
+        loop {
+            match self.receiver.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => res.extend(buf[..n].iter().filter_map(|s| match (*s).into() {
+                    libc::SIGTERM | libc::SIGINT => Some(Signal::Terminate),
+                    libc::SIGHUP => Some(Signal::Reload),
+                    _ => None,
+                })),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,126 @@
+use std::{env, io, os::unix::net::UnixDatagram};
+
+use log::{debug, error};
+use veloce::time::{Duration, Instant};
+
+/// Service manager notifier, implementing the `sd_notify` protocol.
+///
+/// Notifications are sent on the datagram socket whose path is given by the `NOTIFY_SOCKET`
+/// environment variable. When the variable is not set, ie: Veloce is not started by systemd
+/// as a `Type=notify` service, notifications are silently discarded.
+#[derive(Debug)]
+pub struct Notifier {
+    /// Notification socket and its path.
+    socket: Option<(UnixDatagram, String)>,
+    /// Watchdog keep-alive interval, half of the `WATCHDOG_USEC` timeout.
+    watchdog_interval: Option<Duration>,
+    /// Next instant at which a watchdog keep-alive should be sent.
+    watchdog_at: Instant,
+}
+
+impl Notifier {
+    /// Constructs a [Notifier] from the `NOTIFY_SOCKET`, `WATCHDOG_USEC` and `WATCHDOG_PID`
+    /// environment variables.
+    pub fn from_env() -> Notifier {
+        let socket = env::var("NOTIFY_SOCKET").ok().and_then(|path| {
+            UnixDatagram::unbound()
+                .inspect_err(|e| error!("Failed to create notification socket: {}", e))
+                .ok()
+                .map(|s| (s, path))
+        });
+
+        let watchdog_pid_matches = env::var("WATCHDOG_PID")
+            .ok()
+            .and_then(|pid| pid.parse::<libc::pid_t>().ok())
+            .is_none_or(|pid| pid == unsafe { libc::getpid() });
+
+        let watchdog_interval = env::var("WATCHDOG_USEC")
+            .ok()
+            .and_then(|usec| usec.parse::<u64>().ok())
+            .filter(|usec| *usec > 0 && watchdog_pid_matches && socket.is_some())
+            .map(|usec| Duration::from_micros(usec / 2));
+
+        if let Some(interval) = watchdog_interval {
+            debug!("Watchdog enabled, keep-alive interval {}", interval);
+        }
+
+        Notifier {
+            socket,
+            watchdog_interval,
+            watchdog_at: Instant::ZERO,
+        }
+    }
+
+    /// Notify the service manager startup or configuration reload is finished.
+    pub fn ready(&self) {
+        self.notify("READY=1\nSTATUS=Running");
+    }
+
+    /// Notify the service manager the configuration is being reloaded.
+    pub fn reloading(&self) {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+        let usec = ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1_000;
+
+        self.notify(&format!(
+            "RELOADING=1\nMONOTONIC_USEC={usec}\nSTATUS=Reloading configuration"
+        ));
+    }
+
+    /// Notify the service manager the service is stopping.
+    pub fn stopping(&self) {
+        self.notify("STOPPING=1\nSTATUS=Stopping");
+    }
+
+    /// Send a watchdog keep-alive to the service manager, if due at `now`.
+    pub fn watchdog(&mut self, now: Instant) {
+        let Some(interval) = self.watchdog_interval else {
+            return;
+        };
+
+        if self.watchdog_at <= now {
+            self.notify("WATCHDOG=1");
+            self.watchdog_at = now + interval;
+        }
+    }
+
+    /// Returns the delay until the next watchdog keep-alive.
+    pub fn poll_delay(&self, now: Instant) -> Option<Duration> {
+        self.watchdog_interval.map(|_| {
+            if self.watchdog_at > now {
+                self.watchdog_at - now
+            } else {
+                Duration::ZERO
+            }
+        })
+    }
+
+    /// Send the `state` notification to the service manager.
+    fn notify(&self, state: &str) {
+        let Some((socket, path)) = &self.socket else {
+            return;
+        };
+
+        Self::send(socket, path, state)
+            .inspect_err(|e| error!("Failed to notify service manager: {}", e))
+            .ok();
+    }
+
+    /// Send `state` on `socket`, to `path`. Paths starting with '@' are abstract socket
+    /// addresses.
+    fn send(socket: &UnixDatagram, path: &str, state: &str) -> io::Result<()> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Some(name) = path.strip_prefix('@') {
+            use std::os::linux::net::SocketAddrExt;
+            use std::os::unix::net::SocketAddr;
+
+            let addr = SocketAddr::from_abstract_name(name)?;
+            return socket.send_to_addr(state.as_bytes(), &addr).map(|_| ());
+        }
+
+        socket.send_to(state.as_bytes(), path).map(|_| ())
+    }
+}
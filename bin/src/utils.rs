@@ -1,23 +1,27 @@
 use core::fmt;
 use std::{
-    fs::File,
+    fs::{self, File, OpenOptions},
     io::{self, Write},
+    os::fd::AsRawFd,
     rc::Rc,
     str::{self},
+    sync::{Arc, OnceLock, RwLock},
 };
 
 use crate::{Cli, config::Config};
 
-use env_logger::Builder;
-use log::{Level, LevelFilter};
+use env_logger::{Builder, Logger};
+use log::{Level, LevelFilter, Log, Metadata, Record, debug, error};
 use secrecy::ExposeSecret;
 use veloce::{
     security::{
         DirectoryStorage, DirectoryStorageConfig, OpensslBackend, OpensslBackendConfig,
         backend::openssl::OpensslBackendError, storage::directory::DirectoryStorageError,
     },
+    socket::denm::OriginatingTable,
     time::Instant,
 };
+use veloce_ipc::{denm as ipc_denm, prelude::prost::Message};
 
 #[derive(Debug)]
 pub enum UtilError {
@@ -27,6 +31,14 @@ pub enum UtilError {
     WritePidFile(String, io::Error),
     /// Error while syncing the PID file.
     SyncPidFile(String, io::Error),
+    /// Error while creating the lock file.
+    CreateLockFile(String, io::Error),
+    /// Error while acquiring the lock on the lock file.
+    AcquireLock(String, io::Error),
+    /// Error while writing the PID in the lock file.
+    WriteLockFile(String, io::Error),
+    /// Lock file is locked by another running instance.
+    AlreadyRunning(String),
     /// Error while getting the configuration file path.
     GetConfigFilePath,
     /// Directory storage error.
@@ -38,6 +50,13 @@ pub enum UtilError {
         path: String,
         io_error: std::io::Error,
     },
+    /// Error while writing a file.
+    Writing {
+        path: String,
+        io_error: std::io::Error,
+    },
+    /// Persisted DENM originating table is malformed.
+    InvalidDenmTable(String),
 }
 
 impl fmt::Display for UtilError {
@@ -50,6 +69,16 @@ impl fmt::Display for UtilError {
                 write!(f, "cannot write pid file {path}: {err}")
             }
             UtilError::SyncPidFile(path, err) => write!(f, "cannot sync pid file {path}: {err}"),
+            UtilError::CreateLockFile(path, err) => {
+                write!(f, "cannot create lock file {path}: {err}")
+            }
+            UtilError::AcquireLock(path, err) => write!(f, "cannot lock file {path}: {err}"),
+            UtilError::WriteLockFile(path, err) => {
+                write!(f, "cannot write lock file {path}: {err}")
+            }
+            UtilError::AlreadyRunning(path) => {
+                write!(f, "another instance is already running, holding {path}")
+            }
             UtilError::GetConfigFilePath => write!(
                 f,
                 "No configuration file specified. Either use -c with the start command, or use the VELOCE_CFG_PATH environment variable when building Veloce."
@@ -59,6 +88,12 @@ impl fmt::Display for UtilError {
             UtilError::Reading { path, io_error } => {
                 write!(f, "cannot read file {}: {}", path, io_error)
             }
+            UtilError::Writing { path, io_error } => {
+                write!(f, "cannot write file {}: {}", path, io_error)
+            }
+            UtilError::InvalidDenmTable(path) => {
+                write!(f, "invalid DENM originating table in {path}")
+            }
         }
     }
 }
@@ -71,28 +106,120 @@ pub fn load_file(path: &str) -> Result<String, UtilError> {
     })
 }
 
-pub fn write_pid_file(config: &Config) -> Result<(), UtilError> {
-    let cfg_pid_file_path: Option<&str> = config.pid_file_path.as_ref().map(|p| p.as_ref());
-
-    let maybe_path = match (cfg_pid_file_path, option_env!("VELOCE_PID_FILE_PATH")) {
-        (Some(fp), _) => Some(fp),
-        (None, Some(fp)) => Some(fp),
-        (None, None) => None,
-    };
+/// A file containing the PID of the process, removed when dropped.
+#[derive(Debug)]
+pub struct PidFile {
+    /// Path of the file.
+    path: String,
+    /// File handle.
+    _file: File,
+}
 
-    if let Some(path) = maybe_path {
-        let mut file = File::create(path)
+impl PidFile {
+    /// Create the PID file at `path`.
+    pub fn create(path: &str) -> Result<PidFile, UtilError> {
+        let file = File::create(path)
             .map_err(|io_err| UtilError::CreatePidFile(path.to_owned(), io_err))?;
 
+        Self::write(path, file)
+    }
+
+    fn write(path: &str, mut file: File) -> Result<PidFile, UtilError> {
         let pid = unsafe { libc::getpid() };
 
         file.write_all(format!("{pid}").as_bytes())
             .map_err(|write_err| UtilError::WritePidFile(path.to_owned(), write_err))?;
         file.sync_all()
             .map_err(|sync_err| UtilError::SyncPidFile(path.to_owned(), sync_err))?;
+
+        Ok(PidFile {
+            path: path.to_owned(),
+            _file: file,
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path)
+            .inspect_err(|e| error!("Failed to remove pid file {}: {}", self.path, e))
+            .ok();
+    }
+}
+
+/// A file locked for the lifetime of the process, containing its PID.
+///
+/// The file is never removed: another process could otherwise create and lock a new file
+/// at the same path while the lock is still held on the removed one. It is truncated when
+/// dropped, and the lock is released by the kernel when the process exits, even on a crash.
+#[derive(Debug)]
+pub struct LockFile {
+    /// Path of the file.
+    path: String,
+    /// File handle, holding the lock.
+    file: File,
+}
+
+impl LockFile {
+    /// Open or create the lock file at `path`, lock it and write the PID of the process.
+    /// Fails with [UtilError::AlreadyRunning] if the file is locked by another process.
+    pub fn lock(path: &str) -> Result<LockFile, UtilError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|io_err| UtilError::CreateLockFile(path.to_owned(), io_err))?;
+
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let err = io::Error::last_os_error();
+            return Err(if err.kind() == io::ErrorKind::WouldBlock {
+                UtilError::AlreadyRunning(path.to_owned())
+            } else {
+                UtilError::AcquireLock(path.to_owned(), err)
+            });
+        }
+
+        // Content is only rewritten once the lock is held.
+        let pid = unsafe { libc::getpid() };
+        file.set_len(0)
+            .and_then(|_| file.write_all(format!("{pid}").as_bytes()))
+            .and_then(|_| file.sync_all())
+            .map_err(|io_err| UtilError::WriteLockFile(path.to_owned(), io_err))?;
+
+        Ok(LockFile {
+            path: path.to_owned(),
+            file,
+        })
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        self.file
+            .set_len(0)
+            .inspect_err(|e| error!("Failed to truncate lock file {}: {}", self.path, e))
+            .ok();
     }
+}
+
+pub fn write_pid_file(config: &Config) -> Result<Option<PidFile>, UtilError> {
+    let cfg_pid_file_path: Option<&str> = config.pid_file_path.as_ref().map(|p| p.as_ref());
 
-    Ok(())
+    let maybe_path = match (cfg_pid_file_path, option_env!("VELOCE_PID_FILE_PATH")) {
+        (Some(fp), _) => Some(fp),
+        (None, Some(fp)) => Some(fp),
+        (None, None) => None,
+    };
+
+    maybe_path.map(PidFile::create).transpose()
+}
+
+/// Lock the PHY lock file of the configuration, preventing another instance to run on the same PHY.
+pub fn lock_phy(config: &Config) -> Result<LockFile, UtilError> {
+    debug!("Locking {}", config.lock_file_path);
+    LockFile::lock(&config.lock_file_path)
 }
 
 pub fn get_config_file_path(args: &Cli) -> Result<&str, UtilError> {
@@ -102,10 +229,80 @@ pub fn get_config_file_path(args: &Cli) -> Result<&str, UtilError> {
     }
 }
 
-pub fn setup_logging_with_clock<F>(filter: &str, since_startup: F)
-where
-    F: Fn() -> Instant + Send + Sync + 'static,
-{
+/// Load the DENM originating table persisted at `path`.
+/// Returns [None] if the file does not exist, ie: on the first start.
+pub fn load_denm_table(path: &str) -> Result<Option<OriginatingTable>, UtilError> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(io_error) => {
+            return Err(UtilError::Reading {
+                path: path.to_owned(),
+                io_error,
+            });
+        }
+    };
+
+    ipc_denm::OriginatingTable::decode(bytes.as_slice())
+        .ok()
+        .and_then(|table| OriginatingTable::try_from(table).ok())
+        .map(Some)
+        .ok_or_else(|| UtilError::InvalidDenmTable(path.to_owned()))
+}
+
+/// Persist the DENM originating `table` at `path`. The file is replaced atomically, so a crash
+/// while writing leaves the previous table intact.
+pub fn store_denm_table(path: &str, table: OriginatingTable) -> Result<(), UtilError> {
+    let tmp_path = format!("{path}.tmp");
+    let bytes = ipc_denm::OriginatingTable::from(table).encode_to_vec();
+
+    File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(&bytes)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|io_error| UtilError::Writing {
+            path: path.to_owned(),
+            io_error,
+        })
+}
+
+/// Clock of the log records timestamps.
+type LogClock = Arc<dyn Fn() -> Instant + Send + Sync>;
+
+/// Logger whose filter can be replaced at runtime, ie: on configuration reload.
+struct ReloadableLogger {
+    /// Clock of the log records timestamps.
+    clock: LogClock,
+    /// Inner logger.
+    inner: RwLock<Logger>,
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner
+            .read()
+            .map(|l| l.enabled(metadata))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(l) = self.inner.read() {
+            l.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(l) = self.inner.read() {
+            l.flush();
+        }
+    }
+}
+
+static LOGGER: OnceLock<ReloadableLogger> = OnceLock::new();
+
+fn build_logger(filter: &str, since_startup: LogClock) -> Logger {
     Builder::new()
         .format(move |buf, record| {
             let elapsed = since_startup();
@@ -139,13 +336,44 @@ where
         .filter(None, LevelFilter::Trace)
         .parse_filters(filter)
         .parse_env("VELOCE_LOG")
-        .init();
+        .build()
+}
+
+pub fn setup_logging_with_clock<F>(filter: &str, since_startup: F)
+where
+    F: Fn() -> Instant + Send + Sync + 'static,
+{
+    let clock: LogClock = Arc::new(since_startup);
+    let inner = build_logger(filter, clock.clone());
+    let max_level = inner.filter();
+
+    let logger = LOGGER.get_or_init(|| ReloadableLogger {
+        clock,
+        inner: RwLock::new(inner),
+    });
+
+    log::set_logger(logger).expect("logger already set");
+    log::set_max_level(max_level);
 }
 
 pub fn setup_logging(filter: &str) {
     setup_logging_with_clock(filter, Instant::now)
 }
 
+/// Replace the filter of the logger set up with [setup_logging].
+pub fn reload_logging(filter: &str) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+
+    let inner = build_logger(filter, logger.clock.clone());
+    log::set_max_level(inner.filter());
+
+    if let Ok(mut l) = logger.inner.write() {
+        *l = inner;
+    }
+}
+
 pub fn setup_openssl_and_directory_storage(
     config: &Config,
 ) -> Result<(OpensslBackend, Rc<DirectoryStorage>), UtilError> {
@@ -160,3 +388,37 @@ pub fn setup_openssl_and_directory_storage(
 
     Ok((backend, storage))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_file() {
+        let path = std::env::temp_dir().join(format!("veloce-test-{}.lock", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let lock = LockFile::lock(path).unwrap();
+        let pid = unsafe { libc::getpid() };
+        assert_eq!(fs::read_to_string(path).unwrap(), pid.to_string());
+
+        // A second instance cannot lock the same file.
+        assert!(matches!(
+            LockFile::lock(path),
+            Err(UtilError::AlreadyRunning(p)) if p == path
+        ));
+
+        // The file is kept, empty, once released, and can be locked again.
+        drop(lock);
+        assert!(Path::new(path).exists());
+        assert!(fs::read_to_string(path).unwrap().is_empty());
+
+        let lock = LockFile::lock(path).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), pid.to_string());
+
+        drop(lock);
+        fs::remove_file(path).unwrap();
+    }
+}
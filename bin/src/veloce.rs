@@ -3,8 +3,11 @@ mod config;
 mod device;
 mod gnss;
 mod ipc;
+mod metrics;
 mod router;
 mod security;
+mod signal;
+mod systemd;
 mod utils;
 
 use std::fmt;
@@ -16,7 +19,7 @@ use device::{AnyDevice, DeviceError};
 use log::info;
 use router::{Router, RouterError};
 use security::SecurityError;
-use utils::{UtilError, get_config_file_path, setup_logging, write_pid_file};
+use utils::{UtilError, get_config_file_path, lock_phy, setup_logging, write_pid_file};
 
 pub type VeloceResult<T> = core::result::Result<T, VeloceError>;
 
@@ -31,6 +34,8 @@ pub enum VeloceError {
     PhyDevice(DeviceError),
     /// Error while writing PID file.
     PidFile(UtilError),
+    /// Error while locking the PHY lock file.
+    LockFile(UtilError),
     /// Security setup error.
     Security(SecurityError),
     /// Router setup error.
@@ -44,6 +49,7 @@ impl fmt::Display for VeloceError {
             VeloceError::ConfigLoad(e) => write!(f, "Failed to load configuration file: {e}"),
             VeloceError::PhyDevice(e) => write!(f, "Failed to setup PHY device: {e}"),
             VeloceError::PidFile(e) => write!(f, "Failed to write PID file: {e}"),
+            VeloceError::LockFile(e) => write!(f, "Failed to lock PHY: {e}"),
             VeloceError::Security(e) => write!(f, "Failed to setup security: {e}"),
             VeloceError::Router(e) => write!(f, "Failed to setup router: {e}"),
         }
@@ -60,7 +66,9 @@ pub fn main() -> VeloceResult<()> {
     setup_logging(&config.log_level);
     info!("Starting Veloce");
 
-    write_pid_file(&config).map_err(VeloceError::PidFile)?;
+    // Files are removed when dropped, on exit.
+    let _lock_file = lock_phy(&config).map_err(VeloceError::LockFile)?;
+    let _pid_file = write_pid_file(&config).map_err(VeloceError::PidFile)?;
 
     info!("Configuring PHY network device");
     let device = AnyDevice::setup_phy_device(&config).map_err(VeloceError::PhyDevice)?;
@@ -68,8 +76,13 @@ pub fn main() -> VeloceResult<()> {
     let maybe_security_and_storage_config =
         security::setup_security(&config).map_err(VeloceError::Security)?;
 
-    let mut router = Router::new(&config, device, maybe_security_and_storage_config)
-        .map_err(VeloceError::Router)?;
+    let mut router = Router::new(
+        &config,
+        config_file_path,
+        device,
+        maybe_security_and_storage_config,
+    )
+    .map_err(VeloceError::Router)?;
 
     router.run();
 
@@ -122,6 +122,33 @@ fn main() {
         }
     }
 
+    // Compiler for ETSI Misbehavior Reporting files.
+    #[cfg(feature = "etsi-mbr-v2")]
+    match Compiler::<RasnBackend, _>::new()
+        .add_asn_sources_by_path(
+            [PathBuf::from(
+                "asn/security/etsi_103759_v2.1.1/EtsiTs103759Core.asn",
+            )]
+            .iter(),
+        )
+        .compile_to_string()
+    {
+        Ok(res) => {
+            println!("ASN1 compiler warnings: {:?}", res.warnings);
+            let contents = res.generated.replace(
+                "super::ieee1609_dot2",
+                "crate::etsi_103097_v211::ieee1609_dot2",
+            );
+            fs::write(out_path.join("etsi_103759_v211.rs"), contents).unwrap();
+        }
+        Err(error) => {
+            panic!(
+                "Cannot compile ETSI misbehavior reporting ASN1 descriptions: {:?}",
+                error
+            );
+        }
+    }
+
     // Compiler for ETSI PKI files.
     #[cfg(feature = "etsi-pki-r2")]
     match Compiler::<RasnBackend, _>::new()
@@ -416,6 +416,8 @@ pub mod etsi_ts102941_trust_lists {
         aa(AaEntry),
         dc(DcEntry),
         tlm(TlmEntry),
+        #[rasn(extension_addition)]
+        ma(MaEntry),
     }
     #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
     #[rasn(automatic_tags)]
@@ -514,6 +516,22 @@ pub mod etsi_ts102941_trust_lists {
     pub struct FullCtl(pub CtlFormat);
     #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
     #[rasn(automatic_tags)]
+    pub struct MaEntry {
+        #[rasn(identifier = "maCertificate")]
+        pub ma_certificate: EtsiTs103097Certificate,
+        #[rasn(identifier = "accessPoint")]
+        pub access_point: Url,
+    }
+    impl MaEntry {
+        pub fn new(ma_certificate: EtsiTs103097Certificate, access_point: Url) -> Self {
+            Self {
+                ma_certificate,
+                access_point,
+            }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
     pub struct RootCaEntry {
         #[rasn(identifier = "selfsignedRootCa")]
         pub selfsigned_root_ca: EtsiTs103097Certificate,
@@ -152,12 +152,12 @@ pub mod ieee1609_dot2 {
     extern crate alloc;
     use super::etsi_ts103097_extension_module::EtsiOriginatingHeaderInfoExtension;
     use super::ieee1609_dot2_base_types::{
-        CrlSeries, EccP256CurvePoint, EciesP256EncryptedKey, EncryptionKey, GeographicRegion,
-        GroupLinkageValue, HashAlgorithm, HashedId3, HashedId8, Hostname, IValue, LinkageValue,
-        Opaque, Psid, PsidSsp, PsidSspRange, PublicEncryptionKey, PublicVerificationKey,
-        SequenceOfHashedId3, SequenceOfPsidSsp, SequenceOfPsidSspRange, ServiceSpecificPermissions,
-        Signature, SubjectAssurance, SymmetricEncryptionKey, ThreeDLocation, Time64, Uint16, Uint3,
-        Uint32, Uint8, ValidityPeriod,
+        CrlSeries, EccP256CurvePoint, EcencP256EncryptedKey, EciesP256EncryptedKey, EncryptionKey,
+        GeographicRegion, GroupLinkageValue, HashAlgorithm, HashedId3, HashedId8, Hostname, IValue,
+        LinkageValue, Opaque, Psid, PsidSsp, PsidSspRange, PublicEncryptionKey,
+        PublicVerificationKey, SequenceOfHashedId3, SequenceOfPsidSsp, SequenceOfPsidSspRange,
+        ServiceSpecificPermissions, Signature, SubjectAssurance, SymmetricEncryptionKey,
+        ThreeDLocation, Time64, Uint16, Uint3, Uint32, Uint8, ValidityPeriod,
     };
     use core::borrow::Borrow;
     use lazy_static::lazy_static;
@@ -470,6 +470,8 @@ pub mod ieee1609_dot2 {
     pub enum EncryptedDataEncryptionKey {
         eciesNistP256(EciesP256EncryptedKey),
         eciesBrainpoolP256r1(EciesP256EncryptedKey),
+        #[rasn(extension_addition)]
+        ecencSm2256(EcencP256EncryptedKey),
     }
     #[doc = "*"]
     #[doc = " * @class EndEntityType"]
@@ -761,6 +763,8 @@ pub mod ieee1609_dot2 {
         R_self(HashAlgorithm),
         #[rasn(extension_addition)]
         sha384AndDigest(HashedId8),
+        #[rasn(extension_addition)]
+        sm3AndDigest(HashedId8),
     }
     #[doc = "*"]
     #[doc = " * @class LinkageData"]
@@ -1041,6 +1045,32 @@ pub mod ieee1609_dot2 {
         rekRecipInfo(PKRecipientInfo),
     }
     #[doc = "*"]
+    #[doc = " * @class SM4CcmCiphertext"]
+    #[doc = " *"]
+    #[doc = " * @brief This data structure encapsulates an encrypted ciphertext for the"]
+    #[doc = " * SM4-CCM symmetric algorithm as specified in GB/T 32907."]
+    #[doc = " *"]
+    #[doc = " * @param nonce contains the nonce N as specified in 5.3.8."]
+    #[doc = " *"]
+    #[doc = " * @param ccmCiphertext contains the ciphertext C, including the 16-byte"]
+    #[doc = " * authentication tag."]
+    #[doc = " "]
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct SM4CcmCiphertext {
+        pub nonce: FixedOctetString<12>,
+        #[rasn(identifier = "ccmCiphertext")]
+        pub ccm_ciphertext: Opaque,
+    }
+    impl SM4CcmCiphertext {
+        pub fn new(nonce: FixedOctetString<12>, ccm_ciphertext: Opaque) -> Self {
+            Self {
+                nonce,
+                ccm_ciphertext,
+            }
+        }
+    }
+    #[doc = "*"]
     #[doc = " * @class SequenceOfCertificate"]
     #[doc = " *"]
     #[doc = " * @brief This type is used for clarity of definitions."]
@@ -1310,6 +1340,8 @@ pub mod ieee1609_dot2 {
     #[non_exhaustive]
     pub enum SymmetricCiphertext {
         aes128ccm(AesCcmCiphertext),
+        #[rasn(extension_addition)]
+        sm4Ccm(SM4CcmCiphertext),
     }
     #[doc = "*"]
     #[doc = " * @class ToBeSignedCertificate"]
@@ -1593,6 +1625,8 @@ pub mod ieee1609_dot2_base_types {
     pub enum BasePublicEncryptionKey {
         eciesNistP256(EccP256CurvePoint),
         eciesBrainpoolP256r1(EccP256CurvePoint),
+        #[rasn(extension_addition)]
+        ecencSm2(EccP256CurvePoint),
     }
     #[doc = "*"]
     #[doc = " * @class BitmapSsp"]
@@ -2036,6 +2070,30 @@ pub mod ieee1609_dot2_base_types {
         }
     }
     #[doc = "*"]
+    #[doc = " * @class EcencP256EncryptedKey"]
+    #[doc = " *"]
+    #[doc = " * @brief This data structure is used to transfer a 16-byte symmetric key"]
+    #[doc = " * encrypted using SM2 encryption as specified in GB/T 32918.4."]
+    #[doc = " *"]
+    #[doc = " * @param v is the sender's ephemeral public key C1."]
+    #[doc = " *"]
+    #[doc = " * @param c is the encrypted symmetric key C2."]
+    #[doc = " *"]
+    #[doc = " * @param t is the authentication tag C3, computed with SM3."]
+    #[doc = " "]
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct EcencP256EncryptedKey {
+        pub v: EccP256CurvePoint,
+        pub c: FixedOctetString<16>,
+        pub t: FixedOctetString<32>,
+    }
+    impl EcencP256EncryptedKey {
+        pub fn new(v: EccP256CurvePoint, c: FixedOctetString<16>, t: FixedOctetString<32>) -> Self {
+            Self { v, c, t }
+        }
+    }
+    #[doc = "*"]
     #[doc = " * @class EciesP256EncryptedKey"]
     #[doc = " *"]
     #[doc = " * @brief This data structure is used to transfer a 16-byte symmetric key"]
@@ -2068,6 +2126,30 @@ pub mod ieee1609_dot2_base_types {
         }
     }
     #[doc = "*"]
+    #[doc = " * @class EcsigP256Signature"]
+    #[doc = " *"]
+    #[doc = " * @brief This structure represents an elliptic curve signature where the"]
+    #[doc = " * component r is constrained to be an integer. This structure supports SM2"]
+    #[doc = " * signatures as specified in GB/T 32918.2 and YD/T 3957."]
+    #[doc = " *"]
+    #[doc = " * @param rSig contains the 32-byte integer r of the signature."]
+    #[doc = " *"]
+    #[doc = " * @param sSig contains the 32-byte integer s of the signature."]
+    #[doc = " "]
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct EcsigP256Signature {
+        #[rasn(identifier = "rSig")]
+        pub r_sig: FixedOctetString<32>,
+        #[rasn(identifier = "sSig")]
+        pub s_sig: FixedOctetString<32>,
+    }
+    impl EcsigP256Signature {
+        pub fn new(r_sig: FixedOctetString<32>, s_sig: FixedOctetString<32>) -> Self {
+            Self { r_sig, s_sig }
+        }
+    }
+    #[doc = "*"]
     #[doc = " * @class Elevation"]
     #[doc = " *"]
     #[doc = " * @brief This structure contains an estimate of the geodetic altitude above"]
@@ -2182,6 +2264,8 @@ pub mod ieee1609_dot2_base_types {
         sha256 = 0,
         #[rasn(extension_addition)]
         sha384 = 1,
+        #[rasn(extension_addition)]
+        sm3 = 2,
     }
     #[doc = "*"]
     #[doc = " * @class HashedId10"]
@@ -2587,6 +2671,8 @@ pub mod ieee1609_dot2_base_types {
         ecdsaBrainpoolP384r1(EccP384CurvePoint),
         #[rasn(extension_addition)]
         ecdsaNistP384(EccP384CurvePoint),
+        #[rasn(extension_addition)]
+        ecsigSm2(EccP256CurvePoint),
     }
     #[doc = "*"]
     #[doc = " * @class RectangularRegion"]
@@ -2800,6 +2886,8 @@ pub mod ieee1609_dot2_base_types {
         ecdsaBrainpoolP384r1Signature(EcdsaP384Signature),
         #[rasn(extension_addition)]
         ecdsaNistP384Signature(EcdsaP384Signature),
+        #[rasn(extension_addition)]
+        sm2Signature(EcsigP256Signature),
     }
     #[doc = "*"]
     #[doc = " * @class SspRange"]
@@ -2913,6 +3001,8 @@ pub mod ieee1609_dot2_base_types {
     #[non_exhaustive]
     pub enum SymmAlgorithm {
         aes128Ccm = 0,
+        #[rasn(extension_addition)]
+        sm4Ccm = 1,
     }
     #[doc = "*"]
     #[doc = " * @class SymmetricEncryptionKey"]
@@ -2926,6 +3016,8 @@ pub mod ieee1609_dot2_base_types {
     #[non_exhaustive]
     pub enum SymmetricEncryptionKey {
         aes128Ccm(FixedOctetString<16>),
+        #[rasn(extension_addition)]
+        sm4Ccm(FixedOctetString<16>),
     }
     #[doc = "*"]
     #[doc = " * @class ThreeDLocation"]
@@ -0,0 +1,150 @@
+#[allow(
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    unused,
+    clippy::too_many_arguments
+)]
+pub mod etsi_ts103759_core {
+    extern crate alloc;
+    use crate::etsi_103097_v211::ieee1609_dot2::Certificate;
+    use crate::etsi_103097_v211::ieee1609_dot2_base_types::{
+        Opaque, Psid, ThreeDLocation, Time64, Uint8,
+    };
+    use core::borrow::Borrow;
+    use lazy_static::lazy_static;
+    use rasn::prelude::*;
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct AidSpecificReport {
+        pub aid: Psid,
+        pub content: Option<OctetString>,
+    }
+    impl AidSpecificReport {
+        pub fn new(aid: Psid, content: Option<OctetString>) -> Self {
+            Self { aid, content }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    #[non_exhaustive]
+    pub struct AsrCam {
+        pub observations: SequenceOf<ObservationsByTarget>,
+        #[rasn(size("1.."), identifier = "v2xPduEvidence")]
+        pub v2x_pdu_evidence: SequenceOf<V2xPduStream>,
+        #[rasn(identifier = "nonV2xPduEvidence")]
+        pub non_v2x_pdu_evidence: SequenceOf<NonV2xPduEvidenceItem>,
+    }
+    impl AsrCam {
+        pub fn new(
+            observations: SequenceOf<ObservationsByTarget>,
+            v2x_pdu_evidence: SequenceOf<V2xPduStream>,
+            non_v2x_pdu_evidence: SequenceOf<NonV2xPduEvidenceItem>,
+        ) -> Self {
+            Self {
+                observations,
+                v2x_pdu_evidence,
+                non_v2x_pdu_evidence,
+            }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(delegate)]
+    pub struct AsrDenm(pub AsrCam);
+    #[doc = "***********"]
+    #[doc = "-- Misbehavior report"]
+    #[doc = "-- Reduced version of ETSI TS 103 759 v2.1.1, where open types are carried"]
+    #[doc = "-- as their OER encoding in an OCTET STRING, which is encoding compatible."]
+    #[doc = "***********"]
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    #[non_exhaustive]
+    pub struct EtsiTs103759Data {
+        pub version: Uint8,
+        #[rasn(identifier = "generationTime")]
+        pub generation_time: Time64,
+        #[rasn(identifier = "observationLocation")]
+        pub observation_location: ThreeDLocation,
+        pub report: AidSpecificReport,
+    }
+    impl EtsiTs103759Data {
+        pub fn new(
+            version: Uint8,
+            generation_time: Time64,
+            observation_location: ThreeDLocation,
+            report: AidSpecificReport,
+        ) -> Self {
+            Self {
+                version,
+                generation_time,
+                observation_location,
+                report,
+            }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct MbSingleObservation {
+        #[rasn(identifier = "obsId")]
+        pub obs_id: Uint8,
+        pub obs: OctetString,
+    }
+    impl MbSingleObservation {
+        pub fn new(obs_id: Uint8, obs: OctetString) -> Self {
+            Self { obs_id, obs }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct NonV2xPduEvidenceItem {
+        pub id: Uint8,
+        pub evidence: OctetString,
+    }
+    impl NonV2xPduEvidenceItem {
+        pub fn new(id: Uint8, evidence: OctetString) -> Self {
+            Self { id, evidence }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    pub struct ObservationsByTarget {
+        #[rasn(identifier = "tgtId")]
+        pub tgt_id: Uint8,
+        pub observations: SequenceOf<MbSingleObservation>,
+    }
+    impl ObservationsByTarget {
+        pub fn new(tgt_id: Uint8, observations: SequenceOf<MbSingleObservation>) -> Self {
+            Self {
+                tgt_id,
+                observations,
+            }
+        }
+    }
+    #[derive(AsnType, Debug, Clone, Decode, Encode, PartialEq, Eq, Hash)]
+    #[rasn(automatic_tags)]
+    #[non_exhaustive]
+    pub struct V2xPduStream {
+        #[rasn(identifier = "type")]
+        pub r_type: Uint8,
+        #[rasn(size("1..=255"), identifier = "v2xPdus")]
+        pub v2x_pdus: SequenceOf<Opaque>,
+        pub certificate: Option<Certificate>,
+        #[rasn(identifier = "subjectPduIndex")]
+        pub subject_pdu_index: Uint8,
+    }
+    impl V2xPduStream {
+        pub fn new(
+            r_type: Uint8,
+            v2x_pdus: SequenceOf<Opaque>,
+            certificate: Option<Certificate>,
+            subject_pdu_index: Uint8,
+        ) -> Self {
+            Self {
+                r_type,
+                v2x_pdus,
+                certificate,
+                subject_pdu_index,
+            }
+        }
+    }
+}
@@ -10,6 +10,9 @@ mod etsi_103097_v211;
 #[cfg(feature = "etsi-pki-r2")]
 mod etsi_102941_v221;
 
+#[cfg(feature = "etsi-mbr-v2")]
+mod etsi_103759_v211;
+
 #[cfg(any(
     feature = "etsi-cdd-r2",
     feature = "etsi-cam-r2",
@@ -34,6 +37,11 @@ pub mod defs {
         pub use crate::etsi_102941_v221::*;
     }
 
+    #[cfg(feature = "etsi-mbr-v2")]
+    pub mod etsi_103759_v211 {
+        pub use crate::etsi_103759_v211::*;
+    }
+
     #[cfg(any(
         feature = "etsi-cdd-r2",
         feature = "etsi-cam-r2",
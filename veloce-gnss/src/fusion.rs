@@ -0,0 +1,496 @@
+use log::{debug, info};
+use uom::si::acceleration::meter_per_second_squared;
+use uom::si::angle::{degree, radian};
+use uom::si::angular_velocity::degree_per_second;
+use uom::si::f64::{Acceleration, Angle, AngularVelocity, Length, Velocity};
+use uom::si::length::meter;
+
+use veloce::{
+    common::{PotiFix, PotiMode, PotiPositionConfidence},
+    time::{Duration, Instant},
+    types::meter_per_second,
+};
+
+/// Mean earth radius, in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+/// Minimum standard deviation of the wheel speed, in meters per second.
+const MIN_SPEED_ERROR: f64 = 0.05;
+/// Standard deviation growth of the speed when only the acceleration is available,
+/// in meters per second squared.
+const ACCELERATION_ERROR: f64 = 0.2;
+/// Standard deviation growth of the heading when no yaw rate is available, in degrees per second.
+const NO_YAW_RATE_ERROR: f64 = 3.0;
+/// Altitude error growth, in ratio of the travelled distance, ie: the road grade.
+const ALTITUDE_ERROR_RATIO: f64 = 0.05;
+
+/// [Fusion] layer configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FusionConfig {
+    /// Delay without GNSS fix after which the position is dead reckoned.
+    pub gnss_timeout: Duration,
+    /// Interval between two dead reckoned fixes.
+    pub interval: Duration,
+    /// Maximum duration of dead reckoning. No fix is produced after this duration.
+    pub max_duration: Duration,
+    /// Maximum age of a sensor sample. Dead reckoning stops when samples are older.
+    pub sensor_timeout: Duration,
+    /// Standard deviation of the wheel speed error, in ratio of the speed.
+    pub speed_error_ratio: f64,
+    /// Standard deviation of the yaw rate sensor bias.
+    pub yaw_rate_bias: AngularVelocity,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            gnss_timeout: Duration::from_millis(1500),
+            interval: Duration::from_millis(100),
+            max_duration: Duration::from_secs(30),
+            sensor_timeout: Duration::from_millis(500),
+            speed_error_ratio: 0.02,
+            yaw_rate_bias: AngularVelocity::new::<degree_per_second>(0.2),
+        }
+    }
+}
+
+/// Vehicle motion sensors sample, pushed by the application.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct SensorSample {
+    /// Vehicle speed, measured by the wheel speed sensors.
+    pub wheel_speed: Option<Velocity>,
+    /// Yaw rate, positive when turning left (counter-clockwise), as defined in ISO 8855.
+    pub yaw_rate: Option<AngularVelocity>,
+    /// Longitudinal acceleration, positive when accelerating forward.
+    pub longitudinal_acceleration: Option<Acceleration>,
+}
+
+/// Dead reckoning state, propagated from the last GNSS fix.
+#[derive(Debug, Clone, Copy)]
+struct State {
+    /// Instant of the state.
+    at: Instant,
+    /// Latitude, in degrees.
+    latitude: f64,
+    /// Longitude, in degrees.
+    longitude: f64,
+    /// Heading, in degrees from true north.
+    heading: f64,
+    /// Speed, in meters per second.
+    speed: f64,
+    /// Standard deviation of the speed, in meters per second.
+    speed_error: f64,
+    /// Accumulated standard deviation of the heading, in degrees.
+    heading_error: f64,
+    /// Accumulated standard deviation of the position along the track, in meters.
+    along_track_error: f64,
+    /// Accumulated standard deviation of the position across the track, in meters.
+    cross_track_error: f64,
+    /// Distance travelled since the GNSS fix, in meters.
+    distance: f64,
+}
+
+impl State {
+    /// Builds a state from a GNSS `fix` received at `at`.
+    fn from_fix(fix: &PotiFix, at: Instant) -> Option<State> {
+        Some(State {
+            at,
+            latitude: fix.position.latitude?.get::<degree>(),
+            longitude: fix.position.longitude?.get::<degree>(),
+            heading: fix.motion.heading.map_or(0.0, |h| h.get::<degree>()),
+            speed: fix
+                .motion
+                .speed
+                .map_or(0.0, |s| s.get::<meter_per_second>()),
+            speed_error: fix
+                .confidence
+                .speed
+                .map_or(0.0, |s| s.get::<meter_per_second>()),
+            heading_error: 0.0,
+            along_track_error: 0.0,
+            cross_track_error: 0.0,
+            distance: 0.0,
+        })
+    }
+}
+
+/// GNSS and vehicle sensors fusion layer.
+///
+/// GNSS fixes are used as is while they are received. When they stop, the position is dead
+/// reckoned from the last GNSS fix using the vehicle sensors samples, and its confidence grows
+/// with time and travelled distance. Dead reckoned fixes use the [PotiMode::DeadReckoning] mode.
+#[derive(Debug)]
+pub struct Fusion {
+    /// Fusion configuration.
+    config: FusionConfig,
+    /// Last GNSS fix and its reception instant.
+    gnss: Option<(PotiFix, Instant)>,
+    /// Last sensor sample and its reception instant.
+    sensors: Option<(SensorSample, Instant)>,
+    /// Dead reckoning state.
+    state: Option<State>,
+    /// Instant at which the next dead reckoned fix should be produced.
+    next_fix_at: Instant,
+    /// Whether the last produced fix is dead reckoned.
+    dead_reckoning: bool,
+}
+
+impl Fusion {
+    /// Constructs a new [Fusion] layer with the given `config`.
+    pub fn new(config: FusionConfig) -> Fusion {
+        Fusion {
+            config,
+            gnss: None,
+            sensors: None,
+            state: None,
+            next_fix_at: Instant::ZERO,
+            dead_reckoning: false,
+        }
+    }
+
+    /// Query whether the position is currently dead reckoned.
+    pub fn is_dead_reckoning(&self) -> bool {
+        self.dead_reckoning
+    }
+
+    /// Push a GNSS `fix` received at `timestamp`. Only 3D fixes are used,
+    /// others are considered as a GNSS outage.
+    pub fn push_gnss_fix(&mut self, fix: PotiFix, timestamp: Instant) {
+        if !fix.is_mode_3d() {
+            return;
+        }
+
+        let Some(state) = State::from_fix(&fix, timestamp) else {
+            return;
+        };
+
+        if self.dead_reckoning {
+            info!("GNSS fix received - dead reckoning stopped");
+            self.dead_reckoning = false;
+        }
+
+        self.gnss = Some((fix, timestamp));
+        self.state = Some(state);
+    }
+
+    /// Push a vehicle sensors `sample` measured at `timestamp`.
+    pub fn push_sensors(&mut self, sample: SensorSample, timestamp: Instant) {
+        // Integrate the previous sample up to this one.
+        self.propagate(timestamp);
+        self.sensors = Some((sample, timestamp));
+    }
+
+    /// Return the instant at which the fusion layer should be polled, if any.
+    pub fn poll_at(&self) -> Option<Instant> {
+        let (_, gnss_at) = self.gnss?;
+        let start = gnss_at + self.config.gnss_timeout;
+
+        if self.next_fix_at > start + self.config.max_duration {
+            return None;
+        }
+
+        Some(self.next_fix_at.max(start))
+    }
+
+    /// Produces a dead reckoned fix at `timestamp`, if GNSS fixes stopped and it is time to.
+    pub fn poll(&mut self, timestamp: Instant) -> Option<PotiFix> {
+        let (gnss_fix, gnss_at) = self.gnss?;
+
+        if timestamp < gnss_at + self.config.gnss_timeout || timestamp < self.next_fix_at {
+            return None;
+        }
+
+        let outage = timestamp - gnss_at;
+        if outage > self.config.gnss_timeout + self.config.max_duration {
+            if self.dead_reckoning {
+                info!("maximum dead reckoning duration reached");
+                self.dead_reckoning = false;
+            }
+            return None;
+        }
+
+        match self.sensors {
+            Some((_, at)) if timestamp - at <= self.config.sensor_timeout => {}
+            _ => {
+                if self.dead_reckoning {
+                    info!("no sensor sample - dead reckoning stopped");
+                    self.dead_reckoning = false;
+                }
+                return None;
+            }
+        }
+
+        self.propagate(timestamp);
+        let state = self.state?;
+
+        if !self.dead_reckoning {
+            info!("GNSS outage - dead reckoning started");
+            self.dead_reckoning = true;
+        }
+
+        self.next_fix_at = timestamp + self.config.interval;
+
+        let fix = self.dead_reckoned_fix(&gnss_fix, gnss_at, &state);
+        debug!("dead reckoned fix: {:?}", fix);
+
+        Some(fix)
+    }
+
+    /// Propagates the dead reckoning state up to `timestamp`, with the last sensors sample.
+    fn propagate(&mut self, timestamp: Instant) {
+        let (Some(state), Some((sample, _))) = (&mut self.state, self.sensors) else {
+            return;
+        };
+
+        if timestamp <= state.at {
+            return;
+        }
+
+        let dt = (timestamp - state.at).total_micros() as f64 / 1_000_000.0;
+
+        match (sample.wheel_speed, sample.longitudinal_acceleration) {
+            (Some(speed), _) => {
+                state.speed = speed.get::<meter_per_second>();
+                state.speed_error = state.speed * self.config.speed_error_ratio + MIN_SPEED_ERROR;
+            }
+            (None, Some(acc)) => {
+                state.speed = (state.speed + acc.get::<meter_per_second_squared>() * dt).max(0.0);
+                state.speed_error += ACCELERATION_ERROR * dt;
+            }
+            (None, None) => {
+                state.speed_error += ACCELERATION_ERROR * dt;
+            }
+        }
+
+        // Yaw rate is counter-clockwise whereas heading is clockwise.
+        let (yaw_rate, yaw_rate_error) = match sample.yaw_rate {
+            Some(yaw_rate) => (
+                yaw_rate.get::<degree_per_second>(),
+                self.config.yaw_rate_bias.get::<degree_per_second>(),
+            ),
+            None => (0.0, NO_YAW_RATE_ERROR),
+        };
+
+        let mid_heading = Angle::new::<degree>(state.heading - yaw_rate * dt / 2.0);
+        let distance = state.speed * dt;
+
+        let north = distance * mid_heading.cos().value;
+        let east = distance * mid_heading.sin().value;
+        let latitude = Angle::new::<degree>(state.latitude);
+
+        state.latitude += Angle::new::<radian>(north / EARTH_RADIUS).get::<degree>();
+        state.longitude +=
+            Angle::new::<radian>(east / (EARTH_RADIUS * latitude.cos().value)).get::<degree>();
+        state.heading = (state.heading - yaw_rate * dt).rem_euclid(360.0);
+
+        state.heading_error += yaw_rate_error * dt;
+        state.along_track_error += state.speed_error * dt;
+        state.cross_track_error +=
+            distance * Angle::new::<degree>(state.heading_error).get::<radian>();
+        state.distance += distance;
+        state.at = timestamp;
+    }
+
+    /// Builds a dead reckoned fix from the `state` propagated from the `gnss_fix`
+    /// received at `gnss_at`.
+    fn dead_reckoned_fix(&self, gnss_fix: &PotiFix, gnss_at: Instant, state: &State) -> PotiFix {
+        let gnss_confidence = gnss_fix.confidence.position;
+        let gnss_major = gnss_confidence.semi_major.map_or(0.0, |l| l.get::<meter>());
+        let gnss_minor = gnss_confidence.semi_minor.map_or(0.0, |l| l.get::<meter>());
+
+        let (dr_major, dr_minor, dr_orientation) =
+            if state.along_track_error >= state.cross_track_error {
+                (
+                    state.along_track_error,
+                    state.cross_track_error,
+                    state.heading,
+                )
+            } else {
+                (
+                    state.cross_track_error,
+                    state.along_track_error,
+                    (state.heading + 90.0).rem_euclid(360.0),
+                )
+            };
+
+        // Dead reckoning error ellipse is oriented along its major error once it
+        // exceeds the GNSS one.
+        let semi_major_orientation = if dr_major > gnss_major {
+            Some(Angle::new::<degree>(dr_orientation))
+        } else {
+            gnss_confidence.semi_major_orientation
+        };
+
+        let mut fix = *gnss_fix;
+        fix.mode = PotiMode::DeadReckoning;
+        fix.timestamp = gnss_fix.timestamp + (state.at - gnss_at);
+        fix.position.latitude = Some(Angle::new::<degree>(state.latitude));
+        fix.position.longitude = Some(Angle::new::<degree>(state.longitude));
+        fix.motion.speed = Some(Velocity::new::<meter_per_second>(state.speed));
+        fix.motion.vertical_speed = None;
+        fix.motion.heading = Some(Angle::new::<degree>(state.heading));
+        fix.confidence.position = PotiPositionConfidence {
+            semi_major: Some(Length::new::<meter>(gnss_major.hypot(dr_major))),
+            semi_minor: Some(Length::new::<meter>(gnss_minor.hypot(dr_minor))),
+            semi_major_orientation,
+        };
+        fix.confidence.altitude = gnss_fix
+            .confidence
+            .altitude
+            .map(|alt| alt + Length::new::<meter>(state.distance * ALTITUDE_ERROR_RATIO));
+        fix.confidence.speed = Some(Velocity::new::<meter_per_second>(state.speed_error));
+        fix.confidence.heading = gnss_fix
+            .confidence
+            .heading
+            .map(|hdg| hdg + Angle::new::<degree>(state.heading_error));
+
+        fix
+    }
+}
+
+#[cfg(all(test, feature = "replay"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use uom::si::f64::Time;
+    use uom::si::time::second;
+    use veloce::common::geo_area::GeoPosition;
+
+    use super::*;
+    use crate::Replay;
+
+    /// Replays the `road.nmea` trace, returning the fixes and their reception instants.
+    fn replay_trace() -> Vec<(Instant, PotiFix)> {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("examples/assets/road.nmea");
+
+        let mut replay = Replay::new(path, Duration::from_secs(1)).unwrap();
+        let mut trace: Vec<(Instant, PotiFix)> = Vec::new();
+
+        // Stop before the end of the trace, as the replay rewinds.
+        let mut now = Instant::ZERO;
+        while now < Instant::from_secs(6) {
+            if replay.poll(now).is_ok() {
+                if let Ok(fix) = TryInto::<PotiFix>::try_into(replay.fetch_position()) {
+                    match trace.last() {
+                        Some((_, last)) if last.position == fix.position => {}
+                        _ => trace.push((now, fix)),
+                    }
+                }
+            }
+            now += Duration::from_millis(10);
+        }
+
+        trace
+    }
+
+    fn geo_position(fix: &PotiFix) -> GeoPosition {
+        GeoPosition {
+            latitude: fix.position.latitude.unwrap(),
+            longitude: fix.position.longitude.unwrap(),
+        }
+    }
+
+    /// Bearing from `from` to `to`, in degrees.
+    fn bearing(from: &PotiFix, to: &PotiFix) -> f64 {
+        let (from, to) = (geo_position(from), geo_position(to));
+        let north = (to.latitude - from.latitude).get::<radian>();
+        let east = (to.longitude - from.longitude).get::<radian>() * from.latitude.cos().value;
+        east.atan2(north).to_degrees().rem_euclid(360.0)
+    }
+
+    #[test]
+    fn test_gnss_outage() {
+        let trace = replay_trace();
+        assert!(trace.len() > 40);
+
+        let outage_start = Instant::from_millis(2000);
+        let outage_end = Instant::from_millis(4500);
+
+        let mut fusion = Fusion::new(FusionConfig {
+            gnss_timeout: Duration::from_millis(250),
+            ..Default::default()
+        });
+
+        let mut dead_reckoned = 0;
+        let mut first_semi_major = None;
+        let mut last_semi_major = Length::new::<meter>(0.0);
+        let mut prev_bearing = bearing(&trace[0].1, &trace[1].1);
+
+        for window in trace.windows(2) {
+            let ((prev_at, prev), (at, truth)) = (window[0], window[1]);
+            let dt = (at - prev_at).total_micros() as f64 / 1_000_000.0;
+
+            // Simulated vehicle sensors, computed from the ground truth.
+            let distance = geo_position(&prev).distance_to(&geo_position(&truth));
+            let bearing = bearing(&prev, &truth);
+            let turn = (bearing - prev_bearing + 180.0).rem_euclid(360.0) - 180.0;
+            prev_bearing = bearing;
+
+            fusion.push_sensors(
+                SensorSample {
+                    wheel_speed: Some(distance / Time::new::<second>(dt)),
+                    yaw_rate: Some(AngularVelocity::new::<degree_per_second>(-turn / dt)),
+                    longitudinal_acceleration: None,
+                },
+                at,
+            );
+
+            if at < outage_start || at >= outage_end {
+                fusion.push_gnss_fix(truth, at);
+                assert_eq!(fusion.poll(at), None);
+                assert!(!fusion.is_dead_reckoning());
+                continue;
+            }
+
+            let Some(fix) = fusion.poll(at) else {
+                // GNSS timeout not elapsed yet.
+                assert!(at - outage_start <= Duration::from_millis(350));
+                continue;
+            };
+
+            dead_reckoned += 1;
+            assert_eq!(fix.mode, PotiMode::DeadReckoning);
+            assert!(fusion.is_dead_reckoning());
+
+            // Confidence grows, and the ground truth stays inside the confidence ellipse.
+            let semi_major = fix.confidence.position.semi_major.unwrap();
+            assert!(semi_major >= last_semi_major);
+            first_semi_major.get_or_insert(semi_major);
+            last_semi_major = semi_major;
+
+            let error = geo_position(&fix).distance_to(&geo_position(&truth));
+            assert!(
+                error <= 3.0 * semi_major,
+                "error {:?} exceeds confidence {:?}",
+                error,
+                semi_major
+            );
+        }
+
+        assert!(dead_reckoned > 15);
+        assert!(Some(last_semi_major) > first_semi_major);
+        assert!(!fusion.is_dead_reckoning());
+    }
+
+    #[test]
+    fn test_no_sensors() {
+        let trace = replay_trace();
+        let (at, fix) = trace[0];
+
+        let mut fusion = Fusion::new(FusionConfig::default());
+        fusion.push_gnss_fix(fix, at);
+
+        // No sensor sample, no dead reckoning.
+        assert_eq!(fusion.poll(at + Duration::from_secs(2)), None);
+
+        // Stale sensor sample, no dead reckoning.
+        fusion.push_sensors(SensorSample::default(), at);
+        assert_eq!(fusion.poll(at + Duration::from_secs(3)), None);
+
+        // Without speed nor yaw rate, confidence grows faster.
+        fusion.push_sensors(SensorSample::default(), at + Duration::from_secs(3));
+        let dr_fix = fusion.poll(at + Duration::from_secs(3)).unwrap();
+        assert_eq!(dr_fix.mode, PotiMode::DeadReckoning);
+        assert!(dr_fix.confidence.speed.unwrap() > fix.confidence.speed.unwrap());
+    }
+}
@@ -2,23 +2,17 @@ use std::io::{ErrorKind, Read, Result, Write};
 use std::mem;
 use std::net::SocketAddr;
 
-use chrono::{DateTime, TimeDelta, Utc};
-use gpsd_proto::{Mode as GpsdMode, UnifiedResponse as GpsdResponse};
+use chrono::{DateTime, Utc};
+use gpsd_proto::UnifiedResponse as GpsdResponse;
 use log::{debug, error, info, trace};
 use mio::event::Event;
 use mio::net::TcpStream;
 use mio::{Interest, Registry, Token};
-use uom::si::angle::degree;
-use uom::si::f64::{Angle, Length, Velocity};
-use uom::si::length::meter;
-use uom::si::velocity::meter_per_second;
 use veloce::time::{Duration, Instant};
 
-use crate::{FixMode, GpsInfo};
+use crate::{gpsd_json, FixMode, GpsInfo};
 
 const RETRY: Duration = Duration::from_secs(5);
-/// Allowed time difference between several GPSD frames.
-const ALLOWED_TIME_DIFF: TimeDelta = TimeDelta::milliseconds(50);
 /// Size of the buffer for incoming data.
 const BUFFER_SIZE: usize = 8192;
 
@@ -378,121 +372,9 @@ impl Gpsd {
         }
     }
 
-    /// Fill a [GpsInfo] from a [GpsdResponse].
-    /// Cache is valid for a fix timestamp. When fix timestamp changes, cache is cleared.
+    /// Fill `self.cache` from GPSD `responses`.
     /// Returns `true` if cache is updated.
     fn update_cache(&mut self, responses: Vec<GpsdResponse>) -> bool {
-        let mut updated = false;
-
-        for data in responses {
-            match data {
-                GpsdResponse::Tpv(tpv) => {
-                    let tpv_time = match tpv.time {
-                        Some(datetime_str) => match DateTime::parse_from_rfc3339(&datetime_str) {
-                            Ok(datetime) => datetime.to_utc(),
-                            Err(_) => {
-                                error!("failed to parse TPV time - using system time");
-                                Utc::now()
-                            }
-                        },
-                        None => {
-                            // If no fix time info from GPSD, get the local system time.
-                            Utc::now()
-                        }
-                    };
-
-                    // Detect a new fix from GPSD using the time info.
-                    match self.cache.gst.time {
-                        Some(gst_time) if (tpv_time - gst_time).abs() > ALLOWED_TIME_DIFF => {
-                            self.cache = GpsInfo::default();
-                        }
-                        _ => {}
-                    }
-
-                    // Reset cache if no signal from GPS.
-                    match (tpv.mode, tpv.status) {
-                        (GpsdMode::NoFix, _) | (_, Some(0)) => {
-                            trace!("NoFix or status = 0");
-                            self.cache = GpsInfo::default();
-                        }
-                        _ => {
-                            self.cache.fix.mode = tpv.mode.into();
-                        }
-                    }
-
-                    self.cache.fix.time = Some(tpv_time);
-
-                    self.cache.fix.ept = tpv.ept.map(|e| Duration::from_secs(e as u64));
-                    self.cache.fix.latitude = tpv.lat.map(Angle::new::<degree>);
-                    self.cache.fix.epy = tpv.epy.map(|epy| Length::new::<meter>(epy.into()));
-                    self.cache.fix.longitude = tpv.lon.map(Angle::new::<degree>);
-                    self.cache.fix.epx = tpv.epx.map(|epx| Length::new::<meter>(epx.into()));
-                    self.cache.fix.eph = tpv.eph.map(|eph| Length::new::<meter>(eph.into()));
-                    self.cache.fix.altitude =
-                        tpv.alt_hae.map(|alt| Length::new::<meter>(alt.into()));
-                    self.cache.fix.epv = tpv.epv.map(|epv| Length::new::<meter>(epv.into()));
-                    self.cache.fix.track =
-                        tpv.track.map(|track| Angle::new::<degree>(track.into()));
-                    self.cache.fix.epd = tpv.epd.map(|epd| Angle::new::<degree>(epd.into()));
-                    self.cache.fix.speed = tpv
-                        .speed
-                        .map(|speed| Velocity::new::<meter_per_second>(speed.into()));
-                    self.cache.fix.eps = tpv
-                        .eps
-                        .map(|eps| Velocity::new::<meter_per_second>(eps.into()));
-                    self.cache.fix.climb = tpv
-                        .climb
-                        .map(|climb| Velocity::new::<meter_per_second>(climb.into()));
-                    self.cache.fix.epc = tpv
-                        .epc
-                        .map(|epc| Velocity::new::<meter_per_second>(epc.into()));
-
-                    updated |= true;
-                }
-                GpsdResponse::Gst(gst) => {
-                    let gst_time = if let Some(datetime_str) = gst.time {
-                        match DateTime::parse_from_rfc3339(&datetime_str) {
-                            Ok(datetime) => datetime.to_utc(),
-                            Err(_) => Utc::now(),
-                        }
-                    } else {
-                        Utc::now()
-                    };
-
-                    // Detect a new fix from GPSD using the time info.
-                    match self.cache.fix.time {
-                        Some(tpv_time) if (tpv_time - gst_time).abs() > ALLOWED_TIME_DIFF => {
-                            self.cache = GpsInfo::default();
-                        }
-                        _ => {}
-                    }
-
-                    self.cache.gst.time = Some(gst_time);
-
-                    self.cache.gst.rms_deviation = gst.rms.map(|rms| rms.into());
-                    self.cache.gst.major_deviation = gst
-                        .major
-                        .map(|major_dev| Length::new::<meter>(major_dev.into()));
-                    self.cache.gst.minor_deviation = gst
-                        .minor
-                        .map(|minor_dev| Length::new::<meter>(minor_dev.into()));
-                    self.cache.gst.major_orientation = gst
-                        .orient
-                        .map(|major_orient| Angle::new::<degree>(major_orient.into()));
-                    self.cache.gst.lat_err_deviation =
-                        gst.lat.map(|lat_dev| Length::new::<meter>(lat_dev.into()));
-                    self.cache.gst.lon_err_deviation =
-                        gst.lon.map(|lon_dev| Length::new::<meter>(lon_dev.into()));
-                    self.cache.gst.alt_err_deviation = gst
-                        .minor
-                        .map(|minor_dev| Length::new::<meter>(minor_dev.into()));
-
-                    updated |= true;
-                }
-                _ => updated |= false,
-            }
-        }
-
-        updated
+        gpsd_json::update_cache(&mut self.cache, responses)
     }
 }
@@ -0,0 +1,132 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use gpsd_proto::{Mode as GpsdMode, UnifiedResponse as GpsdResponse};
+use log::{error, trace};
+use uom::si::angle::degree;
+use uom::si::f64::{Angle, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+use veloce::time::Duration;
+
+use crate::GpsInfo;
+
+/// Allowed time difference between several GPSD frames.
+pub(crate) const ALLOWED_TIME_DIFF: TimeDelta = TimeDelta::milliseconds(50);
+
+/// Fill a `cache` [GpsInfo] from GPSD TPV and GST `responses`.
+/// Cache is valid for a fix timestamp. When fix timestamp changes, cache is cleared.
+/// Returns `true` if cache is updated.
+pub(crate) fn update_cache(
+    cache: &mut GpsInfo,
+    responses: impl IntoIterator<Item = GpsdResponse>,
+) -> bool {
+    let mut updated = false;
+
+    for data in responses {
+        match data {
+            GpsdResponse::Tpv(tpv) => {
+                let tpv_time = match tpv.time {
+                    Some(datetime_str) => match DateTime::parse_from_rfc3339(&datetime_str) {
+                        Ok(datetime) => datetime.to_utc(),
+                        Err(_) => {
+                            error!("failed to parse TPV time - using system time");
+                            Utc::now()
+                        }
+                    },
+                    None => {
+                        // If no fix time info from GPSD, get the local system time.
+                        Utc::now()
+                    }
+                };
+
+                // Detect a new fix from GPSD using the time info.
+                match cache.gst.time {
+                    Some(gst_time) if (tpv_time - gst_time).abs() > ALLOWED_TIME_DIFF => {
+                        *cache = GpsInfo::default();
+                    }
+                    _ => {}
+                }
+
+                // Reset cache if no signal from GPS.
+                match (tpv.mode, tpv.status) {
+                    (GpsdMode::NoFix, _) | (_, Some(0)) => {
+                        trace!("NoFix or status = 0");
+                        *cache = GpsInfo::default();
+                    }
+                    _ => {
+                        cache.fix.mode = tpv.mode.into();
+                    }
+                }
+
+                cache.fix.time = Some(tpv_time);
+
+                cache.fix.ept = tpv.ept.map(|e| Duration::from_secs(e as u64));
+                cache.fix.latitude = tpv.lat.map(Angle::new::<degree>);
+                cache.fix.epy = tpv.epy.map(|epy| Length::new::<meter>(epy.into()));
+                cache.fix.longitude = tpv.lon.map(Angle::new::<degree>);
+                cache.fix.epx = tpv.epx.map(|epx| Length::new::<meter>(epx.into()));
+                cache.fix.eph = tpv.eph.map(|eph| Length::new::<meter>(eph.into()));
+                cache.fix.altitude = tpv.alt_hae.map(|alt| Length::new::<meter>(alt.into()));
+                cache.fix.epv = tpv.epv.map(|epv| Length::new::<meter>(epv.into()));
+                cache.fix.track = tpv.track.map(|track| Angle::new::<degree>(track.into()));
+                cache.fix.epd = tpv.epd.map(|epd| Angle::new::<degree>(epd.into()));
+                cache.fix.speed = tpv
+                    .speed
+                    .map(|speed| Velocity::new::<meter_per_second>(speed.into()));
+                cache.fix.eps = tpv
+                    .eps
+                    .map(|eps| Velocity::new::<meter_per_second>(eps.into()));
+                cache.fix.climb = tpv
+                    .climb
+                    .map(|climb| Velocity::new::<meter_per_second>(climb.into()));
+                cache.fix.epc = tpv
+                    .epc
+                    .map(|epc| Velocity::new::<meter_per_second>(epc.into()));
+
+                updated |= true;
+            }
+            GpsdResponse::Gst(gst) => {
+                let gst_time = if let Some(datetime_str) = gst.time {
+                    match DateTime::parse_from_rfc3339(&datetime_str) {
+                        Ok(datetime) => datetime.to_utc(),
+                        Err(_) => Utc::now(),
+                    }
+                } else {
+                    Utc::now()
+                };
+
+                // Detect a new fix from GPSD using the time info.
+                match cache.fix.time {
+                    Some(tpv_time) if (tpv_time - gst_time).abs() > ALLOWED_TIME_DIFF => {
+                        *cache = GpsInfo::default();
+                    }
+                    _ => {}
+                }
+
+                cache.gst.time = Some(gst_time);
+
+                cache.gst.rms_deviation = gst.rms.map(|rms| rms.into());
+                cache.gst.major_deviation = gst
+                    .major
+                    .map(|major_dev| Length::new::<meter>(major_dev.into()));
+                cache.gst.minor_deviation = gst
+                    .minor
+                    .map(|minor_dev| Length::new::<meter>(minor_dev.into()));
+                cache.gst.major_orientation = gst
+                    .orient
+                    .map(|major_orient| Angle::new::<degree>(major_orient.into()));
+                cache.gst.lat_err_deviation =
+                    gst.lat.map(|lat_dev| Length::new::<meter>(lat_dev.into()));
+                cache.gst.lon_err_deviation =
+                    gst.lon.map(|lon_dev| Length::new::<meter>(lon_dev.into()));
+                cache.gst.alt_err_deviation = gst
+                    .minor
+                    .map(|minor_dev| Length::new::<meter>(minor_dev.into()));
+
+                updated |= true;
+            }
+            _ => updated |= false,
+        }
+    }
+
+    updated
+}
@@ -17,15 +17,35 @@ mod fixed;
 #[cfg(feature = "fixed")]
 pub use fixed::{Fixed, FixedError};
 
+#[cfg(feature = "fusion")]
+mod fusion;
+#[cfg(feature = "fusion")]
+pub use fusion::{Fusion, FusionConfig, SensorSample};
+
 #[cfg(feature = "gpsd")]
 mod gpsd;
+#[cfg(any(feature = "gpsd", feature = "replay"))]
+mod gpsd_json;
 #[cfg(feature = "gpsd")]
 pub use gpsd::Gpsd;
 
 #[cfg(feature = "replay")]
 mod replay;
 #[cfg(feature = "replay")]
-pub use replay::{Replay, ReplayError};
+pub use replay::{CsvColumns, Replay, ReplayConfig, ReplayError, ReplayFormat};
+
+#[cfg(feature = "serial")]
+mod serial;
+#[cfg(feature = "serial")]
+pub use serial::Serial;
+
+#[cfg(feature = "ubx")]
+mod ubx;
+#[cfg(feature = "ubx")]
+pub use ubx::Ubx;
+
+#[cfg(any(feature = "serial", feature = "ubx"))]
+mod tty;
 
 /// Accumulated GPS data. Most of the nested fields are optional,
 /// due to GPSs not sending all the relevant data at once.
@@ -45,6 +65,12 @@ impl GpsInfo {
     /// or don't send a GST frame at all. Anyway, this function tries to fill the
     /// `confidence` field with what is available in [GpsInfo].
     pub fn self_confidence(&mut self) {
+        self.self_confidence_at(Utc::now());
+    }
+
+    /// Same as [Self::self_confidence], `now` being the current UTC time, against
+    /// which the freshness of the fix is checked.
+    pub fn self_confidence_at(&mut self, now: DateTime<Utc>) {
         if let Some((maj_dev, min_dev, maj_orient)) = self.gst.ellipse_deviation_values() {
             trace!("GST ellipse data available");
             self.confidence = Some(Confidence {
@@ -68,7 +94,7 @@ impl GpsInfo {
                 semi_major_orientation,
             });
         } else if self.fix.time.is_some_and(|fix_time| {
-            (-29..30).contains(&Utc::now().signed_duration_since(fix_time).num_seconds())
+            (-29..30).contains(&now.signed_duration_since(fix_time).num_seconds())
         }) {
             match (self.fix.epx, self.fix.epy, self.fix.eph) {
                 (Some(epx), Some(epy), _) => {
@@ -323,7 +349,7 @@ pub enum FixMode {
     Fix3d,
 }
 
-#[cfg(feature = "gpsd")]
+#[cfg(any(feature = "gpsd", feature = "replay"))]
 impl From<gpsd_proto::Mode> for FixMode {
     fn from(value: gpsd_proto::Mode) -> Self {
         match value {
@@ -335,7 +361,6 @@ impl From<gpsd_proto::Mode> for FixMode {
     }
 }
 
-#[cfg(feature = "gpsd")]
 impl From<FixMode> for PotiMode {
     fn from(value: FixMode) -> Self {
         match value {
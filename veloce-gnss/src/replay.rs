@@ -1,100 +1,25 @@
 use core::fmt;
-use std::{
-    fs::File,
-    io::{self, BufRead, BufReader},
-    iter::{Cycle, Peekable},
-    path::PathBuf,
-    vec::IntoIter,
-};
+use std::{fs, io, path::PathBuf};
 
-use chrono::{NaiveTime, TimeDelta, Utc};
-use log::error;
-use nmea::{
-    sentences::{
-        AamData, BwcData, BwwData, DbkData, DbsData, GgaData, GllData, GnsData, GstData, GsvData,
-        HdtData, MdaData, MtwData, MwvData, TtmData, WncData, ZfoData, ZtgData,
-    },
-    Nmea, ParseResult, SentenceType,
-};
-use uom::si::f64::{Angle, Velocity};
-use uom::si::{angle::degree, f64::Length, length::meter};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use gpsd_proto::UnifiedResponse as GpsdResponse;
+use log::{error, trace};
+use nmea::{Nmea, ParseResult, SentenceType};
+use uom::si::f64::{Angle, Length, Velocity};
+use uom::si::{angle::degree, length::meter, velocity::knot};
 
 use veloce::{
+    common::geo_area::GeoPosition,
     time::{Duration, Instant},
     types::meter_per_second,
 };
 
+use crate::gpsd_json::{self, ALLOWED_TIME_DIFF};
 use crate::{FixMode, GpsInfo};
 
-/// Newtype pattern for NMEA sentences because clone is not implemented for [ParseResult].
-#[derive(Debug)]
-struct NmeaSentenceWrapper(ParseResult);
-
-impl NmeaSentenceWrapper {
-    /// Get a reference on the timestamp of the sentence, if any.
-    pub fn timestamp(&self) -> Option<&NaiveTime> {
-        match &self.0 {
-            ParseResult::BWC(a) => a.fix_time.as_ref(),
-            ParseResult::GBS(a) => a.time.as_ref(),
-            ParseResult::GGA(a) => a.fix_time.as_ref(),
-            ParseResult::GLL(a) => a.fix_time.as_ref(),
-            ParseResult::GNS(a) => a.fix_time.as_ref(),
-            ParseResult::RMC(a) => a.fix_time.as_ref(),
-            ParseResult::ZDA(a) => a.utc_time.as_ref(),
-            ParseResult::ZFO(a) => a.fix_time.as_ref(),
-            ParseResult::ZTG(a) => a.fix_time.as_ref(),
-            _ => None,
-        }
-    }
-}
-
-impl Clone for NmeaSentenceWrapper {
-    fn clone(&self) -> Self {
-        let res = match &self.0 {
-            ParseResult::AAM(a) => ParseResult::AAM(AamData { ..*a }),
-            ParseResult::ALM(a) => ParseResult::ALM(*a),
-            ParseResult::APA(a) => ParseResult::APA(a.clone()),
-            ParseResult::BOD(a) => ParseResult::BOD(*a),
-            ParseResult::BWC(a) => ParseResult::BWC(BwcData { ..*a }),
-            ParseResult::BWW(a) => ParseResult::BWW(BwwData { ..*a }),
-            ParseResult::DBK(a) => ParseResult::DBK(DbkData { ..*a }),
-            ParseResult::DBS(a) => ParseResult::DBS(DbsData { ..*a }),
-            ParseResult::DPT(a) => ParseResult::DPT(*a),
-            ParseResult::GBS(a) => ParseResult::GBS(*a),
-            ParseResult::GGA(a) => ParseResult::GGA(GgaData { ..*a }),
-            ParseResult::GLL(a) => ParseResult::GLL(GllData { ..*a }),
-            ParseResult::GNS(a) => ParseResult::GNS(GnsData { ..*a }),
-            ParseResult::GSA(a) => ParseResult::GSA(a.clone()),
-            ParseResult::GST(a) => ParseResult::GST(GstData { ..*a }),
-            ParseResult::GSV(a) => ParseResult::GSV({
-                GsvData {
-                    sats_info: a.sats_info.clone(),
-                    ..*a
-                }
-            }),
-            ParseResult::HDT(a) => ParseResult::HDT(HdtData { ..*a }),
-            ParseResult::MDA(a) => ParseResult::MDA(MdaData { ..*a }),
-            ParseResult::MTW(a) => ParseResult::MTW(MtwData { ..*a }),
-            ParseResult::MWV(a) => ParseResult::MWV(MwvData { ..*a }),
-            ParseResult::RMC(a) => ParseResult::RMC(*a),
-            ParseResult::TTM(a) => ParseResult::TTM(TtmData {
-                target_name: a.target_name.clone(),
-                ..*a
-            }),
-            ParseResult::TXT(a) => ParseResult::TXT(*a),
-            ParseResult::VHW(a) => ParseResult::VHW(a.clone()),
-            ParseResult::VTG(a) => ParseResult::VTG(*a),
-            ParseResult::WNC(a) => ParseResult::WNC(WncData { ..*a }),
-            ParseResult::ZDA(a) => ParseResult::ZDA(a.clone()),
-            ParseResult::ZFO(a) => ParseResult::ZFO(ZfoData { ..*a }),
-            ParseResult::ZTG(a) => ParseResult::ZTG(ZtgData { ..*a }),
-            ParseResult::PGRMZ(a) => ParseResult::PGRMZ(*a),
-            ParseResult::Unsupported(a) => ParseResult::Unsupported(*a),
-        };
-
-        NmeaSentenceWrapper(res)
-    }
-}
+/// User Equivalent Range Error, used to estimate the horizontal position error
+/// from the HDOP of GPX track points.
+const UERE: f64 = 5.0;
 
 #[derive(Debug)]
 pub enum ReplayError {
@@ -102,6 +27,14 @@ pub enum ReplayError {
     Io(io::Error),
     /// NMEA error.
     Nmea(String),
+    /// Replay file format cannot be detected.
+    UnknownFormat,
+    /// No position in replay file.
+    Empty,
+    /// Start offset is beyond the end of the replay file.
+    InvalidStartOffset,
+    /// Playback rate is not strictly positive.
+    InvalidRate,
 }
 
 impl From<nmea::Error<'_>> for ReplayError {
@@ -115,62 +48,226 @@ impl fmt::Display for ReplayError {
         match self {
             ReplayError::Io(e) => write!(f, "IO error: {e}"),
             ReplayError::Nmea(e) => write!(f, "NMEA error: {e}"),
+            ReplayError::UnknownFormat => write!(f, "Unknown replay file format"),
+            ReplayError::Empty => write!(f, "No position in replay file"),
+            ReplayError::InvalidStartOffset => {
+                write!(f, "Start offset is beyond the end of the replay file")
+            }
+            ReplayError::InvalidRate => write!(f, "Playback rate should be strictly positive"),
         }
     }
 }
 
 pub type ReplayResult<T> = Result<T, ReplayError>;
 
-/// Re-player of NMEA sentences.
+/// Columns of a CSV replay file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvColumns {
+    /// Fields delimiter.
+    pub delimiter: char,
+    /// Whether the first line is a header, which is skipped.
+    pub header: bool,
+    /// Index of the time column, either RFC 3339 or seconds since the Unix epoch.
+    pub time: usize,
+    /// Index of the latitude column, in degrees.
+    pub latitude: usize,
+    /// Index of the longitude column, in degrees.
+    pub longitude: usize,
+    /// Index of the altitude column, in meters above the ellipsoid.
+    pub altitude: Option<usize>,
+    /// Index of the speed column, in meters per second.
+    pub speed: Option<usize>,
+    /// Index of the track column, in degrees from true north.
+    pub track: Option<usize>,
+    /// Index of the horizontal position error column, in meters.
+    pub eph: Option<usize>,
+}
+
+impl Default for CsvColumns {
+    /// `time,latitude,longitude,altitude,speed,track` columns, with a header.
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            time: 0,
+            latitude: 1,
+            longitude: 2,
+            altitude: Some(3),
+            speed: Some(4),
+            track: Some(5),
+            eph: None,
+        }
+    }
+}
+
+impl CsvColumns {
+    /// Builds the [CsvColumns] from the names of the `header` line.
+    /// Returns `None` if the time, latitude or longitude column is missing.
+    pub fn from_header(header: &str) -> Option<CsvColumns> {
+        let delimiter = [',', ';', '\t'].into_iter().find(|d| header.contains(*d))?;
+
+        let names: Vec<String> = header
+            .split(delimiter)
+            .map(|name| name.trim().trim_matches('"').to_lowercase())
+            .collect();
+        let column = |candidates: &[&str]| {
+            names
+                .iter()
+                .position(|name| candidates.contains(&name.as_str()))
+        };
+
+        Some(CsvColumns {
+            delimiter,
+            header: true,
+            time: column(&["time", "timestamp", "utc"])?,
+            latitude: column(&["lat", "latitude"])?,
+            longitude: column(&["lon", "lng", "long", "longitude"])?,
+            altitude: column(&["alt", "altitude", "ele", "elevation"]),
+            speed: column(&["speed", "velocity"]),
+            track: column(&["track", "course", "heading"]),
+            eph: column(&["eph", "accuracy", "hacc"]),
+        })
+    }
+}
+
+/// Format of a replay file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFormat {
+    /// NMEA sentences. Only sentences of type `GGA` and `RMC` are processed.
+    Nmea,
+    /// GPSD JSON reports, as recorded with `gpspipe -w`.
+    /// Only `TPV` and `GST` reports are processed.
+    GpsdJson,
+    /// GPX track, with timestamped track points.
+    Gpx,
+    /// Comma separated values, with the given columns.
+    Csv(CsvColumns),
+}
+
+impl ReplayFormat {
+    /// Detects the format of a replay file from its `content`.
+    pub fn detect(content: &str) -> Option<ReplayFormat> {
+        let first_line = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+
+        if first_line.starts_with('$') {
+            Some(ReplayFormat::Nmea)
+        } else if first_line.starts_with('{') {
+            Some(ReplayFormat::GpsdJson)
+        } else if first_line.starts_with("<?xml") || first_line.starts_with("<gpx") {
+            Some(ReplayFormat::Gpx)
+        } else {
+            CsvColumns::from_header(first_line).map(ReplayFormat::Csv)
+        }
+    }
+}
+
+/// [Replay] configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayConfig {
+    /// Format of the replay file. Detected from the file content if `None`.
+    pub format: Option<ReplayFormat>,
+    /// Delay before rewinding to the beginning of the sequence.
+    pub rewind_delay: Duration,
+    /// Playback rate, ie: `2.0` plays twice as fast as recorded, `0.5` twice as slow.
+    pub rate: f64,
+    /// Offset in the recording at which playing starts, and rewinds to.
+    pub start_offset: Duration,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            format: None,
+            rewind_delay: Duration::from_secs(1),
+            rate: 1.0,
+            start_offset: Duration::ZERO,
+        }
+    }
+}
+
+/// A recorded position.
+#[derive(Debug, Clone, Copy)]
+struct Epoch {
+    /// Offset of the epoch from the beginning of the recording.
+    offset: TimeDelta,
+    /// Recorded position.
+    position: GpsInfo,
+}
+
+/// Re-player of recorded GPS positions.
 #[derive(Debug)]
 pub struct Replay {
-    /// Never ending iterator over filtered NMEA sentences from file.
-    cycle: Peekable<Cycle<IntoIter<(NmeaSentenceWrapper, String)>>>,
+    /// Recorded positions.
+    epochs: Vec<Epoch>,
+    /// Index of the first epoch to play, according to the start offset.
+    first: usize,
+    /// Index of the next epoch to play.
+    next: usize,
     /// Gps position.
     position: GpsInfo,
-    /// NMEA data temp cache. Used for building [Self::position].
-    cache: Nmea,
     /// Rewind delay, ie: delay before rewinding to the beginning of the sequence.
     rewind_delay: Duration,
+    /// Playback rate.
+    rate: f64,
     /// Instant at which the re-player should be polled at.
-    next_sentence_at: Instant,
+    next_epoch_at: Instant,
+    /// Shift applied to the recorded times, so they keep going forward after a rewind.
+    time_shift: TimeDelta,
 }
 
 impl Replay {
     /// Constructs a new [Replay] which will read from the given `file`. [Replay] will rewind
     /// to the beginning of the sequence when end of file is reached and start another playing.
-    /// Repetitions will be delayed by `rewind_delay` .
-    /// Only valid NMEA sentences of type `GGA` and `RMC` will be processed, others will be ignored.
+    /// Repetitions will be delayed by `rewind_delay`.
+    /// Format of the file is detected from its content.
     pub fn new(file: PathBuf, rewind_delay: Duration) -> ReplayResult<Replay> {
-        let file = File::open(&file).map_err(ReplayError::Io)?;
-        let reader = BufReader::new(file);
-
-        let sentences = reader
-            .lines()
-            .filter_map(|l| {
-                let line = l
-                    .inspect_err(|e| error!("Cannot read line from file: {}", e))
-                    .ok()?;
-
-                // Ignore GPGSA sentences, NMEA parser is bugged.
-                if line.starts_with("$GPGSA") {
-                    return None;
-                }
+        Self::with_config(
+            file,
+            ReplayConfig {
+                rewind_delay,
+                ..Default::default()
+            },
+        )
+    }
 
-                nmea::parse_str(&line)
-                    .inspect_err(|e| error!("Cannot parse NMEA sentence: {}", e))
-                    .ok()
-                    .map(|sentence| (NmeaSentenceWrapper(sentence), line))
-            })
-            .collect::<Vec<_>>();
+    /// Constructs a new [Replay] which will read from the given `file`, according to `config`.
+    pub fn with_config(file: PathBuf, config: ReplayConfig) -> ReplayResult<Replay> {
+        if !(config.rate.is_finite() && config.rate > 0.0) {
+            return Err(ReplayError::InvalidRate);
+        }
+
+        let content = fs::read_to_string(&file).map_err(ReplayError::Io)?;
+        let format = config
+            .format
+            .or_else(|| ReplayFormat::detect(&content))
+            .ok_or(ReplayError::UnknownFormat)?;
+
+        let epochs = match format {
+            ReplayFormat::Nmea => nmea_epochs(&content)?,
+            ReplayFormat::GpsdJson => gpsd_epochs(&content),
+            ReplayFormat::Gpx => track_epochs(gpx_track_points(&content)),
+            ReplayFormat::Csv(columns) => track_epochs(csv_track_points(&content, &columns)),
+        };
+
+        if epochs.is_empty() {
+            return Err(ReplayError::Empty);
+        }
+
+        let start_offset = TimeDelta::microseconds(config.start_offset.total_micros() as i64);
+        let first = epochs
+            .iter()
+            .position(|epoch| epoch.offset >= start_offset)
+            .ok_or(ReplayError::InvalidStartOffset)?;
 
         Ok(Self {
-            cycle: sentences.into_iter().cycle().peekable(),
+            epochs,
+            first,
+            next: first,
             position: Default::default(),
-            cache: Nmea::create_for_navigation(&[SentenceType::RMC, SentenceType::GGA])
-                .map_err(ReplayError::from)?,
-            rewind_delay,
-            next_sentence_at: Instant::ZERO,
+            rewind_delay: config.rewind_delay,
+            rate: config.rate,
+            next_epoch_at: Instant::ZERO,
+            time_shift: TimeDelta::zero(),
         })
     }
 
@@ -179,111 +276,603 @@ impl Replay {
         self.position
     }
 
-    /// Processes a GPS sentence present in the replay file.
+    /// Plays the next recorded position, if it is time to.
     ///
     /// This function returns a boolean value indicating whether a new position is available or not.
     pub fn poll(&mut self, timestamp: Instant) -> ReplayResult<bool> {
-        if timestamp < self.next_sentence_at {
+        if timestamp < self.next_epoch_at {
             return Ok(false);
         }
 
-        let ready = loop {
-            // First we call next() to get the value.
-            let (current, current_str) = self.cycle.next().unwrap_or_else(|| unreachable!());
-            let current_tst_opt = current.timestamp().cloned();
-
-            let ready = self.update_cache(&current_str);
-
-            // Then we call peek() to see when the next sentence will have to be processed.
-            let (next, _) = self.cycle.peek().unwrap_or_else(|| unreachable!());
-            match (next.timestamp(), current_tst_opt) {
-                (Some(next_tst), Some(current_tst)) => {
-                    let delta = *next_tst - current_tst;
-                    if delta == TimeDelta::zero() {
-                        // Next sentence has the same timestamp as the current one.
-                        continue;
-                    } else if delta > TimeDelta::zero() {
-                        self.next_sentence_at = timestamp
-                            + Duration::from_millis(delta.num_milliseconds().unsigned_abs());
-
-                        break ready;
-                    } else {
-                        // Rewind to the beginning of the sequence.
-                        self.cache =
-                            Nmea::create_for_navigation(&[SentenceType::RMC, SentenceType::GGA])
-                                .map_err(ReplayError::from)?;
-                        self.next_sentence_at = timestamp + self.rewind_delay;
-
-                        break ready;
-                    }
-                }
-                _ => {
-                    // Skip incomplete sentences.
-                    continue;
-                }
-            }
-        };
+        let epoch = self.epochs[self.next];
 
-        Ok(ready)
+        // Recorded positions are replayed with their recorded time. Positions
+        // recorded without a time are replayed as if they were received now.
+        let time = gps_info_time(&epoch.position).map_or_else(Utc::now, |t| t + self.time_shift);
+        self.position = epoch.position;
+        self.position.fix.time = Some(time);
+        self.position.gst.time = self.position.gst.time.map(|t| t + self.time_shift);
+        if self.position.confidence.is_none() {
+            self.position.self_confidence_at(time);
+        }
+
+        self.next += 1;
+        if self.next == self.epochs.len() {
+            // Rewind to the beginning of the sequence.
+            self.next = self.first;
+            self.next_epoch_at = timestamp + self.rewind_delay;
+
+            let rewind_delay = TimeDelta::microseconds(self.rewind_delay.total_micros() as i64);
+            self.time_shift += epoch.offset - self.epochs[self.first].offset + rewind_delay;
+        } else {
+            let delta = self.epochs[self.next].offset - epoch.offset;
+            let micros = delta.num_microseconds().unwrap_or(0).max(0) as f64 / self.rate;
+            self.next_epoch_at = timestamp + Duration::from_micros(micros as u64);
+        }
+
+        Ok(true)
     }
 
     /// Return the instant at which the re-player should be polled.
     pub fn poll_at(&self) -> Instant {
-        self.next_sentence_at
+        self.next_epoch_at
     }
 
     /// Return the duration to wait until the next call to [Self::poll].
     pub fn poll_delay(&self, timestamp: Instant) -> Duration {
-        self.next_sentence_at - timestamp
+        self.next_epoch_at - timestamp
+    }
+}
+
+/// Get the timestamp of an NMEA `sentence`, if any.
+fn nmea_timestamp(sentence: &ParseResult) -> Option<NaiveTime> {
+    match sentence {
+        ParseResult::BWC(a) => a.fix_time,
+        ParseResult::GBS(a) => a.time,
+        ParseResult::GGA(a) => a.fix_time,
+        ParseResult::GLL(a) => a.fix_time,
+        ParseResult::GNS(a) => a.fix_time,
+        ParseResult::RMC(a) => a.fix_time,
+        ParseResult::ZDA(a) => a.utc_time,
+        ParseResult::ZFO(a) => a.fix_time,
+        ParseResult::ZTG(a) => a.fix_time,
+        _ => None,
+    }
+}
+
+/// Decodes the positions recorded in an NMEA file `content`.
+fn nmea_epochs(content: &str) -> ReplayResult<Vec<Epoch>> {
+    let mut cache = Nmea::create_for_navigation(&[SentenceType::RMC, SentenceType::GGA])?;
+    let mut position = GpsInfo::default();
+    let mut epochs = Vec::new();
+    let mut epoch_time: Option<NaiveTime> = None;
+    let mut date: Option<NaiveDate> = None;
+    let mut offset = TimeDelta::zero();
+    let mut updated = false;
+
+    // Full date and time of an epoch, the date being given by RMC sentences.
+    let epoch_datetime =
+        |date: Option<NaiveDate>, time: Option<NaiveTime>| Some(date?.and_time(time?).and_utc());
+
+    for line in content.lines() {
+        // Ignore GPGSA sentences, NMEA parser is bugged.
+        if line.starts_with("$GPGSA") {
+            continue;
+        }
+
+        let sentence = match nmea::parse_str(line) {
+            Ok(sentence) => sentence,
+            Err(e) => {
+                error!("Cannot parse NMEA sentence: {}", e);
+                continue;
+            }
+        };
+
+        if let ParseResult::RMC(rmc) = &sentence {
+            date = rmc.fix_date.or(date);
+        }
+
+        // Sentences without timestamp belong to the current epoch.
+        if let Some(time) = nmea_timestamp(&sentence) {
+            match epoch_time {
+                Some(current) if current != time => {
+                    if updated {
+                        position.fix.time = epoch_datetime(date, Some(current));
+                        epochs.push(Epoch { offset, position });
+                        updated = false;
+                    }
+
+                    let mut delta = time - current;
+                    if delta < TimeDelta::zero() {
+                        // Midnight rollover.
+                        delta += TimeDelta::days(1);
+                    }
+                    offset += delta;
+                }
+                _ => {}
+            }
+            epoch_time = Some(time);
+        }
+
+        updated |= update_nmea_position(&mut cache, &mut position, line);
+    }
+
+    if updated {
+        position.fix.time = epoch_datetime(date, epoch_time);
+        epochs.push(Epoch { offset, position });
+    }
+
+    Ok(epochs)
+}
+
+/// Updates `position` with an NMEA `sentence`, using the `cache`.
+/// Returns `true` if the position is updated.
+fn update_nmea_position(cache: &mut Nmea, position: &mut GpsInfo, sentence: &str) -> bool {
+    if cache.parse_for_fix(sentence).is_err() {
+        return false;
+    }
+
+    position.fix.mode = match (cache.latitude(), cache.longitude(), cache.altitude()) {
+        (Some(_), Some(_), Some(_)) => FixMode::Fix3d,
+        (Some(_), Some(_), None) => FixMode::Fix2d,
+        _ => FixMode::NoFix,
+    };
+
+    position.fix.latitude = cache.latitude.map(Angle::new::<degree>);
+    position.fix.longitude = cache.longitude.map(Angle::new::<degree>);
+    position.fix.altitude = cache.altitude.map(|alt| Length::new::<meter>(alt.into()));
+
+    position.fix.track = cache
+        .true_course
+        .map(|track| Angle::new::<degree>(track.into()));
+
+    position.fix.speed = cache
+        .speed_over_ground
+        .map(|speed| Velocity::new::<knot>(speed.into()));
+
+    // Fake confidence values since NMEA parser does not provide them.
+    position.confidence = Some(crate::Confidence {
+        semi_major_axis: Length::new::<meter>(10.0),
+        semi_minor_axis: Length::new::<meter>(10.0),
+        semi_major_orientation: Angle::new::<degree>(0.0),
+    });
+
+    position.fix.epv = Some(Length::new::<meter>(10.0));
+    position.fix.eps = Some(Velocity::new::<meter_per_second>(10.0));
+    position.fix.epd = Some(Angle::new::<degree>(3.0));
+
+    true
+}
+
+/// Time of a [GpsInfo], ie: its fix time or its GST time.
+fn gps_info_time(info: &GpsInfo) -> Option<DateTime<Utc>> {
+    info.fix.time.or(info.gst.time)
+}
+
+/// Decodes the positions recorded in a GPSD JSON file `content`.
+/// Reports are grouped by fix time, the same way GPSD client does.
+fn gpsd_epochs(content: &str) -> Vec<Epoch> {
+    let mut cache = GpsInfo::default();
+    let mut epochs = Vec::new();
+    let mut start: Option<DateTime<Utc>> = None;
+
+    let mut push_epoch = |info: GpsInfo, epochs: &mut Vec<Epoch>| {
+        // Same as GPSD client, fixes which are not updated are ignored.
+        let Some(time) = gps_info_time(&info) else {
+            return;
+        };
+        if info.fix.mode == FixMode::NotUpdated {
+            return;
+        }
+
+        let start = *start.get_or_insert(time);
+        epochs.push(Epoch {
+            offset: time - start,
+            position: info,
+        });
+    };
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let response: GpsdResponse = match serde_json::from_str(line) {
+            Ok(response) => response,
+            Err(e) => {
+                trace!("ignoring GPSD report: {e}");
+                continue;
+            }
+        };
+
+        if !matches!(response, GpsdResponse::Tpv(_) | GpsdResponse::Gst(_)) {
+            continue;
+        }
+
+        let previous = cache;
+        gpsd_json::update_cache(&mut cache, [response]);
+
+        match (gps_info_time(&previous), gps_info_time(&cache)) {
+            (Some(previous_time), Some(time))
+                if (time - previous_time).abs() > ALLOWED_TIME_DIFF =>
+            {
+                push_epoch(previous, &mut epochs);
+            }
+            _ => {}
+        }
     }
 
-    fn update_cache(&mut self, sentence: &str) -> bool {
-        match self.cache.parse_for_fix(sentence) {
-            Ok(_) => {
-                self.position.fix.time = Some(Utc::now());
-
-                let mode = match (
-                    self.cache.latitude(),
-                    self.cache.longitude(),
-                    self.cache.altitude(),
-                ) {
-                    (Some(_), Some(_), Some(_)) => FixMode::Fix3d,
-                    (Some(_), Some(_), None) => FixMode::Fix2d,
-                    _ => FixMode::NoFix,
+    push_epoch(cache, &mut epochs);
+
+    epochs
+}
+
+/// A track point, from a GPX or CSV file.
+#[derive(Debug, Default, Clone, Copy)]
+struct TrackPoint {
+    /// Fix mode, if recorded.
+    mode: Option<FixMode>,
+    /// UTC time of the point.
+    time: DateTime<Utc>,
+    /// Latitude, in degrees.
+    latitude: f64,
+    /// Longitude, in degrees.
+    longitude: f64,
+    /// Altitude, in meters above the ellipsoid.
+    altitude: Option<f64>,
+    /// Speed, in meters per second.
+    speed: Option<f64>,
+    /// Track, in degrees from true north.
+    track: Option<f64>,
+    /// Horizontal position error, in meters.
+    eph: Option<f64>,
+}
+
+impl TrackPoint {
+    fn geo_position(&self) -> GeoPosition {
+        GeoPosition {
+            latitude: Angle::new::<degree>(self.latitude),
+            longitude: Angle::new::<degree>(self.longitude),
+        }
+    }
+
+    /// Fills the missing speed and track values from the motion between `from` and `to`.
+    fn fill_kinematics(&mut self, from: &TrackPoint, to: &TrackPoint) {
+        let elapsed = (to.time - from.time).num_microseconds().unwrap_or(0) as f64 / 1e6;
+        if elapsed <= 0.0 {
+            return;
+        }
+
+        let distance = from.geo_position().distance_to(&to.geo_position());
+        self.speed = self.speed.or(Some(distance.get::<meter>() / elapsed));
+
+        let north = (to.latitude - from.latitude).to_radians();
+        let east = (to.longitude - from.longitude).to_radians() * from.latitude.to_radians().cos();
+        if north != 0.0 || east != 0.0 {
+            self.track = self
+                .track
+                .or(Some(east.atan2(north).to_degrees().rem_euclid(360.0)));
+        }
+    }
+
+    fn into_gps_info(self) -> GpsInfo {
+        let mut info = GpsInfo::default();
+
+        info.fix.mode = self.mode.unwrap_or(if self.altitude.is_some() {
+            FixMode::Fix3d
+        } else {
+            FixMode::Fix2d
+        });
+        info.fix.time = Some(self.time);
+        info.fix.latitude = Some(Angle::new::<degree>(self.latitude));
+        info.fix.longitude = Some(Angle::new::<degree>(self.longitude));
+        info.fix.altitude = self.altitude.map(Length::new::<meter>);
+        info.fix.speed = self.speed.map(Velocity::new::<meter_per_second>);
+        info.fix.track = self.track.map(Angle::new::<degree>);
+        info.fix.eph = self.eph.map(Length::new::<meter>);
+
+        info
+    }
+}
+
+/// Converts track `points` into epochs. Missing speed and track values are
+/// computed from the neighbor points.
+fn track_epochs(mut points: Vec<TrackPoint>) -> Vec<Epoch> {
+    for i in 0..points.len() {
+        let (from, to) = match (i.checked_sub(1), points.get(i + 1)) {
+            (_, Some(next)) => (points[i], *next),
+            (Some(prev), None) => (points[prev], points[i]),
+            (None, None) => continue,
+        };
+        points[i].fill_kinematics(&from, &to);
+    }
+
+    let Some(start) = points.first().map(|p| p.time) else {
+        return Vec::new();
+    };
+
+    points
+        .into_iter()
+        .map(|point| Epoch {
+            offset: point.time - start,
+            position: point.into_gps_info(),
+        })
+        .collect()
+}
+
+/// Parses an RFC 3339 time or a number of seconds since the Unix epoch.
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.to_utc())
+        .ok()
+        .or_else(|| {
+            let secs: f64 = value.parse().ok()?;
+            DateTime::from_timestamp_micros((secs * 1e6).round() as i64)
+        })
+}
+
+/// Get the value of the `name` attribute of an XML `tag`.
+fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    ['"', '\''].into_iter().find_map(|quote| {
+        let pattern = format!(" {name}={quote}");
+        let start = tag.find(&pattern)? + pattern.len();
+        let len = tag[start..].find(quote)?;
+        Some(&tag[start..start + len])
+    })
+}
+
+/// Get the text content of the first `name` element in XML `content`.
+/// Namespace prefixes are ignored, ie. `<gpxtpx:speed>` is a `speed` element.
+fn xml_element<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let qualified = tag.split_whitespace().next().unwrap_or_default();
+        let local = qualified.rsplit(':').next().unwrap_or_default();
+        if local != name || tag.ends_with('/') {
+            continue;
+        }
+
+        let len = rest.find(&format!("</{qualified}>"))?;
+        return Some(rest[..len].trim());
+    }
+
+    None
+}
+
+/// Decodes the track points of a GPX file `content`.
+fn gpx_track_points(content: &str) -> Vec<TrackPoint> {
+    const END: &str = "</trkpt>";
+
+    let mut points = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("<trkpt") {
+        rest = &rest[start..];
+        let Some(end) = rest.find(END) else {
+            break;
+        };
+        let element = &rest[..end];
+        rest = &rest[end + END.len()..];
+
+        let tag = &element[..element.find('>').unwrap_or(element.len())];
+        let parse = |value: Option<&str>| value.and_then(|v| v.parse::<f64>().ok());
+
+        let (Some(latitude), Some(longitude), Some(time)) = (
+            parse(xml_attribute(tag, "lat")),
+            parse(xml_attribute(tag, "lon")),
+            xml_element(element, "time").and_then(parse_time),
+        ) else {
+            error!("Ignoring GPX track point without position or time");
+            continue;
+        };
+
+        let mode = xml_element(element, "fix").map(|fix| match fix {
+            "none" => FixMode::NoFix,
+            "2d" => FixMode::Fix2d,
+            _ => FixMode::Fix3d,
+        });
+
+        points.push(TrackPoint {
+            mode,
+            time,
+            latitude,
+            longitude,
+            altitude: parse(xml_element(element, "ele")),
+            speed: parse(xml_element(element, "speed")),
+            track: parse(xml_element(element, "course")),
+            eph: parse(xml_element(element, "hdop")).map(|hdop| hdop * UERE),
+        });
+    }
+
+    points
+}
+
+/// Decodes the track points of a CSV file `content` with the given `columns`.
+fn csv_track_points(content: &str, columns: &CsvColumns) -> Vec<TrackPoint> {
+    content
+        .lines()
+        .skip(usize::from(columns.header))
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|line| {
+            let fields: Vec<&str> = line
+                .split(columns.delimiter)
+                .map(|f| f.trim().trim_matches('"'))
+                .collect();
+            let field = |index: Option<usize>| -> Option<f64> {
+                fields.get(index?).and_then(|f| f.parse().ok())
+            };
+
+            let point = TrackPoint {
+                mode: None,
+                time: parse_time(fields.get(columns.time)?)?,
+                latitude: field(Some(columns.latitude))?,
+                longitude: field(Some(columns.longitude))?,
+                altitude: field(columns.altitude),
+                speed: field(columns.speed),
+                track: field(columns.track),
+                eph: field(columns.eph),
+            };
+
+            Some(point)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("examples/assets");
+        path.push(name);
+        path
+    }
+
+    /// Plays `replay` for `duration`, returning the positions and the instants they were played at.
+    fn play(replay: &mut Replay, duration: Duration) -> Vec<(Instant, GpsInfo)> {
+        let mut positions = Vec::new();
+        let mut now = Instant::ZERO;
+
+        while now < Instant::ZERO + duration {
+            if let Ok(true) = replay.poll(now) {
+                positions.push((now, replay.fetch_position()));
+            }
+            now += Duration::from_millis(10);
+        }
+
+        positions
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let detect = |name| ReplayFormat::detect(&fs::read_to_string(asset(name)).unwrap());
+
+        assert_eq!(detect("road.nmea"), Some(ReplayFormat::Nmea));
+        assert_eq!(detect("road.json"), Some(ReplayFormat::GpsdJson));
+        assert_eq!(detect("road.gpx"), Some(ReplayFormat::Gpx));
+        assert_eq!(
+            detect("road.csv"),
+            Some(ReplayFormat::Csv(CsvColumns {
+                eph: Some(6),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(ReplayFormat::detect("hello world"), None);
+    }
+
+    #[test]
+    fn test_formats() {
+        let mut nmea = Replay::new(asset("road.nmea"), Duration::from_secs(1)).unwrap();
+        let expected = play(&mut nmea, Duration::from_secs(1));
+        assert!(expected.len() > 5);
+        assert_eq!(
+            expected[0].1.fix.time,
+            DateTime::parse_from_rfc3339("2024-08-09T14:52:28.10Z")
+                .ok()
+                .map(|time| time.to_utc())
+        );
+
+        for name in ["road.json", "road.gpx", "road.csv"] {
+            let mut replay = Replay::new(asset(name), Duration::from_secs(1)).unwrap();
+            let positions = play(&mut replay, Duration::from_secs(1));
+            assert_eq!(positions.len(), expected.len(), "{name}");
+
+            for ((at, pos), (expected_at, expected_pos)) in positions.iter().zip(expected.iter()) {
+                let delta = |a: Option<Angle>, b: Option<Angle>| {
+                    (a.unwrap().get::<degree>() - b.unwrap().get::<degree>()).abs()
                 };
 
-                self.position.fix.mode = mode;
-                self.position.fix.latitude = self.cache.latitude.map(Angle::new::<degree>);
-                self.position.fix.longitude = self.cache.longitude.map(Angle::new::<degree>);
-                self.position.fix.altitude = self
-                    .cache
-                    .altitude
-                    .map(|alt| Length::new::<meter>(alt.into()));
-
-                self.position.fix.track = self
-                    .cache
-                    .true_course
-                    .map(|track| Angle::new::<degree>(track.into()));
-
-                self.position.fix.speed = self
-                    .cache
-                    .speed_over_ground
-                    .map(|speed| Velocity::new::<meter_per_second>(speed.into()));
-
-                // Fake confidence values since NMEA parser does not provide them.
-                self.position.confidence = Some(crate::Confidence {
-                    semi_major_axis: Length::new::<meter>(10.0),
-                    semi_minor_axis: Length::new::<meter>(10.0),
-                    semi_major_orientation: Angle::new::<degree>(0.0),
-                });
-
-                self.position.fix.epv = Some(Length::new::<meter>(10.0));
-                self.position.fix.eps = Some(Velocity::new::<meter_per_second>(10.0));
-                self.position.fix.epd = Some(Angle::new::<degree>(3.0));
-
-                true
+                assert_eq!(at, expected_at, "{name}");
+                assert_eq!(pos.fix.mode, FixMode::Fix3d, "{name}");
+                assert!(delta(pos.fix.latitude, expected_pos.fix.latitude) < 1e-9);
+                assert!(delta(pos.fix.longitude, expected_pos.fix.longitude) < 1e-9);
+                assert!(delta(pos.fix.track, expected_pos.fix.track) < 1.0, "{name}");
+
+                let speed = pos.fix.speed.unwrap().get::<meter_per_second>();
+                let expected_speed = expected_pos.fix.speed.unwrap().get::<meter_per_second>();
+                assert!((speed - expected_speed).abs() < 0.5, "{name}");
+
+                // Replayed positions carry their recorded time.
+                assert!(pos.confidence.is_some(), "{name}");
+                assert_eq!(pos.fix.time, expected_pos.fix.time, "{name}");
             }
-            Err(_) => false,
         }
     }
+
+    #[test]
+    fn test_gpx_extensions() {
+        let content = r#"<trkpt lat="48.2764" lon="-3.5519">
+            <time>2024-05-25T22:00:00Z</time>
+            <extensions>
+                <gpxtpx:TrackPointExtension>
+                    <gpxtpx:speed>12.5</gpxtpx:speed>
+                    <gpxtpx:course unit="degree">90.0</gpxtpx:course>
+                </gpxtpx:TrackPointExtension>
+                <hdop/>
+            </extensions>
+        </trkpt>"#;
+
+        let points = gpx_track_points(content);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].speed, Some(12.5));
+        assert_eq!(points[0].track, Some(90.0));
+        assert_eq!(points[0].eph, None);
+    }
+
+    #[test]
+    fn test_rate_and_start_offset() {
+        let mut replay = Replay::with_config(
+            asset("road.csv"),
+            ReplayConfig {
+                rate: 2.0,
+                start_offset: Duration::from_millis(500),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let positions = play(&mut replay, Duration::from_millis(500));
+        assert!(positions.len() > 5);
+        assert_eq!(positions[1].0, Instant::from_millis(50));
+
+        // Playing starts at, and rewinds to, the start offset.
+        let mut csv = Replay::new(asset("road.csv"), Duration::from_secs(1)).unwrap();
+        let all = play(&mut csv, Duration::from_secs(1));
+        assert_eq!(positions[0].1.fix.latitude, all[5].1.fix.latitude);
+
+        let positions = play(&mut replay, Duration::from_secs(2));
+        let rewinded = positions
+            .iter()
+            .any(|(_, p)| p.fix.latitude == all[5].1.fix.latitude);
+        assert!(rewinded);
+
+        // Recorded times keep going forward across the rewinds.
+        assert!(positions
+            .windows(2)
+            .all(|w| w[0].1.fix.time < w[1].1.fix.time));
+
+        assert!(matches!(
+            Replay::with_config(
+                asset("road.csv"),
+                ReplayConfig {
+                    start_offset: Duration::from_secs(3600),
+                    ..Default::default()
+                },
+            ),
+            Err(ReplayError::InvalidStartOffset)
+        ));
+        assert!(matches!(
+            Replay::with_config(
+                asset("road.csv"),
+                ReplayConfig {
+                    rate: 0.0,
+                    ..Default::default()
+                },
+            ),
+            Err(ReplayError::InvalidRate)
+        ));
+    }
 }
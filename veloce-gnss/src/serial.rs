@@ -0,0 +1,368 @@
+use std::io::Result;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use log::{debug, error, trace};
+use mio::event::Event;
+use mio::{Registry, Token};
+use nmea::sentences::{FixType, RmcStatusOfFix};
+use nmea::ParseResult;
+use uom::si::angle::degree;
+use uom::si::f64::{Angle, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::knot;
+use veloce::time::Instant;
+
+use crate::tty::TtyClient;
+use crate::{FixMode, GpsInfo};
+
+/// User Equivalent Range Error, used to estimate the horizontal position error
+/// from the HDOP when no GST sentence is received.
+const UERE: f64 = 5.0;
+
+/// NMEA serial GNSS receiver client.
+/// Processes `GGA`, `RMC`, `GST` and `VTG` sentences, others are ignored.
+#[derive(Debug)]
+pub struct Serial {
+    /// Gps position.
+    position: GpsInfo,
+    /// Gps data temp cache. Used for building [Self::position].
+    cache: GpsInfo,
+    /// Fix time of the last published position.
+    last_rx_time: DateTime<Utc>,
+    /// Date of the last `RMC` sentence, as other sentences only contain the time of day.
+    date: Option<NaiveDate>,
+    /// Time of day of the fix stored in [Self::cache].
+    epoch: Option<NaiveTime>,
+    /// Serial device.
+    tty: TtyClient,
+    /// Buffer for incoming data.
+    buffer: Vec<u8>,
+}
+
+impl Serial {
+    /// Constructs a new [Serial] client, reading NMEA sentences from the serial device
+    /// at `path` configured at `baud_rate`. The device is not opened until the client is polled.
+    pub fn new(
+        path: PathBuf,
+        baud_rate: u32,
+        registry: Registry,
+        reg_token: Token,
+        timestamp: Instant,
+    ) -> Result<Serial> {
+        Ok(Serial {
+            position: GpsInfo::default(),
+            cache: GpsInfo::default(),
+            last_rx_time: DateTime::default(),
+            date: None,
+            epoch: None,
+            tty: TtyClient::new(path, baud_rate, registry, reg_token, timestamp)?,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Fetch the position.
+    pub fn fetch_position(&self) -> GpsInfo {
+        self.position
+    }
+
+    /// Opens the serial device, if not already opened.
+    pub fn poll(&mut self, timestamp: Instant) -> Result<()> {
+        self.tty.poll(timestamp).map(|_| ())
+    }
+
+    /// Query whether the serial device is ready to receive data.
+    /// Returns `true` if there is new position data to be fetched.
+    pub fn ready(&mut self, event: &Event, timestamp: Instant) -> bool {
+        if event.token() != self.tty.token() {
+            error!("event token does not match");
+            return false;
+        }
+
+        if event.is_readable() {
+            self.tty.read(&mut self.buffer, timestamp);
+        }
+
+        let mut position_updated = false;
+
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let Ok(sentence) = core::str::from_utf8(&line) else {
+                error!("failed to perform conversion to string");
+                continue;
+            };
+
+            if self.update_cache(sentence.trim()) {
+                position_updated |= self.update_position();
+            }
+        }
+
+        debug!("position updated: {}", position_updated);
+        position_updated
+    }
+
+    /// Updates `self.last_rx_time` and `self.position` if position is valid.
+    /// Returns `true` if position is updated.
+    fn update_position(&mut self) -> bool {
+        match self.cache.fix.mode {
+            FixMode::NotUpdated => {
+                // Ignore fix if not updated.
+                trace!("FixMode::NotUpdated");
+                return false;
+            }
+            FixMode::NoFix => {
+                // Send NoFix to notify no GPS signal or GPS loss.
+                trace!("FixMode::NoFix");
+                let changed = self.position.fix.mode != FixMode::NoFix;
+                self.position = self.cache;
+                return changed;
+            }
+            _ => {}
+        }
+
+        // Only update if we have time, latitude, longitude and speed.
+        match self.cache.fix.time_position_and_kinematics_values() {
+            Some((time, ..)) if time >= self.last_rx_time => {
+                self.last_rx_time = time;
+
+                // Fill confidence values.
+                self.cache.self_confidence();
+
+                let changed = self.position != self.cache;
+
+                if changed {
+                    self.position = self.cache;
+                }
+
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    /// Resets the cache if `time` belongs to a new epoch, ie: a new fix.
+    /// Returns the full date and time of the epoch.
+    fn new_epoch(&mut self, time: Option<NaiveTime>) -> Option<DateTime<Utc>> {
+        let time = time?;
+
+        if self.epoch.is_some_and(|epoch| epoch != time) {
+            self.cache = GpsInfo::default();
+        }
+        self.epoch = Some(time);
+
+        let date = self.date.unwrap_or_else(|| Utc::now().date_naive());
+        let datetime = NaiveDateTime::new(date, time).and_utc();
+
+        self.cache.fix.time = Some(datetime);
+        Some(datetime)
+    }
+
+    /// Fill the cache from an NMEA `sentence`.
+    /// Cache is valid for a fix timestamp. When fix timestamp changes, cache is cleared.
+    /// Returns `true` if cache is updated.
+    fn update_cache(&mut self, sentence: &str) -> bool {
+        let parsed = match nmea::parse_str(sentence) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                trace!("cannot parse NMEA sentence: {e}");
+                return false;
+            }
+        };
+
+        match parsed {
+            ParseResult::GGA(gga) => {
+                self.new_epoch(gga.fix_time);
+
+                if matches!(gga.fix_type, None | Some(FixType::Invalid)) {
+                    self.cache.fix.mode = FixMode::NoFix;
+                    return true;
+                }
+
+                // GGA altitude is above mean sea level, whereas fix altitude is above ellipsoid.
+                let altitude = gga
+                    .altitude
+                    .map(|alt| f64::from(alt) + gga.geoid_separation.map_or(0.0, f64::from));
+
+                self.cache.fix.mode = if altitude.is_some() {
+                    FixMode::Fix3d
+                } else {
+                    FixMode::Fix2d
+                };
+                self.cache.fix.latitude = gga.latitude.map(Angle::new::<degree>);
+                self.cache.fix.longitude = gga.longitude.map(Angle::new::<degree>);
+                self.cache.fix.altitude = altitude.map(Length::new::<meter>);
+                self.cache.fix.eph = gga
+                    .hdop
+                    .map(|hdop| Length::new::<meter>(f64::from(hdop) * UERE));
+            }
+            ParseResult::RMC(rmc) => {
+                self.date = rmc.fix_date.or(self.date);
+                self.new_epoch(rmc.fix_time);
+
+                if matches!(rmc.status_of_fix, RmcStatusOfFix::Invalid) {
+                    self.cache.fix.mode = FixMode::NoFix;
+                    return true;
+                }
+
+                if matches!(self.cache.fix.mode, FixMode::NotUpdated | FixMode::NoFix) {
+                    self.cache.fix.mode = FixMode::Fix2d;
+                }
+                self.cache.fix.latitude = rmc.lat.map(Angle::new::<degree>);
+                self.cache.fix.longitude = rmc.lon.map(Angle::new::<degree>);
+                self.cache.fix.speed = rmc
+                    .speed_over_ground
+                    .map(|speed| Velocity::new::<knot>(speed.into()));
+                self.cache.fix.track = rmc
+                    .true_course
+                    .map(|track| Angle::new::<degree>(track.into()));
+            }
+            ParseResult::GST(gst) => {
+                self.cache.gst.time = self.new_epoch(gst.time);
+
+                self.cache.gst.rms_deviation = gst.rms_sd.map(|rms| rms.into());
+                self.cache.gst.major_deviation = gst
+                    .ellipse_semi_major_sd
+                    .map(|major_dev| Length::new::<meter>(major_dev.into()));
+                self.cache.gst.minor_deviation = gst
+                    .ellipse_semi_minor_sd
+                    .map(|minor_dev| Length::new::<meter>(minor_dev.into()));
+                self.cache.gst.major_orientation = gst
+                    .err_ellipse_orientation
+                    .map(|major_orient| Angle::new::<degree>(major_orient.into()));
+                self.cache.gst.lat_err_deviation = gst
+                    .lat_sd
+                    .map(|lat_dev| Length::new::<meter>(lat_dev.into()));
+                self.cache.gst.lon_err_deviation = gst
+                    .long_sd
+                    .map(|lon_dev| Length::new::<meter>(lon_dev.into()));
+                self.cache.gst.alt_err_deviation = gst
+                    .alt_sd
+                    .map(|alt_dev| Length::new::<meter>(alt_dev.into()));
+            }
+            ParseResult::VTG(vtg) => {
+                // VTG has no time, it belongs to the current epoch.
+                if let Some(speed) = vtg.speed_over_ground {
+                    self.cache.fix.speed = Some(Velocity::new::<knot>(speed.into()));
+                }
+                if let Some(track) = vtg.true_course {
+                    self.cache.fix.track = Some(Angle::new::<degree>(track.into()));
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::time::Duration as StdDuration;
+
+    use mio::{Events, Poll};
+
+    use super::*;
+    use crate::tty::tests::open_pty;
+
+    const TOKEN: Token = Token(0);
+
+    /// Builds an NMEA sentence from its `body`, with a valid checksum.
+    fn nmea_sentence(body: &str) -> String {
+        let checksum = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        format!("${body}*{checksum:02X}\r\n")
+    }
+
+    /// Polls `serial` until a position is available, or timeout.
+    fn wait_position(serial: &mut Serial, poll: &mut Poll) -> Option<GpsInfo> {
+        let mut events = Events::with_capacity(8);
+        for _ in 0..20 {
+            poll.poll(&mut events, Some(StdDuration::from_millis(50)))
+                .unwrap();
+            for event in events.iter() {
+                if serial.ready(event, Instant::now())
+                    && serial.fetch_position().confidence.is_some()
+                {
+                    return Some(serial.fetch_position());
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_serial_nmea() {
+        let (mut master, path) = open_pty();
+        let mut poll = Poll::new().unwrap();
+
+        let mut serial = Serial::new(
+            path,
+            9600,
+            poll.registry().try_clone().unwrap(),
+            TOKEN,
+            Instant::now(),
+        )
+        .unwrap();
+        serial.poll(Instant::now()).unwrap();
+
+        let sentences = [
+            "GPGGA,092750.000,5321.6802,N,00630.3372,W,1,8,1.03,61.7,M,55.2,M,,",
+            "GPRMC,092750.000,A,5321.6802,N,00630.3372,W,0.02,31.66,280511,,,A",
+            "GPGST,092750.000,1.2,4.0,2.0,45.0,3.0,3.5,5.0",
+            "GPTXT,01,01,02,ANTSTATUS=OK",
+        ]
+        .map(nmea_sentence)
+        .concat();
+        master.write_all(sentences.as_bytes()).unwrap();
+
+        let position = wait_position(&mut serial, &mut poll).expect("no position");
+        let (time, lat, lon) = position.fix.time_and_position_values().unwrap();
+
+        assert_eq!(position.fix.mode, FixMode::Fix3d);
+        assert_eq!(time.to_rfc3339(), "2011-05-28T09:27:50+00:00");
+        assert!((lat.get::<degree>() - 53.36134).abs() < 1e-5);
+        assert!((lon.get::<degree>() + 6.50562).abs() < 1e-5);
+        assert!((position.fix.altitude.unwrap().get::<meter>() - 116.9).abs() < 1e-3);
+
+        let confidence = position.confidence.unwrap();
+        assert_eq!(confidence.semi_major_axis, Length::new::<meter>(4.0));
+        assert_eq!(confidence.semi_minor_axis, Length::new::<meter>(2.0));
+        assert_eq!(
+            confidence.semi_major_orientation,
+            Angle::new::<degree>(45.0)
+        );
+    }
+
+    #[test]
+    fn test_serial_no_fix() {
+        let (mut master, path) = open_pty();
+        let mut poll = Poll::new().unwrap();
+        let mut events = Events::with_capacity(8);
+
+        let mut serial = Serial::new(
+            path,
+            115200,
+            poll.registry().try_clone().unwrap(),
+            TOKEN,
+            Instant::now(),
+        )
+        .unwrap();
+        serial.poll(Instant::now()).unwrap();
+
+        let sentence = nmea_sentence("GPRMC,092751.000,V,,,,,,,280511,,,N");
+        master.write_all(sentence.as_bytes()).unwrap();
+
+        let mut updated = false;
+        for _ in 0..20 {
+            poll.poll(&mut events, Some(StdDuration::from_millis(50)))
+                .unwrap();
+            if events.iter().any(|e| serial.ready(e, Instant::now())) {
+                updated = true;
+                break;
+            }
+        }
+
+        assert!(updated);
+        assert_eq!(serial.fetch_position().fix.mode, FixMode::NoFix);
+    }
+}
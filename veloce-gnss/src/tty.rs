@@ -0,0 +1,276 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::mem;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use log::{error, info};
+use mio::event::Source;
+use mio::unix::SourceFd;
+use mio::{Interest, Registry, Token};
+use veloce::time::{Duration, Instant};
+
+/// Delay before re-opening a serial device after a failure.
+const RETRY: Duration = Duration::from_secs(5);
+/// Size of the chunks read from the serial device.
+const READ_SIZE: usize = 1024;
+/// Maximum size of the buffer for incoming data.
+pub(crate) const BUFFER_SIZE: usize = 8192;
+
+/// A serial device, configured in raw and non-blocking mode.
+#[derive(Debug)]
+struct Tty {
+    file: File,
+}
+
+impl Tty {
+    /// Opens the serial device at `path` and configures it at `baud_rate`.
+    fn open(path: &Path, baud_rate: u32) -> io::Result<Tty> {
+        let speed = baud_rate_to_speed(baud_rate)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(path)?;
+
+        let fd = file.as_raw_fd();
+
+        // SAFETY: `fd` is a valid file descriptor for the lifetime of `file`, and `termios`
+        // is fully initialized by tcgetattr() before being used.
+        unsafe {
+            let mut termios: libc::termios = mem::zeroed();
+            if libc::tcgetattr(fd, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            libc::cfmakeraw(&mut termios);
+            termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+            termios.c_cc[libc::VMIN] = 0;
+            termios.c_cc[libc::VTIME] = 0;
+
+            if libc::cfsetispeed(&mut termios, speed) != 0
+                || libc::cfsetospeed(&mut termios, speed) != 0
+                || libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+
+            // Discard stale data received before opening.
+            libc::tcflush(fd, libc::TCIFLUSH);
+        }
+
+        Ok(Tty { file })
+    }
+}
+
+impl Source for Tty {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.file.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.file.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.file.as_raw_fd()).deregister(registry)
+    }
+}
+
+/// Describes the status of the serial device.
+#[derive(Debug)]
+enum TtyState {
+    /// Device is closed, and should be (re)opened at the given instant.
+    Closed(Instant),
+    /// Device is opened and registered.
+    Opened(Tty),
+}
+
+/// Serial device client, re-opening the device when it disappears.
+#[derive(Debug)]
+pub(crate) struct TtyClient {
+    /// Path of the serial device.
+    path: PathBuf,
+    /// Baud rate of the serial device.
+    baud_rate: u32,
+    /// Mio poll registry.
+    registry: Registry,
+    /// Registry token for the serial device.
+    token: Token,
+    /// Device state.
+    state: TtyState,
+}
+
+impl TtyClient {
+    /// Constructs a new [TtyClient]. The device is not opened until the client is polled.
+    pub fn new(
+        path: PathBuf,
+        baud_rate: u32,
+        registry: Registry,
+        token: Token,
+        timestamp: Instant,
+    ) -> io::Result<TtyClient> {
+        baud_rate_to_speed(baud_rate)?;
+
+        Ok(TtyClient {
+            path,
+            baud_rate,
+            registry,
+            token,
+            state: TtyState::Closed(timestamp),
+        })
+    }
+
+    /// Registry token of the serial device.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Opens the serial device if closed and it is time to.
+    /// Returns `true` if the device has just been opened.
+    pub fn poll(&mut self, timestamp: Instant) -> io::Result<bool> {
+        match self.state {
+            TtyState::Closed(when) if when <= timestamp => {}
+            _ => return Ok(false),
+        }
+
+        let mut tty = match Tty::open(&self.path, self.baud_rate) {
+            Ok(tty) => tty,
+            Err(e) => {
+                error!(
+                    "failed to open serial device {}, retry in {} secs - {}",
+                    self.path.display(),
+                    RETRY.secs(),
+                    e
+                );
+                self.state = TtyState::Closed(timestamp + RETRY);
+                return Ok(false);
+            }
+        };
+
+        self.registry
+            .register(&mut tty, self.token, Interest::READABLE)?;
+
+        info!("opened serial device {}", self.path.display());
+        self.state = TtyState::Opened(tty);
+
+        Ok(true)
+    }
+
+    /// Reads all the available bytes from the serial device into `buffer`.
+    /// The device is closed on error or hang up.
+    pub fn read(&mut self, buffer: &mut Vec<u8>, timestamp: Instant) {
+        let TtyState::Opened(tty) = &mut self.state else {
+            return;
+        };
+
+        let mut chunk = [0u8; READ_SIZE];
+        loop {
+            match tty.file.read(&mut chunk) {
+                Ok(0) => {
+                    error!(
+                        "serial device {} hung up, re-opening in {} secs",
+                        self.path.display(),
+                        RETRY.secs()
+                    );
+                    self.close(timestamp);
+                    return;
+                }
+                Ok(num_bytes) => {
+                    if buffer.len() + num_bytes > BUFFER_SIZE {
+                        error!("Maximum buffer size reached - clearing buffer");
+                        buffer.clear();
+                    }
+                    buffer.extend_from_slice(&chunk[..num_bytes]);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    error!(
+                        "failed to read serial device {}, re-opening in {} secs - {}",
+                        self.path.display(),
+                        RETRY.secs(),
+                        e
+                    );
+                    self.close(timestamp);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Writes `data` to the serial device, if opened.
+    pub fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        match &mut self.state {
+            TtyState::Opened(tty) => tty.file.write_all(data),
+            TtyState::Closed(_) => Err(ErrorKind::NotConnected.into()),
+        }
+    }
+
+    /// Closes the serial device, which will be re-opened after a delay.
+    pub fn close(&mut self, timestamp: Instant) {
+        if let TtyState::Opened(mut tty) =
+            mem::replace(&mut self.state, TtyState::Closed(timestamp + RETRY))
+        {
+            self.registry.deregister(&mut tty).ok();
+        }
+    }
+}
+
+/// Converts a `baud_rate` into its termios speed value.
+fn baud_rate_to_speed(baud_rate: u32) -> io::Result<libc::speed_t> {
+    let speed = match baud_rate {
+        4800 => libc::B4800,
+        9600 => libc::B9600,
+        19200 => libc::B19200,
+        38400 => libc::B38400,
+        57600 => libc::B57600,
+        115200 => libc::B115200,
+        230400 => libc::B230400,
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        460800 => libc::B460800,
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        921600 => libc::B921600,
+        _ => return Err(ErrorKind::InvalidInput.into()),
+    };
+
+    Ok(speed)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::ffi::CStr;
+    use std::fs::File;
+    use std::os::fd::FromRawFd;
+    use std::path::PathBuf;
+
+    /// Opens a pseudo-terminal. Returns the master side and the path of the slave side.
+    pub fn open_pty() -> (File, PathBuf) {
+        // SAFETY: return values are checked, and ptsname() result is copied before
+        // any other call.
+        unsafe {
+            let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            assert!(fd >= 0, "posix_openpt() failed");
+            assert_eq!(libc::grantpt(fd), 0, "grantpt() failed");
+            assert_eq!(libc::unlockpt(fd), 0, "unlockpt() failed");
+
+            let name = libc::ptsname(fd);
+            assert!(!name.is_null(), "ptsname() failed");
+            let path = PathBuf::from(CStr::from_ptr(name).to_str().unwrap());
+
+            (File::from_raw_fd(fd), path)
+        }
+    }
+}
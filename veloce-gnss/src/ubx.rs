@@ -0,0 +1,535 @@
+use std::io::Result;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use log::{debug, error, trace};
+use mio::event::Event;
+use mio::{Registry, Token};
+use uom::si::angle::degree;
+use uom::si::f64::{Angle, Length, Velocity};
+use uom::si::length::{meter, millimeter};
+use uom::si::velocity::millimeter_per_second;
+use veloce::time::{Duration, Instant};
+
+use crate::tty::{TtyClient, BUFFER_SIZE};
+use crate::{FixMode, GpsInfo};
+
+/// UBX frame synchronization characters.
+const SYNC: [u8; 2] = [0xb5, 0x62];
+/// UBX frame header length, ie: sync characters, class, id and length.
+const HEADER_LEN: usize = 6;
+/// UBX frame checksum length.
+const CHECKSUM_LEN: usize = 2;
+
+/// UBX-NAV message class.
+const CLASS_NAV: u8 = 0x01;
+/// UBX-CFG message class.
+const CLASS_CFG: u8 = 0x06;
+/// UBX-NAV-PVT message id.
+const ID_NAV_PVT: u8 = 0x07;
+/// UBX-NAV-COV message id.
+const ID_NAV_COV: u8 = 0x36;
+/// UBX-CFG-MSG message id.
+const ID_CFG_MSG: u8 = 0x01;
+
+/// UBX-NAV-PVT payload length.
+const NAV_PVT_LEN: usize = 92;
+/// UBX-NAV-COV payload length.
+const NAV_COV_LEN: usize = 64;
+
+/// A UBX protocol frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UbxFrame {
+    /// Message class.
+    class: u8,
+    /// Message id.
+    id: u8,
+    /// Message payload.
+    payload: Vec<u8>,
+}
+
+impl UbxFrame {
+    /// Serializes the frame, including the sync characters and the checksum.
+    fn to_bytes(&self) -> Vec<u8> {
+        let len = (self.payload.len() as u16).to_le_bytes();
+        let mut bytes = [&SYNC[..], &[self.class, self.id], &len, &self.payload].concat();
+        let (ck_a, ck_b) = checksum(&bytes[SYNC.len()..]);
+        bytes.extend_from_slice(&[ck_a, ck_b]);
+        bytes
+    }
+
+    /// Extracts the first valid frame from `buffer`. Leading garbage and invalid frames are
+    /// dropped from the buffer, incomplete frames are left in it.
+    fn parse(buffer: &mut Vec<u8>) -> Option<UbxFrame> {
+        loop {
+            match buffer.windows(SYNC.len()).position(|w| w == SYNC) {
+                Some(start) => {
+                    buffer.drain(..start);
+                }
+                None => {
+                    // Keep a possible first sync character.
+                    let keep = usize::from(buffer.last() == Some(&SYNC[0]));
+                    buffer.drain(..buffer.len() - keep);
+                    return None;
+                }
+            }
+
+            if buffer.len() < HEADER_LEN {
+                return None;
+            }
+
+            let len = usize::from(u16::from_le_bytes([buffer[4], buffer[5]]));
+            if len + HEADER_LEN + CHECKSUM_LEN > BUFFER_SIZE {
+                trace!("UBX frame too long - skipping");
+                buffer.drain(..SYNC.len());
+                continue;
+            }
+
+            let end = HEADER_LEN + len;
+            if buffer.len() < end + CHECKSUM_LEN {
+                return None;
+            }
+
+            if checksum(&buffer[SYNC.len()..end]) != (buffer[end], buffer[end + 1]) {
+                trace!("UBX frame checksum mismatch - skipping");
+                buffer.drain(..SYNC.len());
+                continue;
+            }
+
+            let frame: Vec<u8> = buffer.drain(..end + CHECKSUM_LEN).collect();
+
+            return Some(UbxFrame {
+                class: frame[2],
+                id: frame[3],
+                payload: frame[HEADER_LEN..end].to_vec(),
+            });
+        }
+    }
+}
+
+/// Computes the 8-bit Fletcher checksum of `data`, as defined by the UBX protocol.
+fn checksum(data: &[u8]) -> (u8, u8) {
+    data.iter().fold((0u8, 0u8), |(ck_a, ck_b), b| {
+        let ck_a = ck_a.wrapping_add(*b);
+        (ck_a, ck_b.wrapping_add(ck_a))
+    })
+}
+
+fn u16_at(payload: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([payload[offset], payload[offset + 1]])
+}
+
+fn u32_at(payload: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        payload[offset],
+        payload[offset + 1],
+        payload[offset + 2],
+        payload[offset + 3],
+    ])
+}
+
+fn i32_at(payload: &[u8], offset: usize) -> i32 {
+    u32_at(payload, offset) as i32
+}
+
+fn f32_at(payload: &[u8], offset: usize) -> f32 {
+    f32::from_bits(u32_at(payload, offset))
+}
+
+/// Computes the error ellipse from the `nn`, `ne` and `ee` terms of the horizontal
+/// position covariance matrix, in square meters.
+/// Returns the standard deviations of the semi-major and semi-minor axis, and the
+/// orientation of the semi-major axis from true north.
+fn error_ellipse(nn: f64, ne: f64, ee: f64) -> (Length, Length, Angle) {
+    let mean = (nn + ee) / 2.0;
+    let delta = (((nn - ee) / 2.0).powi(2) + ne.powi(2)).sqrt();
+
+    let major = (mean + delta).max(0.0).sqrt();
+    let minor = (mean - delta).max(0.0).sqrt();
+
+    let mut orientation = (0.5 * (2.0 * ne).atan2(nn - ee)).to_degrees();
+    if orientation < 0.0 {
+        orientation += 180.0;
+    }
+
+    (
+        Length::new::<meter>(major),
+        Length::new::<meter>(minor),
+        Angle::new::<degree>(orientation),
+    )
+}
+
+/// u-blox UBX serial GNSS receiver client.
+/// Processes `UBX-NAV-PVT` and `UBX-NAV-COV` messages, others are ignored.
+#[derive(Debug)]
+pub struct Ubx {
+    /// Gps position.
+    position: GpsInfo,
+    /// Gps data temp cache. Used for building [Self::position].
+    cache: GpsInfo,
+    /// Fix time of the last published position.
+    last_rx_time: DateTime<Utc>,
+    /// GPS time of week of the navigation epoch stored in [Self::cache].
+    itow: Option<u32>,
+    /// Serial device.
+    tty: TtyClient,
+    /// Buffer for incoming data.
+    buffer: Vec<u8>,
+}
+
+impl Ubx {
+    /// Constructs a new [Ubx] client, reading UBX messages from the serial device
+    /// at `path` configured at `baud_rate`. The device is not opened until the client is polled.
+    pub fn new(
+        path: PathBuf,
+        baud_rate: u32,
+        registry: Registry,
+        reg_token: Token,
+        timestamp: Instant,
+    ) -> Result<Ubx> {
+        Ok(Ubx {
+            position: GpsInfo::default(),
+            cache: GpsInfo::default(),
+            last_rx_time: DateTime::default(),
+            itow: None,
+            tty: TtyClient::new(path, baud_rate, registry, reg_token, timestamp)?,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Fetch the position.
+    pub fn fetch_position(&self) -> GpsInfo {
+        self.position
+    }
+
+    /// Opens the serial device, if not already opened. Once opened, the receiver is
+    /// configured to output `UBX-NAV-PVT` and `UBX-NAV-COV` messages at each navigation epoch.
+    pub fn poll(&mut self, timestamp: Instant) -> Result<()> {
+        if !self.tty.poll(timestamp)? {
+            return Ok(());
+        }
+
+        for id in [ID_NAV_PVT, ID_NAV_COV] {
+            let cfg_msg = UbxFrame {
+                class: CLASS_CFG,
+                id: ID_CFG_MSG,
+                payload: vec![CLASS_NAV, id, 1],
+            };
+
+            if let Err(e) = self.tty.write_all(&cfg_msg.to_bytes()) {
+                error!("failed to configure UBX receiver - {}", e);
+                self.tty.close(timestamp);
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Query whether the serial device is ready to receive data.
+    /// Returns `true` if there is new position data to be fetched.
+    pub fn ready(&mut self, event: &Event, timestamp: Instant) -> bool {
+        if event.token() != self.tty.token() {
+            error!("event token does not match");
+            return false;
+        }
+
+        if event.is_readable() {
+            self.tty.read(&mut self.buffer, timestamp);
+        }
+
+        let mut position_updated = false;
+
+        while let Some(frame) = UbxFrame::parse(&mut self.buffer) {
+            let updated = match (frame.class, frame.id) {
+                (CLASS_NAV, ID_NAV_PVT) => self.update_nav_pvt(&frame.payload),
+                (CLASS_NAV, ID_NAV_COV) => self.update_nav_cov(&frame.payload),
+                _ => false,
+            };
+
+            if updated {
+                position_updated |= self.update_position();
+            }
+        }
+
+        debug!("position updated: {}", position_updated);
+        position_updated
+    }
+
+    /// Updates `self.last_rx_time` and `self.position` if position is valid.
+    /// Returns `true` if position is updated.
+    fn update_position(&mut self) -> bool {
+        match self.cache.fix.mode {
+            FixMode::NotUpdated => {
+                // Ignore fix if not updated.
+                trace!("FixMode::NotUpdated");
+                return false;
+            }
+            FixMode::NoFix => {
+                // Send NoFix to notify no GPS signal or GPS loss.
+                trace!("FixMode::NoFix");
+                let changed = self.position.fix.mode != FixMode::NoFix;
+                self.position = self.cache;
+                return changed;
+            }
+            _ => {}
+        }
+
+        // Only update if we have time, latitude, longitude and speed.
+        match self.cache.fix.time_position_and_kinematics_values() {
+            Some((time, ..)) if time >= self.last_rx_time => {
+                self.last_rx_time = time;
+
+                // Fill confidence values.
+                self.cache.self_confidence();
+
+                let changed = self.position != self.cache;
+
+                if changed {
+                    self.position = self.cache;
+                }
+
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    /// Resets the cache if `itow` belongs to a new navigation epoch.
+    fn new_epoch(&mut self, itow: u32) {
+        if self.itow.is_some_and(|i| i != itow) {
+            self.cache = GpsInfo::default();
+        }
+        self.itow = Some(itow);
+    }
+
+    /// Fill the cache from a `UBX-NAV-PVT` payload.
+    /// Returns `true` if cache is updated.
+    fn update_nav_pvt(&mut self, payload: &[u8]) -> bool {
+        if payload.len() < NAV_PVT_LEN {
+            error!("UBX-NAV-PVT payload too short");
+            return false;
+        }
+
+        self.new_epoch(u32_at(payload, 0));
+
+        // Use the local system time if the receiver time is not valid.
+        let valid_date_time = payload[11] & 0x03 == 0x03;
+        let time = NaiveDate::from_ymd_opt(
+            u16_at(payload, 4).into(),
+            payload[6].into(),
+            payload[7].into(),
+        )
+        .and_then(|date| date.and_hms_opt(payload[8].into(), payload[9].into(), payload[10].into()))
+        .map(|dt| dt.and_utc() + TimeDelta::nanoseconds(i32_at(payload, 16).into()))
+        .filter(|_| valid_date_time)
+        .unwrap_or_else(Utc::now);
+
+        self.cache.fix.time = Some(time);
+        self.cache.fix.ept = Some(Duration::from_micros(
+            u64::from(u32_at(payload, 12)).div_ceil(1000),
+        ));
+
+        let gnss_fix_ok = payload[21] & 0x01 != 0;
+        self.cache.fix.mode = match (gnss_fix_ok, payload[20]) {
+            (true, 3 | 4) => FixMode::Fix3d,
+            (true, 2) => FixMode::Fix2d,
+            _ => FixMode::NoFix,
+        };
+
+        if self.cache.fix.mode == FixMode::NoFix {
+            return true;
+        }
+
+        self.cache.fix.longitude =
+            Some(Angle::new::<degree>(f64::from(i32_at(payload, 24)) * 1e-7));
+        self.cache.fix.latitude = Some(Angle::new::<degree>(f64::from(i32_at(payload, 28)) * 1e-7));
+        self.cache.fix.altitude = Some(Length::new::<millimeter>(i32_at(payload, 32).into()));
+        self.cache.fix.eph = Some(Length::new::<millimeter>(u32_at(payload, 40).into()));
+        self.cache.fix.epv = Some(Length::new::<millimeter>(u32_at(payload, 44).into()));
+        self.cache.fix.climb = Some(Velocity::new::<millimeter_per_second>(-f64::from(i32_at(
+            payload, 56,
+        ))));
+        self.cache.fix.speed = Some(Velocity::new::<millimeter_per_second>(
+            i32_at(payload, 60).into(),
+        ));
+        self.cache.fix.track = Some(Angle::new::<degree>(f64::from(i32_at(payload, 64)) * 1e-5));
+        self.cache.fix.eps = Some(Velocity::new::<millimeter_per_second>(
+            u32_at(payload, 68).into(),
+        ));
+        self.cache.fix.epd = Some(Angle::new::<degree>(f64::from(u32_at(payload, 72)) * 1e-5));
+
+        true
+    }
+
+    /// Fill the cache from a `UBX-NAV-COV` payload.
+    /// Returns `true` if cache is updated.
+    fn update_nav_cov(&mut self, payload: &[u8]) -> bool {
+        if payload.len() < NAV_COV_LEN {
+            error!("UBX-NAV-COV payload too short");
+            return false;
+        }
+
+        self.new_epoch(u32_at(payload, 0));
+
+        let pos_cov_valid = payload[5] != 0;
+        if !pos_cov_valid {
+            trace!("UBX-NAV-COV position covariance not valid");
+            return false;
+        }
+
+        let nn = f64::from(f32_at(payload, 16));
+        let ne = f64::from(f32_at(payload, 20));
+        let ee = f64::from(f32_at(payload, 28));
+        let dd = f64::from(f32_at(payload, 36));
+
+        let (major, minor, orientation) = error_ellipse(nn, ne, ee);
+
+        self.cache.gst.time = self.cache.fix.time;
+        self.cache.gst.major_deviation = Some(major);
+        self.cache.gst.minor_deviation = Some(minor);
+        self.cache.gst.major_orientation = Some(orientation);
+        self.cache.gst.lat_err_deviation = Some(Length::new::<meter>(nn.max(0.0).sqrt()));
+        self.cache.gst.lon_err_deviation = Some(Length::new::<meter>(ee.max(0.0).sqrt()));
+        self.cache.gst.alt_err_deviation = Some(Length::new::<meter>(dd.max(0.0).sqrt()));
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::time::Duration as StdDuration;
+
+    use mio::{Events, Poll};
+
+    use super::*;
+    use crate::tty::tests::open_pty;
+
+    const TOKEN: Token = Token(0);
+
+    fn nav_pvt(itow: u32) -> UbxFrame {
+        let mut payload = vec![0u8; NAV_PVT_LEN];
+        payload[0..4].copy_from_slice(&itow.to_le_bytes());
+        payload[4..6].copy_from_slice(&2024u16.to_le_bytes());
+        payload[6..11].copy_from_slice(&[3, 14, 15, 9, 26]);
+        payload[11] = 0x07;
+        payload[20] = 3;
+        payload[21] = 0x01;
+        payload[24..28].copy_from_slice(&23_522_100i32.to_le_bytes());
+        payload[28..32].copy_from_slice(&488_566_100i32.to_le_bytes());
+        payload[32..36].copy_from_slice(&80_000i32.to_le_bytes());
+        payload[40..44].copy_from_slice(&1_500u32.to_le_bytes());
+        payload[60..64].copy_from_slice(&13_890i32.to_le_bytes());
+        payload[64..68].copy_from_slice(&9_000_000i32.to_le_bytes());
+
+        UbxFrame {
+            class: CLASS_NAV,
+            id: ID_NAV_PVT,
+            payload,
+        }
+    }
+
+    fn nav_cov(itow: u32, nn: f32, ne: f32, ee: f32) -> UbxFrame {
+        let mut payload = vec![0u8; NAV_COV_LEN];
+        payload[0..4].copy_from_slice(&itow.to_le_bytes());
+        payload[5] = 1;
+        payload[16..20].copy_from_slice(&nn.to_le_bytes());
+        payload[20..24].copy_from_slice(&ne.to_le_bytes());
+        payload[28..32].copy_from_slice(&ee.to_le_bytes());
+        payload[36..40].copy_from_slice(&4.0f32.to_le_bytes());
+
+        UbxFrame {
+            class: CLASS_NAV,
+            id: ID_NAV_COV,
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_ubx_frame_parse() {
+        let frame = nav_cov(1000, 1.0, 0.0, 1.0);
+        let mut corrupted = frame.to_bytes();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+
+        let mut buffer = [&[0x00, 0xb5, 0x12][..], &corrupted, &frame.to_bytes()].concat();
+        assert_eq!(UbxFrame::parse(&mut buffer), Some(frame.clone()));
+        assert!(buffer.is_empty());
+
+        // Incomplete frame is kept in the buffer.
+        let bytes = frame.to_bytes();
+        let mut buffer = bytes[..10].to_vec();
+        assert_eq!(UbxFrame::parse(&mut buffer), None);
+        buffer.extend_from_slice(&bytes[10..]);
+        assert_eq!(UbxFrame::parse(&mut buffer), Some(frame));
+    }
+
+    #[test]
+    fn test_error_ellipse() {
+        // Error is larger along the east axis.
+        let (major, minor, orientation) = error_ellipse(1.0, 0.0, 9.0);
+        assert!((major.get::<meter>() - 3.0).abs() < 1e-9);
+        assert!((minor.get::<meter>() - 1.0).abs() < 1e-9);
+        assert!((orientation.get::<degree>() - 90.0).abs() < 1e-9);
+
+        // Error is correlated along the north-east diagonal.
+        let (major, minor, orientation) = error_ellipse(5.0, 4.0, 5.0);
+        assert!((major.get::<meter>() - 3.0).abs() < 1e-9);
+        assert!((minor.get::<meter>() - 1.0).abs() < 1e-9);
+        assert!((orientation.get::<degree>() - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ubx_nav_pvt_cov() {
+        let (mut master, path) = open_pty();
+        let mut poll = Poll::new().unwrap();
+        let mut events = Events::with_capacity(8);
+
+        let mut ubx = Ubx::new(
+            path,
+            115200,
+            poll.registry().try_clone().unwrap(),
+            TOKEN,
+            Instant::now(),
+        )
+        .unwrap();
+        ubx.poll(Instant::now()).unwrap();
+
+        let bytes = [
+            nav_pvt(1000).to_bytes(),
+            nav_cov(1000, 5.0, 4.0, 5.0).to_bytes(),
+        ]
+        .concat();
+        master.write_all(&bytes).unwrap();
+
+        let mut position = None;
+        for _ in 0..20 {
+            poll.poll(&mut events, Some(StdDuration::from_millis(50)))
+                .unwrap();
+            for event in events.iter() {
+                if ubx.ready(event, Instant::now()) && ubx.fetch_position().confidence.is_some() {
+                    position = Some(ubx.fetch_position());
+                }
+            }
+            if position.is_some() {
+                break;
+            }
+        }
+
+        let position = position.expect("no position");
+        let (time, lat, lon) = position.fix.time_and_position_values().unwrap();
+
+        assert_eq!(position.fix.mode, FixMode::Fix3d);
+        assert_eq!(time.to_rfc3339(), "2024-03-14T15:09:26+00:00");
+        assert!((lat.get::<degree>() - 48.85661).abs() < 1e-7);
+        assert!((lon.get::<degree>() - 2.35221).abs() < 1e-7);
+        assert!((position.fix.altitude.unwrap().get::<meter>() - 80.0).abs() < 1e-9);
+        assert!((position.fix.track.unwrap().get::<degree>() - 90.0).abs() < 1e-9);
+
+        let confidence = position.confidence.unwrap();
+        assert!((confidence.semi_major_axis.get::<meter>() - 3.0).abs() < 1e-6);
+        assert!((confidence.semi_minor_axis.get::<meter>() - 1.0).abs() < 1e-6);
+        assert!((confidence.semi_major_orientation.get::<degree>() - 45.0).abs() < 1e-6);
+    }
+}
@@ -12,4 +12,5 @@ pub use zmq::{
 };
 
 pub use proto::denm;
+pub use proto::stats;
 pub use proto::message::{event::EventType as IpcEventType, Event as IpcEvent};
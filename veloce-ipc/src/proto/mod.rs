@@ -4,6 +4,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// Contains all the events emitted and received with the IPC.
 pub mod message;
 pub mod denm;
+pub mod stats;
 
 impl message::Event {
     pub fn new(r#type: message::event::EventType) -> Self {
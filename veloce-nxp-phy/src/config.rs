@@ -54,6 +54,11 @@ impl Config {
         }
     }
 
+    /// Returns the radio used for Tx and Rx.
+    pub fn radio(&self) -> Radio {
+        self.radio
+    }
+
     /// Set the filter mac address.
     pub fn set_filter_addr(&mut self, addr: EthernetAddress) {
         self.filter_addr = addr;
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use veloce::conformance::runner::{Config, Runner, Scenario};
+
+use clap::Parser;
+
+#[derive(Parser, Default, Debug)]
+struct Arguments {
+    /// Scenario files to run.
+    #[arg(required = true)]
+    scenarios: Vec<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Arguments::parse();
+    let runner = Runner::new(Config::default());
+
+    let mut failed = 0;
+    for path in args.scenarios.iter() {
+        let scenario = match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Scenario::from_toml(&content).map_err(|e| e.to_string()))
+        {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                println!("{}: cannot load scenario: {}", path.display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match runner.run(&scenario) {
+            Ok(()) => println!("{}: pass", scenario.name),
+            Err(failure) => {
+                println!("{}: FAIL, {}", scenario.name, failure);
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} passed, {} failed",
+        args.scenarios.len() - failed,
+        failed
+    );
+
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::io;
 use std::net::UdpSocket;
 use std::rc::Rc;
@@ -72,18 +74,28 @@ fn main() {
     // Create denm socket
     let denm_socket = socket::denm::Socket::new(vec![], vec![]);
 
+    // Create cam socket, received CAMs are forwarded to the UpperTester.
+    let mut cam_socket = socket::cam::Socket::new();
+    let cam_rx = Rc::new(RefCell::new(VecDeque::new()));
+    let cam_rx_cb = cam_rx.clone();
+    cam_socket.register_recv_callback(move |uper, _| {
+        cam_rx_cb.borrow_mut().push_back(uper.to_vec());
+    });
+
     // Add them to a SocketSet
     let mut sockets = SocketSet::new(vec![]);
     let gn_handle: veloce::iface::SocketHandle = sockets.add(gn_socket);
     let denm_handle: veloce::iface::SocketHandle = sockets.add(denm_socket);
+    let cam_handle: veloce::iface::SocketHandle = sockets.add(cam_socket);
 
     // Configure UpperTester
-    let mut ut = UpperTester::new(router.address(), gn_handle, denm_handle);
+    let mut ut = UpperTester::new(router.address(), gn_handle, denm_handle, cam_handle);
 
     loop {
-        // Update timestamp.
-        let timestamp = Instant::now();
+        // Update timestamp, shifted by the ITS time set by the UpperTester.
+        let timestamp = ut.timestamp(Instant::now());
         router.set_timestamp(timestamp);
+        ut.poll_position(timestamp, &mut router);
 
         match udp_socket.recv_from(&mut udp_buffer) {
             Ok((size, source)) => {
@@ -124,6 +136,19 @@ fn main() {
             debug!("Sent {} bytes to {}", data.len(), dst);
         }
 
+        while let Some(cam) = cam_rx.borrow_mut().pop_front() {
+            if let Some((dst, data)) = ut.ut_cam_event(&cam) {
+                udp_socket.send_to(&data, dst).unwrap();
+                debug!("Sent {} bytes to {}", data.len(), dst);
+            }
+        }
+
+        #[cfg(feature = "proto-security")]
+        while let Some((dst, data)) = ut.ut_sec_event() {
+            udp_socket.send_to(&data, dst).unwrap();
+            debug!("Sent {} bytes to {}", data.len(), dst);
+        }
+
         wait_many(&fds, iface.poll_delay(timestamp, &sockets)).expect("wait error");
     }
 }
@@ -92,6 +92,11 @@ where
         self.len == 0 && self.storage.is_empty()
     }
 
+    /// Returns the number of packets in the buffer.
+    pub fn packet_count(&self) -> usize {
+        self.storage.len()
+    }
+
     /// Returns the maximum number of bytes the buffer can hold.
     pub const fn capacity(&self) -> usize {
         self.capacity
@@ -150,7 +150,8 @@ impl Poti {
 
     fn push_fix_inner(&mut self, mut fix: Fix) -> Result<Fix, Error> {
         // ETSI EN 302 890-2 V2.1.1 requires that the fix must be in 3D mode.
-        if !fix.is_mode_3d() {
+        // Dead reckoned fixes are extrapolated from a 3D one.
+        if !fix.is_mode_3d() && !fix.is_dead_reckoning() {
             return Err(Error::Fix(FixError::Mode));
         }
 
@@ -260,6 +261,11 @@ impl Fix {
         self.mode == Mode::Fix3d
     }
 
+    /// Query whether the fix is dead reckoned, ie: not computed from GNSS signals.
+    pub fn is_dead_reckoning(&self) -> bool {
+        self.mode == Mode::DeadReckoning
+    }
+
     /// Query whether self contains a stationary fix.
     /// "Does not move" is defined as when the speed is below 8cm/s.
     /// See C2C Consortium Vehicle C-ITS station profile, requirement RS_BSP_511.
@@ -349,6 +355,8 @@ pub enum Mode {
     Fix3d,
     /// GNSS signal lost.
     Lost,
+    /// Position extrapolated from vehicle sensors (dead reckoning) during a GNSS outage.
+    DeadReckoning,
 }
 
 /// Position category dimensions.
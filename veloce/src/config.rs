@@ -68,6 +68,43 @@ pub(crate) const DCC_QUEUE_SIZE: usize = 10 * 1000;
 /// Lifetime of a certificate cache entry.
 pub(crate) const SEC_CERT_CACHE_ENTRY_LIFETIME: Duration = Duration::from_secs(20);
 
+#[cfg(feature = "proto-security")]
+/// Duration during which messages encrypted for a previous AT certificate are still decrypted
+/// after an AT certificate change, ie: while remote stations may keep it in their cache.
+pub(crate) const SEC_AT_DECRYPTION_GRACE_PERIOD: Duration = SEC_CERT_CACHE_ENTRY_LIFETIME;
+
+#[cfg(feature = "proto-security")]
+/// Lifetime of a signature cache entry.
+pub(crate) const SEC_SIG_CACHE_ENTRY_LIFETIME: Duration = Duration::from_secs(10);
+
+#[cfg(feature = "proto-security")]
+/// Maximum number of entries in the signature cache.
+pub(crate) const SEC_SIG_CACHE_SIZE: usize = 512;
+
+#[cfg(feature = "proto-security")]
+/// Maximum number of received frames whose signatures are verified in a single batch.
+pub(crate) const SEC_VERIFY_BATCH_SIZE: usize = 32;
+
+#[cfg(feature = "proto-security")]
+/// Lifetime of a misbehavior detection sender entry.
+pub(crate) const SEC_MBD_SENDER_ENTRY_LIFETIME: Duration = Duration::from_secs(60);
+
+#[cfg(feature = "proto-security")]
+/// Maximum number of path points kept per sender by the misbehavior detection.
+pub(crate) const SEC_MBD_HISTORY_LENGTH: usize = 5;
+
+#[cfg(feature = "proto-security")]
+/// Maximum number of misbehavior detections kept as evidence for misbehavior reports.
+pub(crate) const SEC_MBD_DETECTION_QUEUE_SIZE: usize = 32;
+
+#[cfg(feature = "pki")]
+/// Maximum number of misbehavior reports waiting for submission to the Misbehavior Authority.
+pub(crate) const PKI_MBR_QUEUE_SIZE: usize = 32;
+
+#[cfg(feature = "pki")]
+/// Lifetime of a misbehavior report waiting for submission to the Misbehavior Authority.
+pub(crate) const PKI_MBR_LIFETIME: Duration = Duration::from_secs(3600);
+
 #[cfg(not(test))]
 mod cfg {
     /// Location service maximum concurrent requests.
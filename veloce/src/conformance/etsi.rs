@@ -1,40 +1,73 @@
 use std::{collections::HashMap, net::SocketAddr};
 
+#[cfg(feature = "proto-security")]
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+#[cfg(feature = "proto-security")]
+use crate::{
+    security::{
+        service::{decap::DecapResult, SecurityServiceError},
+        signer_policy::{SignerIdentifierPolicy, SignerPolicy},
+        storage::{StorageError, StorageTrait},
+        HashedId8, SecurityService,
+    },
+    wire::uppertester::security::{
+        SignerType, UtSecChangeAtCertificate, UtSecEventInd, UtSecTrigger, VerificationResult,
+    },
+};
+
 use crate::{
-    common::geo_area::{Circle, GeoArea, Shape},
+    common::{
+        geo_area::{Circle, GeoArea, Shape},
+        PotiConfidence, PotiFix, PotiMode, PotiMotion, PotiPosition, PotiPositionConfidence,
+    },
     iface::{Interface, SocketHandle, SocketSet},
     network::{GnCore, Indication, Request, Transport, UpperProtocol},
     rand::Rand,
     socket::{
         self,
+        cam::VehicleState,
         denm::{ActionId, EventHandle, EventParameters},
     },
-    time::{Instant, TAI2004},
-    types::{tenth_of_microdegree, Distance, Pseudonym},
+    time::{Duration, Instant, TAI2004},
+    types::{decidegree, tenth_of_microdegree, Distance, Heading, Pseudonym, Speed},
     wire::{
         uppertester::{
             btp::{UtBtpTriggerA, UtBtpTriggerB},
+            cam::{UtCamEventInd, UtCamTrigger},
             denm::{
                 UtDenmEventInd, UtDenmTermination, UtDenmTrigger, UtDenmTriggerResult,
                 UtDenmUpdate, UtDenmUpdateResult,
             },
+            security::UtSecSetItsTime,
             UtResultPacket,
         },
-        BtpAHeader, BtpARepr, BtpBHeader, BtpBRepr, GnAddress, GnTrafficClass, UtChangePosition,
-        UtGnEventInd, UtGnTriggerGeoAnycast, UtGnTriggerGeoBroadcast, UtGnTriggerGeoUnicast,
-        UtGnTriggerShb, UtGnTriggerTsb, UtInitialize, UtMessageType, UtPacket, UtResult,
+        BtpAHeader, BtpARepr, BtpBHeader, BtpBRepr, GnAddress, GnTrafficClass, StationType,
+        UtChangePosition, UtGnEventInd, UtGnTriggerGeoAnycast, UtGnTriggerGeoBroadcast,
+        UtGnTriggerGeoUnicast, UtGnTriggerShb, UtGnTriggerTsb, UtInitialize, UtMessageType,
+        UtPacket, UtResult,
     },
 };
 
 use log::{debug, error, trace};
-use uom::si::{angle::degree, f64::Angle, length::meter};
+use uom::si::{
+    angle::degree,
+    f64::Angle,
+    length::{centimeter, meter},
+    velocity::{centimeter_per_second, meter_per_second},
+};
 use veloce_asn1::{
     defs::etsi_messages_r2::{denm__pdu__descriptions as denm, etsi__its__cdd as cdd},
-    prelude::rasn,
+    prelude::rasn::{self, types::Enumerated},
 };
 
 pub type Result<T> = core::result::Result<T, ()>;
 
+/// Security verification event, ie: the verification result, the signer certificate
+/// digest if known, and the secured packet.
+#[cfg(feature = "proto-security")]
+type SecurityEvent = (VerificationResult, Option<HashedId8>, Vec<u8>);
+
 pub struct State {
     /// Initially configured Geonetworking address.
     initial_address: GnAddress,
@@ -42,22 +75,105 @@ pub struct State {
     gn_socket_handle: SocketHandle,
     /// DENM socket handle.
     denm_socket_handle: SocketHandle,
+    /// CAM socket handle.
+    cam_socket_handle: SocketHandle,
     /// UT server address.
     ut_server: Option<SocketAddr>,
     /// DENM handles.
     denm_handles: HashMap<EventHandle, EventParameters>,
+    /// Offset between the ITS time and the system time, in microseconds.
+    its_time_offset: i64,
+    /// Security service, while the test configuration requires unsecured messages.
+    #[cfg(feature = "proto-security")]
+    suspended_security: Option<SecurityService>,
+    /// Security verification events to notify to the Uppertester.
+    #[cfg(feature = "proto-security")]
+    sec_events: Rc<RefCell<VecDeque<SecurityEvent>>>,
 }
 
 impl State {
     /// Constructs a new State.
-    pub fn new(addr: GnAddress, gn_handle: SocketHandle, denm_handle: SocketHandle) -> Self {
+    pub fn new(
+        addr: GnAddress,
+        gn_handle: SocketHandle,
+        denm_handle: SocketHandle,
+        cam_handle: SocketHandle,
+    ) -> Self {
         State {
             initial_address: addr,
             gn_socket_handle: gn_handle,
             denm_socket_handle: denm_handle,
+            cam_socket_handle: cam_handle,
             ut_server: None,
             denm_handles: HashMap::new(),
+            its_time_offset: 0,
+            #[cfg(feature = "proto-security")]
+            suspended_security: None,
+            #[cfg(feature = "proto-security")]
+            sec_events: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    /// Return the ITS timestamp at the system time `now`, ie: `now` shifted by
+    /// the time set with UtSecSetItsTime. Should be used as the stack timestamp.
+    pub fn timestamp(&self, now: Instant) -> Instant {
+        Instant::from_micros(now.total_micros() + self.its_time_offset)
+    }
+
+    /// Load the ETSI test certificate set from `storage` into the security service of
+    /// `router`, ie: the root and AA certificates, which replace the local trust chain,
+    /// then the AT certificates from index 0 until no certificate is found. Certificates
+    /// are checked against their issuer at `timestamp`. The private keys matching the AT
+    /// certificates should be available in the security backend.
+    /// Returns the number of loaded AT certificates.
+    #[cfg(feature = "proto-security")]
+    pub fn load_certificates<S: StorageTrait + ?Sized>(
+        &mut self,
+        timestamp: Instant,
+        storage: &S,
+        router: &mut GnCore,
+    ) -> Result<usize> {
+        let Some(sec) = router
+            .security
+            .as_mut()
+            .or(self.suspended_security.as_mut())
+        else {
+            error!("Failed to load certificates: no security service");
+            return Err(());
+        };
+
+        let raw_root = storage.load_root_certificate().map_err(|e| {
+            error!("Failed to load root certificate: {}", e);
+        })?;
+        let raw_aa = storage.load_aa_certificate().map_err(|e| {
+            error!("Failed to load AA certificate: {}", e);
+        })?;
+        let aa_hash = sec
+            .set_own_authorities(&raw_root, &raw_aa, timestamp)
+            .map_err(|e| {
+                error!("Failed to set root and AA certificates: {}", e);
+            })?;
+        debug!("Loaded AA certificate: {}", aa_hash);
+
+        let mut index = 0;
+        loop {
+            let raw = match storage.load_at_certificate(index) {
+                Ok(raw) => raw,
+                Err(StorageError::NotFound) => break,
+                Err(e) => {
+                    error!("Failed to load AT certificate {}: {}", index, e);
+                    return Err(());
+                }
+            };
+
+            let hashed_id8 = sec.add_at_cert(index, &raw, timestamp).map_err(|e| {
+                error!("Failed to add AT certificate {}: {}", index, e);
+            })?;
+            debug!("Loaded AT certificate {}: {}", index, hashed_id8);
+            index += 1;
         }
+
+        Ok(index)
     }
 
     /// Dispatch an Uppertester request.
@@ -183,6 +299,48 @@ impl State {
                 res_len = 2;
                 self.ut_denm_terminate(timestamp, sockets, router, ut_packet.payload())
             }
+            msg_type @ (UtMessageType::UtCamChangeCurvature
+            | UtMessageType::UtCamChangeSpeed
+            | UtMessageType::UtCamSetAccelerationControlStatus
+            | UtMessageType::UtCamSetExteriorLightsStatus
+            | UtMessageType::UtCamChangeHeading
+            | UtMessageType::UtCamSetDriveDirection
+            | UtMessageType::UtCamChangeYawRate
+            | UtMessageType::UtCamSetStationType
+            | UtMessageType::UtCamSetVehicleRole
+            | UtMessageType::UtCamSetEmbarkationStatus
+            | UtMessageType::UtCamSetPtActivation
+            | UtMessageType::UtCamSetDangerousGoods
+            | UtMessageType::UtCamSetLightBarSiren)
+                if self.ut_server.is_some_and(|s| s.ip() == source.ip()) =>
+            {
+                res_packet.set_result_message_type(UtMessageType::UtCamTriggerResult);
+                res_len = 2;
+                self.ut_cam_trigger(msg_type, timestamp, sockets, router, ut_packet.payload())
+            }
+            #[cfg(feature = "proto-security")]
+            UtMessageType::UtSecChangeAtCertificate
+                if self.ut_server.is_some_and(|s| s.ip() == source.ip()) =>
+            {
+                res_packet.set_result_message_type(UtMessageType::UtSecChangeAtCertificateResult);
+                res_len = 2;
+                self.ut_sec_change_at_certificate(router, ut_packet.payload())
+            }
+            #[cfg(feature = "proto-security")]
+            UtMessageType::UtSecTrigger
+                if self.ut_server.is_some_and(|s| s.ip() == source.ip()) =>
+            {
+                res_packet.set_result_message_type(UtMessageType::UtSecTriggerResult);
+                res_len = 2;
+                self.ut_sec_trigger(sockets, router, ut_packet.payload())
+            }
+            UtMessageType::UtSecSetItsTime
+                if self.ut_server.is_some_and(|s| s.ip() == source.ip()) =>
+            {
+                res_packet.set_result_message_type(UtMessageType::UtSecSetItsTimeResult);
+                res_len = 2;
+                self.ut_sec_set_its_time(timestamp, ut_packet.payload())
+            }
             _ => {
                 return None;
             }
@@ -220,9 +378,31 @@ impl State {
         Some((ut_server, res_buf))
     }
 
+    /// Refresh the position of the IUT.
+    /// The IUT has no positioning system during conformance tests, hence a fix
+    /// located at the ego position is pushed periodically, to keep the CAM
+    /// transmission going.
+    pub fn poll_position(&self, timestamp: Instant, router: &mut GnCore) {
+        if self.ut_server.is_none() {
+            return;
+        }
+
+        let fix = router.position();
+        if fix.is_mode_3d()
+            && TAI2004::from_unix_instant(timestamp) - fix.timestamp < Duration::from_secs(1)
+        {
+            return;
+        }
+
+        let fix = Self::simulated_fix(timestamp, router);
+        if let Err(e) = router.set_position(fix, timestamp) {
+            error!("Failed to refresh position: {:?}", e);
+        }
+    }
+
     fn ut_initialize(
         &mut self,
-        _timestamp: Instant,
+        timestamp: Instant,
         sockets: &mut SocketSet<'_>,
         iface: &mut Interface,
         router: &mut GnCore,
@@ -231,8 +411,10 @@ impl State {
     ) -> Result<()> {
         let ut_init = UtInitialize::new(buffer);
 
-        // TODO: set correct certificate if testing with security.
-        // return an error since we don't support security yet.
+        // A zero HashedId8 means the test configuration does not use security.
+        #[cfg(feature = "proto-security")]
+        self.ut_initialize_security(router, ut_init.hashed_id8())?;
+        #[cfg(not(feature = "proto-security"))]
         if ut_init.hashed_id8() != UtInitialize::<&[u8]>::ZERO_HASHEDID8 {
             return Err(());
         }
@@ -242,6 +424,10 @@ impl State {
         denm_socket.reset();
         self.denm_handles.clear();
 
+        // Reset vehicle state sent in CAMs.
+        let cam_socket = sockets.get_mut::<socket::cam::Socket>(self.cam_socket_handle);
+        cam_socket.set_vehicle_state(VehicleState::default());
+
         // Reset buffers
         iface.ls_buffer.clear();
         iface.uc_forwarding_buffer.clear();
@@ -260,6 +446,13 @@ impl State {
         // Set server address
         self.ut_server = Some(source);
 
+        // Stop the vehicle at the ego position.
+        let mut fix = Self::simulated_fix(timestamp, router);
+        fix.motion.speed = Some(Speed::new::<meter_per_second>(0.0));
+        router.set_position(fix, timestamp).map_err(|e| {
+            error!("Failed to set position: {:?}", e);
+        })?;
+
         Ok(())
     }
 
@@ -275,6 +468,27 @@ impl State {
         router.ego_position_vector.longitude += ut_ch_pos.delta_longitude();
         router.ego_position_vector.timestamp = TAI2004::from_unix_instant(timestamp).into();
 
+        // Move the position fix along, if any.
+        let mut fix = router.position();
+        if fix.is_mode_3d() {
+            fix.position.latitude = fix
+                .position
+                .latitude
+                .map(|l| l + ut_ch_pos.delta_latitude());
+            fix.position.longitude = fix
+                .position
+                .longitude
+                .map(|l| l + ut_ch_pos.delta_longitude());
+            fix.position.altitude = fix
+                .position
+                .altitude
+                .map(|a| a + ut_ch_pos.delta_elevation());
+            fix.timestamp = TAI2004::from_unix_instant(timestamp);
+            router.set_position(fix, timestamp).map_err(|e| {
+                error!("Failed to set position: {:?}", e);
+            })?;
+        }
+
         Ok(())
     }
 
@@ -480,6 +694,300 @@ impl State {
         Some((ut_server, res_buf))
     }
 
+    // Notify to the Uppertester a received CAM, serialized as UPER in `buffer`.
+    pub fn ut_cam_event(&self, buffer: &[u8]) -> Option<(SocketAddr, Vec<u8>)> {
+        let ut_server = self.ut_server?;
+
+        let mut res_buf = vec![0u8; 3 + buffer.len()];
+        let mut res_pkt = UtPacket::new(&mut res_buf);
+        res_pkt.set_message_type(UtMessageType::UtCamEventInd);
+
+        let mut ind_pkt = UtCamEventInd::new(res_pkt.payload_mut());
+        ind_pkt.set_payload_len(buffer.len());
+        ind_pkt.payload_mut().copy_from_slice(buffer);
+
+        Some((ut_server, res_buf))
+    }
+
+    fn ut_cam_trigger(
+        &self,
+        msg_type: UtMessageType,
+        timestamp: Instant,
+        sockets: &mut SocketSet<'_>,
+        router: &mut GnCore,
+        buffer: &[u8],
+    ) -> Result<()> {
+        let trigger = UtCamTrigger::new(buffer);
+        let socket = sockets.get_mut::<socket::cam::Socket>(self.cam_socket_handle);
+        let mut state = socket.vehicle_state().clone();
+
+        match msg_type {
+            UtMessageType::UtCamChangeCurvature => {
+                state.curvature = Some(cdd::CurvatureValue(trigger.curvature()));
+            }
+            UtMessageType::UtCamChangeSpeed => {
+                let delta = Speed::new::<centimeter_per_second>(trigger.speed_variation().into());
+                return Self::change_motion(timestamp, router, |motion| {
+                    motion.speed = motion.speed.map(|speed| {
+                        let value = (speed + delta).get::<meter_per_second>().max(0.0);
+                        Speed::new::<meter_per_second>(value)
+                    });
+                });
+            }
+            UtMessageType::UtCamSetAccelerationControlStatus => {
+                state.acceleration_control = Some(cdd::AccelerationControl(raw_to_bit_string(
+                    trigger.acceleration_control(),
+                )));
+            }
+            UtMessageType::UtCamSetExteriorLightsStatus => {
+                state.exterior_lights =
+                    cdd::ExteriorLights(raw_to_bit_string(trigger.exterior_lights()));
+            }
+            UtMessageType::UtCamChangeHeading => {
+                let delta = Heading::new::<decidegree>(trigger.heading_variation().into());
+                return Self::change_motion(timestamp, router, |motion| {
+                    motion.heading = motion.heading.map(|heading| {
+                        let value = (heading + delta).get::<degree>().rem_euclid(360.0);
+                        Heading::new::<degree>(value)
+                    });
+                });
+            }
+            UtMessageType::UtCamSetDriveDirection => {
+                state.drive_direction = raw_to_enum_variant(trigger.drive_direction())?;
+            }
+            UtMessageType::UtCamChangeYawRate => {
+                state.yaw_rate = Some(cdd::YawRateValue(trigger.yaw_rate()));
+            }
+            UtMessageType::UtCamSetStationType => {
+                router.set_station_type(StationType::from(u16::from(trigger.station_type())));
+            }
+            UtMessageType::UtCamSetVehicleRole => {
+                state.vehicle_role = raw_to_enum_variant(trigger.vehicle_role())?;
+            }
+            UtMessageType::UtCamSetEmbarkationStatus => {
+                state.embarkation_status = trigger.embarkation_status();
+            }
+            UtMessageType::UtCamSetPtActivation => {
+                state.pt_activation = Some(cdd::PtActivation::new(
+                    cdd::PtActivationType(trigger.pt_activation_type()),
+                    cdd::PtActivationData(trigger.pt_activation_data().to_vec().into()),
+                ));
+            }
+            UtMessageType::UtCamSetDangerousGoods => {
+                state.dangerous_goods = Some(raw_to_enum_variant(trigger.dangerous_goods())?);
+            }
+            UtMessageType::UtCamSetLightBarSiren => {
+                state.light_bar_siren =
+                    cdd::LightBarSirenInUse(raw_to_bit_string(trigger.light_bar_siren()));
+            }
+            _ => return Err(()),
+        }
+
+        socket.set_vehicle_state(state);
+
+        Ok(())
+    }
+
+    /// Apply `f` on the motion of the current position fix of the IUT.
+    fn change_motion(
+        timestamp: Instant,
+        router: &mut GnCore,
+        f: impl FnOnce(&mut PotiMotion),
+    ) -> Result<()> {
+        let mut fix = router.position();
+        if !fix.is_mode_3d() {
+            error!("Failed to change motion: no position fix");
+            return Err(());
+        }
+
+        f(&mut fix.motion);
+        fix.timestamp = TAI2004::from_unix_instant(timestamp);
+
+        router
+            .set_position(fix, timestamp)
+            .map(|_| ())
+            .map_err(|e| {
+                error!("Failed to set position: {:?}", e);
+            })
+    }
+
+    /// Build a position fix at the ego position of the IUT, keeping the
+    /// motion of the current fix, if any.
+    fn simulated_fix(timestamp: Instant, router: &GnCore) -> PotiFix {
+        let current = router.position();
+        let ego = router.geo_position();
+
+        let motion = if current.is_mode_3d() {
+            current.motion
+        } else {
+            PotiMotion {
+                speed: Some(Speed::new::<meter_per_second>(0.0)),
+                vertical_speed: None,
+                heading: Some(Heading::new::<degree>(0.0)),
+            }
+        };
+
+        PotiFix {
+            mode: PotiMode::Fix3d,
+            timestamp: TAI2004::from_unix_instant(timestamp),
+            position: PotiPosition {
+                latitude: Some(ego.latitude),
+                longitude: Some(ego.longitude),
+                altitude: current
+                    .position
+                    .altitude
+                    .or(Some(Distance::new::<meter>(0.0))),
+            },
+            motion,
+            confidence: PotiConfidence {
+                position: PotiPositionConfidence {
+                    semi_major: Some(Distance::new::<centimeter>(100.0)),
+                    semi_minor: Some(Distance::new::<centimeter>(100.0)),
+                    semi_major_orientation: Some(Heading::new::<degree>(0.0)),
+                },
+                altitude: Some(Distance::new::<meter>(1.0)),
+                speed: Some(Speed::new::<centimeter_per_second>(1.0)),
+                heading: Some(Heading::new::<decidegree>(1.0)),
+            },
+        }
+    }
+
+    /// Notify to the Uppertester a security verification event, if any.
+    #[cfg(feature = "proto-security")]
+    pub fn ut_sec_event(&self) -> Option<(SocketAddr, Vec<u8>)> {
+        let ut_server = self.ut_server?;
+        let (result, signer, packet) = self.sec_events.borrow_mut().pop_front()?;
+
+        let mut res_buf = vec![0u8; 12 + packet.len()];
+        let mut res_pkt = UtPacket::new(&mut res_buf);
+        res_pkt.set_message_type(UtMessageType::UtSecEventInd);
+
+        let mut ind_pkt = UtSecEventInd::new(res_pkt.payload_mut());
+        ind_pkt.set_result(result);
+        ind_pkt.set_signer(&signer.unwrap_or_default().as_bytes());
+        ind_pkt.set_payload_len(packet.len());
+        ind_pkt.payload_mut().copy_from_slice(&packet);
+
+        Some((ut_server, res_buf))
+    }
+
+    #[cfg(feature = "proto-security")]
+    fn ut_initialize_security(&mut self, router: &mut GnCore, hashed_id8: &[u8]) -> Result<()> {
+        self.sec_events.borrow_mut().clear();
+
+        if hashed_id8 == UtInitialize::<&[u8]>::ZERO_HASHEDID8 {
+            self.set_security_enabled(router, false);
+            return Ok(());
+        }
+
+        self.set_security_enabled(router, true);
+        let Some(sec) = router.security.as_mut() else {
+            error!("Failed to initialize security: no security service");
+            return Err(());
+        };
+
+        let events = self.sec_events.clone();
+        sec.register_verification_callback(move |packet, res| {
+            let signer = res.as_ref().ok().map(|(confirm, _)| confirm.cert_id);
+            events
+                .borrow_mut()
+                .push_back((verification_result(res), signer, packet.to_vec()));
+        });
+        sec.set_signing_enabled(true);
+        sec.set_signer_policy(SignerPolicy::default());
+
+        sec.select_at_cert(HashedId8::from_bytes(hashed_id8), router.now)
+            .map(|_| ())
+            .map_err(|e| {
+                error!("Failed to select AT certificate: {}", e);
+            })
+    }
+
+    /// Enable or disable security. The security service is kept aside while disabled.
+    #[cfg(feature = "proto-security")]
+    fn set_security_enabled(&mut self, router: &mut GnCore, enabled: bool) {
+        if enabled {
+            if let Some(sec) = self.suspended_security.take() {
+                router.security = Some(sec);
+            }
+        } else if let Some(sec) = router.security.take() {
+            self.suspended_security = Some(sec);
+        }
+    }
+
+    #[cfg(feature = "proto-security")]
+    fn ut_sec_change_at_certificate(&mut self, router: &mut GnCore, buffer: &[u8]) -> Result<()> {
+        let ut_change = UtSecChangeAtCertificate::new(buffer);
+        let Some(sec) = router.security.as_mut() else {
+            error!("Failed to change AT certificate: security is disabled");
+            return Err(());
+        };
+
+        sec.select_at_cert(HashedId8::from_bytes(ut_change.hashed_id8()), router.now)
+            .map(|index| debug!("Selected AT certificate {}", index))
+            .map_err(|e| {
+                error!("Failed to select AT certificate: {}", e);
+            })
+    }
+
+    #[cfg(feature = "proto-security")]
+    fn ut_sec_trigger(
+        &mut self,
+        sockets: &mut SocketSet<'_>,
+        router: &mut GnCore,
+        buffer: &[u8],
+    ) -> Result<()> {
+        let ut_trigger = UtSecTrigger::new(buffer);
+
+        let policy = match ut_trigger.signer_type() {
+            SignerType::Unsecured => None,
+            SignerType::Digest => Some(SignerIdentifierPolicy::Digest),
+            SignerType::Certificate => Some(SignerIdentifierPolicy::Certificate),
+            SignerType::Unknown(t) => {
+                error!("Unknown signer type: {}", t);
+                return Err(());
+            }
+        };
+
+        // Signer type applies to the triggered message and the following ones. Received
+        // messages are still verified while the messages are sent unsecured.
+        if policy.is_some() {
+            self.set_security_enabled(router, true);
+        }
+        match (router.security.as_mut(), policy) {
+            (Some(sec), Some(policy)) => {
+                sec.set_signing_enabled(true);
+                sec.set_signer_policy(SignerPolicy::new(policy));
+            }
+            (Some(sec), None) => sec.set_signing_enabled(false),
+            (None, Some(_)) => {
+                error!("Failed to trigger signed message: no security service");
+                return Err(());
+            }
+            (None, None) => {}
+        }
+
+        let socket = sockets.get_mut::<socket::geonet::Socket>(self.gn_socket_handle);
+        let req_meta = Request {
+            transport: Transport::SingleHopBroadcast,
+            ..Default::default()
+        };
+        socket
+            .send_slice(&ut_trigger.payload()[..ut_trigger.payload_len()], req_meta)
+            .map_err(|e| {
+                error!("Failed to send SHB packet: {}", e);
+            })
+    }
+
+    fn ut_sec_set_its_time(&mut self, timestamp: Instant, buffer: &[u8]) -> Result<()> {
+        let ut_time = UtSecSetItsTime::new(buffer);
+        let its_time = ut_time.its_time().as_unix_instant();
+        self.its_time_offset += its_time.total_micros() - timestamp.total_micros();
+        debug!("ITS time set to {}", its_time);
+
+        Ok(())
+    }
+
     fn ut_denm_trigger(
         &mut self,
         _timestamp: Instant,
@@ -802,6 +1310,53 @@ impl State {
     }
 }
 
+/// Convert a decapsulation result to the verification result reported to the Uppertester.
+#[cfg(feature = "proto-security")]
+fn verification_result(res: &DecapResult) -> VerificationResult {
+    let Err(e) = res else {
+        return VerificationResult::Success;
+    };
+
+    match e {
+        SecurityServiceError::InvalidContent(_) => VerificationResult::InvalidContent,
+        SecurityServiceError::FalseSignature => VerificationResult::FalseSignature,
+        SecurityServiceError::InvalidCertificate(_) => VerificationResult::InvalidCertificate,
+        SecurityServiceError::RevokedCertificate => VerificationResult::RevokedCertificate,
+        SecurityServiceError::InsufficientPermissions => {
+            VerificationResult::InsufficientPermissions
+        }
+        SecurityServiceError::InconsistentChain => VerificationResult::InconsistentChain,
+        SecurityServiceError::InvalidTimestamp => VerificationResult::InvalidTimestamp,
+        SecurityServiceError::OffValidityPeriod => VerificationResult::OffValidityPeriod,
+        SecurityServiceError::DuplicateMessage => VerificationResult::DuplicateMessage,
+        SecurityServiceError::InvalidMobilityData => VerificationResult::InvalidMobilityData,
+        SecurityServiceError::UnsignedMessage => VerificationResult::UnsignedMessage,
+        SecurityServiceError::SignerCertificateNotFound => {
+            VerificationResult::SignerCertificateNotFound
+        }
+        SecurityServiceError::SignerCertificateFalseSignature => {
+            VerificationResult::SignerCertificateFalseSignature
+        }
+        SecurityServiceError::DecryptionError => VerificationResult::DecryptionError,
+        SecurityServiceError::BlacklistedSigner => VerificationResult::BlacklistedSigner,
+        _ => VerificationResult::Other,
+    }
+}
+
+fn raw_to_enum_variant<E: Enumerated>(raw: u8) -> Result<E> {
+    E::from_discriminant(raw.into()).ok_or(())
+}
+
+/// Convert a `raw` octet to a bit string, the first bit being the most
+/// significant bit of the octet.
+fn raw_to_bit_string<const N: usize>(raw: u8) -> rasn::types::FixedBitString<N> {
+    let mut bits = rasn::types::FixedBitString::<N>::default();
+    for i in 0..N.min(8) {
+        bits.set(i, raw & (0x80 >> i) != 0);
+    }
+    bits
+}
+
 fn raw_relevance_distance_to_enum_variant(rd: u8) -> Result<cdd::StandardLength3b> {
     match rd {
         0 => Ok(cdd::StandardLength3b::lessThan50m),
@@ -1112,3 +1667,418 @@ fn raw_cc_and_scc_to_enum_variant(cc: u8, scc: u8) -> cdd::CauseCodeChoice {
         255 => cdd::CauseCodeChoice::reserved255(cdd::SubCauseCodeType(scc)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{phy::Medium, storage::PacketBuffer, tests::setup};
+
+    #[cfg(feature = "proto-security")]
+    use crate::{
+        security::{
+            permission::Permission,
+            ssp::cam::CamSsp,
+            tests::{get_test_storage_path, setup_security_service, setup_storage_and_crypto},
+        },
+        types::{Latitude, Longitude},
+        wire::EthernetFrame,
+    };
+
+    const UT_SERVER: &str = "192.0.2.1:12345";
+
+    /// Create the sockets used by the Uppertester and return the Uppertester state.
+    fn setup_state(router: &GnCore, sockets: &mut SocketSet<'_>) -> State {
+        let gn_rx_buffer =
+            PacketBuffer::new(vec![socket::geonet::RxPacketMetadata::EMPTY], vec![0; 4096]);
+        let gn_tx_buffer =
+            PacketBuffer::new(vec![socket::geonet::TxPacketMetadata::EMPTY], vec![0; 4096]);
+        let gn_handle = sockets.add(socket::geonet::Socket::new(gn_rx_buffer, gn_tx_buffer));
+        let denm_handle = sockets.add(socket::denm::Socket::new(vec![], vec![]));
+        let cam_handle = sockets.add(socket::cam::Socket::new());
+
+        State::new(router.address(), gn_handle, denm_handle, cam_handle)
+    }
+
+    #[test]
+    fn test_ut_sec_set_its_time() {
+        let (mut router, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_000);
+
+        let its_time = TAI2004::from_millis(650_000_000_000i64);
+        let mut set_time = vec![0x94];
+        set_time.extend_from_slice(&its_time.total_millis().to_be_bytes());
+
+        // Request is ignored until the Uppertester is initialized.
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &set_time,
+            source,
+        );
+        assert!(res.is_none());
+        assert_eq!(state.timestamp(now), now);
+
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &[0x00; 9],
+            source,
+        );
+        assert_eq!(res, Some(vec![0x01, 0x01]));
+
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &set_time,
+            source,
+        );
+        assert_eq!(res, Some(vec![0x95, 0x01]));
+        assert_eq!(state.timestamp(now), its_time.as_unix_instant());
+
+        // Offset is kept as the system time elapses.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            state.timestamp(later),
+            its_time.as_unix_instant() + Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_ut_cam_trigger() {
+        let (mut router, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_000);
+        let cam_handle = state.cam_socket_handle;
+
+        let mut dispatch = |router: &mut GnCore, sockets: &mut SocketSet<'_>, req: &[u8]| {
+            state.ut_dispatcher(now, &mut iface, router, sockets, req, source)
+        };
+
+        // Triggers are ignored until the Uppertester is initialized.
+        assert!(dispatch(&mut router, &mut sockets, &[0x30, 0x01, 0xf4]).is_none());
+        assert_eq!(
+            dispatch(&mut router, &mut sockets, &[0x00; 9]),
+            Some(vec![0x01, 0x01])
+        );
+
+        let requests: [&[u8]; 11] = [
+            &[0x30, 0xfe, 0x0c],
+            &[0x32, 0xa0],
+            &[0x33, 0x81],
+            &[0x35, 0x01],
+            &[0x36, 0x01, 0xf4],
+            &[0x39, 0x06],
+            &[0x3a, 0x01],
+            &[0x3b, 0x01],
+            &[0x3c, 0x02, 0x02, 0xca, 0xfe],
+            &[0x3d, 0x02],
+            &[0x3f, 0x40],
+        ];
+        for req in requests {
+            assert_eq!(
+                dispatch(&mut router, &mut sockets, req),
+                Some(vec![0x21, 0x01])
+            );
+        }
+
+        let vehicle = sockets
+            .get::<socket::cam::Socket>(cam_handle)
+            .vehicle_state()
+            .clone();
+        assert_eq!(vehicle.curvature, Some(cdd::CurvatureValue(-500)));
+        assert_eq!(
+            vehicle.acceleration_control,
+            Some(cdd::AccelerationControl(raw_to_bit_string(0xa0)))
+        );
+        assert!(vehicle.exterior_lights.0[0]);
+        assert!(vehicle.exterior_lights.0[7]);
+        assert!(!vehicle.exterior_lights.0[1]);
+        assert_eq!(vehicle.drive_direction, cdd::DriveDirection::backward);
+        assert_eq!(vehicle.yaw_rate, Some(cdd::YawRateValue(500)));
+        assert_eq!(router.station_type(), StationType::Bus);
+        assert_eq!(vehicle.vehicle_role, cdd::VehicleRole::publicTransport);
+        assert!(vehicle.embarkation_status);
+        assert_eq!(
+            vehicle.pt_activation,
+            Some(cdd::PtActivation::new(
+                cdd::PtActivationType(2),
+                cdd::PtActivationData(vec![0xca, 0xfe].into()),
+            ))
+        );
+        assert_eq!(
+            vehicle.dangerous_goods,
+            Some(cdd::DangerousGoodsBasic::explosives3)
+        );
+        assert!(vehicle.light_bar_siren.0[1]);
+        assert!(!vehicle.light_bar_siren.0[0]);
+
+        // Out of range values are rejected, leaving the vehicle state untouched.
+        for req in [[0x35, 0xff], [0x3a, 0xff], [0x3d, 0xff]] {
+            assert_eq!(
+                dispatch(&mut router, &mut sockets, &req),
+                Some(vec![0x21, 0x00])
+            );
+        }
+        assert_eq!(
+            sockets
+                .get::<socket::cam::Socket>(cam_handle)
+                .vehicle_state(),
+            &vehicle
+        );
+
+        // Speed and heading are changed on the position fix, speed not going below zero.
+        let heading = router.position().motion.heading.unwrap();
+        assert_eq!(
+            dispatch(&mut router, &mut sockets, &[0x31, 0x01, 0xf4]),
+            Some(vec![0x21, 0x01])
+        );
+        assert_eq!(
+            dispatch(&mut router, &mut sockets, &[0x34, 0x03, 0x84]),
+            Some(vec![0x21, 0x01])
+        );
+        let motion = router.position().motion;
+        assert!((motion.speed.unwrap().get::<meter_per_second>() - 5.0).abs() < 1e-6);
+        let expected = (heading.get::<degree>() + 90.0).rem_euclid(360.0);
+        assert!((motion.heading.unwrap().get::<degree>() - expected).abs() < 1e-6);
+
+        assert_eq!(
+            dispatch(&mut router, &mut sockets, &[0x31, 0xec, 0x78]),
+            Some(vec![0x21, 0x01])
+        );
+        let speed = router.position().motion.speed.unwrap();
+        assert_eq!(speed.get::<meter_per_second>(), 0.0);
+
+        // Vehicle state is reset by a new initialization.
+        dispatch(&mut router, &mut sockets, &[0x00; 9]);
+        assert_eq!(
+            sockets
+                .get::<socket::cam::Socket>(cam_handle)
+                .vehicle_state(),
+            &VehicleState::default()
+        );
+    }
+
+    #[test]
+    fn test_ut_cam_event() {
+        let (mut router, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_000);
+        let cam = [0x02, 0x02, 0x00, 0x00, 0x00, 0x01];
+
+        assert!(state.ut_cam_event(&cam).is_none());
+
+        state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &[0x00; 9],
+            source,
+        );
+
+        let (dst, ind) = state.ut_cam_event(&cam).unwrap();
+        assert_eq!(dst, source);
+        assert_eq!(ind[0], 0x23);
+
+        let ind_pkt = UtCamEventInd::new(&ind[1..]);
+        assert_eq!(ind_pkt.payload_len(), cam.len());
+        assert_eq!(ind_pkt.payload(), &cam);
+    }
+
+    #[cfg(feature = "proto-security")]
+    #[test]
+    fn test_ut_sec_change_at_certificate() {
+        let (mut router, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+        router.security = Some(setup_security_service());
+        let at_hash = router.security.as_ref().unwrap().at_hashed_id8().unwrap();
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_716_674_400);
+
+        let mut init = vec![0x00];
+        init.extend_from_slice(&at_hash.as_bytes());
+        let res = state.ut_dispatcher(now, &mut iface, &mut router, &mut sockets, &init, source);
+        assert_eq!(res, Some(vec![0x01, 0x01]));
+
+        let mut change = vec![0x90];
+        change.extend_from_slice(&at_hash.as_bytes());
+        let res = state.ut_dispatcher(now, &mut iface, &mut router, &mut sockets, &change, source);
+        assert_eq!(res, Some(vec![0x91, 0x01]));
+
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &[0x90, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            source,
+        );
+        assert_eq!(res, Some(vec![0x91, 0x00]));
+
+        // AT certificate cannot be changed when the test configuration is unsecured.
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &[0x00; 9],
+            source,
+        );
+        assert_eq!(res, Some(vec![0x01, 0x01]));
+        assert!(router.security.is_none());
+
+        let res = state.ut_dispatcher(now, &mut iface, &mut router, &mut sockets, &change, source);
+        assert_eq!(res, Some(vec![0x91, 0x00]));
+    }
+
+    #[cfg(feature = "proto-security")]
+    #[test]
+    fn test_ut_sec_trigger() {
+        let (mut router, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+        router.security = Some(setup_security_service());
+        let at_hash = router.security.as_ref().unwrap().at_hashed_id8().unwrap();
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_716_674_400);
+        router.set_timestamp(now);
+
+        let mut init = vec![0x00];
+        init.extend_from_slice(&at_hash.as_bytes());
+        let res = state.ut_dispatcher(now, &mut iface, &mut router, &mut sockets, &init, source);
+        assert_eq!(res, Some(vec![0x01, 0x01]));
+
+        let payload = [0xca, 0xfe, 0xde, 0xca];
+        let trigger = |signer: u8| {
+            let mut trigger = vec![0x92, signer];
+            trigger.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+            trigger.extend_from_slice(&payload);
+            trigger
+        };
+
+        for (signer, next_header) in [(0x01, 0x02), (0x02, 0x02), (0x00, 0x01)] {
+            device.queue.clear();
+
+            let res = state.ut_dispatcher(
+                now,
+                &mut iface,
+                &mut router,
+                &mut sockets,
+                &trigger(signer),
+                source,
+            );
+            assert_eq!(res, Some(vec![0x93, 0x01]));
+            iface.poll_egress(&mut router, &mut device, &mut sockets);
+
+            // Basic header next header is a secured packet when signed, a common header otherwise.
+            let shb = device
+                .queue
+                .iter()
+                .map(|frame| EthernetFrame::new_unchecked(frame.as_slice()).payload())
+                .find(|gn| gn.windows(payload.len()).any(|w| w == payload))
+                .expect("SHB packet should be sent");
+            assert_eq!(shb[0] & 0x0f, next_header);
+        }
+
+        // Security service is kept to verify the received messages.
+        let sec = router.security.as_ref().unwrap();
+        assert!(!sec.signing_enabled());
+
+        let res = state.ut_dispatcher(
+            now,
+            &mut iface,
+            &mut router,
+            &mut sockets,
+            &trigger(0x7f),
+            source,
+        );
+        assert_eq!(res, Some(vec![0x93, 0x00]));
+    }
+
+    #[cfg(feature = "proto-security")]
+    #[test]
+    fn test_load_certificates() {
+        let (mut router, _iface, mut sockets, _device) = setup(Medium::Ethernet);
+        let mut state = setup_state(&router, &mut sockets);
+        let (storage, _) = setup_storage_and_crypto(get_test_storage_path());
+        let now = Instant::from_secs(1_716_674_400);
+
+        assert!(state
+            .load_certificates(now, &*storage, &mut router)
+            .is_err());
+
+        router.security = Some(setup_security_service());
+        let at_hash = router.security.as_ref().unwrap().at_hashed_id8().unwrap();
+        assert_eq!(state.load_certificates(now, &*storage, &mut router), Ok(1));
+
+        // Loaded AT certificate is checked against the loaded AA, then can be selected.
+        let sec = router.security.as_mut().unwrap();
+        assert_eq!(sec.select_at_cert(at_hash, now).unwrap(), 0);
+
+        // Certificates are checked at the given time.
+        let expired = Instant::from_secs(4_102_444_800);
+        assert!(state
+            .load_certificates(expired, &*storage, &mut router)
+            .is_err());
+    }
+
+    #[cfg(feature = "proto-security")]
+    #[test]
+    fn test_ut_sec_event() {
+        let (mut router, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+        router.security = Some(setup_security_service());
+        let at_hash = router.security.as_ref().unwrap().at_hashed_id8().unwrap();
+        let mut state = setup_state(&router, &mut sockets);
+        let source = UT_SERVER.parse().unwrap();
+        let now = Instant::from_secs(1_716_674_400);
+
+        let mut init = vec![0x00];
+        init.extend_from_slice(&at_hash.as_bytes());
+        state.ut_dispatcher(now, &mut iface, &mut router, &mut sockets, &init, source);
+        assert!(state.ut_sec_event().is_none());
+
+        let position = PotiPosition {
+            latitude: Some(Latitude::new::<degree>(48.2764384)),
+            longitude: Some(Longitude::new::<degree>(-3.5519532)),
+            altitude: None,
+        };
+        let permissions = Permission::CAM(CamSsp::new_v1().into());
+        let sec = router.security.as_mut().unwrap();
+        let secured = sec
+            .encap_packet(vec![0xca, 0xfe], permissions, now, position)
+            .unwrap();
+        sec.decap_packet(&secured, now + Duration::from_millis(50))
+            .unwrap();
+
+        // Indication carries the result, the signer digest and the secured packet.
+        let (dst, ind) = state.ut_sec_event().unwrap();
+        assert_eq!(dst, source);
+        assert_eq!(ind[0], 0x96);
+        assert_eq!(ind[1], u8::from(VerificationResult::Success));
+        assert_eq!(ind[2..10], at_hash.as_bytes());
+        assert_eq!(ind[10..12], (secured.len() as u16).to_be_bytes());
+        assert_eq!(ind[12..], secured);
+
+        let mut tampered = secured.clone();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert!(sec
+            .decap_packet(&tampered, now + Duration::from_millis(100))
+            .is_err());
+
+        let (_, ind) = state.ut_sec_event().unwrap();
+        assert_ne!(ind[1], u8::from(VerificationResult::Success));
+        assert_eq!(ind[2..10], [0; 8]);
+        assert_eq!(ind[12..], tampered);
+        assert!(state.ut_sec_event().is_none());
+    }
+}
@@ -1,2 +1,20 @@
 #[cfg(all(feature = "conformance", feature = "proto-geonet"))]
 pub mod etsi;
+
+// Also built for the unit tests, so the shipped scenarios run with `cargo test`.
+#[cfg(all(
+    feature = "proto-geonet",
+    any(
+        feature = "conformance-runner",
+        all(
+            test,
+            feature = "std",
+            feature = "log",
+            feature = "medium-ethernet",
+            feature = "socket-geonet",
+            feature = "socket-cam",
+            feature = "socket-denm"
+        )
+    )
+))]
+pub mod runner;
@@ -0,0 +1,827 @@
+/*! Headless conformance test runner.
+
+The runner plays [Scenario] scripts against an IUT running the full stack on a
+simulated [Channel], without any external test system. The IUT is driven through
+the [etsi](crate::conformance::etsi) Uppertester, exactly as a TTCN-3 tool would, and
+a tester device attached to the same channel injects Geonetworking frames and
+captures the frames emitted by the IUT.
+
+Each scenario runs on a fresh IUT, with the channel virtual clock advanced by
+[Config::tick] steps. Uppertester indications (received packets, DENM and CAM events)
+are captured and checked by the `expect_indication` and `expect_no_indication` steps.
+*/
+
+mod scenario;
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    rc::Rc,
+};
+
+use log::{debug, trace};
+use uom::si::{angle::degree, f64::Angle, length::meter, velocity::meter_per_second};
+
+use crate::{
+    config,
+    conformance::etsi::State as UpperTester,
+    iface::{Config as IfaceConfig, Interface, SocketHandle, SocketSet},
+    network::{GnAddrConfigMode, GnCore, GnCoreGonfig},
+    phy::{
+        sim::{Channel, Config as SimConfig, SimDevice},
+        Device, Medium, RxToken, TxToken,
+    },
+    socket,
+    storage::PacketBuffer,
+    time::{Duration, Instant, TAI2004},
+    types::{Distance, Heading, Latitude, Longitude, Pseudonym, Speed},
+    wire::{
+        BHNextHeader, BasicHeader, BasicHeaderRepr, BeaconHeader, BeaconHeaderRepr, CommonHeader,
+        CommonHeaderRepr, EthernetAddress, EthernetFrame, EthernetProtocol, EthernetRepr,
+        GeoAnycastHeader, GeoBroadcastHeader, GeoBroadcastRepr, GeonetBeacon, GeonetGeoAnycast,
+        GeonetGeoBroadcast, GeonetLocationServiceReply, GeonetLocationServiceRequest,
+        GeonetPacketType, GeonetSingleHop, GeonetTopoBroadcast, GeonetUnicast, GeonetVariant,
+        GnAddress, GnProtocol, GnTrafficClass, LocationServiceReplyHeader,
+        LocationServiceRequestHeader, LocationServiceRequestRepr, LongPositionVectorRepr,
+        SequenceNumber, ShortPositionVectorRepr, SingleHopHeader, SingleHopHeaderRepr, StationType,
+        TopoBroadcastHeader, TopoBroadcastRepr, UnicastHeader, UnicastRepr, ETHERNET_HEADER_LEN,
+    },
+};
+
+pub use scenario::{Area, AreaShape, Frame, FrameFilter, Iut, PacketType, Scenario, Step, Target};
+
+use scenario::{parse_address, parse_hex};
+
+/// Address of the virtual Uppertester server.
+const UT_SERVER: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 29000));
+
+/// Error returned when a scenario step fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A step parameter is invalid.
+    InvalidParameter(&'static str),
+    /// The IUT did not answer the Uppertester message.
+    NoResult,
+    /// The IUT answered the Uppertester message with an unexpected result code.
+    UnexpectedResult(u8),
+    /// No matching frame was emitted by the IUT in time.
+    MissingFrame,
+    /// A matching frame was emitted by the IUT while none was expected.
+    UnexpectedFrame,
+    /// No matching indication was notified by the IUT in time.
+    MissingIndication,
+    /// A matching indication was notified by the IUT while none was expected.
+    UnexpectedIndication,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidParameter(p) => write!(f, "invalid parameter '{}'", p),
+            Error::NoResult => write!(f, "no result for Uppertester message"),
+            Error::UnexpectedResult(rc) => write!(f, "unexpected result code 0x{:02x}", rc),
+            Error::MissingFrame => write!(f, "expected frame not emitted"),
+            Error::UnexpectedFrame => write!(f, "unexpected frame emitted"),
+            Error::MissingIndication => write!(f, "expected indication not notified"),
+            Error::UnexpectedIndication => write!(f, "unexpected indication notified"),
+        }
+    }
+}
+
+/// A scenario failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// Number of the failed step, starting from 1. 0 means the IUT setup failed.
+    pub step: usize,
+    /// Failure reason.
+    pub error: Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}: {}", self.step, self.error)
+    }
+}
+
+/// Runner configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// Duration of a simulation step.
+    pub tick: Duration,
+    /// Random seed of the IUT.
+    pub random_seed: u64,
+    /// Virtual time at which each scenario starts.
+    pub start_time: Instant,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            tick: Duration::from_millis(1),
+            random_seed: 0xfadecafedeadbeef,
+            // 2024-05-25T22:00:00Z, fixed so every run is reproducible.
+            start_time: Instant::from_secs(1_716_674_400),
+        }
+    }
+}
+
+/// Conformance test runner.
+#[derive(Debug, Default)]
+pub struct Runner {
+    config: Config,
+}
+
+impl Runner {
+    /// Constructs a new [Runner] with the given `config`.
+    pub fn new(config: Config) -> Self {
+        Runner { config }
+    }
+
+    /// Play the `scenario` steps in order, on a fresh IUT.
+    /// Stops at the first failed step.
+    pub fn run(&self, scenario: &Scenario) -> Result<(), Failure> {
+        debug!("Running scenario {}", scenario.name);
+
+        let mut testbed = Testbed::new(&self.config, &scenario.iut)
+            .map_err(|error| Failure { step: 0, error })?;
+
+        for (index, step) in scenario.steps.iter().enumerate() {
+            trace!("Step {}: {:?}", index + 1, step);
+            testbed.play(step).map_err(|error| Failure {
+                step: index + 1,
+                error,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// IUT and tester, attached to the same simulated channel.
+struct Testbed {
+    /// Simulated channel.
+    channel: Channel,
+    /// Duration of a simulation step.
+    tick: Duration,
+    /// IUT device.
+    device: SimDevice,
+    /// Tester device.
+    tester: SimDevice,
+    /// IUT link layer address.
+    address: EthernetAddress,
+    /// IUT interface.
+    iface: Interface,
+    /// IUT router.
+    router: GnCore,
+    /// IUT sockets.
+    sockets: SocketSet<'static>,
+    /// IUT Uppertester.
+    ut: UpperTester,
+    /// Geonetworking socket handle.
+    gn_handle: SocketHandle,
+    /// DENM socket handle.
+    denm_handle: SocketHandle,
+    /// CAMs received by the IUT.
+    cam_rx: Rc<RefCell<VecDeque<Vec<u8>>>>,
+    /// Frames emitted by the IUT, not yet checked.
+    frames: VecDeque<Vec<u8>>,
+    /// Uppertester indications notified by the IUT, not yet checked.
+    indications: VecDeque<Vec<u8>>,
+}
+
+impl Testbed {
+    fn new(config: &Config, iut: &Iut) -> Result<Self, Error> {
+        let address = parse_address(&iut.address, EthernetAddress::BROADCAST)
+            .filter(|a| a.is_unicast())
+            .ok_or(Error::InvalidParameter("iut.address"))?;
+
+        let channel = Channel::new(SimConfig::default(), config.start_time);
+        let mut device = channel.device(Medium::Ethernet);
+        let tester = channel.device(Medium::Ethernet);
+
+        let iface = Interface::new(IfaceConfig::new(address.into()), &mut device);
+
+        let mut router_config =
+            GnCoreGonfig::new(StationType::from(iut.station_type), Pseudonym(0xabcd));
+        router_config.random_seed = config.random_seed;
+        router_config.addr_config_mode = GnAddrConfigMode::Managed(address);
+        router_config.latitude = Latitude::new::<degree>(iut.latitude);
+        router_config.longitude = Longitude::new::<degree>(iut.longitude);
+        router_config.position_accurate = true;
+        let router = GnCore::new(router_config, channel.now());
+
+        let gn_rx_buffer =
+            PacketBuffer::new(vec![socket::geonet::RxPacketMetadata::EMPTY], vec![0; 4096]);
+        let gn_tx_buffer =
+            PacketBuffer::new(vec![socket::geonet::TxPacketMetadata::EMPTY], vec![0; 4096]);
+        let gn_socket = socket::geonet::Socket::new(gn_rx_buffer, gn_tx_buffer);
+        let denm_socket = socket::denm::Socket::new(vec![], vec![]);
+
+        let mut cam_socket = socket::cam::Socket::new();
+        let cam_rx = Rc::new(RefCell::new(VecDeque::new()));
+        let cam_rx_cb = cam_rx.clone();
+        cam_socket.register_recv_callback(move |uper, _| {
+            cam_rx_cb.borrow_mut().push_back(uper.to_vec());
+        });
+
+        let mut sockets = SocketSet::new(vec![]);
+        let gn_handle = sockets.add(gn_socket);
+        let denm_handle = sockets.add(denm_socket);
+        let cam_handle = sockets.add(cam_socket);
+
+        let ut = UpperTester::new(router.address(), gn_handle, denm_handle, cam_handle);
+
+        Ok(Testbed {
+            channel,
+            tick: config.tick,
+            device,
+            tester,
+            address,
+            iface,
+            router,
+            sockets,
+            ut,
+            gn_handle,
+            denm_handle,
+            cam_rx,
+            frames: VecDeque::new(),
+            indications: VecDeque::new(),
+        })
+    }
+
+    /// Play a scenario `step`.
+    fn play(&mut self, step: &Step) -> Result<(), Error> {
+        match step {
+            Step::Ut { message, success } => {
+                let message = parse_hex(message).ok_or(Error::InvalidParameter("message"))?;
+                self.ut_request(&message, *success)
+            }
+            Step::Inject(frame) => {
+                let frame = self.build_frame(frame)?;
+                if let Some(token) = self.tester.transmit(self.channel.now()) {
+                    token.consume(frame.len(), |buf| buf.copy_from_slice(&frame));
+                }
+                Ok(())
+            }
+            Step::Wait { ms } => {
+                self.run_for(Duration::from_millis(*ms), |_| false);
+                Ok(())
+            }
+            Step::ExpectFrame { within_ms, filter } => {
+                let matcher = Matcher::new(filter, self.address)?;
+                let found = self.run_for(Duration::from_millis(*within_ms), |tb| {
+                    tb.take_frame(&matcher)
+                });
+                found.then_some(()).ok_or(Error::MissingFrame)
+            }
+            Step::ExpectNoFrame { within_ms, filter } => {
+                let matcher = Matcher::new(filter, self.address)?;
+                let found = self.run_for(Duration::from_millis(*within_ms), |tb| {
+                    tb.take_frame(&matcher)
+                });
+                (!found).then_some(()).ok_or(Error::UnexpectedFrame)
+            }
+            Step::ExpectIndication { within_ms, message } => {
+                let message = parse_hex(message).ok_or(Error::InvalidParameter("message"))?;
+                let found = self.run_for(Duration::from_millis(*within_ms), |tb| {
+                    tb.take_indication(&message)
+                });
+                found.then_some(()).ok_or(Error::MissingIndication)
+            }
+            Step::ExpectNoIndication { within_ms, message } => {
+                let message = message
+                    .as_deref()
+                    .map(|m| parse_hex(m).ok_or(Error::InvalidParameter("message")))
+                    .transpose()?;
+                let found = self.run_for(Duration::from_millis(*within_ms), |tb| {
+                    match message.as_deref() {
+                        Some(message) => tb.take_indication(message),
+                        None => tb.indications.pop_front().is_some(),
+                    }
+                });
+                (!found).then_some(()).ok_or(Error::UnexpectedIndication)
+            }
+        }
+    }
+
+    /// Send an Uppertester request `message` to the IUT, and check its result code.
+    fn ut_request(&mut self, message: &[u8], success: bool) -> Result<(), Error> {
+        let res = self
+            .ut
+            .ut_dispatcher(
+                self.channel.now(),
+                &mut self.iface,
+                &mut self.router,
+                &mut self.sockets,
+                message,
+                UT_SERVER,
+            )
+            .ok_or(Error::NoResult)?;
+
+        let rc = res.get(1).copied().ok_or(Error::NoResult)?;
+        let expected = if success { 0x01 } else { 0x00 };
+        if rc != expected {
+            return Err(Error::UnexpectedResult(rc));
+        }
+
+        Ok(())
+    }
+
+    /// Run the IUT for `duration`, or until `done` returns true.
+    /// Returns whether `done` returned true.
+    fn run_for<F>(&mut self, duration: Duration, mut done: F) -> bool
+    where
+        F: FnMut(&mut Self) -> bool,
+    {
+        let end = self.channel.now() + duration;
+        loop {
+            if done(self) {
+                return true;
+            }
+
+            if self.channel.now() >= end {
+                return false;
+            }
+
+            self.step();
+        }
+    }
+
+    /// Advance the simulation by one tick.
+    fn step(&mut self) {
+        self.channel.advance(self.tick);
+        let timestamp = self.channel.now();
+
+        self.router.set_timestamp(timestamp);
+        self.ut.poll_position(timestamp, &mut self.router);
+        self.iface
+            .poll(&mut self.router, &mut self.device, &mut self.sockets);
+
+        // Capture frames emitted by the IUT.
+        while let Some((rx_token, _)) = self.tester.receive(timestamp) {
+            rx_token.consume(|buf| self.frames.push_back(buf.to_vec()));
+        }
+
+        // Capture Uppertester indications.
+        let gn_socket = self
+            .sockets
+            .get_mut::<socket::geonet::Socket>(self.gn_handle);
+        while let Ok((buf, meta)) = gn_socket.recv() {
+            if let Some((_, ind)) = self.ut.ut_gn_event(meta, buf) {
+                self.indications.push_back(ind);
+            }
+        }
+
+        let denm_socket = self
+            .sockets
+            .get_mut::<socket::denm::Socket>(self.denm_handle);
+        if let Some((_, ind)) = self.ut.ut_denm_event(denm_socket.poll(timestamp)) {
+            self.indications.push_back(ind);
+        }
+
+        while let Some(cam) = self.cam_rx.borrow_mut().pop_front() {
+            if let Some((_, ind)) = self.ut.ut_cam_event(&cam) {
+                self.indications.push_back(ind);
+            }
+        }
+
+        #[cfg(feature = "proto-security")]
+        while let Some((_, ind)) = self.ut.ut_sec_event() {
+            self.indications.push_back(ind);
+        }
+    }
+
+    /// Consume the captured frames until one matches `matcher`.
+    fn take_frame(&mut self, matcher: &Matcher) -> bool {
+        while let Some(frame) = self.frames.pop_front() {
+            if matcher.matches(&frame) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Consume the captured indications until one equals `message`.
+    fn take_indication(&mut self, message: &[u8]) -> bool {
+        while let Some(ind) = self.indications.pop_front() {
+            if ind == message {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Build a Geonetworking frame from its `frame` description.
+    fn build_frame(&self, frame: &Frame) -> Result<Vec<u8>, Error> {
+        let source =
+            parse_address(&frame.source, self.address).ok_or(Error::InvalidParameter("source"))?;
+        let destination = parse_address(&frame.destination, self.address)
+            .ok_or(Error::InvalidParameter("destination"))?;
+        let payload = parse_hex(&frame.payload).ok_or(Error::InvalidParameter("payload"))?;
+        let timestamp = TAI2004::from_unix_instant(self.channel.now());
+
+        let source_position_vector = LongPositionVectorRepr {
+            address: GnAddress::new(true, StationType::from(frame.station_type), source),
+            timestamp: timestamp.into(),
+            latitude: Latitude::new::<degree>(frame.latitude),
+            longitude: Longitude::new::<degree>(frame.longitude),
+            is_accurate: true,
+            speed: Speed::new::<meter_per_second>(frame.speed),
+            heading: Heading::new::<degree>(frame.heading),
+        };
+
+        let target = || -> Result<ShortPositionVectorRepr, Error> {
+            let target = frame
+                .target
+                .as_ref()
+                .ok_or(Error::InvalidParameter("target"))?;
+            let address = parse_address(&target.address, self.address)
+                .ok_or(Error::InvalidParameter("target.address"))?;
+            Ok(ShortPositionVectorRepr {
+                address: GnAddress::new(true, StationType::from(target.station_type), address),
+                timestamp: timestamp.into(),
+                latitude: Latitude::new::<degree>(target.latitude),
+                longitude: Longitude::new::<degree>(target.longitude),
+            })
+        };
+
+        let area = || -> Result<(AreaShape, GeoBroadcastRepr), Error> {
+            let area = frame.area.as_ref().ok_or(Error::InvalidParameter("area"))?;
+            let repr = GeoBroadcastRepr {
+                sequence_number: SequenceNumber(frame.sequence_number),
+                source_position_vector,
+                latitude: Latitude::new::<degree>(area.latitude),
+                longitude: Longitude::new::<degree>(area.longitude),
+                distance_a: Distance::new::<meter>(area.distance_a),
+                distance_b: Distance::new::<meter>(area.distance_b),
+                angle: Angle::new::<degree>(area.angle),
+            };
+            Ok((area.shape, repr))
+        };
+
+        let basic_header = BasicHeaderRepr {
+            version: config::GN_PROTOCOL_VERSION,
+            next_header: BHNextHeader::CommonHeader,
+            lifetime: frame
+                .lifetime_ms
+                .map(Duration::from_millis)
+                .unwrap_or(config::GN_DEFAULT_PACKET_LIFETIME),
+            remaining_hop_limit: frame.remaining_hop_limit,
+        };
+
+        let mut common_header = CommonHeaderRepr {
+            next_header: GnProtocol::Any,
+            header_type: GeonetPacketType::Any,
+            traffic_class: GnTrafficClass::from_byte(&frame.traffic_class),
+            mobile: true,
+            payload_len: payload.len(),
+            max_hop_limit: frame.max_hop_limit.unwrap_or(frame.remaining_hop_limit),
+        };
+
+        let sequence_number = SequenceNumber(frame.sequence_number);
+        let packet: GeonetVariant = match frame.packet {
+            PacketType::Beacon => {
+                common_header.header_type = GeonetPacketType::Beacon;
+                let extended_header = BeaconHeaderRepr {
+                    source_position_vector,
+                };
+                GeonetBeacon::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::Shb => {
+                common_header.header_type = GeonetPacketType::TsbSingleHop;
+                let extended_header = SingleHopHeaderRepr {
+                    source_position_vector,
+                    extension: [0; SingleHopHeaderRepr::extension_len()],
+                };
+                GeonetSingleHop::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::Tsb => {
+                common_header.header_type = GeonetPacketType::TsbMultiHop;
+                let extended_header = TopoBroadcastRepr {
+                    sequence_number,
+                    source_position_vector,
+                };
+                GeonetTopoBroadcast::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::Gbc => {
+                let (shape, extended_header) = area()?;
+                common_header.header_type = match shape {
+                    AreaShape::Circle => GeonetPacketType::GeoBroadcastCircle,
+                    AreaShape::Rectangle => GeonetPacketType::GeoBroadcastRect,
+                    AreaShape::Ellipse => GeonetPacketType::GeoBroadcastElip,
+                };
+                GeonetGeoBroadcast::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::Gac => {
+                let (shape, extended_header) = area()?;
+                common_header.header_type = match shape {
+                    AreaShape::Circle => GeonetPacketType::GeoAnycastCircle,
+                    AreaShape::Rectangle => GeonetPacketType::GeoAnycastRect,
+                    AreaShape::Ellipse => GeonetPacketType::GeoAnycastElip,
+                };
+                GeonetGeoAnycast::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::Guc => {
+                common_header.header_type = GeonetPacketType::GeoUnicast;
+                let extended_header = UnicastRepr {
+                    sequence_number,
+                    source_position_vector,
+                    destination_position_vector: target()?,
+                };
+                GeonetUnicast::new(basic_header, common_header, extended_header).into()
+            }
+            PacketType::LsRequest => {
+                common_header.header_type = GeonetPacketType::LsRequest;
+                let extended_header = LocationServiceRequestRepr {
+                    sequence_number,
+                    source_position_vector,
+                    request_address: target()?.address,
+                };
+                GeonetLocationServiceRequest::new(basic_header, common_header, extended_header)
+                    .into()
+            }
+            PacketType::LsReply => {
+                common_header.header_type = GeonetPacketType::LsReply;
+                let extended_header = UnicastRepr {
+                    sequence_number,
+                    source_position_vector,
+                    destination_position_vector: target()?,
+                };
+                GeonetLocationServiceReply::new(basic_header, common_header, extended_header).into()
+            }
+        };
+
+        let header_len = packet.header_len();
+        let mut buffer = vec![0u8; ETHERNET_HEADER_LEN + header_len + payload.len()];
+        let mut ethernet = EthernetFrame::new_unchecked(&mut buffer);
+        EthernetRepr {
+            src_addr: source,
+            dst_addr: destination,
+            ethertype: EthernetProtocol::Geonet,
+        }
+        .emit(&mut ethernet);
+
+        let gn_buf = ethernet.payload_mut();
+        packet.emit(&mut gn_buf[..header_len]);
+        gn_buf[header_len..].copy_from_slice(&payload);
+
+        Ok(buffer)
+    }
+}
+
+/// A [FrameFilter], with its parameters parsed.
+struct Matcher {
+    packet: Option<PacketType>,
+    sender: Option<EthernetAddress>,
+    destination: Option<EthernetAddress>,
+    source: Option<EthernetAddress>,
+    sequence_number: Option<u16>,
+    remaining_hop_limit: Option<u8>,
+    max_hop_limit: Option<u8>,
+    traffic_class: Option<u8>,
+    payload: Option<Vec<u8>>,
+}
+
+impl Matcher {
+    fn new(filter: &FrameFilter, iut: EthernetAddress) -> Result<Self, Error> {
+        let address = |value: &Option<String>, name| match value {
+            Some(v) => parse_address(v, iut)
+                .map(Some)
+                .ok_or(Error::InvalidParameter(name)),
+            None => Ok(None),
+        };
+
+        let payload = match &filter.payload {
+            Some(p) => Some(parse_hex(p).ok_or(Error::InvalidParameter("payload"))?),
+            None => None,
+        };
+
+        Ok(Matcher {
+            packet: filter.packet,
+            sender: address(&filter.sender, "sender")?,
+            destination: address(&filter.destination, "destination")?,
+            source: address(&filter.source, "source")?,
+            sequence_number: filter.sequence_number,
+            remaining_hop_limit: filter.remaining_hop_limit,
+            max_hop_limit: filter.max_hop_limit,
+            traffic_class: filter.traffic_class,
+            payload,
+        })
+    }
+
+    /// Query whether the `frame` emitted by the IUT matches.
+    fn matches(&self, frame: &[u8]) -> bool {
+        let Some((ethernet, packet, payload)) = parse_frame(frame) else {
+            return false;
+        };
+
+        let sequence_number = match packet {
+            GeonetVariant::Beacon(_) | GeonetVariant::SingleHopBroadcast(_) => None,
+            _ => Some(packet.sequence_number().0),
+        };
+
+        fn check<T: PartialEq>(expected: &Option<T>, value: T) -> bool {
+            expected.as_ref().is_none_or(|e| *e == value)
+        }
+
+        check(&self.packet, packet_type(&packet))
+            && check(&self.sender, ethernet.src_addr)
+            && check(&self.destination, ethernet.dst_addr)
+            && check(
+                &self.source,
+                packet.source_position_vector().address.mac_addr(),
+            )
+            && self
+                .sequence_number
+                .is_none_or(|sn| sequence_number == Some(sn))
+            && check(
+                &self.remaining_hop_limit,
+                packet.basic_header().remaining_hop_limit,
+            )
+            && check(&self.max_hop_limit, packet.common_header().max_hop_limit)
+            && check(
+                &self.traffic_class,
+                *packet.common_header().traffic_class.as_byte(),
+            )
+            && self.payload.as_deref().is_none_or(|p| p == payload)
+    }
+}
+
+/// Return the scenario packet type of `packet`.
+fn packet_type(packet: &GeonetVariant) -> PacketType {
+    match packet {
+        GeonetVariant::Beacon(_) => PacketType::Beacon,
+        GeonetVariant::Unicast(_) => PacketType::Guc,
+        GeonetVariant::Anycast(_) => PacketType::Gac,
+        GeonetVariant::Broadcast(_) => PacketType::Gbc,
+        GeonetVariant::SingleHopBroadcast(_) => PacketType::Shb,
+        GeonetVariant::TopoBroadcast(_) => PacketType::Tsb,
+        GeonetVariant::LocationServiceRequest(_) => PacketType::LsRequest,
+        GeonetVariant::LocationServiceReply(_) => PacketType::LsReply,
+    }
+}
+
+/// Parse an unsecured Geonetworking Ethernet `frame`. Returns the Ethernet header,
+/// the Geonetworking packet and its payload.
+fn parse_frame(frame: &[u8]) -> Option<(EthernetRepr, GeonetVariant, &[u8])> {
+    let ethernet = EthernetFrame::new_checked(frame).ok()?;
+    let ethernet_repr = EthernetRepr::parse(&ethernet).ok()?;
+    if ethernet_repr.ethertype != EthernetProtocol::Geonet {
+        return None;
+    }
+
+    let buffer = ethernet.payload();
+    let bh = BasicHeader::new_checked(buffer).ok()?;
+    let bh_repr = BasicHeaderRepr::parse(&bh).ok()?;
+    if bh_repr.next_header != BHNextHeader::CommonHeader {
+        return None;
+    }
+
+    let ch = CommonHeader::new_checked(bh.payload()).ok()?;
+    let ch_repr = CommonHeaderRepr::parse(&ch).ok()?;
+    let eh = ch.payload();
+
+    let packet: GeonetVariant = match ch_repr.header_type {
+        GeonetPacketType::Beacon => {
+            let repr = BeaconHeaderRepr::parse(&BeaconHeader::new_checked(eh).ok()?).ok()?;
+            GeonetBeacon::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::TsbSingleHop => {
+            let repr = SingleHopHeaderRepr::parse(&SingleHopHeader::new_checked(eh).ok()?).ok()?;
+            GeonetSingleHop::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::TsbMultiHop => {
+            let repr =
+                TopoBroadcastRepr::parse(&TopoBroadcastHeader::new_checked(eh).ok()?).ok()?;
+            GeonetTopoBroadcast::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::GeoBroadcastCircle
+        | GeonetPacketType::GeoBroadcastRect
+        | GeonetPacketType::GeoBroadcastElip => {
+            let repr = GeoBroadcastRepr::parse(&GeoBroadcastHeader::new_checked(eh).ok()?).ok()?;
+            GeonetGeoBroadcast::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::GeoAnycastCircle
+        | GeonetPacketType::GeoAnycastRect
+        | GeonetPacketType::GeoAnycastElip => {
+            let repr = GeoBroadcastRepr::parse(&GeoAnycastHeader::new_checked(eh).ok()?).ok()?;
+            GeonetGeoAnycast::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::GeoUnicast => {
+            let repr = UnicastRepr::parse(&UnicastHeader::new_checked(eh).ok()?).ok()?;
+            GeonetUnicast::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::LsRequest => {
+            let repr = LocationServiceRequestRepr::parse(
+                &LocationServiceRequestHeader::new_checked(eh).ok()?,
+            )
+            .ok()?;
+            GeonetLocationServiceRequest::new(bh_repr, ch_repr, repr).into()
+        }
+        GeonetPacketType::LsReply => {
+            let repr =
+                UnicastRepr::parse(&LocationServiceReplyHeader::new_checked(eh).ok()?).ok()?;
+            GeonetLocationServiceReply::new(bh_repr, ch_repr, repr).into()
+        }
+        _ => return None,
+    };
+
+    let payload = buffer.get(packet.header_len()..)?;
+    let payload_len = ch_repr.payload_len.min(payload.len());
+
+    Some((ethernet_repr, packet, &payload[..payload_len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! scenario {
+        ($name: ident, $path: literal) => {
+            #[test]
+            fn $name() {
+                let script = include_str!(concat!("../../../conformance/", $path));
+                let scenario = Scenario::from_toml(script).unwrap();
+                if let Err(failure) = Runner::default().run(&scenario) {
+                    panic!("{}: {}", scenario.name, failure);
+                }
+            }
+        };
+    }
+
+    scenario!(test_geonet_beacon, "geonet/beacon.toml");
+    scenario!(test_geonet_shb, "geonet/shb.toml");
+    scenario!(test_geonet_tsb, "geonet/tsb.toml");
+    scenario!(test_geonet_gbc, "geonet/gbc.toml");
+    scenario!(test_geonet_gac, "geonet/gac.toml");
+    scenario!(test_geonet_guc, "geonet/guc.toml");
+    scenario!(test_geonet_ls, "geonet/ls.toml");
+
+    #[test]
+    fn test_parse_params() {
+        let iut = EthernetAddress([0x02, 0x02, 0x02, 0x02, 0x02, 0x02]);
+
+        assert_eq!(parse_hex("00 0a0B\nff"), Some(vec![0x00, 0x0a, 0x0b, 0xff]));
+        assert_eq!(parse_hex("abc"), None);
+        assert_eq!(parse_hex("zz"), None);
+
+        assert_eq!(parse_address("iut", iut), Some(iut));
+        assert_eq!(
+            parse_address("broadcast", iut),
+            Some(EthernetAddress::BROADCAST)
+        );
+        assert_eq!(
+            parse_address("03:03:03:03:03:0a", iut),
+            Some(EthernetAddress([0x03, 0x03, 0x03, 0x03, 0x03, 0x0a]))
+        );
+        assert_eq!(parse_address("03:03:03:03:03", iut), None);
+        assert_eq!(parse_address("03:03:03:03:03:03:03", iut), None);
+    }
+
+    #[test]
+    fn test_inject_roundtrip() {
+        let testbed = Testbed::new(&Config::default(), &Iut::default()).unwrap();
+        let frame = Frame {
+            packet: PacketType::Tsb,
+            source: "03:03:03:03:03:03".into(),
+            destination: "broadcast".into(),
+            station_type: 5,
+            latitude: 48.271947,
+            longitude: -3.614961,
+            speed: 0.0,
+            heading: 0.0,
+            sequence_number: 42,
+            remaining_hop_limit: 3,
+            max_hop_limit: None,
+            lifetime_ms: None,
+            traffic_class: 0,
+            area: None,
+            target: None,
+            payload: "cafe".into(),
+        };
+
+        let buffer = testbed.build_frame(&frame).unwrap();
+        let filter = FrameFilter {
+            packet: Some(PacketType::Tsb),
+            source: Some("03:03:03:03:03:03".into()),
+            sequence_number: Some(42),
+            remaining_hop_limit: Some(3),
+            max_hop_limit: Some(3),
+            payload: Some("cafe".into()),
+            ..Default::default()
+        };
+        assert!(Matcher::new(&filter, testbed.address)
+            .unwrap()
+            .matches(&buffer));
+
+        let filter = FrameFilter {
+            packet: Some(PacketType::Shb),
+            ..Default::default()
+        };
+        assert!(!Matcher::new(&filter, testbed.address)
+            .unwrap()
+            .matches(&buffer));
+    }
+}
@@ -0,0 +1,324 @@
+use serde::Deserialize;
+
+use crate::wire::EthernetAddress;
+
+/// A conformance test scenario, ie: a sequence of steps played against the IUT.
+///
+/// Scenarios are written in TOML. Byte strings (Uppertester messages, payloads) are
+/// written in hexadecimal, whitespaces are allowed between octets. Link layer addresses
+/// are written as `xx:xx:xx:xx:xx:xx`, `iut` designates the address of the IUT and
+/// `broadcast` the broadcast address.
+///
+/// ```toml
+/// name = "TP_GEONET_PON_SHB_BV_01"
+///
+/// [[step]]
+/// action = "ut"
+/// message = "00 0000000000000000"
+///
+/// [[step]]
+/// action = "ut"
+/// message = "53 00 0002 cafe"
+///
+/// [[step]]
+/// action = "expect_frame"
+/// packet = "shb"
+/// payload = "cafe"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Name of the scenario, usually the test purpose identifier.
+    pub name: String,
+    /// Description of the scenario.
+    #[serde(default)]
+    pub description: String,
+    /// IUT setup.
+    #[serde(default)]
+    pub iut: Iut,
+    /// Steps of the scenario, played in order.
+    #[serde(rename = "step", default)]
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    /// Deserialize a scenario from a TOML string.
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+}
+
+/// IUT setup.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Iut {
+    /// Link layer address of the IUT.
+    pub address: String,
+    /// Station type of the IUT.
+    pub station_type: u16,
+    /// Latitude of the IUT, in degrees.
+    pub latitude: f64,
+    /// Longitude of the IUT, in degrees.
+    pub longitude: f64,
+}
+
+impl Default for Iut {
+    fn default() -> Self {
+        Iut {
+            address: "02:02:02:02:02:02".into(),
+            // Passenger car.
+            station_type: 5,
+            latitude: 48.276434,
+            longitude: -3.5519532,
+        }
+    }
+}
+
+/// A scenario step.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    /// Send an Uppertester message to the IUT, and check the result code.
+    Ut {
+        /// Uppertester message.
+        message: String,
+        /// Whether the IUT should report a success.
+        #[serde(default = "default_true")]
+        success: bool,
+    },
+    /// Inject a Geonetworking frame on the channel.
+    Inject(Frame),
+    /// Let the IUT run for the given duration.
+    Wait {
+        /// Duration, in milliseconds.
+        ms: u64,
+    },
+    /// Expect a frame matching `filter` to be emitted by the IUT.
+    /// Frames not matching the filter are discarded.
+    ExpectFrame {
+        /// Maximum waiting duration, in milliseconds.
+        #[serde(default = "default_timeout")]
+        within_ms: u64,
+        /// Frame filter.
+        #[serde(flatten)]
+        filter: FrameFilter,
+    },
+    /// Expect no frame matching `filter` to be emitted by the IUT.
+    ExpectNoFrame {
+        /// Observation duration, in milliseconds.
+        #[serde(default = "default_timeout")]
+        within_ms: u64,
+        /// Frame filter.
+        #[serde(flatten)]
+        filter: FrameFilter,
+    },
+    /// Expect an Uppertester indication to be notified by the IUT.
+    /// Indications not matching `message` are discarded.
+    ExpectIndication {
+        /// Maximum waiting duration, in milliseconds.
+        #[serde(default = "default_timeout")]
+        within_ms: u64,
+        /// Uppertester indication message.
+        message: String,
+    },
+    /// Expect no Uppertester indication to be notified by the IUT.
+    /// When `message` is set, indications not equal to it are discarded.
+    ExpectNoIndication {
+        /// Observation duration, in milliseconds.
+        #[serde(default = "default_timeout")]
+        within_ms: u64,
+        /// Uppertester indication message.
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+/// Geonetworking packet type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketType {
+    Beacon,
+    Shb,
+    Tsb,
+    Gbc,
+    Gac,
+    Guc,
+    LsRequest,
+    LsReply,
+}
+
+/// Shape of a destination area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AreaShape {
+    Circle,
+    Rectangle,
+    Ellipse,
+}
+
+/// Destination area of a GBC or GAC frame.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Area {
+    /// Shape of the area.
+    pub shape: AreaShape,
+    /// Latitude of the area center, in degrees.
+    pub latitude: f64,
+    /// Longitude of the area center, in degrees.
+    pub longitude: f64,
+    /// Distance a, in meters.
+    pub distance_a: f64,
+    /// Distance b, in meters.
+    #[serde(default)]
+    pub distance_b: f64,
+    /// Angle of the area, in degrees.
+    #[serde(default)]
+    pub angle: f64,
+}
+
+/// Destination of a GUC or LS Reply frame, or requested station of a LS Request frame.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Target {
+    /// Link layer address of the destination.
+    pub address: String,
+    /// Station type of the destination.
+    #[serde(default = "default_station_type")]
+    pub station_type: u16,
+    /// Latitude of the destination, in degrees.
+    #[serde(default)]
+    pub latitude: f64,
+    /// Longitude of the destination, in degrees.
+    #[serde(default)]
+    pub longitude: f64,
+}
+
+/// Geonetworking frame injected on the channel.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Frame {
+    /// Packet type.
+    pub packet: PacketType,
+    /// Link layer address of the source, also used in the source position vector.
+    pub source: String,
+    /// Link layer destination address.
+    #[serde(default = "default_destination")]
+    pub destination: String,
+    /// Station type of the source.
+    #[serde(default = "default_station_type")]
+    pub station_type: u16,
+    /// Latitude of the source, in degrees.
+    pub latitude: f64,
+    /// Longitude of the source, in degrees.
+    pub longitude: f64,
+    /// Speed of the source, in meters per second.
+    #[serde(default)]
+    pub speed: f64,
+    /// Heading of the source, in degrees.
+    #[serde(default)]
+    pub heading: f64,
+    /// Sequence number, for packet types containing one.
+    #[serde(default)]
+    pub sequence_number: u16,
+    /// Remaining hop limit.
+    #[serde(default = "default_hop_limit")]
+    pub remaining_hop_limit: u8,
+    /// Maximum hop limit. Defaults to the remaining hop limit.
+    #[serde(default)]
+    pub max_hop_limit: Option<u8>,
+    /// Packet lifetime, in milliseconds. Defaults to the GN default packet lifetime.
+    #[serde(default)]
+    pub lifetime_ms: Option<u64>,
+    /// Traffic class.
+    #[serde(default)]
+    pub traffic_class: u8,
+    /// Destination area, for GBC and GAC packets.
+    #[serde(default)]
+    pub area: Option<Area>,
+    /// Destination, for GUC, LS Request and LS Reply packets.
+    #[serde(default)]
+    pub target: Option<Target>,
+    /// Geonetworking payload.
+    #[serde(default)]
+    pub payload: String,
+}
+
+/// Filter on the frames emitted by the IUT. Unset fields match any value.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct FrameFilter {
+    /// Packet type.
+    pub packet: Option<PacketType>,
+    /// Link layer source address.
+    pub sender: Option<String>,
+    /// Link layer destination address.
+    pub destination: Option<String>,
+    /// Address of the source position vector.
+    pub source: Option<String>,
+    /// Sequence number.
+    pub sequence_number: Option<u16>,
+    /// Remaining hop limit.
+    pub remaining_hop_limit: Option<u8>,
+    /// Maximum hop limit.
+    pub max_hop_limit: Option<u8>,
+    /// Traffic class.
+    pub traffic_class: Option<u8>,
+    /// Geonetworking payload.
+    pub payload: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout() -> u64 {
+    1000
+}
+
+fn default_station_type() -> u16 {
+    // Passenger car.
+    5
+}
+
+fn default_destination() -> String {
+    "broadcast".into()
+}
+
+fn default_hop_limit() -> u8 {
+    1
+}
+
+/// Parse a hexadecimal byte string, ignoring whitespaces.
+pub(super) fn parse_hex(input: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = input.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = core::str::from_utf8(pair).ok()?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// Parse a link layer address. `iut` is resolved to the `iut` address.
+pub(super) fn parse_address(input: &str, iut: EthernetAddress) -> Option<EthernetAddress> {
+    match input {
+        "iut" => return Some(iut),
+        "broadcast" => return Some(EthernetAddress::BROADCAST),
+        _ => {}
+    }
+
+    let mut bytes = [0u8; 6];
+    let mut octets = input.split(':');
+    for byte in bytes.iter_mut() {
+        *byte = u8::from_str_radix(octets.next()?, 16).ok()?;
+    }
+
+    match octets.next() {
+        Some(_) => None,
+        None => Some(EthernetAddress(bytes)),
+    }
+}
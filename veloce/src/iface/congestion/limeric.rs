@@ -224,7 +224,7 @@ impl RateController for Limeric {
         self.tx_interval
     }
 
-    fn notify_tx(&mut self, tx_at: Instant, duration: Duration) {
+    fn notify_tx(&mut self, tx_at: Instant, duration: Duration, _prio: AccessCategory) {
         let interval = duration.total_micros() as f64 / self.duty_cycle;
         let interval = Duration::from_micros(interval as u64);
         self.tx_interval = interval.max(MIN_INTERVAL).min(MAX_INTERVAL);
@@ -10,10 +10,11 @@ use crate::{
     wire::{ieee80211::AccessCategory, EthernetAddress, GeonetRepr, GeonetVariant},
 };
 
-use super::{location_table::LocationTable, packet::GeonetPacket};
+use super::{location_table::LocationTable, packet::GeonetPacket, stats::DccStats};
 
 pub(crate) mod limeric;
 pub(crate) mod no_control;
+pub(crate) mod reactive;
 
 pub(crate) use self::{Error as CongestionError, Success as CongestionSuccess};
 
@@ -30,8 +31,8 @@ pub(crate) trait RateController {
     fn tx_interval(&self) -> Duration;
     /// Notify DCC algorithm for transmission activity,
     /// at `tx_at` time instant, and for over-the-air
-    /// transmission `duration`.
-    fn notify_tx(&mut self, tx_at: Instant, duration: Duration);
+    /// transmission `duration` on the `prio` queue.
+    fn notify_tx(&mut self, tx_at: Instant, duration: Duration, prio: AccessCategory);
     /// Update CBR value.
     fn update_cbr(&mut self, timestamp: Instant, cbr: ChannelBusyRatio);
     /// Returns the local CBR value.
@@ -77,6 +78,8 @@ pub(crate) struct Congestion {
     prev_local_cbr: ChannelBusyRatio,
     /// Instant at which `global_cbr` value should be (re)computed.
     compute_global_cbr_at: Instant,
+    /// Queued and dropped packets counters.
+    pub stats: DccStats,
 }
 
 impl Congestion {
@@ -103,9 +106,50 @@ impl Congestion {
             global_cbr: ChannelBusyRatio::from_ratio(0.0),
             prev_local_cbr: ChannelBusyRatio::from_ratio(0.0),
             compute_global_cbr_at: Instant::ZERO,
+            stats: DccStats::default(),
         }
     }
 
+    /// Replace the rate controller with `controller`. Queued packets are kept
+    /// and will be released according to the new controller.
+    pub fn set_controller(&mut self, controller: AnyController) {
+        self.controller = controller;
+
+        if self.queues.iter().any(|q| !q.1.is_empty()) {
+            self.egress_at = Some(self.egress_at.unwrap_or(Instant::ZERO));
+        }
+    }
+
+    /// Returns the allowed duration between two transmissions,
+    /// as computed by the rate controller.
+    pub fn tx_interval(&self) -> Duration {
+        self.controller.inner().tx_interval()
+    }
+
+    /// Returns the number of packets waiting in the `prio` queue.
+    pub fn backlog(&self, prio: AccessCategory) -> usize {
+        self.queues.get(&prio).map_or(0, |q| q.packet_count())
+    }
+
+    /// Returns the number of packets waiting in all the queues.
+    pub fn total_backlog(&self) -> usize {
+        self.queues.values().map(|q| q.packet_count()).sum()
+    }
+
+    /// Returns the minimum generation interval of messages sent on the `prio` queue,
+    /// ie: the DCC_FAC generation interval. It accounts for the packets already waiting
+    /// in queues of equal or higher priority, which will be transmitted first.
+    pub fn gen_interval(&self, prio: AccessCategory) -> Duration {
+        let ahead: usize = self
+            .queues
+            .iter()
+            .filter(|e| *e.0 >= prio)
+            .map(|e| e.1.packet_count())
+            .sum();
+
+        self.tx_interval() * (ahead as u32 + 1)
+    }
+
     /// Returns the local Channel Busy Ratio value.
     pub fn local_cbr(&self) -> ChannelBusyRatio {
         self.controller.inner().local_cbr()
@@ -145,6 +189,7 @@ impl Congestion {
 
         // Enqueue packet
         let Some(queue) = self.queues.get_mut(&cat) else {
+            self.stats.dropped += 1;
             return Err(Error::NoMatchingQueue);
         };
 
@@ -156,9 +201,11 @@ impl Congestion {
         // Special treatment for empty payload.
         let payload = packet.payload().unwrap_or_default();
 
-        queue
-            .enqueue(pkt, payload, timestamp)
-            .map_err(|_| Error::Buffer)?;
+        if queue.enqueue(pkt, payload, timestamp).is_err() {
+            self.stats.dropped += 1;
+            return Err(Error::Buffer);
+        }
+        self.stats.enqueued += 1;
 
         // Schedule for egress.
         if self.egress_at.is_none() {
@@ -281,6 +328,9 @@ pub(crate) enum Error {
     Buffer,
     /// Duplicate packet detected (only when CBF routing algorithm is used).
     CbfDuplicate,
+    /// Packet carries the ego position vector and the station is in a silent period.
+    #[cfg(feature = "proto-security")]
+    SilentPeriod,
 }
 
 /// Transmit Rate Control controller algorithm.
@@ -290,6 +340,8 @@ pub(crate) enum AnyController {
     None(no_control::NoControl),
     /// Limeric algorithm.
     Limeric(limeric::Limeric),
+    /// Reactive algorithm.
+    Reactive(reactive::Reactive),
 }
 
 impl AnyController {
@@ -309,6 +361,7 @@ impl AnyController {
         match self {
             AnyController::None(n) => n,
             AnyController::Limeric(l) => l,
+            AnyController::Reactive(r) => r,
         }
     }
 
@@ -317,6 +370,114 @@ impl AnyController {
         match self {
             AnyController::None(n) => n,
             AnyController::Limeric(l) => l,
+            AnyController::Reactive(r) => r,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config;
+    use crate::wire::{
+        BHNextHeader, BasicHeaderRepr, CommonHeaderRepr, GeonetPacketType, GeonetSingleHop,
+        GnProtocol, GnTrafficClass, LongPositionVectorRepr, SingleHopHeaderRepr,
+    };
+
+    use super::*;
+
+    /// Make a SHB packet sent with the `traffic_class` traffic class.
+    fn shb_repr(traffic_class: GnTrafficClass) -> GeonetRepr<GeonetVariant> {
+        let shb = GeonetSingleHop {
+            basic_header: BasicHeaderRepr {
+                version: config::GN_PROTOCOL_VERSION,
+                next_header: BHNextHeader::CommonHeader,
+                lifetime: config::GN_DEFAULT_PACKET_LIFETIME,
+                remaining_hop_limit: 1,
+            },
+            common_header: CommonHeaderRepr {
+                next_header: GnProtocol::Any,
+                header_type: GeonetPacketType::TsbSingleHop,
+                traffic_class,
+                mobile: true,
+                payload_len: 0,
+                max_hop_limit: 1,
+            },
+            extended_header: SingleHopHeaderRepr {
+                source_position_vector: LongPositionVectorRepr::default(),
+                extension: [0; SingleHopHeaderRepr::extension_len()],
+            },
+        };
+
+        GeonetRepr::Unsecured(GeonetVariant::SingleHopBroadcast(shb))
+    }
+
+    fn reactive() -> Congestion {
+        Congestion::new(AnyController::Reactive(reactive::Reactive::new(
+            Default::default(),
+        )))
+    }
+
+    fn feed(congestion: &mut Congestion, timestamp: &mut Instant, cbr: f64, count: usize) {
+        let rc = congestion.controller.inner_mut();
+        for _ in 0..count {
+            *timestamp += Duration::from_millis(100);
+            rc.update_cbr(*timestamp, ChannelBusyRatio::from_ratio(cbr));
+            rc.run(*timestamp);
+        }
+    }
+
+    #[test]
+    fn test_gen_interval_channel_load() {
+        let mut congestion = reactive();
+        let mut timestamp = Instant::from_secs(10);
+
+        let cat = AccessCategory::BestEffort;
+        assert_eq!(congestion.gen_interval(cat), Duration::from_millis(60));
+
+        // Interval grows with the channel load.
+        feed(&mut congestion, &mut timestamp, 0.45, 2);
+        assert_eq!(congestion.gen_interval(cat), Duration::from_millis(180));
+
+        feed(&mut congestion, &mut timestamp, 0.8, 2);
+        assert_eq!(congestion.gen_interval(cat), Duration::from_secs(1));
+
+        // And shrinks back once the channel is relaxed.
+        feed(&mut congestion, &mut timestamp, 0.1, 10);
+        assert_eq!(congestion.gen_interval(cat), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn test_gen_interval_backlog() {
+        let mut congestion = reactive();
+        let timestamp = Instant::from_secs(10);
+        let tx_interval = Duration::from_millis(60);
+
+        // Close the gate so packets are enqueued.
+        congestion.controller.inner_mut().notify_tx(
+            timestamp,
+            Duration::from_micros(300),
+            AccessCategory::Voice,
+        );
+
+        // pCamTrafficClass, mapped to AccessCategory::BestEffort.
+        let repr = shb_repr(GnTrafficClass::new(false, 2));
+        for _ in 0..3 {
+            let packet = GeonetPacket::new(repr.clone(), None);
+            let res = congestion.dispatch(&packet, EthernetAddress::BROADCAST, timestamp);
+            assert!(matches!(res, Ok(Success::Enqueued)));
         }
+        assert_eq!(congestion.backlog(AccessCategory::BestEffort), 3);
+
+        // Packets waiting on equal or higher priority queues are transmitted first.
+        assert_eq!(
+            congestion.gen_interval(AccessCategory::Background),
+            tx_interval * 4
+        );
+        assert_eq!(
+            congestion.gen_interval(AccessCategory::BestEffort),
+            tx_interval * 4
+        );
+        assert_eq!(congestion.gen_interval(AccessCategory::Video), tx_interval);
+        assert_eq!(congestion.gen_interval(AccessCategory::Voice), tx_interval);
     }
 }
@@ -25,7 +25,7 @@ impl RateController for NoControl {
         Duration::ZERO
     }
 
-    fn notify_tx(&mut self, _tx_at: Instant, _duration: Duration) {}
+    fn notify_tx(&mut self, _tx_at: Instant, _duration: Duration, _prio: AccessCategory) {}
 
     fn update_cbr(&mut self, _timestamp: Instant, _cbr: ChannelBusyRatio) {}
 
@@ -0,0 +1,364 @@
+use heapless::HistoryBuf;
+
+use crate::phy::ChannelBusyRatio;
+use crate::time::{Duration, Instant};
+use crate::wire::ieee80211::AccessCategory;
+
+use super::RateController;
+
+/// Reactive DCC needs CBR measurements on a duration of 1s to relax its state.
+const CBR_HISTORY_SIZE: usize = 10;
+/// Number of CBR measurements used to move to a more restrictive state, ie: 200ms.
+const CBR_UP_SAMPLES: usize = 2;
+
+/// Number of states of the reactive approach.
+pub const STATE_COUNT: usize = 5;
+
+/// States of the reactive approach,
+/// as defined in ETSI TS 102 687 v1.2.1, Annex A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum State {
+    /// Channel is lightly loaded.
+    Relaxed,
+    /// First active state.
+    Active1,
+    /// Second active state.
+    Active2,
+    /// Third active state.
+    Active3,
+    /// Channel is congested.
+    Restrictive,
+}
+
+impl State {
+    const ALL: [State; STATE_COUNT] = [
+        State::Relaxed,
+        State::Active1,
+        State::Active2,
+        State::Active3,
+        State::Restrictive,
+    ];
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Parameter values of reactive approach,
+/// as defined in ETSI TS 102 687 v1.2.1, Table A.2.
+#[derive(Debug)]
+pub struct Parameters {
+    /// Lower CBR bound of each state above [State::Relaxed].
+    /// Values must be in ascending order.
+    pub cbr_thresholds: [f64; STATE_COUNT - 1],
+    /// Minimum time between two transmissions, for each state.
+    pub t_off: [Duration; STATE_COUNT],
+    /// Reference packet over-the-air duration `t_off` values are given for.
+    /// `t_off` is scaled up for longer packets.
+    pub t_on_ref: Duration,
+    /// Multiplier applied to `t_off` for each access category, in order
+    /// Background, Best Effort, Video and Voice. A value of 1 means the access
+    /// category is only limited by the gate shared by all access categories.
+    pub ac_factors: [u32; 4],
+    /// CBR measurement interval.
+    pub cbr_interval: Duration,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            cbr_thresholds: [0.30, 0.40, 0.50, 0.65],
+            t_off: [
+                Duration::from_millis(60),
+                Duration::from_millis(100),
+                Duration::from_millis(180),
+                Duration::from_millis(260),
+                Duration::from_millis(1000),
+            ],
+            t_on_ref: Duration::from_micros(500),
+            ac_factors: [2, 1, 1, 1],
+            cbr_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Transmission token of an access category.
+#[derive(Debug, Default, Clone, Copy)]
+struct Token {
+    /// Instant at which the access category is allowed to transmit.
+    available_at: Instant,
+    /// Number of tokens consumed, ie: transmissions.
+    consumed: u64,
+    /// Total over-the-air duration of the transmissions.
+    airtime: Duration,
+}
+
+/// Reactive DCC gatekeeper, as defined in ETSI TS 102 687 v1.2.1, Annex A.
+///
+/// The gate is shared between all access categories and stays closed during
+/// `t_off` after each transmission. Each access category also holds its own
+/// token, which is consumed on transmission and given back after `t_off`
+/// scaled by the access category factor.
+#[derive(Debug)]
+pub struct Reactive {
+    /// Current state.
+    state: State,
+    /// Last transmission over-the-air duration.
+    last_tx_duration: Duration,
+    /// Instant at which the gate opens.
+    gate_open_at: Instant,
+    /// Tokens of each access category.
+    tokens: [Token; 4],
+    /// History of CBR values.
+    cbr_hist: HistoryBuf<f64, CBR_HISTORY_SIZE>,
+    /// Reactive algorithm parameters.
+    params: Parameters,
+    /// Instant at which reschedule the state machine evaluation.
+    next_run_at: Instant,
+    /// Instant at which the CBR value should be read.
+    next_cbr_read_at: Instant,
+}
+
+impl Reactive {
+    /// Constructs a new reactive DCC with provided `params`.
+    pub fn new(params: Parameters) -> Self {
+        Reactive {
+            state: State::Relaxed,
+            last_tx_duration: Duration::ZERO,
+            gate_open_at: Instant::ZERO,
+            tokens: Default::default(),
+            cbr_hist: HistoryBuf::new(),
+            params,
+            next_run_at: Instant::ZERO,
+            next_cbr_read_at: Instant::ZERO,
+        }
+    }
+
+    /// Return the current state.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Return the number of transmissions done for the `prio` access category.
+    pub fn tx_count(&self, prio: AccessCategory) -> u64 {
+        self.tokens[Self::ac_index(prio)].consumed
+    }
+
+    /// Return the total over-the-air duration of transmissions done for the
+    /// `prio` access category.
+    pub fn tx_airtime(&self, prio: AccessCategory) -> Duration {
+        self.tokens[Self::ac_index(prio)].airtime
+    }
+
+    /// Map an access category to its token index.
+    fn ac_index(prio: AccessCategory) -> usize {
+        match prio {
+            AccessCategory::Background => 0,
+            AccessCategory::BestEffort | AccessCategory::Unknown(_) => 1,
+            AccessCategory::Video => 2,
+            AccessCategory::Voice => 3,
+        }
+    }
+
+    /// Return the state matching the `cbr` value.
+    fn state_for(&self, cbr: f64) -> State {
+        let idx = self
+            .params
+            .cbr_thresholds
+            .iter()
+            .take_while(|t| cbr >= **t)
+            .count();
+        State::ALL[idx]
+    }
+
+    /// Return `t_off` for the current state, scaled with the
+    /// duration of the last transmission.
+    fn t_off(&self) -> Duration {
+        let t_off = self.params.t_off[self.state.index()];
+        let t_on_ref = self.params.t_on_ref.total_micros();
+
+        if t_on_ref == 0 || self.last_tx_duration.total_micros() <= t_on_ref {
+            return t_off;
+        }
+
+        let scale = self.last_tx_duration.total_micros() as f64 / t_on_ref as f64;
+        let max = self.params.t_off[State::Restrictive.index()];
+        Duration::from_micros((t_off.total_micros() as f64 * scale) as u64).min(max)
+    }
+
+    /// Computes the mean value of the `count` most recent CBR values.
+    fn recent_cbr_average(&self, count: usize) -> f64 {
+        let count = count.min(self.cbr_hist.len());
+        if count == 0 {
+            return 0.0;
+        }
+
+        let skip = self.cbr_hist.len() - count;
+        let sum: f64 = self.cbr_hist.oldest_ordered().skip(skip).sum();
+        sum / count as f64
+    }
+
+    /// Computes the maximum CBR value inside `cbr_hist`.
+    fn cbr_hist_max(&self) -> f64 {
+        self.cbr_hist.iter().copied().fold(0.0, f64::max)
+    }
+}
+
+impl RateController for Reactive {
+    /// Evaluate the reactive state machine once. The state moves up as soon as
+    /// the CBR averaged over 200ms crosses a threshold, and moves down only
+    /// when the CBR stayed below the threshold for 1s.
+    fn run(&mut self, timestamp: Instant) {
+        if timestamp < self.next_run_at {
+            return;
+        }
+
+        let up = self.state_for(self.recent_cbr_average(CBR_UP_SAMPLES));
+        if up > self.state {
+            self.state = up;
+        } else if self.cbr_hist.len() == self.cbr_hist.capacity() {
+            let down = self.state_for(self.cbr_hist_max());
+            if down < self.state {
+                self.state = down;
+            }
+        }
+
+        self.next_run_at = timestamp + self.params.cbr_interval;
+    }
+
+    fn run_at(&self) -> Instant {
+        self.next_run_at.min(self.next_cbr_read_at)
+    }
+
+    fn tx_allowed_at(&self, prio: Option<AccessCategory>) -> Instant {
+        match prio {
+            Some(p) => self
+                .gate_open_at
+                .max(self.tokens[Self::ac_index(p)].available_at),
+            None => self
+                .tokens
+                .iter()
+                .map(|t| self.gate_open_at.max(t.available_at))
+                .min()
+                .unwrap_or(self.gate_open_at),
+        }
+    }
+
+    fn tx_interval(&self) -> Duration {
+        self.t_off()
+    }
+
+    fn notify_tx(&mut self, tx_at: Instant, duration: Duration, prio: AccessCategory) {
+        self.last_tx_duration = duration;
+
+        let t_off = self.t_off();
+        let idx = Self::ac_index(prio);
+        let factor = self.params.ac_factors[idx];
+
+        self.gate_open_at = tx_at + t_off;
+
+        let token = &mut self.tokens[idx];
+        token.available_at = tx_at + t_off * factor;
+        token.consumed += 1;
+        token.airtime += duration;
+    }
+
+    fn update_cbr(&mut self, timestamp: Instant, cbr: ChannelBusyRatio) {
+        if timestamp < self.next_cbr_read_at {
+            return;
+        }
+
+        self.cbr_hist.write(cbr.as_ratio());
+        self.next_cbr_read_at = timestamp + self.params.cbr_interval;
+    }
+
+    fn local_cbr(&self) -> ChannelBusyRatio {
+        match self.cbr_hist.recent() {
+            Some(cbr) => ChannelBusyRatio::from_ratio(*cbr),
+            None => ChannelBusyRatio::from_ratio(0.0),
+        }
+    }
+
+    /// Returns the CBR value at which the restrictive state is entered.
+    fn target_cbr(&self) -> ChannelBusyRatio {
+        ChannelBusyRatio::from_ratio(self.params.cbr_thresholds[STATE_COUNT - 2])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn feed(reactive: &mut Reactive, timestamp: &mut Instant, cbr: f64, count: usize) {
+        for _ in 0..count {
+            *timestamp += Duration::from_millis(100);
+            reactive.update_cbr(*timestamp, ChannelBusyRatio::from_ratio(cbr));
+            reactive.run(*timestamp);
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let reactive = Reactive::new(Default::default());
+        assert_eq!(reactive.state(), State::Relaxed);
+        assert_eq!(reactive.tx_interval(), Duration::from_millis(60));
+        assert_eq!(reactive.tx_allowed_at(None), Instant::ZERO);
+    }
+
+    #[test]
+    fn test_state_up_and_down() {
+        let mut reactive = Reactive::new(Default::default());
+        let mut timestamp = Instant::from_secs(10);
+
+        feed(&mut reactive, &mut timestamp, 0.45, 2);
+        assert_eq!(reactive.state(), State::Active2);
+
+        feed(&mut reactive, &mut timestamp, 0.8, 2);
+        assert_eq!(reactive.state(), State::Restrictive);
+        assert_eq!(reactive.tx_interval(), Duration::from_secs(1));
+
+        // Stays restrictive until the CBR is low during 1s.
+        feed(&mut reactive, &mut timestamp, 0.1, 9);
+        assert_eq!(reactive.state(), State::Restrictive);
+
+        feed(&mut reactive, &mut timestamp, 0.1, 1);
+        assert_eq!(reactive.state(), State::Relaxed);
+    }
+
+    #[test]
+    fn test_per_access_category_gate() {
+        let mut reactive = Reactive::new(Default::default());
+        let tx_at = Instant::from_secs(10);
+
+        reactive.notify_tx(
+            tx_at,
+            Duration::from_micros(300),
+            AccessCategory::Background,
+        );
+
+        let gate = tx_at + Duration::from_millis(60);
+        assert_eq!(reactive.tx_allowed_at(Some(AccessCategory::Voice)), gate);
+        assert_eq!(reactive.tx_allowed_at(None), gate);
+        assert_eq!(
+            reactive.tx_allowed_at(Some(AccessCategory::Background)),
+            tx_at + Duration::from_millis(120)
+        );
+        assert_eq!(reactive.tx_count(AccessCategory::Background), 1);
+        assert_eq!(reactive.tx_count(AccessCategory::Voice), 0);
+    }
+
+    #[test]
+    fn test_long_packet_scaling() {
+        let mut reactive = Reactive::new(Default::default());
+        let tx_at = Instant::from_secs(10);
+
+        reactive.notify_tx(tx_at, Duration::from_millis(1), AccessCategory::BestEffort);
+        assert_eq!(reactive.tx_interval(), Duration::from_millis(120));
+        assert_eq!(
+            reactive.tx_allowed_at(Some(AccessCategory::BestEffort)),
+            tx_at + Duration::from_millis(120)
+        );
+    }
+}
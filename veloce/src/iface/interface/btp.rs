@@ -4,8 +4,11 @@ use crate::{
     socket::{btp::Indication as BtpIndication, *},
 };
 
+#[cfg(any(feature = "socket-btp-a", feature = "socket-btp-b"))]
+use crate::wire::GeonetVariant;
+
 #[cfg(feature = "socket-btp-a")]
-use crate::wire::{BtpAHeader, BtpARepr, GeonetVariant};
+use crate::wire::{BtpAHeader, BtpARepr};
 
 #[cfg(feature = "socket-btp-b")]
 use crate::wire::{BtpBHeader, BtpBRepr};
@@ -17,7 +20,7 @@ impl InterfaceInner {
     /// Processes a BTP-A packet.
     pub(super) fn process_btp_a(
         &mut self,
-        srv: &InterfaceContext,
+        srv: &mut InterfaceContext,
         sockets: &mut SocketSet,
         ind: Indication,
         _handled_by_geonet_socket: bool,
@@ -41,6 +44,8 @@ impl InterfaceInner {
             its_aid: ind.its_aid,
             #[cfg(feature = "proto-security")]
             cert_id: ind.cert_id,
+            #[cfg(feature = "proto-security")]
+            confidential: ind.confidential,
             rem_lifetime: ind.rem_lifetime,
             rem_hop_limit: ind.rem_hop_limit,
             traffic_class: ind.traffic_class,
@@ -51,7 +56,9 @@ impl InterfaceInner {
             .filter_map(|i| btp::SocketA::downcast_mut(&mut i.socket))
         {
             if btp_socket.accepts(self, srv, uc_repr, &btp_a_repr) {
-                btp_socket.process(self, srv, btp_ind, uc_repr, &btp_a_repr, payload);
+                if self.consume(srv, packet) {
+                    btp_socket.process(self, srv, btp_ind, uc_repr, &btp_a_repr, payload);
+                }
                 return;
             }
         }
@@ -61,10 +68,11 @@ impl InterfaceInner {
     /// Processes a BTP-B packet.
     pub(super) fn process_btp_b(
         &mut self,
-        srv: &InterfaceContext,
+        srv: &mut InterfaceContext,
         sockets: &mut SocketSet,
         ind: Indication,
         _handled_by_geonet_socket: bool,
+        packet: &GeonetVariant,
         payload: &[u8],
     ) {
         let btp_b = check!(BtpBHeader::new_checked(payload));
@@ -79,6 +87,8 @@ impl InterfaceInner {
             its_aid: ind.its_aid,
             #[cfg(feature = "proto-security")]
             cert_id: ind.cert_id,
+            #[cfg(feature = "proto-security")]
+            confidential: ind.confidential,
             rem_lifetime: ind.rem_lifetime,
             rem_hop_limit: ind.rem_hop_limit,
             traffic_class: ind.traffic_class,
@@ -89,7 +99,9 @@ impl InterfaceInner {
             .filter_map(|i| btp::SocketB::downcast_mut(&mut i.socket))
         {
             if btp_socket.accepts(self, srv, &btp_b_repr) {
-                btp_socket.process(self, srv, btp_ind, payload);
+                if self.consume(srv, packet) {
+                    btp_socket.process(self, srv, btp_ind, payload);
+                }
                 return;
             }
         }
@@ -100,7 +112,9 @@ impl InterfaceInner {
             .filter_map(|i| cam::Socket::downcast_mut(&mut i.socket))
         {
             if cam_socket.accepts(self, srv, &btp_b_repr) {
-                cam_socket.process(self, srv, btp_ind, payload);
+                if self.consume(srv, packet) {
+                    cam_socket.process(self, srv, btp_ind, payload);
+                }
                 return;
             }
         }
@@ -111,7 +125,9 @@ impl InterfaceInner {
             .filter_map(|i| denm::Socket::downcast_mut(&mut i.socket))
         {
             if denm_socket.accepts(self, srv, &btp_b_repr) {
-                denm_socket.process(self, srv, btp_ind, payload);
+                if self.consume(srv, packet) {
+                    denm_socket.process(self, srv, btp_ind, payload);
+                }
                 return;
             }
         }
@@ -1,10 +1,15 @@
+use core::cmp::Reverse;
+
 use crate::{
     config::{self, GnAreaForwardingAlgorithm, GnNonAreaForwardingAlgorithm},
     iface::{congestion::CongestionError, location_table::LocationTable},
     network::GnCore,
     phy::{ChannelBusyRatio, Device, Medium, TxToken},
     time::{Duration, Instant},
-    wire::{EthernetAddress, EthernetFrame, EthernetProtocol, GeonetRepr, GeonetVariant},
+    wire::{
+        ieee80211::AccessCategory, EthernetAddress, EthernetFrame, EthernetProtocol, GeonetRepr,
+        GeonetVariant,
+    },
 };
 
 #[cfg(feature = "medium-ieee80211p")]
@@ -25,13 +30,19 @@ pub enum CongestionControl {
     Limeric,
     /// Congestion control backed by Limeric with Dual Alpha algorithm.
     LimericDualAlpha,
+    /// Congestion control backed by the reactive algorithm.
+    Reactive,
 }
 
 impl Interface {
     /// Set an algorithm for congestion control.
     ///
     /// `CongestionControl::None` indicates that no congestion control is applied.
-    /// Options `CongestionControl::Limeric` and `CongestionControl::LimericDualAlpha` are also available.
+    /// Options `CongestionControl::Limeric`, `CongestionControl::LimericDualAlpha` and
+    /// `CongestionControl::Reactive` are also available.
+    ///
+    /// The algorithm can be changed at run-time. Packets waiting in the congestion control
+    /// queues are kept and released according to the new algorithm.
     pub fn set_congestion_control(&mut self, congestion_control: CongestionControl) {
         use crate::iface::congestion::*;
 
@@ -46,9 +57,12 @@ impl Interface {
                 lim.enable_dual_alpha(Default::default());
                 AnyController::Limeric(lim)
             }
+            CongestionControl::Reactive => {
+                AnyController::Reactive(reactive::Reactive::new(Default::default()))
+            }
         };
 
-        self.congestion_control = Congestion::new(controller);
+        self.congestion_control.set_controller(controller);
     }
 
     /// Return the current congestion control algorithm.
@@ -64,9 +78,28 @@ impl Interface {
                     CongestionControl::Limeric
                 }
             }
+            AnyController::Reactive(_) => CongestionControl::Reactive,
         }
     }
 
+    /// Return the allowed duration between two transmissions,
+    /// as computed by the congestion control algorithm.
+    pub fn congestion_tx_interval(&self) -> Duration {
+        self.congestion_control.tx_interval()
+    }
+
+    /// Return the number of packets waiting for transmission in the congestion
+    /// control queue of `access_category`.
+    pub fn congestion_backlog(&self, access_category: AccessCategory) -> usize {
+        self.congestion_control.backlog(access_category)
+    }
+
+    /// Return the number of packets waiting for transmission in all the congestion
+    /// control queues.
+    pub fn congestion_total_backlog(&self) -> usize {
+        self.congestion_control.total_backlog()
+    }
+
     /// Runs the congestion control algorithm.
     pub(crate) fn run_congestion_control(&mut self, timestamp: Instant, cbr: ChannelBusyRatio) {
         let rc = self.congestion_control.controller.inner_mut();
@@ -94,21 +127,27 @@ impl Interface {
         } */
 
         let rc = loop {
-            // Find the queue with the smallest delay.
+            // Find the queue with the smallest delay, and the highest priority on equal delays.
             let queue_opt = trc
                 .queues
                 .iter_mut()
                 .filter(|e| !e.1.is_empty())
-                .map(|e| (e.1, trc.controller.inner().tx_allowed_at(Some(*e.0))))
-                .min_by_key(|k| k.1);
+                .map(|e| (*e.0, e.1, trc.controller.inner().tx_allowed_at(Some(*e.0))))
+                .min_by_key(|k| (k.2, Reverse(k.0)));
 
-            let Some((q, _)) = queue_opt else {
+            let Some((cat, q, allowed_at)) = queue_opt else {
                 // Nothing to transmit. If we get here, this means trc.egress_at has some value.
                 // Set it to None to avoid rescheduling for egress with empty queues.
                 trc.egress_at = None;
                 return false;
             };
 
+            // Queue gate is still closed.
+            if allowed_at > core.now {
+                trc.egress_at = Some(allowed_at);
+                return false;
+            }
+
             let res = q.dequeue_one(|node| {
                 let tx_token = device
                     .transmit(core.now)
@@ -134,14 +173,31 @@ impl Interface {
                     return Err(CongestionError::CbfDuplicate);
                 }
 
+                // Packets queued before a silent period started still carry the ego position
+                // vector, drop them.
+                #[cfg(feature = "proto-security")]
+                if core.is_silent()
+                    && gn_pkt.repr().inner().source_address().mac_addr()
+                        == core.address().mac_addr()
+                {
+                    return Err(CongestionError::SilentPeriod);
+                }
+
                 self.inner
                     .dispatch_congestion_control(tx_token, core, dst_hw_addr, gn_pkt)
             });
 
             match res {
-                Some(Ok(l)) => break Some(l),
+                Some(Ok(l)) => break Some((l, cat)),
                 Some(Err(CongestionError::CbfDuplicate)) => {
                     net_debug!("skipping DCC buffered packet: duplicate packet");
+                    trc.stats.dropped += 1;
+                    continue;
+                }
+                #[cfg(feature = "proto-security")]
+                Some(Err(CongestionError::SilentPeriod)) => {
+                    net_debug!("skipping DCC buffered packet: silent period");
+                    trc.stats.dropped += 1;
                     continue;
                 }
                 Some(Err(CongestionError::Exhausted)) => {
@@ -151,13 +207,15 @@ impl Interface {
                 _ => break None,
             }
         }
-        .is_some_and(|total_len| {
+        .is_some_and(|(total_len, cat)| {
             // G5 bandwidth is 6 Mbps.
             let bytes_per_usec: f64 = 6.144 / 8.0;
             let tx_duration_usec = bytes_per_usec * total_len as f64;
-            trc.controller
-                .inner_mut()
-                .notify_tx(core.now, Duration::from_micros(tx_duration_usec as u64));
+            trc.controller.inner_mut().notify_tx(
+                core.now,
+                Duration::from_micros(tx_duration_usec as u64),
+                cat,
+            );
             true
         });
 
@@ -208,7 +266,7 @@ impl InterfaceInner {
     pub(super) fn dispatch_congestion_control<Tx: TxToken>(
         &mut self,
         mut tx_token: Tx,
-        _: &mut GnCore,
+        core: &mut GnCore,
         dst_hw_addr: EthernetAddress,
         packet: GeonetPacket,
     ) -> Result<usize, CongestionError> {
@@ -310,23 +368,25 @@ impl InterfaceInner {
         };
 
         tx_token.set_meta(Default::default());
-        tx_token.consume(total_len, |mut tx_buffer| {
-            #[cfg(feature = "medium-ethernet")]
-            if matches!(caps.medium, Medium::Ethernet) {
-                emit_ethernet(tx_buffer);
-                tx_buffer = &mut tx_buffer[EthernetFrame::<&[u8]>::header_len()..];
-            }
+        tx_token
+            .consume(total_len, |mut tx_buffer| {
+                #[cfg(feature = "medium-ethernet")]
+                if matches!(caps.medium, Medium::Ethernet) {
+                    emit_ethernet(tx_buffer);
+                    tx_buffer = &mut tx_buffer[EthernetFrame::<&[u8]>::header_len()..];
+                }
 
-            #[cfg(feature = "medium-ieee80211p")]
-            if matches!(caps.medium, Medium::Ieee80211p) {
-                emit_ieee80211(tx_buffer);
-                let pl_start =
-                    Ieee80211Frame::<&[u8]>::header_len() + LlcFrame::<&[u8]>::header_len();
-                tx_buffer = &mut tx_buffer[pl_start..];
-            }
+                #[cfg(feature = "medium-ieee80211p")]
+                if matches!(caps.medium, Medium::Ieee80211p) {
+                    emit_ieee80211(tx_buffer);
+                    let pl_start =
+                        Ieee80211Frame::<&[u8]>::header_len() + LlcFrame::<&[u8]>::header_len();
+                    tx_buffer = &mut tx_buffer[pl_start..];
+                }
 
-            emit_gn(gn_repr, tx_buffer);
-            Ok(total_len)
-        })
+                emit_gn(gn_repr, tx_buffer);
+                Ok(total_len)
+            })
+            .inspect(|_| self.count_tx(core, gn_repr.inner()))
     }
 }
@@ -41,4 +41,15 @@ impl InterfaceInner {
             _ => None,
         }
     }
+
+    /// Returns the Geonetworking packet carried by the Ethernet `frame`, if any.
+    #[cfg(feature = "proto-security")]
+    pub(super) fn ethernet_geonet_packet(frame: &[u8]) -> Option<&[u8]> {
+        let eth_frame = EthernetFrame::new_checked(frame).ok()?;
+
+        match eth_frame.ethertype() {
+            EthernetProtocol::Geonet => Some(eth_frame.payload()),
+            _ => None,
+        }
+    }
 }
@@ -2,7 +2,7 @@ use uom::si::angle::{degree, radian};
 use uom::si::f64::{Angle, Length};
 use uom::si::length::meter;
 
-use crate::iface::location_table::DuplicatePacketListEntry;
+use crate::iface::location_table::{DuplicatePacketListEntry, LocationTable, LocationTableEntry};
 #[cfg(feature = "medium-ieee80211p")]
 use crate::{iface::location_table::LocationTableG5Extension, phy::Medium, wire::G5Extension};
 
@@ -13,7 +13,7 @@ use crate::config::{
 };
 use crate::iface::location_service::LocationServiceRequest;
 use crate::iface::packet::GeonetPacket;
-use crate::iface::{Congestion, SocketSet};
+use crate::iface::{Congestion, DropReason, SocketSet};
 use crate::network::{
     GeoAnycastReqMeta, GeoBroadcastReqMeta, GnCore, Indication, SingleHopReqMeta,
     TopoScopedReqMeta, UnicastReqMeta, UpperProtocol,
@@ -26,7 +26,7 @@ use crate::wire::{
     HardwareAddress,
 };
 use crate::{
-    common::geo_area::{DistanceAB, GeoArea, Shape},
+    common::geo_area::{DistanceAB, GeoArea, GeoPosition, Shape},
     config::{
         GnAreaForwardingAlgorithm, GnNonAreaForwardingAlgorithm, GN_AREA_FORWARDING_ALGORITHM,
         GN_BEACON_SERVICE_MAX_JITTER, GN_BEACON_SERVICE_RETRANSMIT_TIMER, GN_DEFAULT_HOP_LIMIT,
@@ -51,28 +51,39 @@ use crate::{
 };
 
 #[cfg(feature = "proto-security")]
-use crate::security::permission::Permission;
+use crate::{
+    security::{permission::Permission, secured_message::SecuredMessage},
+    wire::{geonet::PositionVectorTimestamp, GnAddress},
+};
 
 use super::{check, next_sequence_number, InterfaceContext, InterfaceInner, SecuredDataBuffer};
 
+#[cfg(feature = "proto-security")]
+use super::DecapContext;
+
 impl InterfaceInner {
     /// Defer the beaconing retransmission if the packet source address is ours.
     pub(super) fn defer_beacon(&mut self, core: &mut GnCore, gn_repr: &GeonetVariant) -> bool {
         let deferred =
             gn_repr.source_position_vector().address.mac_addr() == core.address().mac_addr();
         if deferred {
-            let rand_jitter = Duration::from_millis(
-                core.rand
-                    .rand_range(0..=GN_BEACON_SERVICE_MAX_JITTER.millis() as u32)
-                    .into(),
-            );
-
-            self.retransmit_beacon_at = core.now + GN_BEACON_SERVICE_RETRANSMIT_TIMER + rand_jitter;
+            self.rearm_beacon_timer(core);
         }
 
         deferred
     }
 
+    /// Schedule the next beacon transmission after the retransmit timer and a random jitter.
+    fn rearm_beacon_timer(&mut self, core: &mut GnCore) {
+        let rand_jitter = Duration::from_millis(
+            core.rand
+                .rand_range(0..=GN_BEACON_SERVICE_MAX_JITTER.millis() as u32)
+                .into(),
+        );
+
+        self.retransmit_beacon_at = core.now + GN_BEACON_SERVICE_RETRANSMIT_TIMER + rand_jitter;
+    }
+
     /// Processes a Geonetworking packet.
     pub(crate) fn process_geonet_packet<'packet, 'ctx>(
         &mut self,
@@ -105,8 +116,8 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let bh = check!(BasicHeader::new_checked(packet));
-        let bh_repr = check!(BasicHeaderRepr::parse(&bh));
+        let bh = check!(self.stats, BasicHeader::new_checked(packet));
+        let bh_repr = check!(self.stats, BasicHeaderRepr::parse(&bh));
 
         // Check Geonetworking protocol version.
         if bh_repr.version != GN_PROTOCOL_VERSION {
@@ -116,6 +127,7 @@ impl InterfaceInner {
                 bh_repr.version,
                 GN_PROTOCOL_VERSION
             );
+            self.stats.dropped.count(DropReason::Version);
             return None;
         }
 
@@ -125,6 +137,7 @@ impl InterfaceInner {
                 #[cfg(feature = "proto-security")]
                 if ctx.core.security.is_some() {
                     net_trace!("network: unsecured packet received");
+                    self.stats.dropped.count(DropReason::Unsecured);
                     return None;
                 }
                 self.process_common_header(ctx, sockets, meta, bh_repr, packet, link_layer)
@@ -135,10 +148,12 @@ impl InterfaceInner {
             #[cfg(not(feature = "proto-security"))]
             BHNextHeader::SecuredHeader => {
                 net_trace!("network: secured header not supported");
+                self.stats.dropped.count(DropReason::UnsupportedHeader);
                 None
             }
             BHNextHeader::Unknown(_) => {
                 net_trace!("network: unknown basic header next header field value");
+                self.stats.dropped.count(DropReason::UnsupportedHeader);
                 None
             }
         }
@@ -160,10 +175,11 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let timestamp = ctx.core.timestamp();
+        let timestamp = ctx.core.clock_timestamp();
 
         let Some(sec) = &mut ctx.core.security else {
             net_trace!("network: no security service available");
+            self.stats.dropped.count(DropReason::Security);
             return None;
         };
 
@@ -174,6 +190,7 @@ impl InterfaceInner {
             }
             Err(e) => {
                 net_trace!("network: security decap failure: {}", e);
+                self.stats.dropped.count(DropReason::Security);
                 return None;
             }
         };
@@ -181,6 +198,21 @@ impl InterfaceInner {
         self.process_common_header(ctx, sockets, meta, bh_repr, &sec_buf.buffer, link_layer)
     }
 
+    /// Returns the secured message carried by the Geonetworking `packet`, if any.
+    #[cfg(feature = "proto-security")]
+    pub(super) fn secured_message(packet: &[u8]) -> Option<SecuredMessage> {
+        let bh = BasicHeader::new_checked(packet).ok()?;
+        let bh_repr = BasicHeaderRepr::parse(&bh).ok()?;
+
+        if bh_repr.version != GN_PROTOCOL_VERSION
+            || bh_repr.next_header != BHNextHeader::SecuredHeader
+        {
+            return None;
+        }
+
+        SecuredMessage::from_bytes(bh.payload()).ok()
+    }
+
     /// Processes the Common Header of a Geonetworking packet.
     fn process_common_header<'packet, 'ctx>(
         &mut self,
@@ -195,8 +227,8 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let ch = check!(CommonHeader::new_checked(packet));
-        let ch_repr = check!(CommonHeaderRepr::parse(&ch));
+        let ch = check!(self.stats, CommonHeader::new_checked(packet));
+        let ch_repr = check!(self.stats, CommonHeaderRepr::parse(&ch));
         let timestamp = ctx.core.now;
 
         // Step 1: check the MHL field.
@@ -205,9 +237,12 @@ impl InterfaceInner {
                 "network: malformed {}",
                 stringify!(ch_repr.max_hop_limit < bh_repr.remaining_hop_limit)
             );
+            self.stats.dropped.count(DropReason::HopLimit);
             return None;
         }
 
+        self.stats.rx.count(ch_repr.header_type);
+
         // Step 2: process the BC forwarding packet buffer
         ctx.bc_forwarding_buffer.mark_flush(timestamp, |_| true);
 
@@ -215,6 +250,7 @@ impl InterfaceInner {
         match ch_repr.header_type {
             GeonetPacketType::Any => {
                 net_trace!("network: discard 'Any' packet type");
+                self.stats.dropped.count(DropReason::UnsupportedHeader);
                 None
             }
             GeonetPacketType::Beacon => {
@@ -247,15 +283,17 @@ impl InterfaceInner {
             }
             GeonetPacketType::Unknown(u) => {
                 net_trace!("network: discard 'Unknown={}' packet type", u);
+                self.stats.dropped.count(DropReason::UnsupportedHeader);
                 None
             }
         }
     }
 
     /// Processes a Beaconing packet.
+    #[allow(unused_mut)]
     fn process_beacon<'packet, 'ctx>(
         &mut self,
-        ctx: InterfaceContext<'ctx>,
+        mut ctx: InterfaceContext<'ctx>,
         bh_repr: BasicHeaderRepr,
         ch_repr: CommonHeaderRepr,
         packet: &'packet [u8],
@@ -265,8 +303,8 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let beacon = check!(BeaconHeader::new_checked(packet));
-        let beacon_repr = check!(BeaconHeaderRepr::parse(&beacon));
+        let beacon = check!(self.stats, BeaconHeader::new_checked(packet));
+        let beacon_repr = check!(self.stats, BeaconHeaderRepr::parse(&beacon));
         let timestamp = ctx.core.now;
 
         // TODO: check if payload length is 0.
@@ -278,10 +316,19 @@ impl InterfaceInner {
                 self.hardware_addr = HardwareAddress::from(*addr);
             });
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, beacon_repr.src_addr());
+
         /* Step 4: update Location table */
-        let entry = self
-            .location_table
-            .update_mut(timestamp, &beacon_repr.source_position_vector);
+        let Some(entry) = update_location_table(
+            &mut self.location_table,
+            &mut ctx,
+            &beacon_repr.source_position_vector,
+        ) else {
+            self.stats.dropped.count(DropReason::Security);
+            return None;
+        };
 
         /* Step 5: update PDR in Location table */
         #[cfg(not(feature = "proto-security"))]
@@ -296,12 +343,18 @@ impl InterfaceInner {
         entry.update_pdr(packet_size, timestamp);
 
         /* Step 6: set `is_neighbour` flag in Location table */
+        #[cfg(feature = "proto-security")]
+        if !entry.is_neighbour {
+            if let Some(sec) = ctx.core.security.as_mut() {
+                sec.notify_new_neighbour();
+            }
+        }
         entry.is_neighbour = true;
 
         /* Step 7: Do nothing */
 
         /* Step 8: flush location service and unicast buffers for this packet source address */
-        if let Some(handle) = entry.ls_pending {
+        if let Some(handle) = entry.ls_pending.take() {
             ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                 packet_node.metadata().inner().dst_addr().mac_addr()
                     == beacon_repr.src_addr().mac_addr()
@@ -332,23 +385,37 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let ls_req = check!(LocationServiceRequestHeader::new_checked(packet));
-        let ls_req_repr = check!(LocationServiceRequestRepr::parse(&ls_req));
+        let ls_req = check!(
+            self.stats,
+            LocationServiceRequestHeader::new_checked(packet)
+        );
+        let ls_req_repr = check!(self.stats, LocationServiceRequestRepr::parse(&ls_req));
         let timestamp = ctx.core.now;
 
         // TODO: check if there are no bytes following the LS Request header.
 
+        /* Check if we are the sender of the packet */
+        if ls_req_repr.src_addr() == ctx.core.address() {
+            self.stats.dropped.count(DropReason::Loopback);
+            return None;
+        }
+
         /* Steps 3 to 6 are equal on both destination and forwarder operations */
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, ls_req_repr.src_addr());
+
         /* Step 3: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
-            ls_req_repr.request_address,
+            ls_req_repr.src_addr(),
             ls_req_repr.sequence_number,
             timestamp,
         );
 
         if dup_opt.is_some_and(|x| x) {
             /* Packet is duplicate, discard packet. */
+            self.stats.duplicates += 1;
             return None;
         }
 
@@ -359,10 +426,22 @@ impl InterfaceInner {
                 self.hardware_addr = HardwareAddress::from(*addr);
             });
 
+        /* Packet is always consumed, verify it before updating the Location Table. */
+        #[cfg(feature = "proto-security")]
+        if !ctx.verify_on_demand() {
+            self.stats.dropped.count(DropReason::Security);
+            return None;
+        }
+
         /* Step 5-6: add/update location table */
-        let entry = self
-            .location_table
-            .update_mut(timestamp, &ls_req_repr.source_position_vector);
+        let Some(entry) = update_location_table(
+            &mut self.location_table,
+            &mut ctx,
+            &ls_req_repr.source_position_vector,
+        ) else {
+            self.stats.dropped.count(DropReason::Security);
+            return None;
+        };
 
         /* Add received packet sequence number to the duplicate packet list */
         if dup_opt.is_none() {
@@ -389,11 +468,11 @@ impl InterfaceInner {
             let reply_bh_repr = BasicHeaderRepr {
                 version: GN_PROTOCOL_VERSION,
                 #[cfg(feature = "proto-security")]
-                next_header: ctx
-                    .core
-                    .security
-                    .as_ref()
-                    .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+                next_header: if ctx.core.signing_enabled() {
+                    BHNextHeader::SecuredHeader
+                } else {
+                    BHNextHeader::CommonHeader
+                },
                 #[cfg(not(feature = "proto-security"))]
                 next_header: BHNextHeader::CommonHeader,
                 lifetime: GN_DEFAULT_PACKET_LIFETIME,
@@ -421,6 +500,7 @@ impl InterfaceInner {
                 GeonetRepr::ToSecure {
                     repr: repr.into(),
                     permission: Permission::GnMgmt,
+                    confidential: false,
                 }
             } else {
                 GeonetRepr::Unsecured(repr.into())
@@ -446,7 +526,7 @@ impl InterfaceInner {
             /* Step 8: Flush packets inside Location Service and Unicast forwarding buffers
             that are destined to the source of the incoming Location Service Request packet. */
             /* Step 8a */
-            if let Some(handle) = entry.ls_pending {
+            if let Some(handle) = entry.ls_pending.take() {
                 ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                     packet_node.metadata().inner().dst_addr().mac_addr()
                         == ls_req_repr.src_addr().mac_addr()
@@ -465,6 +545,7 @@ impl InterfaceInner {
             /* Step 9: decrement Remaining Hop limit */
             if bh_repr.remaining_hop_limit == 0 {
                 /* Remaining Hop Limit is reached, discard packet. */
+                self.stats.dropped.count(DropReason::HopLimit);
                 return None;
             }
 
@@ -486,7 +567,8 @@ impl InterfaceInner {
             /* Step 12: Return packet. */
             let packet = GeonetLocationServiceRequest::new(reply_bh_repr, ch_repr, ls_req_repr);
             #[cfg(feature = "proto-security")]
-            let packet = super::to_gn_repr(packet.into(), ctx.decap_context);
+            let packet =
+                self.to_gn_repr(packet.into(), &mut ctx, ls_req_repr.src_addr().mac_addr())?;
             #[cfg(not(feature = "proto-security"))]
             let packet = GeonetRepr::Unsecured(packet.into());
 
@@ -499,9 +581,10 @@ impl InterfaceInner {
     }
 
     /// Processes a Location Service reply packet.
+    #[allow(unused_mut)]
     fn process_ls_reply<'packet, 'ctx>(
         &mut self,
-        ctx: InterfaceContext<'ctx>,
+        mut ctx: InterfaceContext<'ctx>,
         bh_repr: BasicHeaderRepr,
         ch_repr: CommonHeaderRepr,
         packet: &'packet [u8],
@@ -511,8 +594,8 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let ls_rep = check!(LocationServiceReplyHeader::new_checked(packet));
-        let ls_rep_repr = check!(LocationServiceReplyRepr::parse(&ls_rep));
+        let ls_rep = check!(self.stats, LocationServiceReplyHeader::new_checked(packet));
+        let ls_rep_repr = check!(self.stats, LocationServiceReplyRepr::parse(&ls_rep));
         let timestamp = ctx.core.now;
 
         // TODO: check if there are no bytes following the LS Reply header.
@@ -522,6 +605,10 @@ impl InterfaceInner {
             == ctx.core.address().mac_addr()
         {
             /* We are the destination. */
+            /* Save the source Location Table entry, restored if the packet is not verified. */
+            #[cfg(feature = "proto-security")]
+            self.snapshot_location_entry(&mut ctx, ls_rep_repr.src_addr());
+
             /* Step 3: duplicate packet detection */
             let dup_opt = self.location_table.duplicate_packet_detection(
                 ls_rep_repr.src_addr(),
@@ -530,6 +617,7 @@ impl InterfaceInner {
             );
 
             if dup_opt.is_some_and(|x| x) {
+                self.stats.duplicates += 1;
                 return None;
             }
 
@@ -540,10 +628,22 @@ impl InterfaceInner {
                     self.hardware_addr = HardwareAddress::from(*addr);
                 });
 
+            /* Packet is always consumed, verify it before updating the Location Table. */
+            #[cfg(feature = "proto-security")]
+            if !ctx.verify_on_demand() {
+                self.stats.dropped.count(DropReason::Security);
+                return None;
+            }
+
             /* Step 4: update Location table */
-            let entry = self
-                .location_table
-                .update_mut(timestamp, &ls_rep_repr.source_position_vector);
+            let Some(entry) = update_location_table(
+                &mut self.location_table,
+                &mut ctx,
+                &ls_rep_repr.source_position_vector,
+            ) else {
+                self.stats.dropped.count(DropReason::Security);
+                return None;
+            };
 
             /* Add received packet sequence number to the duplicate packet list */
             if dup_opt.is_none() {
@@ -567,7 +667,7 @@ impl InterfaceInner {
 
             /* Step 6-7-8: Flush packets inside Location Service and Unicast forwarding buffers
             that are destined to the source of the incoming Location Service Reply packet. */
-            if let Some(handle) = entry.ls_pending {
+            if let Some(handle) = entry.ls_pending.take() {
                 ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                     packet_node.metadata().inner().dst_addr().mac_addr()
                         == ls_rep_repr.src_addr().mac_addr()
@@ -594,7 +694,7 @@ impl InterfaceInner {
     #[allow(clippy::too_many_arguments)]
     fn process_single_hop_broadcast<'packet, 'ctx>(
         &mut self,
-        ctx: InterfaceContext<'ctx>,
+        mut ctx: InterfaceContext<'ctx>,
         sockets: &mut SocketSet,
         meta: PacketMeta,
         bh_repr: BasicHeaderRepr,
@@ -606,14 +706,15 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let shb = check!(SingleHopHeader::new_checked(packet));
-        let shb_repr = check!(SingleHopHeaderRepr::parse(&shb));
+        let shb = check!(self.stats, SingleHopHeader::new_checked(packet));
+        let shb_repr = check!(self.stats, SingleHopHeaderRepr::parse(&shb));
         let timestamp = ctx.core.now;
 
         let packet = shb.payload();
 
         /* Check if we are the sender of the packet */
         if shb_repr.src_addr() == ctx.core.address() {
+            self.stats.dropped.count(DropReason::Loopback);
             return None;
         }
 
@@ -624,11 +725,20 @@ impl InterfaceInner {
                 self.hardware_addr = HardwareAddress::from(*addr);
             });
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, shb_repr.src_addr());
+
         let ls_pending = {
             /* Step 4: update Location table */
-            let entry = self
-                .location_table
-                .update_mut(timestamp, &shb_repr.source_position_vector);
+            let Some(entry) = update_location_table(
+                &mut self.location_table,
+                &mut ctx,
+                &shb_repr.source_position_vector,
+            ) else {
+                self.stats.dropped.count(DropReason::Security);
+                return None;
+            };
 
             /* Step 5: update PDR in Location table */
             #[cfg(not(feature = "proto-security"))]
@@ -651,6 +761,12 @@ impl InterfaceInner {
             entry.update_pdr(packet_size, timestamp);
 
             /* Step 6: set ìs_neighbour` flag in Location table */
+            #[cfg(feature = "proto-security")]
+            if !entry.is_neighbour {
+                if let Some(sec) = ctx.core.security.as_mut() {
+                    sec.notify_new_neighbour();
+                }
+            }
             entry.is_neighbour = true;
 
             /*  Update media dependent data in Location Table */
@@ -672,12 +788,12 @@ impl InterfaceInner {
                 entry.extensions = Some(extension.into());
             }
 
-            entry.ls_pending
+            entry.ls_pending.take()
         };
 
         /* Step 7: Go to upper layer */
         let gn_repr = GeonetSingleHop::new(bh_repr, ch_repr, shb_repr).into();
-        self.pass_up(&ctx, sockets, meta, &gn_repr, packet);
+        self.pass_up(&mut ctx, sockets, meta, &gn_repr, packet);
 
         /* Step 8: Flush packets inside Location Service and Unicast forwarding buffers
         that are destined to the source of the incoming SHB packet. */
@@ -703,7 +819,7 @@ impl InterfaceInner {
     #[allow(clippy::too_many_arguments)]
     fn process_topo_scoped_broadcast<'packet, 'ctx>(
         &mut self,
-        ctx: InterfaceContext<'ctx>,
+        mut ctx: InterfaceContext<'ctx>,
         sockets: &mut SocketSet,
         meta: PacketMeta,
         bh_repr: BasicHeaderRepr,
@@ -715,17 +831,22 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let tsb = check!(TopoBroadcastHeader::new_checked(packet));
-        let tsb_repr = check!(TopoBroadcastRepr::parse(&tsb));
+        let tsb = check!(self.stats, TopoBroadcastHeader::new_checked(packet));
+        let tsb_repr = check!(self.stats, TopoBroadcastRepr::parse(&tsb));
 
         let payload = tsb.payload();
         let timestamp = ctx.core.now;
 
         /* Check if we are the sender of the packet */
         if tsb_repr.src_addr() == ctx.core.address() {
+            self.stats.dropped.count(DropReason::Loopback);
             return None;
         }
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, tsb_repr.src_addr());
+
         /* Step 3: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
             tsb_repr.src_addr(),
@@ -734,6 +855,7 @@ impl InterfaceInner {
         );
 
         if dup_opt.is_some_and(|x| x) {
+            self.stats.duplicates += 1;
             return None;
         }
 
@@ -746,9 +868,14 @@ impl InterfaceInner {
 
         let ls_pending = {
             /* Step 5-6: update Location table */
-            let entry = self
-                .location_table
-                .update_mut(timestamp, &tsb_repr.source_position_vector);
+            let Some(entry) = update_location_table(
+                &mut self.location_table,
+                &mut ctx,
+                &tsb_repr.source_position_vector,
+            ) else {
+                self.stats.dropped.count(DropReason::Security);
+                return None;
+            };
 
             /* Add received packet sequence number to the duplicate packet list */
             if dup_opt.is_none() {
@@ -776,12 +903,12 @@ impl InterfaceInner {
 
             entry.update_pdr(packet_size, timestamp);
 
-            entry.ls_pending
+            entry.ls_pending.take()
         };
 
         /* Step 7: Go to upper layer */
         let gn_repr = GeonetTopoBroadcast::new(bh_repr, ch_repr, tsb_repr).into();
-        self.pass_up(&ctx, sockets, meta, &gn_repr, payload);
+        self.pass_up(&mut ctx, sockets, meta, &gn_repr, payload);
 
         /* Step 8: Flush packets inside Location Service and Unicast forwarding buffers
         that are destined to the source of the incoming TSB packet. */
@@ -803,6 +930,7 @@ impl InterfaceInner {
         /* Step 9: Build packet and decrement RHL. */
         if bh_repr.remaining_hop_limit == 0 {
             /* Remaining Hop Limit is reached, discard packet. */
+            self.stats.dropped.count(DropReason::HopLimit);
             return None;
         }
 
@@ -818,13 +946,17 @@ impl InterfaceInner {
             /* Buffer the packet into the broadcast buffer */
             let buf_packet = GeonetTopoBroadcast::new(fwd_bh_repr, ch_repr, tsb_repr).into();
             #[cfg(feature = "proto-security")]
-            let metadata = super::to_gn_repr(buf_packet, ctx.decap_context);
+            let metadata = self.to_gn_repr(buf_packet, &mut ctx, tsb_repr.src_addr().mac_addr())?;
             #[cfg(not(feature = "proto-security"))]
             let metadata = GeonetRepr::Unsecured(buf_packet);
 
-            ctx.bc_forwarding_buffer
+            if ctx
+                .bc_forwarding_buffer
                 .enqueue(metadata, payload, timestamp)
-                .ok();
+                .is_err()
+            {
+                self.stats.dropped.count(DropReason::BufferFull);
+            }
 
             return None;
         }
@@ -832,7 +964,7 @@ impl InterfaceInner {
         // Packet is sent with a broadcast link layer destination address.
         let packet = GeonetTopoBroadcast::new(fwd_bh_repr, ch_repr, tsb_repr).into();
         #[cfg(feature = "proto-security")]
-        let packet = super::to_gn_repr(packet, ctx.decap_context);
+        let packet = self.to_gn_repr(packet, &mut ctx, tsb_repr.src_addr().mac_addr())?;
         #[cfg(not(feature = "proto-security"))]
         let packet = GeonetRepr::Unsecured(packet);
 
@@ -861,8 +993,8 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let uc = check!(UnicastHeader::new_checked(packet));
-        let uc_repr = check!(UnicastRepr::parse(&uc));
+        let uc = check!(self.stats, UnicastHeader::new_checked(packet));
+        let uc_repr = check!(self.stats, UnicastRepr::parse(&uc));
 
         let payload = uc.payload();
         /* Determine if we are the unicast destination */
@@ -892,6 +1024,10 @@ impl InterfaceInner {
     )> {
         let timestamp = ctx.core.now;
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, uc_repr.src_addr());
+
         /* Step 3: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
             uc_repr.src_addr(),
@@ -903,6 +1039,14 @@ impl InterfaceInner {
         if GN_NON_AREA_FORWARDING_ALGORITHM != GnNonAreaForwardingAlgorithm::Cbf
             && dup_opt.is_some_and(|x| x)
         {
+            self.stats.duplicates += 1;
+            return None;
+        }
+
+        /* Packet is always consumed, verify it before updating the Location Table. */
+        #[cfg(feature = "proto-security")]
+        if !ctx.verify_on_demand() {
+            self.stats.dropped.count(DropReason::Security);
             return None;
         }
 
@@ -942,9 +1086,14 @@ impl InterfaceInner {
             });
 
         /* Step 4: update Location table */
-        let entry = self
-            .location_table
-            .update_mut(timestamp, &uc_repr.source_position_vector);
+        let Some(entry) = update_location_table(
+            &mut self.location_table,
+            &mut ctx,
+            &uc_repr.source_position_vector,
+        ) else {
+            self.stats.dropped.count(DropReason::Security);
+            return None;
+        };
 
         /* Add received packet sequence number to the duplicate packet list */
         if dup_opt.is_none() {
@@ -974,7 +1123,7 @@ impl InterfaceInner {
 
         /* Step 9: Flush packets inside Location Service and Unicast forwarding buffers
         that are destined to the source of the incoming Unicast packet. */
-        if let Some(handle) = entry.ls_pending {
+        if let Some(handle) = entry.ls_pending.take() {
             ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                 packet_node.metadata().inner().dst_addr().mac_addr()
                     == uc_repr.src_addr().mac_addr()
@@ -992,6 +1141,7 @@ impl InterfaceInner {
         /* Step 10: Build packet and decrement RHL. */
         if bh_repr.remaining_hop_limit == 0 {
             /* Remaining Hop Limit is reached, discard packet. */
+            self.stats.dropped.count(DropReason::HopLimit);
             return None;
         }
 
@@ -1007,13 +1157,17 @@ impl InterfaceInner {
             /* Buffer the packet into the unicast buffer */
             let fwd_packet = GeonetUnicast::new(fwd_bh_repr, ch_repr, uc_repr);
             #[cfg(feature = "proto-security")]
-            let metadata = super::to_gn_repr(fwd_packet, ctx.decap_context);
+            let metadata = self.to_gn_repr(fwd_packet, &mut ctx, uc_repr.src_addr().mac_addr())?;
             #[cfg(not(feature = "proto-security"))]
             let metadata = GeonetRepr::Unsecured(fwd_packet);
 
-            ctx.uc_forwarding_buffer
+            if ctx
+                .uc_forwarding_buffer
                 .enqueue(metadata, payload, timestamp)
-                .ok();
+                .is_err()
+            {
+                self.stats.dropped.count(DropReason::BufferFull);
+            }
 
             return None;
         }
@@ -1025,7 +1179,7 @@ impl InterfaceInner {
         };
 
         #[cfg(feature = "proto-security")]
-        let packet = super::to_gn_repr(packet, ctx.decap_context);
+        let packet = self.to_gn_repr(packet, &mut ctx, uc_repr.src_addr().mac_addr())?;
         #[cfg(not(feature = "proto-security"))]
         let packet = GeonetRepr::Unsecured(packet);
 
@@ -1046,7 +1200,7 @@ impl InterfaceInner {
     #[allow(clippy::too_many_arguments)]
     fn receive_unicast(
         &mut self,
-        ctx: InterfaceContext,
+        mut ctx: InterfaceContext,
         sockets: &mut SocketSet,
         meta: PacketMeta,
         bh_repr: BasicHeaderRepr,
@@ -1057,6 +1211,10 @@ impl InterfaceInner {
     ) {
         let timestamp = ctx.core.now;
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, uc_repr.src_addr());
+
         /* Step 3: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
             uc_repr.src_addr(),
@@ -1076,9 +1234,14 @@ impl InterfaceInner {
             });
 
         /* Step 5-6: update Location table */
-        let entry = self
-            .location_table
-            .update_mut(timestamp, &uc_repr.source_position_vector);
+        let Some(entry) = update_location_table(
+            &mut self.location_table,
+            &mut ctx,
+            &uc_repr.source_position_vector,
+        ) else {
+            self.stats.dropped.count(DropReason::Security);
+            return;
+        };
 
         /* Add received packet sequence number to the duplicate packet list */
         if dup_opt.is_none() {
@@ -1108,7 +1271,7 @@ impl InterfaceInner {
 
         /* Step 7: Flush packets inside Location Service and Unicast forwarding buffers
         that are destined to the source of the incoming Unicast packet. */
-        if let Some(handle) = entry.ls_pending {
+        if let Some(handle) = entry.ls_pending.take() {
             ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                 packet_node.metadata().inner().dst_addr().mac_addr()
                     == uc_repr.src_addr().mac_addr()
@@ -1125,7 +1288,7 @@ impl InterfaceInner {
 
         /* Step 8: pass payload to upper protocol. */
         let gn_repr = GeonetUnicast::new(bh_repr, ch_repr, uc_repr).into();
-        self.pass_up(&ctx, sockets, meta, &gn_repr, payload);
+        self.pass_up(&mut ctx, sockets, meta, &gn_repr, payload);
     }
 
     /// Process a Geo Broadcast packet.
@@ -1144,14 +1307,15 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let gbc = check!(GeoBroadcastHeader::new_checked(packet));
-        let gbc_repr = check!(GeoBroadcastRepr::parse(&gbc));
+        let gbc = check!(self.stats, GeoBroadcastHeader::new_checked(packet));
+        let gbc_repr = check!(self.stats, GeoBroadcastRepr::parse(&gbc));
         let timestamp = ctx.core.now;
 
         let payload = gbc.payload();
 
         /* Check if we are the sender of the packet */
         if gbc_repr.src_addr() == ctx.core.address() {
+            self.stats.dropped.count(DropReason::Loopback);
             return None;
         }
 
@@ -1159,6 +1323,10 @@ impl InterfaceInner {
         let dst_area = GeoArea::from_gbc(&ch_repr.header_type, &gbc_repr);
         let inside = dst_area.inside_or_at_border(ctx.core.geo_position());
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, gbc_repr.src_addr());
+
         /* Step 3a-3b: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
             gbc_repr.src_addr(),
@@ -1171,6 +1339,7 @@ impl InterfaceInner {
             || (inside && GN_AREA_FORWARDING_ALGORITHM != GnAreaForwardingAlgorithm::Cbf))
             && dup_opt.is_some_and(|x| x)
         {
+            self.stats.duplicates += 1;
             return None;
         }
 
@@ -1183,9 +1352,14 @@ impl InterfaceInner {
 
         let ls_pending = {
             /* Step 5-6: update Location table */
-            let entry = self
-                .location_table
-                .update_mut(timestamp, &gbc_repr.source_position_vector);
+            let Some(entry) = update_location_table(
+                &mut self.location_table,
+                &mut ctx,
+                &gbc_repr.source_position_vector,
+            ) else {
+                self.stats.dropped.count(DropReason::Security);
+                return None;
+            };
 
             /* Add received packet sequence number to the duplicate packet list */
             if dup_opt.is_none() {
@@ -1213,13 +1387,14 @@ impl InterfaceInner {
 
             entry.update_pdr(packet_size, timestamp);
 
-            entry.ls_pending
+            entry.ls_pending.take()
         };
 
-        /* Step 7: pass payload to upper protocol if we are inside the destination area */
-        if inside {
+        /* Step 7: pass payload to upper protocol if we are inside the destination area.
+        Duplicates are only kept for the CBF algorithm, they are not delivered again. */
+        if inside && !dup_opt.is_some_and(|x| x) {
             let gn_repr = GeonetGeoBroadcast::new(bh_repr, ch_repr, gbc_repr).into();
-            self.pass_up(&ctx, sockets, meta, &gn_repr, payload);
+            self.pass_up(&mut ctx, sockets, meta, &gn_repr, payload);
         }
 
         /* Step 8: Flush packets inside Location Service and Unicast forwarding buffers
@@ -1242,6 +1417,7 @@ impl InterfaceInner {
         /* Step 9: decrement Remaining Hop limit */
         if bh_repr.remaining_hop_limit == 0 {
             /* Remaining Hop Limit is reached, discard packet. */
+            self.stats.dropped.count(DropReason::HopLimit);
             return None;
         }
 
@@ -1254,16 +1430,20 @@ impl InterfaceInner {
 
         let packet = GeonetGeoBroadcast::new(fwd_bh_repr, ch_repr, gbc_repr);
         #[cfg(feature = "proto-security")]
-        let packet = super::to_gn_repr(packet.into(), ctx.decap_context);
+        let packet = self.to_gn_repr(packet.into(), &mut ctx, gbc_repr.src_addr().mac_addr())?;
         #[cfg(not(feature = "proto-security"))]
         let packet = GeonetRepr::Unsecured(packet.into());
 
         /* Step 10: check if we should buffer the packet */
         if !self.location_table.has_neighbour() && ch_repr.traffic_class.store_carry_forward() {
             /* Buffer the packet into the broadcast buffer */
-            ctx.bc_forwarding_buffer
+            if ctx
+                .bc_forwarding_buffer
                 .enqueue(packet, payload, timestamp)
-                .ok();
+                .is_err()
+            {
+                self.stats.dropped.count(DropReason::BufferFull);
+            }
 
             return None;
         }
@@ -1298,17 +1478,22 @@ impl InterfaceInner {
         EthernetAddress,
         GeonetPacket<'packet>,
     )> {
-        let gac = check!(GeoAnycastHeader::new_checked(packet));
-        let gac_repr = check!(GeoAnycastRepr::parse(&gac));
+        let gac = check!(self.stats, GeoAnycastHeader::new_checked(packet));
+        let gac_repr = check!(self.stats, GeoAnycastRepr::parse(&gac));
         let timestamp = ctx.core.now;
 
         let payload = gac.payload();
 
         /* Check if we are the sender of the packet */
         if gac_repr.src_addr() == ctx.core.address() {
+            self.stats.dropped.count(DropReason::Loopback);
             return None;
         }
 
+        /* Save the source Location Table entry, restored if the packet is not verified. */
+        #[cfg(feature = "proto-security")]
+        self.snapshot_location_entry(&mut ctx, gac_repr.src_addr());
+
         /* Step 3: duplicate packet detection */
         let dup_opt = self.location_table.duplicate_packet_detection(
             gac_repr.src_addr(),
@@ -1317,6 +1502,7 @@ impl InterfaceInner {
         );
 
         if dup_opt.is_some_and(|x| x) {
+            self.stats.duplicates += 1;
             return None;
         }
 
@@ -1328,9 +1514,14 @@ impl InterfaceInner {
             });
 
         /* Step 5-6: update Location table */
-        let entry = self
-            .location_table
-            .update_mut(timestamp, &gac_repr.source_position_vector);
+        let Some(entry) = update_location_table(
+            &mut self.location_table,
+            &mut ctx,
+            &gac_repr.source_position_vector,
+        ) else {
+            self.stats.dropped.count(DropReason::Security);
+            return None;
+        };
 
         /* Add received packet sequence number to the duplicate packet list */
         if dup_opt.is_none() {
@@ -1364,7 +1555,7 @@ impl InterfaceInner {
 
         /* Step 8: Flush packets inside Location Service and Unicast forwarding buffers
         that are destined to the source of the incoming GAC packet. */
-        if let Some(handle) = entry.ls_pending {
+        if let Some(handle) = entry.ls_pending.take() {
             ctx.ls_buffer.mark_flush(timestamp, |packet_node| {
                 packet_node.metadata().inner().dst_addr().mac_addr()
                     == gac_repr.src_addr().mac_addr()
@@ -1382,12 +1573,13 @@ impl InterfaceInner {
         /* Step 9: pass payload to upper protocol if we are inside the destination area */
         if inside {
             let gn_repr = GeonetGeoAnycast::new(bh_repr, ch_repr, gac_repr).into();
-            self.pass_up(&ctx, sockets, meta, &gn_repr, payload);
+            self.pass_up(&mut ctx, sockets, meta, &gn_repr, payload);
         }
 
         /* Step 10a: decrement Remaining Hop limit */
         if bh_repr.remaining_hop_limit == 0 {
             /* Remaining Hop Limit is reached, discard packet. */
+            self.stats.dropped.count(DropReason::HopLimit);
             return None;
         }
 
@@ -1400,16 +1592,20 @@ impl InterfaceInner {
 
         let packet = GeonetGeoAnycast::new(fwd_bh_repr, ch_repr, gac_repr);
         #[cfg(feature = "proto-security")]
-        let packet = super::to_gn_repr(packet.into(), ctx.decap_context);
+        let packet = self.to_gn_repr(packet.into(), &mut ctx, gac_repr.src_addr().mac_addr())?;
         #[cfg(not(feature = "proto-security"))]
         let packet = GeonetRepr::Unsecured(packet.into());
 
         /* Step 10b: check if we should buffer the packet */
         if !self.location_table.has_neighbour() && ch_repr.traffic_class.store_carry_forward() {
             /* Buffer the packet into the broadcast buffer */
-            ctx.bc_forwarding_buffer
+            if ctx
+                .bc_forwarding_buffer
                 .enqueue(packet, payload, timestamp)
-                .ok();
+                .is_err()
+            {
+                self.stats.dropped.count(DropReason::BufferFull);
+            }
 
             return None;
         }
@@ -1444,15 +1640,22 @@ impl InterfaceInner {
             return Ok(());
         }
 
+        // Beacons disclose the station position, skip them during silent periods.
+        #[cfg(feature = "proto-security")]
+        if ctx.core.is_silent() {
+            self.rearm_beacon_timer(ctx.core);
+            return Ok(());
+        }
+
         /* Step 1a: set the fields of the basic header */
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: GN_DEFAULT_PACKET_LIFETIME,
@@ -1485,6 +1688,7 @@ impl InterfaceInner {
             GeonetRepr::ToSecure {
                 repr: repr.into(),
                 permission: Permission::GnMgmt,
+                confidential: false,
             }
         } else {
             GeonetRepr::Unsecured(repr.into())
@@ -1539,13 +1743,11 @@ impl InterfaceInner {
                         let bh_repr = BasicHeaderRepr {
                             version: GN_PROTOCOL_VERSION,
                             #[cfg(feature = "proto-security")]
-                            next_header: ctx
-                                .core
-                                .security
-                                .as_ref()
-                                .map_or(BHNextHeader::CommonHeader, |_| {
-                                    BHNextHeader::SecuredHeader
-                                }),
+                            next_header: if ctx.core.signing_enabled() {
+                                BHNextHeader::SecuredHeader
+                            } else {
+                                BHNextHeader::CommonHeader
+                            },
                             #[cfg(not(feature = "proto-security"))]
                             next_header: BHNextHeader::CommonHeader,
                             lifetime: GN_DEFAULT_PACKET_LIFETIME,
@@ -1575,6 +1777,7 @@ impl InterfaceInner {
                             GeonetRepr::ToSecure {
                                 repr: repr.into(),
                                 permission: Permission::GnMgmt,
+                                confidential: false,
                             }
                         } else {
                             GeonetRepr::Unsecured(repr.into())
@@ -1636,11 +1839,11 @@ impl InterfaceInner {
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: metadata.max_lifetime,
@@ -1676,6 +1879,7 @@ impl InterfaceInner {
                     GeonetRepr::ToSecure {
                         repr: buf_packet,
                         permission: metadata.its_aid,
+                        confidential: metadata.confidential,
                     }
                 } else {
                     GeonetRepr::Unsecured(buf_packet)
@@ -1699,6 +1903,7 @@ impl InterfaceInner {
                     GeonetRepr::ToSecure {
                         repr: buf_packet,
                         permission: metadata.its_aid,
+                        confidential: metadata.confidential,
                     }
                 } else {
                     GeonetRepr::Unsecured(buf_packet)
@@ -1720,6 +1925,7 @@ impl InterfaceInner {
                 GeonetRepr::ToSecure {
                     repr: buf_packet.into(),
                     permission: metadata.its_aid,
+                    confidential: metadata.confidential,
                 }
             } else {
                 GeonetRepr::Unsecured(buf_packet.into())
@@ -1783,6 +1989,7 @@ impl InterfaceInner {
                 GeonetRepr::ToSecure {
                     repr: buf_packet,
                     permission: metadata.its_aid,
+                    confidential: metadata.confidential,
                 }
             } else {
                 GeonetRepr::Unsecured(buf_packet)
@@ -1818,11 +2025,11 @@ impl InterfaceInner {
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: metadata.max_lifetime,
@@ -1853,6 +2060,7 @@ impl InterfaceInner {
             GeonetRepr::ToSecure {
                 repr: buf_packet.into(),
                 permission: metadata.its_aid,
+                confidential: false,
             }
         } else {
             GeonetRepr::Unsecured(buf_packet.into())
@@ -1906,11 +2114,11 @@ impl InterfaceInner {
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: metadata.max_lifetime,
@@ -1954,6 +2162,7 @@ impl InterfaceInner {
             GeonetRepr::ToSecure {
                 repr: buf_packet.into(),
                 permission: metadata.its_aid,
+                confidential: false,
             }
         } else {
             GeonetRepr::Unsecured(buf_packet.into())
@@ -2007,11 +2216,11 @@ impl InterfaceInner {
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: metadata.max_lifetime,
@@ -2051,6 +2260,7 @@ impl InterfaceInner {
             GeonetRepr::ToSecure {
                 repr: buf_packet.into(),
                 permission: metadata.its_aid,
+                confidential: false,
             }
         } else {
             GeonetRepr::Unsecured(buf_packet.into())
@@ -2108,11 +2318,11 @@ impl InterfaceInner {
         let bh_repr = BasicHeaderRepr {
             version: GN_PROTOCOL_VERSION,
             #[cfg(feature = "proto-security")]
-            next_header: ctx
-                .core
-                .security
-                .as_ref()
-                .map_or(BHNextHeader::CommonHeader, |_| BHNextHeader::SecuredHeader),
+            next_header: if ctx.core.signing_enabled() {
+                BHNextHeader::SecuredHeader
+            } else {
+                BHNextHeader::CommonHeader
+            },
             #[cfg(not(feature = "proto-security"))]
             next_header: BHNextHeader::CommonHeader,
             lifetime: metadata.max_lifetime,
@@ -2152,6 +2362,7 @@ impl InterfaceInner {
             GeonetRepr::ToSecure {
                 repr: buf_packet.into(),
                 permission: metadata.its_aid,
+                confidential: false,
             }
         } else {
             GeonetRepr::Unsecured(buf_packet.into())
@@ -2215,18 +2426,33 @@ impl InterfaceInner {
             meta.set_source_position_vector(ctx.core.ego_position_vector());
 
             // Update destination position vector in stored packet.
-            if let Some(dst_entry) = self.location_table.find(&dest_mac_addr) {
-                meta.set_destination_position_vector(dst_entry.position_vector.into());
-            }
+            let dst_is_neighbour = match self.location_table.find(&dest_mac_addr) {
+                Some(dst_entry) => {
+                    meta.set_destination_position_vector(dst_entry.position_vector.into());
+                    dst_entry.is_neighbour
+                }
+                None => false,
+            };
 
             // Convert to variant.
             let variant = unicast_to_variant_repr(packet.metadata());
 
+            // Destination is not necessarily a neighbour, execute the forwarding algorithm.
+            let next_hop = match dst_is_neighbour {
+                true => dest_mac_addr,
+                false if GN_NON_AREA_FORWARDING_ALGORITHM == GnNonAreaForwardingAlgorithm::Cbf => {
+                    EthernetAddress::BROADCAST
+                }
+                false => self
+                    .greedy_next_hop(ctx.core.geo_position(), variant.inner().geo_destination())
+                    .unwrap_or(EthernetAddress::BROADCAST),
+            };
+
             emit(
                 self,
                 ctx.core,
                 ctx.congestion_control,
-                (dest_mac_addr, variant, packet.payload()),
+                (next_hop, variant, packet.payload()),
             )
         })
     }
@@ -2404,13 +2630,14 @@ impl InterfaceInner {
                         && area.inside_or_at_border(neigh.geo_position())
                 });
 
+            /* Packets received from a sender inside the area shall not leave it. */
             match (inside, GN_NON_AREA_FORWARDING_ALGORITHM) {
                 (
-                    true,
+                    false,
                     GnNonAreaForwardingAlgorithm::Unspecified
                     | GnNonAreaForwardingAlgorithm::Greedy,
                 ) => self.non_area_greedy_forwarding(ctx, packet, payload),
-                (true, GnNonAreaForwardingAlgorithm::Cbf) => {
+                (false, GnNonAreaForwardingAlgorithm::Cbf) => {
                     self.non_area_contention_based_forwarding(ctx, packet, payload, link_layer)
                 }
                 _ => None,
@@ -2435,22 +2662,9 @@ impl InterfaceInner {
         let timestamp = ctx.core.now;
         let inner = packet.inner();
         let dest = inner.geo_destination();
-        let dist_ego_dest = ctx.core.geo_position().distance_to(&dest);
-        let mut mfr = dist_ego_dest;
-
-        let mut next_hop = None;
-        for neighbor in self.location_table.neighbour_list().into_iter() {
-            let dist = inner
-                .geo_destination()
-                .distance_to(&neighbor.geo_position());
-            if dist < mfr {
-                next_hop = Some(neighbor.position_vector.address.mac_addr());
-                mfr = dist;
-            }
-        }
 
-        if mfr < dist_ego_dest {
-            next_hop
+        if let Some(next_hop) = self.greedy_next_hop(ctx.core.geo_position(), dest) {
+            Some(next_hop)
         } else if inner.traffic_class().store_carry_forward() {
             match inner {
                 GeonetVariant::Unicast(u) => {
@@ -2467,9 +2681,14 @@ impl InterfaceInner {
                             secured_message_size: *secured_message_size,
                         },
                         #[cfg(feature = "proto-security")]
-                        GeonetRepr::ToSecure { permission, .. } => GeonetRepr::ToSecure {
+                        GeonetRepr::ToSecure {
+                            permission,
+                            confidential,
+                            ..
+                        } => GeonetRepr::ToSecure {
                             repr: u.to_owned(),
                             permission: permission.to_owned(),
+                            confidential: *confidential,
                         },
                         #[cfg(feature = "proto-security")]
                         GeonetRepr::Secured { encapsulated, .. } => GeonetRepr::Secured {
@@ -2495,6 +2714,29 @@ impl InterfaceInner {
         }
     }
 
+    /// Returns the neighbour with the most forward progress towards `dest`,
+    /// if any is closer to `dest` than `ego`. Neighbours whose position has not
+    /// been verified yet are not elected.
+    fn greedy_next_hop(&self, ego: GeoPosition, dest: GeoPosition) -> Option<EthernetAddress> {
+        let mut mfr = ego.distance_to(&dest);
+
+        let mut next_hop = None;
+        for neighbor in self
+            .location_table
+            .neighbour_list()
+            .into_iter()
+            .filter(|n| !n.tentative)
+        {
+            let dist = dest.distance_to(&neighbor.geo_position());
+            if dist < mfr {
+                next_hop = Some(neighbor.position_vector.address.mac_addr());
+                mfr = dist;
+            }
+        }
+
+        next_hop
+    }
+
     /// Executes the non area contention algorithm as
     /// described in ETSI TS 103 836-4-1 V2.1.1 clause E.3.
     ///
@@ -2755,21 +2997,147 @@ impl InterfaceInner {
         if progress > max_range {
             GN_CBF_MIN_TIME
         } else if progress > Length::new::<meter>(0.0) {
-            let eq = GN_CBF_MAX_TIME.millis()
-                + (GN_CBF_MIN_TIME.millis() - GN_CBF_MAX_TIME.millis())
-                    / GN_DEFAULT_MAX_COMMUNICATION_RANGE as u64
-                    * progress.get::<meter>() as u64;
-            Duration::from_millis(eq)
+            let max = GN_CBF_MAX_TIME.total_millis() as f64;
+            let min = GN_CBF_MIN_TIME.total_millis() as f64;
+            let eq =
+                max + (min - max) / GN_DEFAULT_MAX_COMMUNICATION_RANGE * progress.get::<meter>();
+            Duration::from_millis(eq as u64)
         } else {
             GN_CBF_MAX_TIME
         }
     }
 
+    /// Converts the received `variant`, sent by `source`, into a [GeonetRepr] to forward it.
+    /// Forwarding consumes the packet, its deferred signature verification is run if any.
+    #[cfg(feature = "proto-security")]
+    fn to_gn_repr<T>(
+        &mut self,
+        variant: T,
+        ctx: &mut InterfaceContext,
+        source: EthernetAddress,
+    ) -> Option<GeonetRepr<T>>
+    where
+        T: crate::common::PacketBufferMeta,
+    {
+        if !self.verify_consumed(ctx, source) {
+            return None;
+        }
+
+        let repr = if let Some(d) = ctx.decap_context.decap_confirm.take() {
+            GeonetRepr::SecuredDecap {
+                repr: variant,
+                secured_message: d.secured_message,
+                secured_message_size: d.size,
+            }
+        } else {
+            GeonetRepr::Unsecured(variant)
+        };
+
+        Some(repr)
+    }
+
+    /// Runs the deferred signature verification of the received packet sent by `source`,
+    /// before consuming it. The Location Table entry of `source`, tentatively updated with
+    /// the packet, is confirmed once the packet is verified. See [Self::restore_location_entry]
+    /// otherwise.
+    #[cfg(feature = "proto-security")]
+    fn verify_consumed(&mut self, ctx: &mut InterfaceContext, source: EthernetAddress) -> bool {
+        let rejected = ctx.decap_context.rejected;
+        let verified = ctx.verify_on_demand();
+
+        if !verified && !rejected {
+            self.stats.dropped.count(DropReason::Security);
+        }
+
+        if verified {
+            if let Some(entry) = self.location_table.find_mut(&source) {
+                entry.tentative = false;
+            }
+        }
+
+        verified
+    }
+
+    /// Saves the Location Table entry of `source` before it is updated with the received
+    /// packet, if the packet signature verification is deferred.
+    #[cfg(feature = "proto-security")]
+    fn snapshot_location_entry(&self, ctx: &mut InterfaceContext, source: GnAddress) {
+        if ctx.verification_pending() {
+            let addr = source.mac_addr();
+            let entry = self.location_table.find(&addr).cloned();
+            ctx.decap_context.loc_te_snapshot = Some((addr, entry));
+        }
+    }
+
+    /// Restores the Location Table entry saved by [Self::snapshot_location_entry] once the
+    /// received packet has been processed without being verified. A rejected packet does not
+    /// leave any change in the Location Table, and a packet whose verification is still
+    /// deferred does not alter the entry of a verified station.
+    #[cfg(feature = "proto-security")]
+    pub(super) fn restore_location_entry(&mut self, decap_context: &mut DecapContext) {
+        let Some((addr, snapshot)) = decap_context.loc_te_snapshot.take() else {
+            return;
+        };
+
+        match snapshot {
+            Some(mut entry) if decap_context.rejected || !entry.tentative => {
+                // Location Service request may have been completed with the packet, which
+                // is not undone.
+                if let Some(current) = self.location_table.find(&addr) {
+                    entry.ls_pending = current.ls_pending;
+                }
+                self.location_table.restore(addr, entry);
+            }
+            None if decap_context.rejected => {
+                self.location_table.remove(&addr);
+            }
+            _ => {}
+        }
+    }
+
+    /// Consumes the received `packet`, ie: delivers it to a socket. Runs its deferred
+    /// signature verification if any. Returns whether the packet can be delivered.
+    #[allow(unused_variables)]
+    pub(super) fn consume(&mut self, ctx: &mut InterfaceContext, packet: &GeonetVariant) -> bool {
+        #[cfg(feature = "proto-security")]
+        {
+            let source = packet.source_address().mac_addr();
+            if !self.verify_consumed(ctx, source) {
+                return false;
+            }
+
+            // Message is verified, its source address can be used to encrypt to the sender.
+            if let (Some(sec), Some(confirm)) =
+                (ctx.core.security.as_mut(), &ctx.decap_context.decap_confirm)
+            {
+                sec.bind_certificate_address(confirm.cert_id, source);
+            }
+
+            let now = ctx.core.tai2004();
+            ctx.decap_context.sender_position = self.location_table.find(&source).map(|entry| {
+                // Position vector timestamp is a 32 bits modulo, rebuild the full value.
+                let delta = PositionVectorTimestamp::from(now)
+                    .0
+                    .wrapping_sub(entry.position_vector.timestamp.0)
+                    as i32;
+                let generated_at = if delta >= 0 {
+                    now - Duration::from_millis(delta as u64)
+                } else {
+                    now + Duration::from_millis(delta.unsigned_abs().into())
+                };
+                (entry.geo_position(), generated_at)
+            });
+        }
+
+        true
+    }
+
     /// Pass received Geonetworking payload to upper layer.
+    /// Packet is consumed by the first socket accepting it.
     #[allow(unused_variables)]
     fn pass_up(
         &mut self,
-        ctx: &InterfaceContext,
+        ctx: &mut InterfaceContext,
         sockets: &mut SocketSet,
         _meta: PacketMeta,
         packet: &GeonetVariant,
@@ -2791,13 +3159,20 @@ impl InterfaceInner {
                 .decap_confirm
                 .as_ref()
                 .map_or_else(Default::default, |d| d.cert_id),
+            #[cfg(feature = "proto-security")]
+            confidential: ctx
+                .decap_context
+                .decap_confirm
+                .as_ref()
+                .is_some_and(|d| d.confidential),
             rem_lifetime: packet.lifetime(),
             rem_hop_limit: packet.hop_limit(),
             traffic_class: packet.traffic_class(),
         };
 
         #[cfg(feature = "socket-geonet")]
-        let handled_by_gn_socket = self.geonet_socket_filter(sockets, ind.clone(), payload);
+        let handled_by_gn_socket =
+            self.geonet_socket_filter(ctx, sockets, ind.clone(), packet, payload);
         #[cfg(not(feature = "socket-geonet"))]
         let handled_by_gn_socket = false;
 
@@ -2810,7 +3185,7 @@ impl InterfaceInner {
             UpperProtocol::BtpA => {}
             #[cfg(feature = "socket-btp-b")]
             UpperProtocol::BtpB => {
-                self.process_btp_b(ctx, sockets, ind, handled_by_gn_socket, payload)
+                self.process_btp_b(ctx, sockets, ind, handled_by_gn_socket, packet, payload)
             }
             #[cfg(not(feature = "socket-btp-b"))]
             UpperProtocol::BtpB => {}
@@ -2819,3 +3194,38 @@ impl InterfaceInner {
         };
     }
 }
+
+/// Updates the `location_table` entry of the received packet source with its `position_vector`.
+/// The entry of an unknown station is tentative until one of its packets is verified. A packet
+/// whose signature verification is deferred does not evict a verified entry: it is verified
+/// first. Returns [None] if the packet is rejected.
+fn update_location_table<'t>(
+    location_table: &'t mut LocationTable,
+    ctx: &mut InterfaceContext,
+    position_vector: &LongPositionVector,
+) -> Option<&'t mut LocationTableEntry> {
+    #[cfg(feature = "proto-security")]
+    let known = {
+        let addr = position_vector.address.mac_addr();
+        if ctx.verification_pending()
+            && location_table.evicts_verified(&addr)
+            && !ctx.verify_on_demand()
+        {
+            return None;
+        }
+
+        location_table.find(&addr).is_some()
+    };
+
+    let entry = location_table.update_mut(ctx.core.now, position_vector);
+
+    #[cfg(feature = "proto-security")]
+    {
+        let pending = ctx.verification_pending();
+        if !known || !pending {
+            entry.tentative = pending;
+        }
+    }
+
+    Some(entry)
+}
@@ -60,4 +60,16 @@ impl InterfaceInner {
             _ => None,
         }
     }
+
+    /// Returns the Geonetworking packet carried by the IEEE 802.11p `frame`, if any.
+    #[cfg(feature = "proto-security")]
+    pub(super) fn ieee80211p_geonet_packet(frame: &[u8]) -> Option<&[u8]> {
+        let ieee80211_frame = Ieee80211Frame::new_checked(frame).ok()?;
+        let llc_frame = LlcFrame::new_checked(ieee80211_frame.payload()).ok()?;
+
+        match llc_frame.snap_protocol() {
+            EthernetProtocol::Geonet => Some(llc_frame.payload()),
+            _ => None,
+        }
+    }
 }
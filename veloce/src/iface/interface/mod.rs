@@ -17,6 +17,7 @@ use super::{
     congestion::{AnyController, Congestion, CongestionSuccess},
     location_service::LocationService,
     location_table::LocationTable,
+    stats::InterfaceStats,
 };
 
 use super::packet::*;
@@ -33,7 +34,21 @@ use crate::network::GnCore;
 use crate::phy::{Device, DeviceCapabilities, Medium, PacketMeta, RxToken, TxToken};
 
 #[cfg(feature = "proto-security")]
-use crate::security::service::{decap::DecapConfirm, SecurityServiceError};
+use crate::config::SEC_VERIFY_BATCH_SIZE;
+#[cfg(feature = "proto-security")]
+use crate::security::{
+    secured_message::SecuredMessage,
+    service::{decap::DecapConfirm, SecurityServiceError, VerificationMode},
+};
+#[cfg(feature = "proto-security")]
+use crate::{
+    common::geo_area::GeoPosition,
+    iface::location_table::LocationTableEntry,
+    security::misbehavior::{MisbehaviorVerdict, Observation},
+    time::TAI2004,
+    types::{tenth_of_microdegree, Heading, Latitude, Longitude, Speed},
+    wire::BHNextHeader,
+};
 
 #[cfg(feature = "socket-geonet")]
 use crate::network::Indication;
@@ -58,6 +73,19 @@ use crate::{
 };
 
 macro_rules! check {
+    ($stats:expr, $e:expr) => {
+        match $e {
+            Ok(x) => x,
+            Err(_) => {
+                #[cfg(not(feature = "defmt"))]
+                net_trace!(concat!("iface: malformed ", stringify!($e)));
+                #[cfg(feature = "defmt")]
+                net_trace!("iface: malformed");
+                $stats.dropped.count($crate::iface::DropReason::Malformed);
+                return Default::default();
+            }
+        }
+    };
     ($e:expr) => {
         match $e {
             Ok(x) => x,
@@ -81,23 +109,6 @@ macro_rules! next_sequence_number {
     }};
 }
 
-#[cfg(all(feature = "proto-geonet", feature = "proto-security"))]
-#[inline]
-pub(super) fn to_gn_repr<T>(variant: T, ctx: &mut DecapContext) -> GeonetRepr<T>
-where
-    T: crate::common::PacketBufferMeta,
-{
-    if let Some(d) = ctx.decap_confirm.take() {
-        GeonetRepr::SecuredDecap {
-            repr: variant,
-            secured_message: d.secured_message,
-            secured_message_size: d.size,
-        }
-    } else {
-        GeonetRepr::Unsecured(variant)
-    }
-}
-
 use check;
 
 #[cfg(feature = "proto-geonet")]
@@ -197,6 +208,11 @@ pub struct InterfaceInner {
     /// Sequence Number of the Access Handler.
     #[cfg(feature = "proto-geonet")]
     sequence_number: SequenceNumber,
+    /// Packet counters of the Access Handler.
+    #[cfg(feature = "proto-geonet")]
+    stats: InterfaceStats,
+    /// Whether a pseudonym change has to be propagated to the device and the sockets.
+    pseudonym_changed: bool,
 }
 
 /// Configuration structure used for creating a network interface.
@@ -237,6 +253,140 @@ pub(crate) struct InterfaceContext<'a> {
     pub decap_context: &'a mut DecapContext,
 }
 
+#[cfg(feature = "proto-security")]
+impl InterfaceContext<'_> {
+    /// Query whether the signature verification of the received message being
+    /// processed has been deferred and not run yet.
+    pub(crate) fn verification_pending(&self) -> bool {
+        self.decap_context
+            .decap_confirm
+            .as_ref()
+            .is_some_and(|d| !d.is_verified())
+    }
+
+    /// Runs the deferred signature verification of the received message being
+    /// processed, if any. Returns whether the message can be consumed, ie: delivered
+    /// to the upper layers or forwarded.
+    pub(crate) fn verify_on_demand(&mut self) -> bool {
+        if self.decap_context.rejected {
+            return false;
+        }
+
+        let Some(confirm) = self.decap_context.decap_confirm.as_mut() else {
+            return true;
+        };
+
+        if confirm.is_verified() {
+            return true;
+        }
+
+        let timestamp = self.core.timestamp();
+        let Some(sec) = self.core.security.as_mut() else {
+            return false;
+        };
+
+        match sec.verify_decap(confirm, timestamp) {
+            Ok(()) => {
+                // Location Table entry of the sender can keep the packet update.
+                self.decap_context.loc_te_snapshot = None;
+                true
+            }
+            Err(e) => {
+                net_trace!("network: deferred security verification failure: {}", e);
+                self.decap_context.rejected = true;
+                false
+            }
+        }
+    }
+
+    /// Runs the misbehavior detection plausibility checks on the received message
+    /// being processed, which advertises the sender `position`, `speed` and `heading`.
+    /// Returns [None] if security is disabled or the message is not secured.
+    pub(crate) fn check_misbehavior(
+        &mut self,
+        position: Option<GeoPosition>,
+        speed: Option<Speed>,
+        heading: Option<Heading>,
+    ) -> Option<MisbehaviorVerdict> {
+        self.check_observation(position, speed, heading, None, None)
+    }
+
+    /// Runs the misbehavior detection plausibility checks on the received DENM being
+    /// processed, which reports an event located at `event_position` and detected at
+    /// `detection_time`. The sender position is the generation location of the secured message.
+    /// Returns [None] if security is disabled or the message is not secured.
+    pub(crate) fn check_denm_misbehavior(
+        &mut self,
+        event_position: Option<GeoPosition>,
+        detection_time: TAI2004,
+    ) -> Option<MisbehaviorVerdict> {
+        let location = self
+            .decap_context
+            .decap_confirm
+            .as_ref()?
+            .secured_message
+            .generation_location()
+            .ok()
+            .flatten();
+
+        let position = location
+            .filter(|l| l.latitude.0 .0 != 900_000_001 && l.longitude.0 .0 != 1_800_000_001)
+            .map(|l| GeoPosition {
+                latitude: Latitude::new::<tenth_of_microdegree>(l.latitude.0 .0.into()),
+                longitude: Longitude::new::<tenth_of_microdegree>(l.longitude.0 .0.into()),
+            });
+
+        self.check_observation(position, None, None, event_position, Some(detection_time))
+    }
+
+    /// Runs the misbehavior detection plausibility checks on the received message being
+    /// processed, and records the verdict for misbehavior reporting.
+    fn check_observation(
+        &mut self,
+        position: Option<GeoPosition>,
+        speed: Option<Speed>,
+        heading: Option<Heading>,
+        event_position: Option<GeoPosition>,
+        detection_time: Option<TAI2004>,
+    ) -> Option<MisbehaviorVerdict> {
+        let now = self.core.clock_timestamp();
+        let confirm = self.decap_context.decap_confirm.as_ref()?;
+        let timestamp = confirm
+            .secured_message
+            .generation_time()
+            .unwrap_or_else(|_| self.core.tai2004());
+
+        let fix = self.core.position();
+        let ego_position =
+            fix.position
+                .latitude
+                .zip(fix.position.longitude)
+                .map(|(latitude, longitude)| GeoPosition {
+                    latitude,
+                    longitude,
+                });
+
+        let observation = Observation {
+            timestamp,
+            position,
+            speed,
+            heading,
+            ego_position,
+            beacon_position: self.decap_context.sender_position,
+            event_position,
+            detection_time,
+        };
+
+        let sec = self.core.security.as_mut()?;
+        let verdict = sec
+            .misbehavior_mut()
+            .check(confirm.cert_id, &observation, now);
+        sec.record_misbehavior(confirm, &verdict, fix.position.as_3d_location(), now);
+
+        Some(verdict)
+    }
+}
+
 #[cfg(not(feature = "proto-geonet"))]
 pub(crate) struct InterfaceContext<'a> {
     phantom: core::marker::PhantomData<&'a ()>,
@@ -247,6 +397,16 @@ pub(crate) struct InterfaceContext<'a> {
 pub(crate) struct DecapContext {
     /// Security decap service result.
     pub decap_confirm: Option<DecapConfirm>,
+    /// Position of the packet sender in the Location Table, with
+    /// the time it was generated at. For misbehavior detection purposes.
+    pub sender_position: Option<(GeoPosition, TAI2004)>,
+    /// Whether the packet has been rejected by the deferred signature verification.
+    /// A rejected packet is neither delivered nor forwarded.
+    pub rejected: bool,
+    /// Location Table entry of the packet sender, saved before being updated with the packet
+    /// while its signature verification is deferred. Inner value is [None] if the sender was
+    /// unknown.
+    pub(super) loc_te_snapshot: Option<(EthernetAddress, Option<LocationTableEntry>)>,
 }
 
 /// Buffer type for data enclosed in the security wrapper.
@@ -303,6 +463,9 @@ impl Interface {
                 location_table: LocationTable::new(),
                 #[cfg(feature = "proto-geonet")]
                 sequence_number: SequenceNumber(0),
+                #[cfg(feature = "proto-geonet")]
+                stats: InterfaceStats::default(),
+                pseudonym_changed: false,
             },
         }
     }
@@ -336,9 +499,25 @@ impl Interface {
     {
         let mut res = PollResult::None;
 
+        // Signatures are verified in batches, unless their verification is deferred.
+        #[cfg(feature = "proto-security")]
+        let batch = core
+            .security
+            .as_ref()
+            .is_some_and(|s| s.verification_mode() == VerificationMode::Immediate);
+
         // Process ingress while there's packets available.
         loop {
-            match self.socket_ingress(core, device, sockets) {
+            #[cfg(feature = "proto-security")]
+            let ingress = if batch {
+                self.socket_ingress_batch(core, device, sockets)
+            } else {
+                self.socket_ingress(core, device, sockets)
+            };
+            #[cfg(not(feature = "proto-security"))]
+            let ingress = self.socket_ingress(core, device, sockets);
+
+            match ingress {
                 PollIngressSingleResult::None => break,
                 PollIngressSingleResult::PacketProcessed => {}
                 PollIngressSingleResult::SocketStateChanged => res = PollResult::SocketStateChanged,
@@ -369,6 +548,8 @@ impl Interface {
     where
         D: Device + ?Sized,
     {
+        self.pseudonym_change_egress(core, device, sockets);
+
         #[cfg(feature = "proto-geonet")]
         {
             self.run_congestion_control(core.now, device.channel_busy_ratio());
@@ -458,6 +639,16 @@ impl Interface {
         }
     }
 
+    /// Returns the packet, congestion control and Location Table counters of the interface.
+    #[cfg(feature = "proto-geonet")]
+    pub fn stats(&self) -> InterfaceStats {
+        InterfaceStats {
+            dcc: self.congestion_control.stats,
+            location_table: self.inner.location_table.stats(),
+            ..self.inner.stats
+        }
+    }
+
     fn socket_ingress<D>(
         &mut self,
         core: &mut GnCore,
@@ -472,77 +663,152 @@ impl Interface {
         };
 
         let rx_meta = rx_token.meta();
-        let res = rx_token.consume(|frame| {
-            if frame.is_empty() {
-                return PollIngressSingleResult::PacketProcessed;
-            }
+        let res = rx_token
+            .consume(|frame| self.process_frame(core, sockets, rx_meta, frame, Some(tx_token)));
 
-            let mut sec_buf = SecuredDataBuffer::default();
-            #[cfg(feature = "proto-geonet")]
-            let ctx = InterfaceContext {
-                core,
-                ls: &mut self.location_service,
-                congestion_control: &mut self.congestion_control,
-                ls_buffer: &mut self.ls_buffer,
-                uc_forwarding_buffer: &mut self.uc_forwarding_buffer,
-                bc_forwarding_buffer: &mut self.bc_forwarding_buffer,
-                cb_forwarding_buffer: &mut self.cb_forwarding_buffer,
-                #[cfg(feature = "proto-security")]
-                decap_context: &mut DecapContext::default(),
-            };
-            #[cfg(not(feature = "proto-geonet"))]
-            let ctx = InterfaceContext {
-                phantom: core::marker::PhantomData,
+        self.update_filter_addr(device);
+
+        res
+    }
+
+    /// Receives up to [SEC_VERIFY_BATCH_SIZE] frames from `device` and verifies the
+    /// signatures of the secured packets they carry in a single batch, before processing
+    /// the frames one after another.
+    #[cfg(feature = "proto-security")]
+    fn socket_ingress_batch<D>(
+        &mut self,
+        core: &mut GnCore,
+        device: &mut D,
+        sockets: &mut SocketSet<'_>,
+    ) -> PollIngressSingleResult
+    where
+        D: Device + ?Sized,
+    {
+        let mut frames = Vec::new();
+        while frames.len() < SEC_VERIFY_BATCH_SIZE {
+            let Some((rx_token, _)) = device.receive(core.now) else {
+                break;
             };
 
-            match self.inner.caps.medium {
-                #[cfg(feature = "medium-ethernet")]
-                Medium::Ethernet => {
-                    if let Some((ctx, dst_addr, packet)) =
-                        self.inner
-                            .process_ethernet(ctx, sockets, rx_meta, frame, &mut sec_buf)
-                    {
-                        if let Err(err) = self.inner.dispatch(
-                            tx_token,
-                            ctx.core,
-                            dst_addr,
-                            packet,
-                            ctx.congestion_control,
-                        ) {
-                            net_debug!("Failed to send response: {:?}", err);
-                        }
-                    }
-                }
-                #[cfg(feature = "medium-ieee80211p")]
-                Medium::Ieee80211p => {
-                    if let Some((ctx, dst_addr, packet)) =
-                        self.inner
-                            .process_ieee80211p(ctx, sockets, rx_meta, frame, &mut sec_buf)
-                    {
-                        if let Err(err) = self.inner.dispatch(
-                            tx_token,
-                            ctx.core,
-                            dst_addr,
-                            packet,
-                            ctx.congestion_control,
-                        ) {
-                            net_debug!("Failed to send response: {:?}", err);
-                        }
-                    }
+            let rx_meta = rx_token.meta();
+            frames.push((rx_meta, rx_token.consume(|frame| frame.to_vec())));
+        }
+
+        if frames.is_empty() {
+            return PollIngressSingleResult::None;
+        }
+
+        let messages: Vec<_> = frames
+            .iter()
+            .filter_map(|(_, frame)| self.inner.frame_secured_message(frame))
+            .collect();
+
+        let timestamp = core.clock_timestamp();
+        if let Some(sec) = core.security.as_mut() {
+            sec.verify_batch(&messages, timestamp);
+        }
+
+        let mut res = PollIngressSingleResult::PacketProcessed;
+        for (rx_meta, frame) in frames {
+            let tx_token = device.transmit(core.now);
+            if self.process_frame(core, sockets, rx_meta, &frame, tx_token)
+                == PollIngressSingleResult::SocketStateChanged
+            {
+                res = PollIngressSingleResult::SocketStateChanged;
+            }
+        }
+
+        self.update_filter_addr(device);
+
+        res
+    }
+
+    /// Processes a received `frame`. Response, if any, is sent with `tx_token`.
+    fn process_frame<Tx>(
+        &mut self,
+        core: &mut GnCore,
+        sockets: &mut SocketSet<'_>,
+        rx_meta: PacketMeta,
+        frame: &[u8],
+        tx_token: Option<Tx>,
+    ) -> PollIngressSingleResult
+    where
+        Tx: TxToken,
+    {
+        if frame.is_empty() {
+            return PollIngressSingleResult::PacketProcessed;
+        }
+
+        let mut sec_buf = SecuredDataBuffer::default();
+        #[cfg(feature = "proto-security")]
+        let mut decap_context = DecapContext::default();
+        #[cfg(feature = "proto-geonet")]
+        let ctx = InterfaceContext {
+            core,
+            ls: &mut self.location_service,
+            congestion_control: &mut self.congestion_control,
+            ls_buffer: &mut self.ls_buffer,
+            uc_forwarding_buffer: &mut self.uc_forwarding_buffer,
+            bc_forwarding_buffer: &mut self.bc_forwarding_buffer,
+            cb_forwarding_buffer: &mut self.cb_forwarding_buffer,
+            #[cfg(feature = "proto-security")]
+            decap_context: &mut decap_context,
+        };
+        #[cfg(not(feature = "proto-geonet"))]
+        let ctx = InterfaceContext {
+            phantom: core::marker::PhantomData,
+        };
+
+        let response = match self.inner.caps.medium {
+            #[cfg(feature = "medium-ethernet")]
+            Medium::Ethernet => {
+                self.inner
+                    .process_ethernet(ctx, sockets, rx_meta, frame, &mut sec_buf)
+            }
+            #[cfg(feature = "medium-ieee80211p")]
+            Medium::Ieee80211p => {
+                self.inner
+                    .process_ieee80211p(ctx, sockets, rx_meta, frame, &mut sec_buf)
+            }
+            #[cfg(feature = "medium-pc5")]
+            Medium::PC5 => todo!(),
+        };
+
+        match (response, tx_token) {
+            (Some((ctx, dst_addr, packet)), Some(tx_token)) => {
+                if let Err(err) = self.inner.dispatch(
+                    tx_token,
+                    ctx.core,
+                    dst_addr,
+                    packet,
+                    ctx.congestion_control,
+                ) {
+                    net_debug!("Failed to send response: {:?}", err);
                 }
-                #[cfg(feature = "medium-pc5")]
-                Medium::PC5 => todo!(),
             }
+            (Some(_), None) => {
+                net_debug!("Failed to send response: device exhausted");
+            }
+            _ => {}
+        }
 
-            // TODO: Propagate the PollIngressSingleResult from deeper.
-            // There's many received packets that we process but can't cause sockets
-            // to change state. For example Beacons, forwarded stuff...
-            // We should return `PacketProcessed` for these to save the user from
-            // doing useless socket polls.
-            PollIngressSingleResult::SocketStateChanged
-        });
+        #[cfg(feature = "proto-security")]
+        self.inner.restore_location_entry(&mut decap_context);
+
+        // TODO: Propagate the PollIngressSingleResult from deeper.
+        // There's many received packets that we process but can't cause sockets
+        // to change state. For example Beacons, forwarded stuff...
+        // We should return `PacketProcessed` for these to save the user from
+        // doing useless socket polls.
+        PollIngressSingleResult::SocketStateChanged
+    }
 
-        // Update device filter.
+    /// Updates the `device` address filter with the interface hardware address.
+    #[allow(unused_variables)]
+    fn update_filter_addr<D>(&self, device: &mut D)
+    where
+        D: Device + ?Sized,
+    {
         #[cfg(any(feature = "medium-pc5", feature = "medium-ieee80211p"))]
         if self.inner.caps.radio.mac_filter == MacFilterCapabilities::Rx {
             let hardware_addr = self.inner.hardware_addr;
@@ -553,8 +819,88 @@ impl Interface {
                 _ => {}
             }
         }
+    }
 
-        res
+    /// Applies the interface part of a pseudonym change transaction, ie: sets the new
+    /// `hardware_addr`, resets the sequence number and drops the location table, the pending
+    /// location service requests and the buffered packets, which all refer to the previous
+    /// identity. Device address filter and sockets are updated on the next egress poll.
+    pub(crate) fn change_pseudonym(&mut self, hardware_addr: HardwareAddress) {
+        self.inner.hardware_addr = hardware_addr;
+
+        #[cfg(feature = "proto-geonet")]
+        {
+            // Car 2 Car Vehicle C-ITS station profile, requirement RS_BSP_182.
+            // Set sequence number to 0.
+            self.inner.reset_sequence_number();
+            self.inner.location_table.clear();
+            self.location_service = LocationService::new();
+            self.ls_buffer.clear();
+            self.uc_forwarding_buffer.clear();
+            self.bc_forwarding_buffer.clear();
+            self.cb_forwarding_buffer.clear();
+        }
+
+        self.inner.pseudonym_changed = true;
+    }
+
+    /// Completes a pending pseudonym change by updating the `device` address filter and
+    /// notifying the `sockets`. When pseudonym changes are deferred, also holds the privacy
+    /// AT certificate rotations while a locally originated DENM event is disseminated.
+    fn pseudonym_change_egress<D>(
+        &mut self,
+        core: &mut GnCore,
+        device: &mut D,
+        sockets: &mut SocketSet<'_>,
+    ) where
+        D: Device + ?Sized,
+    {
+        #[cfg(feature = "proto-security")]
+        if core.defer_pseudonym_change {
+            #[cfg(feature = "socket-denm")]
+            let hold = sockets.items().any(|item| match &item.socket {
+                Socket::Denm(s) => s.has_active_events(core.now),
+                #[allow(unreachable_patterns)]
+                _ => false,
+            });
+
+            #[cfg(not(feature = "socket-denm"))]
+            let hold = false;
+
+            if let Some(sec) = core.security.as_mut() {
+                if sec.is_privacy_rotation_held() != hold {
+                    net_debug!("pseudonym change on hold: {}", hold);
+                }
+                sec.hold_privacy_rotation(hold);
+            }
+        }
+
+        if !self.inner.pseudonym_changed {
+            return;
+        }
+        self.inner.pseudonym_changed = false;
+
+        #[cfg(any(feature = "medium-pc5", feature = "medium-ieee80211p"))]
+        if self.inner.caps.radio.mac_filter == MacFilterCapabilities::Rx {
+            device.set_filter_addr(Some(self.inner.hardware_addr));
+        }
+
+        #[cfg(not(any(feature = "medium-pc5", feature = "medium-ieee80211p")))]
+        let _ = device;
+
+        #[cfg(not(any(feature = "proto-security", feature = "socket-denm")))]
+        let _ = core;
+
+        for item in sockets.items_mut() {
+            match &mut item.socket {
+                #[cfg(feature = "socket-cam")]
+                Socket::Cam(s) => s.notify_pseudonym_change(),
+                #[cfg(feature = "socket-denm")]
+                Socket::Denm(s) => s.notify_pseudonym_change(core.now),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
     }
 
     fn socket_egress<D>(
@@ -1210,6 +1556,21 @@ impl InterfaceInner {
         self.hardware_addr = addr;
     }
 
+    /// Returns the secured message carried by the received `frame`, if any.
+    #[cfg(feature = "proto-security")]
+    fn frame_secured_message(&self, frame: &[u8]) -> Option<SecuredMessage> {
+        let packet = match self.caps.medium {
+            #[cfg(feature = "medium-ethernet")]
+            Medium::Ethernet => Self::ethernet_geonet_packet(frame),
+            #[cfg(feature = "medium-ieee80211p")]
+            Medium::Ieee80211p => Self::ieee80211p_geonet_packet(frame),
+            #[cfg(feature = "medium-pc5")]
+            Medium::PC5 => None,
+        }?;
+
+        Self::secured_message(packet)
+    }
+
     #[cfg(feature = "medium-ethernet")]
     #[allow(unused)]
     fn check_hardware_addr(addr: &HardwareAddress) {
@@ -1221,8 +1582,10 @@ impl InterfaceInner {
     #[cfg(feature = "socket-geonet")]
     fn geonet_socket_filter(
         &mut self,
+        ctx: &mut InterfaceContext,
         sockets: &mut SocketSet,
         indication: Indication,
+        packet: &GeonetVariant,
         payload: &[u8],
     ) -> bool {
         let mut handled_by_raw_socket = false;
@@ -1232,6 +1595,10 @@ impl InterfaceInner {
             .items_mut()
             .filter_map(|i| GeonetSocket::downcast_mut(&mut i.socket))
         {
+            if !handled_by_raw_socket && !self.consume(ctx, packet) {
+                return false;
+            }
+
             raw_socket.process(self, indication.clone(), payload);
             handled_by_raw_socket = true;
         }
@@ -1273,13 +1640,46 @@ impl InterfaceInner {
         packet: GeonetPacket,
         trc: &mut Congestion,
     ) -> Result<(), DispatchError> {
+        // Packets carrying the ego position vector would allow to link the successive
+        // pseudonyms of the station through its trajectory, do not send them during
+        // silent periods.
+        #[cfg(feature = "proto-security")]
+        if core.is_silent()
+            && packet.repr().inner().source_address().mac_addr() == core.address().mac_addr()
+        {
+            return Err(DispatchError::Security(SecurityServiceError::SilentPeriod));
+        }
+
         self.defer_beacon(core, packet.repr().inner());
 
         #[cfg(feature = "proto-security")]
         let position = core.position().position;
         #[cfg(feature = "proto-security")]
+        let generated_at = core.clock_timestamp();
+        #[cfg(feature = "proto-security")]
         let (packet, mut total_len) = match (&mut core.security, packet.repr()) {
-            (Some(sec_srv), GeonetRepr::ToSecure { repr, permission }) => {
+            (
+                Some(sec_srv),
+                GeonetRepr::ToSecure {
+                    repr,
+                    permission,
+                    confidential,
+                },
+            ) if sec_srv.signing_enabled() => {
+                // Confidential packets are encrypted for their destination only, forwarders
+                // could not route them: refuse them when the destination is not a neighbour.
+                if let GeonetVariant::Unicast(u) = repr {
+                    let dst_addr = u.dst_addr().mac_addr();
+                    if *confidential
+                        && !self
+                            .location_table
+                            .find(&dst_addr)
+                            .is_some_and(|e| e.is_neighbour)
+                    {
+                        return Err(DispatchError::NotNeighbour);
+                    }
+                }
+
                 // Packet has to be secured. Secured content consists of the common header, extended header and payload.
 
                 // Emit the common header, extended header and payload in a buffer.
@@ -1290,8 +1690,20 @@ impl InterfaceInner {
                 let payload_buf = &mut buffer[repr.common_and_extended_header_len()..];
                 packet.emit_payload(payload_buf);
 
-                // Sign the emitted content.
-                match sec_srv.encap_packet(buffer, permission.clone(), core.now, position) {
+                // Sign the emitted content, and encrypt it for the destination if required.
+                let res = match repr {
+                    GeonetVariant::Unicast(u) if *confidential => sec_srv
+                        .encap_packet_confidential(
+                            buffer,
+                            permission.clone(),
+                            generated_at,
+                            position,
+                            u.dst_addr().mac_addr(),
+                        ),
+                    _ => sec_srv.encap_packet(buffer, permission.clone(), generated_at, position),
+                };
+
+                match res {
                     Ok(encapsulated) => {
                         let len = repr.basic_header_len() + encapsulated.len();
                         let pkt = GeonetPacket::new(
@@ -1333,11 +1745,12 @@ impl InterfaceInner {
                 }
             },
             _ => {
-                let len = packet.repr().inner().buffer_len();
-                let pkt = GeonetPacket::new(
-                    GeonetRepr::Unsecured(packet.repr().inner().to_owned()),
-                    packet.payload(),
-                );
+                // Packets built while signing was enabled still announce a Secured Header.
+                let mut repr = packet.repr().inner().to_owned();
+                repr.set_next_header(BHNextHeader::CommonHeader);
+
+                let len = repr.buffer_len();
+                let pkt = GeonetPacket::new(GeonetRepr::Unsecured(repr), packet.payload());
                 (pkt, len)
             }
         };
@@ -1358,6 +1771,7 @@ impl InterfaceInner {
 
         let gn_repr = packet.repr();
         let caps = self.caps.clone();
+        let access_category = gn_repr.inner().traffic_class().access_category();
 
         #[cfg(not(feature = "proto-security"))]
         // First we calculate the total length that we will have to emit.
@@ -1470,12 +1884,24 @@ impl InterfaceInner {
                 // G5 bandwidth is 6 Mbps.
                 let bytes_per_usec: f64 = 6.144 / 8.0;
                 let tx_duration_usec = bytes_per_usec * total_len as f64;
-                trc.controller
-                    .inner_mut()
-                    .notify_tx(core.now, Duration::from_micros(tx_duration_usec as u64));
+                trc.controller.inner_mut().notify_tx(
+                    core.now,
+                    Duration::from_micros(tx_duration_usec as u64),
+                    access_category,
+                );
+                self.count_tx(core, gn_repr.inner());
             })
     }
 
+    /// Accounts the transmission of `repr`, as forwarded if originated by another station.
+    #[cfg(feature = "proto-geonet")]
+    fn count_tx(&mut self, core: &GnCore, repr: &GeonetVariant) {
+        self.stats.tx.count_variant(repr);
+        if repr.source_address().mac_addr() != core.address().mac_addr() {
+            self.stats.forwarded += 1;
+        }
+    }
+
     #[cfg(all(feature = "proto-geonet", feature = "conformance"))]
     pub fn clear_location_table(&mut self) {
         self.location_table.clear();
@@ -1497,4 +1923,7 @@ enum DispatchError {
     /// Security service returned an error on dispatch.
     #[cfg(feature = "proto-security")]
     Security(SecurityServiceError),
+    /// Confidential packet destination is not a neighbour.
+    #[cfg(feature = "proto-security")]
+    NotNeighbour,
 }
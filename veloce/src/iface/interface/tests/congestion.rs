@@ -0,0 +1,181 @@
+use crate::iface::CongestionControl;
+use crate::phy::ChannelBusyRatio;
+use crate::time::Duration;
+
+use super::*;
+
+/// Run the egress path of the interface during `duration`, with the device
+/// sensing the `cbr` channel load. `update` is called before each poll.
+fn run<F>(cbr: f64, duration: Duration, mut update: F) -> SocketSet<'static>
+where
+    F: FnMut(&mut GnCore, &mut SocketSet<'static>),
+{
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    iface.set_congestion_control(CongestionControl::Reactive);
+    device.cbr = ChannelBusyRatio::from_ratio(cbr);
+
+    let start = Instant::from_secs(1_716_674_400);
+    let mut now = start;
+    while now < start + duration {
+        core.set_timestamp(now);
+        update(&mut core, &mut sockets);
+        iface.poll_egress(&mut core, &mut device, &mut sockets);
+        device.queue.clear();
+        now += Duration::from_millis(10);
+    }
+
+    sockets
+}
+
+#[cfg(feature = "socket-cam")]
+#[test]
+fn test_cam_generation_channel_load() {
+    use uom::si::{
+        angle::degree,
+        length::{centimeter, meter},
+        velocity::{centimeter_per_second, meter_per_second},
+    };
+
+    use crate::common::{
+        PotiConfidence, PotiFix, PotiMode, PotiMotion, PotiPosition, PotiPositionConfidence,
+    };
+    use crate::socket::cam::Socket as CamSocket;
+    use crate::types::{decidegree, Heading, Latitude, Length, Longitude, Speed};
+    use crate::wire::StationType;
+
+    // Vehicle turning by 10 degrees every 100ms, ie: a CAM is triggered each
+    // T_CheckCamGen when the channel is not congested.
+    let cam_tx_count = |cbr: f64| {
+        let mut handle = None;
+        let mut heading = 0.0;
+
+        let sockets = run(cbr, Duration::from_secs(5), |core, sockets| {
+            if handle.is_none() {
+                core.set_station_type(StationType::PassengerCar);
+                handle = Some(sockets.add(CamSocket::new()));
+            }
+
+            heading = (heading + 1.0) % 360.0;
+            let fix = PotiFix {
+                mode: PotiMode::Fix3d,
+                timestamp: core.tai2004(),
+                position: PotiPosition {
+                    latitude: Some(Latitude::new::<degree>(48.2764384)),
+                    longitude: Some(Longitude::new::<degree>(-3.5519532)),
+                    altitude: Some(Length::new::<meter>(120.23)),
+                },
+                motion: PotiMotion {
+                    speed: Some(Speed::new::<meter_per_second>(10.0)),
+                    vertical_speed: None,
+                    heading: Some(Heading::new::<degree>(heading)),
+                },
+                confidence: PotiConfidence {
+                    position: PotiPositionConfidence {
+                        semi_major: Some(Length::new::<centimeter>(123.0)),
+                        semi_minor: Some(Length::new::<centimeter>(123.0)),
+                        semi_major_orientation: Some(Heading::new::<decidegree>(10.0)),
+                    },
+                    altitude: Some(Length::new::<meter>(3.7)),
+                    speed: Some(Speed::new::<centimeter_per_second>(5.0)),
+                    heading: Some(Heading::new::<decidegree>(10.0)),
+                },
+            };
+            core.set_position(fix, core.now).unwrap();
+        });
+
+        sockets.get::<CamSocket>(handle.unwrap()).stats().tx
+    };
+
+    // Generation follows the vehicle dynamics, every 100ms.
+    let relaxed = cam_tx_count(0.1);
+    assert!(relaxed >= 45, "relaxed: {relaxed} CAMs");
+
+    // Restrictive state, T_GenCam_DCC is clamped to T_GenCamMax.
+    let congested = cam_tx_count(0.8);
+    assert!(congested <= 6, "congested: {congested} CAMs");
+}
+
+#[cfg(feature = "socket-denm")]
+#[test]
+fn test_denm_repetition_channel_load() {
+    use uom::si::{angle::degree, length::meter};
+    use veloce_asn1::defs::etsi_messages_r2::etsi__its__cdd as cdd;
+
+    use crate::common::geo_area::{Circle, GeoArea, GeoPosition, Shape};
+    use crate::socket::denm::{
+        EventAwareness, EventParameters, RepetitionParameters, Socket as DenmSocket,
+    };
+    use crate::time::TAI2004;
+    use crate::types::{Angle, Distance, Latitude, Longitude};
+    use crate::wire::GnTrafficClass;
+
+    // Event repeated every 100ms during 5 secs.
+    let denm_tx_count = |cbr: f64| {
+        let mut handle = None;
+
+        let sockets = run(cbr, Duration::from_secs(5), |core, sockets| {
+            if handle.is_some() {
+                return;
+            }
+
+            // Station is inside the destination area.
+            core.ego_position_vector.latitude = Latitude::new::<degree>(48.2764384);
+            core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+
+            let denm_handle = sockets.add(DenmSocket::new(vec![], vec![]));
+            let params = EventParameters {
+                detection_time: TAI2004::from_unix_instant(core.now),
+                validity_duration: Some(Duration::from_secs(60)),
+                position: cdd::ReferencePosition {
+                    latitude: cdd::Latitude(482764384),
+                    longitude: cdd::Longitude(-35519532),
+                    position_confidence_ellipse: cdd::PosConfidenceEllipse {
+                        semi_major_confidence: cdd::SemiAxisLength(4095),
+                        semi_minor_confidence: cdd::SemiAxisLength(4095),
+                        semi_major_orientation: cdd::HeadingValue(3601),
+                    },
+                    altitude: cdd::Altitude {
+                        altitude_value: cdd::AltitudeValue(800001),
+                        altitude_confidence: cdd::AltitudeConfidence::unavailable,
+                    },
+                },
+                awareness: EventAwareness::default(),
+                geo_area: GeoArea {
+                    shape: Shape::Circle(Circle {
+                        radius: Distance::new::<meter>(100.0),
+                    }),
+                    position: GeoPosition {
+                        latitude: Latitude::new::<degree>(48.2764384),
+                        longitude: Longitude::new::<degree>(-3.5519532),
+                    },
+                    angle: Angle::new::<degree>(0.0),
+                },
+                repetition: Some(RepetitionParameters {
+                    duration: Duration::from_secs(5),
+                    interval: Duration::from_millis(100),
+                }),
+                keep_alive: None,
+                traffic_class: GnTrafficClass(10),
+                situation_container: None,
+                location_container: None,
+                alacarte_container: None,
+            };
+            sockets
+                .get_mut::<DenmSocket>(denm_handle)
+                .trigger(core, params)
+                .unwrap();
+
+            handle = Some(denm_handle);
+        });
+
+        sockets.get::<DenmSocket>(handle.unwrap()).stats().tx
+    };
+
+    // Repetition interval is respected.
+    let relaxed = denm_tx_count(0.1);
+    assert!(relaxed >= 40, "relaxed: {relaxed} DENMs");
+
+    // Restrictive state, repetitions are stretched to the DCC_FAC generation interval.
+    let congested = denm_tx_count(0.8);
+    assert!(congested <= 6, "congested: {congested} DENMs");
+}
@@ -0,0 +1,154 @@
+use crate::{
+    network::{Request, Transport, UpperProtocol},
+    security::{
+        permission::Permission,
+        secured_message::SecuredMessage,
+        ssp::cam::CamSsp,
+        tests::{create_temp_veloce_dir, setup_confidential_service},
+        EcKeyType,
+    },
+    socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata},
+    storage::PacketBuffer,
+    wire::EthernetFrame,
+};
+
+use super::*;
+
+/// Sends a confidential unicast packet to the local station, which holds a `key_type` AT
+/// certificate with a `key_type` encryption key.
+fn confidential_unicast(key_type: EcKeyType) {
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(1_716_674_400));
+
+    let (storage_dir, _temp_dir) = create_temp_veloce_dir();
+    let mut sec = setup_confidential_service(storage_dir, key_type);
+    let permission = Permission::CAM(CamSsp::new_v1().into());
+
+    // Learn the local AT certificate as the one of the destination, which is the local
+    // station itself so the packet is received back through the testing device.
+    let destination = core.address();
+    let position = core.position().position;
+    let signed = sec
+        .encap_packet(vec![0u8; 8], permission.clone(), core.now, position)
+        .unwrap();
+    let (confirm, _) = sec.decap_packet(&signed, core.now).unwrap();
+    sec.bind_certificate_address(confirm.cert_id, destination.mac_addr());
+    core.security = Some(sec);
+
+    iface
+        .inner
+        .location_table
+        .update_mut(core.now, &core.ego_position_vector())
+        .is_neighbour = true;
+
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY], vec![0; 4096]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 4096]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+
+    let payload = [0xca, 0xfe, 0xca, 0xfe];
+    let request = Request {
+        upper_proto: UpperProtocol::Any,
+        transport: Transport::Unicast(destination),
+        its_aid: permission,
+        confidential: true,
+        ..Default::default()
+    };
+    sockets
+        .get_mut::<GeonetSocket>(handle)
+        .send_slice(&payload, request)
+        .unwrap();
+
+    iface.poll_egress(&mut core, &mut device, &mut sockets);
+
+    // Unicast packet is encrypted, so its secured content is not a signed message.
+    let encrypted = device
+        .queue
+        .iter()
+        .filter(|frame| {
+            let frame = EthernetFrame::new_unchecked(&frame[..]);
+            let gn = frame.payload();
+            gn[0] & 0x0f == 2 && SecuredMessage::from_bytes(&gn[BASIC_HEADER_LEN..]).is_err()
+        })
+        .count();
+    assert_eq!(encrypted, 1);
+    assert!(!device
+        .queue
+        .iter()
+        .any(|frame| frame.windows(payload.len()).any(|w| w == payload)));
+
+    // Packet is decrypted on reception, and delivered as confidential.
+    while !matches!(
+        iface.poll_ingress_single(&mut core, &mut device, &mut sockets),
+        PollIngressSingleResult::None
+    ) {}
+
+    let (data, ind) = sockets.get_mut::<GeonetSocket>(handle).recv().unwrap();
+    assert_eq!(data, payload);
+    assert!(ind.confidential);
+}
+
+#[test]
+fn test_confidential_unicast_nistp256() {
+    confidential_unicast(EcKeyType::NistP256r1);
+}
+
+#[test]
+fn test_confidential_unicast_sm2() {
+    confidential_unicast(EcKeyType::Sm2);
+}
+
+#[test]
+fn test_confidential_unicast_not_neighbour() {
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(1_716_674_400));
+
+    let (storage_dir, _temp_dir) = create_temp_veloce_dir();
+    let mut sec = setup_confidential_service(storage_dir, EcKeyType::NistP256r1);
+    let permission = Permission::CAM(CamSsp::new_v1().into());
+
+    // Destination certificate is known, but the destination is not a neighbour.
+    let destination = core.address();
+    let position = core.position().position;
+    let signed = sec
+        .encap_packet(vec![0u8; 8], permission.clone(), core.now, position)
+        .unwrap();
+    let (confirm, _) = sec.decap_packet(&signed, core.now).unwrap();
+    sec.bind_certificate_address(confirm.cert_id, destination.mac_addr());
+    core.security = Some(sec);
+
+    iface
+        .inner
+        .location_table
+        .update_mut(core.now, &core.ego_position_vector())
+        .is_neighbour = false;
+
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY], vec![0; 4096]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 4096]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+
+    let payload = [0xca, 0xfe, 0xca, 0xfe];
+    let request = Request {
+        upper_proto: UpperProtocol::Any,
+        transport: Transport::Unicast(destination),
+        its_aid: permission,
+        confidential: true,
+        ..Default::default()
+    };
+    sockets
+        .get_mut::<GeonetSocket>(handle)
+        .send_slice(&payload, request)
+        .unwrap();
+
+    iface.poll_egress(&mut core, &mut device, &mut sockets);
+
+    // Forwarders could not decrypt the packet, so it is refused instead of being encrypted.
+    assert!(!device.queue.iter().any(|frame| {
+        let frame = EthernetFrame::new_unchecked(&frame[..]);
+        let gn = frame.payload();
+        gn[0] & 0x0f == 2 && SecuredMessage::from_bytes(&gn[BASIC_HEADER_LEN..]).is_err()
+    }));
+    assert!(!device
+        .queue
+        .iter()
+        .any(|frame| frame.windows(payload.len()).any(|w| w == payload)));
+}
@@ -77,7 +77,11 @@ fn test_receive_gbc() {
     core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
     core.now = Instant::now();
 
-    let (ethernet, gbc) = make_gbc_packet();
+    let (ethernet, mut gbc) = make_gbc_packet();
+
+    // Destination area is away from the sender, the packet is forwarded towards it.
+    gbc.extended_header.latitude = Latitude::new::<degree>(48.265);
+    gbc.extended_header.longitude = Longitude::new::<degree>(-3.58);
 
     let ctx_meta = meta!(core, iface);
     let pkt_meta = PacketMeta::default();
@@ -189,3 +193,130 @@ fn test_receive_gbc() {
     let entry = entry_opt.unwrap();
     assert!(!entry.is_neighbour);
 }
+
+#[test]
+fn test_receive_gbc_from_inside_area() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    // Destination area is centered on the sender, and we are outside of it.
+    let (ethernet, gbc) = make_gbc_packet();
+
+    let ctx_meta = meta!(core, iface);
+    let pkt_meta = PacketMeta::default();
+    let mut sec_buf = SecuredDataBuffer::default();
+
+    let mut buf = [0u8; GBC_LEN];
+    gbc.emit(&mut buf);
+
+    let res = iface.inner.process_geonet_packet(
+        ctx_meta,
+        &mut sockets,
+        pkt_meta,
+        &buf,
+        ethernet,
+        &mut sec_buf,
+    );
+
+    // Packet received from a sender inside the area should not leave it.
+    assert!(res.is_none());
+
+    // Station should be in Location table
+    let entry_opt = iface.inner.location_table.find(&ethernet.src_addr);
+    assert!(entry_opt.is_some());
+}
+
+#[cfg(feature = "socket-geonet")]
+#[test]
+fn test_receive_gbc_duplicate() {
+    use crate::socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata};
+    use crate::storage::PacketBuffer;
+
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY; 2], vec![0; 1024]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 1024]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+
+    // We are inside the destination area, duplicates are not discarded by the CBF algorithm.
+    let (ethernet, mut gbc) = make_gbc_packet();
+    gbc.extended_header.latitude = core.ego_position_vector.latitude;
+    gbc.extended_header.longitude = core.ego_position_vector.longitude;
+    gbc.common_header.payload_len = 4;
+
+    let mut buf = [0u8; GBC_LEN + 4];
+    gbc.emit(&mut buf);
+    buf[GBC_LEN..].copy_from_slice(&[0xca, 0xfe, 0xca, 0xfe]);
+
+    for _ in 0..2 {
+        let ctx_meta = meta!(core, iface);
+        let mut sec_buf = SecuredDataBuffer::default();
+        iface.inner.process_geonet_packet(
+            ctx_meta,
+            &mut sockets,
+            PacketMeta::default(),
+            &buf,
+            ethernet,
+            &mut sec_buf,
+        );
+    }
+
+    // Packet should be passed to the upper layer only once.
+    let socket = sockets.get_mut::<GeonetSocket>(handle);
+    assert!(socket.recv().is_ok());
+    assert!(socket.recv().is_err());
+}
+
+#[test]
+fn test_receive_gbc_cbf_timer() {
+    use crate::time::Duration;
+
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.ego_position_vector.is_accurate = true;
+    core.now = Instant::now();
+
+    // We are inside the destination area, the sender is 500 meters away.
+    let (ethernet, mut gbc) = make_gbc_packet();
+    gbc.extended_header.latitude = core.ego_position_vector.latitude;
+    gbc.extended_header.longitude = core.ego_position_vector.longitude;
+    gbc.extended_header.source_position_vector.latitude = Latitude::new::<degree>(48.280931);
+    gbc.extended_header.source_position_vector.longitude = core.ego_position_vector.longitude;
+
+    let ctx_meta = meta!(core, iface);
+    let pkt_meta = PacketMeta::default();
+    let mut sec_buf = SecuredDataBuffer::default();
+
+    let mut buf = [0u8; GBC_LEN];
+    gbc.emit(&mut buf);
+
+    let forwarded = iface
+        .inner
+        .process_geonet_packet(
+            ctx_meta,
+            &mut sockets,
+            pkt_meta,
+            &buf,
+            ethernet,
+            &mut sec_buf,
+        )
+        .is_some();
+
+    // Packet should be buffered by the CBF algorithm.
+    assert!(!forwarded);
+
+    // CBF timer is halfway between the maximum and minimum ones.
+    assert_eq!(
+        iface.cb_forwarding_buffer.poll_at(),
+        Some(core.now + Duration::from_millis(50))
+    );
+}
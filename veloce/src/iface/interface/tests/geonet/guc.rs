@@ -192,3 +192,83 @@ fn test_receive_ls_rep() {
     let entry = entry_opt.unwrap();
     assert!(!entry.is_neighbour);
 }
+
+/// Buffer `packet` in the Location Service buffer and flush it, returns its next hop.
+fn flush_ls_buffer(
+    core: &mut GnCore,
+    iface: &mut Interface,
+    packet: GeonetUnicast,
+) -> Option<EthernetAddress> {
+    let packet = GeonetRepr::Unsecured(packet);
+    assert!(iface.ls_buffer.enqueue(packet, &[], core.now).is_ok());
+    iface.ls_buffer.mark_flush(core.now, |_| true);
+
+    let mut next_hop = None;
+    let ctx_meta = meta!(core, iface);
+    iface
+        .inner
+        .dispatch_ls_buffer(ctx_meta, |_, _, _, (dst_ll_addr, _, _)| {
+            next_hop = Some(dst_ll_addr);
+            Ok::<(), ()>(())
+        });
+    next_hop
+}
+
+#[test]
+fn test_flush_ls_buffer_next_hop() {
+    let (mut core, mut iface, _sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    let (_, geo_uc) = make_guc_packet();
+    let dst_pv = LongPositionVectorRepr {
+        address: geo_uc.extended_header.destination_position_vector.address,
+        latitude: Latitude::new::<degree>(48.300000),
+        longitude: Longitude::new::<degree>(-3.5519532),
+        is_accurate: true,
+        ..Default::default()
+    };
+    let neigh_pv = LongPositionVectorRepr {
+        address: GnAddress::new(
+            true,
+            StationType::PassengerCar,
+            EthernetAddress([0x05, 0x05, 0x05, 0x05, 0x05, 0x05]),
+        ),
+        latitude: Latitude::new::<degree>(48.285000),
+        longitude: Longitude::new::<degree>(-3.5519532),
+        is_accurate: true,
+        ..Default::default()
+    };
+    let dst_ll_addr = dst_pv.address.mac_addr();
+    let neigh_ll_addr = neigh_pv.address.mac_addr();
+
+    // Destination has been resolved, a neighbour is between us and the destination.
+    iface.inner.location_table.update_mut(core.now, &dst_pv);
+    iface
+        .inner
+        .location_table
+        .update_mut(core.now, &neigh_pv)
+        .is_neighbour = true;
+
+    // Packet should be forwarded to the neighbour with the most forward progress.
+    let next_hop = flush_ls_buffer(&mut core, &mut iface, geo_uc.clone());
+    assert_eq!(next_hop, Some(neigh_ll_addr));
+
+    // Packet should be sent directly to a neighbour destination.
+    let dst_entry = iface.inner.location_table.find_mut(&dst_ll_addr).unwrap();
+    dst_entry.is_neighbour = true;
+
+    let next_hop = flush_ls_buffer(&mut core, &mut iface, geo_uc.clone());
+    assert_eq!(next_hop, Some(dst_ll_addr));
+
+    // Packet should be broadcast when no neighbour is closer to the destination.
+    let dst_entry = iface.inner.location_table.find_mut(&dst_ll_addr).unwrap();
+    dst_entry.is_neighbour = false;
+    let neigh_entry = iface.inner.location_table.find_mut(&neigh_ll_addr).unwrap();
+    neigh_entry.is_neighbour = false;
+
+    let next_hop = flush_ls_buffer(&mut core, &mut iface, geo_uc);
+    assert_eq!(next_hop, Some(EthernetAddress::BROADCAST));
+}
@@ -6,7 +6,7 @@ use uom::si::{
 
 use crate::{
     config,
-    iface::ContextMeta,
+    iface::{ContextMeta, DropReason},
     types::{tenth_of_microdegree, Heading, Latitude, Longitude, Speed},
     wire::{
         BHNextHeader, BasicHeaderRepr, CommonHeaderRepr, EthernetRepr, GeonetLocationServiceReply,
@@ -123,6 +123,29 @@ fn make_ls_rep_packet() -> (EthernetRepr, GeonetLocationServiceReply) {
     (ethernet, ls_rep)
 }
 
+/// Process the received Location Service `packet`, returns whether it is forwarded.
+fn process_ls_packet(
+    core: &mut GnCore,
+    iface: &mut Interface,
+    sockets: &mut SocketSet,
+    packet: &[u8],
+    ethernet: EthernetRepr,
+) -> bool {
+    let ctx_meta = meta!(core, iface);
+    let mut sec_buf = SecuredDataBuffer::default();
+    iface
+        .inner
+        .process_geonet_packet(
+            ctx_meta,
+            sockets,
+            PacketMeta::default(),
+            packet,
+            ethernet,
+            &mut sec_buf,
+        )
+        .is_some()
+}
+
 #[test]
 fn test_receive_ls_req() {
     let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
@@ -351,3 +374,119 @@ fn test_receive_ls_rep() {
     let entry = entry_opt.unwrap();
     assert!(!entry.is_neighbour);
 }
+
+#[test]
+fn test_receive_ls_req_duplicate() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    let (ethernet, mut ls_req) = make_ls_req_packet();
+
+    let mut buf = [0u8; LS_REQ_LEN];
+    ls_req.emit(&mut buf);
+
+    // Duplicates are detected on the source of the request, not on the requested address.
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(forwarded);
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(!forwarded);
+    assert_eq!(iface.stats().duplicates, 1);
+
+    // Same request, with the same sequence number, from another station.
+    let other_ll_addr = EthernetAddress([0x05, 0x05, 0x05, 0x05, 0x05, 0x05]);
+    ls_req.extended_header.source_position_vector.address =
+        GnAddress::new(true, StationType::PassengerCar, other_ll_addr);
+    ls_req.emit(&mut buf);
+
+    let ethernet = EthernetRepr {
+        src_addr: other_ll_addr,
+        ..ethernet
+    };
+
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(forwarded);
+    assert_eq!(iface.stats().duplicates, 1);
+}
+
+#[test]
+fn test_receive_ls_req_loopback() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    // Our own request, received back from a forwarder.
+    let (ethernet, mut ls_req) = make_ls_req_packet();
+    ls_req.extended_header.source_position_vector.address = core.address();
+
+    let mut buf = [0u8; LS_REQ_LEN];
+    ls_req.emit(&mut buf);
+
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(!forwarded);
+    assert_eq!(iface.stats().dropped.get(DropReason::Loopback), 1);
+
+    // Station should not be in Location table
+    assert!(iface
+        .inner
+        .location_table
+        .find(&core.address().mac_addr())
+        .is_none());
+}
+
+#[test]
+fn test_receive_ls_rep_completes_request() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+
+    core.ego_position_vector.latitude = Latitude::new::<degree>(48.276434);
+    core.ego_position_vector.longitude = Longitude::new::<degree>(-3.5519532);
+    core.now = Instant::now();
+
+    // Reply to our own request.
+    let (ethernet, mut ls_rep) = make_ls_rep_packet();
+    ls_rep.extended_header.destination_position_vector.address = core.address();
+    let src_addr = ls_rep.extended_header.source_position_vector.address;
+
+    // Location Service request is pending for the replying station.
+    let handle = iface.location_service.request(src_addr, core.now).ok();
+    assert!(handle.is_some());
+    let pv = LongPositionVectorRepr {
+        address: src_addr,
+        ..Default::default()
+    };
+    iface
+        .inner
+        .location_table
+        .update_mut(core.now, &pv)
+        .ls_pending = handle;
+
+    let mut buf = [0u8; LS_REP_LEN];
+    ls_rep.emit(&mut buf);
+
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(!forwarded);
+
+    // Request should be completed.
+    assert!(iface
+        .inner
+        .location_table
+        .find(&src_addr.mac_addr())
+        .is_some_and(|e| e.ls_pending.is_none()));
+    assert!(iface
+        .location_service
+        .ls_requests
+        .iter()
+        .all(Option::is_none));
+
+    // Request should not be cancelled again by the next packets of the station.
+    ls_rep.extended_header.sequence_number = SequenceNumber(1665);
+    ls_rep.emit(&mut buf);
+
+    let forwarded = process_ls_packet(&mut core, &mut iface, &mut sockets, &buf, ethernet);
+    assert!(!forwarded);
+    assert_eq!(iface.stats().duplicates, 0);
+}
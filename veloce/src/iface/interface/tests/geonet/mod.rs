@@ -1,3 +1,9 @@
+#[cfg(all(
+    feature = "pki",
+    feature = "security-backend-openssl",
+    feature = "socket-geonet"
+))]
+mod confidential;
 mod gac;
 mod gbc;
 mod guc;
@@ -35,6 +41,18 @@ macro_rules! meta {
             decap_context: &mut DecapContext::default(),
         }
     };
+    ($core: ident, $iface: ident, $decap_context: ident) => {
+        ContextMeta {
+            core: &mut $core,
+            ls: &mut $iface.location_service,
+            congestion_control: &mut $iface.congestion_control,
+            ls_buffer: &mut $iface.ls_buffer,
+            uc_forwarding_buffer: &mut $iface.uc_forwarding_buffer,
+            bc_forwarding_buffer: &mut $iface.bc_forwarding_buffer,
+            cb_forwarding_buffer: &mut $iface.cb_forwarding_buffer,
+            decap_context: &mut $decap_context,
+        }
+    };
 }
 
 use meta;
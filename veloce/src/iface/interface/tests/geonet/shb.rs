@@ -2,7 +2,7 @@ use uom::si::{angle::degree, velocity::meter_per_second};
 
 use crate::{
     config,
-    iface::ContextMeta,
+    iface::{ContextMeta, DropReason},
     types::{Heading, Latitude, Longitude, Speed},
     wire::{
         BHNextHeader, BasicHeaderRepr, CommonHeaderRepr, EthernetRepr, GeonetPacketType,
@@ -100,3 +100,338 @@ fn test_receive_shb() {
     let entry = entry_opt.unwrap();
     assert!(entry.is_neighbour);
 }
+
+#[test]
+fn test_receive_shb_stats() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+    core.now = Instant::now();
+
+    let (ethernet, shb) = make_shb_packet();
+    let mut buf = [0u8; SHB_LEN];
+    shb.emit(&mut buf);
+
+    let ctx_meta = meta!(core, iface);
+    let mut sec_buf = SecuredDataBuffer::default();
+    iface.inner.process_geonet_packet(
+        ctx_meta,
+        &mut sockets,
+        PacketMeta::default(),
+        &buf,
+        ethernet,
+        &mut sec_buf,
+    );
+
+    let stats = iface.stats();
+    assert_eq!(stats.rx.single_hop_broadcast, 1);
+    assert_eq!(stats.rx.total(), 1);
+    assert_eq!(stats.tx.total(), 0);
+    assert_eq!(stats.dropped.total(), 0);
+    assert_eq!(stats.location_table.inserted, 1);
+    assert_eq!(stats.location_table.entries, 1);
+}
+
+#[test]
+fn test_receive_shb_dropped_stats() {
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+    core.now = Instant::now();
+
+    let (ethernet, mut shb) = make_shb_packet();
+    shb.basic_header.version = config::GN_PROTOCOL_VERSION + 1;
+    let mut buf = [0u8; SHB_LEN];
+    shb.emit(&mut buf);
+
+    // Unsupported protocol version.
+    let ctx_meta = meta!(core, iface);
+    let mut sec_buf = SecuredDataBuffer::default();
+    iface.inner.process_geonet_packet(
+        ctx_meta,
+        &mut sockets,
+        PacketMeta::default(),
+        &buf,
+        ethernet,
+        &mut sec_buf,
+    );
+
+    // Truncated basic header.
+    let ctx_meta = meta!(core, iface);
+    iface.inner.process_geonet_packet(
+        ctx_meta,
+        &mut sockets,
+        PacketMeta::default(),
+        &buf[..BASIC_HEADER_LEN - 1],
+        ethernet,
+        &mut sec_buf,
+    );
+
+    let stats = iface.stats();
+    assert_eq!(stats.dropped.get(DropReason::Version), 1);
+    assert_eq!(stats.dropped.get(DropReason::Malformed), 1);
+    assert_eq!(stats.dropped.total(), 2);
+    assert_eq!(stats.rx.total(), 0);
+    assert_eq!(stats.location_table.inserted, 0);
+}
+
+/// Make a SHB packet secured by `sec`, sent at `timestamp` from `position`.
+#[cfg(all(feature = "proto-security", feature = "security-backend-openssl"))]
+fn make_secured_shb_packet(
+    sec: &mut crate::security::SecurityService,
+    timestamp: Instant,
+    position: crate::common::PotiPosition,
+) -> (EthernetRepr, GeonetSingleHop, alloc::vec::Vec<u8>) {
+    use crate::security::{permission::Permission, ssp::cam::CamSsp};
+
+    // Secure the SHB packet, the Basic Header being outside of the security envelope.
+    let (ethernet, mut shb) = make_shb_packet();
+    shb.basic_header.next_header = BHNextHeader::SecuredHeader;
+    shb.extended_header.source_position_vector.timestamp =
+        TAI2004::from_unix_instant(timestamp).into();
+    let mut buf = [0u8; SHB_LEN];
+    shb.emit(&mut buf);
+
+    let secured = sec
+        .encap_packet(
+            buf[BASIC_HEADER_LEN..].to_vec(),
+            Permission::CAM(CamSsp::new_v1().into()),
+            timestamp,
+            position,
+        )
+        .unwrap();
+
+    (ethernet, shb, [&buf[..BASIC_HEADER_LEN], &secured].concat())
+}
+
+/// Process the received `packet` as the interface does for a received frame, ie: the Location
+/// Table entry of the sender is restored once processed if the packet has not been verified.
+#[cfg(feature = "proto-security")]
+fn process_received(
+    core: &mut GnCore,
+    iface: &mut Interface,
+    sockets: &mut SocketSet,
+    packet: &[u8],
+    ethernet: EthernetRepr,
+) {
+    let mut decap_context = DecapContext::default();
+    let ctx_meta = meta!(core, iface, decap_context);
+    let mut sec_buf = SecuredDataBuffer::default();
+    iface.inner.process_geonet_packet(
+        ctx_meta,
+        sockets,
+        PacketMeta::default(),
+        packet,
+        ethernet,
+        &mut sec_buf,
+    );
+    iface.inner.restore_location_entry(&mut decap_context);
+}
+
+#[cfg(all(
+    feature = "proto-security",
+    feature = "security-backend-openssl",
+    feature = "socket-geonet"
+))]
+#[test]
+fn test_receive_shb_on_demand_verification() {
+    use crate::security::{service::VerificationMode, tests::setup_security_service};
+    use crate::socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata};
+    use crate::storage::PacketBuffer;
+
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(1_716_674_400));
+
+    let mut sec = setup_security_service();
+    sec.set_verification_mode(VerificationMode::OnDemand);
+    let (ethernet, shb, packet) =
+        make_secured_shb_packet(&mut sec, core.now, core.position().position);
+    core.security = Some(sec);
+
+    // Signature is the last field of the security envelope.
+    let mut forged = packet.clone();
+    *forged.last_mut().unwrap() ^= 0xff;
+
+    let src_addr = shb
+        .extended_header
+        .source_position_vector
+        .address
+        .mac_addr();
+
+    // Location Table is tentatively updated with a packet which is not consumed,
+    // and its signature is not verified.
+    process_received(&mut core, &mut iface, &mut sockets, &packet, ethernet);
+
+    assert!(iface
+        .inner
+        .location_table
+        .find(&src_addr)
+        .is_some_and(|e| e.tentative));
+
+    let stats = core.security().unwrap().verification_stats();
+    assert_eq!(stats.deferred, 1);
+    assert_eq!(stats.verified, 0);
+    assert_eq!(stats.skipped(), 1);
+
+    // Packets are consumed by a socket from now on.
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY; 2], vec![0; 1024]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 1024]);
+    sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+
+    // Tentative entry is restored when a packet whose signature is false is consumed.
+    process_received(&mut core, &mut iface, &mut sockets, &forged, ethernet);
+
+    assert!(iface
+        .inner
+        .location_table
+        .find(&src_addr)
+        .is_some_and(|e| e.tentative));
+    assert_eq!(iface.stats().dropped.get(DropReason::Security), 1);
+    assert_eq!(core.security().unwrap().verification_stats().failed, 1);
+
+    // Entry is confirmed once a consumed packet is verified.
+    process_received(&mut core, &mut iface, &mut sockets, &packet, ethernet);
+
+    assert!(iface
+        .inner
+        .location_table
+        .find(&src_addr)
+        .is_some_and(|e| !e.tentative));
+    assert_eq!(iface.stats().dropped.get(DropReason::Security), 1);
+
+    let stats = core.security().unwrap().verification_stats();
+    assert_eq!(stats.deferred, 3);
+    assert_eq!(stats.consumed, 2);
+    assert_eq!(stats.verified, 2);
+    assert_eq!(stats.failed, 1);
+}
+
+#[cfg(all(
+    feature = "proto-security",
+    feature = "security-backend-openssl",
+    feature = "socket-geonet"
+))]
+#[test]
+fn test_receive_forged_shb_verified_neighbour() {
+    use crate::security::{service::VerificationMode, tests::setup_security_service};
+    use crate::socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata};
+    use crate::storage::PacketBuffer;
+    use crate::time::Duration;
+
+    let (mut core, mut iface, mut sockets, _device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(1_716_674_400));
+
+    let mut sec = setup_security_service();
+    sec.set_verification_mode(VerificationMode::OnDemand);
+    let (ethernet, shb, packet) =
+        make_secured_shb_packet(&mut sec, core.now, core.position().position);
+    core.security = Some(sec);
+
+    let src_addr = shb
+        .extended_header
+        .source_position_vector
+        .address
+        .mac_addr();
+
+    // Neighbour is verified with a consumed packet.
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY; 2], vec![0; 1024]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 1024]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+    process_received(&mut core, &mut iface, &mut sockets, &packet, ethernet);
+
+    let verified = iface.inner.location_table.find(&src_addr).cloned().unwrap();
+    assert!(!verified.tentative && verified.is_neighbour);
+    sockets.remove(handle);
+
+    // Forged packet claims a newer position of the neighbour.
+    core.set_timestamp(core.now + Duration::from_secs(1));
+    let mut sec = core.security.take().unwrap();
+    let (_, _, mut forged) = make_secured_shb_packet(&mut sec, core.now, core.position().position);
+    core.security = Some(sec);
+    *forged.last_mut().unwrap() ^= 0xff;
+
+    // Forged packet is not consumed: neither verified nor applied to the verified entry.
+    process_received(&mut core, &mut iface, &mut sockets, &forged, ethernet);
+
+    let entry = iface.inner.location_table.find(&src_addr).unwrap();
+    assert!(!entry.tentative);
+    assert_eq!(entry.position_vector, verified.position_vector);
+    assert_eq!(entry.expires_at, verified.expires_at);
+    assert_eq!(core.security().unwrap().verification_stats().verified, 1);
+
+    // Forged packet is consumed: rejected, and the verified entry is kept unchanged.
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY; 2], vec![0; 1024]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 1024]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+    process_received(&mut core, &mut iface, &mut sockets, &forged, ethernet);
+
+    let entry = iface.inner.location_table.find(&src_addr).unwrap();
+    assert!(!entry.tentative);
+    assert_eq!(entry.position_vector, verified.position_vector);
+    assert_eq!(entry.expires_at, verified.expires_at);
+    assert_eq!(iface.stats().dropped.get(DropReason::Security), 1);
+    assert_eq!(core.security().unwrap().verification_stats().failed, 1);
+    assert!(sockets.get_mut::<GeonetSocket>(handle).recv().is_err());
+}
+
+#[cfg(all(feature = "proto-security", feature = "security-backend-openssl"))]
+#[test]
+fn test_receive_shb_batch_verification() {
+    use crate::security::{
+        permission::Permission, ssp::cam::CamSsp, tests::setup_security_service,
+    };
+    use crate::wire::EthernetFrame;
+
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(1_716_674_400));
+
+    let mut sec = setup_security_service();
+
+    let (ethernet, mut shb) = make_shb_packet();
+    shb.basic_header.next_header = BHNextHeader::SecuredHeader;
+    shb.extended_header.source_position_vector.timestamp =
+        TAI2004::from_unix_instant(core.now).into();
+    let mut buf = [0u8; SHB_LEN];
+    shb.emit(&mut buf);
+
+    // First frame carries the signer certificate, the next ones its digest.
+    let frames: Vec<_> = (0..3)
+        .map(|i| {
+            let secured = sec
+                .encap_packet(
+                    buf[BASIC_HEADER_LEN..].to_vec(),
+                    Permission::CAM(CamSsp::new_v1().into()),
+                    core.now + Duration::from_millis(100 * i),
+                    core.position().position,
+                )
+                .unwrap();
+            let packet = [&buf[..BASIC_HEADER_LEN], &secured].concat();
+
+            let mut frame = vec![0u8; ethernet.buffer_len() + packet.len()];
+            let mut eth_frame = EthernetFrame::new_unchecked(&mut frame[..]);
+            ethernet.emit(&mut eth_frame);
+            eth_frame.payload_mut().copy_from_slice(&packet);
+            heapless::Vec::from_slice(&frame).unwrap()
+        })
+        .collect();
+    core.security = Some(sec);
+
+    // Signer certificate is unknown, the frame is verified on its own.
+    device.queue.push_back(frames[0].clone()).unwrap();
+    iface.poll(&mut core, &mut device, &mut sockets);
+    device.queue.clear();
+
+    let stats = core.security().unwrap().verification_stats();
+    assert_eq!(stats.verified, 1);
+    assert_eq!(stats.batched, 0);
+
+    // Frames received in the same poll are verified in a single batch, a frame received
+    // twice being verified once.
+    for frame in [&frames[1], &frames[2], &frames[1]] {
+        device.queue.push_back(frame.clone()).unwrap();
+    }
+    iface.poll(&mut core, &mut device, &mut sockets);
+
+    let stats = core.security().unwrap().verification_stats();
+    assert_eq!(stats.verified, 3);
+    assert_eq!(stats.batched, 2);
+    assert_eq!(stats.cache_hits, 1);
+    assert_eq!(stats.failed, 0);
+    assert_eq!(iface.stats().dropped.get(DropReason::Security), 0);
+}
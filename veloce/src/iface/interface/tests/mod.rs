@@ -1,5 +1,11 @@
 #[cfg(feature = "proto-geonet")]
+mod congestion;
+#[cfg(feature = "proto-geonet")]
 mod geonet;
+#[cfg(feature = "proto-geonet")]
+mod pseudonym;
+#[cfg(feature = "proto-geonet")]
+mod sim;
 
 use crate::tests::setup;
 
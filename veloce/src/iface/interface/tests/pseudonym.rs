@@ -0,0 +1,222 @@
+use crate::network::{GnAddrConfigMode, GnCoreGonfig};
+use crate::types::Pseudonym;
+use crate::wire::{SequenceNumber, StationType};
+
+use super::*;
+
+/// Fill the interface with state linked to the current identity of the station.
+fn fill_identity_state(iface: &mut Interface, core: &GnCore) {
+    iface
+        .inner
+        .location_table
+        .update(core.now, &core.ego_position_vector());
+    iface.inner.sequence_number = SequenceNumber(42);
+}
+
+/// Check the interface state linked to the previous identity has been dropped.
+fn check_identity_state_dropped(iface: &Interface, core: &GnCore) {
+    assert_eq!(
+        iface.inner.hardware_addr,
+        core.address().mac_addr().into_hardware_address()
+    );
+    assert_eq!(iface.inner.sequence_number.0, 0);
+    assert_eq!(iface.inner.location_table.stats().entries, 0);
+    assert!(iface.inner.pseudonym_changed);
+}
+
+#[test]
+fn test_anonymous_pseudonym_change() {
+    let (_, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+
+    let mut config = GnCoreGonfig::new(StationType::RoadSideUnit, Pseudonym(0xabcd));
+    config.addr_config_mode = GnAddrConfigMode::Anonymous;
+    let mut core = GnCore::new(config, Instant::ZERO);
+
+    // Address is derived from the pseudonym.
+    assert_eq!(
+        core.address().mac_addr().as_bytes()[2..],
+        0xabcd_u32.to_be_bytes()
+    );
+
+    fill_identity_state(&mut iface, &core);
+    let prev_addr = core.address();
+
+    core.change_pseudonym(&mut iface, Pseudonym(0x1234_5678));
+
+    assert_eq!(core.pseudonym(), Pseudonym(0x1234_5678));
+    assert_ne!(core.address(), prev_addr);
+    assert_eq!(
+        core.address().mac_addr().as_bytes()[2..],
+        0x1234_5678_u32.to_be_bytes()
+    );
+    check_identity_state_dropped(&iface, &core);
+
+    // Change is completed on the next egress poll.
+    iface.poll_egress(&mut core, &mut device, &mut sockets);
+    assert!(!iface.inner.pseudonym_changed);
+}
+
+#[test]
+fn test_managed_pseudonym_change() {
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+
+    fill_identity_state(&mut iface, &core);
+    let prev_addr = core.address();
+
+    core.change_pseudonym(&mut iface, Pseudonym(0x1234_5678));
+
+    // Managed address is kept.
+    assert_eq!(core.pseudonym(), Pseudonym(0x1234_5678));
+    assert_eq!(core.address(), prev_addr);
+    check_identity_state_dropped(&iface, &core);
+
+    iface.poll_egress(&mut core, &mut device, &mut sockets);
+    assert!(!iface.inner.pseudonym_changed);
+}
+
+#[cfg(all(
+    feature = "proto-security",
+    feature = "security-backend-openssl",
+    feature = "socket-denm"
+))]
+#[test]
+fn test_deferred_pseudonym_change() {
+    use uom::si::{angle::degree, length::meter};
+    use veloce_asn1::defs::etsi_messages_r2::etsi__its__cdd as cdd;
+
+    use crate::common::geo_area::{Circle, GeoArea, GeoPosition, Shape};
+    use crate::security::tests::setup_security_service;
+    use crate::socket::denm::{EventAwareness, EventParameters, Socket as DenmSocket};
+    use crate::time::{Duration, TAI2004};
+    use crate::types::{Angle, Distance, Latitude, Longitude};
+    use crate::wire::GnTrafficClass;
+
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    core.set_timestamp(Instant::from_secs(100));
+
+    // Trigger an event, valid for 60 secs.
+    let denm_handle = sockets.add(DenmSocket::new(vec![], vec![]));
+    let params = EventParameters {
+        detection_time: TAI2004::from_unix_instant(core.now),
+        validity_duration: Some(Duration::from_secs(60)),
+        position: cdd::ReferencePosition {
+            latitude: cdd::Latitude(482764384),
+            longitude: cdd::Longitude(-35519532),
+            position_confidence_ellipse: cdd::PosConfidenceEllipse {
+                semi_major_confidence: cdd::SemiAxisLength(4095),
+                semi_minor_confidence: cdd::SemiAxisLength(4095),
+                semi_major_orientation: cdd::HeadingValue(3601),
+            },
+            altitude: cdd::Altitude {
+                altitude_value: cdd::AltitudeValue(800001),
+                altitude_confidence: cdd::AltitudeConfidence::unavailable,
+            },
+        },
+        awareness: EventAwareness::default(),
+        geo_area: GeoArea {
+            shape: Shape::Circle(Circle {
+                radius: Distance::new::<meter>(100.0),
+            }),
+            position: GeoPosition {
+                latitude: Latitude::new::<degree>(48.2764384),
+                longitude: Longitude::new::<degree>(-3.5519532),
+            },
+            angle: Angle::new::<degree>(0.0),
+        },
+        repetition: None,
+        keep_alive: None,
+        traffic_class: GnTrafficClass(10),
+        situation_container: None,
+        location_container: None,
+        alacarte_container: None,
+    };
+    sockets
+        .get_mut::<DenmSocket>(denm_handle)
+        .trigger(&core, params)
+        .unwrap();
+
+    core.security = Some(setup_security_service());
+    let held = |core: &GnCore| core.security().unwrap().is_privacy_rotation_held();
+
+    // Pseudonym changes are not deferred.
+    iface.pseudonym_change_egress(&mut core, &mut device, &mut sockets);
+    assert!(!held(&core));
+
+    // Pseudonym changes are deferred while the event is active.
+    core.defer_pseudonym_change = true;
+    iface.pseudonym_change_egress(&mut core, &mut device, &mut sockets);
+    assert!(held(&core));
+    assert!(core.security().unwrap().poll_at().is_none());
+
+    // Rotation is released once the event has expired.
+    core.set_timestamp(core.now + Duration::from_secs(61));
+    iface.pseudonym_change_egress(&mut core, &mut device, &mut sockets);
+    assert!(!held(&core));
+}
+
+#[cfg(all(feature = "proto-security", feature = "security-backend-openssl"))]
+#[test]
+fn test_silent_period_egress() {
+    use crate::config::GN_BEACON_SERVICE_RETRANSMIT_TIMER;
+    use crate::network::{Request, Transport, UpperProtocol};
+    use crate::security::{
+        permission::Permission, privacy::PrivacyStrategy, ssp::denm::DenmSsp,
+        tests::setup_privacy_security_service, trust_chain::ATDailyUsage,
+    };
+    use crate::socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata};
+    use crate::storage::PacketBuffer;
+    use crate::time::Duration;
+
+    let (mut core, mut iface, mut sockets, mut device) = setup(Medium::Ethernet);
+    let t0 = Instant::from_secs(1_716_674_400);
+    core.set_timestamp(t0);
+    core.security = Some(setup_privacy_security_service(
+        PrivacyStrategy::SilentPeriod {
+            interval: Duration::from_secs(60),
+            min_silence: Duration::from_secs(10),
+            max_silence: Duration::from_secs(10),
+            seed: 0xfeed_beef,
+        },
+        ATDailyUsage::default(),
+    ));
+
+    let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY], vec![0; 4096]);
+    let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 4096]);
+    let handle = sockets.add(GeonetSocket::new(rx_buffer, tx_buffer));
+    let request = Request {
+        upper_proto: UpperProtocol::Any,
+        transport: Transport::SingleHopBroadcast,
+        its_aid: Permission::DENM(DenmSsp::new_v1().into()),
+        ..Default::default()
+    };
+
+    // Poll the core and the interface egress at `timestamp`, returns the number of sent frames.
+    let mut poll_at = |core: &mut GnCore, sockets: &mut SocketSet, timestamp: Instant| {
+        core.set_timestamp(timestamp);
+        core.poll(&mut iface, timestamp);
+        device.queue.clear();
+        iface.poll_egress(core, &mut device, sockets);
+        device.queue.len()
+    };
+
+    // Beacon is sent before the certificate change.
+    assert_eq!(poll_at(&mut core, &mut sockets, t0), 1);
+    assert!(!core.is_silent());
+
+    // Certificate change starts a silent period.
+    let change = t0 + Duration::from_secs(60);
+    poll_at(&mut core, &mut sockets, change);
+    assert!(core.is_silent());
+
+    // Neither the beacon nor the SHB carrying the ego position vector are sent.
+    sockets
+        .get_mut::<GeonetSocket>(handle)
+        .send_slice(&[0xca, 0xfe], request)
+        .unwrap();
+    let sent = poll_at(&mut core, &mut sockets, change + Duration::from_secs(5));
+    assert_eq!(sent, 0);
+
+    // Beacons are sent again after the silent period.
+    let end = change + Duration::from_secs(10) + GN_BEACON_SERVICE_RETRANSMIT_TIMER;
+    assert!(poll_at(&mut core, &mut sockets, end) > 0);
+}
@@ -0,0 +1,362 @@
+use uom::si::angle::degree;
+#[cfg(feature = "socket-geonet")]
+use uom::si::length::meter;
+
+use crate::common::geo_area::GeoPosition;
+#[cfg(feature = "socket-geonet")]
+use crate::common::geo_area::{Circle, GeoArea, Shape};
+use crate::iface::{Config, Interface, SocketSet};
+use crate::network::{GnAddrConfigMode, GnCore, GnCoreGonfig};
+use crate::phy::sim::{Channel, Config as SimConfig, SimDevice};
+use crate::phy::{Device, Medium};
+use crate::time::{Duration, Instant};
+#[cfg(feature = "socket-geonet")]
+use crate::types::{Angle, Distance};
+use crate::types::{Latitude, Longitude, Pseudonym};
+use crate::wire::{EthernetAddress, HardwareAddress, StationType};
+#[cfg(feature = "socket-geonet")]
+use crate::{
+    iface::SocketHandle,
+    network::{Request, Transport},
+    socket::geonet::{RxPacketMetadata, Socket as GeonetSocket, TxPacketMetadata},
+    socket::SendError,
+    storage::PacketBuffer,
+};
+
+/// An ITS station attached to a simulated channel.
+struct Station {
+    core: GnCore,
+    iface: Interface,
+    sockets: SocketSet<'static>,
+    device: SimDevice,
+    ll_addr: EthernetAddress,
+}
+
+impl Station {
+    fn new(channel: &Channel, id: u8, latitude: f64) -> Self {
+        let ll_addr = EthernetAddress([0x02, 0x00, 0x00, 0x00, 0x00, id]);
+        let latitude = Latitude::new::<degree>(latitude);
+        let longitude = Longitude::new::<degree>(-3.614);
+
+        let mut device = channel.device(Medium::Ethernet);
+        channel.set_position(
+            device.id(),
+            Some(GeoPosition {
+                latitude,
+                longitude,
+            }),
+        );
+
+        let iface = Interface::new(Config::new(HardwareAddress::Ethernet(ll_addr)), &mut device);
+
+        let mut config = GnCoreGonfig::new(StationType::RoadSideUnit, Pseudonym(id.into()));
+        config.latitude = latitude;
+        config.longitude = longitude;
+        config.position_accurate = true;
+        config.addr_config_mode = GnAddrConfigMode::Managed(ll_addr);
+        let core = GnCore::new(config, channel.now());
+
+        Station {
+            core,
+            iface,
+            sockets: SocketSet::new(vec![]),
+            device,
+            ll_addr,
+        }
+    }
+
+    fn poll(&mut self, timestamp: Instant) {
+        self.core.set_timestamp(timestamp);
+        self.iface
+            .poll(&mut self.core, &mut self.device, &mut self.sockets);
+    }
+
+    fn has_neighbour(&self, other: &Station) -> bool {
+        self.iface
+            .inner
+            .location_table
+            .find(&other.ll_addr)
+            .is_some_and(|e| e.is_neighbour)
+    }
+}
+
+#[cfg(feature = "socket-geonet")]
+impl Station {
+    /// Add a Geonetworking socket to the station.
+    fn bind(&mut self) -> SocketHandle {
+        let rx_buffer = PacketBuffer::new(vec![RxPacketMetadata::EMPTY; 64], vec![0; 4096]);
+        let tx_buffer = PacketBuffer::new(vec![TxPacketMetadata::EMPTY], vec![0; 4096]);
+        self.sockets.add(GeonetSocket::new(rx_buffer, tx_buffer))
+    }
+
+    /// Send a small payload on the `handle` socket, with the `transport` transport.
+    fn send(&mut self, handle: SocketHandle, transport: Transport) -> Result<(), SendError> {
+        let request = Request {
+            transport,
+            ..Default::default()
+        };
+        self.sockets
+            .get_mut::<GeonetSocket>(handle)
+            .send_slice(&[0xca, 0xfe, 0xca, 0xfe], request)
+    }
+
+    /// Count the packets received on the `handle` socket.
+    fn recv_count(&mut self, handle: SocketHandle) -> usize {
+        let socket = self.sockets.get_mut::<GeonetSocket>(handle);
+        let mut count = 0;
+        while socket.recv().is_ok() {
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Create a simulated channel, with its virtual clock set at a fixed date.
+fn channel() -> Channel {
+    Channel::new(SimConfig::default(), Instant::from_secs(1_716_674_400))
+}
+
+fn run(channel: &Channel, stations: &mut [Station], duration: Duration) {
+    run_with(channel, stations, duration, |_| {});
+}
+
+/// Run the `stations` during `duration`, calling `step` before each poll.
+fn run_with<F>(channel: &Channel, stations: &mut [Station], duration: Duration, mut step: F)
+where
+    F: FnMut(&mut [Station]),
+{
+    let end = channel.now() + duration;
+    while channel.now() < end {
+        channel.advance(Duration::from_millis(10));
+        step(stations);
+        for station in stations.iter_mut() {
+            station.poll(channel.now());
+        }
+    }
+}
+
+#[test]
+fn test_beacon_neighbourhood() {
+    let channel = channel();
+
+    // Roughly 667 meters between each station, with a 1000 meters range.
+    let mut stations = [
+        Station::new(&channel, 1, 48.270),
+        Station::new(&channel, 2, 48.276),
+        Station::new(&channel, 3, 48.282),
+    ];
+
+    run(&channel, &mut stations, Duration::from_millis(100));
+
+    let [a, b, c] = &stations;
+    assert!(a.has_neighbour(b));
+    assert!(b.has_neighbour(a));
+    assert!(b.has_neighbour(c));
+    assert!(c.has_neighbour(b));
+    assert!(!a.has_neighbour(c));
+    assert!(!c.has_neighbour(a));
+
+    // Beacons are sensed on the channel.
+    assert!(b.device.channel_busy_ratio().as_ratio() > 0.0);
+}
+
+#[test]
+fn test_lossy_channel() {
+    let channel = channel();
+    channel.set_loss(1.0);
+
+    let mut stations = [
+        Station::new(&channel, 1, 48.270),
+        Station::new(&channel, 2, 48.276),
+    ];
+
+    run(&channel, &mut stations, Duration::from_millis(100));
+
+    let [a, b] = &stations;
+    assert!(!a.has_neighbour(b));
+    assert!(!b.has_neighbour(a));
+    assert_eq!(channel.lost_count(), channel.tx_count());
+}
+
+/// Make a circular area of `radius` meters centered on `latitude`.
+#[cfg(feature = "socket-geonet")]
+fn circle(latitude: f64, radius: f64) -> GeoArea {
+    GeoArea {
+        shape: Shape::Circle(Circle {
+            radius: Distance::new::<meter>(radius),
+        }),
+        position: GeoPosition {
+            latitude: Latitude::new::<degree>(latitude),
+            longitude: Longitude::new::<degree>(-3.614),
+        },
+        angle: Angle::new::<degree>(0.0),
+    }
+}
+
+#[cfg(feature = "socket-geonet")]
+#[test]
+fn test_gbc_multi_hop() {
+    let channel = channel();
+
+    // Only the last station of the chain is inside the destination area.
+    let mut stations = [
+        Station::new(&channel, 1, 48.270),
+        Station::new(&channel, 2, 48.276),
+        Station::new(&channel, 3, 48.282),
+        Station::new(&channel, 4, 48.288),
+    ];
+    let handles = stations.each_mut().map(|s| s.bind());
+
+    run(&channel, &mut stations, Duration::from_millis(100));
+
+    stations[0]
+        .send(handles[0], Transport::Broadcast(circle(48.288, 100.0)))
+        .unwrap();
+    run(&channel, &mut stations, Duration::from_millis(300));
+
+    // Greedy forwarded up to the area, delivered only inside it.
+    let received = [0, 1, 2, 3].map(|i| stations[i].recv_count(handles[i]));
+    assert_eq!(received, [0, 0, 0, 1]);
+}
+
+#[cfg(feature = "socket-geonet")]
+#[test]
+fn test_gbc_contention_based_forwarding() {
+    let channel = channel();
+
+    // Every station is inside the destination area. The farthest one from the source
+    // has the shortest CBF timer.
+    let mut stations = [
+        Station::new(&channel, 1, 48.270),
+        Station::new(&channel, 2, 48.273),
+        Station::new(&channel, 3, 48.276),
+    ];
+    let handles = stations.each_mut().map(|s| s.bind());
+
+    run(&channel, &mut stations, Duration::from_millis(100));
+    let tx_count = channel.tx_count();
+
+    stations[0]
+        .send(handles[0], Transport::Broadcast(circle(48.270, 2000.0)))
+        .unwrap();
+    run(&channel, &mut stations, Duration::from_millis(300));
+
+    // Delivered once, even though the middle station receives the re-broadcast.
+    let received = [0, 1, 2].map(|i| stations[i].recv_count(handles[i]));
+    assert_eq!(received, [0, 1, 1]);
+
+    // Source transmission and re-broadcast of the farthest station. The middle
+    // station timer is cancelled on the duplicate reception.
+    assert_eq!(channel.tx_count() - tx_count, 2);
+}
+
+#[cfg(feature = "socket-geonet")]
+#[test]
+fn test_location_service_unicast() {
+    let channel = channel();
+
+    let mut stations = [
+        Station::new(&channel, 1, 48.270),
+        Station::new(&channel, 2, 48.276),
+        Station::new(&channel, 3, 48.282),
+        Station::new(&channel, 4, 48.288),
+    ];
+    let handles = stations.each_mut().map(|s| s.bind());
+    let destination = stations[3].core.address();
+
+    run(&channel, &mut stations, Duration::from_millis(100));
+
+    // Destination is unknown, the packet is buffered until the Location Service
+    // resolves it.
+    stations[0]
+        .send(handles[0], Transport::Unicast(destination))
+        .unwrap();
+    run(&channel, &mut stations, Duration::from_millis(500));
+    assert!(stations[0]
+        .iface
+        .inner
+        .location_table
+        .find(&stations[3].ll_addr)
+        .is_some_and(|e| e.ls_pending.is_none()));
+    assert_eq!(stations[3].recv_count(handles[3]), 1);
+
+    // Destination is known, the packet is greedy forwarded right away.
+    stations[0]
+        .send(handles[0], Transport::Unicast(destination))
+        .unwrap();
+    run(&channel, &mut stations, Duration::from_millis(100));
+
+    let received = [0, 1, 2, 3].map(|i| stations[i].recv_count(handles[i]));
+    assert_eq!(received, [0, 0, 0, 1]);
+}
+
+#[cfg(feature = "socket-geonet")]
+#[test]
+fn test_congestion_control() {
+    use crate::iface::congestion::{reactive::State, AnyController};
+    use crate::iface::CongestionControl;
+    use crate::phy::TxToken;
+
+    // Send a SHB every 10ms during 2 secs and count the ones received by the neighbour.
+    let shb_rx_count = |jammed: bool| {
+        let channel = channel();
+        let mut jammer = channel.device(Medium::Ethernet);
+
+        let mut stations = [
+            Station::new(&channel, 1, 48.270),
+            Station::new(&channel, 2, 48.276),
+        ];
+        let handles = stations.each_mut().map(|s| s.bind());
+        for station in stations.iter_mut() {
+            station
+                .iface
+                .set_congestion_control(CongestionControl::Reactive);
+        }
+
+        // Each jammer frame occupies the channel during 10ms.
+        let mut jam = |_: &mut [Station]| {
+            if jammed {
+                let now = channel.now();
+                jammer.transmit(now).unwrap().consume(7500, |_| ());
+            }
+        };
+
+        run_with(
+            &channel,
+            &mut stations,
+            Duration::from_millis(500),
+            &mut jam,
+        );
+
+        let mut received = 0;
+        run_with(
+            &channel,
+            &mut stations,
+            Duration::from_secs(2),
+            |stations| {
+                jam(stations);
+                stations[0]
+                    .send(handles[0], Transport::SingleHopBroadcast)
+                    .ok();
+                received += stations[1].recv_count(handles[1]);
+            },
+        );
+
+        let state = match &stations[0].iface.congestion_control.controller {
+            AnyController::Reactive(r) => r.state(),
+            _ => unreachable!(),
+        };
+
+        (received, state)
+    };
+
+    // Gate keeper only lets one packet go every 60ms.
+    let (relaxed, state) = shb_rx_count(false);
+    assert_eq!(state, State::Relaxed);
+    assert!(relaxed >= 25, "relaxed: {relaxed} SHBs");
+
+    // Channel is saturated, one packet goes every second.
+    let (jammed, state) = shb_rx_count(true);
+    assert_eq!(state, State::Restrictive);
+    assert!(jammed <= 4, "jammed: {jammed} SHBs");
+}
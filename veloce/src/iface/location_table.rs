@@ -18,6 +18,7 @@ pub use uom::si::f64::InformationRate;
 pub use uom::si::information_rate::{byte_per_second, kilobit_per_second};
 
 use super::location_service::LocationServiceRequestHandle;
+use super::stats::LocationTableStats;
 
 /// An entry of the duplicate packet list.
 ///
@@ -113,6 +114,9 @@ pub(super) struct LocationTableEntry {
     pub ls_pending: Option<LocationServiceRequestHandle>,
     /// Flag indicating if the station is a neighbour.
     pub is_neighbour: bool,
+    /// Flag indicating the entry has been updated with a secured packet whose signature
+    /// verification is deferred. Cleared once a packet of the station has been verified.
+    pub tentative: bool,
     /// Duplicate packet list received from the station.
     pub dup_packet_list: DuplicatePacketList<GN_DPL_LENGTH>,
     /// Packet data rate as Exponential Moving Average.
@@ -203,6 +207,8 @@ impl LocationTableEntry {
 #[derive(Debug)]
 pub(super) struct LocationTable {
     storage: FnvIndexMap<MacAddress, LocationTableEntry, GN_LOC_TABLE_ENTRY_COUNT>,
+    /// Insertion and removal counters.
+    stats: LocationTableStats,
 }
 
 impl LocationTable {
@@ -210,13 +216,43 @@ impl LocationTable {
     pub const fn new() -> Self {
         Self {
             storage: FnvIndexMap::new(),
+            stats: LocationTableStats {
+                inserted: 0,
+                removed: 0,
+                entries: 0,
+            },
+        }
+    }
+
+    /// Returns the Location Table counters.
+    pub fn stats(&self) -> LocationTableStats {
+        LocationTableStats {
+            entries: self.storage.len(),
+            ..self.stats
         }
     }
 
     /// Remove the LocationTable entry for the given `ll_addr` [`MacAddress`].
     /// Return `None` if `ll_addr` is not in the LocationTable.
     pub fn remove(&mut self, ll_addr: &MacAddress) -> Option<LocationTableEntry> {
-        self.storage.remove(ll_addr)
+        self.storage
+            .remove(ll_addr)
+            .inspect(|_| self.stats.removed += 1)
+    }
+
+    /// Restores the LocationTable `entry` for the given `ll_addr` [`MacAddress`], replacing
+    /// the current one if any.
+    pub fn restore(&mut self, ll_addr: MacAddress, entry: LocationTableEntry) {
+        self.storage.insert(ll_addr, entry).ok();
+    }
+
+    /// Query whether inserting an entry for the given `ll_addr` [`MacAddress`] evicts a
+    /// verified entry, ie: the station is unknown and the table is full without any
+    /// tentative entry.
+    pub fn evicts_verified(&self, ll_addr: &MacAddress) -> bool {
+        !self.storage.contains_key(ll_addr)
+            && self.storage.len() == self.storage.capacity()
+            && self.storage.values().all(|e| !e.tentative)
     }
 
     /// Finds the LocationTable entry for the given `ll_addr` [`MacAddress`].
@@ -261,6 +297,7 @@ impl LocationTable {
                 position_vector: *position_vector,
                 ls_pending: None,
                 is_neighbour: false,
+                tentative: false,
                 dup_packet_list: DuplicatePacketList::new(),
                 packet_data_rate: InformationRate::new::<kilobit_per_second>(0.0),
                 packet_data_rate_updated_at: timestamp,
@@ -270,23 +307,25 @@ impl LocationTable {
 
             /* Check if storage is full */
             if self.storage.len() == self.storage.capacity() {
-                /* Storage is full: we remove the oldest entry. */
+                /* Storage is full: we remove the oldest entry, tentative entries first. */
                 let old_addr = match self
                     .storage
                     .iter()
-                    .min_by_key(|(_, neighbour)| neighbour.expires_at)
+                    .min_by_key(|(_, neighbour)| (!neighbour.tentative, neighbour.expires_at))
                 {
                     Some((a, _)) => *a,
                     None => unreachable!(),
                 };
 
                 self.storage.remove(&old_addr);
+                self.stats.removed += 1;
             }
 
             /* Insert the entry in the storage */
             self.storage
                 .insert(position_vector.address.mac_addr(), new_entry)
                 .ok();
+            self.stats.inserted += 1;
         };
 
         self.storage
@@ -333,6 +372,7 @@ impl LocationTable {
                 position_vector: *position_vector,
                 ls_pending: None,
                 is_neighbour: false,
+                tentative: false,
                 dup_packet_list: DuplicatePacketList::new(),
                 packet_data_rate: InformationRate::new::<kilobit_per_second>(0.0),
                 packet_data_rate_updated_at: timestamp,
@@ -342,23 +382,25 @@ impl LocationTable {
 
             /* Check if storage is full */
             if self.storage.len() == self.storage.capacity() {
-                /* Storage is full: we remove the oldest entry. */
+                /* Storage is full: we remove the oldest entry, tentative entries first. */
                 let old_addr = match self
                     .storage
                     .iter()
-                    .min_by_key(|(_, neighbour)| neighbour.expires_at)
+                    .min_by_key(|(_, neighbour)| (!neighbour.tentative, neighbour.expires_at))
                 {
                     Some((a, _)) => *a,
                     None => unreachable!(),
                 };
 
                 self.storage.remove(&old_addr);
+                self.stats.removed += 1;
             }
 
             /* Insert the entry in the storage */
             self.storage
                 .insert(position_vector.address.mac_addr(), new_entry)
                 .ok();
+            self.stats.inserted += 1;
         }
 
         self.storage
@@ -406,8 +448,8 @@ impl LocationTable {
     }
 
     /// Removes all the entries of the Location Table.
-    #[cfg(feature = "conformance")]
     pub fn clear(&mut self) {
+        self.stats.removed += self.storage.len() as u64;
         self.storage.clear();
     }
 
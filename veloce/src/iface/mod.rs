@@ -14,6 +14,8 @@ mod location_service;
 mod location_table;
 #[cfg(feature = "socket")]
 mod socket_set;
+#[cfg(feature = "proto-geonet")]
+mod stats;
 
 pub(crate) mod packet;
 
@@ -30,3 +32,7 @@ pub(crate) use self::interface::DecapContext;
 pub(crate) use self::interface::InterfaceContext as ContextMeta;
 #[cfg(feature = "socket")]
 pub use self::socket_set::{SocketHandle, SocketSet, SocketStorage};
+#[cfg(feature = "proto-geonet")]
+pub use self::stats::{
+    DccStats, DropCounters, DropReason, InterfaceStats, LocationTableStats, PacketCounters,
+};
@@ -0,0 +1,258 @@
+use core::fmt;
+
+use crate::wire::{GeonetPacketType, GeonetVariant};
+
+/// Geonetworking packet counters, per header type.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PacketCounters {
+    /// Beacon packets.
+    pub beacon: u64,
+    /// Geo Unicast packets.
+    pub unicast: u64,
+    /// Geo Anycast packets.
+    pub geo_anycast: u64,
+    /// Geo Broadcast packets.
+    pub geo_broadcast: u64,
+    /// Single-Hop Broadcast packets.
+    pub single_hop_broadcast: u64,
+    /// Topologically Scoped Broadcast packets.
+    pub topo_broadcast: u64,
+    /// Location Service request packets.
+    pub ls_request: u64,
+    /// Location Service reply packets.
+    pub ls_reply: u64,
+    /// Packets of 'Any' or unknown header type.
+    pub unknown: u64,
+}
+
+impl PacketCounters {
+    /// Accounts a packet of `header_type`.
+    pub(crate) fn count(&mut self, header_type: GeonetPacketType) {
+        let counter = match header_type {
+            GeonetPacketType::Beacon => &mut self.beacon,
+            GeonetPacketType::GeoUnicast => &mut self.unicast,
+            GeonetPacketType::GeoAnycastCircle
+            | GeonetPacketType::GeoAnycastRect
+            | GeonetPacketType::GeoAnycastElip => &mut self.geo_anycast,
+            GeonetPacketType::GeoBroadcastCircle
+            | GeonetPacketType::GeoBroadcastRect
+            | GeonetPacketType::GeoBroadcastElip => &mut self.geo_broadcast,
+            GeonetPacketType::TsbSingleHop => &mut self.single_hop_broadcast,
+            GeonetPacketType::TsbMultiHop => &mut self.topo_broadcast,
+            GeonetPacketType::LsRequest => &mut self.ls_request,
+            GeonetPacketType::LsReply => &mut self.ls_reply,
+            GeonetPacketType::Any | GeonetPacketType::Unknown(_) => &mut self.unknown,
+        };
+
+        *counter += 1;
+    }
+
+    /// Accounts a packet of the `variant` type.
+    pub(crate) fn count_variant(&mut self, variant: &GeonetVariant) {
+        let counter = match variant {
+            GeonetVariant::Beacon(_) => &mut self.beacon,
+            GeonetVariant::Unicast(_) => &mut self.unicast,
+            GeonetVariant::Anycast(_) => &mut self.geo_anycast,
+            GeonetVariant::Broadcast(_) => &mut self.geo_broadcast,
+            GeonetVariant::SingleHopBroadcast(_) => &mut self.single_hop_broadcast,
+            GeonetVariant::TopoBroadcast(_) => &mut self.topo_broadcast,
+            GeonetVariant::LocationServiceRequest(_) => &mut self.ls_request,
+            GeonetVariant::LocationServiceReply(_) => &mut self.ls_reply,
+        };
+
+        *counter += 1;
+    }
+
+    /// Returns an iterator over the counters, along with the name of their header type.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, u64)> {
+        [
+            ("beacon", self.beacon),
+            ("unicast", self.unicast),
+            ("geo_anycast", self.geo_anycast),
+            ("geo_broadcast", self.geo_broadcast),
+            ("single_hop_broadcast", self.single_hop_broadcast),
+            ("topo_broadcast", self.topo_broadcast),
+            ("ls_request", self.ls_request),
+            ("ls_reply", self.ls_reply),
+            ("unknown", self.unknown),
+        ]
+        .into_iter()
+    }
+
+    /// Returns the sum of all the counters.
+    pub fn total(&self) -> u64 {
+        self.iter().map(|(_, c)| c).sum()
+    }
+}
+
+/// Reason for discarding a received Geonetworking packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DropReason {
+    /// Packet headers are malformed.
+    Malformed,
+    /// Geonetworking protocol version is not supported.
+    Version,
+    /// Header type is 'Any', unknown or not supported.
+    UnsupportedHeader,
+    /// Packet is not secured while security is enabled.
+    Unsecured,
+    /// Packet is rejected by the security service.
+    Security,
+    /// Hop limit is invalid, or reached on a packet to forward.
+    HopLimit,
+    /// Packet has been sent by the local station.
+    Loopback,
+    /// Forwarding packet buffer is full.
+    BufferFull,
+}
+
+impl DropReason {
+    /// All the drop reasons.
+    pub const ALL: [DropReason; 8] = [
+        DropReason::Malformed,
+        DropReason::Version,
+        DropReason::UnsupportedHeader,
+        DropReason::Unsecured,
+        DropReason::Security,
+        DropReason::HopLimit,
+        DropReason::Loopback,
+        DropReason::BufferFull,
+    ];
+
+    /// Returns the drop reason as a snake case string.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::Malformed => "malformed",
+            DropReason::Version => "version",
+            DropReason::UnsupportedHeader => "unsupported_header",
+            DropReason::Unsecured => "unsecured",
+            DropReason::Security => "security",
+            DropReason::HopLimit => "hop_limit",
+            DropReason::Loopback => "loopback",
+            DropReason::BufferFull => "buffer_full",
+        }
+    }
+}
+
+impl fmt::Display for DropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Discarded packet counters, per [DropReason].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DropCounters([u64; DropReason::ALL.len()]);
+
+impl DropCounters {
+    /// Accounts a packet discarded for `reason`.
+    pub(crate) fn count(&mut self, reason: DropReason) {
+        self.0[reason as usize] += 1;
+    }
+
+    /// Returns the number of packets discarded for `reason`.
+    pub fn get(&self, reason: DropReason) -> u64 {
+        self.0[reason as usize]
+    }
+
+    /// Returns an iterator over the counters, along with their drop reason.
+    pub fn iter(&self) -> impl Iterator<Item = (DropReason, u64)> + '_ {
+        DropReason::ALL.into_iter().map(|r| (r, self.get(r)))
+    }
+
+    /// Returns the number of discarded packets, for any reason.
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+/// Decentralized Congestion Control counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DccStats {
+    /// Packets which had to wait in a DCC queue before transmission.
+    pub enqueued: u64,
+    /// Packets dropped by the DCC, because their queue is full
+    /// or they are CBF duplicates.
+    pub dropped: u64,
+}
+
+/// Location Table counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LocationTableStats {
+    /// Entries inserted in the Location Table.
+    pub inserted: u64,
+    /// Entries removed from the Location Table, ie: evicted when full, after
+    /// a Location Service failure or a pseudonym change.
+    pub removed: u64,
+    /// Current number of entries in the Location Table.
+    pub entries: usize,
+}
+
+/// Interface statistics. See [Interface::stats](super::Interface::stats).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InterfaceStats {
+    /// Received packets, per header type.
+    pub rx: PacketCounters,
+    /// Transmitted packets, per header type. Includes the forwarded packets.
+    pub tx: PacketCounters,
+    /// Transmitted packets originated by another station.
+    pub forwarded: u64,
+    /// Received duplicate packets.
+    pub duplicates: u64,
+    /// Discarded received packets, per drop reason.
+    pub dropped: DropCounters,
+    /// Decentralized Congestion Control counters.
+    pub dcc: DccStats,
+    /// Location Table counters.
+    pub location_table: LocationTableStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_counters() {
+        let mut counters = PacketCounters::default();
+        counters.count(GeonetPacketType::GeoBroadcastCircle);
+        counters.count(GeonetPacketType::GeoBroadcastElip);
+        counters.count(GeonetPacketType::TsbSingleHop);
+        counters.count(GeonetPacketType::Unknown(0xf));
+
+        assert_eq!(counters.geo_broadcast, 2);
+        assert_eq!(counters.single_hop_broadcast, 1);
+        assert_eq!(counters.unknown, 1);
+        assert_eq!(counters.total(), 4);
+        assert_eq!(
+            counters.iter().find(|(name, _)| *name == "geo_broadcast"),
+            Some(("geo_broadcast", 2))
+        );
+    }
+
+    #[test]
+    fn test_drop_counters() {
+        let mut counters = DropCounters::default();
+        counters.count(DropReason::Malformed);
+        counters.count(DropReason::Malformed);
+        counters.count(DropReason::BufferFull);
+
+        assert_eq!(counters.get(DropReason::Malformed), 2);
+        assert_eq!(counters.get(DropReason::BufferFull), 1);
+        assert_eq!(counters.get(DropReason::Security), 0);
+        assert_eq!(counters.total(), 3);
+
+        // Each reason has its own counter, in declaration order.
+        let reasons: Vec<_> = counters.iter().map(|(r, _)| r).collect();
+        assert_eq!(reasons, DropReason::ALL);
+        assert_eq!(
+            counters.iter().map(|(_, c)| c).collect::<Vec<_>>(),
+            [2, 0, 0, 0, 0, 0, 0, 1]
+        );
+    }
+}
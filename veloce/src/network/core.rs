@@ -9,7 +9,7 @@ use crate::common::{Poti, PotiError, PotiFix, PotiPositionHistory};
 use crate::config;
 use crate::iface::Interface;
 use crate::rand::Rand;
-use crate::time::{Duration, Instant, TAI2004};
+use crate::time::{Clock, ClockConfig, Duration, Instant, TAI2004};
 use crate::types::{degree, kilometer_per_hour, Heading, Latitude, Longitude, Pseudonym, Speed};
 use crate::wire::{
     EthernetAddress, GnAddress, LongPositionVectorRepr as LongPositionVector, StationType,
@@ -17,8 +17,8 @@ use crate::wire::{
 
 #[cfg(feature = "proto-security")]
 use crate::security::{
-    privacy::PrivacyStrategy, SecurityBackend, SecurityService, SecurityServicePollEvent,
-    TrustChain,
+    privacy::PrivacyStrategy, signer_policy::SignerPolicy, HashedId8, SecurityBackend,
+    SecurityService, SecurityServicePollEvent, TrustChain,
 };
 
 /// Core module poll event.
@@ -41,7 +41,8 @@ pub enum AddrConfigMode {
     Auto,
     /// Geonetworking address is manually set by the user.
     Managed(EthernetAddress),
-    /// Geonetworking address is derived from security certificate.
+    /// Geonetworking address is derived from security certificate, or from the pseudonym
+    /// when security is disabled. Address changes with the pseudonym.
     Anonymous,
 }
 
@@ -53,8 +54,20 @@ pub struct SecurityConfig {
     pub security_backend: SecurityBackend,
     /// Own trust chain of the local ITS station.
     pub own_trust_chain: TrustChain,
+    /// Root certificates listed in the trusted ECTL, identified with their [HashedId8].
+    /// Root certificates learned over the air are only trusted if listed here.
+    pub trusted_roots: Vec<HashedId8>,
     /// Privacy strategy.
     pub privacy_strategy: PrivacyStrategy,
+    /// Signer identifier policy.
+    pub signer_policy: SignerPolicy,
+    /// Defer the pseudonym changes triggered by the privacy strategy while a DENM event
+    /// originated by the local station is active, so the event is not disseminated under
+    /// two different identities.
+    pub defer_pseudonym_change: bool,
+    /// Time accuracy required to sign messages, as required by ETSI TS 103 097.
+    /// Set to None to sign messages regardless of the time accuracy.
+    pub required_time_accuracy: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -79,6 +92,8 @@ pub struct Config {
     pub station_type: StationType,
     /// Geonetworking address config mode.
     pub addr_config_mode: AddrConfigMode,
+    /// Clock service configuration.
+    pub clock: ClockConfig,
     #[cfg(feature = "proto-security")]
     /// Security backend. Set to None to disable security.
     pub security: Option<SecurityConfig>,
@@ -97,6 +112,7 @@ impl Config {
             pseudonym,
             station_type,
             addr_config_mode: AddrConfigMode::Auto,
+            clock: ClockConfig::default(),
             #[cfg(feature = "proto-security")]
             security: None,
         }
@@ -112,24 +128,42 @@ pub struct Core {
     pub(crate) rand: Rand,
     /// Address automatic configuration flag.
     pub(crate) addr_auto_mode: bool,
+    /// Geonetworking address is derived from the pseudonym.
+    pub(crate) addr_anonymous: bool,
     /// Ego Position Vector which includes the local Geonetworking address.
     pub(crate) ego_position_vector: LongPositionVector,
     /// Pseudonym aka. StationId of the Geonetworking router.
     pub(crate) pseudonym: Pseudonym,
     /// Poti for position and timing.
     pub(crate) poti: Poti,
+    /// Clock service.
+    pub(crate) clock: Clock,
     #[cfg(feature = "proto-security")]
     /// Security service.
     pub(crate) security: Option<SecurityService>,
+    #[cfg(feature = "proto-security")]
+    /// Defer pseudonym changes while a locally originated DENM event is active.
+    pub(crate) defer_pseudonym_change: bool,
 }
 
 impl Core {
     pub fn new(config: Config, now: Instant) -> Self {
         let mut rand = Rand::new(config.random_seed);
 
+        #[cfg(feature = "proto-security")]
+        let defer_pseudonym_change = config
+            .security
+            .as_ref()
+            .is_some_and(|s| s.defer_pseudonym_change);
+
         #[cfg(feature = "proto-security")]
         let security = config.security.map(|s| {
-            SecurityService::new(s.own_trust_chain, s.security_backend, s.privacy_strategy)
+            let mut sec =
+                SecurityService::new(s.own_trust_chain, s.security_backend, s.privacy_strategy);
+            sec.set_signer_policy(s.signer_policy);
+            sec.set_required_time_accuracy(s.required_time_accuracy);
+            sec.store_mut().set_trusted_roots(s.trusted_roots);
+            sec
         });
 
         #[cfg(feature = "proto-security")]
@@ -149,10 +183,15 @@ impl Core {
                 (GnAddress::new(true, config.station_type, mac_addr), false)
             }
             (AddrConfigMode::Anonymous, None) => {
-                panic!("Anonymous address mode is not supported when security is disabled")
+                let mac_addr = anonymous_mac_addr(config.pseudonym, &mut rand);
+                (GnAddress::new(false, config.station_type, mac_addr), false)
             }
         };
 
+        #[cfg(feature = "proto-security")]
+        let addr_anonymous =
+            matches!(config.addr_config_mode, AddrConfigMode::Anonymous) && security.is_none();
+
         #[cfg(not(feature = "proto-security"))]
         let (address, addr_auto_mode) = match config.addr_config_mode {
             AddrConfigMode::Auto => {
@@ -162,9 +201,15 @@ impl Core {
             AddrConfigMode::Managed(mac_addr) => {
                 (GnAddress::new(true, config.station_type, mac_addr), false)
             }
-            AddrConfigMode::Anonymous => unimplemented!(),
+            AddrConfigMode::Anonymous => {
+                let mac_addr = anonymous_mac_addr(config.pseudonym, &mut rand);
+                (GnAddress::new(false, config.station_type, mac_addr), false)
+            }
         };
 
+        #[cfg(not(feature = "proto-security"))]
+        let addr_anonymous = matches!(config.addr_config_mode, AddrConfigMode::Anonymous);
+
         #[cfg(feature = "proto-security")]
         let pseudonym = security
             .as_ref()
@@ -188,11 +233,15 @@ impl Core {
             now,
             rand,
             addr_auto_mode,
+            addr_anonymous,
             ego_position_vector,
             pseudonym,
             poti: Poti::new(),
+            clock: Clock::new(config.clock),
             #[cfg(feature = "proto-security")]
             security,
+            #[cfg(feature = "proto-security")]
+            defer_pseudonym_change,
         }
     }
 
@@ -266,6 +315,44 @@ impl Core {
         self.pseudonym = pseudo
     }
 
+    /// Returns a reference to the security service, if security is enabled.
+    #[cfg(feature = "proto-security")]
+    pub fn security(&self) -> Option<&SecurityService> {
+        self.security.as_ref()
+    }
+
+    /// Returns a mutable reference to the security service, if security is enabled.
+    #[cfg(feature = "proto-security")]
+    pub fn security_mut(&mut self) -> Option<&mut SecurityService> {
+        self.security.as_mut()
+    }
+
+    /// Returns whether outgoing packets are signed, ie: security is enabled
+    /// and signing has not been disabled on the security service.
+    #[cfg(feature = "proto-security")]
+    pub fn signing_enabled(&self) -> bool {
+        self.security.as_ref().is_some_and(|s| s.signing_enabled())
+    }
+
+    /// Returns whether the station is in a silent period required by the privacy strategy,
+    /// ie: it shall not emit any packet carrying its position vector.
+    #[cfg(feature = "proto-security")]
+    pub fn is_silent(&self) -> bool {
+        self.security
+            .as_ref()
+            .is_some_and(|s| s.is_silent(self.now))
+    }
+
+    /// Returns a reference to the clock service.
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Returns a mutable reference to the clock service.
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+
     /// Returns the timestamp of the local ITS Station.
     pub fn timestamp(&self) -> Instant {
         self.now
@@ -276,6 +363,18 @@ impl Core {
         self.now = now;
     }
 
+    /// Returns the timestamp of the local ITS Station corrected by the clock service,
+    /// ie: the estimated GNSS time. Used for the generation times and the certificates
+    /// validity checks.
+    pub fn clock_timestamp(&self) -> Instant {
+        self.clock.now(self.now)
+    }
+
+    /// Returns [Core::clock_timestamp] as a TAI time.
+    pub fn tai2004(&self) -> TAI2004 {
+        self.clock.tai2004(self.now)
+    }
+
     /// Returns the position of the local ITS Station.
     pub fn position(&self) -> PotiFix {
         self.poti.fix().to_owned()
@@ -292,7 +391,7 @@ impl Core {
     /// Set the position of the local ITS Station.
     pub fn set_position(&mut self, fix: PotiFix, timestamp: Instant) -> Result<(), PotiError> {
         self.poti.push_fix(fix).map(|fix| {
-            self.ego_position_vector.timestamp = TAI2004::from_unix_instant(timestamp).into();
+            self.ego_position_vector.timestamp = self.clock.tai2004(timestamp).into();
             self.ego_position_vector.latitude = fix
                 .position
                 .latitude
@@ -318,9 +417,16 @@ impl Core {
                 self.ego_position_vector.is_accurate = false;
             }
 
+            // Only fixes computed by the GNSS receiver carry the GNSS time.
+            if fix.is_mode_3d() {
+                self.clock.notify_gnss_time(fix.timestamp, timestamp);
+            }
+
             // Notify the security service about the new position, if any.
             if let Some(s) = &mut self.security {
                 s.notify_position(fix, timestamp);
+                s.notify_time_accuracy(self.clock.accuracy(timestamp));
+                s.set_tai_utc_offset(self.clock.tai_utc_offset());
             }
         })
     }
@@ -337,39 +443,34 @@ impl Core {
         #[cfg(not(feature = "proto-security"))]
         let res = None;
 
-        res
+        let clock_delay = self.clock.poll_at(timestamp).map(|poll_at| {
+            if timestamp < poll_at {
+                poll_at - timestamp
+            } else {
+                Duration::ZERO
+            }
+        });
+
+        match (res, clock_delay) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
     }
 
     /// Poll the Core module for internal processing.
     pub fn poll(&mut self, iface: &mut Interface, timestamp: Instant) -> PollEvent {
+        self.clock.poll(timestamp);
+
         #[cfg(feature = "proto-security")]
         if let Some(s) = &mut self.security {
+            s.notify_time_accuracy(self.clock.accuracy(timestamp));
+            s.set_tai_utc_offset(self.clock.tai_utc_offset());
             s.poll(timestamp)
-                .map(|sec_evt| {
-                    match sec_evt {
-                        SecurityServicePollEvent::PrivacyATCertificateRotation(_, h)
-                        | SecurityServicePollEvent::ATCertificateExpiration(_, h) => {
-                            // Change the pseudonym to the new certificate.
-                            self.pseudonym = h.into_pseudonym();
-
-                            // Same for the hardware address.
-                            let eth_addr = h.into_ethernet_address();
-                            self.ego_position_vector.address.set_mac_addr(eth_addr);
-                            iface
-                                .inner
-                                .set_hardware_addr(eth_addr.into_hardware_address());
-
-                            // Car 2 Car Vehicle C-ITS station profile, requirement RS_BSP_182.
-                            // Set sequence number to 0.
-                            iface.inner.reset_sequence_number();
-                            // Clear the path history.
-                            self.poti.clear_path_history();
-
-                            // Sync elections statistics.
-                            // s.at_certs_stats()
-
-                            PollEvent::SecurityService(sec_evt)
-                        }
+                .map(|sec_evt| match sec_evt {
+                    SecurityServicePollEvent::PrivacyATCertificateRotation(_, h)
+                    | SecurityServicePollEvent::ATCertificateExpiration(_, h) => {
+                        self.rotate_pseudonym(iface, h);
+                        PollEvent::SecurityService(sec_evt)
                     }
                 })
                 .unwrap_or(PollEvent::None)
@@ -377,6 +478,72 @@ impl Core {
             PollEvent::None
         }
     }
+
+    /// Changes the pseudonym of the local station to `pseudonym`, when security is disabled.
+    /// In [AddrConfigMode::Anonymous] mode, the Geonetworking address is derived from the new
+    /// pseudonym. See [Core::set_pseudonym] to only change the StationId.
+    ///
+    /// When security is enabled, pseudonym changes follow the AT certificate rotations and
+    /// this function has no effect.
+    pub fn change_pseudonym(&mut self, iface: &mut Interface, pseudonym: Pseudonym) {
+        #[cfg(feature = "proto-security")]
+        if self.security.is_some() {
+            net_debug!("pseudonym is derived from the AT certificate - ignoring change");
+            return;
+        }
+
+        net_debug!("changing pseudonym to {}", pseudonym.0);
+
+        let eth_addr = if self.addr_anonymous {
+            anonymous_mac_addr(pseudonym, &mut self.rand)
+        } else {
+            self.address().mac_addr()
+        };
+
+        self.apply_pseudonym_change(iface, pseudonym, eth_addr);
+    }
+
+    /// Changes the pseudonym of the local station to the one derived from the AT certificate
+    /// identified with `hash`.
+    #[cfg(feature = "proto-security")]
+    fn rotate_pseudonym(&mut self, iface: &mut Interface, hash: HashedId8) {
+        net_debug!("changing pseudonym to certificate {}", hash);
+
+        // Geonetworking address is always derived from the certificate when security is enabled.
+        self.apply_pseudonym_change(iface, hash.into_pseudonym(), hash.into_ethernet_address());
+    }
+
+    /// Pseudonym change transaction. Changes at once all the identifiers of the local station,
+    /// and drops any state which could link the previous and the new identities, ie:
+    /// - the pseudonym, aka. StationId of CAMs and DENMs,
+    /// - the Geonetworking address and the interface hardware address,
+    /// - the Geonetworking sequence number, the location table and the forwarding buffers,
+    /// - the path history.
+    ///
+    /// Device address filter and sockets are updated by the interface on its next poll.
+    fn apply_pseudonym_change(
+        &mut self,
+        iface: &mut Interface,
+        pseudonym: Pseudonym,
+        eth_addr: EthernetAddress,
+    ) {
+        self.pseudonym = pseudonym;
+        self.ego_position_vector.address.set_mac_addr(eth_addr);
+
+        iface.change_pseudonym(eth_addr.into_hardware_address());
+
+        // Clear the path history.
+        self.poti.clear_path_history();
+    }
+}
+
+/// Derive an anonymous link layer address from `pseudonym`, for the [AddrConfigMode::Anonymous]
+/// mode when security is disabled. Address is random, except its last four bytes which are the
+/// pseudonym value.
+fn anonymous_mac_addr(pseudonym: Pseudonym, rand: &mut Rand) -> EthernetAddress {
+    let mut addr = rand.rand_mac_addr();
+    addr[2..].copy_from_slice(&pseudonym.0.to_be_bytes());
+    EthernetAddress::from_bytes(&addr)
 }
 
 #[cfg(test)]
@@ -405,9 +572,13 @@ mod test {
             rand: Rand::new(0xfadecafe),
             pseudonym: Pseudonym(123456789),
             poti: Poti::new(),
+            clock: Clock::new(ClockConfig::default()),
             addr_auto_mode: true,
+            addr_anonymous: false,
             #[cfg(feature = "proto-security")]
             security: None,
+            #[cfg(feature = "proto-security")]
+            defer_pseudonym_change: false,
         };
 
         let eth_addr = EthernetAddress::new(0, 1, 2, 3, 4, 5);
@@ -432,9 +603,13 @@ mod test {
             rand: Rand::new(0xcafefade),
             pseudonym: Pseudonym(123456789),
             poti: Poti::new(),
+            clock: Clock::new(ClockConfig::default()),
             addr_auto_mode: true,
+            addr_anonymous: false,
             #[cfg(feature = "proto-security")]
             security: None,
+            #[cfg(feature = "proto-security")]
+            defer_pseudonym_change: false,
         };
 
         core.duplicate_address_detection(
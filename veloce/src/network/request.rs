@@ -16,6 +16,8 @@ pub struct AddressableRequest<Transport> {
     pub ali_id: (),
     #[cfg(feature = "proto-security")]
     pub its_aid: Permission,
+    #[cfg(feature = "proto-security")]
+    pub confidential: bool,
     pub max_lifetime: Duration,
     pub max_hop_limit: u8,
     pub traffic_class: GnTrafficClass,
@@ -49,11 +51,13 @@ pub struct HoppableRequest<Transport> {
 
 impl<Transport: Addressable> AddressableRequest<Transport> {
     #[cfg(feature = "proto-security")]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         upper_proto: UpperProtocol,
         destination: GnAddress,
         ali_id: (),
         its_aid: Permission,
+        confidential: bool,
         max_lifetime: Duration,
         max_hop_limit: u8,
         traffic_class: GnTrafficClass,
@@ -63,6 +67,7 @@ impl<Transport: Addressable> AddressableRequest<Transport> {
             destination,
             ali_id,
             its_aid,
+            confidential,
             max_lifetime,
             max_hop_limit,
             traffic_class,
@@ -0,0 +1,665 @@
+#[cfg(not(feature = "std"))]
+use alloc::collections::vec_deque::VecDeque;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use alloc::vec::Vec;
+
+#[cfg(any(feature = "medium-pc5", feature = "medium-ieee80211p"))]
+use crate::wire::HardwareAddress;
+
+use crate::phy::{self, ChannelBusyRatio, Device, DeviceCapabilities, PacketMeta};
+use crate::rand::Rand;
+use crate::time::{Duration, Instant};
+
+// We use our own RNG to stay compatible with #![no_std].
+// The use of the RNG below has a slight bias, but it doesn't matter.
+fn check_rng(rng: &mut Rand, pct: u8) -> bool {
+    // make sure our rng generates a value in [0, 100)
+    let value = rng.rand_u32() % 100;
+    value < pct as u32
+}
+
+// This could be fixed once associated consts are stable.
+const MTU: usize = 1536;
+
+/// Maximum number of received frames held by the injector.
+const RX_QUEUE_LEN: usize = 64;
+
+#[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Config {
+    corrupt_pct: u8,
+    drop_pct: u8,
+    duplicate_pct: u8,
+    reorder_pct: u8,
+    delay: Duration,
+    max_size: usize,
+    max_tx_rate: u64,
+    max_rx_rate: u64,
+    interval: Duration,
+    channel_busy_ratio: Option<ChannelBusyRatio>,
+}
+
+#[derive(Debug)]
+struct State {
+    rng: Rand,
+    refilled_at: Instant,
+    tx_bucket: u64,
+    rx_bucket: u64,
+}
+
+impl State {
+    fn maybe(&mut self, pct: u8) -> bool {
+        check_rng(&mut self.rng, pct)
+    }
+
+    fn corrupt<T: AsMut<[u8]>>(&mut self, mut buffer: T) {
+        let buffer = buffer.as_mut();
+        if buffer.is_empty() {
+            return;
+        }
+        // We introduce a single bitflip, as the most likely, and the hardest to detect, error.
+        let index = self.rng.rand_u32() as usize % buffer.len();
+        let bit = 1 << (self.rng.rand_u32() % 8) as u8;
+        buffer[index] ^= bit;
+    }
+
+    fn refill(&mut self, config: &Config, timestamp: Instant) {
+        if timestamp - self.refilled_at > config.interval {
+            self.tx_bucket = config.max_tx_rate;
+            self.rx_bucket = config.max_rx_rate;
+            self.refilled_at = timestamp;
+        }
+    }
+
+    fn maybe_transmit(&mut self, config: &Config, timestamp: Instant) -> bool {
+        if config.max_tx_rate == 0 {
+            return true;
+        }
+
+        self.refill(config, timestamp);
+        if self.tx_bucket > 0 {
+            self.tx_bucket -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn maybe_receive(&mut self, config: &Config, timestamp: Instant) -> bool {
+        if config.max_rx_rate == 0 {
+            return true;
+        }
+
+        self.refill(config, timestamp);
+        if self.rx_bucket > 0 {
+            self.rx_bucket -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A frame held by the injector before being handed to the stack.
+#[derive(Debug)]
+struct Frame {
+    /// Time at which the frame can be received.
+    deliver_at: Instant,
+    /// Frame metadata.
+    meta: PacketMeta,
+    /// Frame content.
+    buffer: Vec<u8>,
+}
+
+/// A fault injector device.
+///
+/// A fault injector is a device that alters packets traversing through it to simulate
+/// adverse network conditions (such as random packet loss or corruption), or software
+/// or hardware limitations (such as a limited number or size of usable network buffers).
+///
+/// Dropping, corruption and rate limiting apply in both directions. Duplication,
+/// reordering and delay only apply to received frames, which are held by the injector
+/// until they are due. Frames can only be reordered with frames still held, ie. when a
+/// delay is configured or when frames are received in bursts.
+///
+/// Every decision is made with a [Rand] generator seeded at creation, so a given seed
+/// and sequence of frames always produce the same faults.
+#[derive(Debug)]
+pub struct FaultInjector<D: Device> {
+    inner: D,
+    state: State,
+    config: Config,
+    rx_queue: VecDeque<Frame>,
+}
+
+impl<D: Device> FaultInjector<D> {
+    /// Create a fault injector device, using the given random number generator seed.
+    pub fn new(inner: D, seed: u64) -> FaultInjector<D> {
+        FaultInjector {
+            inner,
+            state: State {
+                rng: Rand::new(seed),
+                refilled_at: Instant::from_millis(0),
+                tx_bucket: 0,
+                rx_bucket: 0,
+            },
+            config: Config::default(),
+            rx_queue: VecDeque::new(),
+        }
+    }
+
+    /// Get a reference to the underlying device.
+    ///
+    /// It is inadvisable to directly read from the device as doing so will circumvent the
+    /// fault injection.
+    pub fn get_ref(&self) -> &D {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying device.
+    ///
+    /// It is inadvisable to directly read from the device as doing so will circumvent the
+    /// fault injection.
+    pub fn get_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    /// Return the underlying device, consuming the fault injector.
+    /// Frames held by the injector are lost.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    /// Return the probability of corrupting a packet, in percents.
+    pub fn corrupt_chance(&self) -> u8 {
+        self.config.corrupt_pct
+    }
+
+    /// Return the probability of dropping a packet, in percents.
+    pub fn drop_chance(&self) -> u8 {
+        self.config.drop_pct
+    }
+
+    /// Return the probability of duplicating a received packet, in percents.
+    pub fn duplicate_chance(&self) -> u8 {
+        self.config.duplicate_pct
+    }
+
+    /// Return the probability of reordering a received packet, in percents.
+    pub fn reorder_chance(&self) -> u8 {
+        self.config.reorder_pct
+    }
+
+    /// Return the delay applied to received packets.
+    pub fn delay(&self) -> Duration {
+        self.config.delay
+    }
+
+    /// Return the maximum packet size, in octets.
+    pub fn max_packet_size(&self) -> usize {
+        self.config.max_size
+    }
+
+    /// Return the maximum packet transmission rate, in packets per interval.
+    pub fn max_tx_rate(&self) -> u64 {
+        self.config.max_tx_rate
+    }
+
+    /// Return the maximum packet reception rate, in packets per interval.
+    pub fn max_rx_rate(&self) -> u64 {
+        self.config.max_rx_rate
+    }
+
+    /// Return the interval for packet rate limiting, in milliseconds.
+    pub fn bucket_interval(&self) -> Duration {
+        self.config.interval
+    }
+
+    /// Return the faked channel busy ratio, if any.
+    pub fn fake_channel_busy_ratio(&self) -> Option<ChannelBusyRatio> {
+        self.config.channel_busy_ratio
+    }
+
+    /// Set the probability of corrupting a packet, in percents.
+    ///
+    /// # Panics
+    /// This function panics if the probability is not between 0% and 100%.
+    pub fn set_corrupt_chance(&mut self, pct: u8) {
+        if pct > 100 {
+            panic!("percentage out of range")
+        }
+        self.config.corrupt_pct = pct
+    }
+
+    /// Set the probability of dropping a packet, in percents.
+    ///
+    /// # Panics
+    /// This function panics if the probability is not between 0% and 100%.
+    pub fn set_drop_chance(&mut self, pct: u8) {
+        if pct > 100 {
+            panic!("percentage out of range")
+        }
+        self.config.drop_pct = pct
+    }
+
+    /// Set the probability of duplicating a received packet, in percents.
+    ///
+    /// # Panics
+    /// This function panics if the probability is not between 0% and 100%.
+    pub fn set_duplicate_chance(&mut self, pct: u8) {
+        if pct > 100 {
+            panic!("percentage out of range")
+        }
+        self.config.duplicate_pct = pct
+    }
+
+    /// Set the probability of reordering a received packet, in percents.
+    ///
+    /// # Panics
+    /// This function panics if the probability is not between 0% and 100%.
+    pub fn set_reorder_chance(&mut self, pct: u8) {
+        if pct > 100 {
+            panic!("percentage out of range")
+        }
+        self.config.reorder_pct = pct
+    }
+
+    /// Set the delay applied to received packets.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.config.delay = delay
+    }
+
+    /// Set the maximum packet size, in octets.
+    pub fn set_max_packet_size(&mut self, size: usize) {
+        self.config.max_size = size
+    }
+
+    /// Set the maximum packet transmission rate, in packets per interval.
+    pub fn set_max_tx_rate(&mut self, rate: u64) {
+        self.config.max_tx_rate = rate
+    }
+
+    /// Set the maximum packet reception rate, in packets per interval.
+    pub fn set_max_rx_rate(&mut self, rate: u64) {
+        self.config.max_rx_rate = rate
+    }
+
+    /// Set the interval for packet rate limiting, in milliseconds.
+    pub fn set_bucket_interval(&mut self, interval: Duration) {
+        self.state.refilled_at = Instant::from_millis(0);
+        self.config.interval = interval
+    }
+
+    /// Set a fake channel busy ratio, returned instead of the one measured by
+    /// the underlying device. Set to `None` to use the measured value.
+    pub fn set_fake_channel_busy_ratio(&mut self, cbr: Option<ChannelBusyRatio>) {
+        self.config.channel_busy_ratio = cbr
+    }
+
+    /// Pull the frames available in the underlying device and apply the
+    /// reception faults on them.
+    fn fill_rx_queue(&mut self, timestamp: Instant) {
+        while self.rx_queue.len() < RX_QUEUE_LEN {
+            let Some((rx_token, _)) = self.inner.receive(timestamp) else {
+                break;
+            };
+
+            let meta = phy::RxToken::meta(&rx_token);
+            let mut buffer = phy::RxToken::consume(rx_token, |buffer| buffer.to_vec());
+
+            if self.state.maybe(self.config.drop_pct) {
+                net_trace!("rx: randomly dropping a packet");
+                continue;
+            }
+
+            if !self.state.maybe_receive(&self.config, timestamp) {
+                net_trace!("rx: dropping a packet because of rate limiting");
+                continue;
+            }
+
+            if self.config.max_size > 0 && buffer.len() > self.config.max_size {
+                net_trace!("rx: dropping a packet that is too large");
+                continue;
+            }
+
+            if self.state.maybe(self.config.corrupt_pct) {
+                net_trace!("rx: randomly corrupting a packet");
+                self.state.corrupt(&mut buffer);
+            }
+
+            let deliver_at = timestamp + self.config.delay;
+            let duplicate = self.state.maybe(self.config.duplicate_pct);
+            let reorder = self.state.maybe(self.config.reorder_pct);
+
+            if duplicate {
+                net_trace!("rx: randomly duplicating a packet");
+                self.rx_queue.push_back(Frame {
+                    deliver_at,
+                    meta,
+                    buffer: buffer.clone(),
+                });
+            }
+
+            let frame = Frame {
+                deliver_at,
+                meta,
+                buffer,
+            };
+
+            match self.rx_queue.len() {
+                len if reorder && len > 0 => {
+                    net_trace!("rx: randomly reordering a packet");
+                    // Swap with the previously queued frame, keeping the earliest delivery time.
+                    let prev_deliver_at = self.rx_queue[len - 1].deliver_at;
+                    self.rx_queue.insert(
+                        len - 1,
+                        Frame {
+                            deliver_at: prev_deliver_at,
+                            ..frame
+                        },
+                    );
+                    self.rx_queue[len].deliver_at = deliver_at;
+                }
+                _ => self.rx_queue.push_back(frame),
+            }
+        }
+    }
+}
+
+impl<D: Device> Device for FaultInjector<D> {
+    type RxToken<'a>
+        = RxToken
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken<'a, D::TxToken<'a>>
+    where
+        Self: 'a;
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = self.inner.capabilities();
+        if caps.max_transmission_unit > MTU {
+            caps.max_transmission_unit = MTU;
+        }
+        caps
+    }
+
+    fn receive(&mut self, timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        self.fill_rx_queue(timestamp);
+
+        if !self
+            .rx_queue
+            .front()
+            .is_some_and(|f| f.deliver_at <= timestamp)
+        {
+            return None;
+        }
+
+        let tx_token = self.inner.transmit(timestamp)?;
+        let frame = self.rx_queue.pop_front()?;
+
+        let rx = RxToken {
+            buffer: frame.buffer,
+            meta: frame.meta,
+        };
+        let tx = TxToken {
+            state: &mut self.state,
+            config: self.config,
+            token: tx_token,
+            timestamp,
+        };
+        Some((rx, tx))
+    }
+
+    fn transmit(&mut self, timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        self.inner.transmit(timestamp).map(|token| TxToken {
+            state: &mut self.state,
+            config: self.config,
+            token,
+            timestamp,
+        })
+    }
+
+    #[cfg(any(feature = "medium-pc5", feature = "medium-ieee80211p"))]
+    fn filter_addr(&self) -> Option<HardwareAddress> {
+        self.inner.filter_addr()
+    }
+
+    #[cfg(any(feature = "medium-pc5", feature = "medium-ieee80211p"))]
+    fn set_filter_addr(&mut self, addr: Option<HardwareAddress>) {
+        self.inner.set_filter_addr(addr)
+    }
+
+    fn channel_busy_ratio(&self) -> ChannelBusyRatio {
+        self.config
+            .channel_busy_ratio
+            .unwrap_or_else(|| self.inner.channel_busy_ratio())
+    }
+}
+
+#[doc(hidden)]
+pub struct RxToken {
+    buffer: Vec<u8>,
+    meta: PacketMeta,
+}
+
+impl phy::RxToken for RxToken {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        f(&self.buffer)
+    }
+
+    fn meta(&self) -> PacketMeta {
+        self.meta
+    }
+}
+
+#[doc(hidden)]
+pub struct TxToken<'a, Tx: phy::TxToken> {
+    state: &'a mut State,
+    config: Config,
+    token: Tx,
+    timestamp: Instant,
+}
+
+impl<'a, Tx: phy::TxToken> phy::TxToken for TxToken<'a, Tx> {
+    fn consume<R, F>(mut self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let drop = if self.state.maybe(self.config.drop_pct) {
+            net_trace!("tx: randomly dropping a packet");
+            true
+        } else if self.config.max_size > 0 && len > self.config.max_size {
+            net_trace!("tx: dropping a packet that is too large");
+            true
+        } else if !self.state.maybe_transmit(&self.config, self.timestamp) {
+            net_trace!("tx: dropping a packet because of rate limiting");
+            true
+        } else {
+            false
+        };
+
+        if drop {
+            let mut buffer = alloc::vec![0; len];
+            return f(&mut buffer);
+        }
+
+        let Self {
+            token,
+            state,
+            config,
+            ..
+        } = self;
+        token.consume(len, |buf| {
+            let result = f(buf);
+            if state.maybe(config.corrupt_pct) {
+                net_trace!("tx: corrupting a packet");
+                state.corrupt(buf);
+            }
+            result
+        })
+    }
+
+    fn set_meta(&mut self, meta: PacketMeta) {
+        self.token.set_meta(meta);
+    }
+}
+
+#[cfg(all(test, feature = "medium-ethernet"))]
+mod test {
+    use super::*;
+    use crate::phy::{Medium, RxToken as _, TxToken as _};
+    use crate::tests::TestingDevice;
+
+    /// Fixed seed, so every test run injects the same faults.
+    const SEED: u64 = 0xcafe;
+
+    fn device() -> FaultInjector<TestingDevice> {
+        FaultInjector::new(TestingDevice::new(Medium::Ethernet), SEED)
+    }
+
+    fn send(device: &mut FaultInjector<TestingDevice>, byte: u8) {
+        let tx = device.transmit(Instant::ZERO).unwrap();
+        tx.consume(8, |buf| buf.fill(byte));
+    }
+
+    fn recv(device: &mut FaultInjector<TestingDevice>, timestamp: Instant) -> Option<Vec<u8>> {
+        device
+            .receive(timestamp)
+            .map(|(rx, _)| rx.consume(|buf| buf.to_vec()))
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut device = device();
+        device.set_drop_chance(100);
+
+        send(&mut device, 1);
+        assert!(device.get_ref().queue.is_empty());
+
+        // Dropped packets are still emitted in a buffer of the requested length.
+        let tx = device.transmit(Instant::ZERO).unwrap();
+        assert_eq!(tx.consume(MTU + 8, |buf| buf.len()), MTU + 8);
+
+        device.set_drop_chance(0);
+        send(&mut device, 1);
+        device.set_drop_chance(100);
+        assert!(recv(&mut device, Instant::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let flipped = |buf: Vec<u8>| buf.iter().map(|b| b.count_ones()).sum::<u32>();
+        let mut device = device();
+
+        // Corrupted on tx only.
+        device.set_corrupt_chance(100);
+        send(&mut device, 0);
+        device.set_corrupt_chance(0);
+        assert_eq!(flipped(recv(&mut device, Instant::ZERO).unwrap()), 1);
+
+        // Corrupted on rx only.
+        send(&mut device, 0);
+        device.set_corrupt_chance(100);
+        assert_eq!(flipped(recv(&mut device, Instant::ZERO).unwrap()), 1);
+    }
+
+    #[test]
+    fn test_seed() {
+        let faults = || {
+            let mut device = device();
+            device.set_corrupt_chance(50);
+            device.set_drop_chance(10);
+            device.set_reorder_chance(50);
+
+            for byte in 0..16 {
+                send(&mut device, byte);
+            }
+            let mut frames = Vec::new();
+            while let Some(buf) = recv(&mut device, Instant::ZERO) {
+                frames.push(buf);
+            }
+            frames
+        };
+
+        // Same seed, same faults.
+        assert_eq!(faults(), faults());
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let mut device = device();
+        device.set_duplicate_chance(100);
+
+        send(&mut device, 1);
+        assert_eq!(recv(&mut device, Instant::ZERO), Some(vec![1; 8]));
+        assert_eq!(recv(&mut device, Instant::ZERO), Some(vec![1; 8]));
+        assert!(recv(&mut device, Instant::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut device = device();
+        device.set_reorder_chance(100);
+
+        send(&mut device, 1);
+        send(&mut device, 2);
+        assert_eq!(recv(&mut device, Instant::ZERO), Some(vec![2; 8]));
+        assert_eq!(recv(&mut device, Instant::ZERO), Some(vec![1; 8]));
+
+        // Frames received in a burst are shuffled, none is lost nor duplicated.
+        device.set_reorder_chance(50);
+        for byte in 0..16 {
+            send(&mut device, byte);
+        }
+        let mut order = Vec::new();
+        while let Some(buf) = recv(&mut device, Instant::ZERO) {
+            order.push(buf[0]);
+        }
+        assert_ne!(order, (0..16).collect::<Vec<u8>>());
+        order.sort_unstable();
+        assert_eq!(order, (0..16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_delay() {
+        let mut device = device();
+        device.set_delay(Duration::from_millis(10));
+
+        send(&mut device, 1);
+        assert!(recv(&mut device, Instant::ZERO).is_none());
+        assert!(recv(&mut device, Instant::from_millis(9)).is_none());
+        assert_eq!(
+            recv(&mut device, Instant::from_millis(10)),
+            Some(vec![1; 8])
+        );
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut device = device();
+        device.set_bucket_interval(Duration::from_millis(100));
+        device.set_max_tx_rate(1);
+
+        let timestamp = Instant::from_millis(200);
+        for byte in 1..=2 {
+            let tx = device.transmit(timestamp).unwrap();
+            tx.consume(8, |buf| buf.fill(byte));
+        }
+        assert_eq!(device.get_ref().queue.len(), 1);
+    }
+
+    #[test]
+    fn test_fake_channel_busy_ratio() {
+        let mut device = device();
+        assert_eq!(device.channel_busy_ratio().as_ratio(), 0.0);
+
+        device.set_fake_channel_busy_ratio(Some(ChannelBusyRatio::from_percentage(62.0)));
+        assert_eq!(device.channel_busy_ratio().as_percentage(), 62.0);
+    }
+}
@@ -7,9 +7,11 @@ and implementations of it:
   * the [_loopback_](struct.Loopback.html), for zero dependency testing;
   * _middleware_ [Tracer](struct.Tracer.html) and
     [FaultInjector](struct.FaultInjector.html), to facilitate debugging;
-  * _adapters_ [RawSocket](struct.RawSocket.html) and
-    [TunTapInterface](struct.TunTapInterface.html), to transmit and receive frames
-    on the host OS.
+  * _adapters_ [RawSocket](struct.RawSocket.html),
+    [TunTapInterface](struct.TunTapInterface.html) and [OcbDevice](struct.OcbDevice.html),
+    to transmit and receive frames on the host OS;
+  * the [_simulated medium_](sim/index.html), to run many virtual devices sharing
+    the same channel in a single process.
 */
 
 #[cfg(feature = "medium-ieee80211p")]
@@ -23,6 +25,9 @@ use crate::{time::Instant, types::Power};
 ))]
 pub mod sys;
 
+mod fault_injector;
+#[cfg(all(feature = "phy-ocb", any(target_os = "linux", target_os = "android")))]
+pub mod ocb;
 #[cfg(all(feature = "phy-raw_socket", unix))]
 mod raw_socket;
 mod tracer;
@@ -38,6 +43,9 @@ mod tuntap_interface;
 ))]
 pub use self::sys::{wait, wait_many};
 
+pub use self::fault_injector::FaultInjector;
+#[cfg(all(feature = "phy-ocb", any(target_os = "linux", target_os = "android")))]
+pub use self::ocb::OcbDevice;
 #[cfg(all(feature = "phy-raw_socket", unix))]
 pub use self::raw_socket::RawSocket;
 pub use self::tracer::Tracer;
@@ -46,6 +54,8 @@ pub use self::tracer::Tracer;
     any(target_os = "linux", target_os = "android")
 ))]
 pub use self::tuntap_interface::TunTapInterface;
+#[cfg(any(feature = "phy-sim", test))]
+pub mod sim;
 #[cfg(feature = "phy-udp")]
 pub mod udp;
 #[cfg(feature = "phy-udp")]
@@ -80,6 +90,9 @@ pub struct PacketMeta {
     pub id: u32,
     #[cfg(feature = "packetmeta-power")]
     pub power: Option<Power>,
+    /// Data rate, in units of 500 kbps.
+    #[cfg(feature = "packetmeta-rate")]
+    pub rate: Option<u8>,
 }
 
 /// A description of filter behavior for every supported protocol.
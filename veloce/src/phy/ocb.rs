@@ -0,0 +1,580 @@
+use std::cell::{Cell, RefCell};
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::rc::Rc;
+use std::vec::Vec;
+
+use mio::event::Source;
+use mio::unix::SourceFd;
+
+use crate::phy::sys::{self, nl80211::Survey};
+use crate::phy::{self, ChannelBusyRatio, Device, DeviceCapabilities, Medium, PacketMeta};
+use crate::time::Instant;
+use crate::types::Power;
+
+pub use crate::phy::sys::nl80211::ChannelWidth;
+
+/// Radiotap header length emitted on transmission.
+const RADIOTAP_TX_LEN: usize = 14;
+/// Radiotap "present" bits.
+const RADIOTAP_TSFT: u32 = 0;
+const RADIOTAP_FLAGS: u32 = 1;
+const RADIOTAP_RATE: u32 = 2;
+const RADIOTAP_CHANNEL: u32 = 3;
+const RADIOTAP_FHSS: u32 = 4;
+const RADIOTAP_DBM_ANTSIGNAL: u32 = 5;
+const RADIOTAP_DBM_TX_POWER: u32 = 10;
+const RADIOTAP_TX_FLAGS: u32 = 15;
+const RADIOTAP_EXT: u32 = 31;
+/// Radiotap flag indicating the frame includes the FCS.
+const RADIOTAP_F_FCS: u8 = 0x10;
+/// Radiotap tx flag asking for no acknowledgment.
+const RADIOTAP_F_TX_NOACK: u16 = 0x0008;
+/// Length of the 802.11 FCS.
+const FCS_LEN: usize = 4;
+
+/// Minimum delay between two channel survey requests.
+const SURVEY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Configuration of an [OcbDevice].
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Channel center frequency, in MHz.
+    pub frequency: u32,
+    /// Channel width.
+    pub width: ChannelWidth,
+    /// Default transmission power, used when a frame has no power metadata.
+    pub tx_power: Power,
+    /// Default transmission data rate, in units of 500 kbps, used when a frame has no
+    /// rate metadata.
+    pub data_rate: u8,
+    /// Name of the monitor interface used to transmit and receive frames.
+    /// Defaults to the OCB interface name suffixed with `mon`.
+    pub monitor_name: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            // ITS-G5 control channel, aka channel 180.
+            frequency: 5900,
+            width: ChannelWidth::Mhz10,
+            tx_power: Power::from_dbm_i32(23),
+            // 6 Mbps, ITS-G5 default data rate.
+            data_rate: 12,
+            monitor_name: None,
+        }
+    }
+}
+
+/// A Linux mac80211 wireless interface in 802.11 OCB mode.
+///
+/// The OCB interface is configured through nl80211, then frames are transmitted
+/// and received on a monitor interface created on the same radio, with a radiotap
+/// header carrying the per-frame transmission power and data rate. The channel busy
+/// ratio is computed from the channel survey data reported by the driver.
+///
+/// Any mac80211 driver supporting OCB can be used, including `mac80211_hwsim`:
+///
+/// ```sh
+/// modprobe mac80211_hwsim radios=2
+/// ```
+///
+/// This requires superuser privileges or the `CAP_NET_ADMIN` and `CAP_NET_RAW`
+/// capabilities set on the executable.
+#[derive(Debug)]
+pub struct OcbDevice {
+    config: Config,
+    lower: Rc<RefCell<sys::RawSocketDesc>>,
+    nl: RefCell<sys::Nl80211Desc>,
+    ifindex: u32,
+    /// Index of the monitor interface, if created by the device.
+    monitor: Option<u32>,
+    mtu: usize,
+    cbr: Cell<ChannelBusyRatio>,
+    survey: Cell<Option<(std::time::Instant, Survey)>>,
+}
+
+impl AsRawFd for OcbDevice {
+    fn as_raw_fd(&self) -> RawFd {
+        self.lower.borrow().as_raw_fd()
+    }
+}
+
+impl OcbDevice {
+    /// Creates an OCB device on the wireless interface called `name`.
+    ///
+    /// The interface is switched to OCB mode and joins the channel described in `config`.
+    pub fn new(name: &str, config: Config) -> io::Result<OcbDevice> {
+        let mut nl = sys::Nl80211Desc::new()?;
+        let ifindex = sys::nl80211::if_index(name)?;
+        let wiphy = nl.wiphy(ifindex)?;
+
+        sys::nl80211::set_if_up(name, false)?;
+        nl.set_ocb_mode(ifindex)?;
+        sys::nl80211::set_if_up(name, true)?;
+        nl.join_ocb(ifindex, config.frequency, config.width)?;
+
+        let monitor_name = config
+            .monitor_name
+            .clone()
+            .unwrap_or_else(|| format!("{name}mon"));
+        let created = nl.new_monitor(wiphy, &monitor_name)?;
+        let monitor_index = sys::nl80211::if_index(&monitor_name)?;
+        let monitor = created.then_some(monitor_index);
+
+        let lower = sys::nl80211::set_if_up(&monitor_name, true).and_then(|_| {
+            let mut lower = sys::RawSocketDesc::new(&monitor_name, Medium::Ieee80211p)?;
+            lower.bind_interface()?;
+            let mtu = lower.interface_mtu()?;
+            Ok((lower, mtu))
+        });
+
+        let (lower, mtu) = match lower {
+            Ok(lower) => lower,
+            Err(e) => {
+                if let Some(monitor) = monitor {
+                    let _ = nl.del_interface(monitor);
+                }
+                return Err(e);
+            }
+        };
+
+        Ok(OcbDevice {
+            config,
+            lower: Rc::new(RefCell::new(lower)),
+            nl: RefCell::new(nl),
+            ifindex,
+            monitor,
+            mtu,
+            cbr: Cell::new(ChannelBusyRatio::from_ratio(0.0)),
+            survey: Cell::new(None),
+        })
+    }
+
+    /// Set the default transmission data rate, in units of 500 kbps.
+    pub fn set_data_rate(&mut self, rate: u8) {
+        self.config.data_rate = rate;
+    }
+
+    /// Refresh the channel busy ratio from the driver survey data.
+    fn refresh_cbr(&self) {
+        let now = std::time::Instant::now();
+        let prev = self.survey.get();
+
+        if prev.is_some_and(|(at, _)| now - at < SURVEY_INTERVAL) {
+            return;
+        }
+
+        let survey = match self
+            .nl
+            .borrow_mut()
+            .survey(self.ifindex, self.config.frequency)
+        {
+            Ok(Some(survey)) => survey,
+            Ok(None) => return,
+            Err(e) => {
+                net_debug!("phy: cannot get channel survey: {}", e);
+                return;
+            }
+        };
+
+        if let Some((_, prev)) = prev {
+            let time = survey.time.saturating_sub(prev.time);
+            let busy = survey.time_busy.saturating_sub(prev.time_busy);
+            if time > 0 {
+                self.cbr
+                    .set(ChannelBusyRatio::from_ratio(busy as f64 / time as f64));
+            }
+        }
+
+        self.survey.set(Some((now, survey)));
+    }
+}
+
+impl Drop for OcbDevice {
+    fn drop(&mut self) {
+        let nl = self.nl.get_mut();
+        if let Some(monitor) = self.monitor {
+            let _ = nl.del_interface(monitor);
+        }
+        let _ = nl.leave_ocb(self.ifindex);
+    }
+}
+
+impl Device for OcbDevice {
+    type RxToken<'a>
+        = RxToken
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken
+    where
+        Self: 'a;
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+        caps.max_transmission_unit = self.mtu;
+        caps.medium = Medium::Ieee80211p;
+        caps.radio.tx_power = self.config.tx_power;
+        caps
+    }
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        let mut lower = self.lower.borrow_mut();
+
+        loop {
+            let mut buffer = vec![0; self.mtu + 256];
+            let size = match lower.recv(&mut buffer[..]) {
+                Ok(size) => size,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return None,
+                Err(err) => panic!("{}", err),
+            };
+            buffer.truncate(size);
+
+            let Some(info) = parse_radiotap(&buffer) else {
+                net_trace!("phy: malformed radiotap header");
+                continue;
+            };
+
+            // Frames we transmitted are reported back on the monitor interface.
+            if info.tx_status {
+                continue;
+            }
+
+            let mut end = buffer.len();
+            if info.flags & RADIOTAP_F_FCS != 0 {
+                end = end.saturating_sub(FCS_LEN);
+            }
+            if end < info.len {
+                continue;
+            }
+
+            #[allow(unused_mut)]
+            let mut meta = PacketMeta::default();
+            #[cfg(feature = "packetmeta-power")]
+            {
+                meta.power = info.signal.map(|s| Power::from_dbm_i32(s.into()));
+            }
+            #[cfg(feature = "packetmeta-rate")]
+            {
+                meta.rate = info.rate;
+            }
+
+            buffer.truncate(end);
+            buffer.drain(..info.len);
+
+            let rx = RxToken { buffer, meta };
+            let tx = TxToken {
+                lower: self.lower.clone(),
+                tx_power: self.config.tx_power,
+                data_rate: self.config.data_rate,
+                meta: PacketMeta::default(),
+            };
+            return Some((rx, tx));
+        }
+    }
+
+    fn transmit(&mut self, _timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        Some(TxToken {
+            lower: self.lower.clone(),
+            tx_power: self.config.tx_power,
+            data_rate: self.config.data_rate,
+            meta: PacketMeta::default(),
+        })
+    }
+
+    fn channel_busy_ratio(&self) -> ChannelBusyRatio {
+        self.refresh_cbr();
+        self.cbr.get()
+    }
+}
+
+#[doc(hidden)]
+pub struct RxToken {
+    buffer: Vec<u8>,
+    meta: PacketMeta,
+}
+
+impl phy::RxToken for RxToken {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        f(&self.buffer[..])
+    }
+
+    fn meta(&self) -> PacketMeta {
+        self.meta
+    }
+}
+
+#[doc(hidden)]
+pub struct TxToken {
+    lower: Rc<RefCell<sys::RawSocketDesc>>,
+    tx_power: Power,
+    data_rate: u8,
+    meta: PacketMeta,
+}
+
+impl phy::TxToken for TxToken {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        #[cfg(feature = "packetmeta-power")]
+        let tx_power = self.meta.power.unwrap_or(self.tx_power);
+        #[cfg(not(feature = "packetmeta-power"))]
+        let tx_power = self.tx_power;
+
+        #[cfg(feature = "packetmeta-rate")]
+        let data_rate = self.meta.rate.unwrap_or(self.data_rate);
+        #[cfg(not(feature = "packetmeta-rate"))]
+        let data_rate = self.data_rate;
+
+        let mut lower = self.lower.borrow_mut();
+        let mut buffer = vec![0; RADIOTAP_TX_LEN + len];
+        emit_radiotap(&mut buffer[..RADIOTAP_TX_LEN], data_rate, tx_power);
+
+        let result = f(&mut buffer[RADIOTAP_TX_LEN..]);
+        match lower.send(&buffer[..]) {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                net_debug!("phy: tx failed due to WouldBlock")
+            }
+            Err(err) => panic!("{}", err),
+        }
+        result
+    }
+
+    fn set_meta(&mut self, meta: PacketMeta) {
+        self.meta = meta;
+    }
+}
+
+impl Source for OcbDevice {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+/// Radiotap information of a received frame.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RadiotapInfo {
+    /// Radiotap header length.
+    len: usize,
+    /// Radiotap flags field.
+    flags: u8,
+    /// Data rate, in units of 500 kbps.
+    rate: Option<u8>,
+    /// Received signal power, in dBm.
+    signal: Option<i8>,
+    /// Whether the frame is a transmission status report.
+    tx_status: bool,
+}
+
+/// Emit a radiotap transmission header in `buffer`.
+fn emit_radiotap(buffer: &mut [u8], data_rate: u8, tx_power: Power) {
+    let present = (1 << RADIOTAP_FLAGS)
+        | (1 << RADIOTAP_RATE)
+        | (1 << RADIOTAP_DBM_TX_POWER)
+        | (1 << RADIOTAP_TX_FLAGS);
+
+    buffer[0] = 0; // Version.
+    buffer[1] = 0; // Padding.
+    buffer[2..4].copy_from_slice(&(RADIOTAP_TX_LEN as u16).to_le_bytes());
+    buffer[4..8].copy_from_slice(&(present as u32).to_le_bytes());
+    buffer[8] = 0; // Flags, no FCS.
+    buffer[9] = data_rate;
+    buffer[10] = tx_power.as_dbm_i32().clamp(i8::MIN.into(), i8::MAX.into()) as i8 as u8;
+    buffer[11] = 0; // Alignment padding.
+    buffer[12..14].copy_from_slice(&RADIOTAP_F_TX_NOACK.to_le_bytes());
+}
+
+/// Parse the radiotap header at the beginning of `buffer`.
+fn parse_radiotap(buffer: &[u8]) -> Option<RadiotapInfo> {
+    if buffer.len() < 8 || buffer[0] != 0 {
+        return None;
+    }
+
+    let len = u16::from_le_bytes([buffer[2], buffer[3]]) as usize;
+    if len > buffer.len() {
+        return None;
+    }
+
+    let word = |offset: usize| -> Option<u32> {
+        buffer
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let present = word(4)?;
+
+    // Skip the extended presence bitmaps.
+    let mut offset = 8;
+    let mut last = present;
+    while last & (1 << RADIOTAP_EXT) != 0 {
+        last = word(offset)?;
+        offset += 4;
+    }
+
+    let mut info = RadiotapInfo {
+        len,
+        tx_status: present & (1 << RADIOTAP_TX_FLAGS) != 0,
+        ..Default::default()
+    };
+
+    // Walk the fields preceding the antenna signal, with their (alignment, size).
+    let fields = [
+        (RADIOTAP_TSFT, 8, 8),
+        (RADIOTAP_FLAGS, 1, 1),
+        (RADIOTAP_RATE, 1, 1),
+        (RADIOTAP_CHANNEL, 2, 4),
+        (RADIOTAP_FHSS, 1, 2),
+        (RADIOTAP_DBM_ANTSIGNAL, 1, 1),
+    ];
+
+    for (bit, align, size) in fields {
+        if present & (1 << bit) == 0 {
+            continue;
+        }
+
+        offset = (offset + align - 1) & !(align - 1);
+        if offset + size > len {
+            return None;
+        }
+
+        match bit {
+            RADIOTAP_FLAGS => info.flags = buffer[offset],
+            RADIOTAP_RATE => info.rate = Some(buffer[offset]),
+            RADIOTAP_DBM_ANTSIGNAL => info.signal = Some(buffer[offset] as i8),
+            _ => {}
+        }
+
+        offset += size;
+    }
+
+    Some(info)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::phy::{RxToken as _, TxToken as _};
+
+    /// Names of the network interfaces backed by the `mac80211_hwsim` driver.
+    fn hwsim_interfaces() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let driver = std::fs::read_link(entry.path().join("device/driver")).ok()?;
+                driver
+                    .ends_with("mac80211_hwsim")
+                    .then(|| entry.file_name().to_string_lossy().into_owned())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_emit_radiotap() {
+        let mut buffer = [0xffu8; RADIOTAP_TX_LEN];
+        emit_radiotap(&mut buffer, 12, Power::from_dbm_i32(20));
+
+        assert_eq!(
+            buffer,
+            [0x00, 0x00, 0x0e, 0x00, 0x06, 0x84, 0x00, 0x00, 0x00, 0x0c, 0x14, 0x00, 0x08, 0x00]
+        );
+
+        let info = parse_radiotap(&buffer).unwrap();
+        assert_eq!(info.len, RADIOTAP_TX_LEN);
+        assert!(info.tx_status);
+        assert_eq!(info.rate, Some(12));
+        assert_eq!(info.signal, None);
+    }
+
+    #[test]
+    fn test_parse_radiotap() {
+        // Typical mac80211_hwsim rx header: flags, rate, channel, antenna signal, rx flags.
+        let buffer = [
+            0x00, 0x00, 0x12, 0x00, 0x2e, 0x48, 0x00, 0x00, 0x10, 0x0c, 0x6c, 0x17, 0x40, 0x01,
+            0xc4, 0x00, 0x00, 0x00, 0xaa, 0xaa,
+        ];
+
+        let info = parse_radiotap(&buffer).unwrap();
+        assert_eq!(info.len, 18);
+        assert_eq!(info.flags, RADIOTAP_F_FCS);
+        assert_eq!(info.rate, Some(12));
+        assert_eq!(info.signal, Some(-60));
+        assert!(!info.tx_status);
+    }
+
+    #[test]
+    fn test_parse_radiotap_malformed() {
+        assert!(parse_radiotap(&[0x00, 0x00, 0x12]).is_none());
+        assert!(parse_radiotap(&[0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00]).is_none());
+    }
+
+    #[test]
+    #[ignore = "requires `modprobe mac80211_hwsim radios=2` and CAP_NET_ADMIN + CAP_NET_RAW"]
+    fn test_hwsim_exchange() {
+        let names = hwsim_interfaces();
+        assert!(names.len() >= 2, "two mac80211_hwsim radios are needed");
+
+        let mut a = OcbDevice::new(&names[0], Config::default()).unwrap();
+        let mut b = OcbDevice::new(&names[1], Config::default()).unwrap();
+        let monitor_a = format!("{}mon", names[0]);
+        assert!(sys::nl80211::if_index(&monitor_a).is_ok());
+
+        // Broadcast 802.11 data frame, with the wildcard BSSID used in OCB mode.
+        let mut frame = vec![0x08, 0x00, 0x00, 0x00];
+        frame.extend_from_slice(&[0xff; 6]);
+        frame.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        frame.extend_from_slice(&[0xff; 6]);
+        frame.extend_from_slice(&[0x00, 0x00]);
+        frame.extend_from_slice(b"veloce hwsim test");
+
+        let tx = a.transmit(Instant::ZERO).unwrap();
+        tx.consume(frame.len(), |buf| buf.copy_from_slice(&frame));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+        let received = loop {
+            if let Some((rx, _)) = b.receive(Instant::ZERO) {
+                let buffer = rx.consume(|buf| buf.to_vec());
+                if buffer.ends_with(b"veloce hwsim test") {
+                    break buffer;
+                }
+            }
+            assert!(std::time::Instant::now() < deadline, "frame not received");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert_eq!(received[10..16], frame[10..16]);
+
+        // The monitor interface created by the device is deleted on drop.
+        drop(a);
+        assert!(sys::nl80211::if_index(&monitor_a).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+/*! Simulated radio medium.
+
+The `sim` module provides a [Channel] shared between any number of virtual
+[SimDevice], to run several ITS stations in the same process. Each device is
+attached to a node of the channel, which models:
+
+  * per-link range, computed from the position of each node,
+  * a frame loss probability,
+  * a fixed propagation/processing latency,
+  * the airtime of each frame, from which the channel busy ratio is derived.
+
+The channel runs on a virtual clock, advanced by the user with [Channel::advance].
+Frames are delivered to the receiving nodes once the virtual clock reaches their
+reception time, regardless of the timestamp given to [Device::receive].
+*/
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::vec_deque::VecDeque;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use uom::si::f64::Length;
+use uom::si::length::meter;
+
+use crate::common::geo_area::GeoPosition;
+use crate::common::PotiFix;
+use crate::phy::{self, ChannelBusyRatio, Device, DeviceCapabilities, Medium};
+use crate::rand::Rand;
+use crate::time::{Duration, Instant};
+
+/// Identifier of a node attached to a [Channel].
+pub type NodeId = usize;
+
+/// Simulated channel configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// Maximum communication range between two nodes.
+    pub range: Length,
+    /// Probability for a frame to be lost on a link, between 0.0 and 1.0 inclusive.
+    pub loss: f64,
+    /// Latency added to the airtime of each frame before it is delivered.
+    pub latency: Duration,
+    /// Channel bitrate, in bits per second. Used to compute frame airtime.
+    pub bitrate: u64,
+    /// Measurement window of the channel busy ratio.
+    pub cbr_window: Duration,
+    /// Random seed used for the loss model.
+    pub random_seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            range: Length::new::<meter>(1000.0),
+            loss: 0.0,
+            latency: Duration::from_micros(100),
+            // G5 default data rate is 6 Mbps.
+            bitrate: 6_000_000,
+            cbr_window: Duration::from_millis(100),
+            random_seed: 0,
+        }
+    }
+}
+
+/// A frame traveling over the simulated medium.
+#[derive(Debug)]
+struct Frame {
+    /// Virtual time at which the frame is received.
+    deliver_at: Instant,
+    /// Frame content.
+    buffer: Vec<u8>,
+}
+
+/// A node of the simulated medium.
+#[derive(Debug)]
+struct Node {
+    /// Position of the node. A node without position is in range of every other node.
+    position: Option<GeoPosition>,
+    /// Frames waiting to be received by the node, sorted by delivery time.
+    rx_queue: VecDeque<Frame>,
+    /// Time intervals during which the node sensed the channel as busy.
+    busy: VecDeque<(Instant, Instant)>,
+}
+
+#[derive(Debug)]
+struct ChannelInner {
+    /// Channel configuration.
+    config: Config,
+    /// Virtual clock.
+    now: Instant,
+    /// Random number generator for the loss model.
+    rand: Rand,
+    /// Attached nodes, indexed by [NodeId].
+    nodes: Vec<Node>,
+    /// Number of frames transmitted on the channel.
+    tx_count: usize,
+    /// Number of frames lost on the channel links.
+    lost_count: usize,
+}
+
+impl ChannelInner {
+    /// Query whether `a` and `b` nodes are in communication range.
+    fn in_range(&self, a: NodeId, b: NodeId) -> bool {
+        match (self.nodes[a].position, self.nodes[b].position) {
+            (Some(pos_a), Some(pos_b)) => pos_a.distance_to(&pos_b) <= self.config.range,
+            _ => true,
+        }
+    }
+
+    /// Compute the airtime of a frame of `len` bytes.
+    fn airtime(&self, len: usize) -> Duration {
+        let micros = (len as u64 * 8 * 1_000_000).div_ceil(self.config.bitrate.max(1));
+        Duration::from_micros(micros)
+    }
+
+    /// Transmit `buffer` from the `src` node to every node in range.
+    fn transmit(&mut self, src: NodeId, buffer: Vec<u8>) {
+        let start = self.now;
+        let end = start + self.airtime(buffer.len());
+        let deliver_at = end + self.config.latency;
+        let oldest = start - self.config.cbr_window;
+
+        self.tx_count += 1;
+
+        for dst in 0..self.nodes.len() {
+            if dst != src && !self.in_range(src, dst) {
+                continue;
+            }
+
+            // Every node in range senses the channel as busy, including the sender.
+            let node = &mut self.nodes[dst];
+            while node.busy.front().is_some_and(|(_, e)| *e < oldest) {
+                node.busy.pop_front();
+            }
+            node.busy.push_back((start, end));
+
+            if dst == src {
+                continue;
+            }
+
+            let draw = self.rand.rand_u32() as f64 / u32::MAX as f64;
+            if draw < self.config.loss {
+                self.lost_count += 1;
+                continue;
+            }
+
+            let node = &mut self.nodes[dst];
+            let idx = node
+                .rx_queue
+                .partition_point(|f| f.deliver_at <= deliver_at);
+            node.rx_queue.insert(
+                idx,
+                Frame {
+                    deliver_at,
+                    buffer: buffer.clone(),
+                },
+            );
+        }
+    }
+
+    /// Compute the channel busy ratio sensed by the `node` node.
+    fn channel_busy_ratio(&self, node: NodeId) -> ChannelBusyRatio {
+        let window = self.config.cbr_window;
+        if window == Duration::ZERO {
+            return ChannelBusyRatio::from_ratio(0.0);
+        }
+
+        let win_start = self.now - window;
+        let mut busy = Duration::ZERO;
+        let mut cursor = win_start;
+
+        // Busy intervals are pushed in start order, so merging them is a single pass.
+        for (start, end) in self.nodes[node].busy.iter() {
+            let start = (*start).max(cursor);
+            let end = (*end).min(self.now);
+            if end > start {
+                busy += end - start;
+                cursor = end;
+            }
+        }
+
+        ChannelBusyRatio::from_ratio(busy.total_micros() as f64 / window.total_micros() as f64)
+    }
+}
+
+/// A simulated radio channel.
+///
+/// A [Channel] is a cheap handle on the shared medium state, it can be cloned
+/// and each clone refers to the same medium.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    inner: Rc<RefCell<ChannelInner>>,
+}
+
+impl Channel {
+    /// Create a new simulated channel, with its virtual clock set at `now`.
+    pub fn new(config: Config, now: Instant) -> Self {
+        Channel {
+            inner: Rc::new(RefCell::new(ChannelInner {
+                config,
+                now,
+                rand: Rand::new(config.random_seed),
+                nodes: Vec::new(),
+                tx_count: 0,
+                lost_count: 0,
+            })),
+        }
+    }
+
+    /// Attach a new device of type `medium` to the channel.
+    pub fn device(&self, medium: Medium) -> SimDevice {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.nodes.len();
+        inner.nodes.push(Node {
+            position: None,
+            rx_queue: VecDeque::new(),
+            busy: VecDeque::new(),
+        });
+
+        SimDevice {
+            channel: self.clone(),
+            id,
+            max_transmission_unit: match medium {
+                #[cfg(feature = "medium-ethernet")]
+                Medium::Ethernet => 1514,
+                #[cfg(feature = "medium-ieee80211p")]
+                Medium::Ieee80211p => 1500,
+                #[cfg(feature = "medium-pc5")]
+                Medium::PC5 => 1500,
+            },
+            medium,
+        }
+    }
+
+    /// Return the current virtual time of the channel.
+    pub fn now(&self) -> Instant {
+        self.inner.borrow().now
+    }
+
+    /// Set the virtual time of the channel.
+    ///
+    /// # Panics
+    /// This method panics if `now` is before the current virtual time.
+    pub fn set_now(&self, now: Instant) {
+        let mut inner = self.inner.borrow_mut();
+        assert!(now >= inner.now, "virtual clock cannot go backwards");
+        inner.now = now;
+    }
+
+    /// Advance the virtual clock of the channel by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.inner.borrow_mut().now += duration;
+    }
+
+    /// Return the delivery time of the next frame waiting on the channel, if any.
+    /// Useful to jump the virtual clock straight to the next event.
+    pub fn next_delivery(&self) -> Option<Instant> {
+        self.inner
+            .borrow()
+            .nodes
+            .iter()
+            .filter_map(|n| n.rx_queue.front().map(|f| f.deliver_at))
+            .min()
+    }
+
+    /// Set the loss probability of the channel links.
+    /// `loss` is clamped between 0.0 and 1.0.
+    pub fn set_loss(&self, loss: f64) {
+        self.inner.borrow_mut().config.loss = loss.clamp(0.0, 1.0);
+    }
+
+    /// Set the maximum communication range between two nodes.
+    pub fn set_range(&self, range: Length) {
+        self.inner.borrow_mut().config.range = range;
+    }
+
+    /// Set the position of the `node` node.
+    pub fn set_position(&self, node: NodeId, position: Option<GeoPosition>) {
+        self.inner.borrow_mut().nodes[node].position = position;
+    }
+
+    /// Query whether nodes `a` and `b` are in communication range.
+    pub fn in_range(&self, a: NodeId, b: NodeId) -> bool {
+        self.inner.borrow().in_range(a, b)
+    }
+
+    /// Return the number of frames transmitted on the channel.
+    pub fn tx_count(&self) -> usize {
+        self.inner.borrow().tx_count
+    }
+
+    /// Return the number of frames lost on the channel links.
+    pub fn lost_count(&self) -> usize {
+        self.inner.borrow().lost_count
+    }
+}
+
+/// A virtual device attached to a simulated [Channel].
+#[derive(Debug)]
+pub struct SimDevice {
+    channel: Channel,
+    id: NodeId,
+    max_transmission_unit: usize,
+    medium: Medium,
+}
+
+impl SimDevice {
+    /// Return the node identifier of the device on the channel.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Return a handle on the channel the device is attached to.
+    pub fn channel(&self) -> Channel {
+        self.channel.clone()
+    }
+
+    /// Set the device position from a Poti `fix`.
+    /// The device is considered without position if the fix has no latitude or longitude.
+    pub fn set_position(&mut self, fix: &PotiFix) {
+        let position = match (fix.latitude(), fix.longitude()) {
+            (Ok(latitude), Ok(longitude)) => Some(GeoPosition {
+                latitude,
+                longitude,
+            }),
+            _ => None,
+        };
+
+        self.channel.set_position(self.id, position);
+    }
+
+    /// Return the number of frames waiting to be received by the device,
+    /// including the ones not yet deliverable.
+    pub fn pending(&self) -> usize {
+        self.channel.inner.borrow().nodes[self.id].rx_queue.len()
+    }
+}
+
+impl Device for SimDevice {
+    type RxToken<'a>
+        = RxToken
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken
+    where
+        Self: 'a;
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        let mut inner = self.channel.inner.borrow_mut();
+        let now = inner.now;
+        let rx_queue = &mut inner.nodes[self.id].rx_queue;
+
+        if !rx_queue.front().is_some_and(|f| f.deliver_at <= now) {
+            return None;
+        }
+
+        rx_queue.pop_front().map(|frame| {
+            let rx = RxToken {
+                buffer: frame.buffer,
+            };
+            let tx = TxToken {
+                channel: self.channel.clone(),
+                id: self.id,
+            };
+            (rx, tx)
+        })
+    }
+
+    fn transmit(&mut self, _timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        Some(TxToken {
+            channel: self.channel.clone(),
+            id: self.id,
+        })
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        DeviceCapabilities {
+            medium: self.medium,
+            max_transmission_unit: self.max_transmission_unit,
+            ..DeviceCapabilities::default()
+        }
+    }
+
+    fn channel_busy_ratio(&self) -> ChannelBusyRatio {
+        self.channel.inner.borrow().channel_busy_ratio(self.id)
+    }
+}
+
+#[doc(hidden)]
+pub struct RxToken {
+    buffer: Vec<u8>,
+}
+
+impl phy::RxToken for RxToken {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        f(&self.buffer)
+    }
+}
+
+#[doc(hidden)]
+pub struct TxToken {
+    channel: Channel,
+    id: NodeId,
+}
+
+impl phy::TxToken for TxToken {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let mut buffer = alloc::vec![0; len];
+        let result = f(&mut buffer);
+        self.channel.inner.borrow_mut().transmit(self.id, buffer);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::phy::{RxToken as _, TxToken as _};
+    use crate::types::{degree, Latitude, Longitude};
+    use approx::assert_abs_diff_eq;
+
+    fn position(lat: f64, lon: f64) -> Option<GeoPosition> {
+        Some(GeoPosition {
+            latitude: Latitude::new::<degree>(lat),
+            longitude: Longitude::new::<degree>(lon),
+        })
+    }
+
+    fn send(dev: &mut SimDevice, len: usize) {
+        let tx = dev.transmit(Instant::ZERO).unwrap();
+        tx.consume(len, |buf| buf.fill(0xaa));
+    }
+
+    #[test]
+    fn test_latency_and_airtime() {
+        let channel = Channel::new(Config::default(), Instant::ZERO);
+        let mut a = channel.device(Medium::Ethernet);
+        let mut b = channel.device(Medium::Ethernet);
+
+        // 750 bytes at 6 Mbps is 1 ms of airtime, plus 100 us of latency.
+        send(&mut a, 750);
+        assert!(b.receive(Instant::ZERO).is_none());
+        assert!(a.receive(Instant::ZERO).is_none());
+
+        channel.advance(Duration::from_micros(1099));
+        assert!(b.receive(Instant::ZERO).is_none());
+
+        channel.advance(Duration::from_micros(1));
+        let (rx, _) = b.receive(Instant::ZERO).unwrap();
+        rx.consume(|buf| assert_eq!(buf.len(), 750));
+        assert_eq!(b.pending(), 0);
+    }
+
+    #[test]
+    fn test_range() {
+        let channel = Channel::new(Config::default(), Instant::ZERO);
+        let mut a = channel.device(Medium::Ethernet);
+        let b = channel.device(Medium::Ethernet);
+        let c = channel.device(Medium::Ethernet);
+
+        // Roughly 667 meters between each node.
+        channel.set_position(a.id(), position(48.270, -3.614));
+        channel.set_position(b.id(), position(48.276, -3.614));
+        channel.set_position(c.id(), position(48.282, -3.614));
+
+        assert!(channel.in_range(a.id(), b.id()));
+        assert!(channel.in_range(b.id(), c.id()));
+        assert!(!channel.in_range(a.id(), c.id()));
+
+        send(&mut a, 100);
+        assert_eq!(b.pending(), 1);
+        assert_eq!(c.pending(), 0);
+    }
+
+    #[test]
+    fn test_loss() {
+        let config = Config {
+            loss: 1.0,
+            ..Config::default()
+        };
+        let channel = Channel::new(config, Instant::ZERO);
+        let mut a = channel.device(Medium::Ethernet);
+        let b = channel.device(Medium::Ethernet);
+
+        send(&mut a, 100);
+        assert_eq!(b.pending(), 0);
+        assert_eq!(channel.lost_count(), 1);
+
+        channel.set_loss(0.0);
+        send(&mut a, 100);
+        assert_eq!(b.pending(), 1);
+        assert_eq!(channel.tx_count(), 2);
+    }
+
+    #[test]
+    fn test_channel_busy_ratio() {
+        let channel = Channel::new(Config::default(), Instant::ZERO);
+        let mut a = channel.device(Medium::Ethernet);
+        let b = channel.device(Medium::Ethernet);
+        channel.advance(Duration::from_millis(100));
+
+        // 7500 bytes at 6 Mbps is 10 ms of airtime.
+        send(&mut a, 7500);
+        channel.advance(Duration::from_millis(10));
+        assert_abs_diff_eq!(a.channel_busy_ratio().as_percentage(), 10.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(b.channel_busy_ratio().as_percentage(), 10.0, epsilon = 1e-9);
+
+        // Out of the measurement window.
+        channel.advance(Duration::from_millis(100));
+        assert_abs_diff_eq!(b.channel_busy_ratio().as_percentage(), 0.0, epsilon = 1e-9);
+    }
+}
@@ -14,6 +14,8 @@ mod imp;
     unix
 ))]
 pub mod bpf;
+#[cfg(all(feature = "phy-ocb", any(target_os = "linux", target_os = "android")))]
+pub mod nl80211;
 #[cfg(all(
     feature = "phy-raw_socket",
     any(target_os = "linux", target_os = "android")
@@ -31,6 +33,8 @@ pub mod tuntap_interface;
     unix
 ))]
 pub use self::bpf::BpfDevice as RawSocketDesc;
+#[cfg(all(feature = "phy-ocb", any(target_os = "linux", target_os = "android")))]
+pub use self::nl80211::Nl80211Desc;
 #[cfg(all(
     feature = "phy-raw_socket",
     any(target_os = "linux", target_os = "android")
@@ -0,0 +1,497 @@
+use std::ffi::CString;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::{io, mem};
+
+// Generic netlink constants, from linux/netlink.h and linux/genetlink.h.
+const NLMSG_HDR_LEN: usize = 16;
+const GENL_HDR_LEN: usize = 4;
+const NLA_HDR_LEN: usize = 4;
+
+const NLMSG_ERROR: u16 = 0x2;
+const NLMSG_DONE: u16 = 0x3;
+
+const NLM_F_REQUEST: u16 = 0x01;
+const NLM_F_ACK: u16 = 0x04;
+const NLM_F_DUMP: u16 = 0x300;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+// nl80211 constants, from linux/nl80211.h.
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_SET_INTERFACE: u8 = 6;
+const NL80211_CMD_NEW_INTERFACE: u8 = 7;
+const NL80211_CMD_DEL_INTERFACE: u8 = 8;
+const NL80211_CMD_GET_SURVEY: u8 = 50;
+const NL80211_CMD_JOIN_OCB: u8 = 108;
+const NL80211_CMD_LEAVE_OCB: u8 = 109;
+
+const NL80211_ATTR_WIPHY: u16 = 1;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFNAME: u16 = 4;
+const NL80211_ATTR_IFTYPE: u16 = 5;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_SURVEY_INFO: u16 = 84;
+const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+const NL80211_ATTR_CENTER_FREQ1: u16 = 160;
+
+const NL80211_IFTYPE_MONITOR: u32 = 6;
+const NL80211_IFTYPE_OCB: u32 = 11;
+
+const NL80211_SURVEY_INFO_FREQUENCY: u16 = 1;
+const NL80211_SURVEY_INFO_TIME: u16 = 4;
+const NL80211_SURVEY_INFO_TIME_BUSY: u16 = 5;
+
+// Interface flags ioctls, from linux/sockios.h.
+const SIOCGIFFLAGS: libc::c_ulong = 0x8913;
+const SIOCSIFFLAGS: libc::c_ulong = 0x8914;
+
+/// Width of an OCB channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelWidth {
+    /// 5 MHz channel.
+    Mhz5 = 6,
+    /// 10 MHz channel, the ITS-G5 default.
+    Mhz10 = 7,
+    /// 20 MHz channel, without HT.
+    Mhz20 = 0,
+}
+
+/// Channel survey data, as reported by the wireless driver.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Survey {
+    /// Frequency of the surveyed channel, in MHz.
+    pub frequency: u32,
+    /// Amount of time the radio spent on the channel, in milliseconds.
+    pub time: u64,
+    /// Amount of time the channel was sensed busy, in milliseconds.
+    pub time_busy: u64,
+}
+
+/// Netlink attributes builder.
+#[derive(Debug, Default)]
+struct Attrs(Vec<u8>);
+
+impl Attrs {
+    fn put(&mut self, ty: u16, data: &[u8]) -> &mut Self {
+        let len = NLA_HDR_LEN + data.len();
+        self.0.extend_from_slice(&(len as u16).to_ne_bytes());
+        self.0.extend_from_slice(&ty.to_ne_bytes());
+        self.0.extend_from_slice(data);
+        self.0.resize(align(self.0.len()), 0);
+        self
+    }
+
+    fn put_u32(&mut self, ty: u16, val: u32) -> &mut Self {
+        self.put(ty, &val.to_ne_bytes())
+    }
+
+    fn put_str(&mut self, ty: u16, val: &str) -> &mut Self {
+        let mut data = val.as_bytes().to_vec();
+        data.push(0);
+        self.put(ty, &data)
+    }
+}
+
+/// Align `len` on a netlink 4 bytes boundary.
+const fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Iterate over the netlink attributes contained in `buf`.
+fn parse_attrs(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    core::iter::from_fn(move || {
+        if buf.len() < NLA_HDR_LEN {
+            return None;
+        }
+
+        let len = u16::from_ne_bytes([buf[0], buf[1]]) as usize;
+        // Mask out the nested and byte order flags.
+        let ty = u16::from_ne_bytes([buf[2], buf[3]]) & 0x3fff;
+        if len < NLA_HDR_LEN || len > buf.len() {
+            return None;
+        }
+
+        let data = &buf[NLA_HDR_LEN..len];
+        buf = &buf[align(len).min(buf.len())..];
+        Some((ty, data))
+    })
+}
+
+fn attr_u32(data: &[u8]) -> Option<u32> {
+    data.get(..4)
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn attr_u64(data: &[u8]) -> Option<u64> {
+    data.get(..8).map(|b| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(b);
+        u64::from_ne_bytes(bytes)
+    })
+}
+
+/// A generic netlink socket speaking the nl80211 protocol.
+#[derive(Debug)]
+pub struct Nl80211Desc {
+    lower: libc::c_int,
+    family_id: u16,
+    seq: u32,
+}
+
+impl AsRawFd for Nl80211Desc {
+    fn as_raw_fd(&self) -> RawFd {
+        self.lower
+    }
+}
+
+impl Nl80211Desc {
+    /// Open a generic netlink socket and resolve the nl80211 family.
+    pub fn new() -> io::Result<Nl80211Desc> {
+        let lower = unsafe {
+            let lower = libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_GENERIC,
+            );
+            if lower == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            lower
+        };
+
+        unsafe {
+            let mut sockaddr: libc::sockaddr_nl = mem::zeroed();
+            sockaddr.nl_family = libc::AF_NETLINK as u16;
+            let res = libc::bind(
+                lower,
+                &sockaddr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            );
+            if res == -1 {
+                let err = io::Error::last_os_error();
+                libc::close(lower);
+                return Err(err);
+            }
+        }
+
+        let mut desc = Nl80211Desc {
+            lower,
+            family_id: GENL_ID_CTRL,
+            seq: 0,
+        };
+
+        let mut attrs = Attrs::default();
+        attrs.put_str(CTRL_ATTR_FAMILY_NAME, "nl80211");
+        let replies = desc.request(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, 0, &attrs)?;
+
+        desc.family_id = replies
+            .iter()
+            .flat_map(|r| parse_attrs(r))
+            .find(|(ty, _)| *ty == CTRL_ATTR_FAMILY_ID)
+            .and_then(|(_, data)| data.get(..2).map(|b| u16::from_ne_bytes([b[0], b[1]])))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "nl80211 family not found"))?;
+
+        Ok(desc)
+    }
+
+    /// Send a generic netlink request and collect the attributes of every reply.
+    fn request(
+        &mut self,
+        family: u16,
+        cmd: u8,
+        flags: u16,
+        attrs: &Attrs,
+    ) -> io::Result<Vec<Vec<u8>>> {
+        self.seq = self.seq.wrapping_add(1);
+        let seq = self.seq;
+
+        let len = NLMSG_HDR_LEN + GENL_HDR_LEN + attrs.0.len();
+        let mut msg = Vec::with_capacity(len);
+        msg.extend_from_slice(&(len as u32).to_ne_bytes());
+        msg.extend_from_slice(&family.to_ne_bytes());
+        msg.extend_from_slice(&(NLM_F_REQUEST | NLM_F_ACK | flags).to_ne_bytes());
+        msg.extend_from_slice(&seq.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        msg.extend_from_slice(&[cmd, 1, 0, 0]);
+        msg.extend_from_slice(&attrs.0);
+
+        unsafe {
+            let res = libc::send(
+                self.lower,
+                msg.as_ptr() as *const libc::c_void,
+                msg.len(),
+                0,
+            );
+            if res == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        let mut replies = Vec::new();
+        let mut buffer = vec![0u8; 16384];
+
+        loop {
+            let size = unsafe {
+                let len = libc::recv(
+                    self.lower,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    0,
+                );
+                if len == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                len as usize
+            };
+
+            let mut msgs = &buffer[..size];
+            while msgs.len() >= NLMSG_HDR_LEN {
+                let msg_len = u32::from_ne_bytes([msgs[0], msgs[1], msgs[2], msgs[3]]) as usize;
+                let msg_type = u16::from_ne_bytes([msgs[4], msgs[5]]);
+                let msg_seq = u32::from_ne_bytes([msgs[8], msgs[9], msgs[10], msgs[11]]);
+                if msg_len < NLMSG_HDR_LEN || msg_len > msgs.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "malformed netlink message",
+                    ));
+                }
+
+                let payload = &msgs[NLMSG_HDR_LEN..msg_len];
+                msgs = &msgs[align(msg_len).min(msgs.len())..];
+
+                if msg_seq != seq {
+                    continue;
+                }
+
+                match msg_type {
+                    NLMSG_ERROR => {
+                        let errno = payload
+                            .get(..4)
+                            .map(|b| i32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                            .unwrap_or(0);
+                        if errno != 0 {
+                            return Err(io::Error::from_raw_os_error(-errno));
+                        }
+                        // Acknowledgment, ends the request.
+                        return Ok(replies);
+                    }
+                    NLMSG_DONE => return Ok(replies),
+                    t if t == family && payload.len() >= GENL_HDR_LEN => {
+                        replies.push(payload[GENL_HDR_LEN..].to_vec());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Get the index of the wiphy the `ifindex` interface belongs to.
+    pub fn wiphy(&mut self, ifindex: u32) -> io::Result<u32> {
+        let mut attrs = Attrs::default();
+        attrs.put_u32(NL80211_ATTR_IFINDEX, ifindex);
+        let replies = self.request(self.family_id, NL80211_CMD_GET_INTERFACE, 0, &attrs)?;
+
+        replies
+            .iter()
+            .flat_map(|r| parse_attrs(r))
+            .find(|(ty, _)| *ty == NL80211_ATTR_WIPHY)
+            .and_then(|(_, data)| attr_u32(data))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no wiphy for interface"))
+    }
+
+    /// Switch the `ifindex` interface to OCB mode. The interface must be down.
+    pub fn set_ocb_mode(&mut self, ifindex: u32) -> io::Result<()> {
+        let mut attrs = Attrs::default();
+        attrs
+            .put_u32(NL80211_ATTR_IFINDEX, ifindex)
+            .put_u32(NL80211_ATTR_IFTYPE, NL80211_IFTYPE_OCB);
+        self.request(self.family_id, NL80211_CMD_SET_INTERFACE, 0, &attrs)
+            .map(|_| ())
+    }
+
+    /// Join the OCB network on the `frequency` channel, in MHz.
+    pub fn join_ocb(
+        &mut self,
+        ifindex: u32,
+        frequency: u32,
+        width: ChannelWidth,
+    ) -> io::Result<()> {
+        let mut attrs = Attrs::default();
+        attrs
+            .put_u32(NL80211_ATTR_IFINDEX, ifindex)
+            .put_u32(NL80211_ATTR_WIPHY_FREQ, frequency)
+            .put_u32(NL80211_ATTR_CHANNEL_WIDTH, width as u32)
+            .put_u32(NL80211_ATTR_CENTER_FREQ1, frequency);
+        self.request(self.family_id, NL80211_CMD_JOIN_OCB, 0, &attrs)
+            .map(|_| ())
+    }
+
+    /// Leave the OCB network.
+    pub fn leave_ocb(&mut self, ifindex: u32) -> io::Result<()> {
+        let mut attrs = Attrs::default();
+        attrs.put_u32(NL80211_ATTR_IFINDEX, ifindex);
+        self.request(self.family_id, NL80211_CMD_LEAVE_OCB, 0, &attrs)
+            .map(|_| ())
+    }
+
+    /// Create a monitor interface called `name` on the `wiphy` radio.
+    /// Succeeds if the interface already exists, returning whether it has been created.
+    pub fn new_monitor(&mut self, wiphy: u32, name: &str) -> io::Result<bool> {
+        let mut attrs = Attrs::default();
+        attrs
+            .put_u32(NL80211_ATTR_WIPHY, wiphy)
+            .put_str(NL80211_ATTR_IFNAME, name)
+            .put_u32(NL80211_ATTR_IFTYPE, NL80211_IFTYPE_MONITOR);
+        match self.request(self.family_id, NL80211_CMD_NEW_INTERFACE, 0, &attrs) {
+            Ok(_) => Ok(true),
+            Err(e) if e.raw_os_error() == Some(libc::EEXIST) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete the `ifindex` interface.
+    pub fn del_interface(&mut self, ifindex: u32) -> io::Result<()> {
+        let mut attrs = Attrs::default();
+        attrs.put_u32(NL80211_ATTR_IFINDEX, ifindex);
+        self.request(self.family_id, NL80211_CMD_DEL_INTERFACE, 0, &attrs)
+            .map(|_| ())
+    }
+
+    /// Get the survey data of the channel the `ifindex` interface is tuned on.
+    pub fn survey(&mut self, ifindex: u32, frequency: u32) -> io::Result<Option<Survey>> {
+        let mut attrs = Attrs::default();
+        attrs.put_u32(NL80211_ATTR_IFINDEX, ifindex);
+        let replies = self.request(self.family_id, NL80211_CMD_GET_SURVEY, NLM_F_DUMP, &attrs)?;
+
+        let survey = replies
+            .iter()
+            .flat_map(|r| parse_attrs(r))
+            .filter(|(ty, _)| *ty == NL80211_ATTR_SURVEY_INFO)
+            .map(|(_, info)| {
+                let mut survey = Survey::default();
+                for (ty, data) in parse_attrs(info) {
+                    match ty {
+                        NL80211_SURVEY_INFO_FREQUENCY => {
+                            survey.frequency = attr_u32(data).unwrap_or_default()
+                        }
+                        NL80211_SURVEY_INFO_TIME => {
+                            survey.time = attr_u64(data).unwrap_or_default()
+                        }
+                        NL80211_SURVEY_INFO_TIME_BUSY => {
+                            survey.time_busy = attr_u64(data).unwrap_or_default()
+                        }
+                        _ => {}
+                    }
+                }
+                survey
+            })
+            .find(|s| s.frequency == frequency);
+
+        Ok(survey)
+    }
+}
+
+impl Drop for Nl80211Desc {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.lower);
+        }
+    }
+}
+
+/// Interface request for the flags ioctls.
+#[repr(C)]
+#[derive(Debug)]
+struct IfreqFlags {
+    ifr_name: [libc::c_char; libc::IF_NAMESIZE],
+    ifr_flags: libc::c_short,
+    _pad: [u8; 22],
+}
+
+/// Return the index of the interface called `name`.
+pub fn if_index(name: &str) -> io::Result<u32> {
+    let c_name = CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let index = unsafe { libc::if_nametoindex(c_name.as_ptr()) };
+    if index == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(index)
+}
+
+/// Bring the interface called `name` up or down.
+pub fn set_if_up(name: &str, up: bool) -> io::Result<()> {
+    let mut ifreq = IfreqFlags {
+        ifr_name: [0; libc::IF_NAMESIZE],
+        ifr_flags: 0,
+        _pad: [0; 22],
+    };
+    for (i, byte) in name
+        .as_bytes()
+        .iter()
+        .take(libc::IF_NAMESIZE - 1)
+        .enumerate()
+    {
+        ifreq.ifr_name[i] = *byte as libc::c_char
+    }
+
+    let lower = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if lower == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let res = if_flags_ioctl(lower, &mut ifreq, SIOCGIFFLAGS).and_then(|_| {
+        if up {
+            ifreq.ifr_flags |= libc::IFF_UP as libc::c_short;
+        } else {
+            ifreq.ifr_flags &= !(libc::IFF_UP as libc::c_short);
+        }
+        if_flags_ioctl(lower, &mut ifreq, SIOCSIFFLAGS)
+    });
+
+    unsafe {
+        libc::close(lower);
+    }
+
+    res
+}
+
+fn if_flags_ioctl(
+    lower: libc::c_int,
+    ifreq: &mut IfreqFlags,
+    cmd: libc::c_ulong,
+) -> io::Result<()> {
+    unsafe {
+        let res = libc::ioctl(lower, cmd as _, ifreq as *mut IfreqFlags);
+        if res == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_attrs_roundtrip() {
+        let mut attrs = Attrs::default();
+        attrs
+            .put_u32(NL80211_ATTR_IFINDEX, 3)
+            .put_str(NL80211_ATTR_IFNAME, "mon0");
+
+        // "mon0\0" is padded to 8 bytes.
+        assert_eq!(attrs.0.len(), 8 + 12);
+
+        let parsed: Vec<_> = parse_attrs(&attrs.0).collect();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].0, NL80211_ATTR_IFINDEX);
+        assert_eq!(attr_u32(parsed[0].1), Some(3));
+        assert_eq!(parsed[1].0, NL80211_ATTR_IFNAME);
+        assert_eq!(parsed[1].1, b"mon0\0");
+    }
+}
@@ -21,6 +21,7 @@ use veloce_asn1::{
                 self, HashedData, Ieee1609Dot2Content, RecipientInfo, SymmetricCiphertext,
             },
         },
+        etsi_103759_v211::etsi_ts103759_core::EtsiTs103759Data,
     },
     prelude::rasn::{
         self,
@@ -514,3 +515,17 @@ impl Asn1WrapperTrait for Asn1Wrapper<ToBeSignedCrl> {
         Ok(())
     }
 }
+
+impl Asn1WrapperTrait for Asn1Wrapper<EtsiTs103759Data> {
+    type Wrapped = EtsiTs103759Data;
+
+    /// Check `data` is valid according to EtsiTs103759Data Asn.1 definition.
+    #[inline]
+    fn verify_constraints(data: &Self::Wrapped) -> Asn1WrapperResult<()> {
+        if data.version.0 != 2 {
+            return Err(Asn1WrapperError::UnsupportedProtocolVersion);
+        }
+
+        Ok(())
+    }
+}
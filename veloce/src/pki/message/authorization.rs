@@ -372,8 +372,14 @@ impl AuthorizationRequest {
 
     /// Set the public encryption key of the [AuthorizationRequest].
     pub fn set_public_encryption_key(&mut self, key: EciesKey) -> AuthorizationRequestResult<()> {
+        // SM2 encryption keys are used with SM4-CCM, as defined in YD/T 3957.
+        let supported_symm_alg = match key {
+            EciesKey::Sm2(_) => SymmAlgorithm::sm4Ccm,
+            _ => SymmAlgorithm::aes128Ccm,
+        };
+
         self.inner.public_keys.encryption_key = Some(PublicEncryptionKey {
-            supported_symm_alg: SymmAlgorithm::aes128Ccm,
+            supported_symm_alg,
             public_key: key
                 .try_into()
                 .map_err(AuthorizationRequestError::EncryptionKey)?,
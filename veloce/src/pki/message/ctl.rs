@@ -1,3 +1,7 @@
+//! Certificate Trust Lists (CTL) of ETSI TS 102 941. Trust lists may also advertise the
+//! Misbehavior Authority, with an `ma` entry added to the standard ones as an extension,
+//! see [super::ma].
+
 use core::fmt;
 
 use veloce_asn1::{
@@ -6,8 +10,9 @@ use veloce_asn1::{
         etsi_ts102941_trust_lists::{
             AaEntry as EtsiAaEntry, CtlCommand as EtsiCtlCommand, CtlDelete as EtsiCtlDelete,
             CtlEntry as EtsiCtlEntry, CtlFormat as EtsiCtlFormat, DcDelete as EtsiDcDelete,
-            DcEntry as EtsiDcEntry, EaEntry as EtsiEaEntry, RootCaEntry as EtsiRootCaEntry,
-            TlmEntry as EtsiTlmEntry, ToBeSignedRcaCtl, ToBeSignedTlmCtl, Url as EtsiUrl,
+            DcEntry as EtsiDcEntry, EaEntry as EtsiEaEntry, MaEntry as EtsiMaEntry,
+            RootCaEntry as EtsiRootCaEntry, TlmEntry as EtsiTlmEntry, ToBeSignedRcaCtl,
+            ToBeSignedTlmCtl, Url as EtsiUrl,
         },
         ieee1609_dot2_base_types::{Time32, Uint32},
     },
@@ -167,6 +172,41 @@ impl TryInto<EtsiAaEntry> for AuthorizationAuthorityEntry {
     }
 }
 
+/// CTL Misbehavior Authority entry. Not part of ETSI TS 102 941, see [super::ma].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisbehaviorAuthorityEntry {
+    /// Certificate of the Misbehavior Authority server.
+    pub certificate: EtsiCertificate,
+    /// URL of the Misbehavior Authority server.
+    pub access_point: String,
+}
+
+impl TryFrom<EtsiMaEntry> for MisbehaviorAuthorityEntry {
+    type Error = CtlURLError;
+
+    fn try_from(value: EtsiMaEntry) -> Result<Self, Self::Error> {
+        let res = Self {
+            certificate: value.ma_certificate.0,
+            access_point: value.access_point.0.into(),
+        };
+
+        Ok(res)
+    }
+}
+
+impl TryInto<EtsiMaEntry> for MisbehaviorAuthorityEntry {
+    type Error = CtlURLError;
+
+    fn try_into(self) -> Result<EtsiMaEntry, Self::Error> {
+        let ia5_ap = EtsiUrl(
+            Ia5String::from_iso646_bytes(self.access_point.as_bytes()).map_err(CtlURLError)?,
+        );
+
+        let ma_entry = EtsiMaEntry::new(EtsiTs103097Certificate(self.certificate), ia5_ap);
+        Ok(ma_entry)
+    }
+}
+
 /// CTL Distribution Center entry.
 /// The Distribution Center is a server that provides the CTL and the CRL
 /// of a PKI.
@@ -281,12 +321,19 @@ pub struct AddCommands {
     pub dc: Vec<DistributionCenterEntry>,
     /// TLM certificate entries.
     pub tlm: Vec<TrustListManagerEntry>,
+    /// Misbehavior Authority entries.
+    pub ma: Vec<MisbehaviorAuthorityEntry>,
 }
 
 impl AddCommands {
     /// Returns the number of entries in the [AddCommands].
     pub fn len(&self) -> usize {
-        self.root.len() + self.ea.len() + self.aa.len() + self.dc.len() + self.tlm.len()
+        self.root.len()
+            + self.ea.len()
+            + self.aa.len()
+            + self.dc.len()
+            + self.tlm.len()
+            + self.ma.len()
     }
 }
 
@@ -414,6 +461,11 @@ impl TryFrom<&SequenceOf<EtsiCtlCommand>> for CtlCommands {
                             .try_into()
                             .map_err(|err| Self::Error::Add(AddCommandError::URL(err)))?,
                     ),
+                    EtsiCtlEntry::ma(e) => add.ma.push(
+                        e.to_owned()
+                            .try_into()
+                            .map_err(|err| Self::Error::Add(AddCommandError::URL(err)))?,
+                    ),
                     _ => return Err(CtlCommandsError::Add(AddCommandError::UnsupportedCommand)),
                 },
                 EtsiCtlCommand::delete(c) => {
@@ -465,6 +517,13 @@ impl TryInto<SequenceOf<EtsiCtlCommand>> for CtlCommands {
             )));
         }
 
+        for a in self.add.ma {
+            res.push(EtsiCtlCommand::add(EtsiCtlEntry::ma(
+                a.try_into()
+                    .map_err(|e| Self::Error::Add(AddCommandError::URL(e)))?,
+            )));
+        }
+
         for d in self.delete {
             res.push(EtsiCtlCommand::delete(
                 d.try_into().map_err(Self::Error::Delete)?,
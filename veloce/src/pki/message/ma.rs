@@ -0,0 +1,206 @@
+//! Misbehavior Authority. ETSI TS 102 941 trust lists do not define any Misbehavior Authority
+//! entry: the MA certificate and its access point are advertised by the CTL in an `ma` entry
+//! added to the standard ones as an extension, see [super::ctl::MisbehaviorAuthorityEntry],
+//! or provisioned out of band, ie: by the station configuration. The MA certificate is
+//! issued by a Root CA, and is only used once checked against a root of the trust store.
+
+use core::fmt;
+
+use crate::{
+    pki::message::ctl::MisbehaviorAuthorityEntry,
+    security::{
+        backend::BackendTrait,
+        certificate::{
+            CertificateError, CertificateTrait, CertificateWithHashContainer, ExplicitCertificate,
+            MisbehaviorAuthorityCertificate,
+        },
+        trust_store::Store as TrustStore,
+    },
+    time::Instant,
+};
+
+pub type MisbehaviorAuthorityResult<T> = core::result::Result<T, MisbehaviorAuthorityError>;
+
+/// Misbehavior Authority error.
+#[derive(Debug)]
+pub enum MisbehaviorAuthorityError {
+    /// MA certificate error, ie: it is malformed, expired or its issuer is not a
+    /// root of the trust store.
+    Certificate(CertificateError),
+    /// MA certificate is revoked.
+    Revoked,
+    /// False MA certificate signature.
+    FalseSignature,
+}
+
+impl fmt::Display for MisbehaviorAuthorityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MisbehaviorAuthorityError::Certificate(e) => write!(f, "certificate: {}", e),
+            MisbehaviorAuthorityError::Revoked => write!(f, "revoked certificate"),
+            MisbehaviorAuthorityError::FalseSignature => write!(f, "false certificate signature"),
+        }
+    }
+}
+
+/// Misbehavior Authority, whose certificate has been checked against a root of the trust store.
+#[derive(Debug, Clone)]
+pub struct MisbehaviorAuthority {
+    /// Certificate of the Misbehavior Authority server.
+    certificate: CertificateWithHashContainer<MisbehaviorAuthorityCertificate>,
+    /// URL of the Misbehavior Authority server, where the ITS Stations
+    /// submit their misbehavior reports.
+    access_point: String,
+}
+
+impl MisbehaviorAuthority {
+    /// Constructs a [MisbehaviorAuthority] from the MA `certificate` and its `access_point` URL.
+    /// The certificate should be issued by a root of the trust `store`, and be valid and not
+    /// revoked at `timestamp`.
+    pub fn new<B>(
+        certificate: MisbehaviorAuthorityCertificate,
+        access_point: String,
+        store: &TrustStore,
+        timestamp: Instant,
+        backend: &B,
+    ) -> MisbehaviorAuthorityResult<Self>
+    where
+        B: BackendTrait + ?Sized,
+    {
+        let certificate = certificate
+            .into_with_hash_container(backend)
+            .map_err(MisbehaviorAuthorityError::Certificate)?;
+
+        if store.is_revoked(certificate.hashed_id8()) {
+            return Err(MisbehaviorAuthorityError::Revoked);
+        }
+
+        let valid = certificate
+            .certificate()
+            .check(timestamp, backend, |sh| store.lookup_root(sh))
+            .map_err(MisbehaviorAuthorityError::Certificate)?;
+
+        if !valid {
+            return Err(MisbehaviorAuthorityError::FalseSignature);
+        }
+
+        Ok(Self {
+            certificate,
+            access_point,
+        })
+    }
+
+    /// Constructs a [MisbehaviorAuthority] from the COER encoded MA `certificate` and its
+    /// `access_point` URL. See [MisbehaviorAuthority::new].
+    pub fn from_bytes<B>(
+        certificate: &[u8],
+        access_point: String,
+        store: &TrustStore,
+        timestamp: Instant,
+        backend: &B,
+    ) -> MisbehaviorAuthorityResult<Self>
+    where
+        B: BackendTrait + ?Sized,
+    {
+        let certificate = MisbehaviorAuthorityCertificate::from_bytes(certificate, backend)
+            .map_err(MisbehaviorAuthorityError::Certificate)?;
+
+        Self::new(certificate, access_point, store, timestamp, backend)
+    }
+
+    /// Constructs a [MisbehaviorAuthority] from the MA `entry` of a CTL.
+    /// See [MisbehaviorAuthority::new].
+    pub fn from_ctl_entry<B>(
+        entry: MisbehaviorAuthorityEntry,
+        store: &TrustStore,
+        timestamp: Instant,
+        backend: &B,
+    ) -> MisbehaviorAuthorityResult<Self>
+    where
+        B: BackendTrait + ?Sized,
+    {
+        let certificate =
+            MisbehaviorAuthorityCertificate::from_etsi_cert(entry.certificate, backend)
+                .map_err(MisbehaviorAuthorityError::Certificate)?;
+
+        Self::new(certificate, entry.access_point, store, timestamp, backend)
+    }
+
+    /// Returns the certificate of the Misbehavior Authority.
+    pub fn certificate(&self) -> &CertificateWithHashContainer<MisbehaviorAuthorityCertificate> {
+        &self.certificate
+    }
+
+    /// Returns the URL of the Misbehavior Authority server.
+    pub fn access_point(&self) -> &str {
+        &self.access_point
+    }
+}
+
+#[test]
+fn test_misbehavior_authority_from_ctl() {
+    use veloce_asn1::{
+        defs::etsi_102941_v221::etsi_ts102941_trust_lists::CtlCommand,
+        prelude::rasn::{self, types::SequenceOf},
+    };
+
+    use crate::{
+        pki::message::ctl::{AddCommands, CtlCommands, RootCaEntry},
+        security::{certificate::RootCertificate, tests},
+        time::Duration,
+    };
+
+    let mut service = tests::setup_security_service();
+    let (_, backend) = tests::setup_storage_and_crypto(tests::get_test_storage_path());
+    let assets = tests::get_test_storage_path().join("assets");
+    let raw_ma_cert = std::fs::read(assets.join("MA.cert")).unwrap();
+    let raw_ma_root_cert = std::fs::read(assets.join("MA_RCA.cert")).unwrap();
+
+    let ma_cert = MisbehaviorAuthorityCertificate::from_bytes(&raw_ma_cert, &backend).unwrap();
+    let ma_root = RootCertificate::from_bytes(&raw_ma_root_cert, &backend).unwrap();
+    let timestamp =
+        ma_root.validity_period().start().as_unix_instant() + Duration::from_secs(86400);
+
+    // CTL advertising the MA along with its root, the MA entry survives the COER encoding.
+    let commands = CtlCommands {
+        add: AddCommands {
+            root: vec![RootCaEntry {
+                certificate: ma_root.inner().clone(),
+                successor_to: None,
+            }],
+            ma: vec![MisbehaviorAuthorityEntry {
+                certificate: ma_cert.inner().clone(),
+                access_point: "http://ma.veloce.test".to_string(),
+            }],
+            ..Default::default()
+        },
+        delete: Vec::new(),
+    };
+    let raw_commands: SequenceOf<CtlCommand> = commands.clone().try_into().unwrap();
+    let encoded = rasn::coer::encode(&raw_commands).unwrap();
+    let decoded = rasn::coer::decode::<SequenceOf<CtlCommand>>(&encoded).unwrap();
+    let mut decoded = CtlCommands::try_from(&decoded).unwrap();
+    assert_eq!(decoded, commands);
+    assert_eq!(decoded.add.len(), 2);
+
+    let entry = decoded.add.ma.remove(0);
+
+    // MA root is not trusted yet.
+    assert!(matches!(
+        MisbehaviorAuthority::from_ctl_entry(entry.clone(), service.store(), timestamp, &backend),
+        Err(MisbehaviorAuthorityError::Certificate(
+            CertificateError::UnknownSigner(_)
+        ))
+    ));
+
+    let ma_root = ma_root.into_with_hash_container(&backend).unwrap();
+    service
+        .store_mut()
+        .set_trusted_roots(vec![ma_root.hashed_id8()]);
+    assert!(service.store_mut().add_remote_root(ma_root));
+
+    let ma =
+        MisbehaviorAuthority::from_ctl_entry(entry, service.store(), timestamp, &backend).unwrap();
+    assert_eq!(ma.access_point(), "http://ma.veloce.test");
+    assert_eq!(ma.certificate().certificate().raw_bytes(), raw_ma_cert);
+}
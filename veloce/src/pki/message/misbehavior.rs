@@ -0,0 +1,437 @@
+use core::fmt;
+
+use veloce_asn1::{
+    defs::{
+        etsi_103097_v211::{
+            ieee1609_dot2::Certificate as EtsiCertificate,
+            ieee1609_dot2_base_types::{Opaque, Psid, ThreeDLocation, Time64, Uint64, Uint8},
+        },
+        etsi_103759_v211::etsi_ts103759_core::{
+            AidSpecificReport, AsrCam, AsrDenm, EtsiTs103759Data, MbSingleObservation,
+            ObservationsByTarget, V2xPduStream,
+        },
+    },
+    prelude::rasn::types::OctetString,
+};
+
+use crate::{
+    pki::{
+        asn1_wrapper::{Asn1Wrapper, Asn1WrapperError},
+        encrypted_data::EncryptedDataError,
+        signed_data::{SignedData, SignedDataError, SignedDataPayloadType},
+        SignerIdentifier,
+    },
+    security::{
+        certificate::CertificateError,
+        misbehavior::{MisbehaviorCheck, MisbehaviorDetection},
+        permission::AID,
+        HashedId8,
+    },
+    time::{Instant, TAI2004},
+};
+
+use super::{EncryptionError, SignerError};
+
+/// Marker struct for the Misbehavior Report signed wrapper type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MR;
+
+/// Signed Misbehavior Report type.
+pub type SignedMisbehaviorReport = SignedData<MR>;
+
+/// Version of the ETSI TS 103 759 report structure.
+const MBR_VERSION: u8 = 2;
+
+/// Type of the evidence PDU streams, ie: ETSI TS 103 097 secured messages.
+const PDU_TYPE_ETSI_SECURED: u8 = 1;
+
+pub type MisbehaviorReportResult<T> = core::result::Result<T, MisbehaviorReportError>;
+
+/// Misbehavior Report errors.
+#[derive(Debug)]
+pub enum MisbehaviorReportError {
+    /// Asn.1 wrapper error.
+    Asn1Wrapper(Asn1WrapperError),
+    /// Reported messages AID is not supported.
+    UnsupportedAid(AID),
+    /// Report does not contain any detection.
+    NoDetection,
+    /// Report does not contain any evidence message.
+    NoEvidence,
+    /// Report contains more evidence messages than allowed.
+    TooManyEvidences,
+    /// Report contains an unknown observation.
+    UnknownObservation,
+    /// Detection has no observation defined for the reported messages AID.
+    UnsupportedDetection(MisbehaviorCheck),
+    /// AT certificate error.
+    AtCertificate(CertificateError),
+    /// Signed wrapper.
+    Outer(SignedDataError),
+    /// Something went wrong while signing the signed wrapper.
+    OuterSigner(SignerError),
+    /// Something went wrong while encrypting the signed wrapper.
+    Encryption(EncryptionError),
+    /// Encrypted wrapper.
+    Encrypted(EncryptedDataError),
+}
+
+impl fmt::Display for MisbehaviorReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MisbehaviorReportError::Asn1Wrapper(e) => write!(f, "asn1 wrapper: {}", e),
+            MisbehaviorReportError::UnsupportedAid(aid) => write!(f, "unsupported AID: {}", aid),
+            MisbehaviorReportError::NoDetection => write!(f, "no detection"),
+            MisbehaviorReportError::NoEvidence => write!(f, "no evidence"),
+            MisbehaviorReportError::TooManyEvidences => write!(f, "too many evidences"),
+            MisbehaviorReportError::UnknownObservation => write!(f, "unknown observation"),
+            MisbehaviorReportError::UnsupportedDetection(c) => {
+                write!(f, "unsupported detection: {}", c)
+            }
+            MisbehaviorReportError::AtCertificate(e) => write!(f, "AT certificate: {}", e),
+            MisbehaviorReportError::Outer(e) => write!(f, "outer: {}", e),
+            MisbehaviorReportError::OuterSigner(e) => write!(f, "outer signer: {}", e),
+            MisbehaviorReportError::Encryption(e) => write!(f, "encryption: {}", e),
+            MisbehaviorReportError::Encrypted(e) => write!(f, "encrypted: {}", e),
+        }
+    }
+}
+
+/// Evidence attached to a [MisbehaviorReport].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisbehaviorEvidence {
+    /// Received secured messages of the reported station, COER encoded, in reception order.
+    /// The last one is the subject of the report.
+    pub messages: Vec<Vec<u8>>,
+    /// Certificate of the reported station, if the messages are signed with a digest.
+    pub certificate: Option<EtsiCertificate>,
+}
+
+/// Misbehavior Report, as ETSI TS 103 759 V2.1.1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisbehaviorReport {
+    /// Inner misbehavior report structure.
+    inner: Asn1Wrapper<EtsiTs103759Data>,
+}
+
+impl MisbehaviorReport {
+    /// Constructs a [MisbehaviorReport] of messages identified by `aid`, for the `detections`
+    /// raised on the `evidence`, observed at `location`.
+    pub fn new(
+        aid: AID,
+        detections: &[MisbehaviorCheck],
+        evidence: MisbehaviorEvidence,
+        location: ThreeDLocation,
+        timestamp: Instant,
+    ) -> MisbehaviorReportResult<Self> {
+        if detections.is_empty() {
+            return Err(MisbehaviorReportError::NoDetection);
+        }
+
+        let num_messages = evidence.messages.len();
+        if num_messages == 0 {
+            return Err(MisbehaviorReportError::NoEvidence);
+        } else if num_messages > u8::MAX.into() {
+            return Err(MisbehaviorReportError::TooManyEvidences);
+        }
+
+        let table = observations_table(aid)?;
+
+        // Group the observations by target, keeping the detection order.
+        let mut observations: Vec<ObservationsByTarget> = Vec::new();
+        for check in detections {
+            let (tgt_id, obs_id) = table
+                .iter()
+                .find(|(c, _)| c == check)
+                .map(|(_, ids)| *ids)
+                .ok_or(MisbehaviorReportError::UnsupportedDetection(*check))?;
+            // Observations in this report carry no parameters, ie: their content is NULL.
+            let obs = MbSingleObservation::new(Uint8(obs_id), OctetString::new());
+
+            match observations.iter_mut().find(|o| o.tgt_id.0 == tgt_id) {
+                Some(target) => target.observations.push(obs),
+                None => observations.push(ObservationsByTarget::new(Uint8(tgt_id), vec![obs])),
+            }
+        }
+
+        let stream = V2xPduStream::new(
+            Uint8(PDU_TYPE_ETSI_SECURED),
+            evidence
+                .messages
+                .into_iter()
+                .map(|m| Opaque(m.into()))
+                .collect(),
+            evidence.certificate,
+            Uint8((num_messages - 1) as u8),
+        );
+
+        let asr = AsrCam::new(observations, vec![stream], vec![]);
+        let content = match aid {
+            AID::CA => Asn1Wrapper::encode_coer(&asr),
+            _ => Asn1Wrapper::encode_coer(&AsrDenm(asr)),
+        }
+        .map_err(MisbehaviorReportError::Asn1Wrapper)?;
+
+        let generation_time = TAI2004::from_unix_instant(timestamp);
+        let inner = EtsiTs103759Data::new(
+            Uint8(MBR_VERSION),
+            Time64(Uint64(generation_time.total_micros() as u64)),
+            location,
+            AidSpecificReport::new(Psid(aid.into()), Some(content.into())),
+        );
+
+        Ok(Self {
+            inner: Asn1Wrapper::from_raw_unverified(inner),
+        })
+    }
+
+    /// Constructs a [MisbehaviorReport] of a misbehavior `detection` recorded by the
+    /// security service.
+    pub fn from_detection(detection: &MisbehaviorDetection) -> MisbehaviorReportResult<Self> {
+        let evidence = MisbehaviorEvidence {
+            messages: vec![detection.message.clone()],
+            certificate: detection.certificate.clone(),
+        };
+
+        Self::new(
+            detection.aid,
+            &detection.checks,
+            evidence,
+            detection.location.clone(),
+            detection.timestamp,
+        )
+    }
+
+    /// Constructs a [MisbehaviorReport] from bytes.
+    pub fn from_bytes(bytes: &[u8]) -> MisbehaviorReportResult<Self> {
+        let inner = Asn1Wrapper::from_bytes(bytes).map_err(MisbehaviorReportError::Asn1Wrapper)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Get the [MisbehaviorReport] as bytes.
+    pub fn as_bytes(&self) -> MisbehaviorReportResult<Vec<u8>> {
+        self.inner
+            .as_bytes()
+            .map_err(MisbehaviorReportError::Asn1Wrapper)
+    }
+
+    /// Returns the AID of the reported messages.
+    pub fn aid(&self) -> MisbehaviorReportResult<AID> {
+        let aid = AID::try_from(&self.inner.inner().report.aid.0)
+            .map_err(|_| MisbehaviorReportError::UnsupportedAid(AID::Unknown(0)))?;
+
+        match aid {
+            AID::CA | AID::DEN => Ok(aid),
+            _ => Err(MisbehaviorReportError::UnsupportedAid(aid)),
+        }
+    }
+
+    /// Returns the generation time of the report.
+    pub fn generation_time(&self) -> TAI2004 {
+        TAI2004::from_micros_const(self.inner.inner().generation_time.0 .0 as i64)
+    }
+
+    /// Returns the detections contained in the report.
+    /// A report without AID specific content contains no detection.
+    pub fn detections(&self) -> MisbehaviorReportResult<Vec<MisbehaviorCheck>> {
+        let Some(content) = &self.inner.inner().report.content else {
+            return Ok(Vec::new());
+        };
+        let aid = self.aid()?;
+        let table = observations_table(aid)?;
+        let asr = match aid {
+            AID::DEN => Asn1Wrapper::<AsrDenm>::decode_coer(content).map(|a| a.0),
+            _ => Asn1Wrapper::<AsrCam>::decode_coer(content),
+        }
+        .map_err(MisbehaviorReportError::Asn1Wrapper)?;
+
+        asr.observations
+            .iter()
+            .flat_map(|target| {
+                target
+                    .observations
+                    .iter()
+                    .map(move |obs| (target.tgt_id.0, obs.obs_id.0))
+            })
+            .map(|(tgt_id, obs_id)| {
+                table
+                    .iter()
+                    .find(|(_, ids)| *ids == (tgt_id, obs_id))
+                    .map(|(c, _)| *c)
+                    .ok_or(MisbehaviorReportError::UnknownObservation)
+            })
+            .collect()
+    }
+
+    /// Generate the signed wrapper of the [MisbehaviorReport], signed by the reporter
+    /// Authorization Ticket identified by `signer`.
+    /// The returned [SignedMisbehaviorReport] is ready to be signed. It does not contain any signature.
+    pub fn emit_signed_wrapper(
+        report: &MisbehaviorReport,
+        signer: HashedId8,
+        timestamp: Instant,
+    ) -> MisbehaviorReportResult<SignedMisbehaviorReport> {
+        let report_bytes = report.as_bytes()?;
+
+        let mut wrapper = SignedMisbehaviorReport::new(SignedDataPayloadType::Data(report_bytes))
+            .map_err(MisbehaviorReportError::Outer)?;
+
+        wrapper
+            .set_application_id(AID::MR)
+            .map_err(MisbehaviorReportError::Outer)?;
+
+        wrapper
+            .set_generation_time(TAI2004::from_unix_instant(timestamp))
+            .map_err(MisbehaviorReportError::Outer)?;
+
+        wrapper
+            .set_signer_identifier(SignerIdentifier::Digest(signer))
+            .map_err(MisbehaviorReportError::Outer)?;
+
+        Ok(wrapper)
+    }
+}
+
+/// Target and observation identifiers of the reports, for each [MisbehaviorCheck].
+type ObservationsTable = [(MisbehaviorCheck, (u8, u8))];
+
+/// CAM report targets of ETSI TS 103 759, ie: the `c-CamTgt-*` identifiers.
+const CAM_TGT_BEACON: u8 = 1;
+const CAM_TGT_SPEED: u8 = 3;
+const CAM_TGT_HEADING: u8 = 5;
+const CAM_TGT_POSITION: u8 = 7;
+
+/// DENM report targets of ETSI TS 103 759, ie: the `c-DenmTgt-*` identifiers, the reference
+/// and detection times being the ones of the DENM management container.
+const DENM_TGT_REFERENCE: u8 = 1;
+const DENM_TGT_DETECTION: u8 = 2;
+const DENM_TGT_EVENT_POSITION: u8 = 3;
+const DENM_TGT_POSITION: u8 = 4;
+
+/// Target and observation identifiers of the CAM reports, for each [MisbehaviorCheck] raised
+/// on a CAM. The observation identifiers are numbered per target.
+const CAM_OBSERVATIONS: [(MisbehaviorCheck, (u8, u8)); 6] = [
+    // Message too old.
+    (MisbehaviorCheck::Freshness, (CAM_TGT_BEACON, 1)),
+    // Speed inconsistent with the position changes.
+    (MisbehaviorCheck::SpeedConsistency, (CAM_TGT_SPEED, 1)),
+    // Heading inconsistent with the position changes.
+    (MisbehaviorCheck::HeadingConsistency, (CAM_TGT_HEADING, 1)),
+    // Position change too large.
+    (MisbehaviorCheck::PositionJump, (CAM_TGT_POSITION, 1)),
+    // Position out of the communication range.
+    (MisbehaviorCheck::CommunicationRange, (CAM_TGT_POSITION, 2)),
+    // Position inconsistent with the GeoNetworking beacon.
+    (MisbehaviorCheck::BeaconMismatch, (CAM_TGT_POSITION, 3)),
+];
+
+/// Target and observation identifiers of the DENM reports, for each [MisbehaviorCheck] raised
+/// on a DENM. The observation identifiers are numbered per target.
+const DENM_OBSERVATIONS: [(MisbehaviorCheck, (u8, u8)); 6] = [
+    // Reference time too old.
+    (MisbehaviorCheck::Freshness, (DENM_TGT_REFERENCE, 1)),
+    // Detection time later than the reference time.
+    (MisbehaviorCheck::DetectionTime, (DENM_TGT_DETECTION, 1)),
+    // Event position too far from the sender.
+    (MisbehaviorCheck::EventRange, (DENM_TGT_EVENT_POSITION, 1)),
+    // Sender position change too large.
+    (MisbehaviorCheck::PositionJump, (DENM_TGT_POSITION, 1)),
+    // Sender position out of the communication range.
+    (MisbehaviorCheck::CommunicationRange, (DENM_TGT_POSITION, 2)),
+    // Sender position inconsistent with the GeoNetworking beacon.
+    (MisbehaviorCheck::BeaconMismatch, (DENM_TGT_POSITION, 3)),
+];
+
+/// Returns the target and observation identifiers table of the reports on messages
+/// identified by `aid`.
+fn observations_table(aid: AID) -> MisbehaviorReportResult<&'static ObservationsTable> {
+    match aid {
+        AID::CA => Ok(&CAM_OBSERVATIONS),
+        AID::DEN => Ok(&DENM_OBSERVATIONS),
+        _ => Err(MisbehaviorReportError::UnsupportedAid(aid)),
+    }
+}
+
+#[test]
+fn test_report_round_trip() {
+    use veloce_asn1::defs::etsi_103097_v211::ieee1609_dot2_base_types::{
+        Elevation, Latitude, Longitude, NinetyDegreeInt, OneEightyDegreeInt, Uint16,
+    };
+
+    let location = ThreeDLocation::new(
+        Latitude(NinetyDegreeInt(480_000_000)),
+        Longitude(OneEightyDegreeInt(-30_000_000)),
+        Elevation(Uint16(4095)),
+    );
+    let evidence = MisbehaviorEvidence {
+        messages: vec![vec![0x03, 0x81, 0x00], vec![0x03, 0x81, 0x01]],
+        certificate: None,
+    };
+    let detections = [
+        MisbehaviorCheck::PositionJump,
+        MisbehaviorCheck::Freshness,
+        MisbehaviorCheck::BeaconMismatch,
+    ];
+    let timestamp = Instant::from_secs(1_000);
+
+    let report =
+        MisbehaviorReport::new(AID::CA, &detections, evidence, location.clone(), timestamp)
+            .unwrap();
+    let decoded = MisbehaviorReport::from_bytes(&report.as_bytes().unwrap()).unwrap();
+
+    assert_eq!(decoded.aid().unwrap(), AID::CA);
+    assert_eq!(
+        decoded.generation_time(),
+        TAI2004::from_unix_instant(timestamp)
+    );
+    // Observations are grouped by target.
+    assert_eq!(
+        decoded.detections().unwrap(),
+        vec![
+            MisbehaviorCheck::PositionJump,
+            MisbehaviorCheck::BeaconMismatch,
+            MisbehaviorCheck::Freshness,
+        ]
+    );
+
+    // Targets are numbered per AID: the DENM detections are reported on DENM targets.
+    let evidence = MisbehaviorEvidence {
+        messages: vec![vec![0x03, 0x81, 0x00]],
+        certificate: None,
+    };
+    let detections = [MisbehaviorCheck::EventRange, MisbehaviorCheck::PositionJump];
+    let report = MisbehaviorReport::new(
+        AID::DEN,
+        &detections,
+        evidence.clone(),
+        location.clone(),
+        timestamp,
+    )
+    .unwrap();
+    let decoded = MisbehaviorReport::from_bytes(&report.as_bytes().unwrap()).unwrap();
+    assert_eq!(decoded.aid().unwrap(), AID::DEN);
+    assert_eq!(decoded.detections().unwrap(), detections);
+
+    let content = decoded.inner.inner().report.content.as_ref().unwrap();
+    let asr = Asn1Wrapper::<AsrDenm>::decode_coer(content).unwrap().0;
+    let targets: Vec<u8> = asr.observations.iter().map(|o| o.tgt_id.0).collect();
+    assert_eq!(targets, vec![DENM_TGT_EVENT_POSITION, DENM_TGT_POSITION]);
+
+    // Event range has no CAM observation.
+    assert!(matches!(
+        MisbehaviorReport::new(AID::CA, &detections, evidence, location.clone(), timestamp),
+        Err(MisbehaviorReportError::UnsupportedDetection(
+            MisbehaviorCheck::EventRange
+        ))
+    ));
+
+    let no_evidence = MisbehaviorEvidence {
+        messages: vec![],
+        certificate: None,
+    };
+    assert!(matches!(
+        MisbehaviorReport::new(AID::DEN, &detections, no_evidence, location, timestamp),
+        Err(MisbehaviorReportError::NoEvidence)
+    ));
+}
@@ -2,6 +2,8 @@ pub mod authorization;
 pub mod crl;
 pub mod ctl;
 pub mod enrollment;
+pub mod ma;
+pub mod misbehavior;
 
 use core::fmt;
 
@@ -16,13 +18,13 @@ use veloce_asn1::{
 };
 
 use crate::security::{
-    backend::{BackendError, BackendResult, BackendTrait, PkiBackendTrait},
+    backend::{BackendError, BackendResult, BackendTrait, PkiBackendTrait, Sm2Ciphertext},
     certificate::{CertificateError, CertificateWithHashContainer, ExplicitCertificate},
     ciphertext::{Ciphertext, CiphertextError},
     permission::AID,
     signature::EcdsaSignature,
-    EcdsaKeyError, EciesKeyError, EncryptedEciesKey, EncryptedEciesKeyError, HashAlgorithm,
-    HashedId8, KeyPair,
+    EcdsaKeyError, EciesKey, EciesKeyError, EncryptedEciesKey, EncryptedEciesKeyError,
+    HashAlgorithm, HashedId8, KeyPair,
 };
 
 use super::{
@@ -496,7 +498,7 @@ where
         HashAlgorithm::SHA384 => [backend.sha384(&tbs), backend.sha384(signer_data)].concat(),
         HashAlgorithm::SM3 => [
             backend.sm3(&tbs).map_err(SignerError::Backend)?,
-            backend.sm3(&[]).map_err(SignerError::Backend)?,
+            backend.sm3(signer_data).map_err(SignerError::Backend)?,
         ]
         .concat(),
     };
@@ -538,7 +540,8 @@ impl fmt::Display for EncryptionError {
 }
 
 /// Encrypt `data` bytes using the provided symmetric `symm_encryption_key`, `certificate`,  and cryptography `backend`.
-/// Returns the encrypted data and the ephemeral encryption key pair.
+/// Returns the encrypted data and the ephemeral encryption key pair, if any. SM2 encryption does
+/// not expose the ephemeral key pair.
 /// The returned [EncryptedData] structure is filled and ready to be serialized.
 #[allow(clippy::type_complexity)]
 pub fn encrypt<B>(
@@ -548,7 +551,7 @@ pub fn encrypt<B>(
     backend: &B,
 ) -> EncryptionResult<(
     EncryptedData,
-    KeyPair<B::BackendSecretKey, B::BackendPublicKey>,
+    Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
 )>
 where
     B: PkiBackendTrait,
@@ -558,12 +561,6 @@ where
         .generate_random::<12>()
         .map_err(EncryptionError::Backend)?;
 
-    // Encrypt the outer EC Request.
-    let encrypted_req = backend
-        .encrypt_aes128_ccm(&data, &symm_encryption_key.0, &nonce)
-        .map_err(EncryptionError::Backend)?;
-
-    // Encrypt the encryption key.
     let cert_hashed_id8 = certificate.hashed_id8();
     let public_encryption_key = certificate
         .certificate()
@@ -571,58 +568,98 @@ where
         .map_err(EncryptionError::Certificate)?
         .ok_or(EncryptionError::NoPublicEncryptionKey)?;
 
+    // Encrypt the outer EC Request. SM2 recipients use SM4-CCM, as defined in YD/T 3957.
+    let ciphertext = match public_encryption_key {
+        EciesKey::Sm2(_) => {
+            let encrypted_req = backend
+                .encrypt_sm4_ccm(&data, &symm_encryption_key.0, &nonce)
+                .map_err(EncryptionError::Backend)?;
+            Ciphertext::new_sm4_ccm(nonce.into(), encrypted_req)
+        }
+        _ => {
+            let encrypted_req = backend
+                .encrypt_aes128_ccm(&data, &symm_encryption_key.0, &nonce)
+                .map_err(EncryptionError::Backend)?;
+            Ciphertext::new_aes_128_ccm(nonce.into(), encrypted_req)
+        }
+    };
+
+    // Encrypt the encryption key.
     let key_type = public_encryption_key.key_type();
     let hash_algorithm = public_encryption_key.hash_algorithm();
 
-    let ephemeral_ec_encryption_keypair = backend
-        .generate_ephemeral_keypair(key_type)
-        .map_err(EncryptionError::Backend)?;
-
     let peer_public_key =
         B::BackendPublicKey::try_from(public_encryption_key).map_err(EncryptionError::Backend)?;
 
-    // Build the shared secret.
-    let shared_secret = backend
-        .derive(&ephemeral_ec_encryption_keypair.secret, &peer_public_key)
-        .map_err(EncryptionError::Backend)?;
-
-    let cert_hash = certificate.certificate().hash(hash_algorithm, backend);
-
-    let (ke_size, km_size) = match hash_algorithm {
-        HashAlgorithm::SHA256 | HashAlgorithm::SM3 => (16, 32),
-        HashAlgorithm::SHA384 => (24, 48),
+    let (ephemeral_public_key, encrypted_key, tag, ephemeral_keypair) = match hash_algorithm {
+        HashAlgorithm::SM3 => {
+            let sm2_ciphertext = backend
+                .sm2_encrypt(&peer_public_key, &symm_encryption_key.0)
+                .map_err(EncryptionError::Backend)?;
+
+            (
+                sm2_ciphertext.ephemeral_key,
+                sm2_ciphertext.data,
+                sm2_ciphertext.tag,
+                None,
+            )
+        }
+        _ => {
+            let ephemeral_ec_encryption_keypair = backend
+                .generate_ephemeral_keypair(key_type)
+                .map_err(EncryptionError::Backend)?;
+
+            // Build the shared secret.
+            let shared_secret = backend
+                .derive(&ephemeral_ec_encryption_keypair.secret, &peer_public_key)
+                .map_err(EncryptionError::Backend)?;
+
+            let cert_hash = certificate
+                .certificate()
+                .hash(hash_algorithm, backend)
+                .map_err(EncryptionError::Certificate)?;
+
+            let (ke_size, km_size) = match hash_algorithm {
+                HashAlgorithm::SHA256 | HashAlgorithm::SM3 => (16, 32),
+                HashAlgorithm::SHA384 => (24, 48),
+            };
+
+            let ke_km = kdf2(
+                &shared_secret,
+                &cert_hash,
+                ke_size + km_size,
+                hash_algorithm,
+                backend,
+            )
+            .map_err(EncryptionError::Backend)?;
+
+            // Encrypt the encryption key.
+            let encrypted_key: Vec<u8> = symm_encryption_key
+                .0
+                .iter()
+                .zip(ke_km[..ke_size].iter())
+                .map(|(a, b)| a ^ b)
+                .collect();
+
+            // Generate the associated tag.
+            let mut tag = backend
+                .hmac(hash_algorithm, &ke_km[ke_size..], &encrypted_key)
+                .map_err(EncryptionError::Backend)?;
+
+            // Truncate the tag to the correct size.
+            tag.truncate(16);
+
+            (
+                ephemeral_ec_encryption_keypair.public.clone(),
+                encrypted_key,
+                tag,
+                Some(ephemeral_ec_encryption_keypair),
+            )
+        }
     };
 
-    let ke_km = kdf2(
-        &shared_secret,
-        &cert_hash,
-        ke_size + km_size,
-        hash_algorithm,
-        backend,
-    );
-
-    // Encrypt the encryption key.
-    let encrypted_key: Vec<u8> = symm_encryption_key
-        .0
-        .iter()
-        .zip(ke_km[..ke_size].iter())
-        .map(|(a, b)| a ^ b)
-        .collect();
-
-    // Generate the associated tag.
-    let mut tag = backend
-        .hmac(hash_algorithm, &ke_km[ke_size..], &encrypted_key)
-        .map_err(EncryptionError::Backend)?;
-
-    // Truncate the tag to the correct size.
-    tag.truncate(16);
-
-    let ciphertext = Ciphertext::new_aes_128_ccm(nonce.into(), encrypted_req);
-
     // Get the ephemeral public key into the correct format.
-    let ephemeral_public_key = ephemeral_ec_encryption_keypair
-        .public
-        .clone()
+    let ephemeral_public_key = ephemeral_public_key
         .try_into()
         .map_err(EncryptionError::Backend)?;
 
@@ -631,7 +668,7 @@ where
 
     Ok((
         EncryptedData::new(ciphertext, vec![recipient]).map_err(EncryptionError::Encrypted)?,
-        ephemeral_ec_encryption_keypair,
+        ephemeral_keypair,
     ))
 }
 
@@ -690,10 +727,141 @@ where
         Ciphertext::Aes128Ccm(aes128_inner) => {
             backend.decrypt_aes128_ccm(&aes128_inner.data, &key.0, &aes128_inner.nonce)
         }
+        Ciphertext::Sm4Ccm(sm4_inner) => {
+            backend.decrypt_sm4_ccm(&sm4_inner.data, &key.0, &sm4_inner.nonce)
+        }
     }
     .map_err(DecryptionError::Backend)
 }
 
+/// Secret key used to decrypt an [EncryptedData] with [decrypt_for].
+#[derive(Debug)]
+pub enum DecryptionKey<'a, S> {
+    /// Canonical secret key.
+    Canonical,
+    /// Authorization ticket encryption secret key, tagged with the inner id in the backend.
+    AuthorizationTicket(usize),
+    /// Provided secret key.
+    Secret(&'a S),
+}
+
+/// Decrypt the `encrypted_data` addressed to the `certificate` holder, using the provided `backend`.
+/// `key` is the secret encryption key associated with `certificate`.
+pub fn decrypt_for<B, C>(
+    encrypted_data: &EncryptedData,
+    certificate: &CertificateWithHashContainer<C>,
+    backend: &B,
+    key: DecryptionKey<B::BackendSecretKey>,
+) -> DecryptionResult<Vec<u8>>
+where
+    B: PkiBackendTrait,
+    C: ExplicitCertificate,
+{
+    let recipients = encrypted_data
+        .recipients()
+        .map_err(DecryptionError::Encrypted)?;
+
+    let cert_hashed_id8 = certificate.hashed_id8();
+    let Some(pk_recipient) = recipients.iter().find_map(|r| match r {
+        RecipientInfo::Cert(pkr) if pkr.recipient_id == cert_hashed_id8 => Some(pkr),
+        _ => None,
+    }) else {
+        return Err(match recipients.first() {
+            Some(RecipientInfo::Cert(pkr)) => DecryptionError::UnknownRecipientId(pkr.recipient_id),
+            _ => DecryptionError::UnexpectedRecipientInformation,
+        });
+    };
+
+    let hash_algorithm = pk_recipient.enc_key.hash_algorithm();
+    let enc_key_params = pk_recipient.enc_key.params();
+
+    let peer_public_key = B::BackendPublicKey::try_from(pk_recipient.enc_key.public_key())
+        .map_err(DecryptionError::Backend)?;
+
+    let encryption_key = match hash_algorithm {
+        HashAlgorithm::SM3 => {
+            let sm2_ciphertext = Sm2Ciphertext {
+                ephemeral_key: peer_public_key,
+                data: enc_key_params.encrypted_key.clone(),
+                tag: enc_key_params.tag.clone(),
+            };
+
+            match key {
+                DecryptionKey::Canonical => backend.sm2_decrypt_canonical(&sm2_ciphertext),
+                DecryptionKey::AuthorizationTicket(id) => {
+                    backend.sm2_decrypt_authorization_ticket(id, &sm2_ciphertext)
+                }
+                DecryptionKey::Secret(secret) => backend.sm2_decrypt(secret, &sm2_ciphertext),
+            }
+            .map_err(|e| match e {
+                BackendError::InvalidData => DecryptionError::InvalidTag,
+                e => DecryptionError::Backend(e),
+            })?
+        }
+        _ => {
+            // Reconstruct the shared secret.
+            let shared_secret = match key {
+                DecryptionKey::Canonical => backend.derive_canonical(&peer_public_key),
+                DecryptionKey::AuthorizationTicket(id) => {
+                    backend.derive_authorization_ticket(id, &peer_public_key)
+                }
+                DecryptionKey::Secret(secret) => backend.derive(secret, &peer_public_key),
+            }
+            .map_err(DecryptionError::Backend)?;
+
+            let cert_hash = certificate
+                .certificate()
+                .hash(hash_algorithm, backend)
+                .map_err(DecryptionError::Certificate)?;
+
+            let (ke_size, km_size) = match hash_algorithm {
+                HashAlgorithm::SHA256 | HashAlgorithm::SM3 => (16, 32),
+                HashAlgorithm::SHA384 => (24, 48),
+            };
+
+            let ke_km = kdf2(
+                &shared_secret,
+                &cert_hash,
+                ke_size + km_size,
+                hash_algorithm,
+                backend,
+            )
+            .map_err(DecryptionError::Backend)?;
+
+            // Verify received tag.
+            let mut tag = backend
+                .hmac(
+                    hash_algorithm,
+                    &ke_km[ke_size..],
+                    &enc_key_params.encrypted_key,
+                )
+                .map_err(DecryptionError::Backend)?;
+
+            tag.truncate(16);
+
+            if tag != enc_key_params.tag {
+                return Err(DecryptionError::InvalidTag);
+            }
+
+            // Decrypt the encryption key.
+            enc_key_params
+                .encrypted_key
+                .iter()
+                .zip(ke_km[..ke_size].iter())
+                .map(|(a, b)| a ^ b)
+                .collect()
+        }
+    };
+
+    let encryption_key = Aes128Key(
+        encryption_key
+            .try_into()
+            .map_err(|_| DecryptionError::Backend(BackendError::InvalidKey))?,
+    );
+
+    decrypt(encrypted_data, &encryption_key, backend)
+}
+
 #[derive(Debug)]
 pub enum EncryptedResponseHandlerError {
     /// Asn.1 wrapper error.
@@ -830,7 +998,11 @@ where
             .try_into()
             .map_err(HmacAndTagError::EncryptionKey)?;
 
-        let sa = SymmAlgorithm::aes128Ccm; // Only AES-128-CCM is supported for now.
+        // SM2 encryption keys are used with SM4-CCM, as defined in YD/T 3957.
+        let sa = match eck {
+            EciesKey::Sm2(_) => SymmAlgorithm::sm4Ccm,
+            _ => SymmAlgorithm::aes128Ccm,
+        };
         Some(PublicEncryptionKey::new(sa, bpk))
     } else {
         None
@@ -872,3 +1044,60 @@ where
 
     Ok(HashedId8::from_bytes(&hash[24..]))
 }
+
+#[test]
+fn test_encrypt_decrypt_for() {
+    use veloce_asn1::prelude::rasn;
+
+    use crate::security::{
+        certificate::{CertificateTrait, EnrollmentAuthorityCertificate},
+        storage::StorageTrait,
+        tests, EcKeyType,
+    };
+
+    let base_path = tests::get_test_storage_path();
+    let (storage, backend) = tests::setup_storage_and_crypto(base_path);
+
+    let raw_ea_cert = storage.load_ea_certificate().unwrap();
+    let ea_cert = EnrollmentAuthorityCertificate::from_bytes(&raw_ea_cert, &backend).unwrap();
+
+    for key_type in [EcKeyType::NistP256r1, EcKeyType::Sm2] {
+        // EA secret key is not available, so replace the EA encryption key with one we own.
+        let recipient = backend.generate_ephemeral_keypair(key_type).unwrap();
+        let public_key: EciesKey = recipient.public.clone().try_into().unwrap();
+
+        let mut inner = ea_cert.inner().clone();
+        let encryption_key = inner.0.to_be_signed.encryption_key.as_mut().unwrap();
+        encryption_key.public_key = public_key.try_into().unwrap();
+
+        let raw_cert = rasn::coer::encode(&inner).unwrap();
+        let cert = EnrollmentAuthorityCertificate::from_bytes(&raw_cert, &backend)
+            .unwrap()
+            .into_with_hash_container(&backend)
+            .unwrap();
+
+        let key = Aes128Key(backend.generate_aes128_key().unwrap());
+        let data = vec![0xca, 0xfe, 0xca, 0xfe];
+        let (encrypted, ephemeral) = encrypt(data.clone(), &key, &cert, &backend).unwrap();
+        assert_eq!(ephemeral.is_none(), key_type == EcKeyType::Sm2);
+
+        let res = decrypt_for(
+            &encrypted,
+            &cert,
+            &backend,
+            DecryptionKey::Secret(&recipient.secret),
+        )
+        .unwrap();
+        assert_eq!(res, data);
+
+        // Any other secret key should not pass the tag verification.
+        let other = backend.generate_ephemeral_keypair(key_type).unwrap();
+        let res = decrypt_for(
+            &encrypted,
+            &cert,
+            &backend,
+            DecryptionKey::Secret(&other.secret),
+        );
+        assert!(matches!(res, Err(DecryptionError::InvalidTag)));
+    }
+}
@@ -7,7 +7,10 @@ use veloce_asn1::{
     prelude::rasn::types::FixedOctetString,
 };
 
-use crate::security::{backend::BackendTrait, EcdsaKey, EciesKey, HashAlgorithm, HashedId8};
+use crate::security::{
+    backend::{BackendResult, BackendTrait},
+    EcdsaKey, EciesKey, HashAlgorithm, HashedId8,
+};
 
 pub mod asn1_wrapper;
 pub mod encrypted_data;
@@ -15,7 +18,7 @@ pub mod message;
 pub mod service;
 pub mod signed_data;
 
-/// AES 128 bit key.
+/// AES 128 bit key. Also used as SM4 key, which has the same size.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Aes128Key(pub [u8; 16]);
 
@@ -153,18 +156,24 @@ pub struct IncludedPublicKeys {
 /// The ETSI standard omits the salt, but it is required by the PKI as the
 /// [test suite](https://forge.etsi.org/rep/ITS/TS.ITS/-/blob/devel2/ccsrc/Protocols/Security/security_ecc.cc?ref_type=heads#L1143)
 /// requires it.
+/// With [HashAlgorithm::SM3] and an empty `salt`, this is the KDF defined in GB/T 32918.4.
 pub fn kdf2<B>(
     key: &[u8],
     salt: &[u8],
     output_len: usize,
     hash_algorithm: HashAlgorithm,
     backend: &B,
-) -> Vec<u8>
+) -> BackendResult<Vec<u8>>
 where
     B: BackendTrait + ?Sized,
 {
-    let num_iter = output_len.div_ceil(key.len());
-    let mut res = Vec::with_capacity(key.len() * num_iter);
+    let hash_len = match hash_algorithm {
+        HashAlgorithm::SHA256 | HashAlgorithm::SM3 => 32,
+        HashAlgorithm::SHA384 => 48,
+    };
+
+    let num_iter = output_len.div_ceil(hash_len);
+    let mut res = Vec::with_capacity(hash_len * num_iter);
 
     match hash_algorithm {
         HashAlgorithm::SHA256 => {
@@ -181,11 +190,17 @@ where
                 );
             }
         }
-        HashAlgorithm::SM3 => unimplemented!(),
+        HashAlgorithm::SM3 => {
+            for i in 1..=num_iter {
+                res.extend_from_slice(
+                    &backend.sm3(&[key, &(i as u32).to_be_bytes(), salt].concat())?,
+                );
+            }
+        }
     }
 
     res.truncate(output_len);
-    res
+    Ok(res)
 }
 
 #[test]
@@ -206,7 +221,7 @@ fn test_kdf2() {
         0xde, 0x4d,
     ];
 
-    let res = kdf2(&key, &salt, 48, HashAlgorithm::SHA256, &backend);
+    let res = kdf2(&key, &salt, 48, HashAlgorithm::SHA256, &backend).unwrap();
     assert_eq!(
         res,
         [
@@ -42,8 +42,9 @@ pub struct AuthorizationRequestParameters {
     pub privacy: bool,
     /// Enable Proof of Possession when requesting the AT certificate.
     pub proof_of_possession: bool,
-    /* TODO: /// Optionally request a pubic encryption key in the AT certificate.
-    pub optional_encryption_key: bool, */
+    /// Request a public encryption key in the AT certificate.
+    /// Required to receive encrypted messages signed with this certificate.
+    pub encryption_key: bool,
 }
 
 /// Encryption keys used to cipher the Authorization Request.
@@ -73,7 +74,8 @@ pub struct AuthorizationRequestContext<B: PkiBackendTrait> {
     /// Outer encryption key, used to encrypt the communications with the PKI.
     outer_encryption_key: Aes128Key,
     /// Ephemeral keypair, used to encrypt [Self::outer_encryption_key] with the DH algorithm.
-    outer_ephemeral_keypair: KeyPair<B::BackendSecretKey, B::BackendPublicKey>,
+    /// Not available with SM2 encryption.
+    outer_ephemeral_keypair: Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
     /// Privacy encryption key, if any.
     privacy_encryption_key: Option<Aes128Key>,
     /// Privacy Ephemeral keypair, used to encrypt [Self::privacy_encryption_key] with the DH algorithm.
@@ -119,9 +121,25 @@ impl PkiClientService {
             privacy_encryption_key,
         };
 
+        // Generate the Authorization Ticket encryption key pair, if required.
+        let encryption_key = if params.encryption_key {
+            Some(
+                backend
+                    .generate_authorization_ticket_encryption_keypair(
+                        EcKeyType::NistP256r1,
+                        params.storage_id,
+                    )
+                    .map_err(PkiServiceError::Backend)?
+                    .try_into()
+                    .map_err(PkiServiceError::Backend)?,
+            )
+        } else {
+            None
+        };
+
         let public_keys = IncludedPublicKeys {
             verification_key,
-            encryption_key: None,
+            encryption_key,
         };
 
         let certificates = Certificates {
@@ -166,7 +184,7 @@ impl PkiClientService {
         backend: &B,
     ) -> AuthorizationRequestResult<(
         Vec<u8>,
-        KeyPair<B::BackendSecretKey, B::BackendPublicKey>,
+        Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
         Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
     )>
     where
@@ -219,7 +237,7 @@ impl PkiClientService {
             let (enc_data, keys) = message::encrypt(signed_epl_bytes, key, ea_certificate, backend)
                 .map_err(AuthorizationRequestError::PrivacyEncryption)?;
 
-            (EcSignature::Encrypted(enc_data), Some(keys))
+            (EcSignature::Encrypted(enc_data), keys)
         } else {
             (EcSignature::Plain(signed_epl), None)
         };
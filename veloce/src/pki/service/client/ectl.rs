@@ -28,17 +28,16 @@ impl PkiClientService {
         timestamp: Instant,
         backend: &B,
     ) -> PkiServiceResult<(CertificateTrustList, TrustListManagerCertificate)> {
-        self.ectl_response_inner(response, timestamp, backend)
+        Self::parse_and_check_ectl(response, timestamp, backend)
             .map_err(PkiServiceError::EctlResponse)
     }
 
-    fn ectl_response_inner<B: PkiBackendTrait>(
-        &self,
-        response: &[u8],
+    pub fn parse_and_check_ectl<B: PkiBackendTrait>(
+        bytes: &[u8],
         timestamp: Instant,
         backend: &B,
     ) -> CertificateTrustListResult<(CertificateTrustList, TrustListManagerCertificate)> {
-        let tlm_msg = TlmCertificateTrustListMessage::from_bytes_signed(response)
+        let tlm_msg = TlmCertificateTrustListMessage::from_bytes_signed(bytes)
             .map_err(CertificateTrustListError::Outer)?;
 
         let mut tlm_cert = None;
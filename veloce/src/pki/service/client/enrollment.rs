@@ -37,7 +37,8 @@ pub struct EnrollmentRequestContext<B: PkiBackendTrait> {
     /// Symmetric encryption key, used to encrypt the communications with the PKI.
     symm_encryption_key: Aes128Key,
     /// Ephemeral keypair, used to encrypt [Self::symm_encryption_key] with the DH algorithm.
-    ephemeral_keypair: KeyPair<B::BackendSecretKey, B::BackendPublicKey>,
+    /// Not available with SM2 encryption.
+    ephemeral_keypair: Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
 }
 
 impl PkiClientService {
@@ -143,7 +144,10 @@ impl PkiClientService {
         ea_certificate: &CertificateWithHashContainer<EnrollmentAuthorityCertificate>,
         timestamp: Instant,
         backend: &B,
-    ) -> EnrollmentRequestResult<(Vec<u8>, KeyPair<B::BackendSecretKey, B::BackendPublicKey>)>
+    ) -> EnrollmentRequestResult<(
+        Vec<u8>,
+        Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
+    )>
     where
         B: PkiBackendTrait,
     {
@@ -208,7 +212,10 @@ impl PkiClientService {
         ea_certificate: &CertificateWithHashContainer<EnrollmentAuthorityCertificate>,
         timestamp: Instant,
         backend: &B,
-    ) -> EnrollmentRequestResult<(Vec<u8>, KeyPair<B::BackendSecretKey, B::BackendPublicKey>)>
+    ) -> EnrollmentRequestResult<(
+        Vec<u8>,
+        Option<KeyPair<B::BackendSecretKey, B::BackendPublicKey>>,
+    )>
     where
         B: PkiBackendTrait,
     {
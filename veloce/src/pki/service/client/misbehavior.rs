@@ -0,0 +1,388 @@
+#[cfg(not(feature = "std"))]
+use alloc::collections::vec_deque::VecDeque;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use crate::{
+    config::{PKI_MBR_LIFETIME, PKI_MBR_QUEUE_SIZE},
+    pki::{
+        message::{
+            self,
+            ma::MisbehaviorAuthority,
+            misbehavior::{MisbehaviorReport, MisbehaviorReportError, MisbehaviorReportResult},
+        },
+        service::{PkiServiceError, PkiServiceResult},
+        Aes128Key,
+    },
+    security::{
+        backend::PkiBackendTrait,
+        certificate::{
+            AuthorizationTicketCertificate, CertificateWithHashContainer, ExplicitCertificate,
+            MisbehaviorAuthorityCertificate,
+        },
+        misbehavior::MisbehaviorDetection,
+    },
+    time::Instant,
+};
+
+use super::PkiClientService;
+
+impl PkiClientService {
+    /// Emit a Misbehavior Report to the Misbehavior Authority. The `report` is signed with the
+    /// Authorization Ticket key stored at `at_storage_id`, then encrypted to the MA certificate.
+    pub fn emit_misbehavior_report<B: PkiBackendTrait>(
+        &self,
+        report: &MisbehaviorReport,
+        at_certificate: &CertificateWithHashContainer<AuthorizationTicketCertificate>,
+        at_storage_id: usize,
+        ma_certificate: &CertificateWithHashContainer<MisbehaviorAuthorityCertificate>,
+        timestamp: Instant,
+        backend: &B,
+    ) -> PkiServiceResult<Vec<u8>> {
+        // Generate the AES 128 ephemeral encryption key.
+        let encryption_key = Aes128Key(
+            backend
+                .generate_aes128_key()
+                .map_err(PkiServiceError::Backend)?,
+        );
+
+        self.misbehavior_report_inner(
+            report,
+            at_certificate,
+            at_storage_id,
+            ma_certificate,
+            &encryption_key,
+            timestamp,
+            backend,
+        )
+        .map_err(PkiServiceError::MisbehaviorReport)
+    }
+
+    /// Emit a Misbehavior Report to the Misbehavior Authority `ma` for each of the
+    /// misbehavior `detections`, and enqueue them in `queue` for submission.
+    /// Reports which cannot be emitted are dropped. Returns the number of enqueued reports.
+    #[allow(clippy::too_many_arguments)]
+    pub fn enqueue_misbehavior_reports<B: PkiBackendTrait>(
+        &self,
+        detections: impl IntoIterator<Item = MisbehaviorDetection>,
+        queue: &mut MisbehaviorReportQueue,
+        at_certificate: &CertificateWithHashContainer<AuthorizationTicketCertificate>,
+        at_storage_id: usize,
+        ma: &MisbehaviorAuthority,
+        timestamp: Instant,
+        backend: &B,
+    ) -> usize {
+        let mut enqueued = 0;
+        for detection in detections {
+            let res = MisbehaviorReport::from_detection(&detection)
+                .map_err(PkiServiceError::MisbehaviorReport)
+                .and_then(|report| {
+                    self.emit_misbehavior_report(
+                        &report,
+                        at_certificate,
+                        at_storage_id,
+                        ma.certificate(),
+                        timestamp,
+                        backend,
+                    )
+                });
+
+            match res {
+                Ok(report) => {
+                    queue.enqueue(report, timestamp);
+                    enqueued += 1;
+                }
+                Err(e) => net_debug!("Cannot emit misbehavior report: {}", e),
+            }
+        }
+
+        enqueued
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn misbehavior_report_inner<B: PkiBackendTrait>(
+        &self,
+        report: &MisbehaviorReport,
+        at_certificate: &CertificateWithHashContainer<AuthorizationTicketCertificate>,
+        at_storage_id: usize,
+        ma_certificate: &CertificateWithHashContainer<MisbehaviorAuthorityCertificate>,
+        encryption_key: &Aes128Key,
+        timestamp: Instant,
+        backend: &B,
+    ) -> MisbehaviorReportResult<Vec<u8>> {
+        let mut signed_report =
+            MisbehaviorReport::emit_signed_wrapper(report, at_certificate.hashed_id8(), timestamp)?;
+
+        let hash_algorithm = at_certificate
+            .certificate()
+            .public_verification_key()
+            .map_err(MisbehaviorReportError::AtCertificate)?
+            .hash_algorithm();
+
+        message::sign_with_authorization_key(
+            &mut signed_report,
+            hash_algorithm,
+            at_storage_id,
+            backend,
+        )
+        .map_err(MisbehaviorReportError::OuterSigner)?;
+
+        let to_encrypt = signed_report
+            .as_bytes()
+            .map_err(MisbehaviorReportError::Outer)?;
+
+        let (encrypted, _) = message::encrypt(to_encrypt, encryption_key, ma_certificate, backend)
+            .map_err(MisbehaviorReportError::Encryption)?;
+
+        encrypted
+            .as_bytes()
+            .map_err(MisbehaviorReportError::Encrypted)
+    }
+}
+
+/// Queue of emitted Misbehavior Reports, waiting for their submission to the
+/// Misbehavior Authority. Reports are kept until they are successfully submitted, or until
+/// they expire. When the queue is full, the oldest report is dropped.
+#[derive(Debug, Default)]
+pub struct MisbehaviorReportQueue {
+    /// Encrypted reports, along their expiration time.
+    reports: VecDeque<(Vec<u8>, Instant)>,
+}
+
+impl MisbehaviorReportQueue {
+    /// Constructs an empty [MisbehaviorReportQueue].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of reports waiting in the queue.
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// Enqueue an encrypted `report`, emitted at `timestamp`.
+    pub fn enqueue(&mut self, report: Vec<u8>, timestamp: Instant) {
+        if self.reports.len() >= PKI_MBR_QUEUE_SIZE {
+            self.reports.pop_front();
+        }
+
+        self.reports
+            .push_back((report, timestamp + PKI_MBR_LIFETIME));
+    }
+
+    /// Submit the queued reports with the `send` function, in emission order.
+    /// Expired reports are dropped. Submission stops at the first `send` error, the remaining
+    /// reports are kept for a later attempt, ie: when connectivity to the MA is back.
+    /// Returns the number of submitted reports.
+    pub fn submit<F, E>(&mut self, timestamp: Instant, mut send: F) -> Result<usize, E>
+    where
+        F: FnMut(&[u8]) -> Result<(), E>,
+    {
+        self.reports
+            .retain(|(_, expires_at)| *expires_at > timestamp);
+
+        let mut submitted = 0;
+        while let Some((report, _)) = self.reports.front() {
+            send(report)?;
+            self.reports.pop_front();
+            submitted += 1;
+        }
+
+        Ok(submitted)
+    }
+}
+
+#[test]
+fn test_report_queue_submit() {
+    use crate::time::Duration;
+
+    let mut queue = MisbehaviorReportQueue::new();
+    let now = Instant::from_secs(1_000);
+
+    queue.enqueue(vec![1], now);
+    queue.enqueue(vec![2], now + Duration::from_secs(10));
+    queue.enqueue(vec![3], now + Duration::from_secs(20));
+
+    // No connectivity, reports are kept.
+    let res: Result<usize, ()> = queue.submit(now, |_| Err(()));
+    assert!(res.is_err());
+    assert_eq!(queue.len(), 3);
+
+    // First report expired, connectivity lost after the second one.
+    let mut sent = Vec::new();
+    let res = queue.submit(now + PKI_MBR_LIFETIME, |r| {
+        if sent.len() == 1 {
+            return Err(());
+        }
+        sent.push(r.to_vec());
+        Ok(())
+    });
+    assert!(res.is_err());
+    assert_eq!(sent, vec![vec![2]]);
+    assert_eq!(queue.len(), 1);
+
+    let res: Result<usize, ()> = queue.submit(now + PKI_MBR_LIFETIME, |_| Ok(()));
+    assert_eq!(res, Ok(1));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_enqueue_misbehavior_reports() {
+    use uom::si::angle::degree;
+
+    use crate::{
+        common::PotiPosition,
+        pki::message::ma::MisbehaviorAuthorityError,
+        security::{
+            certificate::{
+                AuthorizationTicketCertificate, CertificateError, CertificateTrait, RootCertificate,
+            },
+            misbehavior::{MisbehaviorCheck, MisbehaviorReaction, MisbehaviorVerdict},
+            permission::{Permission, AID},
+            ssp::cam::CamSsp,
+            storage::StorageTrait,
+            tests,
+        },
+        time::Duration,
+        types::{Latitude, Longitude},
+    };
+
+    let timestamp = Instant::from_secs(1_716_674_400);
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    // Receive a CAM and record a misbehavior on it.
+    let mut service = tests::setup_security_service();
+    let gn_cam = vec![0x20, 0x50, 0x02, 0x00, 0x00, 0x1e, 0x01, 0x00];
+    let secured = service
+        .encap_packet(
+            gn_cam,
+            Permission::CAM(CamSsp::new_v1().into()),
+            timestamp,
+            position,
+        )
+        .unwrap();
+    let rx_at = timestamp + Duration::from_millis(50);
+    let (confirm, _) = service.decap_packet(&secured, rx_at).unwrap();
+
+    let mut config = *service.misbehavior().config();
+    config.reactions.freshness = MisbehaviorReaction::Ignore;
+    service.set_misbehavior_config(config);
+
+    let verdict = MisbehaviorVerdict {
+        detections: vec![MisbehaviorCheck::PositionJump, MisbehaviorCheck::Freshness],
+        reaction: MisbehaviorReaction::Flag,
+    };
+    service.record_misbehavior(&confirm, &verdict, position.as_3d_location(), rx_at);
+
+    // Ignored detections only are not recorded.
+    let ignored = MisbehaviorVerdict {
+        detections: vec![MisbehaviorCheck::Freshness],
+        reaction: MisbehaviorReaction::Ignore,
+    };
+    service.record_misbehavior(&confirm, &ignored, position.as_3d_location(), rx_at);
+
+    let detections = service.take_misbehavior_detections();
+    assert_eq!(detections.len(), 1);
+    assert!(service.take_misbehavior_detections().is_empty());
+
+    let detection = &detections[0];
+    assert_eq!(detection.aid, AID::CA);
+    assert_eq!(detection.checks, vec![MisbehaviorCheck::PositionJump]);
+    assert_eq!(detection.message, secured);
+    assert_eq!(detection.timestamp, rx_at);
+
+    let report = MisbehaviorReport::from_detection(detection).unwrap();
+    assert_eq!(report.aid().unwrap(), AID::CA);
+    assert_eq!(
+        report.detections().unwrap(),
+        vec![MisbehaviorCheck::PositionJump]
+    );
+
+    // The MA certificate is issued by a dedicated test root, unknown until trusted.
+    let (storage, backend) = tests::setup_storage_and_crypto(tests::get_test_storage_path());
+    let assets = tests::get_test_storage_path().join("assets");
+    let raw_ma_cert = std::fs::read(assets.join("MA.cert")).unwrap();
+    let raw_ma_root_cert = std::fs::read(assets.join("MA_RCA.cert")).unwrap();
+    let url = "http://ma.veloce.test".to_string();
+
+    let res = MisbehaviorAuthority::from_bytes(
+        &raw_ma_cert,
+        url.clone(),
+        service.store(),
+        rx_at,
+        &backend,
+    );
+    assert!(matches!(
+        res,
+        Err(MisbehaviorAuthorityError::Certificate(
+            CertificateError::UnknownSigner(_)
+        ))
+    ));
+
+    let ma_root = RootCertificate::from_bytes(&raw_ma_root_cert, &backend)
+        .unwrap()
+        .into_with_hash_container(&backend)
+        .unwrap();
+    service
+        .store_mut()
+        .set_trusted_roots(vec![ma_root.hashed_id8()]);
+    assert!(service.store_mut().add_remote_root(ma_root));
+
+    // Expired MA certificate.
+    let res = MisbehaviorAuthority::from_bytes(
+        &raw_ma_cert,
+        url.clone(),
+        service.store(),
+        rx_at + Duration::from_secs(10 * 365 * 86_400),
+        &backend,
+    );
+    assert!(matches!(
+        res,
+        Err(MisbehaviorAuthorityError::Certificate(
+            CertificateError::Expired(_)
+        ))
+    ));
+
+    // Tampered MA certificate signature.
+    let mut tampered = raw_ma_cert.clone();
+    *tampered.last_mut().unwrap() ^= 0x01;
+    let res =
+        MisbehaviorAuthority::from_bytes(&tampered, url.clone(), service.store(), rx_at, &backend);
+    assert!(matches!(
+        res,
+        Err(MisbehaviorAuthorityError::FalseSignature)
+    ));
+
+    let ma = MisbehaviorAuthority::from_bytes(&raw_ma_cert, url, service.store(), rx_at, &backend)
+        .unwrap();
+
+    let raw_at_cert = storage.load_at_certificate(0).unwrap();
+    let at_cert = AuthorizationTicketCertificate::from_bytes(&raw_at_cert, &backend)
+        .unwrap()
+        .into_with_hash_container(&backend)
+        .unwrap();
+
+    let client = PkiClientService::new("veloce".to_string());
+    let mut queue = MisbehaviorReportQueue::new();
+    let enqueued = client
+        .enqueue_misbehavior_reports(detections, &mut queue, &at_cert, 0, &ma, rx_at, &backend);
+    assert_eq!(enqueued, 1);
+    assert_eq!(queue.len(), 1);
+
+    let mut submitted = Vec::new();
+    let res: Result<usize, ()> = queue.submit(rx_at, |r| {
+        submitted.push(r.to_vec());
+        Ok(())
+    });
+    assert_eq!(res, Ok(1));
+    assert!(!submitted[0].is_empty());
+}
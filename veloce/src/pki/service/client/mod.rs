@@ -3,6 +3,7 @@ pub mod crl;
 pub mod ctl;
 pub mod ectl;
 pub mod enrollment;
+pub mod misbehavior;
 pub mod tlm;
 
 pub struct PkiClientService {
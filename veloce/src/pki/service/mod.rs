@@ -8,6 +8,7 @@ use super::{
         authorization::{AuthorizationRequestError, AuthorizationResponseError},
         ctl::CertificateTrustListError,
         enrollment::{EnrollmentRequestError, EnrollmentResponseError},
+        misbehavior::MisbehaviorReportError,
     },
 };
 
@@ -38,6 +39,8 @@ pub enum PkiServiceError {
     CrlResponse(CertificateRevocationListError),
     /// CTL error.
     CtlResponse(CertificateTrustListError),
+    /// Misbehavior report error.
+    MisbehaviorReport(MisbehaviorReportError),
 }
 
 impl fmt::Display for PkiServiceError {
@@ -70,6 +73,9 @@ impl fmt::Display for PkiServiceError {
             PkiServiceError::CtlResponse(e) => {
                 write!(f, "CTL response: {}", e)
             }
+            PkiServiceError::MisbehaviorReport(e) => {
+                write!(f, "misbehavior report: {}", e)
+            }
         }
     }
 }
@@ -116,6 +116,30 @@ impl Backend {
     }
 }
 
+/// A signature verification, ie: the `data` to verify along its `signature` and the signer
+/// verification `key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureVerification {
+    /// Signature of the data.
+    pub signature: EcdsaSignature,
+    /// Verification key of the signer.
+    pub key: EcdsaKey,
+    /// Signed data.
+    pub data: Vec<u8>,
+}
+
+/// SM2 encrypted data, as defined in GB/T 32918.4.
+#[cfg(feature = "pki")]
+#[derive(Debug, Clone)]
+pub struct Sm2Ciphertext<P> {
+    /// Sender ephemeral public key, ie: `C1`.
+    pub ephemeral_key: P,
+    /// Encrypted data, ie: `C2`.
+    pub data: Vec<u8>,
+    /// SM3 authentication tag, ie: `C3`.
+    pub tag: Vec<u8>,
+}
+
 #[allow(unused_variables)]
 pub trait BackendTrait {
     /// Verifies `data` slice `signature` with `verification_key`.
@@ -126,6 +150,16 @@ pub trait BackendTrait {
         data: &[u8],
     ) -> BackendResult<bool>;
 
+    /// Verifies a `batch` of signatures. Returns the result of each verification, in the
+    /// `batch` order. The default implementation verifies the signatures one after another,
+    /// backends able to batch or parallelize the verification should override it.
+    fn verify_signatures(&self, batch: &[SignatureVerification]) -> Vec<BackendResult<bool>> {
+        batch
+            .iter()
+            .map(|v| self.verify_signature(v.signature.clone(), v.key.clone(), &v.data))
+            .collect()
+    }
+
     /// Sign the given `data` slice with the current authorization ticket private key.
     fn generate_signature(&self, data: &[u8]) -> BackendResult<EcdsaSignature>;
 
@@ -142,7 +176,7 @@ pub trait BackendTrait {
     /// Computes the SHA384 hash for a given `data` slice.
     fn sha384(&self, data: &[u8]) -> [u8; 48];
 
-    /// Computes the SM4 hash for a given `data` slice.s
+    /// Computes the SM3 hash for a given `data` slice.
     fn sm3(&self, data: &[u8]) -> BackendResult<[u8; 32]>;
 
     /// Compress an ECIES key coordinates to the Y0 or Y1 format.
@@ -222,6 +256,17 @@ pub trait PkiBackendTrait: BackendTrait {
         id: usize,
     ) -> BackendResult<Self::BackendPublicKey>;
 
+    /// Generate a new authorization ticket encryption key pair for a given `key_type`, tag it
+    /// wih the given `id`, and return the public key part of it.
+    ///
+    /// Underlying secret key storage is left to the backend, special care should be taken to ensure
+    /// secret key stays secret.
+    fn generate_authorization_ticket_encryption_keypair(
+        &mut self,
+        key_type: EcKeyType,
+        id: usize,
+    ) -> BackendResult<Self::BackendPublicKey>;
+
     /// Generate an EC key pair for a given `key_type`, and return a [KeyPair] containing the
     /// secret and the public key.
     ///
@@ -235,13 +280,52 @@ pub trait PkiBackendTrait: BackendTrait {
     /// Derive canonical secret `key` with the given `peer` public key.
     fn derive_canonical(&self, peer: &Self::BackendPublicKey) -> BackendResult<Vec<u8>>;
 
+    /// Derive the authorization ticket encryption secret key tagged with `id` with the given
+    /// `peer` public key.
+    fn derive_authorization_ticket(
+        &self,
+        id: usize,
+        peer: &Self::BackendPublicKey,
+    ) -> BackendResult<Vec<u8>>;
+
     /// Derive secret `key` with the given `peer` public key.
+    /// SM2 keys cannot be derived, use [PkiBackendTrait::sm2_encrypt] instead.
     fn derive(
         &self,
         key: &Self::BackendSecretKey,
         peer: &Self::BackendPublicKey,
     ) -> BackendResult<Vec<u8>>;
 
+    /// Encrypt `data` for the SM2 `peer` public key, as defined in GB/T 32918.4.
+    fn sm2_encrypt(
+        &self,
+        peer: &Self::BackendPublicKey,
+        data: &[u8],
+    ) -> BackendResult<Sm2Ciphertext<Self::BackendPublicKey>>;
+
+    /// Decrypt the SM2 `ciphertext` with the canonical secret key.
+    /// Returns [BackendError::InvalidData] if the ciphertext cannot be authenticated.
+    fn sm2_decrypt_canonical(
+        &self,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>>;
+
+    /// Decrypt the SM2 `ciphertext` with the authorization ticket encryption secret key tagged
+    /// with `id`. Returns [BackendError::InvalidData] if the ciphertext cannot be authenticated.
+    fn sm2_decrypt_authorization_ticket(
+        &self,
+        id: usize,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>>;
+
+    /// Decrypt the SM2 `ciphertext` with the secret `key`.
+    /// Returns [BackendError::InvalidData] if the ciphertext cannot be authenticated.
+    fn sm2_decrypt(
+        &self,
+        key: &Self::BackendSecretKey,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>>;
+
     /// Sign the given `data` slice with the current enrollment credential private key.
     fn generate_enrollment_signature(&self, data: &[u8]) -> BackendResult<EcdsaSignature>;
 
@@ -272,6 +356,14 @@ pub trait PkiBackendTrait: BackendTrait {
     /// The AES tag is expected to be at the end of the encrypted data.
     fn decrypt_aes128_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>>;
 
+    /// Encrypt the given 'data' slice as an SM4-CCM cipher with the provided `key` and `nonce`.
+    /// The generated tag is appended to the encrypted data.
+    fn encrypt_sm4_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>>;
+
+    /// Decrypt the given SM4-CCM 'data' with the provided `key` and `nonce`.
+    /// The tag is expected to be at the end of the encrypted data.
+    fn decrypt_sm4_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>>;
+
     /// Computes the HMAC of the given `key` and `data` slice using the provided [HashAlgorithm].
     fn hmac(
         &self,
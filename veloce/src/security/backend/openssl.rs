@@ -1,26 +1,30 @@
+use foreign_types::ForeignTypeRef;
 use openssl::{
     bn::{BigNum, BigNumContext},
-    cipher::Cipher,
+    cipher::{Cipher, CipherRef},
     cipher_ctx::CipherCtx,
     ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
     ecdsa::EcdsaSig,
     error::ErrorStack,
     hash::{self, MessageDigest},
+    md::Md,
+    md_ctx::MdCtx,
     nid::Nid,
-    pkey::{PKey, Private, Public},
+    pkey::{Id, PKey, Private, Public},
+    pkey_ctx::PkeyCtxRef,
     sha,
     sign::{Signer, Verifier},
     symm,
 };
 
 #[cfg(feature = "pki")]
-use openssl::{derive::Deriver, rand};
+use openssl::{derive::Deriver, pkey_ctx::PkeyCtx, rand};
 
 use regex::bytes::Regex;
 use secrecy::{ExposeSecret, SecretString};
 use std::{collections::HashMap, fmt, io, path::PathBuf, rc::Rc};
 
-use super::{BackendError, BackendResult, BackendTrait};
+use super::{BackendError, BackendResult, BackendTrait, SignatureVerification};
 use crate::security::{
     signature::{EcdsaSignature, EcdsaSignatureInner},
     DirectoryStorage, EcKeyType, EccPoint, EcdsaKey, EciesKey, HashAlgorithm,
@@ -30,7 +34,7 @@ use crate::security::{
 use crate::security::KeyPair;
 
 #[cfg(feature = "pki")]
-use super::PkiBackendTrait;
+use super::{PkiBackendTrait, Sm2Ciphertext};
 
 #[derive(Debug)]
 pub struct OpensslBackendConfig {
@@ -42,6 +46,8 @@ pub struct OpensslBackendConfig {
     ec_key_filename: String,
     /// AT keys filename prefix.
     at_key_filename_prefix: String,
+    /// AT encryption keys filename prefix.
+    at_enc_key_filename_prefix: String,
 }
 
 impl OpensslBackendConfig {
@@ -61,6 +67,7 @@ impl Default for OpensslBackendConfig {
             canonical_key_filename: "canonical.pem".into(),
             ec_key_filename: "EC.pem".into(),
             at_key_filename_prefix: "AT_".into(),
+            at_enc_key_filename_prefix: "AT_ENC_".into(),
         }
     }
 }
@@ -108,6 +115,8 @@ pub struct OpensslBackend {
     ec_cert_rekeying_secret_key: Option<EcKey<Private>>,
     /// AT certificates secret keys. Used to sign the messages over the air.
     at_certs_secret_keys: HashMap<usize, EcKey<Private>>,
+    /// AT certificates encryption secret keys. Used to decrypt the messages addressed to us.
+    at_certs_encryption_keys: HashMap<usize, EcKey<Private>>,
     /// Index of the current AT certificate secret key used for signing.
     current_at_id: Option<usize>,
 }
@@ -125,10 +134,41 @@ impl OpensslBackend {
             &storage,
         )?;
 
-        let exp = config.at_key_filename_prefix.clone() + "([0-9]+).pem";
+        let at_certs_secret_keys =
+            Self::load_indexed_secret_keys(&config.at_key_filename_prefix, &config, &storage)?;
+        let at_certs_encryption_keys =
+            Self::load_indexed_secret_keys(&config.at_enc_key_filename_prefix, &config, &storage)?;
+
+        // Check EC secret key permissions and load it if exist.
+        let ec_cert_secret_key = Self::load_secret_key(
+            config.ec_key_filename.clone(),
+            &config.keys_password,
+            &storage,
+        )?;
+
+        Ok(Self {
+            config,
+            storage,
+            canonical_secret_key,
+            ec_cert_secret_key,
+            ec_cert_rekeying_secret_key: None,
+            at_certs_secret_keys,
+            at_certs_encryption_keys,
+            current_at_id: None,
+        })
+    }
+
+    /// Load the secret keys stored in files named `prefix` followed by
+    /// the key index, ie: `AT_3.pem`.
+    fn load_indexed_secret_keys(
+        prefix: &str,
+        config: &OpensslBackendConfig,
+        storage: &DirectoryStorage,
+    ) -> OpensslBackendResult<HashMap<usize, EcKey<Private>>> {
+        let exp = prefix.to_string() + "([0-9]+).pem";
         let regex = Regex::new(exp.as_str()).map_err(OpensslBackendError::Regex)?;
 
-        let at_key_files: Vec<(String, usize)> = storage
+        let key_files: Vec<(String, usize)> = storage
             .list_private_files_where(|name| {
                 let Some(caps) = regex.captures(name.as_bytes()) else {
                     return (false, 0);
@@ -150,33 +190,17 @@ impl OpensslBackend {
             })
             .map_err(OpensslBackendError::Io)?;
 
-        let mut at_certs_secret_keys = HashMap::new();
-        for (name, id) in at_key_files {
-            let Some(at_cert_secret_key) =
-                Self::load_secret_key(name, &config.keys_password, &storage)?
+        let mut keys = HashMap::new();
+        for (name, id) in key_files {
+            let Some(secret_key) = Self::load_secret_key(name, &config.keys_password, storage)?
             else {
                 continue;
             };
 
-            at_certs_secret_keys.insert(id, at_cert_secret_key);
+            keys.insert(id, secret_key);
         }
 
-        // Check EC secret key permissions and load it if exist.
-        let ec_cert_secret_key = Self::load_secret_key(
-            config.ec_key_filename.clone(),
-            &config.keys_password,
-            &storage,
-        )?;
-
-        Ok(Self {
-            config,
-            storage,
-            canonical_secret_key,
-            ec_cert_secret_key,
-            ec_cert_rekeying_secret_key: None,
-            at_certs_secret_keys,
-            current_at_id: None,
-        })
+        Ok(keys)
     }
 
     /// Generate a secret key for a given `key_type`.
@@ -293,6 +317,10 @@ impl OpensslBackend {
     }
 
     fn sign(&self, data: &[u8], ec_key: &EcKey<Private>) -> BackendResult<EcdsaSignature> {
+        if ec_key.group().curve_name() == Some(Nid::SM2) {
+            return sign_sm2(data, ec_key);
+        }
+
         let (msg_digest, sig_size) = match ec_key.group().curve_name() {
             Some(Nid::BRAINPOOL_P256R1) | Some(Nid::X9_62_PRIME256V1) => {
                 (MessageDigest::sha256(), 32)
@@ -342,81 +370,40 @@ impl BackendTrait for OpensslBackend {
         verification_key: EcdsaKey,
         data: &[u8],
     ) -> BackendResult<bool> {
-        let msg_digest = match signature.hash_algorithm() {
-            HashAlgorithm::SHA256 => MessageDigest::sha256(),
-            HashAlgorithm::SHA384 => MessageDigest::sha384(),
-            HashAlgorithm::SM3 => MessageDigest::sm3(),
-        };
+        verify_ecdsa_signature(signature, verification_key, data)
+    }
 
-        let (nid, point, signature) = match (verification_key, signature) {
-            (EcdsaKey::NistP256r1(p), EcdsaSignature::NistP256r1(s)) => {
-                (Nid::X9_62_PRIME256V1, p, s)
-            }
-            (EcdsaKey::NistP384r1(p), EcdsaSignature::NistP384r1(s)) => (Nid::SECP384R1, p, s),
-            (EcdsaKey::BrainpoolP256r1(p), EcdsaSignature::BrainpoolP256r1(s)) => {
-                (Nid::BRAINPOOL_P256R1, p, s)
-            }
-            (EcdsaKey::BrainpoolP384r1(p), EcdsaSignature::BrainpoolP384r1(s)) => {
-                (Nid::BRAINPOOL_P384R1, p, s)
-            }
-            _ => return Err(BackendError::AlgorithmMismatch),
+    fn verify_signatures(&self, batch: &[SignatureVerification]) -> Vec<BackendResult<bool>> {
+        let verify = |v: &SignatureVerification| {
+            verify_ecdsa_signature(v.signature.clone(), v.key.clone(), &v.data)
         };
 
-        let group = EcGroup::from_curve_name(nid).map_err(BackendError::OpenSSL)?;
-
-        let mut ctx = BigNumContext::new().map_err(BackendError::OpenSSL)?;
-        let ec_point = match point {
-            EccPoint::CompressedY0(c) => {
-                // According to SECG SEC1 paragraph 2.3.4, Y0 starts with 02.
-                let buf = [vec![0x02], c].concat();
-                EcPoint::from_bytes(&group, &buf, &mut ctx).map_err(BackendError::OpenSSL)?
-            }
-            EccPoint::CompressedY1(c) => {
-                // According to SECG SEC1 paragraph 2.3.4, Y1 starts with 03.
-                let buf = [vec![0x03], c].concat();
-                EcPoint::from_bytes(&group, &buf, &mut ctx).map_err(BackendError::OpenSSL)?
-            }
-            EccPoint::Uncompressed(c) => {
-                let x = BigNum::from_slice(&c.x).map_err(BackendError::OpenSSL)?;
-                let y = BigNum::from_slice(&c.y).map_err(BackendError::OpenSSL)?;
-                let mut pub_key = EcPoint::new(&group).map_err(BackendError::OpenSSL)?;
-                pub_key
-                    .set_affine_coordinates_gfp(&group, &x, &y, &mut ctx)
-                    .map_err(BackendError::OpenSSL)?;
-                pub_key
-            }
-            _ => unreachable!(),
-        };
-
-        if !ec_point
-            .is_on_curve(&group, &mut ctx)
-            .map_err(BackendError::OpenSSL)?
-        {
-            return Err(BackendError::NotOnCurve);
+        let workers = verify_batch_workers(batch.len());
+        if workers == 1 {
+            return batch.iter().map(verify).collect();
         }
 
-        let ec_key = EcKey::from_public_key(&group, &ec_point).map_err(BackendError::OpenSSL)?;
-        ec_key.check_key().map_err(|_| BackendError::InvalidKey)?;
-
-        let key = PKey::from_ec_key(ec_key).map_err(BackendError::OpenSSL)?;
-
-        let r = match &signature.r {
-            EccPoint::XCoordinateOnly(c) => c,
-            EccPoint::CompressedY0(c) => c,
-            EccPoint::CompressedY1(c) => c,
-            EccPoint::Uncompressed(c) => &c.x,
-        };
-
-        let r = BigNum::from_slice(r).map_err(BackendError::OpenSSL)?;
-        let s = BigNum::from_slice(&signature.s).map_err(BackendError::OpenSSL)?;
-
-        let mut verifier = Verifier::new(msg_digest, &key).map_err(BackendError::OpenSSL)?;
-        verifier.update(data).map_err(BackendError::OpenSSL)?;
-
-        let signature = EcdsaSig::from_private_components(r, s).map_err(BackendError::OpenSSL)?;
-        let sig_der = signature.to_der().map_err(BackendError::OpenSSL)?;
-
-        verifier.verify(&sig_der).map_err(BackendError::OpenSSL)
+        let chunk_size = batch.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    (
+                        chunk.len(),
+                        scope.spawn(move || chunk.iter().map(verify).collect::<Vec<_>>()),
+                    )
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|(len, handle)| {
+                    handle.join().unwrap_or_else(|_| {
+                        (0..len).map(|_| Err(BackendError::InternalError)).collect()
+                    })
+                })
+                .collect()
+        })
     }
 
     fn generate_signature(&self, data: &[u8]) -> BackendResult<EcdsaSignature> {
@@ -615,6 +602,25 @@ impl PkiBackendTrait for OpensslBackend {
         PKey::from_ec_key(public_key).map_err(BackendError::OpenSSL)
     }
 
+    fn generate_authorization_ticket_encryption_keypair(
+        &mut self,
+        key_type: EcKeyType,
+        id: usize,
+    ) -> BackendResult<Self::BackendPublicKey> {
+        let name =
+            self.config.at_enc_key_filename_prefix.clone() + id.to_string().as_str() + ".pem";
+
+        let secret_key =
+            OpensslBackend::generate_secret_key(key_type).map_err(BackendError::OpenSSL)?;
+        let public_key = EcKey::from_public_key(secret_key.group(), secret_key.public_key())
+            .map_err(BackendError::OpenSSL)?;
+
+        Self::store_secret_key(&secret_key, name, &self.config.keys_password, &self.storage)?;
+        self.at_certs_encryption_keys.insert(id, secret_key);
+
+        PKey::from_ec_key(public_key).map_err(BackendError::OpenSSL)
+    }
+
     fn generate_ephemeral_keypair(
         &self,
         key_type: EcKeyType,
@@ -640,16 +646,78 @@ impl PkiBackendTrait for OpensslBackend {
         self.derive(&key, peer)
     }
 
+    fn derive_authorization_ticket(
+        &self,
+        id: usize,
+        peer: &Self::BackendPublicKey,
+    ) -> BackendResult<Vec<u8>> {
+        let Some(secret_key) = self.at_certs_encryption_keys.get(&id) else {
+            return Err(BackendError::NoKeyAtIndex);
+        };
+
+        let key = PKey::from_ec_key(secret_key.to_owned()).map_err(BackendError::OpenSSL)?;
+
+        self.derive(&key, peer)
+    }
+
     fn derive(
         &self,
         key: &Self::BackendSecretKey,
         peer: &Self::BackendPublicKey,
     ) -> BackendResult<Vec<u8>> {
+        // SM2 keys are used for encryption only, see PkiBackendTrait::sm2_encrypt.
+        if key.id() == Id::SM2 {
+            return Err(BackendError::UnsupportedKeyType);
+        }
+
         let mut deriver = Deriver::new(key).map_err(BackendError::OpenSSL)?;
         deriver.set_peer(peer).map_err(BackendError::OpenSSL)?;
         deriver.derive_to_vec().map_err(BackendError::OpenSSL)
     }
 
+    fn sm2_encrypt(
+        &self,
+        peer: &Self::BackendPublicKey,
+        data: &[u8],
+    ) -> BackendResult<Sm2Ciphertext<Self::BackendPublicKey>> {
+        encrypt_sm2(peer, data)
+    }
+
+    fn sm2_decrypt_canonical(
+        &self,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>> {
+        let Some(secret_key) = &self.canonical_secret_key else {
+            return Err(BackendError::NoCanonicalSecretKey);
+        };
+
+        let key = PKey::from_ec_key(secret_key.to_owned()).map_err(BackendError::OpenSSL)?;
+
+        decrypt_sm2(&key, ciphertext)
+    }
+
+    fn sm2_decrypt_authorization_ticket(
+        &self,
+        id: usize,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>> {
+        let Some(secret_key) = self.at_certs_encryption_keys.get(&id) else {
+            return Err(BackendError::NoKeyAtIndex);
+        };
+
+        let key = PKey::from_ec_key(secret_key.to_owned()).map_err(BackendError::OpenSSL)?;
+
+        decrypt_sm2(&key, ciphertext)
+    }
+
+    fn sm2_decrypt(
+        &self,
+        key: &Self::BackendSecretKey,
+        ciphertext: &Sm2Ciphertext<Self::BackendPublicKey>,
+    ) -> BackendResult<Vec<u8>> {
+        decrypt_sm2(key, ciphertext)
+    }
+
     fn generate_authorization_signature(
         &self,
         key_index: usize,
@@ -700,64 +768,21 @@ impl PkiBackendTrait for OpensslBackend {
     }
 
     fn encrypt_aes128_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>> {
-        // Cannot use encrypt_aead() with aes128_ccm. See https://github.com/openssl/openssl/issues/23302
-        let mut tag = [0u8; 16];
-        let mut encrypted = vec![];
-        let mut ctx = CipherCtx::new().map_err(BackendError::OpenSSL)?;
-
-        ctx.encrypt_init(Some(Cipher::aes_128_ccm()), Some(key), None)
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.set_iv_length(nonce.len())
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.set_tag_length(tag.len())
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.encrypt_init(None, Some(key), Some(nonce))
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.cipher_update_vec(data, &mut encrypted)
-            .map_err(BackendError::OpenSSL)?;
-        ctx.cipher_final_vec(&mut encrypted)
-            .map_err(BackendError::OpenSSL)?;
-        ctx.tag(&mut tag).map_err(BackendError::OpenSSL)?;
-
-        encrypted.extend_from_slice(&tag);
-
-        Ok(encrypted)
+        encrypt_ccm(Cipher::aes_128_ccm(), data, key, nonce)
     }
 
     fn decrypt_aes128_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>> {
-        if data.len() < 16 {
-            return Err(BackendError::InvalidData);
-        }
-
-        let encrypted = &data[..data.len() - 16];
-        let tag = &data[data.len() - 16..];
-        let mut output = vec![];
-
-        let mut ctx = CipherCtx::new().map_err(BackendError::OpenSSL)?;
-        ctx.decrypt_init(Some(Cipher::aes_128_ccm()), Some(key), None)
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.set_iv_length(nonce.len())
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.set_tag_length(tag.len())
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.decrypt_init(None, Some(key), Some(nonce))
-            .map_err(BackendError::OpenSSL)?;
-
-        ctx.set_tag(tag).map_err(BackendError::OpenSSL)?;
+        decrypt_ccm(Cipher::aes_128_ccm(), data, key, nonce)
+    }
 
-        ctx.cipher_update_vec(encrypted, &mut output)
-            .map_err(BackendError::OpenSSL)?;
-        ctx.cipher_final_vec(&mut output)
-            .map_err(BackendError::OpenSSL)?;
+    fn encrypt_sm4_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>> {
+        let cipher = Cipher::fetch(None, "SM4-CCM", None).map_err(BackendError::OpenSSL)?;
+        encrypt_ccm(&cipher, data, key, nonce)
+    }
 
-        Ok(output)
+    fn decrypt_sm4_ccm(&self, data: &[u8], key: &[u8], nonce: &[u8]) -> BackendResult<Vec<u8>> {
+        let cipher = Cipher::fetch(None, "SM4-CCM", None).map_err(BackendError::OpenSSL)?;
+        decrypt_ccm(&cipher, data, key, nonce)
     }
 
     fn hmac(
@@ -905,6 +930,7 @@ impl TryInto<EciesKey> for PKey<Public> {
         let res = match group.curve_name() {
             Some(Nid::X9_62_PRIME256V1) => EciesKey::NistP256r1(ecc_point),
             Some(Nid::BRAINPOOL_P256R1) => EciesKey::BrainpoolP256r1(ecc_point),
+            Some(Nid::SM2) => EciesKey::Sm2(ecc_point),
             _ => return Err(BackendError::UnsupportedKeyType),
         };
 
@@ -936,9 +962,432 @@ impl TryInto<EcdsaKey> for PKey<Public> {
             Some(Nid::SECP384R1) => EcdsaKey::NistP384r1(ecc_point),
             Some(Nid::BRAINPOOL_P256R1) => EcdsaKey::BrainpoolP256r1(ecc_point),
             Some(Nid::BRAINPOOL_P384R1) => EcdsaKey::BrainpoolP384r1(ecc_point),
+            Some(Nid::SM2) => EcdsaKey::Sm2(ecc_point),
             _ => return Err(BackendError::UnsupportedKeyType),
         };
 
         Ok(res)
     }
 }
+
+/// Minimum number of signatures verified by each worker thread of a batch verification.
+pub(crate) const VERIFY_BATCH_MIN_PER_WORKER: usize = 4;
+
+/// Number of worker threads verifying a batch of `len` signatures. The batch is spread over
+/// the available cores, with enough signatures per worker to amortize the thread spawning cost.
+pub(crate) fn verify_batch_workers(len: usize) -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(len / VERIFY_BATCH_MIN_PER_WORKER)
+        .max(1)
+}
+
+/// Verifies `data` slice `signature` with `verification_key`.
+fn verify_ecdsa_signature(
+    signature: EcdsaSignature,
+    verification_key: EcdsaKey,
+    data: &[u8],
+) -> BackendResult<bool> {
+    let msg_digest = match signature.hash_algorithm() {
+        HashAlgorithm::SHA256 => MessageDigest::sha256(),
+        HashAlgorithm::SHA384 => MessageDigest::sha384(),
+        HashAlgorithm::SM3 => MessageDigest::sm3(),
+    };
+
+    let (nid, point, signature) = match (verification_key, signature) {
+        (EcdsaKey::NistP256r1(p), EcdsaSignature::NistP256r1(s)) => (Nid::X9_62_PRIME256V1, p, s),
+        (EcdsaKey::NistP384r1(p), EcdsaSignature::NistP384r1(s)) => (Nid::SECP384R1, p, s),
+        (EcdsaKey::BrainpoolP256r1(p), EcdsaSignature::BrainpoolP256r1(s)) => {
+            (Nid::BRAINPOOL_P256R1, p, s)
+        }
+        (EcdsaKey::BrainpoolP384r1(p), EcdsaSignature::BrainpoolP384r1(s)) => {
+            (Nid::BRAINPOOL_P384R1, p, s)
+        }
+        (EcdsaKey::Sm2(p), EcdsaSignature::Sm2(s)) => (Nid::SM2, p, s),
+        _ => return Err(BackendError::AlgorithmMismatch),
+    };
+
+    let group = EcGroup::from_curve_name(nid).map_err(BackendError::OpenSSL)?;
+
+    let mut ctx = BigNumContext::new().map_err(BackendError::OpenSSL)?;
+    let ec_point = match point {
+        EccPoint::CompressedY0(c) => {
+            // According to SECG SEC1 paragraph 2.3.4, Y0 starts with 02.
+            let buf = [vec![0x02], c].concat();
+            EcPoint::from_bytes(&group, &buf, &mut ctx).map_err(BackendError::OpenSSL)?
+        }
+        EccPoint::CompressedY1(c) => {
+            // According to SECG SEC1 paragraph 2.3.4, Y1 starts with 03.
+            let buf = [vec![0x03], c].concat();
+            EcPoint::from_bytes(&group, &buf, &mut ctx).map_err(BackendError::OpenSSL)?
+        }
+        EccPoint::Uncompressed(c) => {
+            let x = BigNum::from_slice(&c.x).map_err(BackendError::OpenSSL)?;
+            let y = BigNum::from_slice(&c.y).map_err(BackendError::OpenSSL)?;
+            let mut pub_key = EcPoint::new(&group).map_err(BackendError::OpenSSL)?;
+            pub_key
+                .set_affine_coordinates_gfp(&group, &x, &y, &mut ctx)
+                .map_err(BackendError::OpenSSL)?;
+            pub_key
+        }
+        _ => unreachable!(),
+    };
+
+    if !ec_point
+        .is_on_curve(&group, &mut ctx)
+        .map_err(BackendError::OpenSSL)?
+    {
+        return Err(BackendError::NotOnCurve);
+    }
+
+    let ec_key = EcKey::from_public_key(&group, &ec_point).map_err(BackendError::OpenSSL)?;
+    ec_key.check_key().map_err(|_| BackendError::InvalidKey)?;
+
+    if nid == Nid::SM2 {
+        return verify_sm2_signature(ec_key, &signature, data);
+    }
+
+    let key = PKey::from_ec_key(ec_key).map_err(BackendError::OpenSSL)?;
+
+    let r = match &signature.r {
+        EccPoint::XCoordinateOnly(c) => c,
+        EccPoint::CompressedY0(c) => c,
+        EccPoint::CompressedY1(c) => c,
+        EccPoint::Uncompressed(c) => &c.x,
+    };
+
+    let r = BigNum::from_slice(r).map_err(BackendError::OpenSSL)?;
+    let s = BigNum::from_slice(&signature.s).map_err(BackendError::OpenSSL)?;
+
+    let mut verifier = Verifier::new(msg_digest, &key).map_err(BackendError::OpenSSL)?;
+    verifier.update(data).map_err(BackendError::OpenSSL)?;
+
+    let signature = EcdsaSig::from_private_components(r, s).map_err(BackendError::OpenSSL)?;
+    let sig_der = signature.to_der().map_err(BackendError::OpenSSL)?;
+
+    verifier.verify(&sig_der).map_err(BackendError::OpenSSL)
+}
+
+/// SM2 default distinguishing identifier, as defined in GB/T 35276 and required by YD/T 3957.
+const SM2_DEFAULT_USER_ID: &[u8] = b"1234567812345678";
+
+/// Size in bytes of the SM2 curve coordinates and signature components.
+const SM2_FIELD_SIZE: i32 = 32;
+
+extern "C" {
+    // Not exposed by `openssl-sys`, available since OpenSSL 3.0.
+    fn EVP_PKEY_CTX_set1_id(
+        ctx: *mut openssl_sys::EVP_PKEY_CTX,
+        id: *const core::ffi::c_void,
+        len: core::ffi::c_int,
+    ) -> core::ffi::c_int;
+}
+
+/// Sets the SM2 distinguishing identifier used to compute `ZA` on the `pctx` context.
+fn sm2_set_distid<T>(pctx: &mut PkeyCtxRef<T>) -> BackendResult<()> {
+    // SAFETY: `pctx` is a valid context and the identifier outlives the call, as OpenSSL
+    // copies it.
+    let res = unsafe {
+        EVP_PKEY_CTX_set1_id(
+            pctx.as_ptr(),
+            SM2_DEFAULT_USER_ID.as_ptr().cast(),
+            SM2_DEFAULT_USER_ID.len() as core::ffi::c_int,
+        )
+    };
+
+    if res <= 0 {
+        return Err(BackendError::OpenSSL(ErrorStack::get()));
+    }
+
+    Ok(())
+}
+
+/// Sign `data` with the SM2 `ec_key`, as defined in GB/T 32918.2, with the SM3 digest and the
+/// default distinguishing identifier.
+fn sign_sm2(data: &[u8], ec_key: &EcKey<Private>) -> BackendResult<EcdsaSignature> {
+    // SM2 curve keys are typed as SM2 keys by OpenSSL.
+    let key = PKey::from_ec_key(ec_key.to_owned()).map_err(BackendError::OpenSSL)?;
+    if key.id() != Id::SM2 {
+        return Err(BackendError::UnsupportedKeyType);
+    }
+
+    let mut ctx = MdCtx::new().map_err(BackendError::OpenSSL)?;
+    let pctx = ctx
+        .digest_sign_init(Some(Md::sm3()), &key)
+        .map_err(BackendError::OpenSSL)?;
+    sm2_set_distid(pctx)?;
+
+    let mut raw_signature = vec![];
+    ctx.digest_sign_to_vec(data, &mut raw_signature)
+        .map_err(BackendError::OpenSSL)?;
+
+    let signature = EcdsaSig::from_der(&raw_signature).map_err(BackendError::OpenSSL)?;
+    let sig_inner = EcdsaSignatureInner {
+        r: EccPoint::XCoordinateOnly(
+            signature
+                .r()
+                .to_vec_padded(SM2_FIELD_SIZE)
+                .map_err(BackendError::OpenSSL)?,
+        ),
+        s: signature
+            .s()
+            .to_vec_padded(SM2_FIELD_SIZE)
+            .map_err(BackendError::OpenSSL)?,
+    };
+
+    Ok(EcdsaSignature::Sm2(sig_inner))
+}
+
+/// Verifies the SM2 `signature` of `data` with the `ec_key`, as defined in GB/T 32918.2,
+/// with the SM3 digest and the default distinguishing identifier.
+fn verify_sm2_signature(
+    ec_key: EcKey<Public>,
+    signature: &EcdsaSignatureInner,
+    data: &[u8],
+) -> BackendResult<bool> {
+    let key = PKey::from_ec_key(ec_key).map_err(BackendError::OpenSSL)?;
+    if key.id() != Id::SM2 {
+        return Err(BackendError::UnsupportedKeyType);
+    }
+
+    let r = match &signature.r {
+        EccPoint::XCoordinateOnly(c) => c,
+        EccPoint::CompressedY0(c) => c,
+        EccPoint::CompressedY1(c) => c,
+        EccPoint::Uncompressed(c) => &c.x,
+    };
+
+    let r = BigNum::from_slice(r).map_err(BackendError::OpenSSL)?;
+    let s = BigNum::from_slice(&signature.s).map_err(BackendError::OpenSSL)?;
+    let sig_der = EcdsaSig::from_private_components(r, s)
+        .and_then(|sig| sig.to_der())
+        .map_err(BackendError::OpenSSL)?;
+
+    let mut ctx = MdCtx::new().map_err(BackendError::OpenSSL)?;
+    let pctx = ctx
+        .digest_verify_init(Some(Md::sm3()), &key)
+        .map_err(BackendError::OpenSSL)?;
+    sm2_set_distid(pctx)?;
+
+    // A malformed signature is reported as an error by OpenSSL.
+    Ok(ctx.digest_verify(data, &sig_der).unwrap_or(false))
+}
+
+/// Encrypt `data` for the SM2 `peer` public key, as defined in GB/T 32918.4.
+#[cfg(feature = "pki")]
+fn encrypt_sm2(peer: &PKey<Public>, data: &[u8]) -> BackendResult<Sm2Ciphertext<PKey<Public>>> {
+    if peer.id() != Id::SM2 {
+        return Err(BackendError::UnsupportedKeyType);
+    }
+
+    let mut ctx = PkeyCtx::new(peer).map_err(BackendError::OpenSSL)?;
+    ctx.encrypt_init().map_err(BackendError::OpenSSL)?;
+
+    let mut der = vec![];
+    ctx.encrypt_to_vec(data, &mut der)
+        .map_err(BackendError::OpenSSL)?;
+
+    let (x, y, tag, encrypted) =
+        sm2_ciphertext_from_der(&der).ok_or(BackendError::InternalError)?;
+
+    let group = EcGroup::from_curve_name(Nid::SM2).map_err(BackendError::OpenSSL)?;
+    let x = BigNum::from_slice(x).map_err(BackendError::OpenSSL)?;
+    let y = BigNum::from_slice(y).map_err(BackendError::OpenSSL)?;
+    let ephemeral_key = EcKey::from_public_key_affine_coordinates(&group, &x, &y)
+        .and_then(PKey::from_ec_key)
+        .map_err(BackendError::OpenSSL)?;
+
+    Ok(Sm2Ciphertext {
+        ephemeral_key,
+        data: encrypted.to_vec(),
+        tag: tag.to_vec(),
+    })
+}
+
+/// Decrypt the SM2 `ciphertext` with the SM2 `key`, as defined in GB/T 32918.4.
+/// Returns [BackendError::InvalidData] if the ciphertext cannot be authenticated.
+#[cfg(feature = "pki")]
+fn decrypt_sm2(
+    key: &PKey<Private>,
+    ciphertext: &Sm2Ciphertext<PKey<Public>>,
+) -> BackendResult<Vec<u8>> {
+    if key.id() != Id::SM2 {
+        return Err(BackendError::UnsupportedKeyType);
+    }
+
+    let ephemeral_key = ciphertext
+        .ephemeral_key
+        .ec_key()
+        .map_err(BackendError::OpenSSL)?;
+
+    let mut bn_ctx = BigNumContext::new().map_err(BackendError::OpenSSL)?;
+    let mut x = BigNum::new().map_err(BackendError::OpenSSL)?;
+    let mut y = BigNum::new().map_err(BackendError::OpenSSL)?;
+    ephemeral_key
+        .public_key()
+        .affine_coordinates(ephemeral_key.group(), &mut x, &mut y, &mut bn_ctx)
+        .map_err(BackendError::OpenSSL)?;
+
+    let der = sm2_ciphertext_to_der(&x.to_vec(), &y.to_vec(), &ciphertext.tag, &ciphertext.data);
+
+    let mut ctx = PkeyCtx::new(key).map_err(BackendError::OpenSSL)?;
+    ctx.decrypt_init().map_err(BackendError::OpenSSL)?;
+
+    let mut output = vec![];
+    ctx.decrypt_to_vec(&der, &mut output)
+        .map_err(|_| BackendError::InvalidData)?;
+
+    Ok(output)
+}
+
+/// Encodes the SM2 ciphertext DER structure expected by OpenSSL, as defined in GB/T 35276:
+/// `SEQUENCE { x INTEGER, y INTEGER, hash OCTET STRING, ciphertext OCTET STRING }`.
+#[cfg(feature = "pki")]
+fn sm2_ciphertext_to_der(x: &[u8], y: &[u8], tag: &[u8], data: &[u8]) -> Vec<u8> {
+    fn push_tlv(buf: &mut Vec<u8>, tag: u8, value: &[u8]) {
+        buf.push(tag);
+        match value.len() {
+            len @ 0..=0x7f => buf.push(len as u8),
+            len @ 0x80..=0xff => buf.extend_from_slice(&[0x81, len as u8]),
+            len => {
+                buf.push(0x82);
+                buf.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        buf.extend_from_slice(value);
+    }
+
+    fn integer(value: &[u8]) -> Vec<u8> {
+        let value = match value.iter().position(|b| *b != 0) {
+            Some(pos) => &value[pos..],
+            None => &[0],
+        };
+
+        // Integers are signed, prepend a zero byte to keep them positive.
+        match value.first() {
+            Some(b) if b & 0x80 != 0 => [&[0], value].concat(),
+            _ => value.to_vec(),
+        }
+    }
+
+    let mut content = vec![];
+    push_tlv(&mut content, 0x02, &integer(x));
+    push_tlv(&mut content, 0x02, &integer(y));
+    push_tlv(&mut content, 0x04, tag);
+    push_tlv(&mut content, 0x04, data);
+
+    let mut der = vec![];
+    push_tlv(&mut der, 0x30, &content);
+    der
+}
+
+/// Decodes the SM2 ciphertext DER structure returned by OpenSSL.
+/// Returns the `x` and `y` coordinates of `C1`, the `C3` hash and the `C2` ciphertext.
+#[cfg(feature = "pki")]
+#[allow(clippy::type_complexity)]
+fn sm2_ciphertext_from_der(der: &[u8]) -> Option<(&[u8], &[u8], &[u8], &[u8])> {
+    fn read_tlv(buf: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+        let (&t, buf) = buf.split_first()?;
+        if t != tag {
+            return None;
+        }
+
+        let (&len, buf) = buf.split_first()?;
+        let (len, buf) = match len {
+            0..=0x7f => (len as usize, buf),
+            0x81 => (*buf.first()? as usize, buf.get(1..)?),
+            0x82 => (
+                u16::from_be_bytes([*buf.first()?, *buf.get(1)?]) as usize,
+                buf.get(2..)?,
+            ),
+            _ => return None,
+        };
+
+        (buf.len() >= len).then(|| buf.split_at(len))
+    }
+
+    let (content, _) = read_tlv(der, 0x30)?;
+    let (x, content) = read_tlv(content, 0x02)?;
+    let (y, content) = read_tlv(content, 0x02)?;
+    let (tag, content) = read_tlv(content, 0x04)?;
+    let (data, _) = read_tlv(content, 0x04)?;
+
+    Some((x, y, tag, data))
+}
+
+/// Encrypt `data` with the CCM mode `cipher`, using the provided `key` and `nonce`.
+/// The generated tag is appended to the encrypted data.
+#[cfg(feature = "pki")]
+fn encrypt_ccm(
+    cipher: &CipherRef,
+    data: &[u8],
+    key: &[u8],
+    nonce: &[u8],
+) -> BackendResult<Vec<u8>> {
+    // Cannot use encrypt_aead() with CCM ciphers. See https://github.com/openssl/openssl/issues/23302
+    let mut tag = [0u8; 16];
+    let mut encrypted = vec![];
+    let mut ctx = CipherCtx::new().map_err(BackendError::OpenSSL)?;
+
+    ctx.encrypt_init(Some(cipher), Some(key), None)
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.set_iv_length(nonce.len())
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.set_tag_length(tag.len())
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.encrypt_init(None, Some(key), Some(nonce))
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.cipher_update_vec(data, &mut encrypted)
+        .map_err(BackendError::OpenSSL)?;
+    ctx.cipher_final_vec(&mut encrypted)
+        .map_err(BackendError::OpenSSL)?;
+    ctx.tag(&mut tag).map_err(BackendError::OpenSSL)?;
+
+    encrypted.extend_from_slice(&tag);
+
+    Ok(encrypted)
+}
+
+/// Decrypt `data` with the CCM mode `cipher`, using the provided `key` and `nonce`.
+/// The tag is expected to be at the end of the encrypted data.
+#[cfg(feature = "pki")]
+fn decrypt_ccm(
+    cipher: &CipherRef,
+    data: &[u8],
+    key: &[u8],
+    nonce: &[u8],
+) -> BackendResult<Vec<u8>> {
+    if data.len() < 16 {
+        return Err(BackendError::InvalidData);
+    }
+
+    let encrypted = &data[..data.len() - 16];
+    let tag = &data[data.len() - 16..];
+    let mut output = vec![];
+
+    let mut ctx = CipherCtx::new().map_err(BackendError::OpenSSL)?;
+    ctx.decrypt_init(Some(cipher), Some(key), None)
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.set_iv_length(nonce.len())
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.set_tag_length(tag.len())
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.decrypt_init(None, Some(key), Some(nonce))
+        .map_err(BackendError::OpenSSL)?;
+
+    ctx.set_tag(tag).map_err(BackendError::OpenSSL)?;
+
+    ctx.cipher_update_vec(encrypted, &mut output)
+        .map_err(BackendError::OpenSSL)?;
+    ctx.cipher_final_vec(&mut output)
+        .map_err(BackendError::OpenSSL)?;
+
+    Ok(output)
+}
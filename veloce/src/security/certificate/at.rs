@@ -82,9 +82,11 @@ impl CertificateTrait for AuthorizationTicketCertificate {
         use ieee1609_dot2::CertificateId;
 
         // The component issuer shall be set to sha256AndDigest or sha384AndDigest as defined in IEEE
-        // Std 1609.2 clause 6.4.7.
+        // Std 1609.2 clause 6.4.7, or sm3AndDigest for the YD/T 3957 profile.
         match cert.0.issuer {
-            IssuerIdentifier::sha256AndDigest(_) | IssuerIdentifier::sha384AndDigest(_) => {}
+            IssuerIdentifier::sha256AndDigest(_)
+            | IssuerIdentifier::sha384AndDigest(_)
+            | IssuerIdentifier::sm3AndDigest(_) => {}
             _ => return Err(CertificateError::Malformed),
         }
 
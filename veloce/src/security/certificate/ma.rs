@@ -0,0 +1,115 @@
+use veloce_asn1::{
+    defs::etsi_103097_v211::ieee1609_dot2::{
+        self, Certificate as EtsiCertificate, IssuerIdentifier,
+    },
+    prelude::rasn,
+};
+
+use crate::security::backend::BackendTrait;
+
+use super::{
+    Certificate, CertificateError, CertificateResult, CertificateTrait, ExplicitCertificate,
+};
+
+/// Misbehavior Authority certificate type.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MisbehaviorAuthorityCertificate {
+    /// Raw COER encoded MA certificate.
+    raw: Vec<u8>,
+    /// Inner certificate.
+    inner: EtsiCertificate,
+}
+
+impl MisbehaviorAuthorityCertificate {
+    /// Constructs from a raw ETSI Certificate.
+    /// This method must verify if the certificate is valid relative to a Misbehavior Authority certificate Asn.1 constraints.
+    /// Certificate has to be canonicalized if necessary.
+    pub fn from_etsi_cert<B>(cert: EtsiCertificate, backend: &B) -> CertificateResult<Self>
+    where
+        B: BackendTrait + ?Sized,
+    {
+        Certificate::verify_ieee_constraints(&cert)?;
+        Certificate::verify_etsi_constraints(&cert)?;
+        Self::verify_constraints(&cert)?;
+        let inner = Self::canonicalize(cert, backend)?;
+
+        let raw = rasn::coer::encode(&inner).map_err(|_| CertificateError::Asn1)?;
+
+        Ok(Self { raw, inner })
+    }
+}
+
+impl ExplicitCertificate for MisbehaviorAuthorityCertificate {}
+
+impl CertificateTrait for MisbehaviorAuthorityCertificate {
+    type CertificateType = Self;
+
+    fn from_bytes<B>(bytes: &[u8], backend: &B) -> CertificateResult<Self::CertificateType>
+    where
+        B: BackendTrait + ?Sized,
+    {
+        let raw_cert =
+            rasn::coer::decode::<EtsiCertificate>(bytes).map_err(|_| CertificateError::Asn1)?;
+
+        Self::from_etsi_cert(raw_cert, backend)
+    }
+
+    fn inner(&self) -> &EtsiCertificate {
+        &self.inner
+    }
+
+    fn raw_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    fn verify_constraints(cert: &EtsiCertificate) -> CertificateResult<()> {
+        use ieee1609_dot2::{CertificateId, CertificateType};
+
+        // The certificate shall be of type explicit as specified in IEEE Std 1609.2, clause 6.4.6.
+        match cert.0.r_type {
+            CertificateType::explicit => {}
+            _ => return Err(CertificateError::Malformed),
+        };
+
+        // The MA certificate is issued by the Root CA, so the component issuer shall be set to
+        // sha256AndDigest or sha384AndDigest as defined in IEEE Std 1609.2 clause 6.4.7, or
+        // sm3AndDigest for the YD/T 3957 profile.
+        match cert.0.issuer {
+            IssuerIdentifier::sha256AndDigest(_)
+            | IssuerIdentifier::sha384AndDigest(_)
+            | IssuerIdentifier::sm3AndDigest(_) => {}
+            _ => return Err(CertificateError::Malformed),
+        }
+
+        // This component shall contain a public encryption key for ITS-Stations to encrypt
+        // misbehavior reports to the misbehavior authority.
+        if cert.0.to_be_signed.encryption_key.is_none() {
+            return Err(CertificateError::Malformed);
+        }
+
+        // The MA does not issue certificates, so certIssuePermissions shall be absent.
+        if cert.0.to_be_signed.cert_issue_permissions.is_some() {
+            return Err(CertificateError::Malformed);
+        }
+
+        // appPermissions: this component shall be used to indicate message signing permissions, i.e.
+        // permissions to sign the messages sent by the MA to the ITS-Stations.
+        let Some(app_permissions) = &cert.0.to_be_signed.app_permissions else {
+            return Err(CertificateError::Malformed);
+        };
+
+        if app_permissions.0.is_empty() {
+            return Err(CertificateError::Malformed);
+        }
+
+        // The toBeSigned component CertificateId shall be set to the choice name contain a unique name associated to
+        // the misbehavior authority, or shall be set to the choice none.
+        match cert.0.to_be_signed.id {
+            CertificateId::name(_) | CertificateId::none(_) => {}
+            _ => return Err(CertificateError::UnexpectedId),
+        }
+
+        Ok(())
+    }
+}
@@ -23,12 +23,14 @@ use super::{
 
 mod at;
 mod ec;
+mod ma;
 mod root;
 mod subordinate;
 mod tlm;
 
 pub use at::AuthorizationTicketCertificate;
 pub use ec::EnrollmentCredentialCertificate;
+pub use ma::MisbehaviorAuthorityCertificate;
 pub use root::RootCertificate;
 pub use subordinate::AuthorizationAuthorityCertificate;
 pub use subordinate::EnrollmentAuthorityCertificate;
@@ -370,6 +372,7 @@ pub trait CertificateTrait {
                 EcdsaSignature::BrainpoolP384r1(s) => {
                     EcdsaSignature::BrainpoolP384r1(canonicalize_sig(s))
                 }
+                EcdsaSignature::Sm2(s) => EcdsaSignature::Sm2(canonicalize_sig(s)),
             };
 
             certificate.0.signature = Some(sig.try_into().map_err(CertificateError::Signature)?);
@@ -379,17 +382,22 @@ pub trait CertificateTrait {
     }
 
     /// Computes the hash of the certificate, using the provided `algorithm` [HashAlgorithm].
-    fn hash<B>(&self, algorithm: HashAlgorithm, backend: &B) -> Vec<u8>
+    fn hash<B>(&self, algorithm: HashAlgorithm, backend: &B) -> CertificateResult<Vec<u8>>
     where
         B: BackendTrait + ?Sized,
     {
         let cert_bytes = self.raw_bytes();
 
-        match algorithm {
+        let res = match algorithm {
             HashAlgorithm::SHA256 => backend.sha256(cert_bytes).into(),
             HashAlgorithm::SHA384 => backend.sha384(cert_bytes).into(),
-            _ => unimplemented!(),
-        }
+            HashAlgorithm::SM3 => backend
+                .sm3(cert_bytes)
+                .map_err(CertificateError::Backend)?
+                .into(),
+        };
+
+        Ok(res)
     }
 
     /// Verifies the Asn.1 constraints on the enclosed certificate.
@@ -455,7 +463,7 @@ pub trait ExplicitCertificate: CertificateTrait {
 
         let signer_id = match signer {
             Issuer::SelfSigned(_) => return Err(CertificateError::UnexpectedIssuer),
-            Issuer::SHA256Digest(h) | Issuer::SHA384Digest(h) => h,
+            Issuer::SHA256Digest(h) | Issuer::SHA384Digest(h) | Issuer::SM3Digest(h) => h,
         };
 
         // Get matching signer certificate.
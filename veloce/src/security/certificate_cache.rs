@@ -10,6 +10,7 @@ use std::collections::BTreeMap;
 use crate::config::SEC_CERT_CACHE_ENTRY_LIFETIME;
 use crate::security::certificate::CertificateTrait;
 use crate::time::Instant;
+use crate::wire::EthernetAddress;
 
 use super::certificate::AuthorizationTicketCertificate;
 use super::HashedId8;
@@ -25,6 +26,8 @@ pub struct CachedCertificate {
     certificate: AuthorizationTicketCertificate,
     /// Expiration time.
     expires_at: Instant,
+    /// Hardware address of the certificate holder, as observed in its messages.
+    address: Option<EthernetAddress>,
 }
 
 #[derive(Debug)]
@@ -50,9 +53,12 @@ impl CertificateCache {
         let cert_expiration = certificate.validity_period().end().as_unix_instant();
         let expires_at = cache_expiration.min(cert_expiration);
 
+        // Keep the address bound to a refreshed certificate.
+        let address = self.storage.get(&digest).and_then(|e| e.address);
         let cached_cert = CachedCertificate {
             certificate,
             expires_at,
+            address,
         };
 
         // Evict expired mappings.
@@ -95,6 +101,44 @@ impl CertificateCache {
         })
     }
 
+    /// Bind the certificate identified with `digest` to the `address` hardware address of its
+    /// holder, ie: the source address of a verified message signed with this certificate.
+    /// Any previous binding of `address` to another certificate is removed.
+    pub(crate) fn bind_address(&mut self, digest: &HashedId8, address: EthernetAddress) {
+        if !self.storage.contains_key(digest) {
+            return;
+        }
+
+        for (k, e) in self.storage.iter_mut() {
+            if k == digest {
+                e.address = Some(address);
+            } else if e.address == Some(address) {
+                e.address = None;
+            }
+        }
+    }
+
+    /// Search the certificate cache for a certificate bound to the `address` hardware address,
+    /// see [CertificateCache::bind_address], or whose digest maps to `address` for stations
+    /// deriving their address from their certificate.
+    /// Returns an option containing the certificate, if any.
+    pub(crate) fn lookup_by_address(
+        &self,
+        address: &EthernetAddress,
+        timestamp: Instant,
+    ) -> Option<AuthorizationTicketCertificate> {
+        let mut valid = self
+            .storage
+            .iter()
+            .filter(|(_, e)| e.expires_at > timestamp);
+
+        valid
+            .clone()
+            .find(|(_, e)| e.address == Some(*address))
+            .or_else(|| valid.find(|(k, _)| k.into_ethernet_address() == *address))
+            .map(|(_, e)| e.certificate.to_owned())
+    }
+
     /// Removes all the entries of the Certificate Cache.
     #[allow(unused)]
     pub fn clear(&mut self) {
@@ -3,7 +3,8 @@ use core::fmt;
 use veloce_asn1::{
     defs::etsi_103097_v211::{
         ieee1609_dot2::{
-            AesCcmCiphertext as EtsiAesCcmCiphertext, SymmetricCiphertext as EtsiCiphertext,
+            AesCcmCiphertext as EtsiAesCcmCiphertext, SM4CcmCiphertext as EtsiSm4CcmCiphertext,
+            SymmetricCiphertext as EtsiCiphertext,
         },
         ieee1609_dot2_base_types::Opaque as EtsiOpaque,
     },
@@ -35,6 +36,8 @@ impl fmt::Display for CiphertextError {
 pub enum Ciphertext {
     /// AES-128 CCM ciphertext.
     Aes128Ccm(CiphertextInner),
+    /// SM4 CCM ciphertext.
+    Sm4Ccm(CiphertextInner),
 }
 
 impl Ciphertext {
@@ -45,6 +48,21 @@ impl Ciphertext {
             data: OctetString::from(data),
         })
     }
+
+    /// Create an SM4 CCM ciphertext from `nonce` and `data`.
+    pub fn new_sm4_ccm(nonce: Vec<u8>, data: Vec<u8>) -> Self {
+        Self::Sm4Ccm(CiphertextInner {
+            nonce,
+            data: OctetString::from(data),
+        })
+    }
+
+    /// Get the inner representation of the ciphertext.
+    pub fn inner(&self) -> &CiphertextInner {
+        match self {
+            Ciphertext::Aes128Ccm(i) | Ciphertext::Sm4Ccm(i) => i,
+        }
+    }
 }
 
 /// Inner representation of the ciphertext.
@@ -68,6 +86,10 @@ impl TryFrom<&EtsiCiphertext> for Ciphertext {
                     data: aes_ccm_ciphertext.ccm_ciphertext.0.clone(),
                 })
             }
+            EtsiCiphertext::sm4Ccm(sm4_ccm_ciphertext) => Ciphertext::Sm4Ccm(CiphertextInner {
+                nonce: sm4_ccm_ciphertext.nonce.to_vec(),
+                data: sm4_ccm_ciphertext.ccm_ciphertext.0.clone(),
+            }),
             _ => return Err(CiphertextError::UnsupportedType),
         };
 
@@ -89,6 +111,15 @@ impl TryInto<EtsiCiphertext> for Ciphertext {
                 ),
                 ccm_ciphertext: EtsiOpaque(inner.data),
             }),
+            Ciphertext::Sm4Ccm(inner) => EtsiCiphertext::sm4Ccm(EtsiSm4CcmCiphertext {
+                nonce: FixedOctetString::<12>::new(
+                    inner
+                        .nonce
+                        .try_into()
+                        .map_err(|_| CiphertextError::UnsupportedNonce)?,
+                ),
+                ccm_ciphertext: EtsiOpaque(inner.data),
+            }),
         };
 
         Ok(res)
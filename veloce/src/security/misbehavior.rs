@@ -0,0 +1,478 @@
+//! Misbehavior detection runs local plausibility checks on the mobility data of received
+//! messages, complementing the signature and permissions verification of the security
+//! service. A validly signed message is not necessarily a truthful one: a sender can
+//! advertise positions it cannot physically reach, or a speed unrelated to its movement.
+//!
+//! Detections are accounted per sender certificate, identified by its [HashedId8], and trigger
+//! a [MisbehaviorReaction] configured per [MisbehaviorCheck].
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map::BTreeMap;
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use core::fmt;
+
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Time},
+    length::meter,
+    time::millisecond,
+    velocity::meter_per_second,
+};
+
+use veloce_asn1::defs::etsi_103097_v211::{
+    ieee1609_dot2::Certificate as EtsiCertificate, ieee1609_dot2_base_types::ThreeDLocation,
+};
+
+use crate::{
+    common::{geo_area::GeoPosition, PotiPathPoint, PotiPositionHistory},
+    config::{
+        GN_DEFAULT_MAX_COMMUNICATION_RANGE, SEC_MBD_HISTORY_LENGTH, SEC_MBD_SENDER_ENTRY_LIFETIME,
+    },
+    time::{Duration, Instant, TAI2004},
+    types::{Heading, Speed},
+};
+
+use super::{permission::AID, HashedId8};
+
+/// Plausibility checks executed by the misbehavior detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MisbehaviorCheck {
+    /// Sender position moved further than the maximum plausible speed allows.
+    PositionJump,
+    /// Advertised speed is inconsistent with the distance travelled since the previous message.
+    SpeedConsistency,
+    /// Advertised heading is inconsistent with the course travelled since the previous message.
+    HeadingConsistency,
+    /// Sender is further than the maximum communication range.
+    CommunicationRange,
+    /// Message generation time is too far from the local time.
+    Freshness,
+    /// Position in the message does not match the position of the sender in the Location Table.
+    BeaconMismatch,
+    /// Event position is further than the maximum event distance from the sender position.
+    EventRange,
+    /// Event detection time is later than the message generation time.
+    DetectionTime,
+}
+
+impl fmt::Display for MisbehaviorCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MisbehaviorCheck::PositionJump => write!(f, "position jump"),
+            MisbehaviorCheck::SpeedConsistency => write!(f, "speed consistency"),
+            MisbehaviorCheck::HeadingConsistency => write!(f, "heading consistency"),
+            MisbehaviorCheck::CommunicationRange => write!(f, "communication range"),
+            MisbehaviorCheck::Freshness => write!(f, "freshness"),
+            MisbehaviorCheck::BeaconMismatch => write!(f, "beacon mismatch"),
+            MisbehaviorCheck::EventRange => write!(f, "event range"),
+            MisbehaviorCheck::DetectionTime => write!(f, "detection time"),
+        }
+    }
+}
+
+/// Reaction to a misbehavior detection, sorted by increasing severity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MisbehaviorReaction {
+    /// Detection is not accounted.
+    Ignore,
+    /// Detection is accounted for the sender, and the message is delivered flagged as implausible.
+    #[default]
+    Flag,
+    /// Detection is accounted for the sender, and the message is dropped.
+    Drop,
+    /// Detection is accounted for the sender, the message is dropped and all subsequent
+    /// messages from the sender are rejected for [MisbehaviorConfig::blacklist_duration].
+    Blacklist,
+}
+
+/// Reaction configured for each [MisbehaviorCheck].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MisbehaviorReactions {
+    /// Reaction on [MisbehaviorCheck::PositionJump] detection.
+    pub position_jump: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::SpeedConsistency] detection.
+    pub speed_consistency: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::HeadingConsistency] detection.
+    pub heading_consistency: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::CommunicationRange] detection.
+    pub communication_range: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::Freshness] detection.
+    pub freshness: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::BeaconMismatch] detection.
+    pub beacon_mismatch: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::EventRange] detection.
+    pub event_range: MisbehaviorReaction,
+    /// Reaction on [MisbehaviorCheck::DetectionTime] detection.
+    pub detection_time: MisbehaviorReaction,
+}
+
+impl MisbehaviorReactions {
+    /// Get the reaction configured for `check`.
+    pub fn get(&self, check: MisbehaviorCheck) -> MisbehaviorReaction {
+        match check {
+            MisbehaviorCheck::PositionJump => self.position_jump,
+            MisbehaviorCheck::SpeedConsistency => self.speed_consistency,
+            MisbehaviorCheck::HeadingConsistency => self.heading_consistency,
+            MisbehaviorCheck::CommunicationRange => self.communication_range,
+            MisbehaviorCheck::Freshness => self.freshness,
+            MisbehaviorCheck::BeaconMismatch => self.beacon_mismatch,
+            MisbehaviorCheck::EventRange => self.event_range,
+            MisbehaviorCheck::DetectionTime => self.detection_time,
+        }
+    }
+}
+
+/// Misbehavior detection configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MisbehaviorConfig {
+    /// Maximum plausible speed of a station.
+    pub max_speed: Speed,
+    /// Position error tolerated by the position based checks.
+    pub position_tolerance: Length,
+    /// Error tolerated between the advertised speed and the travelled speed.
+    pub speed_tolerance: Speed,
+    /// Error tolerated between the advertised heading and the travelled course.
+    pub heading_tolerance: Angle,
+    /// Minimum travelled distance for the travelled course to be compared to the heading.
+    pub heading_min_distance: Length,
+    /// Maximum distance between the sender and the local station.
+    pub max_range: Length,
+    /// Maximum difference between the message generation time and the local time.
+    pub max_age: Duration,
+    /// Distance tolerated between the position in the message and the Location Table one.
+    pub beacon_tolerance: Length,
+    /// Maximum distance between the sender and the event it reports.
+    pub max_event_distance: Length,
+    /// Duration a sender is blacklisted for.
+    pub blacklist_duration: Duration,
+    /// Reactions on detection.
+    pub reactions: MisbehaviorReactions,
+}
+
+impl Default for MisbehaviorConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: Speed::new::<meter_per_second>(70.0),
+            position_tolerance: Length::new::<meter>(10.0),
+            speed_tolerance: Speed::new::<meter_per_second>(5.0),
+            heading_tolerance: Angle::new::<degree>(45.0),
+            heading_min_distance: Length::new::<meter>(20.0),
+            max_range: Length::new::<meter>(GN_DEFAULT_MAX_COMMUNICATION_RANGE),
+            max_age: Duration::from_secs(2),
+            beacon_tolerance: Length::new::<meter>(20.0),
+            max_event_distance: Length::new::<meter>(2000.0),
+            blacklist_duration: Duration::from_secs(300),
+            reactions: MisbehaviorReactions::default(),
+        }
+    }
+}
+
+/// Mobility data of a received message, submitted to the plausibility checks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observation {
+    /// Generation time of the message.
+    pub timestamp: TAI2004,
+    /// Position of the sender contained in the message.
+    pub position: Option<GeoPosition>,
+    /// Speed of the sender contained in the message.
+    pub speed: Option<Speed>,
+    /// Heading of the sender contained in the message.
+    pub heading: Option<Heading>,
+    /// Position of the local station.
+    pub ego_position: Option<GeoPosition>,
+    /// Position of the sender in the Location Table, with the time it was generated at.
+    pub beacon_position: Option<(GeoPosition, TAI2004)>,
+    /// Position of the event reported in the message, ie: for a DENM.
+    pub event_position: Option<GeoPosition>,
+    /// Detection time of the event reported in the message, ie: for a DENM.
+    pub detection_time: Option<TAI2004>,
+}
+
+/// Result of the plausibility checks on a received message.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MisbehaviorVerdict {
+    /// Checks which detected a misbehavior, whatever the configured reaction.
+    pub detections: Vec<MisbehaviorCheck>,
+    /// Most severe reaction among the ones configured for the detections.
+    pub reaction: MisbehaviorReaction,
+}
+
+impl MisbehaviorVerdict {
+    /// Query whether the message passed all the plausibility checks.
+    pub fn is_plausible(&self) -> bool {
+        self.detections.is_empty()
+    }
+
+    /// Query whether the message should be flagged as implausible.
+    pub fn is_flagged(&self) -> bool {
+        self.reaction == MisbehaviorReaction::Flag
+    }
+
+    /// Query whether the message should be dropped.
+    pub fn should_drop(&self) -> bool {
+        self.reaction >= MisbehaviorReaction::Drop
+    }
+}
+
+/// Misbehavior detected on a received secured message, kept as evidence to report the
+/// sender to the Misbehavior Authority.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisbehaviorDetection {
+    /// Application of the message.
+    pub aid: AID,
+    /// Checks which detected the misbehavior, excluding the ignored ones.
+    pub checks: Vec<MisbehaviorCheck>,
+    /// Received secured message, COER encoded.
+    pub message: Vec<u8>,
+    /// Certificate of the sender, if the message is signed with its digest.
+    pub certificate: Option<EtsiCertificate>,
+    /// Location of the local station at reception.
+    pub location: ThreeDLocation,
+    /// Reception time.
+    pub timestamp: Instant,
+}
+
+/// Misbehavior detection state of a sender.
+#[derive(Debug, Default)]
+struct SenderEntry {
+    /// Positions advertised by the sender. First element is the most recent one.
+    history: PotiPositionHistory,
+    /// Accounted detections, per check.
+    detections: BTreeMap<MisbehaviorCheck, usize>,
+    /// Instant until which the sender is blacklisted.
+    blacklisted_until: Option<Instant>,
+    /// Instant at which this entry expires.
+    expires_at: Instant,
+}
+
+impl SenderEntry {
+    /// Query whether the sender is blacklisted at `timestamp`.
+    fn is_blacklisted(&self, timestamp: Instant) -> bool {
+        self.blacklisted_until
+            .is_some_and(|until| until > timestamp)
+    }
+}
+
+/// Misbehavior detector. Holds the detection state of each sender.
+#[derive(Debug, Default)]
+pub struct MisbehaviorDetector {
+    /// Detection configuration.
+    config: MisbehaviorConfig,
+    /// Senders detection state, by certificate digest.
+    senders: BTreeMap<HashedId8, SenderEntry>,
+}
+
+impl MisbehaviorDetector {
+    /// Constructs a [MisbehaviorDetector] with the given `config`.
+    pub fn new(config: MisbehaviorConfig) -> Self {
+        Self {
+            config,
+            senders: BTreeMap::new(),
+        }
+    }
+
+    /// Get a reference on the detection configuration.
+    pub fn config(&self) -> &MisbehaviorConfig {
+        &self.config
+    }
+
+    /// Set the detection configuration.
+    pub fn set_config(&mut self, config: MisbehaviorConfig) {
+        self.config = config;
+    }
+
+    /// Query whether `sender` is blacklisted at `timestamp`.
+    pub fn is_blacklisted(&self, sender: &HashedId8, timestamp: Instant) -> bool {
+        self.senders
+            .get(sender)
+            .is_some_and(|e| e.is_blacklisted(timestamp))
+    }
+
+    /// Get the number of detections of `check` accounted for `sender`.
+    pub fn detection_count(&self, sender: &HashedId8, check: MisbehaviorCheck) -> usize {
+        self.senders
+            .get(sender)
+            .and_then(|e| e.detections.get(&check).copied())
+            .unwrap_or_default()
+    }
+
+    /// Clear the detection state of all the senders.
+    pub fn clear(&mut self) {
+        self.senders.clear();
+    }
+
+    /// Runs the plausibility checks on `observation`, received from `sender` at `timestamp`.
+    pub fn check(
+        &mut self,
+        sender: HashedId8,
+        observation: &Observation,
+        timestamp: Instant,
+    ) -> MisbehaviorVerdict {
+        let config = self.config;
+        self.senders.retain(|_, e| e.expires_at > timestamp);
+
+        let entry = self.senders.entry(sender).or_default();
+        entry.expires_at = timestamp + SEC_MBD_SENDER_ENTRY_LIFETIME;
+
+        let mut detections = Vec::new();
+
+        if TAI2004::from_unix_instant(timestamp) - observation.timestamp > config.max_age {
+            detections.push(MisbehaviorCheck::Freshness);
+        }
+
+        if observation
+            .detection_time
+            .is_some_and(|detection| detection > observation.timestamp)
+        {
+            detections.push(MisbehaviorCheck::DetectionTime);
+        }
+
+        if let Some(position) = observation.position {
+            if observation
+                .ego_position
+                .is_some_and(|ego| ego.distance_to(&position) > config.max_range)
+            {
+                detections.push(MisbehaviorCheck::CommunicationRange);
+            }
+
+            if let Some((beacon, beacon_ts)) = observation.beacon_position {
+                let speed = observation.speed.unwrap_or_default();
+                let tolerance = config.beacon_tolerance
+                    + (speed * as_time(observation.timestamp - beacon_ts)).abs();
+                if beacon.distance_to(&position) > tolerance {
+                    detections.push(MisbehaviorCheck::BeaconMismatch);
+                }
+            }
+
+            if observation
+                .event_position
+                .is_some_and(|event| event.distance_to(&position) > config.max_event_distance)
+            {
+                detections.push(MisbehaviorCheck::EventRange);
+            }
+
+            // Observations without speed, ie: DENMs, are checked against the history
+            // but not recorded in it, as the speed consistency relies on the recorded speeds.
+            let recorded = observation.speed.is_some();
+            match entry.history.0.front() {
+                Some(prev) if prev.timestamp >= observation.timestamp => {
+                    // Stale or duplicate message, cannot be compared to the history.
+                }
+                Some(prev) => {
+                    Self::check_mobility(&config, prev, observation, position, &mut detections);
+                    if recorded {
+                        entry
+                            .history
+                            .0
+                            .push_front(path_point(observation, position));
+                    }
+                }
+                None if recorded => entry
+                    .history
+                    .0
+                    .push_front(path_point(observation, position)),
+                None => {}
+            }
+            entry.history.0.truncate(SEC_MBD_HISTORY_LENGTH);
+        }
+
+        let reaction = detections
+            .iter()
+            .map(|c| config.reactions.get(*c))
+            .max()
+            .unwrap_or(MisbehaviorReaction::Ignore);
+
+        for check in detections.iter() {
+            if config.reactions.get(*check) != MisbehaviorReaction::Ignore {
+                *entry.detections.entry(*check).or_default() += 1;
+            }
+        }
+
+        if reaction == MisbehaviorReaction::Blacklist {
+            let until = timestamp + config.blacklist_duration;
+            entry.blacklisted_until = Some(until);
+            entry.expires_at = entry.expires_at.max(until);
+        }
+
+        MisbehaviorVerdict {
+            detections,
+            reaction,
+        }
+    }
+
+    /// Checks the movement from the `prev` path point to the `observation` one.
+    fn check_mobility(
+        config: &MisbehaviorConfig,
+        prev: &PotiPathPoint,
+        observation: &Observation,
+        position: GeoPosition,
+        detections: &mut Vec<MisbehaviorCheck>,
+    ) {
+        let prev_position = GeoPosition {
+            latitude: prev.latitude,
+            longitude: prev.longitude,
+        };
+        let elapsed = as_time(observation.timestamp - prev.timestamp);
+        let travelled = prev_position.distance_to(&position);
+
+        if travelled > config.max_speed * elapsed + config.position_tolerance {
+            detections.push(MisbehaviorCheck::PositionJump);
+        }
+
+        if let Some(speed) = observation.speed {
+            let expected: Length = (prev.speed + speed) / 2.0 * elapsed;
+            let tolerance = config.position_tolerance + config.speed_tolerance * elapsed;
+            if (travelled - expected).abs() > tolerance {
+                detections.push(MisbehaviorCheck::SpeedConsistency);
+            }
+        }
+
+        if let Some(heading) = observation.heading {
+            if travelled >= config.heading_min_distance
+                && angle_between(bearing(&prev_position, &position), heading)
+                    > config.heading_tolerance
+            {
+                detections.push(MisbehaviorCheck::HeadingConsistency);
+            }
+        }
+    }
+}
+
+/// Converts a [Duration] into a [Time] quantity.
+fn as_time(duration: Duration) -> Time {
+    Time::new::<millisecond>(duration.total_millis() as f64)
+}
+
+/// Build a [PotiPathPoint] from an `observation` located at `position`.
+fn path_point(observation: &Observation, position: GeoPosition) -> PotiPathPoint {
+    PotiPathPoint {
+        timestamp: observation.timestamp,
+        latitude: position.latitude,
+        longitude: position.longitude,
+        heading: observation.heading.unwrap_or_default(),
+        speed: observation.speed.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Compute the initial bearing to go from `from` to `to`, relative to the north.
+fn bearing(from: &GeoPosition, to: &GeoPosition) -> Angle {
+    let delta_lon = to.longitude - from.longitude;
+    let y = delta_lon.sin() * to.latitude.cos();
+    let x = from.latitude.cos() * to.latitude.sin()
+        - from.latitude.sin() * to.latitude.cos() * delta_lon.cos();
+
+    y.atan2(x)
+}
+
+/// Compute the absolute angle between `a` and `b`, in the [0, 180] degrees range.
+fn angle_between(a: Angle, b: Angle) -> Angle {
+    let delta = a - b;
+    delta.sin().atan2(delta.cos()).abs()
+}
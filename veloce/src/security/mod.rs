@@ -29,6 +29,7 @@ use veloce_asn1::{
                 EccP256CurvePointUncompressedP256 as Etsi103097EccP256CurvePointUncompressedP256,
                 EccP384CurvePoint as Etsi103097EccP384CurvePoint,
                 EccP384CurvePointUncompressedP384 as Etsi103097EccP384CurvePointUncompressedP384,
+                EcencP256EncryptedKey as Etsi103097EcencP256EncryptedKey,
                 EciesP256EncryptedKey as Etsi103097EciesP256EncryptedKey,
                 HashAlgorithm as EtsiHashAlgorithm, HashedId8 as Etsi103097HashedId8,
                 PublicVerificationKey as Etsi103097PublicVerificationKey,
@@ -49,11 +50,14 @@ pub mod backend;
 pub mod certificate;
 mod certificate_cache;
 pub mod ciphertext;
+pub mod misbehavior;
 pub mod permission;
 pub mod privacy;
 pub mod secured_message;
 pub mod service;
 pub mod signature;
+mod signature_cache;
+pub mod signer_policy;
 pub mod ssp;
 pub mod storage;
 pub mod trust_chain;
@@ -74,7 +78,7 @@ pub use storage::{
 
 pub use certificate::Certificate;
 pub use service::{PollEvent as SecurityServicePollEvent, SecurityService};
-pub use trust_chain::{ATContainer, TrustChain};
+pub use trust_chain::{ATContainer, ATDailyUsage, TrustChain};
 
 /// Hash algorithm for a certificate digest or a signature.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -98,6 +102,7 @@ impl TryFrom<&EtsiHashAlgorithm> for HashAlgorithm {
         let res = match value {
             EtsiHashAlgorithm::sha256 => HashAlgorithm::SHA256,
             EtsiHashAlgorithm::sha384 => HashAlgorithm::SHA384,
+            EtsiHashAlgorithm::sm3 => HashAlgorithm::SM3,
             _ => return Err(HashAlgorithmUnsupportedError),
         };
 
@@ -112,7 +117,7 @@ impl TryInto<EtsiHashAlgorithm> for HashAlgorithm {
         let res = match self {
             HashAlgorithm::SHA256 => EtsiHashAlgorithm::sha256,
             HashAlgorithm::SHA384 => EtsiHashAlgorithm::sha384,
-            _ => return Err(HashAlgorithmUnsupportedError),
+            HashAlgorithm::SM3 => EtsiHashAlgorithm::sm3,
         };
 
         Ok(res)
@@ -134,6 +139,9 @@ pub enum Issuer {
     /// Certificate is signed with another certificate identified with
     /// an SHA384 digest truncated as an [HashedId8].
     SHA384Digest(HashedId8),
+    /// Certificate is signed with another certificate identified with
+    /// an SM3 digest truncated as an [HashedId8].
+    SM3Digest(HashedId8),
 }
 
 impl TryFrom<&IssuerIdentifier> for Issuer {
@@ -147,6 +155,9 @@ impl TryFrom<&IssuerIdentifier> for Issuer {
             IssuerIdentifier::sha384AndDigest(h) => {
                 Issuer::SHA384Digest(HashedId8::from_bytes(h.0.as_slice()))
             }
+            IssuerIdentifier::sm3AndDigest(h) => {
+                Issuer::SM3Digest(HashedId8::from_bytes(h.0.as_slice()))
+            }
             IssuerIdentifier::R_self(h) => {
                 Issuer::SelfSigned(HashAlgorithm::try_from(h).map_err(|_| IssuerUnsupportedError)?)
             }
@@ -786,6 +797,9 @@ impl TryFrom<&Etsi103097PublicVerificationKey> for EcdsaKey {
             Etsi103097PublicVerificationKey::ecdsaBrainpoolP384r1(k) => EcdsaKey::BrainpoolP384r1(
                 EccPoint::try_from(k).map_err(EcdsaKeyError::UnsupportedCoordinates)?,
             ),
+            Etsi103097PublicVerificationKey::ecsigSm2(k) => {
+                EcdsaKey::Sm2(EccPoint::try_from(k).map_err(EcdsaKeyError::UnsupportedCoordinates)?)
+            }
             _ => return Err(EcdsaKeyError::UnsupportedType),
         };
 
@@ -841,7 +855,10 @@ impl TryInto<Etsi103097PublicVerificationKey> for EcdsaKey {
                 p.try_into()
                     .map_err(EcdsaKeyError::UnsupportedCoordinates)?,
             ),
-            _ => return Err(EcdsaKeyError::UnsupportedType),
+            EcdsaKey::Sm2(p) => Etsi103097PublicVerificationKey::ecsigSm2(
+                p.try_into()
+                    .map_err(EcdsaKeyError::UnsupportedCoordinates)?,
+            ),
         };
 
         Ok(res)
@@ -942,6 +959,9 @@ impl TryFrom<&Etsi103097BasePublicEncryptionKey> for EciesKey {
                     EccPoint::try_from(k).map_err(EciesKeyError::UnsupportedCoordinates)?,
                 )
             }
+            Etsi103097BasePublicEncryptionKey::ecencSm2(k) => {
+                EciesKey::Sm2(EccPoint::try_from(k).map_err(EciesKeyError::UnsupportedCoordinates)?)
+            }
             _ => return Err(EciesKeyError::UnsupportedType),
         };
 
@@ -964,7 +984,10 @@ impl TryInto<Etsi103097BasePublicEncryptionKey> for EciesKey {
                         .map_err(EciesKeyError::UnsupportedCoordinates)?,
                 )
             }
-            _ => return Err(EciesKeyError::UnsupportedType),
+            EciesKey::Sm2(p) => Etsi103097BasePublicEncryptionKey::ecencSm2(
+                p.try_into()
+                    .map_err(EciesKeyError::UnsupportedCoordinates)?,
+            ),
         };
 
         Ok(res)
@@ -1072,6 +1095,15 @@ impl EncryptedEciesKey {
         }
     }
 
+    /// Get the parameters of the encrypted key.
+    pub fn params(&self) -> &EncryptedEciesKeyParams {
+        match self {
+            EncryptedEciesKey::NistP256r1(p)
+            | EncryptedEciesKey::BrainpoolP256r1(p)
+            | EncryptedEciesKey::Sm2(p) => p,
+        }
+    }
+
     /// Get the hash algorithm of the encrypted key.
     pub fn hash_algorithm(&self) -> HashAlgorithm {
         match self {
@@ -1131,6 +1163,14 @@ impl TryFrom<&Etsi103097EncryptedDataEncryptionKey> for EncryptedEciesKey {
                     tag: k.t.to_vec(),
                 })
             }
+            Etsi103097EncryptedDataEncryptionKey::ecencSm2256(k) => {
+                EncryptedEciesKey::Sm2(EncryptedEciesKeyParams {
+                    ephemeral_public_key: EccPoint::try_from(&k.v)
+                        .map_err(EncryptedEciesKeyError::UnsupportedCoordinates)?,
+                    encrypted_key: k.c.to_vec(),
+                    tag: k.t.to_vec(),
+                })
+            }
             _ => return Err(EncryptedEciesKeyError::UnsupportedType),
         };
 
@@ -1173,7 +1213,19 @@ impl TryInto<Etsi103097EncryptedDataEncryptionKey> for EncryptedEciesKey {
                     },
                 )
             }
-            _ => return Err(EncryptedEciesKeyError::UnsupportedType),
+            EncryptedEciesKey::Sm2(p) => {
+                Etsi103097EncryptedDataEncryptionKey::ecencSm2256(Etsi103097EcencP256EncryptedKey {
+                    v: p.ephemeral_public_key
+                        .try_into()
+                        .map_err(EncryptedEciesKeyError::UnsupportedCoordinates)?,
+                    c: p.encrypted_key
+                        .try_into()
+                        .map_err(|_| EncryptedEciesKeyError::EncryptedKeySize)?,
+                    t: p.tag
+                        .try_into()
+                        .map_err(|_| EncryptedEciesKeyError::AuthenticationTagSize)?,
+                })
+            }
         };
 
         Ok(res)
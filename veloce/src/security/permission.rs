@@ -16,7 +16,9 @@ use veloce_asn1::{
 };
 
 use super::ssp::{
-    cam::CamSsp, crl::CrlSsp, ctl::CtlSsp, denm::DenmSsp, scr::ScrSsp, SspError, SspTrait,
+    cam::CamSsp, cpm::CpmSsp, crl::CrlSsp, ctl::CtlSsp, denm::DenmSsp, gn6::Gn6Ssp, gpc::GpcSsp,
+    ivim::IvimSsp, mapem::MapemSsp, scr::ScrSsp, spatem::SpatemSsp, srem::SremSsp, ssem::SsemSsp,
+    vam::VamSsp, SspError, SspTrait,
 };
 
 enum_with_unknown! {
@@ -64,6 +66,8 @@ enum_with_unknown! {
         SA = 540_801,
         /// GNSS Positioning Correction service.
         GPC = 540_802,
+        /// IPv6 over GeoNetworking service, ie: GN6ASL packets.
+        GN6 = 270_549_118,
    }
 }
 
@@ -153,6 +157,24 @@ pub enum Permission {
     GnMgmt,
     /// Secured Certificate Request service permission.
     SCR(PermissionSspContainer<ScrSsp>),
+    /// Road Lane Topology service permission, ie: MAPEM message.
+    MAPEM(PermissionSspContainer<MapemSsp>),
+    /// Traffic Light Manoeuver service permission, ie: SPATEM message.
+    SPATEM(PermissionSspContainer<SpatemSsp>),
+    /// In Vehicle Information service permission, ie: IVIM message.
+    IVIM(PermissionSspContainer<IvimSsp>),
+    /// Traffic Light Control Request service permission, ie: SREM message.
+    SREM(PermissionSspContainer<SremSsp>),
+    /// Traffic Light Control Status service permission, ie: SSEM message.
+    SSEM(PermissionSspContainer<SsemSsp>),
+    /// Collective Perception service permission, ie: CPM message.
+    CPM(PermissionSspContainer<CpmSsp>),
+    /// Vulnerable Road User service permission, ie: VAM message.
+    VAM(PermissionSspContainer<VamSsp>),
+    /// GNSS Positioning Correction service permission, ie: RTCMEM message.
+    GPC(PermissionSspContainer<GpcSsp>),
+    /// IPv6 over GeoNetworking service permission.
+    GN6(PermissionSspContainer<Gn6Ssp>),
     /// Fallback variant for an unknown permission type.
     Unknown {
         aid: i64,
@@ -181,6 +203,15 @@ impl Permission {
             Permission::CTL(_) => AID::CTL,
             Permission::GnMgmt => AID::GnMgmt,
             Permission::SCR(_) => AID::SCR,
+            Permission::MAPEM(_) => AID::RLT,
+            Permission::SPATEM(_) => AID::TLM,
+            Permission::IVIM(_) => AID::IVI,
+            Permission::SREM(_) => AID::TLCR,
+            Permission::SSEM(_) => AID::TLCS,
+            Permission::CPM(_) => AID::CP,
+            Permission::VAM(_) => AID::VRU,
+            Permission::GPC(_) => AID::GPC,
+            Permission::GN6(_) => AID::GN6,
             Permission::Unknown { aid, .. } => AID::from(*aid),
         }
     }
@@ -194,6 +225,15 @@ impl Permission {
             (Permission::CTL(l), Permission::CTL(r)) => l.ssp.contains_permissions_of(&r.ssp),
             (Permission::GnMgmt, Permission::GnMgmt) => true,
             (Permission::SCR(l), Permission::SCR(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::MAPEM(l), Permission::MAPEM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::SPATEM(l), Permission::SPATEM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::IVIM(l), Permission::IVIM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::SREM(l), Permission::SREM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::SSEM(l), Permission::SSEM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::CPM(l), Permission::CPM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::VAM(l), Permission::VAM(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::GPC(l), Permission::GPC(r)) => l.ssp.contains_permissions_of(&r.ssp),
+            (Permission::GN6(l), Permission::GN6(r)) => l.ssp.contains_permissions_of(&r.ssp),
             (
                 Permission::Unknown { ssp: Some(l), .. },
                 Permission::Unknown { ssp: Some(r), .. },
@@ -275,6 +315,65 @@ impl<'a> TryFrom<&'a Etsi103097PsidSsp> for Permission {
 
                 Permission::SCR(PermissionSspContainer { ssp, mask: None })
             }
+            AID::RLT => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = MapemSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::MAPEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLM => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SpatemSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::SPATEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::IVI => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = IvimSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::IVIM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLCR => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SremSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::SREM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLCS => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(SsemSsp::new()), |raw| SsemSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::SSEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::CP => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(CpmSsp::new()), |raw| CpmSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::CPM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::VRU => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(VamSsp::new()), |raw| VamSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::VAM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::GPC => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(GpcSsp::new()), |raw| GpcSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::GPC(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::GN6 => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(Gn6Ssp::new()), |raw| Gn6Ssp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::GN6(PermissionSspContainer { ssp, mask: None })
+            }
             _ => {
                 let raw = extract_ssp(value)?;
 
@@ -340,6 +439,65 @@ impl<'a> TryFrom<&'a Etsi102941PsidSsp> for Permission {
 
                 Permission::SCR(PermissionSspContainer { ssp, mask: None })
             }
+            AID::RLT => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = MapemSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::MAPEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLM => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SpatemSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::SPATEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::IVI => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = IvimSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::IVIM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLCR => {
+                let raw = extract_ssp(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SremSsp::parse(&raw.0).map_err(PermissionError::SSP)?;
+
+                Permission::SREM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::TLCS => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(SsemSsp::new()), |raw| SsemSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::SSEM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::CP => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(CpmSsp::new()), |raw| CpmSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::CPM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::VRU => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(VamSsp::new()), |raw| VamSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::VAM(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::GPC => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(GpcSsp::new()), |raw| GpcSsp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::GPC(PermissionSspContainer { ssp, mask: None })
+            }
+            AID::GN6 => {
+                let ssp = extract_ssp(value)?
+                    .map_or(Ok(Gn6Ssp::new()), |raw| Gn6Ssp::parse(&raw.0))
+                    .map_err(PermissionError::SSP)?;
+
+                Permission::GN6(PermissionSspContainer { ssp, mask: None })
+            }
             _ => {
                 let raw = extract_ssp(value)?;
 
@@ -365,6 +523,15 @@ impl Into<Etsi102941PsidSsp> for Permission {
             Permission::CRL(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
             Permission::CTL(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
             Permission::SCR(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::MAPEM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::SPATEM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::IVIM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::SREM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::SSEM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::CPM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::VAM(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::GPC(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
+            Permission::GN6(c) => Some(OctetString::copy_from_slice(&c.ssp.emit())),
             Permission::Unknown { ssp: Some(ssp), .. } => Some(OctetString::from(ssp)),
             _ => None, // Permission::GnMgmt and Permission::Unknown with ssp: None
         };
@@ -438,6 +605,79 @@ impl<'a> TryFrom<&'a PsidSspRange> for Permission {
 
                 Permission::SCR(PermissionSspContainer { ssp, mask })
             }
+            AID::RLT => {
+                let range = extract_ssp_range(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = MapemSsp::parse(&range.ssp_value).map_err(PermissionError::SSP)?;
+                let mask = Some(range.ssp_bitmask.to_vec());
+
+                Permission::MAPEM(PermissionSspContainer { ssp, mask })
+            }
+            AID::TLM => {
+                let range = extract_ssp_range(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SpatemSsp::parse(&range.ssp_value).map_err(PermissionError::SSP)?;
+                let mask = Some(range.ssp_bitmask.to_vec());
+
+                Permission::SPATEM(PermissionSspContainer { ssp, mask })
+            }
+            AID::IVI => {
+                let range = extract_ssp_range(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = IvimSsp::parse(&range.ssp_value).map_err(PermissionError::SSP)?;
+                let mask = Some(range.ssp_bitmask.to_vec());
+
+                Permission::IVIM(PermissionSspContainer { ssp, mask })
+            }
+            AID::TLCR => {
+                let range = extract_ssp_range(value)?.ok_or(PermissionError::NoSSP(aid))?;
+                let ssp = SremSsp::parse(&range.ssp_value).map_err(PermissionError::SSP)?;
+                let mask = Some(range.ssp_bitmask.to_vec());
+
+                Permission::SREM(PermissionSspContainer { ssp, mask })
+            }
+            AID::TLCS => {
+                let range = extract_ssp_range(value)?;
+                let ssp = range
+                    .map_or(Ok(SsemSsp::new()), |r| SsemSsp::parse(&r.ssp_value))
+                    .map_err(PermissionError::SSP)?;
+                let mask = range.map(|r| r.ssp_bitmask.to_vec());
+
+                Permission::SSEM(PermissionSspContainer { ssp, mask })
+            }
+            AID::CP => {
+                let range = extract_ssp_range(value)?;
+                let ssp = range
+                    .map_or(Ok(CpmSsp::new()), |r| CpmSsp::parse(&r.ssp_value))
+                    .map_err(PermissionError::SSP)?;
+                let mask = range.map(|r| r.ssp_bitmask.to_vec());
+
+                Permission::CPM(PermissionSspContainer { ssp, mask })
+            }
+            AID::VRU => {
+                let range = extract_ssp_range(value)?;
+                let ssp = range
+                    .map_or(Ok(VamSsp::new()), |r| VamSsp::parse(&r.ssp_value))
+                    .map_err(PermissionError::SSP)?;
+                let mask = range.map(|r| r.ssp_bitmask.to_vec());
+
+                Permission::VAM(PermissionSspContainer { ssp, mask })
+            }
+            AID::GPC => {
+                let range = extract_ssp_range(value)?;
+                let ssp = range
+                    .map_or(Ok(GpcSsp::new()), |r| GpcSsp::parse(&r.ssp_value))
+                    .map_err(PermissionError::SSP)?;
+                let mask = range.map(|r| r.ssp_bitmask.to_vec());
+
+                Permission::GPC(PermissionSspContainer { ssp, mask })
+            }
+            AID::GN6 => {
+                let range = extract_ssp_range(value)?;
+                let ssp = range
+                    .map_or(Ok(Gn6Ssp::new()), |r| Gn6Ssp::parse(&r.ssp_value))
+                    .map_err(PermissionError::SSP)?;
+                let mask = range.map(|r| r.ssp_bitmask.to_vec());
+
+                Permission::GN6(PermissionSspContainer { ssp, mask })
+            }
             _ => {
                 let range = extract_ssp_range(value)?;
 
@@ -0,0 +1,86 @@
+use crate::{
+    common::PotiFix,
+    security::privacy::PrivacyControllerTrait,
+    time::{Duration, Instant},
+};
+
+/// Number of seconds in a day.
+pub(crate) const SECS_PER_DAY: i64 = 86_400;
+
+/// Return the UTC day of `timestamp`, as a number of days since the Unix epoch.
+pub(crate) fn utc_day(timestamp: Instant) -> i64 {
+    timestamp.secs().div_euclid(SECS_PER_DAY)
+}
+
+/// Return the instant of the midnight UTC following `timestamp`.
+fn next_midnight(timestamp: Instant) -> Instant {
+    Instant::from_secs((utc_day(timestamp) + 1) * SECS_PER_DAY)
+}
+
+/// A daily usage privacy controller.
+/// This strategy limits the usage of an AT certificate to a maximum duration per UTC day, and
+/// never uses an AT certificate across two UTC days: a certificate change is triggered when the
+/// certificate has been used for `max_usage` during the day, or at midnight UTC, whichever comes
+/// first. The usage of each AT certificate is accounted by the security service and persisted in
+/// the storage metadata along the elections statistics, so the limit holds across restarts.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct DailyUsageStrategy {
+    /// Instant at which the next certificate change is triggered.
+    /// None until the strategy has been triggered at startup.
+    change_at: Option<Instant>,
+    /// Maximum usage duration of an AT certificate during a UTC day.
+    max_usage: Duration,
+}
+
+impl DailyUsageStrategy {
+    /// Create a new [DailyUsageStrategy] with the given `max_usage` duration.
+    pub fn new(max_usage: Duration) -> Self {
+        Self {
+            change_at: None,
+            max_usage,
+        }
+    }
+}
+
+impl PrivacyControllerTrait for DailyUsageStrategy {
+    fn run(&mut self, timestamp: Instant) -> bool {
+        match self.change_at {
+            Some(at) if timestamp < at => false,
+            _ => {
+                // Retried at midnight if no AT certificate can be elected before, as
+                // they have all reached their maximum usage.
+                self.change_at = Some(next_midnight(timestamp));
+                true
+            }
+        }
+    }
+
+    fn run_at(&self) -> Option<Instant> {
+        Some(self.change_at.unwrap_or(Instant::ZERO))
+    }
+
+    fn notify_position(&mut self, _position: PotiFix, _timestamp: Instant) {}
+
+    fn notify_signature(&mut self) {}
+
+    fn reset(&mut self, _now: Instant) {
+        // The next change is scheduled when the new AT certificate is elected.
+    }
+
+    fn max_daily_usage(&self) -> Option<Duration> {
+        Some(self.max_usage)
+    }
+
+    fn notify_elected(&mut self, usage: Duration, timestamp: Instant) {
+        let remaining = if usage < self.max_usage {
+            self.max_usage - usage
+        } else {
+            Duration::ZERO
+        };
+
+        let at = (timestamp + remaining).min(next_midnight(timestamp));
+        net_debug!("Daily usage: next certificate change at {}", at);
+        self.change_at = Some(at);
+    }
+}
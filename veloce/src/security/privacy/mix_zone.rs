@@ -0,0 +1,146 @@
+use crate::{
+    common::{
+        geo_area::{GeoArea, GeoPosition},
+        PotiFix,
+    },
+    security::privacy::PrivacyControllerTrait,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of mix zones simultaneously registered in the strategy.
+const MIX_ZONES_MAX: usize = 16;
+
+/// A mix zone, ie: an area where stations cooperatively change their pseudonyms.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct MixZone {
+    /// Area of the mix zone.
+    area: GeoArea,
+    /// Instant at which the mix zone expires.
+    expires_at: Instant,
+}
+
+/// A mix zone privacy controller.
+/// This strategy changes the AT certificate when the station enters a mix zone, ie: an area
+/// where many stations change their pseudonym at the same time, such as an intersection or an
+/// area announced by an RSU. A certificate must be used at least `min_interval` before being
+/// changed in a mix zone, and is changed anyway after `max_interval` if the station does not
+/// cross any mix zone.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct MixZoneStrategy {
+    /// Instant of the last certificate change.
+    /// None until the strategy has been triggered at startup.
+    last_change: Option<Instant>,
+    /// Registered mix zones.
+    zones: Vec<MixZone>,
+    /// Whether the station is inside a mix zone.
+    inside: bool,
+    /// Whether the station has entered a mix zone and a certificate change is pending.
+    pending: bool,
+    /// Minimum usage duration of an AT certificate.
+    min_interval: Duration,
+    /// Maximum usage duration of an AT certificate.
+    max_interval: Duration,
+}
+
+impl MixZoneStrategy {
+    /// Create a new [MixZoneStrategy] with the given `min_interval` and `max_interval`.
+    pub fn new(min_interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            last_change: None,
+            zones: Vec::new(),
+            inside: false,
+            pending: false,
+            min_interval: min_interval.min(max_interval),
+            max_interval: max_interval.max(min_interval),
+        }
+    }
+
+    /// Remove the mix zones expired at `timestamp`.
+    fn purge_zones(&mut self, timestamp: Instant) {
+        self.zones.retain(|z| z.expires_at > timestamp);
+    }
+}
+
+impl PrivacyControllerTrait for MixZoneStrategy {
+    fn run(&mut self, timestamp: Instant) -> bool {
+        let change = match self.last_change {
+            None => true,
+            Some(last) if timestamp >= last + self.max_interval => {
+                net_debug!("Mix zone: no mix zone crossed, maximum interval elapsed");
+                true
+            }
+            Some(last) if self.pending && timestamp >= last + self.min_interval => {
+                net_debug!("Mix zone: changing certificate inside mix zone");
+                true
+            }
+            Some(_) => false,
+        };
+
+        if change {
+            self.last_change = Some(timestamp);
+            self.pending = false;
+        }
+
+        change
+    }
+
+    fn run_at(&self) -> Option<Instant> {
+        match self.last_change {
+            None => Some(Instant::ZERO),
+            Some(last) if self.pending => Some(last + self.min_interval),
+            Some(last) => Some(last + self.max_interval),
+        }
+    }
+
+    fn notify_position(&mut self, position: PotiFix, timestamp: Instant) {
+        self.purge_zones(timestamp);
+
+        let (Ok(latitude), Ok(longitude)) = (position.latitude(), position.longitude()) else {
+            return;
+        };
+
+        let pos = GeoPosition {
+            latitude,
+            longitude,
+        };
+        let inside = self.zones.iter().any(|z| z.area.inside_or_at_border(pos));
+
+        if inside && !self.inside {
+            net_debug!("Mix zone: entering mix zone at {}", timestamp);
+            self.pending = true;
+        } else if !inside {
+            // Changes are only allowed inside the zone, where other stations may also change.
+            self.pending = false;
+        }
+
+        self.inside = inside;
+    }
+
+    fn notify_signature(&mut self) {}
+
+    fn reset(&mut self, now: Instant) {
+        if self.last_change.is_some() {
+            self.last_change = Some(now);
+            self.pending = false;
+        }
+    }
+
+    fn notify_mix_zone(&mut self, area: GeoArea, expires_at: Instant) {
+        if self.zones.len() >= MIX_ZONES_MAX {
+            // Evict the zone expiring first.
+            if let Some(pos) = self
+                .zones
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, z)| z.expires_at)
+                .map(|(i, _)| i)
+            {
+                self.zones.swap_remove(pos);
+            }
+        }
+
+        self.zones.push(MixZone { area, expires_at });
+    }
+}
@@ -1,11 +1,18 @@
 use crate::{
-    common::PotiFix,
-    security::privacy::{c2c::C2CStrategy, no::NoStrategy, threshold::ThresholdStrategy},
-    time::Instant,
+    common::{geo_area::GeoArea, PotiFix},
+    security::privacy::{
+        c2c::C2CStrategy, daily::DailyUsageStrategy, mix_zone::MixZoneStrategy, no::NoStrategy,
+        random::RandomIntervalStrategy, silent::SilentPeriodStrategy, threshold::ThresholdStrategy,
+    },
+    time::{Duration, Instant},
 };
 
 pub(crate) mod c2c;
+pub(crate) mod daily;
+pub(crate) mod mix_zone;
 pub(crate) mod no;
+pub(crate) mod random;
+pub(crate) mod silent;
 pub(crate) mod threshold;
 
 pub(crate) trait PrivacyControllerTrait {
@@ -23,6 +30,21 @@ pub(crate) trait PrivacyControllerTrait {
     /// significate a total reset of the strategy state machine, or just a reset
     /// to the beginning of the current internal state.
     fn reset(&mut self, now: Instant);
+    /// Return the instant until which the station should stay silent, ie: not emit
+    /// any CAM, if any.
+    fn silent_until(&self) -> Option<Instant> {
+        None
+    }
+    /// Indicate a mix zone in `area`, valid until `expires_at`, to the strategy state machine.
+    fn notify_mix_zone(&mut self, _area: GeoArea, _expires_at: Instant) {}
+    /// Return the maximum duration an AT certificate may be used during a UTC day, if limited.
+    /// AT certificates which reached it are not elected until the next UTC day.
+    fn max_daily_usage(&self) -> Option<Duration> {
+        None
+    }
+    /// Indicate to the strategy state machine that an AT certificate, already used for `usage`
+    /// during the current UTC day, has been elected at `timestamp`.
+    fn notify_elected(&mut self, _usage: Duration, _timestamp: Instant) {}
 }
 
 /// Privacy strategy deals with maintaining the ITS station anonymous on the field.
@@ -41,6 +63,32 @@ pub enum PrivacyStrategy {
     /// requirements RS_BSP_520 to RS_BSP_525. Takes an u64 as a seed for selecting random
     /// distance values.
     Car2Car(u64),
+    /// Silent period strategy changes the AT certificate every `interval`. After each change,
+    /// the station stays silent for a random duration between `min_silence` and `max_silence`.
+    /// Takes an u64 as a seed for selecting random durations.
+    SilentPeriod {
+        interval: Duration,
+        min_silence: Duration,
+        max_silence: Duration,
+        seed: u64,
+    },
+    /// Mix zone strategy changes the AT certificate when entering a mix zone, ie: an
+    /// intersection or an area announced by an RSU, if the AT certificate has been used for
+    /// at least `min_interval`. Certificate is changed anyway after `max_interval`.
+    MixZone {
+        min_interval: Duration,
+        max_interval: Duration,
+    },
+    /// Daily usage strategy limits the usage of an AT certificate to the given duration per
+    /// UTC day, and changes the AT certificate at midnight UTC.
+    DailyUsage(Duration),
+    /// Random interval strategy changes the AT certificate after a random duration between
+    /// `min` and `max`. Takes an u64 as a seed for selecting random durations.
+    RandomInterval {
+        min: Duration,
+        max: Duration,
+        seed: u64,
+    },
 }
 
 #[derive(Debug)]
@@ -52,6 +100,14 @@ pub(crate) enum PrivacyController {
     Threshold(ThresholdStrategy),
     /// Car2Car is based on the C2C Consortium Vehicle C-ITS station profile.
     Car2Car(C2CStrategy),
+    /// Silent period strategy is based on a fixed interval, followed by a random silent period.
+    SilentPeriod(SilentPeriodStrategy),
+    /// Mix zone strategy is based on the crossing of mix zones.
+    MixZone(MixZoneStrategy),
+    /// Daily usage strategy is based on a maximum usage duration and the time of day.
+    DailyUsage(DailyUsageStrategy),
+    /// Random interval strategy is based on random durations.
+    RandomInterval(RandomIntervalStrategy),
 }
 
 impl PrivacyController {
@@ -62,6 +118,27 @@ impl PrivacyController {
                 PrivacyController::Threshold(ThresholdStrategy::new(t))
             }
             PrivacyStrategy::Car2Car(seed) => PrivacyController::Car2Car(C2CStrategy::new(seed)),
+            PrivacyStrategy::SilentPeriod {
+                interval,
+                min_silence,
+                max_silence,
+                seed,
+            } => PrivacyController::SilentPeriod(SilentPeriodStrategy::new(
+                interval,
+                min_silence,
+                max_silence,
+                seed,
+            )),
+            PrivacyStrategy::MixZone {
+                min_interval,
+                max_interval,
+            } => PrivacyController::MixZone(MixZoneStrategy::new(min_interval, max_interval)),
+            PrivacyStrategy::DailyUsage(max_usage) => {
+                PrivacyController::DailyUsage(DailyUsageStrategy::new(max_usage))
+            }
+            PrivacyStrategy::RandomInterval { min, max, seed } => {
+                PrivacyController::RandomInterval(RandomIntervalStrategy::new(min, max, seed))
+            }
         }
     }
 
@@ -71,6 +148,10 @@ impl PrivacyController {
             PrivacyController::None(n) => n,
             PrivacyController::Threshold(t) => t,
             PrivacyController::Car2Car(c) => c,
+            PrivacyController::SilentPeriod(s) => s,
+            PrivacyController::MixZone(m) => m,
+            PrivacyController::DailyUsage(d) => d,
+            PrivacyController::RandomInterval(r) => r,
         }
     }
 
@@ -80,6 +161,10 @@ impl PrivacyController {
             PrivacyController::None(n) => n,
             PrivacyController::Threshold(t) => t,
             PrivacyController::Car2Car(c) => c,
+            PrivacyController::SilentPeriod(s) => s,
+            PrivacyController::MixZone(m) => m,
+            PrivacyController::DailyUsage(d) => d,
+            PrivacyController::RandomInterval(r) => r,
         }
     }
 }
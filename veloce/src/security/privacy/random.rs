@@ -0,0 +1,84 @@
+use crate::{
+    common::PotiFix,
+    rand::Rand,
+    security::privacy::PrivacyControllerTrait,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum State {
+    /// Startup state. To trigger the first certificate change at Veloce startup.
+    Startup,
+    /// Running state. Contains the instant at which the current interval ends,
+    /// and the interval duration.
+    Running(Instant, Duration),
+}
+
+/// A random interval privacy controller.
+/// This strategy changes the AT certificate after a random duration, drawn between a minimum
+/// and a maximum value each time a certificate change is triggered. Rotations are not
+/// predictable from one AT certificate to the next one.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct RandomIntervalStrategy {
+    /// Current strategy state.
+    state: State,
+    /// Minimum usage duration of an AT certificate.
+    min: Duration,
+    /// Maximum usage duration of an AT certificate.
+    max: Duration,
+    /// Random number generator.
+    rand: Rand,
+}
+
+impl RandomIntervalStrategy {
+    /// Create a new [RandomIntervalStrategy] with intervals between `min` and `max`.
+    /// Takes an u64 as a seed for selecting random intervals.
+    pub fn new(min: Duration, max: Duration, seed: u64) -> Self {
+        Self {
+            state: State::Startup,
+            min: min.min(max),
+            max: max.max(min),
+            rand: Rand::new(seed),
+        }
+    }
+
+    /// Get a random interval value between `min` and `max`.
+    fn random_interval(&mut self) -> Duration {
+        let min = self.min.total_millis().min(u32::MAX.into()) as u32;
+        let max = self.max.total_millis().min(u32::MAX.into()) as u32;
+        Duration::from_millis(self.rand.rand_range(min..=max).into())
+    }
+}
+
+impl PrivacyControllerTrait for RandomIntervalStrategy {
+    fn run(&mut self, timestamp: Instant) -> bool {
+        match self.state {
+            State::Running(end, _) if timestamp < end => false,
+            _ => {
+                let interval = self.random_interval();
+                net_debug!("Random interval: next certificate change in {}", interval);
+                self.state = State::Running(timestamp + interval, interval);
+                true
+            }
+        }
+    }
+
+    fn run_at(&self) -> Option<Instant> {
+        match self.state {
+            State::Startup => Some(Instant::ZERO),
+            State::Running(at, _) => Some(at),
+        }
+    }
+
+    fn notify_position(&mut self, _position: PotiFix, _timestamp: Instant) {}
+
+    fn notify_signature(&mut self) {}
+
+    fn reset(&mut self, now: Instant) {
+        if let State::Running(_, d) = self.state {
+            self.state = State::Running(now + d, d);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    common::PotiFix,
+    rand::Rand,
+    security::privacy::PrivacyControllerTrait,
+    time::{Duration, Instant},
+};
+
+/// A silent period privacy controller.
+/// This strategy changes the AT certificate each time it has been used for `interval`.
+/// After each certificate change, the station stays silent, ie: it does not emit any CAM, beacon
+/// or Geonetworking packet carrying its position vector, for a random duration between a minimum
+/// and a maximum value. Without messages sent just before and just after the change, an observer
+/// cannot link the old and the new pseudonyms using the station trajectory.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct SilentPeriodStrategy {
+    /// Instant at which the next certificate change is triggered.
+    /// None until the strategy has been triggered at startup.
+    change_at: Option<Instant>,
+    /// End of the current silent period, if any.
+    silent_until: Option<Instant>,
+    /// Usage duration of an AT certificate.
+    interval: Duration,
+    /// Minimum silent period duration.
+    min_silence: Duration,
+    /// Maximum silent period duration.
+    max_silence: Duration,
+    /// Random number generator.
+    rand: Rand,
+}
+
+impl SilentPeriodStrategy {
+    /// Create a new [SilentPeriodStrategy] changing certificate every `interval`, with silent
+    /// periods between `min_silence` and `max_silence`.
+    /// Takes an u64 as a seed for selecting random silent period durations.
+    pub fn new(
+        interval: Duration,
+        min_silence: Duration,
+        max_silence: Duration,
+        seed: u64,
+    ) -> Self {
+        Self {
+            change_at: None,
+            silent_until: None,
+            interval,
+            min_silence: min_silence.min(max_silence),
+            max_silence: max_silence.max(min_silence),
+            rand: Rand::new(seed),
+        }
+    }
+
+    /// Enter a silent period starting at `now`, and schedule the next certificate change
+    /// `interval` after the end of the silent period.
+    fn enter_silence(&mut self, now: Instant) {
+        let min = self.min_silence.total_millis().min(u32::MAX.into()) as u32;
+        let max = self.max_silence.total_millis().min(u32::MAX.into()) as u32;
+        let silence = Duration::from_millis(self.rand.rand_range(min..=max).into());
+        let end = now + silence;
+
+        net_debug!("Silent period: station is silent until {}", end);
+        self.silent_until = Some(end);
+        self.change_at = Some(end + self.interval);
+    }
+}
+
+impl PrivacyControllerTrait for SilentPeriodStrategy {
+    fn run(&mut self, timestamp: Instant) -> bool {
+        match self.change_at {
+            None => {
+                // No previous pseudonym to unlink from at startup.
+                self.change_at = Some(timestamp + self.interval);
+                true
+            }
+            Some(at) if timestamp >= at => {
+                self.enter_silence(timestamp);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    fn run_at(&self) -> Option<Instant> {
+        Some(self.change_at.unwrap_or(Instant::ZERO))
+    }
+
+    fn notify_position(&mut self, _position: PotiFix, _timestamp: Instant) {}
+
+    fn notify_signature(&mut self) {}
+
+    fn reset(&mut self, now: Instant) {
+        // Certificate has been changed outside of the strategy, the station should
+        // also stay silent to avoid linking pseudonyms.
+        if self.change_at.is_some() {
+            self.enter_silence(now);
+        }
+    }
+
+    fn silent_until(&self) -> Option<Instant> {
+        self.silent_until
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     security::{
         certificate::{AuthorizationAuthorityCertificate, CertificateError, ExplicitCertificate},
         service::SecurityService,
+        storage::RemoteCertificateType,
     },
     time::Instant,
 };
@@ -18,6 +19,10 @@ pub enum CertificateRequestError {
     InvalidCertificate(CertificateError),
     /// Enclosed certificate signature is invalid.
     FalseSignature,
+    /// Enclosed certificate is revoked.
+    Revoked,
+    /// Enclosed root certificate is not listed in the trusted ECTL.
+    UntrustedRoot,
     /// Other error type.
     Other,
 }
@@ -29,6 +34,8 @@ impl fmt::Display for CertificateRequestError {
                 write!(f, "invalid certificate: {}", e)
             }
             CertificateRequestError::FalseSignature => write!(f, "invalid signature"),
+            CertificateRequestError::Revoked => write!(f, "revoked certificate"),
+            CertificateRequestError::UntrustedRoot => write!(f, "untrusted root certificate"),
             CertificateRequestError::Other => write!(f, "other"),
         }
     }
@@ -77,9 +84,11 @@ impl SecurityService {
                 }
 
                 // Certificate has been checked and its signer is known.
+                let aa_hash = req_aa.hashed_id8();
                 self.store_mut()
                     .set_remote_aa(signer_hash, req_aa)
                     .map_err(|_| CertificateRequestError::Other)?;
+                self.notify_remote_certificate_learned(RemoteCertificateType::AA, aa_hash);
             }
         }
 
@@ -3,10 +3,22 @@
 use veloce_asn1::prelude::rasn::types::OctetString;
 
 use crate::{
-    security::{permission::Permission, secured_message::SecuredMessage, HashedId8},
+    security::{
+        backend::SignatureVerification, permission::Permission, secured_message::SecuredMessage,
+        HashedId8,
+    },
     time::Instant,
 };
 
+#[cfg(feature = "pki")]
+use crate::{
+    pki::{
+        encrypted_data::EncryptedData,
+        message::{self, DecryptionError, DecryptionKey},
+    },
+    security::{secured_message::SecuredMessageError, SecurityBackend},
+};
+
 use super::{SecurityService, SecurityServiceError};
 
 /// Decap service result type.
@@ -24,12 +36,50 @@ pub struct DecapConfirm {
     pub cert_id: HashedId8,
     /// Service Specific Permissions.
     pub permissions: Permission,
+    /// Whether the secured message was received encrypted.
+    pub confidential: bool,
+    /// Deferred signature verification, see [SecurityService::verify_decap].
+    pub pending: Option<SignatureVerification>,
+}
+
+impl DecapConfirm {
+    /// Returns whether the signature of the secured message has been verified.
+    pub fn is_verified(&self) -> bool {
+        self.pending.is_none()
+    }
 }
 
 impl SecurityService {
+    /// Decapsulates the given `packet` from the security envelope.
+    /// Packets encrypted for the local station are decrypted before verification.
     pub fn decap_packet(&mut self, packet: &[u8], timestamp: Instant) -> DecapResult {
-        let msg =
-            SecuredMessage::from_bytes(packet).map_err(SecurityServiceError::InvalidContent)?;
+        let res = self.decap_packet_inner(packet, timestamp);
+
+        match &res {
+            Ok(_) => self.decap_stats.succeeded += 1,
+            Err(e) => *self.decap_stats.failed.entry(e.kind()).or_default() += 1,
+        }
+
+        if let Some(cb) = self.verification_callback.as_mut() {
+            cb(packet, &res);
+        }
+
+        res
+    }
+
+    fn decap_packet_inner(&mut self, packet: &[u8], timestamp: Instant) -> DecapResult {
+        let (msg, confidential) = match SecuredMessage::from_bytes(packet) {
+            Ok(msg) => (msg, false),
+            #[cfg(feature = "pki")]
+            Err(SecuredMessageError::NotSigned) => {
+                let decrypted = self.decrypt_packet(packet, timestamp)?;
+                let msg = SecuredMessage::from_bytes(&decrypted)
+                    .map_err(SecurityServiceError::InvalidContent)?;
+                (msg, true)
+            }
+            Err(e) => return Err(SecurityServiceError::InvalidContent(e)),
+        };
+
         let payload = msg
             .payload()
             .map_err(SecurityServiceError::InvalidContent)?
@@ -42,8 +92,75 @@ impl SecurityService {
                 size: packet.len(),
                 cert_id: confirm.cert_id,
                 permissions: confirm.permissions,
+                confidential,
+                pending: confirm.pending,
             },
             payload,
         ))
     }
+
+    /// Runs the deferred signature verification of `confirm`, if any.
+    /// Should be called before consuming a message decapsulated in
+    /// [VerificationMode::OnDemand](super::VerificationMode::OnDemand) mode.
+    pub fn verify_decap(
+        &mut self,
+        confirm: &mut DecapConfirm,
+        timestamp: Instant,
+    ) -> Result<(), SecurityServiceError> {
+        let Some(verification) = confirm.pending.as_ref() else {
+            return Ok(());
+        };
+
+        self.verification_stats.consumed += 1;
+        self.check_signature(confirm.cert_id, verification, timestamp)?;
+        confirm.pending = None;
+
+        Ok(())
+    }
+
+    /// Decrypts the given `packet` with the encryption key of the AT certificate it is
+    /// addressed to, either the current one or a previous one still in its decryption grace
+    /// period at `timestamp`.
+    #[cfg(feature = "pki")]
+    fn decrypt_packet(
+        &self,
+        packet: &[u8],
+        timestamp: Instant,
+    ) -> Result<Vec<u8>, SecurityServiceError> {
+        let encrypted =
+            EncryptedData::from_bytes(packet).map_err(|_| SecurityServiceError::UnsignedMessage)?;
+
+        let own_chain = self.store.own_chain();
+        let candidates = self.decryption_at_certs(timestamp);
+        if candidates.is_empty() {
+            return Err(SecurityServiceError::NoSigningCertificate);
+        }
+
+        // Try the current AT certificate first, then the previous ones.
+        let mut res = Err(DecryptionError::UnexpectedRecipientInformation);
+        for index in candidates {
+            let Some(at) = own_chain.at_certs().get(&index) else {
+                continue;
+            };
+
+            res = match &self.backend {
+                #[cfg(feature = "security-backend-openssl")]
+                SecurityBackend::Openssl(backend) => message::decrypt_for(
+                    &encrypted,
+                    at.at_container(),
+                    backend,
+                    DecryptionKey::AuthorizationTicket(index),
+                ),
+            };
+
+            if !matches!(res, Err(DecryptionError::UnknownRecipientId(_))) {
+                break;
+            }
+        }
+
+        res.map_err(|e| match e {
+            DecryptionError::UnknownRecipientId(_) => SecurityServiceError::UnknownRecipient,
+            _ => SecurityServiceError::DecryptionError,
+        })
+    }
 }
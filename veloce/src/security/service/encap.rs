@@ -4,6 +4,17 @@ use crate::{
     common::PotiPosition,
     security::{permission::Permission, secured_message::SecuredMessage},
     time::Instant,
+    wire::EthernetAddress,
+};
+
+#[cfg(feature = "pki")]
+use crate::{
+    pki::{message, Aes128Key},
+    security::{
+        backend::PkiBackendTrait,
+        certificate::{AuthorizationTicketCertificate, ExplicitCertificate},
+        SecurityBackend,
+    },
 };
 
 use super::{SecurityService, SecurityServiceError};
@@ -32,4 +43,63 @@ impl SecurityService {
             .as_bytes()
             .map_err(SecurityServiceError::InvalidContent)
     }
+
+    /// Encapsulates the given `packet` into the security envelope, then encrypts it for the
+    /// station identified with the `destination` hardware address.
+    /// Message is signed according to the given `permissions` and `timestamp`, and encrypted with
+    /// the public encryption key of the destination AT certificate, which should be present in the
+    /// certificate cache. Forwarders cannot decrypt the packet, so the destination should be
+    /// a neighbour.
+    #[allow(unused_variables)]
+    pub fn encap_packet_confidential(
+        &mut self,
+        packet: Vec<u8>,
+        permissions: Permission,
+        timestamp: Instant,
+        position: PotiPosition,
+        destination: EthernetAddress,
+    ) -> EncapResult {
+        let Some(certificate) = self.cache.lookup_by_address(&destination, timestamp) else {
+            return Err(SecurityServiceError::RecipientCertificateNotFound);
+        };
+
+        let signed = self.encap_packet(packet, permissions, timestamp, position)?;
+
+        #[cfg(feature = "pki")]
+        return match &self.backend {
+            #[cfg(feature = "security-backend-openssl")]
+            SecurityBackend::Openssl(backend) => Self::encrypt_for(signed, certificate, backend),
+        };
+
+        #[cfg(not(feature = "pki"))]
+        Err(SecurityServiceError::EncryptionError)
+    }
+
+    /// Encrypts `data` for the holder of the `certificate`, using the provided `backend`.
+    #[cfg(feature = "pki")]
+    fn encrypt_for<B>(
+        data: Vec<u8>,
+        certificate: AuthorizationTicketCertificate,
+        backend: &B,
+    ) -> EncapResult
+    where
+        B: PkiBackendTrait,
+    {
+        let certificate = certificate
+            .into_with_hash_container(backend)
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let encryption_key = Aes128Key(
+            backend
+                .generate_aes128_key()
+                .map_err(SecurityServiceError::Backend)?,
+        );
+
+        let (encrypted, _) = message::encrypt(data, &encryption_key, &certificate, backend)
+            .map_err(|_| SecurityServiceError::EncryptionError)?;
+
+        encrypted
+            .as_bytes()
+            .map_err(|_| SecurityServiceError::EncryptionError)
+    }
 }
@@ -1,38 +1,57 @@
 #[cfg(not(feature = "std"))]
-use alloc::collections::btree_map::BTreeMap;
+use alloc::collections::{btree_map::BTreeMap, vec_deque::VecDeque};
 
 #[cfg(feature = "std")]
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
 use core::fmt::{self, Formatter};
 
-use cert_request::CertificateRequestError;
+pub use cert_request::CertificateRequestError;
+
+use veloce_asn1::defs::etsi_103097_v211::ieee1609_dot2_base_types::ThreeDLocation;
 
 use crate::{
-    common::PotiFix,
+    common::{geo_area::GeoArea, PotiFix},
+    config::{SEC_AT_DECRYPTION_GRACE_PERIOD, SEC_MBD_DETECTION_QUEUE_SIZE},
     security::{
         certificate::CertificateTrait,
-        privacy::{PrivacyController, PrivacyStrategy},
+        misbehavior::{
+            MisbehaviorConfig, MisbehaviorDetection, MisbehaviorDetector, MisbehaviorReaction,
+            MisbehaviorVerdict,
+        },
+        privacy::{
+            daily::{utc_day, SECS_PER_DAY},
+            PrivacyController, PrivacyStrategy,
+        },
     },
-    time::Instant,
+    time::{Duration, Instant, TAI2004},
     types::Pseudonym,
     wire::EthernetAddress,
 };
 
 use super::{
     backend::BackendError,
-    certificate::{CertificateError, ExplicitCertificate},
+    certificate::{
+        AuthorizationAuthorityCertificate, AuthorizationTicketCertificate, CertificateError,
+        ExplicitCertificate, RootCertificate,
+    },
     certificate_cache::CertificateCache,
-    permission::Permission,
-    secured_message::SecuredMessageError,
-    trust_chain::TrustChain,
+    permission::{Permission, AID},
+    secured_message::{SecuredMessageError, SignerIdentifier},
+    signature_cache::{SignatureCache, SignatureCacheKey},
+    signer_policy::SignerPolicy,
+    storage::RemoteCertificateType,
+    trust_chain::{ATContainer, ATDailyUsage, TrustChain},
     trust_store::Store as TrustStore,
     HashedId8, SecurityBackend,
 };
 
+use decap::{DecapConfirm, DecapResult};
+
 mod cert_request;
 pub(crate) mod decap;
 pub(crate) mod encap;
+mod remote;
 pub(crate) mod sign;
 pub(crate) mod verify;
 
@@ -48,6 +67,66 @@ pub enum PollEvent {
     ATCertificateExpiration(usize, HashedId8),
 }
 
+/// Signature verification mode of the received secured messages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VerificationMode {
+    /// Signature is verified at reception, before any further processing. Signatures of the
+    /// messages received in a same interface poll are verified in a batch.
+    #[default]
+    Immediate,
+    /// Signature verification is deferred until the message is consumed, ie: delivered to an
+    /// application or forwarded. The Location Table is updated at reception, the entry of an
+    /// unknown sender being tentative until one of its messages is verified. An unverified
+    /// message does not alter the entry of a verified sender, nor evict it from the table.
+    /// Certificate, permissions and validity checks are still run at reception. Duplicate and
+    /// looped back messages are never verified.
+    OnDemand,
+}
+
+/// Signature verification counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VerificationStats {
+    /// Number of signatures verified by the backend.
+    pub verified: u64,
+    /// Number of signatures verified by the backend in a batch, ahead of the message
+    /// processing. Included in [VerificationStats::verified].
+    pub batched: u64,
+    /// Number of signature verifications avoided thanks to the signature cache.
+    pub cache_hits: u64,
+    /// Number of false signatures.
+    pub failed: u64,
+    /// Number of messages whose signature verification has been deferred.
+    pub deferred: u64,
+    /// Number of deferred messages verified afterwards, because they were consumed.
+    pub consumed: u64,
+}
+
+impl VerificationStats {
+    /// Number of deferred messages that have not been verified, because they were not consumed.
+    pub fn skipped(&self) -> u64 {
+        self.deferred.saturating_sub(self.consumed)
+    }
+}
+
+/// Secured packets decapsulation counters.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DecapStats {
+    /// Number of packets successfully decapsulated at reception.
+    pub succeeded: u64,
+    /// Number of packets rejected at reception, per error kind.
+    /// See [SecurityServiceError::kind].
+    pub failed: BTreeMap<&'static str, u64>,
+}
+
+impl DecapStats {
+    /// Number of packets rejected at reception, for any error kind.
+    pub fn total_failed(&self) -> u64 {
+        self.failed.values().sum()
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SecurityServiceError {
@@ -82,8 +161,22 @@ pub enum SecurityServiceError {
     SignerCertificateFalseSignature,
     /// Message is not encrypted.
     UnencryptedMessage,
+    /// Message encryption has failed.
+    EncryptionError,
     /// Message decryption has failed.
     DecryptionError,
+    /// Recipient certificate not found, ie: destination AT certificate
+    /// is not present in local cache.
+    RecipientCertificateNotFound,
+    /// Message is encrypted for another recipient.
+    UnknownRecipient,
+    /// Signer certificate is blacklisted by the misbehavior detection.
+    BlacklistedSigner,
+    /// Station is in a silent period required by the privacy strategy, CAMs are not signed
+    /// and packets carrying the station position vector are not sent.
+    SilentPeriod,
+    /// Local time accuracy is insufficient to generate a message.
+    InsufficientTimeAccuracy,
     /// Backend error.
     Backend(BackendError),
     /// Certificate Request error.
@@ -91,6 +184,40 @@ pub enum SecurityServiceError {
     CertificateRequest(CertificateRequestError),
 }
 
+impl SecurityServiceError {
+    /// Returns the error kind as a snake case string, without the inner error details.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            SecurityServiceError::NoSigningCertificate => "no_signing_certificate",
+            SecurityServiceError::InvalidContent(_) => "invalid_content",
+            SecurityServiceError::FalseSignature => "false_signature",
+            SecurityServiceError::InvalidCertificate(_) => "invalid_certificate",
+            SecurityServiceError::RevokedCertificate => "revoked_certificate",
+            SecurityServiceError::InsufficientPermissions => "insufficient_permissions",
+            SecurityServiceError::InconsistentChain => "inconsistent_chain",
+            SecurityServiceError::InvalidTimestamp => "invalid_timestamp",
+            SecurityServiceError::OffValidityPeriod => "off_validity_period",
+            SecurityServiceError::DuplicateMessage => "duplicate_message",
+            SecurityServiceError::InvalidMobilityData => "invalid_mobility_data",
+            SecurityServiceError::UnsignedMessage => "unsigned_message",
+            SecurityServiceError::SignerCertificateNotFound => "signer_certificate_not_found",
+            SecurityServiceError::SignerCertificateFalseSignature => {
+                "signer_certificate_false_signature"
+            }
+            SecurityServiceError::UnencryptedMessage => "unencrypted_message",
+            SecurityServiceError::EncryptionError => "encryption_error",
+            SecurityServiceError::DecryptionError => "decryption_error",
+            SecurityServiceError::RecipientCertificateNotFound => "recipient_certificate_not_found",
+            SecurityServiceError::UnknownRecipient => "unknown_recipient",
+            SecurityServiceError::BlacklistedSigner => "blacklisted_signer",
+            SecurityServiceError::SilentPeriod => "silent_period",
+            SecurityServiceError::InsufficientTimeAccuracy => "insufficient_time_accuracy",
+            SecurityServiceError::Backend(_) => "backend",
+            SecurityServiceError::CertificateRequest(_) => "certificate_request",
+        }
+    }
+}
+
 impl fmt::Display for SecurityServiceError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -121,7 +248,17 @@ impl fmt::Display for SecurityServiceError {
                 write!(f, "signer certificate false signature")
             }
             SecurityServiceError::UnencryptedMessage => write!(f, "unencrypted message"),
+            SecurityServiceError::EncryptionError => write!(f, "encryption error"),
             SecurityServiceError::DecryptionError => write!(f, "decryption error"),
+            SecurityServiceError::RecipientCertificateNotFound => {
+                write!(f, "recipient certificate not found")
+            }
+            SecurityServiceError::UnknownRecipient => write!(f, "unknown recipient"),
+            SecurityServiceError::BlacklistedSigner => write!(f, "blacklisted signer"),
+            SecurityServiceError::SilentPeriod => write!(f, "silent period"),
+            SecurityServiceError::InsufficientTimeAccuracy => {
+                write!(f, "insufficient time accuracy")
+            }
             SecurityServiceError::Backend(e) => write!(f, "backend error: {}", e),
             SecurityServiceError::CertificateRequest(cr) => {
                 write!(f, "certificate request error: {}", cr)
@@ -130,11 +267,20 @@ impl fmt::Display for SecurityServiceError {
     }
 }
 
+/// Verification result callback type.
+type VerificationCallback = Box<dyn FnMut(&[u8], &DecapResult)>;
+
 pub struct SecurityService {
-    /// Instant at which to include the full AT certificate in a CAM message signature.
-    at_cert_in_cam_at: Instant,
+    /// Whether outgoing packets are signed. Received packets are verified regardless.
+    signing_enabled: bool,
+    /// Signer identifier policy, ie: whether to include the full AT certificate or its digest.
+    signer_policy: SignerPolicy,
+    /// Instant at which to include the full AT certificate in a message signature, per AID.
+    /// Only for AIDs with a periodic signer identifier policy. A missing entry means the
+    /// certificate should be included in the next message.
+    cert_inclusion_at: BTreeMap<AID, Instant>,
     /// Flag indicating whether the AA certificate should be included in the next CAM message.
-    /// [SecurityService::at_cert_in_cam_at] takes precedence over this flag and will delay AA
+    /// [SecurityService::cert_inclusion_at] takes precedence over this flag and will delay AA
     /// inclusion to the next transmitted CAM message without the full AT certificate.
     aa_cert_in_cam: bool,
     /// Requested certificates, ie: HashedId8 of certificates we don't have in our cache,
@@ -142,24 +288,75 @@ pub struct SecurityService {
     p2p_requested_certs: Vec<HashedId8>,
     /// AT Certificates cache.
     cache: CertificateCache,
+    /// Verified signatures cache.
+    sig_cache: SignatureCache,
+    /// Signature verification results of the received messages verified in a batch,
+    /// not accounted yet.
+    batch_results: BTreeMap<SignatureCacheKey, bool>,
+    /// Signature verification mode.
+    verification_mode: VerificationMode,
+    /// Signature verification counters.
+    verification_stats: VerificationStats,
+    /// Decapsulation counters.
+    decap_stats: DecapStats,
     /// Trust store for chain of trust.
     store: TrustStore,
+    /// Remote certificates learned over the air, not persisted yet.
+    learned_certs: Vec<(RemoteCertificateType, HashedId8)>,
+    /// Revoked certificates list, as persisted the last time.
+    persisted_revoked_certs: Vec<HashedId8>,
     /// Cryptography backend.
     backend: SecurityBackend,
     /// Privacy controller for certificate rotation.
     privacy: PrivacyController,
+    /// Misbehavior detector for received messages.
+    misbehavior: MisbehaviorDetector,
+    /// Misbehavior detections waiting to be reported, oldest first.
+    misbehavior_detections: VecDeque<MisbehaviorDetection>,
+    /// Indexes of the previously used AT certificates, along the end of their decryption
+    /// grace period.
+    previous_at_certs: Vec<(usize, Instant)>,
+    /// Instant since which the usage of the current AT certificate is not accounted yet.
+    at_used_since: Option<Instant>,
     /// Last AT certificate election result.
     last_at_election_successful: bool,
+    /// Whether the AT certificate rotations triggered by the privacy strategy are on hold.
+    privacy_rotation_held: bool,
+    /// Time accuracy required to sign messages. [None] to disable the check.
+    required_time_accuracy: Option<Duration>,
+    /// Current accuracy of the local time, [None] if unknown.
+    time_accuracy: Option<Duration>,
+    /// Current TAI - UTC offset, in seconds.
+    tai_utc_offset: u32,
+    /// Function to call with the result of each decapsulated packet.
+    verification_callback: Option<VerificationCallback>,
 }
 
 impl fmt::Debug for SecurityService {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("SecurityService")
-            .field("at_cert_in_cam_at", &self.at_cert_in_cam_at)
+            .field("signing_enabled", &self.signing_enabled)
+            .field("signer_policy", &self.signer_policy)
+            .field("cert_inclusion_at", &self.cert_inclusion_at)
             .field("aa_cert_in_cam", &self.aa_cert_in_cam)
             .field("cache", &self.cache)
+            .field("sig_cache", &self.sig_cache)
+            .field("batch_results", &self.batch_results)
+            .field("verification_mode", &self.verification_mode)
+            .field("verification_stats", &self.verification_stats)
+            .field("decap_stats", &self.decap_stats)
             .field("store", &self.store)
+            .field("learned_certs", &self.learned_certs)
+            .field("persisted_revoked_certs", &self.persisted_revoked_certs)
             .field("privacy", &self.privacy)
+            .field("misbehavior", &self.misbehavior)
+            .field("misbehavior_detections", &self.misbehavior_detections)
+            .field("previous_at_certs", &self.previous_at_certs)
+            .field("at_used_since", &self.at_used_since)
+            .field("privacy_rotation_held", &self.privacy_rotation_held)
+            .field("required_time_accuracy", &self.required_time_accuracy)
+            .field("time_accuracy", &self.time_accuracy)
+            .field("tai_utc_offset", &self.tai_utc_offset)
             .finish()
     }
 }
@@ -168,14 +365,32 @@ impl SecurityService {
     /// Constructs a [SecurityService].
     pub fn new(own_chain: TrustChain, backend: SecurityBackend, privacy: PrivacyStrategy) -> Self {
         Self {
-            at_cert_in_cam_at: Instant::ZERO,
+            signing_enabled: true,
+            signer_policy: SignerPolicy::default(),
+            cert_inclusion_at: BTreeMap::new(),
             aa_cert_in_cam: false,
             p2p_requested_certs: Vec::new(),
             cache: CertificateCache::new(),
+            sig_cache: SignatureCache::new(),
+            batch_results: BTreeMap::new(),
+            verification_mode: VerificationMode::default(),
+            verification_stats: VerificationStats::default(),
+            decap_stats: DecapStats::default(),
             store: TrustStore::new(own_chain),
+            learned_certs: Vec::new(),
+            persisted_revoked_certs: Vec::new(),
             backend,
             privacy: PrivacyController::new(privacy),
+            misbehavior: MisbehaviorDetector::default(),
+            misbehavior_detections: VecDeque::new(),
+            previous_at_certs: Vec::new(),
+            at_used_since: None,
             last_at_election_successful: false,
+            privacy_rotation_held: false,
+            required_time_accuracy: None,
+            time_accuracy: None,
+            tai_utc_offset: TAI2004::LEAP_SECONDS_DEFAULT,
+            verification_callback: None,
         }
     }
 
@@ -189,6 +404,150 @@ impl SecurityService {
         &mut self.store
     }
 
+    /// Get a reference to the cryptography [SecurityBackend].
+    pub fn backend(&self) -> &SecurityBackend {
+        &self.backend
+    }
+
+    /// Get a reference to the [MisbehaviorDetector].
+    pub fn misbehavior(&self) -> &MisbehaviorDetector {
+        &self.misbehavior
+    }
+
+    /// Get a mutable reference to the [MisbehaviorDetector].
+    pub fn misbehavior_mut(&mut self) -> &mut MisbehaviorDetector {
+        &mut self.misbehavior
+    }
+
+    /// Set the misbehavior detection configuration.
+    pub fn set_misbehavior_config(&mut self, config: MisbehaviorConfig) {
+        self.misbehavior.set_config(config);
+    }
+
+    /// Record the misbehavior `verdict` on the received secured message of `confirm`, as
+    /// evidence for a misbehavior report. `location` is the location of the local station
+    /// at reception `timestamp`. Detections with an [MisbehaviorReaction::Ignore] reaction
+    /// are not recorded. The oldest detection is dropped when too many are waiting.
+    pub fn record_misbehavior(
+        &mut self,
+        confirm: &DecapConfirm,
+        verdict: &MisbehaviorVerdict,
+        location: ThreeDLocation,
+        timestamp: Instant,
+    ) {
+        let reactions = self.misbehavior.config().reactions;
+        let checks: Vec<_> = verdict
+            .detections
+            .iter()
+            .copied()
+            .filter(|check| reactions.get(*check) != MisbehaviorReaction::Ignore)
+            .collect();
+
+        if checks.is_empty() {
+            return;
+        }
+
+        let message = match confirm.secured_message.as_bytes() {
+            Ok(message) => message,
+            Err(e) => {
+                net_debug!("Cannot record misbehavior: {}", e);
+                return;
+            }
+        };
+
+        let certificate = match confirm.secured_message.signer_identifier() {
+            Ok(SignerIdentifier::Digest(digest)) => self
+                .cache
+                .lookup(&digest, timestamp)
+                .map(|cert| cert.inner().clone()),
+            _ => None,
+        };
+
+        if self.misbehavior_detections.len() >= SEC_MBD_DETECTION_QUEUE_SIZE {
+            self.misbehavior_detections.pop_front();
+        }
+
+        self.misbehavior_detections.push_back(MisbehaviorDetection {
+            aid: confirm.permissions.aid(),
+            checks,
+            message,
+            certificate,
+            location,
+            timestamp,
+        });
+    }
+
+    /// Bind the cached AT certificate identified by `cert_id` to the `address` of the station
+    /// which sent a verified message signed with it. Used to encrypt messages to this station.
+    pub fn bind_certificate_address(&mut self, cert_id: HashedId8, address: EthernetAddress) {
+        self.cache.bind_address(&cert_id, address);
+    }
+
+    /// Take the misbehavior detections waiting to be reported, oldest first.
+    pub fn take_misbehavior_detections(&mut self) -> Vec<MisbehaviorDetection> {
+        self.misbehavior_detections.drain(..).collect()
+    }
+
+    /// Get whether outgoing packets are signed.
+    pub fn signing_enabled(&self) -> bool {
+        self.signing_enabled
+    }
+
+    /// Enable or disable the signature of outgoing packets. While disabled, packets
+    /// are sent unsecured, and received packets are still verified.
+    pub fn set_signing_enabled(&mut self, enabled: bool) {
+        self.signing_enabled = enabled;
+    }
+
+    /// Get the signer identifier policy.
+    pub fn signer_policy(&self) -> &SignerPolicy {
+        &self.signer_policy
+    }
+
+    /// Set the signer identifier policy.
+    pub fn set_signer_policy(&mut self, policy: SignerPolicy) {
+        self.signer_policy = policy;
+        self.cert_inclusion_at.clear();
+    }
+
+    /// Notifies the security service a new neighbour has been detected.
+    /// Full AT certificate will be included in the next message of each application
+    /// with a periodic signer identifier policy, so the neighbour can promptly verify
+    /// the digest signed messages.
+    pub fn notify_new_neighbour(&mut self) {
+        self.cert_inclusion_at.clear();
+    }
+
+    /// Get the signature verification mode.
+    pub fn verification_mode(&self) -> VerificationMode {
+        self.verification_mode
+    }
+
+    /// Set the signature verification mode.
+    pub fn set_verification_mode(&mut self, mode: VerificationMode) {
+        self.verification_mode = mode;
+    }
+
+    /// Register a callback for the verification result of the received secured packets.
+    /// First callback parameter contains the secured packet, second one the result of
+    /// its decapsulation by [SecurityService::decap_packet].
+    pub fn register_verification_callback(
+        &mut self,
+        cb: impl FnMut(&[u8], &DecapResult) + 'static,
+    ) {
+        self.verification_callback = Some(Box::new(cb));
+    }
+
+    /// Get the signature verification counters.
+    pub fn verification_stats(&self) -> VerificationStats {
+        self.verification_stats
+    }
+
+    /// Get the decapsulation counters.
+    pub fn decap_stats(&self) -> &DecapStats {
+        &self.decap_stats
+    }
+
     /// Get the application permissions contained in the AT certificate used to sign the messages.
     pub fn application_permissions(&self) -> Result<Vec<Permission>, SecurityServiceError> {
         self.store
@@ -238,8 +597,24 @@ impl SecurityService {
             .ok_or(SecurityServiceError::NoSigningCertificate)
     }
 
+    /// Put the AT certificate rotations triggered by the privacy strategy on hold, or release
+    /// them if `hold` is false. A rotation due while on hold is performed once released.
+    /// Rotations triggered by the AT certificate expiration are never held.
+    pub fn hold_privacy_rotation(&mut self, hold: bool) {
+        self.privacy_rotation_held = hold;
+    }
+
+    /// Query whether the AT certificate rotations triggered by the privacy strategy are on hold.
+    pub fn is_privacy_rotation_held(&self) -> bool {
+        self.privacy_rotation_held
+    }
+
     /// Return a _soft deadline_ for calling [poll] the next time.
     pub fn poll_at(&self) -> Option<Instant> {
+        if self.privacy_rotation_held {
+            return None;
+        }
+
         self.privacy.inner().run_at()
     }
 
@@ -248,16 +623,14 @@ impl SecurityService {
     /// runs the privacy strategy internal state machine. It also changes
     /// the signature private key + AT certificate if needed.
     pub fn poll(&mut self, timestamp: Instant) -> Option<PollEvent> {
-        let at_expired = self.store.own_chain().at_cert().is_some_and(|c| {
-            c.at_container()
-                .certificate()
-                .validity_period()
-                .end()
-                .as_unix_instant()
-                <= timestamp
-        });
+        let now = self.tai2004(timestamp);
+        let at_expired = self
+            .store
+            .own_chain()
+            .at_cert()
+            .is_some_and(|c| c.at_container().certificate().validity_period().end() <= now);
 
-        if self.privacy.inner_mut().run(timestamp) {
+        if !self.privacy_rotation_held && self.privacy.inner_mut().run(timestamp) {
             self.elect_at_cert(timestamp)
                 .map(|(i, h)| PollEvent::PrivacyATCertificateRotation(i, h))
         } else if at_expired && self.last_at_election_successful {
@@ -281,9 +654,60 @@ impl SecurityService {
             .notify_position(position, timestamp);
     }
 
+    /// Notify the Security Service about a mix zone in `area`, valid until `expires_at`, ie: an
+    /// intersection or an area announced by an RSU where stations should change their pseudonym.
+    /// Only used by the mix zone privacy strategy.
+    pub fn notify_mix_zone(&mut self, area: GeoArea, expires_at: Instant) {
+        self.privacy.inner_mut().notify_mix_zone(area, expires_at);
+    }
+
+    /// Query whether the station should stay silent at `timestamp`, ie: not emit any CAM, beacon
+    /// or packet carrying its position vector, as required by the privacy strategy.
+    pub fn is_silent(&self, timestamp: Instant) -> bool {
+        self.privacy
+            .inner()
+            .silent_until()
+            .is_some_and(|until| timestamp < until)
+    }
+
+    /// Set the time accuracy required to sign messages. Messages are refused with
+    /// [SecurityServiceError::InsufficientTimeAccuracy] when the local time accuracy
+    /// is unknown or above `accuracy`. Set to [None] to disable the check.
+    pub fn set_required_time_accuracy(&mut self, accuracy: Option<Duration>) {
+        self.required_time_accuracy = accuracy;
+    }
+
+    /// Notify the Security Service about the current `accuracy` of the local time,
+    /// or [None] if unknown.
+    pub fn notify_time_accuracy(&mut self, accuracy: Option<Duration>) {
+        self.time_accuracy = accuracy;
+    }
+
+    /// Set the current TAI - UTC offset, in seconds, used to convert the timestamps
+    /// into TAI times, ie: for the generation times and the certificates validity.
+    pub fn set_tai_utc_offset(&mut self, offset: u32) {
+        self.tai_utc_offset = offset;
+    }
+
+    /// Converts the `timestamp` into a TAI time, using the current TAI - UTC offset.
+    pub(super) fn tai2004(&self, timestamp: Instant) -> TAI2004 {
+        TAI2004::from_unix_instant_with_offset(timestamp, self.tai_utc_offset)
+    }
+
+    /// Query whether the local time is accurate enough to sign messages.
+    pub fn is_time_accurate(&self) -> bool {
+        match (self.required_time_accuracy, self.time_accuracy) {
+            (None, _) => true,
+            (Some(required), Some(accuracy)) => accuracy <= required,
+            (Some(_), None) => false,
+        }
+    }
+
     /// Elects next AT certificate used to sign messages.
     /// Returns an option containing the AT certificate index along its [HashedId8] if the AT certificate has been changed.
     pub fn elect_at_cert(&mut self, timestamp: Instant) -> Option<(usize, HashedId8)> {
+        self.account_at_usage(timestamp);
+        let previous = self.store.own_chain().at_cert_index();
         let res = match self.find_candidate_at_cert(timestamp) {
             Some((index, h)) => self
                 .backend
@@ -307,9 +731,221 @@ impl SecurityService {
         };
 
         self.last_at_election_successful = res.is_some();
+        if let Some((index, _)) = res {
+            self.retire_at_cert(previous, index, timestamp);
+            self.notify_at_elected(index, timestamp);
+        }
+
         res
     }
 
+    /// Account the usage of the current AT certificate until `timestamp`. Usage is accounted
+    /// on the UTC day of `timestamp` only.
+    fn account_at_usage(&mut self, timestamp: Instant) {
+        let (Some(index), Some(since)) =
+            (self.store.own_chain().at_cert_index(), self.at_used_since)
+        else {
+            return;
+        };
+
+        let day = utc_day(timestamp);
+        let since = since.max(Instant::from_secs(day * SECS_PER_DAY));
+        if timestamp > since {
+            if let Some(at) = self.store.own_chain_mut().at_cert_mut(index) {
+                at.add_usage(day, timestamp - since);
+            }
+        }
+
+        self.at_used_since = Some(timestamp);
+    }
+
+    /// Notify the privacy strategy the AT certificate at `index` has been elected at `timestamp`.
+    fn notify_at_elected(&mut self, index: usize, timestamp: Instant) {
+        let usage = self
+            .store
+            .own_chain()
+            .at_certs()
+            .get(&index)
+            .map_or(Duration::ZERO, |at| at.usage_on(utc_day(timestamp)));
+
+        self.at_used_since = Some(timestamp);
+        self.privacy.inner_mut().notify_elected(usage, timestamp);
+    }
+
+    /// Get the usage of the AT certificates during the last UTC day they have been used,
+    /// accounted until `timestamp`. Should be persisted in the storage metadata, to limit
+    /// the daily usage of the AT certificates across restarts.
+    pub fn at_daily_usages(&mut self, timestamp: Instant) -> Vec<(usize, ATDailyUsage)> {
+        self.account_at_usage(timestamp);
+        self.store
+            .own_chain()
+            .at_certs()
+            .iter()
+            .map(|(index, at)| (*index, at.daily_usage()))
+            .collect()
+    }
+
+    /// Keep the `previous` AT certificate usable for decryption for the grace period
+    /// following its replacement by the AT certificate at `index`, at `timestamp`.
+    fn retire_at_cert(&mut self, previous: Option<usize>, index: usize, timestamp: Instant) {
+        self.previous_at_certs
+            .retain(|(i, until)| *i != index && *until > timestamp);
+
+        if let Some(previous) = previous.filter(|p| *p != index) {
+            self.previous_at_certs.retain(|(i, _)| *i != previous);
+            self.previous_at_certs
+                .push((previous, timestamp + SEC_AT_DECRYPTION_GRACE_PERIOD));
+        }
+    }
+
+    /// Get the indexes of the AT certificates whose encryption key is usable to decrypt
+    /// messages at `timestamp`: the current AT certificate first, then the previous ones
+    /// still in their decryption grace period, most recent first.
+    #[cfg(feature = "pki")]
+    pub(super) fn decryption_at_certs(&self, timestamp: Instant) -> Vec<usize> {
+        self.store
+            .own_chain()
+            .at_cert_index()
+            .into_iter()
+            .chain(
+                self.previous_at_certs
+                    .iter()
+                    .rev()
+                    .filter(|(_, until)| *until > timestamp)
+                    .map(|(i, _)| *i),
+            )
+            .collect()
+    }
+
+    /// Replace the local trust chain with the `raw_root` and `raw_aa` certificates, checked
+    /// at `timestamp`. The AT certificates of the previous chain are dropped, as they are
+    /// not issued by the new AA, and should be added again with [SecurityService::add_at_cert].
+    /// Returns the [HashedId8] of the AA certificate.
+    pub fn set_own_authorities(
+        &mut self,
+        raw_root: &[u8],
+        raw_aa: &[u8],
+        timestamp: Instant,
+    ) -> Result<HashedId8, SecurityServiceError> {
+        let backend = self.backend.inner();
+        let root_cert = RootCertificate::from_bytes(raw_root, backend)
+            .and_then(|c| c.into_with_hash_container(backend))
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let root_valid = root_cert
+            .certificate()
+            .check(timestamp, backend, |_| None::<RootCertificate>)
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let aa_cert = AuthorizationAuthorityCertificate::from_bytes(raw_aa, backend)
+            .and_then(|c| c.into_with_hash_container(backend))
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let aa_valid = aa_cert
+            .certificate()
+            .check(timestamp, backend, |sh| {
+                (sh == root_cert.hashed_id8()).then(|| root_cert.certificate().clone())
+            })
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        if !root_valid || !aa_valid {
+            return Err(SecurityServiceError::SignerCertificateFalseSignature);
+        }
+
+        let aa_hash = aa_cert.hashed_id8();
+        let mut own_chain = TrustChain::new(root_cert);
+        own_chain.set_aa_cert(aa_cert);
+        for digest in self.store.own_chain().revoked_certs() {
+            own_chain.add_revoked_cert(*digest);
+        }
+        *self.store.own_chain_mut() = own_chain;
+
+        self.cert_inclusion_at.clear();
+        self.last_at_election_successful = false;
+
+        Ok(aa_hash)
+    }
+
+    /// Add the `raw` AT certificate at `index` to the AT certificates used to sign messages.
+    /// The certificate is checked at `timestamp` against the AA certificate of the local
+    /// trust chain, which should be its issuer.
+    /// The private key at `index` should be available in the backend.
+    /// Returns the [HashedId8] of the certificate.
+    pub fn add_at_cert(
+        &mut self,
+        index: usize,
+        raw: &[u8],
+        timestamp: Instant,
+    ) -> Result<HashedId8, SecurityServiceError> {
+        let backend = self.backend.inner();
+        let at_cert = AuthorizationTicketCertificate::from_bytes(raw, backend)
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let own_aa = self.store.own_chain().aa_cert();
+        let at_valid = at_cert
+            .check(timestamp, backend, |sh| {
+                own_aa
+                    .filter(|aa| aa.hashed_id8() == sh)
+                    .map(|aa| aa.certificate().clone())
+            })
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        if !at_valid {
+            return Err(SecurityServiceError::SignerCertificateFalseSignature);
+        }
+
+        let at_cert = at_cert
+            .into_with_hash_container(backend)
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+        let hashed_id8 = at_cert.hashed_id8();
+
+        if self.store.is_revoked(hashed_id8) {
+            return Err(SecurityServiceError::RevokedCertificate);
+        }
+
+        self.store
+            .own_chain_mut()
+            .add_at_cert(index, ATContainer::new(at_cert, 0));
+
+        Ok(hashed_id8)
+    }
+
+    /// Select the AT certificate identified by `hashed_id8` to sign messages at `timestamp`,
+    /// bypassing the election. Returns the index of the selected AT certificate.
+    pub fn select_at_cert(
+        &mut self,
+        hashed_id8: HashedId8,
+        timestamp: Instant,
+    ) -> Result<usize, SecurityServiceError> {
+        self.account_at_usage(timestamp);
+        let previous = self.store.own_chain().at_cert_index();
+        let index = self
+            .store
+            .own_chain()
+            .at_certs()
+            .iter()
+            .find(|(_, at)| at.at_container().hashed_id8() == hashed_id8)
+            .map(|(i, _)| *i)
+            .ok_or(SecurityServiceError::NoSigningCertificate)?;
+
+        self.backend
+            .inner_mut()
+            .set_at_key_index(index)
+            .map_err(SecurityServiceError::Backend)?;
+
+        self.store
+            .own_chain_mut()
+            .set_at_cert_index(index)
+            .map_err(|_| SecurityServiceError::NoSigningCertificate)?;
+
+        self.cert_inclusion_at.clear();
+        self.last_at_election_successful = true;
+        self.retire_at_cert(previous, index, timestamp);
+        self.notify_at_elected(index, timestamp);
+
+        Ok(index)
+    }
+
     /// Get the AT certificates elections statistics.
     pub fn at_certs_stats(&self) -> BTreeMap<usize, usize> {
         self.store
@@ -326,21 +962,19 @@ impl SecurityService {
             net_error!("Unable to find a candidate AT certificate: failure listing available AT key indexes: {}", e)
         ).ok()?;
 
-        // We filter certificates with no matching key, expired certificates
-        // and return the one with the lowest number of elections.
+        // We filter certificates with no matching key, expired certificates, certificates
+        // which reached their daily usage limit and return the one with the lowest number
+        // of elections.
+        let now = self.tai2004(timestamp);
+        let day = utc_day(timestamp);
+        let max_usage = self.privacy.inner().max_daily_usage();
         self.store
             .own_chain()
             .at_certs()
             .iter()
             .filter(|c| available_keys.iter().any(|e| e.0 == *c.0))
-            .filter(|c| {
-                c.1.at_container()
-                    .certificate()
-                    .validity_period()
-                    .end()
-                    .as_unix_instant()
-                    > timestamp
-            })
+            .filter(|c| c.1.at_container().certificate().validity_period().end() > now)
+            .filter(|c| max_usage.is_none_or(|max| c.1.usage_on(day) < max))
             .min_by_key(|c| c.1.elected())
             .map(|c| (*c.0, c.1.at_container().hashed_id8()))
     }
@@ -0,0 +1,342 @@
+//! Remote certificates handling, ie: Root, EA and AA certificates of other PKI entities learned
+//! over the air, and their persistence in a [StorageTrait] implementation, so they survive a
+//! restart of the station.
+
+use crate::{
+    security::{
+        certificate::{
+            AuthorizationAuthorityCertificate, CertificateError, CertificateTrait,
+            CertificateWithHashContainer, EnrollmentAuthorityCertificate, ExplicitCertificate,
+            RootCertificate,
+        },
+        service::{
+            cert_request::{CertificateRequestError, CertificateRequestResult},
+            SecurityService,
+        },
+        storage::{RemoteCertificateType, StorageError, StorageResult, StorageTrait},
+        trust_store::Store as TrustStore,
+        HashedId8,
+    },
+    time::{Instant, TAI2004},
+};
+
+impl SecurityService {
+    /// Add the remote certificate of type `kind`, encoded as Asn.1 COER in `bytes`, learned over
+    /// the air. Certificate is validated in time, against the revoked certificates and against
+    /// its issuer, which should be a known root certificate for EA and AA certificates. Root
+    /// certificates are only accepted if listed in the trusted ECTL, see
+    /// [TrustStore::set_trusted_roots].
+    /// Certificate is queued for persistence, see [SecurityService::persist_remote_certificates].
+    /// Returns the certificate [HashedId8].
+    pub fn add_remote_certificate(
+        &mut self,
+        kind: RemoteCertificateType,
+        bytes: &[u8],
+        timestamp: Instant,
+    ) -> CertificateRequestResult<HashedId8> {
+        let hash = self.insert_remote_certificate(kind, bytes, timestamp)?;
+        self.notify_remote_certificate_learned(kind, hash);
+
+        Ok(hash)
+    }
+
+    /// Queue the remote certificate of type `kind` identified with `hash` for persistence.
+    pub(super) fn notify_remote_certificate_learned(
+        &mut self,
+        kind: RemoteCertificateType,
+        hash: HashedId8,
+    ) {
+        if !self.learned_certs.contains(&(kind, hash)) {
+            self.learned_certs.push((kind, hash));
+        }
+    }
+
+    /// Query whether some learned remote certificates or revoked certificates are waiting to be
+    /// persisted.
+    pub fn has_remote_certificates_to_persist(&self) -> bool {
+        !self.learned_certs.is_empty() || self.store.revoked_certs() != self.persisted_revoked_certs
+    }
+
+    /// Persist the remote certificates learned since the last call and the revoked certificates
+    /// list into `storage`. Remote certificates which are expired at `timestamp`, revoked or
+    /// whose root is not trusted anymore are removed from the trust store and from `storage`.
+    pub fn persist_remote_certificates<S>(
+        &mut self,
+        storage: &S,
+        timestamp: Instant,
+    ) -> StorageResult<()>
+    where
+        S: StorageTrait + ?Sized,
+    {
+        for (kind, hash) in self.purge_remote_certificates(timestamp) {
+            match storage.remove_remote_certificate(kind, hash) {
+                Ok(_) | Err(StorageError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let revoked = self.store.revoked_certs();
+        if revoked != self.persisted_revoked_certs {
+            storage.store_revoked_certificates(&revoked)?;
+            self.persisted_revoked_certs = revoked;
+        }
+
+        while let Some((kind, hash)) = self.learned_certs.pop() {
+            let raw = match kind {
+                RemoteCertificateType::Root => {
+                    self.store.lookup_root(hash).map(|c| c.raw_bytes().to_vec())
+                }
+                RemoteCertificateType::EA => {
+                    self.store.lookup_ea(hash).map(|c| c.raw_bytes().to_vec())
+                }
+                RemoteCertificateType::AA => {
+                    self.store.lookup_aa(hash).map(|c| c.raw_bytes().to_vec())
+                }
+            };
+
+            // Certificate may have been purged in between.
+            let Some(raw) = raw else {
+                continue;
+            };
+
+            if let Err(e) = storage.store_remote_certificate(kind, hash, &raw) {
+                self.learned_certs.push((kind, hash));
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restore the revoked certificates list and the remote certificates persisted in `storage`
+    /// into the trust store. Each certificate is validated the same way as a certificate learned
+    /// over the air. Malformed, expired, revoked or invalid certificates are removed from `storage`.
+    /// Returns the number of restored certificates.
+    pub fn restore_remote_certificates<S>(
+        &mut self,
+        storage: &S,
+        timestamp: Instant,
+    ) -> StorageResult<usize>
+    where
+        S: StorageTrait + ?Sized,
+    {
+        // Revoked certificates should be restored first, so revoked ones are not restored.
+        match storage.load_revoked_certificates() {
+            Ok(revoked) => {
+                for hash in revoked.iter() {
+                    self.store.add_revoked_cert(*hash);
+                }
+                self.persisted_revoked_certs = revoked;
+                self.persisted_revoked_certs.sort_unstable();
+            }
+            Err(StorageError::NotFound) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut certs = storage.load_remote_certificates()?;
+
+        // Root certificates should be restored first, as they are the issuers of EA and AA ones.
+        certs.sort_by_key(|(kind, _, _)| *kind);
+
+        let mut restored = 0;
+        for (kind, digest, bytes) in certs {
+            let res = match self.remote_certificate_hash(kind, &bytes) {
+                Some(hash) if hash == digest => {
+                    self.insert_remote_certificate(kind, &bytes, timestamp)
+                }
+                Some(_) => Err(CertificateRequestError::Other),
+                None => Err(CertificateRequestError::InvalidCertificate(
+                    CertificateError::Malformed,
+                )),
+            };
+
+            match res {
+                Ok(_) => restored += 1,
+                Err(e) => {
+                    net_debug!(
+                        "Discarding stored remote {:?} certificate {}: {}",
+                        kind,
+                        digest,
+                        e
+                    );
+                    match storage.remove_remote_certificate(kind, digest) {
+                        Ok(_) | Err(StorageError::NotFound) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+
+        Ok(restored)
+    }
+
+    /// Remove the remote certificates expired at `timestamp` or revoked from the trust store.
+    /// Returns the type and [HashedId8] of each removed certificate.
+    fn purge_remote_certificates(
+        &mut self,
+        timestamp: Instant,
+    ) -> Vec<(RemoteCertificateType, HashedId8)> {
+        fn invalid<C: ExplicitCertificate>(
+            cert: &CertificateWithHashContainer<C>,
+            store: &TrustStore,
+            now: TAI2004,
+        ) -> bool {
+            store.is_revoked(cert.hashed_id8()) || cert.certificate().validity_period().end() <= now
+        }
+
+        let now = self.tai2004(timestamp);
+
+        let store = &self.store;
+        let mut purged: Vec<(RemoteCertificateType, HashedId8)> = Vec::new();
+        purged.extend(
+            store
+                .remote_roots()
+                .filter(|c| invalid(c, store, now) || !store.is_trusted_root(c.hashed_id8()))
+                .map(|c| (RemoteCertificateType::Root, c.hashed_id8())),
+        );
+        purged.extend(
+            store
+                .remote_eas()
+                .filter(|c| invalid(c, store, now))
+                .map(|c| (RemoteCertificateType::EA, c.hashed_id8())),
+        );
+        purged.extend(
+            store
+                .remote_aas()
+                .filter(|c| invalid(c, store, now))
+                .map(|c| (RemoteCertificateType::AA, c.hashed_id8())),
+        );
+
+        for (kind, hash) in purged.iter() {
+            match kind {
+                RemoteCertificateType::Root => {
+                    self.store.remove_remote_root(*hash);
+                }
+                RemoteCertificateType::EA => {
+                    self.store.remove_remote_ea(*hash);
+                }
+                RemoteCertificateType::AA => {
+                    self.store.remove_remote_aa(*hash);
+                }
+            }
+        }
+
+        purged
+    }
+
+    /// Validate and insert the remote certificate of type `kind`, encoded in `bytes`, into the
+    /// trust store. Returns the certificate [HashedId8].
+    fn insert_remote_certificate(
+        &mut self,
+        kind: RemoteCertificateType,
+        bytes: &[u8],
+        timestamp: Instant,
+    ) -> CertificateRequestResult<HashedId8> {
+        let backend = self.backend.inner();
+
+        match kind {
+            RemoteCertificateType::Root => {
+                let cert = RootCertificate::from_bytes(bytes, backend)
+                    .and_then(|c| c.into_with_hash_container(backend))
+                    .map_err(CertificateRequestError::InvalidCertificate)?;
+                let hash = cert.hashed_id8();
+
+                if self.store.is_revoked(hash) {
+                    return Err(CertificateRequestError::Revoked);
+                }
+
+                // A self-signed root certificate proves nothing by itself, it should be listed
+                // in the trusted ECTL to become a trust anchor.
+                if !self.store.is_trusted_root(hash) {
+                    return Err(CertificateRequestError::UntrustedRoot);
+                }
+
+                let valid = cert
+                    .certificate()
+                    .check(timestamp, backend, |_| None::<RootCertificate>)
+                    .map_err(CertificateRequestError::InvalidCertificate)?;
+
+                if !valid {
+                    return Err(CertificateRequestError::FalseSignature);
+                }
+
+                self.store.add_remote_root(cert);
+                Ok(hash)
+            }
+            RemoteCertificateType::EA => {
+                let cert = EnrollmentAuthorityCertificate::from_bytes(bytes, backend)
+                    .and_then(|c| c.into_with_hash_container(backend))
+                    .map_err(CertificateRequestError::InvalidCertificate)?;
+                let hash = cert.hashed_id8();
+                let signer_hash = self.check_remote_subordinate(&cert, timestamp)?;
+
+                self.store
+                    .set_remote_ea(signer_hash, cert)
+                    .map_err(|_| CertificateRequestError::Other)?;
+                Ok(hash)
+            }
+            RemoteCertificateType::AA => {
+                let cert = AuthorizationAuthorityCertificate::from_bytes(bytes, backend)
+                    .and_then(|c| c.into_with_hash_container(backend))
+                    .map_err(CertificateRequestError::InvalidCertificate)?;
+                let hash = cert.hashed_id8();
+                let signer_hash = self.check_remote_subordinate(&cert, timestamp)?;
+
+                self.store
+                    .set_remote_aa(signer_hash, cert)
+                    .map_err(|_| CertificateRequestError::Other)?;
+                Ok(hash)
+            }
+        }
+    }
+
+    /// Check the remote subordinate certificate `cert` against its issuer root certificate.
+    /// Returns the issuer [HashedId8].
+    fn check_remote_subordinate<C: ExplicitCertificate>(
+        &self,
+        cert: &CertificateWithHashContainer<C>,
+        timestamp: Instant,
+    ) -> CertificateRequestResult<HashedId8> {
+        if self.store.is_revoked(cert.hashed_id8()) {
+            return Err(CertificateRequestError::Revoked);
+        }
+
+        let mut signer_hash = Default::default();
+        let valid = cert
+            .certificate()
+            .check(timestamp, self.backend.inner(), |sh| {
+                signer_hash = sh;
+                self.store.lookup_root(sh)
+            })
+            .map_err(CertificateRequestError::InvalidCertificate)?;
+
+        if !valid {
+            return Err(CertificateRequestError::FalseSignature);
+        }
+
+        Ok(signer_hash)
+    }
+
+    /// Compute the [HashedId8] of the remote certificate of type `kind` encoded in `bytes`,
+    /// if it can be decoded.
+    fn remote_certificate_hash(
+        &self,
+        kind: RemoteCertificateType,
+        bytes: &[u8],
+    ) -> Option<HashedId8> {
+        let backend = self.backend.inner();
+        match kind {
+            RemoteCertificateType::Root => RootCertificate::from_bytes(bytes, backend)
+                .and_then(|c| c.hashed_id8(backend))
+                .ok(),
+            RemoteCertificateType::EA => EnrollmentAuthorityCertificate::from_bytes(bytes, backend)
+                .and_then(|c| c.hashed_id8(backend))
+                .ok(),
+            RemoteCertificateType::AA => {
+                AuthorizationAuthorityCertificate::from_bytes(bytes, backend)
+                    .and_then(|c| c.hashed_id8(backend))
+                    .ok()
+            }
+        }
+    }
+}
@@ -4,8 +4,10 @@ use crate::{
         certificate::{CertificateTrait, ExplicitCertificate},
         permission::{Permission, AID},
         secured_message::{SecuredMessage, SignerIdentifier},
+        signer_policy::SignerIdentifierPolicy,
+        HashAlgorithm,
     },
-    time::{Duration, Instant, TAI2004},
+    time::Instant,
 };
 
 use super::{SecurityService, SecurityServiceError};
@@ -24,6 +26,17 @@ impl SecurityService {
         timestamp: Instant,
         position: PotiPosition,
     ) -> SignResult {
+        // CAMs would allow to link pseudonyms through the station trajectory. Other messages
+        // are still signed, the Geonetworking layer drops them if they carry the ego position
+        // vector.
+        if matches!(permission, Permission::CAM(_)) && self.is_silent(timestamp) {
+            return Err(SecurityServiceError::SilentPeriod);
+        }
+
+        if !self.is_time_accurate() {
+            return Err(SecurityServiceError::InsufficientTimeAccuracy);
+        }
+
         let at = self
             .store
             .own_chain()
@@ -32,7 +45,8 @@ impl SecurityService {
             .at_container();
 
         // Check AT is not expired.
-        if TAI2004::from_unix_instant(timestamp) > at.certificate().validity_period().end() {
+        let generation_time = self.tai2004(timestamp);
+        if generation_time > at.certificate().validity_period().end() {
             return Err(SecurityServiceError::OffValidityPeriod);
         }
 
@@ -49,7 +63,7 @@ impl SecurityService {
 
         // Set generation time.
         message
-            .set_generation_time(TAI2004::from_unix_instant(timestamp))
+            .set_generation_time(generation_time)
             .map_err(SecurityServiceError::InvalidContent)?;
 
         // Set application identifier.
@@ -57,10 +71,27 @@ impl SecurityService {
             .set_application_id(permission.aid())
             .map_err(SecurityServiceError::InvalidContent)?;
 
-        let signer = match permission.aid() {
-            AID::CA if self.at_cert_in_cam_at > timestamp => {
+        let aid = permission.aid();
+        let include_cert = match self.signer_policy.get(aid) {
+            SignerIdentifierPolicy::Certificate => true,
+            SignerIdentifierPolicy::Digest => false,
+            SignerIdentifierPolicy::Periodic(interval) => {
+                match self.cert_inclusion_at.get(&aid) {
+                    Some(at) if *at > timestamp => false,
+                    _ => {
+                        // Reset timer.
+                        self.cert_inclusion_at.insert(aid, timestamp + interval);
+                        true
+                    }
+                }
+            }
+        };
+
+        match aid {
+            AID::CA => {
                 // Add our AA certificate in the secured message, if necessary.
-                if self.aa_cert_in_cam {
+                // Requested certificate is sent only if the signer is digest.
+                if !include_cert && self.aa_cert_in_cam {
                     self.aa_cert_in_cam = false;
                     self.store
                         .own_chain()
@@ -83,36 +114,21 @@ impl SecurityService {
                 message
                     .set_p2p_requested_certificates(p2p_hashes)
                     .map_err(SecurityServiceError::InvalidContent)?;
-
-                // Fill Secured Message with the AT certificate HashedId8.
-                SignerIdentifier::Digest(at.hashed_id8().into())
-            }
-            AID::CA => {
-                // Add P2P requested certificates in the secured message.
-                let p2p_hashes = self
-                    .p2p_requested_certs
-                    .drain(..)
-                    .map(|cert_hash| cert_hash.into())
-                    .collect();
-
-                message
-                    .set_p2p_requested_certificates(p2p_hashes)
-                    .map_err(SecurityServiceError::InvalidContent)?;
-
-                // Reset timer and fill Secured Message with the AT certificate.
-                self.at_cert_in_cam_at = timestamp + Duration::from_secs(1);
-                SignerIdentifier::Certificate(at.certificate().inner().clone())
             }
             AID::DEN => {
                 // Set the generation location for the signature.
                 message
                     .set_generation_location(position.as_3d_location())
                     .map_err(SecurityServiceError::InvalidContent)?;
-                // Fill Secured Message with the AT certificate.
-                SignerIdentifier::Certificate(at.certificate().inner().clone())
             }
-            // Fill Secured Message with AT certificate.
-            _ => SignerIdentifier::Certificate(at.certificate().inner().clone()),
+            _ => {}
+        }
+
+        // Fill Secured Message with the AT certificate or its HashedId8.
+        let signer = if include_cert {
+            SignerIdentifier::Certificate(at.certificate().inner().clone())
+        } else {
+            SignerIdentifier::Digest(at.hashed_id8().into())
         };
 
         message
@@ -124,8 +140,25 @@ impl SecurityService {
             .to_be_signed_bytes()
             .map_err(SecurityServiceError::InvalidContent)?;
 
+        // Hash algorithm is determined by the AT verification key, ie: SM3 for SM2 keys.
+        let hash_algorithm = at
+            .certificate()
+            .public_verification_key()
+            .map_err(SecurityServiceError::InvalidCertificate)?
+            .hash_algorithm();
+
         let backend = self.backend.inner();
-        let hash = [backend.sha256(&tbs), backend.sha256(signer_data)].concat();
+        let hash = match hash_algorithm {
+            HashAlgorithm::SHA256 => [backend.sha256(&tbs), backend.sha256(signer_data)].concat(),
+            HashAlgorithm::SHA384 => [backend.sha384(&tbs), backend.sha384(signer_data)].concat(),
+            HashAlgorithm::SM3 => [
+                backend.sm3(&tbs).map_err(SecurityServiceError::Backend)?,
+                backend
+                    .sm3(signer_data)
+                    .map_err(SecurityServiceError::Backend)?,
+            ]
+            .concat(),
+        };
 
         let signature = backend
             .generate_signature(&hash)
@@ -1,16 +1,19 @@
 use crate::{
     security::{
+        backend::SignatureVerification,
         certificate::{
             AuthorizationTicketCertificate, CertificateError, CertificateTrait, ExplicitCertificate,
         },
         permission::{Permission, AID},
         secured_message::{SecuredMessage, SignerIdentifier},
-        HashAlgorithm, HashedId8,
+        signature::EcdsaSignature,
+        signature_cache::SignatureCacheKey,
+        EccPoint, HashAlgorithm, HashedId8,
     },
     time::Instant,
 };
 
-use super::{SecurityService, SecurityServiceError};
+use super::{SecurityService, SecurityServiceError, VerificationMode};
 
 /// Verify service confirmation.
 pub struct VerifyConfirm {
@@ -19,6 +22,9 @@ pub struct VerifyConfirm {
     pub cert_id: HashedId8,
     /// Service Specific Permissions.
     pub permissions: Permission,
+    /// Deferred signature verification, in [VerificationMode::OnDemand] mode.
+    /// [None] if the signature has already been verified.
+    pub pending: Option<SignatureVerification>,
 }
 
 /// Verify service result type.
@@ -26,11 +32,117 @@ pub type VerifyResult = Result<VerifyConfirm, SecurityServiceError>;
 
 impl SecurityService {
     /// Verify the signature of a secured message.
+    /// In [VerificationMode::OnDemand] mode, the signature verification itself is deferred
+    /// and returned in [VerifyConfirm::pending].
     pub fn verify_secured_message(
         &mut self,
         msg: &SecuredMessage,
         timestamp: Instant,
     ) -> VerifyResult {
+        let (cert_id, permissions, verification) = self.prepare_verification(msg, timestamp)?;
+
+        let pending = match self.verification_mode {
+            VerificationMode::Immediate => {
+                self.check_signature(cert_id, &verification, timestamp)?;
+                None
+            }
+            VerificationMode::OnDemand => {
+                // Signature cache is looked up once the message is consumed, sparing
+                // the cache key digest of the messages which are never consumed.
+                self.verification_stats.deferred += 1;
+                Some(verification)
+            }
+        };
+
+        Ok(VerifyConfirm {
+            cert_id,
+            permissions,
+            pending,
+        })
+    }
+
+    /// Verifies the signature described in `verification`, issued by the `cert_id`
+    /// certificate. Signature cache is looked up first, and filled on success.
+    pub(super) fn check_signature(
+        &mut self,
+        cert_id: HashedId8,
+        verification: &SignatureVerification,
+        timestamp: Instant,
+    ) -> Result<(), SecurityServiceError> {
+        let key = self.signature_cache_key(cert_id, verification);
+        if self.sig_cache.lookup(&key, timestamp) {
+            self.verification_stats.cache_hits += 1;
+            return Ok(());
+        }
+
+        // Signature has been verified in a batch, ahead of the message processing.
+        if let Some(res) = self.batch_results.remove(&key) {
+            self.verification_stats.batched += 1;
+            return self.record_signature(key, res, timestamp);
+        }
+
+        let res = self
+            .backend
+            .inner()
+            .verify_signature(
+                verification.signature.clone(),
+                verification.key.clone(),
+                &verification.data,
+            )
+            .map_err(SecurityServiceError::Backend)?;
+
+        self.record_signature(key, res, timestamp)
+    }
+
+    /// Accounts the signature verification result `res` of the signature identified by `key`.
+    pub(super) fn record_signature(
+        &mut self,
+        key: SignatureCacheKey,
+        res: bool,
+        timestamp: Instant,
+    ) -> Result<(), SecurityServiceError> {
+        self.verification_stats.verified += 1;
+
+        if res {
+            self.sig_cache.fill(key, timestamp);
+            Ok(())
+        } else {
+            self.verification_stats.failed += 1;
+            Err(SecurityServiceError::FalseSignature)
+        }
+    }
+
+    /// Computes the signature cache key of `verification`, issued by the `cert_id` certificate.
+    pub(super) fn signature_cache_key(
+        &self,
+        cert_id: HashedId8,
+        verification: &SignatureVerification,
+    ) -> SignatureCacheKey {
+        // Both signature components are hashed, so a message with a tampered `r` does not
+        // match the cache entry of the genuine one.
+        let sig = verification.signature.inner();
+        let (r_type, r_x, r_y): (u8, &[u8], &[u8]) = match &sig.r {
+            EccPoint::XCoordinateOnly(x) => (0x00, x.as_slice(), &[]),
+            EccPoint::CompressedY0(x) => (0x02, x.as_slice(), &[]),
+            EccPoint::CompressedY1(x) => (0x03, x.as_slice(), &[]),
+            EccPoint::Uncompressed(p) => (0x04, p.x.as_slice(), p.y.as_slice()),
+        };
+        let digest = self
+            .backend
+            .inner()
+            .sha256(&[verification.data.as_slice(), &[r_type], r_x, r_y, &sig.s].concat());
+
+        (cert_id, digest)
+    }
+
+    /// Runs all the checks on a secured message, except the signature verification itself.
+    /// Returns the signer certificate digest, its permissions for the message AID and
+    /// the signature verification to perform.
+    fn prepare_verification(
+        &mut self,
+        msg: &SecuredMessage,
+        timestamp: Instant,
+    ) -> Result<(HashedId8, Permission, SignatureVerification), SecurityServiceError> {
         // Retrieve generation time.
         let generation_time = msg
             .generation_time()
@@ -74,7 +186,7 @@ impl SecurityService {
                 // Per ETSI TS 103 097 v2.1.1, paragraph 7.1.1, we shall
                 // include the AT certificate in next CAM transmission if it is
                 // included in the requested certificates.
-                self.at_cert_in_cam_at = Instant::ZERO;
+                self.cert_inclusion_at.remove(&AID::CA);
             }
 
             if aa_requested {
@@ -106,7 +218,7 @@ impl SecurityService {
                     None => {
                         // Per ETSI TS 103 097 v2.1.1, paragraph 7.1.1, we shall
                         // include the AT certificate in next CAM transmission.
-                        self.at_cert_in_cam_at = Instant::ZERO;
+                        self.cert_inclusion_at.remove(&AID::CA);
                         // We should also request the unknown certificate.
                         self.p2p_requested_certs.push(at_digest);
 
@@ -149,16 +261,58 @@ impl SecurityService {
             }
         };
 
+        // Reject messages from senders blacklisted by the misbehavior detection.
+        if self.misbehavior.is_blacklisted(&at_digest, timestamp) {
+            return Err(SecurityServiceError::BlacklistedSigner);
+        }
+
         // Get public verification key.
         let signer_pubkey = signer_cert
             .public_verification_key()
             .map_err(SecurityServiceError::InvalidCertificate)?;
-        let signer_data = signer_cert.raw_bytes();
 
         // Get content to verify.
+        let hash = self.signed_data(msg, &signature, &signer_cert)?;
+
+        // Verify AID permission.
+        let signer_permissions = signer_cert
+            .application_permissions()
+            .map_err(SecurityServiceError::InvalidCertificate)?;
+
+        let Some(permission) = signer_permissions.iter().find(|e| e.aid() == aid) else {
+            return Err(SecurityServiceError::InsufficientPermissions);
+        };
+
+        // Verify generation time vs cert validity period.
+        if !signer_cert
+            .validity_period()
+            .contains_instant(generation_time)
+        {
+            return Err(SecurityServiceError::OffValidityPeriod);
+        }
+
+        Ok((
+            at_digest,
+            permission.to_owned(),
+            SignatureVerification {
+                signature,
+                key: signer_pubkey,
+                data: hash,
+            },
+        ))
+    }
+    /// Computes the data signed with `signature` in `msg` by the `signer_cert` holder, ie: the
+    /// hash of the message content concatenated with the hash of the signer certificate.
+    fn signed_data(
+        &self,
+        msg: &SecuredMessage,
+        signature: &EcdsaSignature,
+        signer_cert: &AuthorizationTicketCertificate,
+    ) -> Result<Vec<u8>, SecurityServiceError> {
         let tbs = msg
             .to_be_signed_bytes()
             .map_err(SecurityServiceError::InvalidContent)?;
+        let signer_data = signer_cert.raw_bytes();
 
         let backend = self.backend.inner();
         let hash = match signature.hash_algorithm() {
@@ -173,34 +327,77 @@ impl SecurityService {
             .concat(),
         };
 
-        // Verify AID permission.
-        let signer_permissions = signer_cert
-            .application_permissions()
-            .map_err(SecurityServiceError::InvalidCertificate)?;
+        Ok(hash)
+    }
 
-        let Some(permission) = signer_permissions.iter().find(|e| e.aid() == aid) else {
-            return Err(SecurityServiceError::InsufficientPermissions);
-        };
+    /// Verifies the signatures of the received `messages` in a single backend batch, ahead of
+    /// their processing. Only the messages signed with the digest of a cached certificate are
+    /// part of the batch, the other ones are verified when processed. Results are accounted
+    /// when the messages are verified with [Self::verify_secured_message].
+    pub fn verify_batch(&mut self, messages: &[SecuredMessage], timestamp: Instant) {
+        // Results of the previous batch not accounted yet belong to messages rejected before
+        // their signature check.
+        self.batch_results.clear();
 
-        // Verify generation time vs cert validity period.
-        if !signer_cert
-            .validity_period()
-            .contains_instant(generation_time)
-        {
-            return Err(SecurityServiceError::OffValidityPeriod);
+        let mut keys = Vec::new();
+        let mut batch = Vec::new();
+        for msg in messages {
+            let Some((cert_id, verification)) = self.batch_verification(msg, timestamp) else {
+                continue;
+            };
+
+            // A message received several times is verified once.
+            let key = self.signature_cache_key(cert_id, &verification);
+            if self.sig_cache.lookup(&key, timestamp) || keys.contains(&key) {
+                continue;
+            }
+
+            keys.push(key);
+            batch.push(verification);
         }
 
-        let res = backend
-            .verify_signature(signature, signer_pubkey, &hash)
-            .map_err(SecurityServiceError::Backend)?;
+        if batch.is_empty() {
+            return;
+        }
 
-        if res {
-            Ok(VerifyConfirm {
-                cert_id: at_digest,
-                permissions: permission.to_owned(),
-            })
-        } else {
-            Err(SecurityServiceError::FalseSignature)
+        let results = self.backend.inner().verify_signatures(&batch);
+        for (key, res) in keys.into_iter().zip(results) {
+            // On backend error, the signature is verified again when the message is processed.
+            if let Ok(res) = res {
+                self.batch_results.insert(key, res);
+            }
         }
     }
+
+    /// Builds the signature verification of `msg`, if it is signed with the digest of a
+    /// cached certificate. Unlike [Self::prepare_verification], no check is performed on the
+    /// message and the security service state is left untouched.
+    fn batch_verification(
+        &self,
+        msg: &SecuredMessage,
+        timestamp: Instant,
+    ) -> Option<(HashedId8, SignatureVerification)> {
+        let SignerIdentifier::Digest(hash) = msg.signer_identifier().ok()? else {
+            return None;
+        };
+
+        let cert_id = HashedId8::from(&hash);
+        if self.misbehavior.is_blacklisted(&cert_id, timestamp) {
+            return None;
+        }
+
+        let signer_cert = self.cache.lookup(&cert_id, timestamp)?;
+        let signature = msg.signature().ok()?;
+        let key = signer_cert.public_verification_key().ok()?;
+        let data = self.signed_data(msg, &signature, &signer_cert).ok()?;
+
+        Some((
+            cert_id,
+            SignatureVerification {
+                signature,
+                key,
+                data,
+            },
+        ))
+    }
 }
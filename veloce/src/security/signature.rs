@@ -1,7 +1,7 @@
 use core::fmt;
 
 use veloce_asn1::defs::etsi_103097_v211::ieee1609_dot2_base_types::{
-    EcdsaP256Signature, EcdsaP384Signature, Signature as EtsiSignature,
+    EcdsaP256Signature, EcdsaP384Signature, EcsigP256Signature, Signature as EtsiSignature,
 };
 
 use super::{EccPoint, EccPointUnsupportedCoordinatesErr, HashAlgorithm};
@@ -41,6 +41,9 @@ pub enum EcdsaSignature {
     BrainpoolP256r1(EcdsaSignatureInner),
     /// Brainpool 384 R1 key type based signature.
     BrainpoolP384r1(EcdsaSignatureInner),
+    /// SM2 key type based signature, as defined in GB/T 32918.2.
+    /// The `r` component is an integer, stored as an [EccPoint::XCoordinateOnly].
+    Sm2(EcdsaSignatureInner),
 }
 
 impl EcdsaSignature {
@@ -53,6 +56,18 @@ impl EcdsaSignature {
             EcdsaSignature::NistP384r1(_) | EcdsaSignature::BrainpoolP384r1(_) => {
                 HashAlgorithm::SHA384
             }
+            EcdsaSignature::Sm2(_) => HashAlgorithm::SM3,
+        }
+    }
+
+    /// Get the inner representation of the signature.
+    pub fn inner(&self) -> &EcdsaSignatureInner {
+        match self {
+            EcdsaSignature::NistP256r1(i)
+            | EcdsaSignature::NistP384r1(i)
+            | EcdsaSignature::BrainpoolP256r1(i)
+            | EcdsaSignature::BrainpoolP384r1(i)
+            | EcdsaSignature::Sm2(i) => i,
         }
     }
 }
@@ -90,6 +105,10 @@ impl TryFrom<&EtsiSignature> for EcdsaSignature {
                     s: s.s_sig.to_vec(),
                 })
             }
+            EtsiSignature::sm2Signature(s) => EcdsaSignature::Sm2(EcdsaSignatureInner {
+                r: EccPoint::XCoordinateOnly(s.r_sig.to_vec()),
+                s: s.s_sig.to_vec(),
+            }),
             _ => return Err(EcdsaSignatureError::UnsupportedType),
         };
 
@@ -138,6 +157,18 @@ impl TryInto<EtsiSignature> for EcdsaSignature {
                     s_sig: i.s.try_into().map_err(|_| EcdsaSignatureError::Other)?,
                 })
             }
+            EcdsaSignature::Sm2(i) => {
+                let EccPoint::XCoordinateOnly(r) = i.r else {
+                    return Err(EcdsaSignatureError::UnsupportedCoordinates(
+                        EccPointUnsupportedCoordinatesErr,
+                    ));
+                };
+
+                EtsiSignature::sm2Signature(EcsigP256Signature {
+                    r_sig: r.try_into().map_err(|_| EcdsaSignatureError::Other)?,
+                    s_sig: i.s.try_into().map_err(|_| EcdsaSignatureError::Other)?,
+                })
+            }
         };
 
         Ok(res)
@@ -0,0 +1,70 @@
+//! Signature cache stores the already verified signatures of received
+//! secured messages, identified by the signer certificate HashedId8 (or digest)
+//! and a digest of the signed data along the signature.
+//! A message received several times, ie: a multi-hop forwarded packet, is verified only once.
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map::BTreeMap;
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::config::{SEC_SIG_CACHE_ENTRY_LIFETIME, SEC_SIG_CACHE_SIZE};
+use crate::time::Instant;
+
+use super::HashedId8;
+
+/// Signature cache key, ie: the signer certificate digest along the SHA256
+/// digest of the signed data and signature.
+pub(crate) type SignatureCacheKey = (HashedId8, [u8; 32]);
+
+#[derive(Debug)]
+pub struct SignatureCache {
+    storage: BTreeMap<SignatureCacheKey, Instant>,
+}
+
+impl SignatureCache {
+    /// Create a Signature Cache.
+    pub const fn new() -> Self {
+        Self {
+            storage: BTreeMap::new(),
+        }
+    }
+
+    /// Insert the verified signature identified by `key` in the cache.
+    pub fn fill(&mut self, key: SignatureCacheKey, timestamp: Instant) {
+        // Evict expired entries.
+        self.storage.retain(|_, expires_at| *expires_at > timestamp);
+
+        // Cache is full, evict the entry expiring first. A signature already in the cache
+        // only has its lifetime extended.
+        if self.storage.len() >= SEC_SIG_CACHE_SIZE && !self.storage.contains_key(&key) {
+            let oldest = self
+                .storage
+                .iter()
+                .min_by_key(|(_, expires_at)| **expires_at)
+                .map(|(k, _)| *k);
+
+            if let Some(k) = oldest {
+                self.storage.remove(&k);
+            }
+        }
+
+        self.storage
+            .insert(key, timestamp + SEC_SIG_CACHE_ENTRY_LIFETIME);
+    }
+
+    /// Search the signature cache for a signature identified with `key`.
+    /// Returns whether the signature has already been verified.
+    pub(crate) fn lookup(&self, key: &SignatureCacheKey, timestamp: Instant) -> bool {
+        self.storage
+            .get(key)
+            .is_some_and(|expires_at| *expires_at > timestamp)
+    }
+
+    /// Removes all the entries of the Signature Cache.
+    #[allow(unused)]
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+}
@@ -0,0 +1,96 @@
+//! Signer identifier policy, ie: whether a secured message carries the full
+//! AT certificate or only its digest, as a trade-off between message size and
+//! the ability of receivers to verify the message without prior knowledge of
+//! the certificate.
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map::BTreeMap;
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::time::Duration;
+
+use super::permission::AID;
+
+/// Signer identifier selection rule of an application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SignerIdentifierPolicy {
+    /// Always include the full AT certificate.
+    Certificate,
+    /// Always include the AT certificate digest. For testing purposes, as
+    /// receivers without the certificate will not be able to verify the message.
+    Digest,
+    /// Include the full AT certificate once per `interval`, the digest otherwise.
+    /// Certificate is also included in the next message after a new neighbour has been
+    /// detected, or when a receiver requested it.
+    Periodic(Duration),
+}
+
+/// Per ITS-AID signer identifier policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerPolicy {
+    /// Policy of the applications without a specific rule.
+    default: SignerIdentifierPolicy,
+    /// Application specific rules.
+    rules: BTreeMap<AID, SignerIdentifierPolicy>,
+}
+
+impl SignerPolicy {
+    /// Constructs a [SignerPolicy] applying `default` to all the applications.
+    pub fn new(default: SignerIdentifierPolicy) -> Self {
+        Self {
+            default,
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Constructs the [SignerPolicy] of the Car 2 Car Consortium Vehicle C-ITS station
+    /// profile, ie: CAMs carry the certificate once per second, the other messages
+    /// always carry the certificate.
+    pub fn car2car() -> Self {
+        let mut policy = Self::new(SignerIdentifierPolicy::Certificate);
+        policy.set(
+            AID::CA,
+            SignerIdentifierPolicy::Periodic(Duration::from_secs(1)),
+        );
+        policy
+    }
+
+    /// Constructs a [SignerPolicy] where all the messages carry the certificate digest.
+    pub fn digest_only() -> Self {
+        Self::new(SignerIdentifierPolicy::Digest)
+    }
+
+    /// Set the policy of the `aid` application.
+    pub fn set(&mut self, aid: AID, policy: SignerIdentifierPolicy) {
+        self.rules.insert(aid, policy);
+    }
+
+    /// Set the policy of the applications without a specific rule.
+    pub fn set_default(&mut self, policy: SignerIdentifierPolicy) {
+        self.default = policy;
+    }
+
+    /// Get the policy of the `aid` application.
+    pub fn get(&self, aid: AID) -> SignerIdentifierPolicy {
+        self.rules.get(&aid).copied().unwrap_or(self.default)
+    }
+}
+
+impl Default for SignerPolicy {
+    fn default() -> Self {
+        Self::car2car()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SignerPolicy {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "default: {:?}", self.default);
+        for (aid, policy) in self.rules.iter() {
+            defmt::write!(f, ", {:?}: {:?}", aid, policy);
+        }
+    }
+}
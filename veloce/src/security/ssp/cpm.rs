@@ -0,0 +1,23 @@
+//! Collective Perception messages SSP definition.
+//! See ETSI TS 103 324 V2.1.1.
+
+version_ssp! {
+    /// Collective Perception Message Service Specific Permissions.
+    pub struct CpmSsp;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CpmSsp;
+    use crate::security::ssp::{SspContainer, SspTrait};
+
+    #[test]
+    fn test_contains_permissions_of() {
+        let v0 = CpmSsp(SspContainer::new(0));
+        let v1 = CpmSsp::new();
+
+        assert!(v1.contains_permissions_of(&CpmSsp::new()));
+        assert!(!v1.contains_permissions_of(&v0));
+        assert!(!v0.contains_permissions_of(&v1));
+    }
+}
@@ -0,0 +1,7 @@
+//! IPv6 over GeoNetworking SSP definition, ie: GN6ASL packets.
+//! See ETSI TS 102 636-6-1.
+
+version_ssp! {
+    /// IPv6 over GeoNetworking Service Specific Permissions.
+    pub struct Gn6Ssp;
+}
@@ -0,0 +1,7 @@
+//! GNSS Positioning Correction messages SSP definition, ie: RTCMEM messages.
+//! See ETSI TS 103 301 V2.1.1.
+
+version_ssp! {
+    /// GNSS Positioning Correction Service Specific Permissions.
+    pub struct GpcSsp;
+}
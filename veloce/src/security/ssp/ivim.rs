@@ -0,0 +1,142 @@
+//! IVIM messages SSP definition, ie: In Vehicle Information service.
+//! See ETSI TS 103 301 V2.1.1.
+
+use super::{SspContainer, SspError, SspResult, SspTrait, SSP_VERSION_1};
+
+mod field {
+    /// ImmediateDangerWarningMessages IviType signing permission bit position.
+    pub const IMMEDIATE_DANGER_WARNING: u8 = 7;
+    /// RegulatoryMessages IviType signing permission bit position.
+    pub const REGULATORY: u8 = 6;
+    /// TrafficRelatedInformationMessages IviType signing permission bit position.
+    pub const TRAFFIC_RELATED_INFORMATION: u8 = 5;
+    /// PollutionMessages IviType signing permission bit position.
+    pub const POLLUTION: u8 = 4;
+    /// NotTrafficRelatedInformationMessages IviType signing permission bit position.
+    pub const NOT_TRAFFIC_RELATED_INFORMATION: u8 = 3;
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// IVIM signing permissions parameters, per IviType.
+pub enum IvimPermission {
+    /// ImmediateDangerWarningMessages IviType signing permission.
+    ImmediateDangerWarning,
+    /// RegulatoryMessages IviType signing permission.
+    Regulatory,
+    /// TrafficRelatedInformationMessages IviType signing permission.
+    TrafficRelatedInformation,
+    /// PollutionMessages IviType signing permission.
+    Pollution,
+    /// NotTrafficRelatedInformationMessages IviType signing permission.
+    NotTrafficRelatedInformation,
+}
+
+/// Length for IVIM SSP.
+const IVIM_SSP_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// IVIM Service Specific Permissions.
+pub struct IvimSsp(SspContainer<IVIM_SSP_LEN>);
+
+impl IvimSsp {
+    /// Constructs an [IvimSsp].
+    pub const fn new() -> IvimSsp {
+        IvimSsp(SspContainer::new(SSP_VERSION_1))
+    }
+
+    /// Get the size of [IvimSsp] in buffer.
+    pub const fn buf_size() -> usize {
+        IVIM_SSP_LEN
+    }
+
+    /// Constructs a [IvimSsp] from the provided `permissions` value.
+    pub const fn from_raw_permissions(permissions: u8) -> IvimSsp {
+        IvimSsp(SspContainer::from_slice([SSP_VERSION_1, permissions]))
+    }
+
+    /// Constructs a [IvimSsp] from bytes, ensuring length and
+    /// version are supported.
+    pub fn parse(buf: &[u8]) -> SspResult<IvimSsp> {
+        // Ensure no panics.
+        if buf.len() < IVIM_SSP_LEN {
+            return Err(SspError::Length);
+        }
+
+        // Ensure version is supported.
+        if buf[0] != SSP_VERSION_1 {
+            return Err(SspError::Version);
+        }
+
+        Ok(IvimSsp(SspContainer::from_bytes(&buf[..IVIM_SSP_LEN])))
+    }
+
+    /// Emit the SSP as a byte array, consuming itself.
+    pub const fn emit(self) -> [u8; IVIM_SSP_LEN] {
+        self.0.into_inner()
+    }
+}
+
+impl Default for IvimSsp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SspTrait for IvimSsp {
+    type SspType = IvimSsp;
+    type PermissionType = IvimPermission;
+
+    fn contains_permissions_of(&self, other: &Self::SspType) -> bool {
+        self.0.inner[1] | other.0.inner[1] == self.0.inner[1]
+    }
+
+    fn has_permission(&self, permission: Self::PermissionType) -> bool {
+        match permission {
+            IvimPermission::ImmediateDangerWarning => {
+                self.0.read_bit::<1, { field::IMMEDIATE_DANGER_WARNING }>()
+            }
+            IvimPermission::Regulatory => self.0.read_bit::<1, { field::REGULATORY }>(),
+            IvimPermission::TrafficRelatedInformation => self
+                .0
+                .read_bit::<1, { field::TRAFFIC_RELATED_INFORMATION }>(),
+            IvimPermission::Pollution => self.0.read_bit::<1, { field::POLLUTION }>(),
+            IvimPermission::NotTrafficRelatedInformation => self
+                .0
+                .read_bit::<1, { field::NOT_TRAFFIC_RELATED_INFORMATION }>(),
+        }
+    }
+
+    fn set_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            IvimPermission::ImmediateDangerWarning => self
+                .0
+                .write_bit::<1, { field::IMMEDIATE_DANGER_WARNING }>(true),
+            IvimPermission::Regulatory => self.0.write_bit::<1, { field::REGULATORY }>(true),
+            IvimPermission::TrafficRelatedInformation => self
+                .0
+                .write_bit::<1, { field::TRAFFIC_RELATED_INFORMATION }>(true),
+            IvimPermission::Pollution => self.0.write_bit::<1, { field::POLLUTION }>(true),
+            IvimPermission::NotTrafficRelatedInformation => self
+                .0
+                .write_bit::<1, { field::NOT_TRAFFIC_RELATED_INFORMATION }>(true),
+        }
+    }
+
+    fn clear_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            IvimPermission::ImmediateDangerWarning => self
+                .0
+                .write_bit::<1, { field::IMMEDIATE_DANGER_WARNING }>(false),
+            IvimPermission::Regulatory => self.0.write_bit::<1, { field::REGULATORY }>(false),
+            IvimPermission::TrafficRelatedInformation => self
+                .0
+                .write_bit::<1, { field::TRAFFIC_RELATED_INFORMATION }>(false),
+            IvimPermission::Pollution => self.0.write_bit::<1, { field::POLLUTION }>(false),
+            IvimPermission::NotTrafficRelatedInformation => self
+                .0
+                .write_bit::<1, { field::NOT_TRAFFIC_RELATED_INFORMATION }>(false),
+        }
+    }
+}
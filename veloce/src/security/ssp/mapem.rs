@@ -0,0 +1,109 @@
+//! MAPEM messages SSP definition, ie: Road Lane Topology service.
+//! See ETSI TS 103 301 V2.1.1.
+
+use super::{SspContainer, SspError, SspResult, SspTrait, SSP_VERSION_1};
+
+mod field {
+    /// IntersectionGeometry / intersections signing permission bit position.
+    pub const INTERSECTION_GEOMETRY: u8 = 7;
+    /// RoadSegment / roadSegments signing permission bit position.
+    pub const ROAD_SEGMENT: u8 = 6;
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// MAPEM signing permissions parameters.
+pub enum MapemPermission {
+    /// IntersectionGeometry / intersections signing permission.
+    IntersectionGeometry,
+    /// RoadSegment / roadSegments signing permission.
+    RoadSegment,
+}
+
+/// Length for MAPEM SSP.
+const MAPEM_SSP_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// MAPEM Service Specific Permissions.
+pub struct MapemSsp(SspContainer<MAPEM_SSP_LEN>);
+
+impl MapemSsp {
+    /// Constructs an [MapemSsp].
+    pub const fn new() -> MapemSsp {
+        MapemSsp(SspContainer::new(SSP_VERSION_1))
+    }
+
+    /// Get the size of [MapemSsp] in buffer.
+    pub const fn buf_size() -> usize {
+        MAPEM_SSP_LEN
+    }
+
+    /// Constructs a [MapemSsp] from the provided `permissions` value.
+    pub const fn from_raw_permissions(permissions: u8) -> MapemSsp {
+        MapemSsp(SspContainer::from_slice([SSP_VERSION_1, permissions]))
+    }
+
+    /// Constructs a [MapemSsp] from bytes, ensuring length and
+    /// version are supported.
+    pub fn parse(buf: &[u8]) -> SspResult<MapemSsp> {
+        // Ensure no panics.
+        if buf.len() < MAPEM_SSP_LEN {
+            return Err(SspError::Length);
+        }
+
+        // Ensure version is supported.
+        if buf[0] != SSP_VERSION_1 {
+            return Err(SspError::Version);
+        }
+
+        Ok(MapemSsp(SspContainer::from_bytes(&buf[..MAPEM_SSP_LEN])))
+    }
+
+    /// Emit the SSP as a byte array, consuming itself.
+    pub const fn emit(self) -> [u8; MAPEM_SSP_LEN] {
+        self.0.into_inner()
+    }
+}
+
+impl Default for MapemSsp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SspTrait for MapemSsp {
+    type SspType = MapemSsp;
+    type PermissionType = MapemPermission;
+
+    fn contains_permissions_of(&self, other: &Self::SspType) -> bool {
+        self.0.inner[1] | other.0.inner[1] == self.0.inner[1]
+    }
+
+    fn has_permission(&self, permission: Self::PermissionType) -> bool {
+        match permission {
+            MapemPermission::IntersectionGeometry => {
+                self.0.read_bit::<1, { field::INTERSECTION_GEOMETRY }>()
+            }
+            MapemPermission::RoadSegment => self.0.read_bit::<1, { field::ROAD_SEGMENT }>(),
+        }
+    }
+
+    fn set_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            MapemPermission::IntersectionGeometry => self
+                .0
+                .write_bit::<1, { field::INTERSECTION_GEOMETRY }>(true),
+            MapemPermission::RoadSegment => self.0.write_bit::<1, { field::ROAD_SEGMENT }>(true),
+        }
+    }
+
+    fn clear_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            MapemPermission::IntersectionGeometry => self
+                .0
+                .write_bit::<1, { field::INTERSECTION_GEOMETRY }>(false),
+            MapemPermission::RoadSegment => self.0.write_bit::<1, { field::ROAD_SEGMENT }>(false),
+        }
+    }
+}
@@ -18,11 +18,91 @@
 
 use core::fmt;
 
+/// Defines a Service Specific Permissions type made of the version octet only,
+/// ie: for an ITS-AID which does not define specific permissions.
+macro_rules! version_ssp {
+    (
+        $( #[$attr:meta] )*
+        pub struct $name:ident;
+    ) => {
+        $( #[$attr] )*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $name(super::SspContainer<1>);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            /// Get the size of the SSP in buffer.
+            pub const fn buf_size() -> usize {
+                1
+            }
+
+            /// Constructs the SSP with default values.
+            pub const fn new() -> $name {
+                $name(super::SspContainer::new(super::SSP_VERSION_1))
+            }
+
+            /// Constructs the SSP from bytes, ensuring length and
+            /// version are supported.
+            pub fn parse(buf: &[u8]) -> super::SspResult<$name> {
+                // Ensure no panics.
+                if buf.is_empty() {
+                    return Err(super::SspError::Length);
+                }
+
+                // Ensure version is supported.
+                if buf[0] != super::SSP_VERSION_1 {
+                    return Err(super::SspError::Version);
+                }
+
+                Ok($name(super::SspContainer::from_bytes(&buf[..1])))
+            }
+
+            /// Emit the SSP as a byte array, consuming itself.
+            pub const fn emit(self) -> [u8; 1] {
+                self.0.into_inner()
+            }
+        }
+
+        impl super::SspTrait for $name {
+            type SspType = $name;
+            type PermissionType = ();
+
+            fn contains_permissions_of(&self, other: &Self::SspType) -> bool {
+                // No permission bits, the SSPs are equivalent only if they share the same version.
+                self.0.version() == other.0.version()
+            }
+
+            fn has_permission(&self, _: Self::PermissionType) -> bool {
+                true
+            }
+
+            fn set_permission(&mut self, _: Self::PermissionType) {}
+
+            fn clear_permission(&mut self, _: Self::PermissionType) {}
+        }
+    };
+}
+
 pub mod cam;
+pub mod cpm;
 pub mod crl;
 pub mod ctl;
 pub mod denm;
+pub mod gn6;
+pub mod gpc;
+pub mod ivim;
+pub mod mapem;
 pub mod scr;
+pub mod spatem;
+pub mod srem;
+pub mod ssem;
+pub mod vam;
 
 /// SSP result type.
 pub type SspResult<T> = core::result::Result<T, SspError>;
@@ -0,0 +1,109 @@
+//! SPATEM messages SSP definition, ie: Traffic Light Manoeuver service.
+//! See ETSI TS 103 301 V2.1.1.
+
+use super::{SspContainer, SspError, SspResult, SspTrait, SSP_VERSION_1};
+
+mod field {
+    /// ConnectionManeuverAssist / maneuverAssistList signing permission bit position.
+    pub const MANEUVER_ASSIST: u8 = 7;
+    /// EnabledLaneList / enabledLanes signing permission bit position.
+    pub const ENABLED_LANES: u8 = 6;
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// SPATEM signing permissions parameters.
+pub enum SpatemPermission {
+    /// ConnectionManeuverAssist / maneuverAssistList signing permission.
+    ManeuverAssist,
+    /// EnabledLaneList / enabledLanes signing permission.
+    EnabledLanes,
+}
+
+/// Length for SPATEM SSP.
+const SPATEM_SSP_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// SPATEM Service Specific Permissions.
+pub struct SpatemSsp(SspContainer<SPATEM_SSP_LEN>);
+
+impl SpatemSsp {
+    /// Constructs an [SpatemSsp].
+    pub const fn new() -> SpatemSsp {
+        SpatemSsp(SspContainer::new(SSP_VERSION_1))
+    }
+
+    /// Get the size of [SpatemSsp] in buffer.
+    pub const fn buf_size() -> usize {
+        SPATEM_SSP_LEN
+    }
+
+    /// Constructs a [SpatemSsp] from the provided `permissions` value.
+    pub const fn from_raw_permissions(permissions: u8) -> SpatemSsp {
+        SpatemSsp(SspContainer::from_slice([SSP_VERSION_1, permissions]))
+    }
+
+    /// Constructs a [SpatemSsp] from bytes, ensuring length and
+    /// version are supported.
+    pub fn parse(buf: &[u8]) -> SspResult<SpatemSsp> {
+        // Ensure no panics.
+        if buf.len() < SPATEM_SSP_LEN {
+            return Err(SspError::Length);
+        }
+
+        // Ensure version is supported.
+        if buf[0] != SSP_VERSION_1 {
+            return Err(SspError::Version);
+        }
+
+        Ok(SpatemSsp(SspContainer::from_bytes(&buf[..SPATEM_SSP_LEN])))
+    }
+
+    /// Emit the SSP as a byte array, consuming itself.
+    pub const fn emit(self) -> [u8; SPATEM_SSP_LEN] {
+        self.0.into_inner()
+    }
+}
+
+impl Default for SpatemSsp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SspTrait for SpatemSsp {
+    type SspType = SpatemSsp;
+    type PermissionType = SpatemPermission;
+
+    fn contains_permissions_of(&self, other: &Self::SspType) -> bool {
+        self.0.inner[1] | other.0.inner[1] == self.0.inner[1]
+    }
+
+    fn has_permission(&self, permission: Self::PermissionType) -> bool {
+        match permission {
+            SpatemPermission::ManeuverAssist => self.0.read_bit::<1, { field::MANEUVER_ASSIST }>(),
+            SpatemPermission::EnabledLanes => self.0.read_bit::<1, { field::ENABLED_LANES }>(),
+        }
+    }
+
+    fn set_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            SpatemPermission::ManeuverAssist => {
+                self.0.write_bit::<1, { field::MANEUVER_ASSIST }>(true)
+            }
+            SpatemPermission::EnabledLanes => self.0.write_bit::<1, { field::ENABLED_LANES }>(true),
+        }
+    }
+
+    fn clear_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            SpatemPermission::ManeuverAssist => {
+                self.0.write_bit::<1, { field::MANEUVER_ASSIST }>(false)
+            }
+            SpatemPermission::EnabledLanes => {
+                self.0.write_bit::<1, { field::ENABLED_LANES }>(false)
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! SREM messages SSP definition, ie: Traffic Light Control Request service.
+//! See ETSI TS 103 301 V2.1.1.
+
+use super::{SspContainer, SspError, SspResult, SspTrait, SSP_VERSION_1};
+
+mod field {
+    /// PublicTransport requestor role signing permission bit position.
+    pub const PUBLIC_TRANSPORT: u8 = 7;
+    /// SpecialTransport requestor role signing permission bit position.
+    pub const SPECIAL_TRANSPORT: u8 = 6;
+    /// DangerousGoods requestor role signing permission bit position.
+    pub const DANGEROUS_GOODS: u8 = 5;
+    /// RoadWork requestor role signing permission bit position.
+    pub const ROAD_WORK: u8 = 4;
+    /// RoadRescue requestor role signing permission bit position.
+    pub const ROAD_RESCUE: u8 = 3;
+    /// Emergency requestor role signing permission bit position.
+    pub const EMERGENCY: u8 = 2;
+    /// SafetyCar requestor role signing permission bit position.
+    pub const SAFETY_CAR: u8 = 1;
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// SREM signing permissions parameters, per requestor BasicVehicleRole.
+pub enum SremPermission {
+    /// PublicTransport requestor role signing permission.
+    PublicTransport,
+    /// SpecialTransport requestor role signing permission.
+    SpecialTransport,
+    /// DangerousGoods requestor role signing permission.
+    DangerousGoods,
+    /// RoadWork requestor role signing permission.
+    RoadWork,
+    /// RoadRescue requestor role signing permission.
+    RoadRescue,
+    /// Emergency requestor role signing permission.
+    Emergency,
+    /// SafetyCar requestor role signing permission.
+    SafetyCar,
+}
+
+/// Length for SREM SSP.
+const SREM_SSP_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// SREM Service Specific Permissions.
+pub struct SremSsp(SspContainer<SREM_SSP_LEN>);
+
+impl SremSsp {
+    /// Constructs an [SremSsp].
+    pub const fn new() -> SremSsp {
+        SremSsp(SspContainer::new(SSP_VERSION_1))
+    }
+
+    /// Get the size of [SremSsp] in buffer.
+    pub const fn buf_size() -> usize {
+        SREM_SSP_LEN
+    }
+
+    /// Constructs a [SremSsp] from the provided `permissions` value.
+    pub const fn from_raw_permissions(permissions: u8) -> SremSsp {
+        SremSsp(SspContainer::from_slice([SSP_VERSION_1, permissions]))
+    }
+
+    /// Constructs a [SremSsp] from bytes, ensuring length and
+    /// version are supported.
+    pub fn parse(buf: &[u8]) -> SspResult<SremSsp> {
+        // Ensure no panics.
+        if buf.len() < SREM_SSP_LEN {
+            return Err(SspError::Length);
+        }
+
+        // Ensure version is supported.
+        if buf[0] != SSP_VERSION_1 {
+            return Err(SspError::Version);
+        }
+
+        Ok(SremSsp(SspContainer::from_bytes(&buf[..SREM_SSP_LEN])))
+    }
+
+    /// Emit the SSP as a byte array, consuming itself.
+    pub const fn emit(self) -> [u8; SREM_SSP_LEN] {
+        self.0.into_inner()
+    }
+}
+
+impl Default for SremSsp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SspTrait for SremSsp {
+    type SspType = SremSsp;
+    type PermissionType = SremPermission;
+
+    fn contains_permissions_of(&self, other: &Self::SspType) -> bool {
+        self.0.inner[1] | other.0.inner[1] == self.0.inner[1]
+    }
+
+    fn has_permission(&self, permission: Self::PermissionType) -> bool {
+        match permission {
+            SremPermission::PublicTransport => self.0.read_bit::<1, { field::PUBLIC_TRANSPORT }>(),
+            SremPermission::SpecialTransport => {
+                self.0.read_bit::<1, { field::SPECIAL_TRANSPORT }>()
+            }
+            SremPermission::DangerousGoods => self.0.read_bit::<1, { field::DANGEROUS_GOODS }>(),
+            SremPermission::RoadWork => self.0.read_bit::<1, { field::ROAD_WORK }>(),
+            SremPermission::RoadRescue => self.0.read_bit::<1, { field::ROAD_RESCUE }>(),
+            SremPermission::Emergency => self.0.read_bit::<1, { field::EMERGENCY }>(),
+            SremPermission::SafetyCar => self.0.read_bit::<1, { field::SAFETY_CAR }>(),
+        }
+    }
+
+    fn set_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            SremPermission::PublicTransport => {
+                self.0.write_bit::<1, { field::PUBLIC_TRANSPORT }>(true)
+            }
+            SremPermission::SpecialTransport => {
+                self.0.write_bit::<1, { field::SPECIAL_TRANSPORT }>(true)
+            }
+            SremPermission::DangerousGoods => {
+                self.0.write_bit::<1, { field::DANGEROUS_GOODS }>(true)
+            }
+            SremPermission::RoadWork => self.0.write_bit::<1, { field::ROAD_WORK }>(true),
+            SremPermission::RoadRescue => self.0.write_bit::<1, { field::ROAD_RESCUE }>(true),
+            SremPermission::Emergency => self.0.write_bit::<1, { field::EMERGENCY }>(true),
+            SremPermission::SafetyCar => self.0.write_bit::<1, { field::SAFETY_CAR }>(true),
+        }
+    }
+
+    fn clear_permission(&mut self, permission: Self::PermissionType) {
+        match permission {
+            SremPermission::PublicTransport => {
+                self.0.write_bit::<1, { field::PUBLIC_TRANSPORT }>(false)
+            }
+            SremPermission::SpecialTransport => {
+                self.0.write_bit::<1, { field::SPECIAL_TRANSPORT }>(false)
+            }
+            SremPermission::DangerousGoods => {
+                self.0.write_bit::<1, { field::DANGEROUS_GOODS }>(false)
+            }
+            SremPermission::RoadWork => self.0.write_bit::<1, { field::ROAD_WORK }>(false),
+            SremPermission::RoadRescue => self.0.write_bit::<1, { field::ROAD_RESCUE }>(false),
+            SremPermission::Emergency => self.0.write_bit::<1, { field::EMERGENCY }>(false),
+            SremPermission::SafetyCar => self.0.write_bit::<1, { field::SAFETY_CAR }>(false),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! SSEM messages SSP definition, ie: Traffic Light Control Status service.
+//! See ETSI TS 103 301 V2.1.1.
+
+version_ssp! {
+    /// Signal Status Extended Message Service Specific Permissions.
+    pub struct SsemSsp;
+}
@@ -0,0 +1,7 @@
+//! VRU Awareness messages SSP definition.
+//! See ETSI TS 103 300-3 V2.2.1.
+
+version_ssp! {
+    /// VRU Awareness Message Service Specific Permissions.
+    pub struct VamSsp;
+}
@@ -6,6 +6,8 @@ use std::collections::BTreeMap;
 
 use core::fmt;
 
+use super::{trust_chain::ATDailyUsage, HashedId8};
+
 #[cfg(feature = "proto-security-storage-directory")]
 pub mod directory;
 
@@ -95,6 +97,18 @@ impl fmt::Display for StorageError {
     }
 }
 
+/// Type of a remote certificate, ie: a certificate of another PKI entity learned over the air.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RemoteCertificateType {
+    /// Root certificate.
+    Root,
+    /// Enrollment Authority certificate.
+    EA,
+    /// Authorization Authority certificate.
+    AA,
+}
+
 pub trait StorageTrait {
     /// Load the Trust List Manager certificate from the storage.
     fn load_tlm_certificate(&self) -> StorageResult<Vec<u8>>;
@@ -123,6 +137,15 @@ pub trait StorageTrait {
     /// Load the certificates metadata from the storage.
     fn load_metadata(&self) -> StorageResult<StorageMetadata>;
 
+    /// Load all the remote certificates from the storage, along their type and the digest
+    /// they have been stored with.
+    fn load_remote_certificates(
+        &self,
+    ) -> StorageResult<Vec<(RemoteCertificateType, HashedId8, Vec<u8>)>>;
+
+    /// Load the revoked certificates digests from the storage.
+    fn load_revoked_certificates(&self) -> StorageResult<Vec<HashedId8>>;
+
     /// Store the Trust List Manager certificate in the storage.
     fn store_tlm_certificate(&self, cert: &[u8]) -> StorageResult<()>;
 
@@ -149,6 +172,24 @@ pub trait StorageTrait {
 
     /// Store the certificates metadata in the storage.
     fn store_metadata(&self, meta: StorageMetadata) -> StorageResult<()>;
+
+    /// Store the remote certificate of type `kind` identified with `digest` in the storage.
+    fn store_remote_certificate(
+        &self,
+        kind: RemoteCertificateType,
+        digest: HashedId8,
+        cert: &[u8],
+    ) -> StorageResult<()>;
+
+    /// Remove the remote certificate of type `kind` identified with `digest` from the storage.
+    fn remove_remote_certificate(
+        &self,
+        kind: RemoteCertificateType,
+        digest: HashedId8,
+    ) -> StorageResult<()>;
+
+    /// Store the `revoked` certificates digests in the storage, replacing the stored ones.
+    fn store_revoked_certificates(&self, revoked: &[HashedId8]) -> StorageResult<()>;
 }
 
 /// Metadata for the AT certificates.
@@ -157,6 +198,8 @@ pub trait StorageTrait {
 pub struct StorageMetadata {
     /// Statistics on the elections of the AT certificates.
     pub(crate) at_elections_stats: BTreeMap<usize, usize>,
+    /// Usage of the AT certificates during the last UTC day they have been used.
+    pub(crate) at_daily_usage: BTreeMap<usize, ATDailyUsage>,
 }
 
 impl StorageMetadata {
@@ -164,6 +207,7 @@ impl StorageMetadata {
     pub fn new() -> Self {
         Self {
             at_elections_stats: BTreeMap::new(),
+            at_daily_usage: BTreeMap::new(),
         }
     }
 
@@ -181,6 +225,16 @@ impl StorageMetadata {
     pub fn increment_elections_stats(&mut self, index: usize) {
         self.at_elections_stats.entry(index).and_modify(|e| *e += 1);
     }
+
+    /// Get the daily usage of the AT certificate at the given `index`.
+    pub fn daily_usage(&self, index: usize) -> Option<ATDailyUsage> {
+        self.at_daily_usage.get(&index).copied()
+    }
+
+    /// Set the daily usage of the AT certificate at the given `index`.
+    pub fn set_daily_usage(&mut self, index: usize, usage: ATDailyUsage) {
+        self.at_daily_usage.insert(index, usage);
+    }
 }
 
 impl Default for StorageMetadata {
@@ -10,9 +10,12 @@ use std::{
 use directories::UserDirs;
 use serde::{Deserialize, Serialize};
 
-use crate::security::storage::StorageMetadata;
+use crate::{
+    security::{storage::StorageMetadata, trust_chain::ATDailyUsage, HashedId8},
+    time::Duration,
+};
 
-use super::{StorageError, StorageResult, StorageTrait};
+use super::{RemoteCertificateType, StorageError, StorageResult, StorageTrait};
 
 #[derive(Debug)]
 pub struct DirectoryStorageConfig {
@@ -38,6 +41,10 @@ pub struct DirectoryStorageConfig {
     at_cert_filename_prefix: String,
     /// Certificates metadata filename.
     metadata_filename: String,
+    /// Remote certificates directory name.
+    remote_dirname: String,
+    /// Revoked certificates filename, in the remote certificates directory.
+    revoked_filename: String,
 }
 
 impl DirectoryStorageConfig {
@@ -66,6 +73,8 @@ impl Default for DirectoryStorageConfig {
             ec_cert_filename: "EC.cert".into(),
             at_cert_filename_prefix: "AT_".into(),
             metadata_filename: "metadata.toml".into(),
+            remote_dirname: "remote".into(),
+            revoked_filename: "revoked.toml".into(),
         }
     }
 }
@@ -113,6 +122,8 @@ pub struct DirectoryStorage {
     assets_path: PathBuf,
     /// Directory path for private assets.
     private_path: PathBuf,
+    /// Directory path for remote certificates.
+    remote_path: PathBuf,
 }
 
 impl DirectoryStorage {
@@ -129,6 +140,7 @@ impl DirectoryStorage {
 
         let assets_path = veloce_path.join("assets");
         let private_path = assets_path.join("private");
+        let remote_path = assets_path.join(&config.remote_dirname);
 
         // Check directory exists and if permissions are ok. Or create them.
         Self::check_or_create_directory(&veloce_path, None)?;
@@ -145,6 +157,49 @@ impl DirectoryStorage {
             config,
             assets_path,
             private_path,
+            remote_path,
+        })
+    }
+
+    /// Get the file name prefix of the remote certificates of type `kind`.
+    const fn remote_cert_prefix(kind: RemoteCertificateType) -> &'static str {
+        match kind {
+            RemoteCertificateType::Root => "RCA_",
+            RemoteCertificateType::EA => "EA_",
+            RemoteCertificateType::AA => "AA_",
+        }
+    }
+
+    /// Get the file path of the remote certificate of type `kind` identified with `digest`.
+    fn remote_cert_path(&self, kind: RemoteCertificateType, digest: HashedId8) -> PathBuf {
+        let file_name = format!(
+            "{}{:016x}.cert",
+            Self::remote_cert_prefix(kind),
+            digest.as_u64()
+        );
+        self.remote_path.join(file_name)
+    }
+
+    /// Parse the type and the digest of a remote certificate from its file `name`.
+    fn parse_remote_cert_name(name: &str) -> Option<(RemoteCertificateType, HashedId8)> {
+        let kinds = [
+            RemoteCertificateType::Root,
+            RemoteCertificateType::EA,
+            RemoteCertificateType::AA,
+        ];
+
+        kinds.into_iter().find_map(|kind| {
+            let digest = name
+                .strip_prefix(Self::remote_cert_prefix(kind))?
+                .strip_suffix(".cert")?;
+
+            if digest.len() != 16 {
+                return None;
+            }
+
+            u64::from_str_radix(digest, 16)
+                .ok()
+                .map(|d| (kind, HashedId8::from_u64(d)))
         })
     }
 
@@ -236,6 +291,23 @@ impl DirectoryStorage {
         file.write_all(content)
     }
 
+    /// Replaces the file at `path` with `content`. Content is first written to a temporary file
+    /// which is then renamed to `path`, so a crash never leaves a partially written file.
+    fn replace_file(path: PathBuf, content: &[u8]) -> IoResult<()> {
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+
+        fs::rename(tmp_path, path)
+    }
+
     /// Loads the file identified by `name` from the private directory.
     pub fn load_private_file(&self, name: String) -> IoResult<Vec<u8>> {
         let file_path = self.private_path.join(name);
@@ -282,6 +354,18 @@ impl DirectoryStorage {
 /// Metadata file content.
 struct FileMetadata {
     at_elections_stats: BTreeMap<String, usize>,
+    #[serde(default)]
+    at_daily_usage: BTreeMap<String, FileDailyUsage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+/// Daily usage of an AT certificate, in the metadata file.
+struct FileDailyUsage {
+    /// UTC day, as a number of days since the Unix epoch.
+    day: i64,
+    /// Usage duration during the day, in seconds.
+    usage: u64,
 }
 
 impl FileMetadata {
@@ -304,7 +388,22 @@ impl From<StorageMetadata> for FileMetadata {
             .map(|(k, v)| (k.to_string(), v))
             .collect();
 
-        Self { at_elections_stats }
+        let at_daily_usage = value
+            .at_daily_usage
+            .into_iter()
+            .map(|(k, v)| {
+                let usage = FileDailyUsage {
+                    day: v.day,
+                    usage: v.usage.secs(),
+                };
+                (k.to_string(), usage)
+            })
+            .collect();
+
+        Self {
+            at_elections_stats,
+            at_daily_usage,
+        }
     }
 }
 
@@ -318,7 +417,61 @@ impl TryFrom<FileMetadata> for StorageMetadata {
             at_elections_stats.insert(ku, *v);
         }
 
-        Ok(Self { at_elections_stats })
+        let mut at_daily_usage = BTreeMap::new();
+        for (k, v) in &value.at_daily_usage {
+            let usage = ATDailyUsage {
+                day: v.day,
+                usage: Duration::from_secs(v.usage),
+            };
+            at_daily_usage.insert(k.parse()?, usage);
+        }
+
+        Ok(Self {
+            at_elections_stats,
+            at_daily_usage,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+/// Revoked certificates file content.
+struct FileRevoked {
+    revoked: Vec<String>,
+}
+
+impl FileRevoked {
+    /// Serialize the revoked certificates to a TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Deserialize the revoked certificates from a TOML string.
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+}
+
+impl From<&[HashedId8]> for FileRevoked {
+    fn from(value: &[HashedId8]) -> Self {
+        let revoked = value
+            .iter()
+            .map(|h| format!("{:016x}", h.as_u64()))
+            .collect();
+
+        Self { revoked }
+    }
+}
+
+impl TryFrom<FileRevoked> for Vec<HashedId8> {
+    type Error = ParseIntError;
+
+    fn try_from(value: FileRevoked) -> Result<Self, Self::Error> {
+        value
+            .revoked
+            .iter()
+            .map(|h| u64::from_str_radix(h, 16).map(HashedId8::from_u64))
+            .collect()
     }
 }
 
@@ -395,6 +548,51 @@ impl StorageTrait for DirectoryStorage {
         Ok(metadata)
     }
 
+    fn load_remote_certificates(
+        &self,
+    ) -> StorageResult<Vec<(RemoteCertificateType, HashedId8, Vec<u8>)>> {
+        // Remote certificates directory is only created when storing the first one.
+        if !self.remote_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let files =
+            Self::list_files(&self.remote_path).map_err(|e| StorageError::Other(e.into()))?;
+
+        let mut res = Vec::new();
+        for path in files {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            if !name.ends_with(".cert") {
+                continue;
+            }
+
+            // Certificate files with a malformed name cannot be identified, so they are deleted.
+            let Some((kind, digest)) = Self::parse_remote_cert_name(name) else {
+                fs::remove_file(&path).map_err(|e| StorageError::Other(e.into()))?;
+                continue;
+            };
+
+            res.push((kind, digest, load_file_storage_map!(path)?));
+        }
+
+        Ok(res)
+    }
+
+    fn load_revoked_certificates(&self) -> StorageResult<Vec<HashedId8>> {
+        let path = self.remote_path.join(self.config.revoked_filename.clone());
+        let bytes = load_file_storage_map!(path)?;
+        let str = String::from_utf8(bytes).map_err(|e| StorageError::Other(e.into()))?;
+        let file_revoked =
+            FileRevoked::from_toml(&str).map_err(|e| StorageError::Other(e.into()))?;
+
+        file_revoked
+            .try_into()
+            .map_err(|e: ParseIntError| StorageError::Other(e.into()))
+    }
+
     fn store_tlm_certificate(&self, cert: &[u8]) -> StorageResult<()> {
         let path = self.assets_path.join(self.config.tlm_cert_filename.clone());
         Self::store_file(path, cert, None).map_err(|e| StorageError::Other(e.into()))
@@ -446,4 +644,43 @@ impl StorageTrait for DirectoryStorage {
             .map_err(|e| StorageError::Other(e.into()))?;
         Self::store_file(path, toml.as_bytes(), None).map_err(|e| StorageError::Other(e.into()))
     }
+
+    fn store_remote_certificate(
+        &self,
+        kind: RemoteCertificateType,
+        digest: HashedId8,
+        cert: &[u8],
+    ) -> StorageResult<()> {
+        Self::check_or_create_directory(&self.remote_path, None)
+            .map_err(StorageError::Directory)?;
+
+        let path = self.remote_cert_path(kind, digest);
+        Self::replace_file(path, cert).map_err(|e| StorageError::Other(e.into()))
+    }
+
+    fn remove_remote_certificate(
+        &self,
+        kind: RemoteCertificateType,
+        digest: HashedId8,
+    ) -> StorageResult<()> {
+        let path = self.remote_cert_path(kind, digest);
+        fs::remove_file(path).map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                StorageError::NotFound
+            } else {
+                StorageError::Other(e.into())
+            }
+        })
+    }
+
+    fn store_revoked_certificates(&self, revoked: &[HashedId8]) -> StorageResult<()> {
+        Self::check_or_create_directory(&self.remote_path, None)
+            .map_err(StorageError::Directory)?;
+
+        let path = self.remote_path.join(self.config.revoked_filename.clone());
+        let toml = FileRevoked::from(revoked)
+            .to_toml()
+            .map_err(|e| StorageError::Other(e.into()))?;
+        Self::replace_file(path, toml.as_bytes()).map_err(|e| StorageError::Other(e.into()))
+    }
 }
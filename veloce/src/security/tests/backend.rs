@@ -5,15 +5,19 @@ use openssl::{
 };
 
 use crate::security::{
-    backend::{openssl::OpensslBackend, BackendTrait},
+    backend::{
+        openssl::{verify_batch_workers, OpensslBackend, VERIFY_BATCH_MIN_PER_WORKER},
+        BackendError, BackendTrait, SignatureVerification,
+    },
+    certificate::{AuthorizationTicketCertificate, CertificateTrait, ExplicitCertificate},
+    storage::StorageTrait,
     EccPoint, EcdsaKey, UncompressedEccPoint,
 };
 
 #[cfg(feature = "pki")]
 use crate::security::{
-    backend::PkiBackendTrait,
-    certificate::{CertificateTrait, EnrollmentAuthorityCertificate, ExplicitCertificate},
-    EcKeyType,
+    backend::PkiBackendTrait, certificate::EnrollmentAuthorityCertificate, EcKeyType,
+    EcdsaSignature,
 };
 
 #[cfg(feature = "pki")]
@@ -76,11 +80,90 @@ fn test_load_secret_key() {
         .unwrap();
 }
 
+/// Build a batch of `len` signature verifications of the first AT signatures. Every third
+/// signature is verified over tampered data, and every fifth with a key of the wrong type.
+fn signature_batch(len: usize) -> (OpensslBackend, Vec<SignatureVerification>) {
+    let (storage, mut backend) = super::setup_storage_and_crypto(super::get_test_storage_path());
+    backend.set_at_key_index(0).unwrap();
+
+    let raw_at_cert = storage.load_at_certificate(0).unwrap();
+    let at_cert = AuthorizationTicketCertificate::from_bytes(&raw_at_cert, &backend).unwrap();
+    let key = at_cert.public_verification_key().unwrap();
+    let EcdsaKey::NistP256r1(point) = key.clone() else {
+        panic!("test AT key should be a NistP256r1 key");
+    };
+
+    let batch = (0..len)
+        .map(|i| {
+            let data = backend.sha256(&i.to_be_bytes()).to_vec();
+            let signature = backend.generate_signature(&data).unwrap();
+            SignatureVerification {
+                signature,
+                key: if i % 5 == 4 {
+                    EcdsaKey::NistP384r1(point.clone())
+                } else {
+                    key.clone()
+                },
+                data: if i % 3 == 2 {
+                    backend.sha256(&[0xca, 0xfe]).to_vec()
+                } else {
+                    data
+                },
+            }
+        })
+        .collect();
+
+    (backend, batch)
+}
+
+/// Check the results of a [signature_batch] verification.
+fn check_batch_results(results: &[Result<bool, BackendError>], len: usize) {
+    assert_eq!(results.len(), len);
+    for (i, res) in results.iter().enumerate() {
+        match res {
+            Err(BackendError::AlgorithmMismatch) => assert_eq!(i % 5, 4, "signature {i}"),
+            Ok(valid) => {
+                assert_ne!(i % 5, 4, "signature {i}");
+                assert_eq!(*valid, i % 3 != 2, "signature {i}");
+            }
+            Err(e) => panic!("signature {i}: unexpected error {e:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_verify_signatures() {
+    // Small batches are verified sequentially.
+    let len = 2 * VERIFY_BATCH_MIN_PER_WORKER - 1;
+    assert_eq!(verify_batch_workers(len), 1);
+
+    let (backend, batch) = signature_batch(len);
+    check_batch_results(&backend.verify_signatures(&batch), len);
+    assert!(backend.verify_signatures(&[]).is_empty());
+}
+
+#[test]
+fn test_verify_signatures_parallel() {
+    // Not a multiple of the number of workers, the last chunk is shorter.
+    let len = 16 * VERIFY_BATCH_MIN_PER_WORKER + 3;
+    let parallelism = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let workers = verify_batch_workers(len);
+    assert_eq!(workers, parallelism.min(16));
+
+    // Results are identical to a one by one verification, in the batch order.
+    let (backend, batch) = signature_batch(len);
+    let results = backend.verify_signatures(&batch);
+    check_batch_results(&results, len);
+
+    for (v, res) in batch.iter().zip(results) {
+        let expected = backend.verify_signature(v.signature.clone(), v.key.clone(), &v.data);
+        assert_eq!(res.ok(), expected.ok());
+    }
+}
+
 #[cfg(feature = "pki")]
 #[test]
 fn derive_key() {
-    use crate::security::storage::StorageTrait;
-
     let base_path = super::get_test_storage_path();
     let (storage, backend) = super::setup_storage_and_crypto(base_path);
 
@@ -98,3 +181,47 @@ fn derive_key() {
         <OpensslBackend as PkiBackendTrait>::BackendPublicKey::try_from(peer_public_key).unwrap();
     let _derived = backend.derive(&keypair.secret, &peer_public_pkey).unwrap();
 }
+
+#[cfg(feature = "pki")]
+#[test]
+fn test_sm2_sign_verify() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, mut backend) = super::setup_storage_and_crypto(base_path);
+
+    let pub_key = backend.generate_canonical_keypair(EcKeyType::Sm2).unwrap();
+    let pub_key: EcdsaKey = pub_key.try_into().unwrap();
+
+    let data = backend.sm3(&[0xca, 0xfe, 0xca, 0xfe]).unwrap();
+    let signature = backend.generate_canonical_signature(&data).unwrap();
+    assert!(matches!(signature, EcdsaSignature::Sm2(_)));
+
+    assert!(backend
+        .verify_signature(signature.clone(), pub_key.clone(), &data)
+        .unwrap());
+
+    let other = backend.sm3(&[0xbe, 0xef]).unwrap();
+    assert!(!backend
+        .verify_signature(signature, pub_key, &other)
+        .unwrap());
+}
+
+#[cfg(feature = "pki")]
+#[test]
+fn test_sm4_ccm_roundtrip() {
+    let base_path = super::get_test_storage_path();
+    let (_, backend) = super::setup_storage_and_crypto(base_path);
+
+    let key = backend.generate_aes128_key().unwrap();
+    let nonce = [0x01; 12];
+    let data = vec![0xca, 0xfe, 0xca, 0xfe];
+
+    let encrypted = backend.encrypt_sm4_ccm(&data, &key, &nonce).unwrap();
+    assert_ne!(encrypted, data);
+
+    let decrypted = backend.decrypt_sm4_ccm(&encrypted, &key, &nonce).unwrap();
+    assert_eq!(decrypted, data);
+
+    let mut tampered = encrypted.clone();
+    tampered[0] ^= 0xff;
+    assert!(backend.decrypt_sm4_ccm(&tampered, &key, &nonce).is_err());
+}
@@ -0,0 +1,338 @@
+use std::path::PathBuf;
+
+use uom::si::angle::degree;
+use veloce_asn1::{
+    defs::etsi_103097_v211::{
+        ieee1609_dot2::{
+            Certificate as EtsiCertificate, IssuerIdentifier, VerificationKeyIndicator,
+        },
+        ieee1609_dot2_base_types::{
+            HashAlgorithm as EtsiHashAlgorithm, PublicEncryptionKey, SymmAlgorithm,
+        },
+    },
+    prelude::rasn,
+};
+
+use crate::{
+    common::PotiPosition,
+    config::SEC_AT_DECRYPTION_GRACE_PERIOD,
+    security::{
+        backend::{BackendTrait, PkiBackendTrait},
+        certificate::{
+            AuthorizationAuthorityCertificate, AuthorizationTicketCertificate, CertificateTrait,
+            ExplicitCertificate, RootCertificate,
+        },
+        permission::Permission,
+        privacy::PrivacyStrategy,
+        secured_message::SecuredMessage,
+        service::{SecurityService, SecurityServiceError},
+        signature::EcdsaSignature,
+        ssp::cam::CamSsp,
+        trust_chain::{ATContainer, TrustChain},
+        EcKeyType, EcdsaKey, EciesKey, HashedId8, OpensslBackend, SecurityBackend,
+    },
+    time::Duration,
+    types::{Latitude, Longitude},
+    wire::EthernetAddress,
+};
+
+use super::{
+    certificate::{load_aa_cert, load_at_cert, load_root_cert, valid_timestamp},
+    secured_message::GN_CAM,
+};
+
+/// Re-issues the `template` certificate for the `verification_key`, along the optional
+/// `encryption_key`. The certificate is self-signed if `issuer` is `None`, otherwise issued by
+/// the `issuer` raw certificate. `sign` signs the certificate hash with the secret key of the
+/// issuer. SM2 certificates are hashed with SM3, other ones with SHA-256.
+fn reissue_certificate<F>(
+    template: EtsiCertificate,
+    verification_key: EcdsaKey,
+    encryption_key: Option<PublicEncryptionKey>,
+    issuer: Option<(HashedId8, &[u8])>,
+    backend: &OpensslBackend,
+    sign: F,
+) -> EtsiCertificate
+where
+    F: FnOnce(&[u8]) -> EcdsaSignature,
+{
+    let sm3 = matches!(verification_key, EcdsaKey::Sm2(_));
+    let mut cert = template;
+    let tbs = &mut cert.0.to_be_signed;
+
+    let key = backend.compress_ecdsa_key(verification_key).unwrap();
+    tbs.verify_key_indicator = VerificationKeyIndicator::verificationKey(key.try_into().unwrap());
+    tbs.encryption_key = encryption_key;
+
+    let signer_data: &[u8] = match issuer {
+        Some((digest, raw)) if sm3 => {
+            cert.0.issuer = IssuerIdentifier::sm3AndDigest(digest.into());
+            raw
+        }
+        Some((digest, raw)) => {
+            cert.0.issuer = IssuerIdentifier::sha256AndDigest(digest.into());
+            raw
+        }
+        None if sm3 => {
+            cert.0.issuer = IssuerIdentifier::R_self(EtsiHashAlgorithm::sm3);
+            &[]
+        }
+        None => {
+            cert.0.issuer = IssuerIdentifier::R_self(EtsiHashAlgorithm::sha256);
+            &[]
+        }
+    };
+
+    let tbs = rasn::coer::encode(&cert.0.to_be_signed).unwrap();
+    let hash = if sm3 {
+        [
+            backend.sm3(&tbs).unwrap(),
+            backend.sm3(signer_data).unwrap(),
+        ]
+        .concat()
+    } else {
+        [backend.sha256(&tbs), backend.sha256(signer_data)].concat()
+    };
+    cert.0.signature = Some(sign(&hash).try_into().unwrap());
+
+    cert
+}
+
+/// Setup an [OpensslBackend] holding `key_type` keys and a `key_type` Root -> AA -> AT trust
+/// chain, with the test assets content.
+pub(super) fn setup_chain(backend: &mut OpensslBackend, key_type: EcKeyType) -> TrustChain {
+    // Canonical key is used as the Root key, and enrollment key as the AA key.
+    let root_key: EcdsaKey = backend
+        .generate_canonical_keypair(key_type)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let aa_key: EcdsaKey = backend
+        .generate_enrollment_keypair(key_type)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let at_key: EcdsaKey = backend
+        .generate_authorization_ticket_keypair(key_type, 0)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    backend.set_at_key_index(0).unwrap();
+
+    let root_cert = reissue_certificate(load_root_cert().0, root_key, None, None, backend, |h| {
+        backend.generate_canonical_signature(h).unwrap()
+    });
+    let root_cert = RootCertificate::from_etsi_cert(root_cert, backend)
+        .unwrap()
+        .into_with_hash_container(backend)
+        .unwrap();
+
+    let issuer = Some((root_cert.hashed_id8(), root_cert.certificate().raw_bytes()));
+    let aa_cert = reissue_certificate(load_aa_cert().0, aa_key, None, issuer, backend, |h| {
+        backend.generate_canonical_signature(h).unwrap()
+    });
+    let aa_cert = AuthorizationAuthorityCertificate::from_etsi_cert(aa_cert, backend)
+        .unwrap()
+        .into_with_hash_container(backend)
+        .unwrap();
+
+    let issuer = Some((aa_cert.hashed_id8(), aa_cert.certificate().raw_bytes()));
+    let at_cert = reissue_certificate(load_at_cert().0, at_key, None, issuer, backend, |h| {
+        backend.generate_enrollment_signature(h).unwrap()
+    });
+    let at_cert = AuthorizationTicketCertificate::from_etsi_cert(at_cert, backend)
+        .unwrap()
+        .into_with_hash_container(backend)
+        .unwrap();
+
+    let mut chain = TrustChain::new(root_cert);
+    chain.set_aa_cert(aa_cert);
+    chain.add_at_cert(0, ATContainer::new(at_cert, 0));
+    chain.set_at_cert_index(0).unwrap();
+
+    chain
+}
+
+/// Issues a `key_type` AT certificate with a `key_type` encryption key, from the AA of
+/// [setup_chain]. SM2 encryption keys are used with SM4-CCM, other ones with AES-128-CCM.
+/// The AT signing and encryption keys are generated in the `backend` at `index`.
+fn issue_encryption_at_cert(
+    backend: &mut OpensslBackend,
+    chain: &TrustChain,
+    key_type: EcKeyType,
+    index: usize,
+) -> ATContainer {
+    let at_key: EcdsaKey = backend
+        .generate_authorization_ticket_keypair(key_type, index)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let enc_key: EciesKey = backend
+        .generate_authorization_ticket_encryption_keypair(key_type, index)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let symm_algorithm = match key_type {
+        EcKeyType::Sm2 => SymmAlgorithm::sm4Ccm,
+        _ => SymmAlgorithm::aes128Ccm,
+    };
+    let encryption_key = PublicEncryptionKey::new(symm_algorithm, enc_key.try_into().unwrap());
+
+    let aa = chain.aa_cert().unwrap();
+    let issuer = Some((aa.hashed_id8(), aa.certificate().raw_bytes()));
+    let at_cert = reissue_certificate(
+        load_at_cert().0,
+        at_key,
+        Some(encryption_key),
+        issuer,
+        backend,
+        |h| backend.generate_enrollment_signature(h).unwrap(),
+    );
+    let at_cert = AuthorizationTicketCertificate::from_etsi_cert(at_cert, backend)
+        .unwrap()
+        .into_with_hash_container(backend)
+        .unwrap();
+
+    ATContainer::new(at_cert, 0)
+}
+
+/// Setup a [SecurityService] signing with a `key_type` AT certificate holding a `key_type`
+/// encryption key, to exchange confidential messages. Secret keys are stored in `storage_dir`.
+pub fn setup_confidential_service(storage_dir: PathBuf, key_type: EcKeyType) -> SecurityService {
+    let (_, mut backend) = super::setup_storage_and_crypto(storage_dir);
+
+    let mut chain = setup_chain(&mut backend, key_type);
+    let at = issue_encryption_at_cert(&mut backend, &chain, key_type, 1);
+    chain.add_at_cert(1, at);
+    chain.set_at_cert_index(1).unwrap();
+    backend.set_at_key_index(1).unwrap();
+
+    SecurityService::new(
+        chain,
+        SecurityBackend::Openssl(backend),
+        PrivacyStrategy::NoStrategy,
+    )
+}
+
+/// Exchanges confidential messages between two `key_type` AT certificates of the same station.
+fn confidential_message(key_type: EcKeyType) {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, mut backend) = super::setup_storage_and_crypto(base_path);
+
+    let mut chain = setup_chain(&mut backend, key_type);
+    let first = issue_encryption_at_cert(&mut backend, &chain, key_type, 1);
+    let second = issue_encryption_at_cert(&mut backend, &chain, key_type, 2);
+    let first_hash = first.at_container().hashed_id8();
+    let second_hash = second.at_container().hashed_id8();
+    chain.add_at_cert(1, first);
+    chain.add_at_cert(2, second);
+
+    let mut service = SecurityService::new(
+        chain,
+        SecurityBackend::Openssl(backend),
+        PrivacyStrategy::NoStrategy,
+    );
+
+    let permissions = Permission::CAM(CamSsp::new_v1().into());
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+    let address = EthernetAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    let now = valid_timestamp();
+
+    // Destination certificate is unknown.
+    assert_eq!(service.select_at_cert(first_hash, now).unwrap(), 1);
+    let res = service.encap_packet_confidential(
+        GN_CAM.to_vec(),
+        permissions.clone(),
+        now,
+        position,
+        address,
+    );
+    assert!(matches!(
+        res,
+        Err(SecurityServiceError::RecipientCertificateNotFound)
+    ));
+
+    // Receive a message signed with the first AT, sent from `address`.
+    let signed = service
+        .encap_packet(GN_CAM.to_vec(), permissions.clone(), now, position)
+        .unwrap();
+    let (confirm, _) = service.decap_packet(&signed, now).unwrap();
+    assert!(!confirm.confidential);
+    service.bind_certificate_address(confirm.cert_id, address);
+
+    let encrypted: Vec<_> = (0..3u8)
+        .map(|i| {
+            let mut packet = GN_CAM.to_vec();
+            packet[0] ^= i;
+            let at = now + Duration::from_millis(10 * u64::from(i + 1));
+            service
+                .encap_packet_confidential(packet, permissions.clone(), at, position, address)
+                .unwrap()
+        })
+        .collect();
+
+    // Encrypted messages are not readable as signed messages.
+    assert!(SecuredMessage::from_bytes(&encrypted[0]).is_err());
+
+    let at = now + Duration::from_millis(50);
+    let (confirm, payload) = service.decap_packet(&encrypted[0], at).unwrap();
+    assert!(confirm.confidential);
+    assert_eq!(confirm.cert_id, first_hash);
+    assert_eq!(payload, GN_CAM.to_vec());
+
+    // A tampered message is not decrypted.
+    let mut tampered = encrypted[0].clone();
+    let len = tampered.len();
+    tampered[len - 1] ^= 0x01;
+    assert!(service.decap_packet(&tampered, at).is_err());
+
+    // Messages encrypted for the previous AT are still decrypted during the grace period.
+    let changed_at = now + Duration::from_millis(100);
+    assert_eq!(service.select_at_cert(second_hash, changed_at).unwrap(), 2);
+    let (confirm, _) = service
+        .decap_packet(&encrypted[1], changed_at + Duration::from_millis(50))
+        .unwrap();
+    assert!(confirm.confidential);
+
+    let res = service.decap_packet(
+        &encrypted[2],
+        changed_at + SEC_AT_DECRYPTION_GRACE_PERIOD + Duration::from_secs(1),
+    );
+    assert!(matches!(res, Err(SecurityServiceError::UnknownRecipient)));
+}
+
+#[test]
+fn test_nistp256_confidential_message() {
+    confidential_message(EcKeyType::NistP256r1);
+}
+
+#[test]
+fn test_sm2_confidential_message() {
+    confidential_message(EcKeyType::Sm2);
+}
+
+#[test]
+fn test_nistp256_encryption_at_cert() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, mut backend) = super::setup_storage_and_crypto(base_path);
+
+    let chain = setup_chain(&mut backend, EcKeyType::NistP256r1);
+    let at = issue_encryption_at_cert(&mut backend, &chain, EcKeyType::NistP256r1, 1);
+    let at = at.at_container();
+    let aa = chain.aa_cert().unwrap();
+
+    assert!(matches!(
+        at.certificate().public_encryption_key().unwrap(),
+        Some(EciesKey::NistP256r1(_))
+    ));
+    assert!(at
+        .certificate()
+        .check(valid_timestamp(), &backend, |h| {
+            (h == aa.hashed_id8()).then(|| aa.certificate().clone())
+        })
+        .unwrap());
+}
@@ -0,0 +1,207 @@
+use uom::si::{angle::degree, velocity::meter_per_second};
+
+use crate::{
+    common::geo_area::GeoPosition,
+    security::{
+        misbehavior::{
+            MisbehaviorCheck, MisbehaviorConfig, MisbehaviorDetector, MisbehaviorReaction,
+            Observation,
+        },
+        HashedId8,
+    },
+    time::{Duration, Instant, TAI2004},
+    types::{Heading, Latitude, Longitude, Speed},
+};
+
+/// Sender certificate digest used in the tests.
+const SENDER: HashedId8 = HashedId8::from_u64(0x0102_0304_0506_0708);
+
+/// Build a position located `north` degrees north of the test origin.
+fn position(north: f64) -> GeoPosition {
+    GeoPosition {
+        latitude: Latitude::new::<degree>(48.0 + north),
+        longitude: Longitude::new::<degree>(-3.0),
+    }
+}
+
+/// Build an observation of a sender heading north at 25 m/s, generated at `at`.
+fn observation(at: Instant, north: f64) -> Observation {
+    Observation {
+        timestamp: TAI2004::from_unix_instant(at),
+        position: Some(position(north)),
+        speed: Some(Speed::new::<meter_per_second>(25.0)),
+        heading: Some(Heading::new::<degree>(0.0)),
+        ego_position: Some(position(0.0)),
+        beacon_position: None,
+        event_position: None,
+        detection_time: None,
+    }
+}
+
+/// Build an observation of a DENM generated at `at`, reporting an event located
+/// `event_north` degrees north of the test origin and detected at `detected_at`.
+fn denm_observation(
+    at: Instant,
+    north: f64,
+    event_north: f64,
+    detected_at: Instant,
+) -> Observation {
+    Observation {
+        speed: None,
+        heading: None,
+        event_position: Some(position(event_north)),
+        detection_time: Some(TAI2004::from_unix_instant(detected_at)),
+        ..observation(at, north)
+    }
+}
+
+/// Latitude offset for 25 meters northwards.
+const STEP: f64 = 0.000_224_8;
+
+#[test]
+fn test_plausible_sender() {
+    let mut mbd = MisbehaviorDetector::default();
+    let start = Instant::from_secs(1_000);
+
+    for i in 0..4 {
+        let at = start + Duration::from_secs(i);
+        let verdict = mbd.check(SENDER, &observation(at, STEP * i as f64), at);
+        assert!(verdict.is_plausible(), "{:?}", verdict.detections);
+        assert_eq!(verdict.reaction, MisbehaviorReaction::Ignore);
+    }
+}
+
+#[test]
+fn test_position_jump() {
+    let mut mbd = MisbehaviorDetector::default();
+    let start = Instant::from_secs(1_000);
+    let next = start + Duration::from_secs(1);
+
+    assert!(mbd
+        .check(SENDER, &observation(start, 0.0), start)
+        .is_plausible());
+
+    // One kilometer in one second.
+    let verdict = mbd.check(SENDER, &observation(next, STEP * 40.0), next);
+    assert!(verdict.detections.contains(&MisbehaviorCheck::PositionJump));
+    assert!(verdict.is_flagged());
+    assert!(!verdict.should_drop());
+    assert_eq!(
+        mbd.detection_count(&SENDER, MisbehaviorCheck::PositionJump),
+        1
+    );
+}
+
+#[test]
+fn test_freshness_and_range() {
+    let mut mbd = MisbehaviorDetector::default();
+    let now = Instant::from_secs(1_000);
+
+    let mut obs = observation(now - Duration::from_secs(5), 0.0);
+    obs.ego_position = Some(position(0.1));
+
+    let verdict = mbd.check(SENDER, &obs, now);
+    assert!(verdict.detections.contains(&MisbehaviorCheck::Freshness));
+    assert!(verdict
+        .detections
+        .contains(&MisbehaviorCheck::CommunicationRange));
+}
+
+#[test]
+fn test_beacon_mismatch() {
+    let mut mbd = MisbehaviorDetector::default();
+    let now = Instant::from_secs(1_000);
+
+    let mut obs = observation(now, 0.0);
+    obs.beacon_position = Some((position(STEP * 4.0), TAI2004::from_unix_instant(now)));
+
+    let verdict = mbd.check(SENDER, &obs, now);
+    assert_eq!(verdict.detections, vec![MisbehaviorCheck::BeaconMismatch]);
+}
+
+#[test]
+fn test_blacklist() {
+    let mut config = MisbehaviorConfig::default();
+    config.reactions.freshness = MisbehaviorReaction::Blacklist;
+    let mut mbd = MisbehaviorDetector::new(config);
+    let now = Instant::from_secs(1_000);
+
+    let verdict = mbd.check(SENDER, &observation(now - Duration::from_secs(5), 0.0), now);
+    assert!(verdict.should_drop());
+    assert!(mbd.is_blacklisted(&SENDER, now));
+    assert!(!mbd.is_blacklisted(&SENDER, now + config.blacklist_duration));
+}
+
+#[test]
+fn test_denm_event_range() {
+    let mut mbd = MisbehaviorDetector::default();
+    let now = Instant::from_secs(1_000);
+    let detected_at = now - Duration::from_secs(1);
+
+    // Event is 100 meters ahead.
+    let verdict = mbd.check(
+        SENDER,
+        &denm_observation(now, 0.0, STEP * 4.0, detected_at),
+        now,
+    );
+    assert!(verdict.is_plausible(), "{:?}", verdict.detections);
+
+    // Event is about 5.5 kilometers away from the sender.
+    let verdict = mbd.check(SENDER, &denm_observation(now, 0.0, 0.05, detected_at), now);
+    assert_eq!(verdict.detections, vec![MisbehaviorCheck::EventRange]);
+    assert_eq!(
+        mbd.detection_count(&SENDER, MisbehaviorCheck::EventRange),
+        1
+    );
+}
+
+#[test]
+fn test_denm_detection_time() {
+    let mut config = MisbehaviorConfig::default();
+    config.reactions.detection_time = MisbehaviorReaction::Drop;
+    let mut mbd = MisbehaviorDetector::new(config);
+    let now = Instant::from_secs(1_000);
+
+    let verdict = mbd.check(SENDER, &denm_observation(now, 0.0, 0.0, now), now);
+    assert!(verdict.is_plausible(), "{:?}", verdict.detections);
+
+    // Event detected after the DENM generation.
+    let detected_at = now + Duration::from_secs(1);
+    let verdict = mbd.check(SENDER, &denm_observation(now, 0.0, 0.0, detected_at), now);
+    assert_eq!(verdict.detections, vec![MisbehaviorCheck::DetectionTime]);
+    assert!(verdict.should_drop());
+}
+
+#[test]
+fn test_denm_sender_position() {
+    let mut mbd = MisbehaviorDetector::default();
+    let start = Instant::from_secs(1_000);
+    let detected_at = start - Duration::from_secs(1);
+
+    assert!(mbd
+        .check(SENDER, &observation(start, 0.0), start)
+        .is_plausible());
+
+    // DENM generation location is checked against the CAM history, 750 meters in one second.
+    let at = start + Duration::from_secs(1);
+    let mut denm = denm_observation(at, STEP * 30.0, STEP * 30.0, detected_at);
+    let verdict = mbd.check(SENDER, &denm, at);
+    assert_eq!(verdict.detections, vec![MisbehaviorCheck::PositionJump]);
+
+    // Generation location is compared to the Location Table and the local station positions.
+    denm.timestamp = TAI2004::from_unix_instant(start);
+    denm.ego_position = Some(position(0.1));
+    denm.beacon_position = Some((position(0.0), TAI2004::from_unix_instant(start)));
+    let verdict = mbd.check(SENDER, &denm, start);
+    assert!(verdict
+        .detections
+        .contains(&MisbehaviorCheck::CommunicationRange));
+    assert!(verdict
+        .detections
+        .contains(&MisbehaviorCheck::BeaconMismatch));
+
+    // DENMs are not recorded in the history, the next CAM is consistent with the previous one.
+    let at = start + Duration::from_secs(2);
+    let verdict = mbd.check(SENDER, &observation(at, STEP * 2.0), at);
+    assert!(verdict.is_plausible(), "{:?}", verdict.detections);
+}
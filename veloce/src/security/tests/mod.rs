@@ -1,13 +1,35 @@
 use std::{fs::DirBuilder, os::unix::fs::DirBuilderExt, path::PathBuf, rc::Rc};
 
-use super::{DirectoryStorage, DirectoryStorageConfig, OpensslBackend, OpensslBackendConfig};
+use super::{
+    backend::BackendTrait,
+    certificate::{
+        AuthorizationAuthorityCertificate, AuthorizationTicketCertificate, CertificateTrait,
+        ExplicitCertificate, RootCertificate,
+    },
+    privacy::PrivacyStrategy,
+    service::SecurityService,
+    storage::StorageTrait,
+    trust_chain::{ATContainer, ATDailyUsage, TrustChain},
+    DirectoryStorage, DirectoryStorageConfig, OpensslBackend, OpensslBackendConfig,
+    SecurityBackend,
+};
 
 #[cfg(feature = "pki")]
 use tempfile::{tempdir, TempDir};
 
 pub(self) mod backend;
 pub(self) mod certificate;
+#[cfg(feature = "pki")]
+pub(self) mod confidential;
+pub(self) mod misbehavior;
+pub(self) mod privacy;
 pub(self) mod secured_message;
+#[cfg(feature = "pki")]
+pub(self) mod sm2;
+pub(self) mod ssp;
+
+#[cfg(feature = "pki")]
+pub use confidential::setup_confidential_service;
 
 /// Create a `veloce` temporary directory and return the path to it, along with the
 /// [TempDir] to instance which should be kept alive until the tempdir is no longer needed.
@@ -44,3 +66,53 @@ pub fn setup_storage_and_crypto(storage_dir: PathBuf) -> (Rc<DirectoryStorage>,
 
     (storage, backend)
 }
+
+/// Setup a [SecurityService] with the test assets trust chain, signing with the first AT.
+pub fn setup_security_service() -> SecurityService {
+    let (storage, mut backend) = setup_storage_and_crypto(get_test_storage_path());
+    backend.set_at_key_index(0).unwrap();
+
+    let mut own_chain = setup_trust_chain(storage.as_ref(), &backend, ATDailyUsage::default());
+    own_chain.set_at_cert_index(0).unwrap();
+
+    SecurityService::new(
+        own_chain,
+        SecurityBackend::Openssl(backend),
+        PrivacyStrategy::NoStrategy,
+    )
+}
+
+/// Setup a [SecurityService] with the test assets trust chain and the `privacy` strategy.
+/// The first AT has been used as described by `daily_usage`, and is not elected yet.
+pub fn setup_privacy_security_service(
+    privacy: PrivacyStrategy,
+    daily_usage: ATDailyUsage,
+) -> SecurityService {
+    let (storage, backend) = setup_storage_and_crypto(get_test_storage_path());
+    let own_chain = setup_trust_chain(storage.as_ref(), &backend, daily_usage);
+
+    SecurityService::new(own_chain, SecurityBackend::Openssl(backend), privacy)
+}
+
+/// Setup the test assets trust chain, the first AT having been used as described by
+/// `daily_usage`.
+fn setup_trust_chain(
+    storage: &DirectoryStorage,
+    backend: &OpensslBackend,
+    daily_usage: ATDailyUsage,
+) -> TrustChain {
+    let raw_root_cert = storage.load_root_certificate().unwrap();
+    let raw_aa_cert = storage.load_aa_certificate().unwrap();
+    let raw_at_cert = storage.load_at_certificate(0).unwrap();
+
+    let root_cert = RootCertificate::from_bytes(&raw_root_cert, backend).unwrap();
+    let aa_cert = AuthorizationAuthorityCertificate::from_bytes(&raw_aa_cert, backend).unwrap();
+    let at_cert = AuthorizationTicketCertificate::from_bytes(&raw_at_cert, backend).unwrap();
+    let mut at_container = ATContainer::new(at_cert.into_with_hash_container(backend).unwrap(), 0);
+    at_container.set_daily_usage(daily_usage);
+
+    let mut own_chain = TrustChain::new(root_cert.into_with_hash_container(backend).unwrap());
+    own_chain.set_aa_cert(aa_cert.into_with_hash_container(backend).unwrap());
+    own_chain.add_at_cert(0, at_container);
+    own_chain
+}
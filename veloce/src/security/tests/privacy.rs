@@ -0,0 +1,395 @@
+use uom::si::{angle::degree, f64::Length, length::meter, velocity::meter_per_second};
+
+use crate::{
+    common::{
+        geo_area::{Circle, GeoArea, GeoPosition, Shape},
+        PotiFix, PotiMode, PotiMotion, PotiPosition,
+    },
+    security::{
+        permission::Permission,
+        privacy::{
+            daily::{utc_day, SECS_PER_DAY},
+            PrivacyController, PrivacyStrategy,
+        },
+        secured_message::SecuredMessage,
+        service::{PollEvent, SecurityService, SecurityServiceError},
+        ssp::{cam::CamSsp, denm::DenmSsp},
+        storage::{StorageMetadata, StorageTrait},
+        trust_chain::ATDailyUsage,
+    },
+    time::{Duration, Instant, TAI2004},
+    types::{Angle, Distance, Heading, Latitude, Longitude, Speed},
+};
+
+use super::{certificate::valid_timestamp, setup_privacy_security_service};
+
+/// GNSS trace, recorded at 1 Hz on a 3.3 km loop.
+static TRACE: &str = include_str!("assets/trace.csv");
+
+/// Parse the GNSS trace, shifting timestamps by `offset` seconds.
+fn trace(offset: i64) -> Vec<(Instant, PotiFix)> {
+    TRACE
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| {
+            let v: Vec<f64> = l.split(',').map(|f| f.parse().unwrap()).collect();
+            let timestamp = Instant::from_secs(v[0] as i64 + offset);
+            let fix = PotiFix {
+                mode: PotiMode::Fix3d,
+                timestamp: TAI2004::from_unix_instant(timestamp),
+                position: PotiPosition {
+                    latitude: Some(Latitude::new::<degree>(v[1])),
+                    longitude: Some(Longitude::new::<degree>(v[2])),
+                    altitude: Some(Length::new::<meter>(158.13)),
+                },
+                motion: PotiMotion {
+                    speed: Some(Speed::new::<meter_per_second>(v[3])),
+                    vertical_speed: None,
+                    heading: Some(Heading::new::<degree>(v[4])),
+                },
+                confidence: Default::default(),
+            };
+            (timestamp, fix)
+        })
+        .collect()
+}
+
+/// Replay `trace` through `controller`, running the strategy at each position as the
+/// security service does when polled. Returns the instants of the certificate changes.
+fn replay(controller: &mut PrivacyController, trace: &[(Instant, PotiFix)]) -> Vec<Instant> {
+    let mut changes = Vec::new();
+    for (timestamp, fix) in trace {
+        controller.inner_mut().notify_position(*fix, *timestamp);
+        if controller.inner_mut().run(*timestamp) {
+            // Security service notifies the strategy about the newly elected certificate.
+            controller
+                .inner_mut()
+                .notify_elected(Duration::ZERO, *timestamp);
+            changes.push(*timestamp);
+        }
+    }
+    changes
+}
+
+/// Mix zone of 50 meters radius, centered on the trace.
+fn mix_zone() -> GeoArea {
+    GeoArea {
+        shape: Shape::Circle(Circle {
+            radius: Distance::new::<meter>(50.0),
+        }),
+        position: GeoPosition {
+            latitude: Latitude::new::<degree>(48.2751129),
+            longitude: Longitude::new::<degree>(-3.5394081),
+        },
+        angle: Angle::new::<degree>(0.0),
+    }
+}
+
+#[test]
+fn test_silent_period() {
+    let trace = trace(0);
+    let mut controller = PrivacyController::new(PrivacyStrategy::SilentPeriod {
+        interval: Duration::from_secs(60),
+        min_silence: Duration::from_secs(10),
+        max_silence: Duration::from_secs(30),
+        seed: 0xfeed_beef,
+    });
+
+    let mut changes = Vec::new();
+    for (timestamp, fix) in &trace {
+        controller.inner_mut().notify_position(*fix, *timestamp);
+        if !controller.inner_mut().run(*timestamp) {
+            continue;
+        }
+
+        let silent_until = controller.inner().silent_until();
+        if changes.is_empty() {
+            // No silent period at startup.
+            assert_eq!(silent_until, None);
+        } else {
+            let silence = silent_until.unwrap() - *timestamp;
+            assert!(silence >= Duration::from_secs(10) && silence <= Duration::from_secs(30));
+            assert_eq!(
+                controller.inner().run_at(),
+                Some(silent_until.unwrap() + Duration::from_secs(60))
+            );
+        }
+        changes.push(*timestamp);
+    }
+
+    assert_eq!(changes[0], trace[0].0);
+    assert!(changes.len() >= 4);
+    for w in changes.windows(2) {
+        let gap = w[1] - w[0];
+        assert!(gap >= Duration::from_secs(60));
+        assert!(gap <= Duration::from_secs(91));
+    }
+}
+
+#[test]
+fn test_mix_zone() {
+    let trace = trace(0);
+    let strategy = PrivacyStrategy::MixZone {
+        min_interval: Duration::from_secs(30),
+        max_interval: Duration::from_secs(3600),
+    };
+    let end = trace.last().unwrap().0;
+
+    // No mix zone: a single change at startup.
+    let mut controller = PrivacyController::new(strategy);
+    assert_eq!(replay(&mut controller, &trace), vec![trace[0].0]);
+
+    // Expired mix zone.
+    let mut controller = PrivacyController::new(strategy);
+    controller
+        .inner_mut()
+        .notify_mix_zone(mix_zone(), trace[0].0 + Duration::from_secs(10));
+    assert_eq!(replay(&mut controller, &trace), vec![trace[0].0]);
+
+    // Certificate is changed each time the station enters the mix zone.
+    let mut controller = PrivacyController::new(strategy);
+    controller.inner_mut().notify_mix_zone(mix_zone(), end);
+    let changes = replay(&mut controller, &trace);
+    assert!(changes.len() >= 2);
+
+    let zone = mix_zone();
+    for change in &changes[1..] {
+        let (_, fix) = trace.iter().find(|(t, _)| t == change).unwrap();
+        let pos = GeoPosition {
+            latitude: fix.latitude().unwrap(),
+            longitude: fix.longitude().unwrap(),
+        };
+        assert!(zone.inside_or_at_border(pos));
+    }
+
+    for w in changes.windows(2) {
+        assert!(w[1] - w[0] >= Duration::from_secs(30));
+    }
+}
+
+#[test]
+fn test_daily_usage() {
+    // Shift the trace so it starts two minutes before midnight UTC.
+    let start = trace(0)[0].0.secs();
+    let midnight = (start.div_euclid(SECS_PER_DAY) + 1) * SECS_PER_DAY;
+    let shifted = trace(midnight - 120 - start);
+
+    let mut controller =
+        PrivacyController::new(PrivacyStrategy::DailyUsage(Duration::from_secs(3600)));
+    assert_eq!(controller.inner().run_at(), Some(Instant::ZERO));
+
+    let changes = replay(&mut controller, &shifted);
+    assert_eq!(changes, vec![shifted[0].0, Instant::from_secs(midnight)]);
+    assert_eq!(
+        controller.inner().run_at(),
+        Some(Instant::from_secs(midnight + 3600))
+    );
+
+    // AT certificate usage is limited to the maximum duration.
+    let trace = trace(0);
+    let mut controller =
+        PrivacyController::new(PrivacyStrategy::DailyUsage(Duration::from_secs(60)));
+    let changes = replay(&mut controller, &trace);
+    assert_eq!(changes.len(), 6);
+    for w in changes.windows(2) {
+        assert_eq!(w[1] - w[0], Duration::from_secs(60));
+    }
+}
+
+#[test]
+fn test_random_interval() {
+    let trace = trace(0);
+    let mut controller = PrivacyController::new(PrivacyStrategy::RandomInterval {
+        min: Duration::from_secs(30),
+        max: Duration::from_secs(90),
+        seed: 0xdead_cafe,
+    });
+
+    let changes = replay(&mut controller, &trace);
+    assert_eq!(changes[0], trace[0].0);
+    assert!(changes.len() >= 4);
+    for w in changes.windows(2) {
+        let gap = w[1] - w[0];
+        assert!(gap >= Duration::from_secs(30));
+        assert!(gap <= Duration::from_secs(91));
+    }
+
+    // An AT certificate change outside of the strategy restarts the current interval.
+    let now = changes[changes.len() - 1] + Duration::from_secs(10);
+    let at = controller.inner().run_at().unwrap();
+    controller.inner_mut().reset(now);
+    assert_eq!(
+        controller.inner().run_at(),
+        Some(now + (at - changes[changes.len() - 1]))
+    );
+}
+
+/// Sign a dummy message with `permission` at `timestamp`.
+fn sign(
+    service: &mut SecurityService,
+    permission: Permission,
+    timestamp: Instant,
+) -> Result<(), SecurityServiceError> {
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    let mut message = SecuredMessage::new(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    service.sign_secured_message(&mut message, permission, timestamp, position)
+}
+
+#[test]
+fn test_service_silent_period() {
+    let mut service = setup_privacy_security_service(
+        PrivacyStrategy::SilentPeriod {
+            interval: Duration::from_secs(60),
+            min_silence: Duration::from_secs(10),
+            max_silence: Duration::from_secs(10),
+            seed: 0xfeed_beef,
+        },
+        ATDailyUsage::default(),
+    );
+    let cam = Permission::CAM(CamSsp::new_v1().into());
+    let denm = Permission::DENM(DenmSsp::new_v1().into());
+    let t0 = valid_timestamp();
+
+    assert!(matches!(
+        service.poll(t0),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert!(!service.is_silent(t0));
+    sign(&mut service, cam.clone(), t0).unwrap();
+
+    // Certificate change starts a silent period.
+    let change = t0 + Duration::from_secs(60);
+    assert!(matches!(
+        service.poll(change),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert!(service.is_silent(change + Duration::from_secs(5)));
+
+    // Only CAMs are refused by the signing service during the silent period.
+    assert!(matches!(
+        sign(&mut service, cam.clone(), change + Duration::from_secs(5)),
+        Err(SecurityServiceError::SilentPeriod)
+    ));
+    sign(&mut service, denm, change + Duration::from_secs(5)).unwrap();
+
+    // CAMs are signed again after the silent period.
+    assert!(!service.is_silent(change + Duration::from_secs(10)));
+    sign(&mut service, cam, change + Duration::from_secs(10)).unwrap();
+}
+
+#[test]
+fn test_service_daily_usage() {
+    let t0 = valid_timestamp();
+    let day = utc_day(t0);
+    let midnight = Instant::from_secs((day + 1) * SECS_PER_DAY);
+    assert!(midnight - t0 > Duration::from_secs(60));
+
+    let mut service = setup_privacy_security_service(
+        PrivacyStrategy::DailyUsage(Duration::from_secs(60)),
+        ATDailyUsage::default(),
+    );
+
+    assert!(matches!(
+        service.poll(t0),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert_eq!(service.poll_at(), Some(t0 + Duration::from_secs(60)));
+
+    // Usage of the AT certificate is accounted.
+    let t1 = t0 + Duration::from_secs(30);
+    assert!(service.poll(t1).is_none());
+    assert_eq!(
+        service.at_daily_usages(t1),
+        vec![(
+            0,
+            ATDailyUsage {
+                day,
+                usage: Duration::from_secs(30)
+            }
+        )]
+    );
+
+    // Only AT certificate has reached its daily usage limit, election is retried at midnight.
+    assert!(service.poll(t0 + Duration::from_secs(60)).is_none());
+    assert_eq!(service.poll_at(), Some(midnight));
+    assert_eq!(
+        service.at_daily_usages(t0 + Duration::from_secs(60)),
+        vec![(
+            0,
+            ATDailyUsage {
+                day,
+                usage: Duration::from_secs(60)
+            }
+        )]
+    );
+
+    // Usage is reset on the next UTC day.
+    assert!(matches!(
+        service.poll(midnight),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert_eq!(service.poll_at(), Some(midnight + Duration::from_secs(60)));
+}
+
+#[test]
+fn test_service_daily_usage_restored() {
+    let t0 = valid_timestamp();
+    let day = utc_day(t0);
+
+    // Usage persisted in the storage metadata before a restart.
+    let mut service = setup_privacy_security_service(
+        PrivacyStrategy::DailyUsage(Duration::from_secs(60)),
+        ATDailyUsage {
+            day,
+            usage: Duration::from_secs(50),
+        },
+    );
+
+    assert!(matches!(
+        service.poll(t0),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert_eq!(service.poll_at(), Some(t0 + Duration::from_secs(10)));
+    assert!(service.poll(t0 + Duration::from_secs(10)).is_none());
+
+    // Usage of a previous day is not accounted.
+    let mut service = setup_privacy_security_service(
+        PrivacyStrategy::DailyUsage(Duration::from_secs(60)),
+        ATDailyUsage {
+            day: day - 1,
+            usage: Duration::from_secs(60),
+        },
+    );
+
+    assert!(matches!(
+        service.poll(t0),
+        Some(PollEvent::PrivacyATCertificateRotation(0, _))
+    ));
+    assert_eq!(service.poll_at(), Some(t0 + Duration::from_secs(60)));
+}
+
+#[test]
+fn test_daily_usage_persistence() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (storage, _) = super::setup_storage_and_crypto(base_path);
+
+    let usage = ATDailyUsage {
+        day: utc_day(valid_timestamp()),
+        usage: Duration::from_secs(1234),
+    };
+
+    let mut meta = StorageMetadata::new();
+    meta.set_elections_stats(0, 3);
+    meta.set_daily_usage(0, usage);
+    storage.store_metadata(meta).unwrap();
+
+    let meta = storage.load_metadata().unwrap();
+    assert_eq!(meta.elections_stats(0), Some(3));
+    assert_eq!(meta.daily_usage(0), Some(usage));
+    assert_eq!(meta.daily_usage(1), None);
+}
@@ -1,33 +1,29 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use approx::assert_relative_eq;
 use uom::si::angle::degree;
 
 use crate::{
     common::PotiPosition,
+    config::{SEC_SIG_CACHE_ENTRY_LIFETIME, SEC_SIG_CACHE_SIZE},
+    pki::message::ctl::{CertificateTrustList, TlmCertificateTrustListMessage},
     security::{
-        backend::{
-            openssl::{OpensslBackend, OpensslBackendConfig},
-            BackendTrait,
-        },
-        certificate::{
-            AuthorizationAuthorityCertificate, AuthorizationTicketCertificate, CertificateTrait,
-            ExplicitCertificate, RootCertificate,
-        },
-        permission::Permission,
-        privacy::PrivacyStrategy,
+        certificate::{CertificateError, CertificateTrait, ExplicitCertificate, RootCertificate},
+        permission::{Permission, AID},
         secured_message::{SecuredMessage, SignerIdentifier},
-        service::SecurityService,
+        service::{CertificateRequestError, SecurityServiceError, VerificationMode},
+        signature_cache::SignatureCache,
+        signer_policy::{SignerIdentifierPolicy, SignerPolicy},
         ssp::{cam::CamSsp, denm::DenmSsp},
-        storage::StorageTrait,
-        trust_chain::{ATContainer, TrustChain},
-        DirectoryStorage, DirectoryStorageConfig, SecurityBackend,
+        storage::{RemoteCertificateType, StorageTrait},
+        trust_chain::TrustChain,
+        HashedId8,
     },
-    time::Duration,
+    time::{Duration, Instant},
     types::{tenth_of_microdegree, Latitude, Longitude},
 };
 
-use super::certificate::valid_timestamp;
+use super::{certificate::valid_timestamp, setup_security_service};
 
 const SECURITY_ENVELOPE: [u8; 313] = [
     0x03, 0x81, 0x00, 0x40, 0x03, 0x80, 0x42, 0x20, 0x50, 0x02, 0x00, 0x00, 0x1e, 0x01, 0x00, 0x3c,
@@ -52,7 +48,7 @@ const SECURITY_ENVELOPE: [u8; 313] = [
     0x75, 0xf8, 0x72, 0x86, 0xb9, 0xc6, 0xbc, 0x7d, 0xec,
 ];
 
-const GN_CAM: [u8; 66] = [
+pub(super) const GN_CAM: [u8; 66] = [
     0x20, 0x50, 0x02, 0x00, 0x00, 0x1e, 0x01, 0x00, 0x3c, 0x00, 0xae, 0x17, 0x15, 0xb4, 0x56, 0x03,
     0xd7, 0x73, 0x4e, 0x6b, 0x1c, 0xa8, 0xac, 0xff, 0xff, 0x04, 0x1e, 0xb0, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0x00, 0x00, 0x07, 0xd1, 0x00, 0x00, 0x02, 0x02, 0xc7, 0x92, 0xbf, 0xbc, 0x63, 0xa4,
@@ -60,51 +56,172 @@ const GN_CAM: [u8; 66] = [
     0x96, 0x80,
 ];
 
-fn setup_security_service() -> SecurityService {
-    let mut key_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    key_path.pop();
-    key_path.push(file!());
-    key_path.pop();
-    let key_path = std::fs::canonicalize(key_path).unwrap();
+#[test]
+fn verify_secured_message() {
+    let mut service = setup_security_service();
 
-    let storage_config =
-        DirectoryStorageConfig::new(Some(key_path.into_os_string().into_string().unwrap()));
-    let storage = Rc::new(DirectoryStorage::new(storage_config).unwrap());
+    let msg = SecuredMessage::from_bytes(&SECURITY_ENVELOPE).unwrap();
+
+    service
+        .verify_secured_message(&msg, valid_timestamp())
+        .unwrap();
+}
+
+#[test]
+fn verify_secured_message_on_demand() {
+    let mut service = setup_security_service();
+    service.set_verification_mode(VerificationMode::OnDemand);
 
-    let config = OpensslBackendConfig::new("test1234".to_string().into());
-    let mut backend = OpensslBackend::new(config, storage.clone()).unwrap();
-    backend.set_at_key_index(0).unwrap();
+    let (mut confirm, _) = service
+        .decap_packet(&SECURITY_ENVELOPE, valid_timestamp())
+        .unwrap();
+    assert!(!confirm.is_verified());
 
-    let raw_root_cert = storage.load_root_certificate().unwrap();
-    let raw_aa_cert = storage.load_aa_certificate().unwrap();
-    let raw_at_cert = storage.load_at_certificate(0).unwrap();
+    // Packet is not consumed.
+    service
+        .decap_packet(&SECURITY_ENVELOPE, valid_timestamp())
+        .unwrap();
 
-    let root_cert = RootCertificate::from_bytes(&raw_root_cert, &backend).unwrap();
-    let aa_cert = AuthorizationAuthorityCertificate::from_bytes(&raw_aa_cert, &backend).unwrap();
-    let at_cert = AuthorizationTicketCertificate::from_bytes(&raw_at_cert, &backend).unwrap();
-    let at_container = ATContainer::new(at_cert.into_with_hash_container(&backend).unwrap(), 0);
+    service
+        .verify_decap(&mut confirm, valid_timestamp())
+        .unwrap();
+    assert!(confirm.is_verified());
 
-    let mut own_chain = TrustChain::new(root_cert.into_with_hash_container(&backend).unwrap());
-    own_chain.set_aa_cert(aa_cert.into_with_hash_container(&backend).unwrap());
-    own_chain.add_at_cert(0, at_container);
-    own_chain.set_at_cert_index(0).unwrap();
+    // Signature is now in the cache, which is looked up once the packet is consumed.
+    let (mut confirm, _) = service
+        .decap_packet(&SECURITY_ENVELOPE, valid_timestamp())
+        .unwrap();
+    assert!(!confirm.is_verified());
 
-    SecurityService::new(
-        own_chain,
-        SecurityBackend::Openssl(backend),
-        PrivacyStrategy::NoStrategy,
-    )
+    service
+        .verify_decap(&mut confirm, valid_timestamp())
+        .unwrap();
+    assert!(confirm.is_verified());
+
+    let stats = service.verification_stats();
+    assert_eq!(stats.verified, 1);
+    assert_eq!(stats.cache_hits, 1);
+    assert_eq!(stats.deferred, 3);
+    assert_eq!(stats.consumed, 2);
+    assert_eq!(stats.skipped(), 1);
 }
 
 #[test]
-fn verify_secured_message() {
+fn verify_secured_message_batch() {
     let mut service = setup_security_service();
 
-    let msg = SecuredMessage::from_bytes(&SECURITY_ENVELOPE).unwrap();
+    let permissions = Permission::CAM(CamSsp::new_v1().into());
+
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    // First message carries the signer certificate, the next ones its digest.
+    let timestamp = valid_timestamp();
+    let messages: Vec<_> = (0..3)
+        .map(|i| {
+            let mut message = SecuredMessage::new(GN_CAM.to_vec());
+            service
+                .sign_secured_message(
+                    &mut message,
+                    permissions.clone(),
+                    timestamp + Duration::from_millis(100 * i),
+                    position,
+                )
+                .unwrap();
+            message
+        })
+        .collect();
+    assert!(matches!(
+        messages[1].signer_identifier().unwrap(),
+        SignerIdentifier::Digest(_)
+    ));
+
+    // Signer certificate is unknown, messages are verified one by one.
+    let timestamp = timestamp + Duration::from_millis(500);
+    service.verify_batch(&messages[..2], timestamp);
+    service
+        .verify_secured_message(&messages[0], timestamp)
+        .unwrap();
+    assert_eq!(service.verification_stats().batched, 0);
+
+    // Signer certificate is cached, a message received twice is verified once.
+    let received = [
+        messages[1].clone(),
+        messages[2].clone(),
+        messages[1].clone(),
+    ];
+    service.verify_batch(&received, timestamp);
+    assert_eq!(service.verification_stats().verified, 1);
+
+    for msg in &received {
+        service.verify_secured_message(msg, timestamp).unwrap();
+    }
+
+    let stats = service.verification_stats();
+    assert_eq!(stats.verified, 3);
+    assert_eq!(stats.batched, 2);
+    assert_eq!(stats.cache_hits, 1);
+    assert_eq!(stats.failed, 0);
+}
+
+#[test]
+fn verify_secured_message_tampered_r() {
+    let mut service = setup_security_service();
 
+    let msg = SecuredMessage::from_bytes(&SECURITY_ENVELOPE).unwrap();
     service
         .verify_secured_message(&msg, valid_timestamp())
         .unwrap();
+
+    // Envelope ends with the signature `r` and `s` components, 32 bytes each.
+    let mut tampered = SECURITY_ENVELOPE;
+    tampered[SECURITY_ENVELOPE.len() - 40] ^= 0x01;
+    let msg = SecuredMessage::from_bytes(&tampered).unwrap();
+    assert!(service
+        .verify_secured_message(&msg, valid_timestamp())
+        .is_err());
+
+    assert_eq!(service.verification_stats().cache_hits, 0);
+}
+
+#[test]
+fn test_signature_cache() {
+    let mut cache = SignatureCache::new();
+    let t0 = valid_timestamp();
+    let key = |i: u64| (HashedId8::from_u64(i), [i as u8; 32]);
+
+    // Entries are filled one millisecond apart, the first one expiring first.
+    for i in 0..SEC_SIG_CACHE_SIZE as u64 {
+        cache.fill(key(i), t0 + Duration::from_millis(i));
+    }
+    let full = t0 + Duration::from_millis(SEC_SIG_CACHE_SIZE as u64);
+    assert!((0..SEC_SIG_CACHE_SIZE as u64).all(|i| cache.lookup(&key(i), full)));
+
+    // Cache is full, the entry expiring first is evicted.
+    let extra = SEC_SIG_CACHE_SIZE as u64;
+    cache.fill(key(extra), full);
+    assert!(!cache.lookup(&key(0), full));
+    assert!(cache.lookup(&key(1), full));
+    assert!(cache.lookup(&key(extra), full));
+
+    // Refilling an entry extends its lifetime instead of evicting another one.
+    let expiry = t0 + SEC_SIG_CACHE_ENTRY_LIFETIME;
+    cache.fill(key(5), full);
+    assert!(cache.lookup(&key(1), full));
+    assert!(cache.lookup(&key(5), expiry + Duration::from_millis(5)));
+
+    // Entries expire after their lifetime.
+    assert!(cache.lookup(&key(1), expiry));
+    assert!(!cache.lookup(&key(1), expiry + Duration::from_millis(1)));
+    assert!(!cache.lookup(&key(2), expiry + Duration::from_millis(2)));
+    assert!(cache.lookup(&key(extra), expiry + Duration::from_millis(2)));
+    assert!(!cache.lookup(&key(extra), full + SEC_SIG_CACHE_ENTRY_LIFETIME));
+
+    cache.clear();
+    assert!(!cache.lookup(&key(extra), full));
 }
 
 #[test]
@@ -201,6 +318,68 @@ fn test_signer_digest_or_certificate_cam() {
     assert!(matches!(signer, SignerIdentifier::Certificate(_)));
 }
 
+#[test]
+fn test_signer_policy() {
+    let mut service = setup_security_service();
+
+    let mut policy = SignerPolicy::digest_only();
+    policy.set(
+        AID::CA,
+        SignerIdentifierPolicy::Periodic(Duration::from_secs(2)),
+    );
+    service.set_signer_policy(policy);
+
+    let cam_permissions = Permission::CAM(CamSsp::new_v1().into());
+    let denm_permissions = Permission::DENM(DenmSsp::new_v1().into());
+
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    let timestamp_start = valid_timestamp();
+    let mut sign = |permissions: &Permission, offset: u64| {
+        let mut message = SecuredMessage::new(GN_CAM.to_vec());
+        service
+            .sign_secured_message(
+                &mut message,
+                permissions.clone(),
+                timestamp_start + Duration::from_millis(offset),
+                position,
+            )
+            .unwrap();
+        message.signer_identifier().unwrap()
+    };
+
+    // DENM should always contain the digest.
+    let signer = sign(&denm_permissions, 0);
+    assert!(matches!(signer, SignerIdentifier::Digest(_)));
+
+    let signer = sign(&cam_permissions, 0);
+    assert!(matches!(signer, SignerIdentifier::Certificate(_)));
+
+    let signer = sign(&cam_permissions, 1500);
+    assert!(matches!(signer, SignerIdentifier::Digest(_)));
+
+    let signer = sign(&cam_permissions, 2000);
+    assert!(matches!(signer, SignerIdentifier::Certificate(_)));
+
+    // New neighbour should trigger the inclusion of the full certificate.
+    service.notify_new_neighbour();
+    let mut message = SecuredMessage::new(GN_CAM.to_vec());
+    service
+        .sign_secured_message(
+            &mut message,
+            cam_permissions,
+            timestamp_start + Duration::from_millis(2100),
+            position,
+        )
+        .unwrap();
+    let signer = message.signer_identifier().unwrap();
+    assert!(matches!(signer, SignerIdentifier::Certificate(_)));
+}
+
 #[test]
 fn test_position_inclusion() {
     let mut service = setup_security_service();
@@ -245,3 +424,336 @@ fn test_position_inclusion() {
 
     assert!(message.generation_location().unwrap().is_none());
 }
+
+#[test]
+fn test_time_accuracy() {
+    let mut service = setup_security_service();
+    let permissions = Permission::CAM(CamSsp::new_v1().into());
+
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    let timestamp = valid_timestamp();
+    service.set_required_time_accuracy(Some(Duration::from_millis(500)));
+
+    // Time accuracy is unknown.
+    let mut message = SecuredMessage::new(GN_CAM.to_vec());
+    let res = service.sign_secured_message(&mut message, permissions.clone(), timestamp, position);
+    assert!(matches!(
+        res,
+        Err(SecurityServiceError::InsufficientTimeAccuracy)
+    ));
+
+    // Time accuracy is insufficient.
+    service.notify_time_accuracy(Some(Duration::from_secs(1)));
+    let res = service.sign_secured_message(&mut message, permissions.clone(), timestamp, position);
+    assert!(matches!(
+        res,
+        Err(SecurityServiceError::InsufficientTimeAccuracy)
+    ));
+
+    // Time accuracy is sufficient.
+    service.notify_time_accuracy(Some(Duration::from_millis(250)));
+    service
+        .sign_secured_message(&mut message, permissions, timestamp, position)
+        .unwrap();
+}
+
+#[test]
+fn test_remote_certificates_persistence() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (remote_storage, _) = super::setup_storage_and_crypto(base_path);
+
+    let mut service = setup_security_service();
+    let raw_aa_cert = std::fs::read(super::get_test_storage_path().join("assets/AA.cert")).unwrap();
+
+    let aa_hash = service
+        .add_remote_certificate(RemoteCertificateType::AA, &raw_aa_cert, valid_timestamp())
+        .unwrap();
+    assert!(service.has_remote_certificates_to_persist());
+
+    service
+        .persist_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert!(!service.has_remote_certificates_to_persist());
+    assert_eq!(remote_storage.load_remote_certificates().unwrap().len(), 1);
+
+    // Certificate is restored after a restart.
+    let mut service = setup_security_service();
+    let restored = service
+        .restore_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert_eq!(restored, 1);
+    assert!(service
+        .store()
+        .remote_aas()
+        .any(|aa| aa.hashed_id8() == aa_hash));
+
+    // Malformed certificates are not restored, and deleted from the storage.
+    remote_storage
+        .store_remote_certificate(RemoteCertificateType::EA, aa_hash, &[0xde, 0xad])
+        .unwrap();
+    let mut service = setup_security_service();
+    let restored = service
+        .restore_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert_eq!(restored, 1);
+    assert_eq!(remote_storage.load_remote_certificates().unwrap().len(), 1);
+
+    // Certificate stored under a different digest is not restored, and deleted from the storage.
+    remote_storage
+        .store_remote_certificate(
+            RemoteCertificateType::AA,
+            HashedId8::from_u64(0xdead_beef),
+            &raw_aa_cert,
+        )
+        .unwrap();
+    let mut service = setup_security_service();
+    let restored = service
+        .restore_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert_eq!(restored, 1);
+    assert_eq!(remote_storage.load_remote_certificates().unwrap().len(), 1);
+}
+
+#[test]
+fn test_revoked_certificates_persistence() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (remote_storage, _) = super::setup_storage_and_crypto(base_path);
+
+    let mut service = setup_security_service();
+    let raw_aa_cert = std::fs::read(super::get_test_storage_path().join("assets/AA.cert")).unwrap();
+
+    let aa_hash = service
+        .add_remote_certificate(RemoteCertificateType::AA, &raw_aa_cert, valid_timestamp())
+        .unwrap();
+    service
+        .persist_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+
+    // Revoked certificate is removed from the storage, and the revocation is persisted.
+    service.store_mut().add_revoked_cert(aa_hash);
+    assert!(service.has_remote_certificates_to_persist());
+    service
+        .persist_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert!(!service.has_remote_certificates_to_persist());
+    assert!(service.store().remote_aas().next().is_none());
+    assert!(remote_storage
+        .load_remote_certificates()
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        remote_storage.load_revoked_certificates().unwrap(),
+        vec![aa_hash]
+    );
+
+    // Revocation survives a restart.
+    let mut service = setup_security_service();
+    service
+        .restore_remote_certificates(remote_storage.as_ref(), valid_timestamp())
+        .unwrap();
+    assert!(service.store().is_revoked(aa_hash));
+    assert!(!service.has_remote_certificates_to_persist());
+    assert!(matches!(
+        service.add_remote_certificate(RemoteCertificateType::AA, &raw_aa_cert, valid_timestamp()),
+        Err(CertificateRequestError::Revoked)
+    ));
+}
+
+#[test]
+fn test_remote_root_certificate_trust() {
+    let mut service = setup_security_service();
+    let (storage, backend) = super::setup_storage_and_crypto(super::get_test_storage_path());
+
+    // Take a root certificate, other than the own one, from the ECTL.
+    let raw_ectl = storage.load_ectl().unwrap();
+    let ectl_msg = TlmCertificateTrustListMessage::from_bytes_signed(&raw_ectl).unwrap();
+    let ectl = CertificateTrustList::from_bytes_tlm(ectl_msg.payload_data().unwrap()).unwrap();
+    let root_entry = ectl.commands().unwrap().add.root.remove(0);
+    let root_cert = RootCertificate::from_etsi_cert(root_entry.certificate, &backend).unwrap();
+    let root_hash = root_cert.hashed_id8(&backend).unwrap();
+    let raw_root_cert = root_cert.raw_bytes().to_vec();
+    let timestamp =
+        root_cert.validity_period().start().as_unix_instant() + Duration::from_secs(86400);
+
+    assert_ne!(
+        root_hash,
+        service.store().own_chain().root_cert().hashed_id8()
+    );
+
+    // Root certificate is not listed in the trusted ECTL.
+    assert!(matches!(
+        service.add_remote_certificate(RemoteCertificateType::Root, &raw_root_cert, timestamp),
+        Err(CertificateRequestError::UntrustedRoot)
+    ));
+    assert!(service.store().remote_roots().next().is_none());
+
+    // Root certificate is listed in the trusted ECTL.
+    service.store_mut().set_trusted_roots(vec![root_hash]);
+    assert_eq!(
+        service
+            .add_remote_certificate(RemoteCertificateType::Root, &raw_root_cert, timestamp)
+            .unwrap(),
+        root_hash
+    );
+    assert!(service.store().is_known_root(root_hash));
+
+    // Root certificate is purged once removed from the trusted ECTL.
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (remote_storage, _) = super::setup_storage_and_crypto(base_path);
+    service
+        .persist_remote_certificates(remote_storage.as_ref(), timestamp)
+        .unwrap();
+    assert_eq!(remote_storage.load_remote_certificates().unwrap().len(), 1);
+
+    service.store_mut().set_trusted_roots(Vec::new());
+    service
+        .persist_remote_certificates(remote_storage.as_ref(), timestamp)
+        .unwrap();
+    assert!(!service.store().is_known_root(root_hash));
+    assert!(remote_storage
+        .load_remote_certificates()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_add_select_at_cert() {
+    let mut service = setup_security_service();
+    let raw_at_cert =
+        std::fs::read(super::get_test_storage_path().join("assets/AT_0.cert")).unwrap();
+    let own_hash = service.at_hashed_id8().unwrap();
+
+    // Certificate is added at a new index, as an election candidate.
+    let hash = service
+        .add_at_cert(1, &raw_at_cert, valid_timestamp())
+        .unwrap();
+    assert_eq!(hash, own_hash);
+    assert_eq!(
+        service.at_certs_stats().keys().copied().collect::<Vec<_>>(),
+        [0, 1]
+    );
+
+    // Malformed certificates are rejected.
+    assert!(matches!(
+        service.add_at_cert(2, &raw_at_cert[..raw_at_cert.len() / 2], valid_timestamp()),
+        Err(SecurityServiceError::InvalidCertificate(_))
+    ));
+    assert_eq!(service.at_certs_stats().len(), 2);
+
+    // Expired certificates are rejected.
+    assert!(matches!(
+        service.add_at_cert(2, &raw_at_cert, Instant::from_secs(4_102_444_800)),
+        Err(SecurityServiceError::InvalidCertificate(
+            CertificateError::Expired(_)
+        ))
+    ));
+    assert_eq!(service.at_certs_stats().len(), 2);
+
+    // Selected certificate is used to sign, at the lowest index holding it.
+    assert_eq!(service.select_at_cert(hash, valid_timestamp()).unwrap(), 0);
+    assert_eq!(service.at_hashed_id8().unwrap(), hash);
+
+    // Unknown certificates cannot be selected.
+    assert!(matches!(
+        service.select_at_cert(HashedId8::from_bytes(&[0xff; 8]), valid_timestamp()),
+        Err(SecurityServiceError::NoSigningCertificate)
+    ));
+    assert_eq!(service.at_hashed_id8().unwrap(), hash);
+}
+
+#[test]
+fn test_verification_callback() {
+    let mut service = setup_security_service();
+
+    let results = Rc::new(RefCell::new(Vec::new()));
+    let cb_results = results.clone();
+    service.register_verification_callback(move |packet, res| {
+        let signer = res.as_ref().ok().map(|(confirm, _)| confirm.cert_id);
+        cb_results.borrow_mut().push((
+            packet.to_vec(),
+            signer,
+            res.as_ref().err().map(|e| e.kind()),
+        ));
+    });
+
+    let (confirm, _) = service
+        .decap_packet(&SECURITY_ENVELOPE, valid_timestamp())
+        .unwrap();
+
+    let mut tampered = SECURITY_ENVELOPE;
+    *tampered.last_mut().unwrap() ^= 0x01;
+    let err = service
+        .decap_packet(&tampered, valid_timestamp())
+        .unwrap_err();
+
+    // Callback is notified of each decapsulation, successful or not.
+    assert_eq!(
+        *results.borrow(),
+        [
+            (SECURITY_ENVELOPE.to_vec(), Some(confirm.cert_id), None),
+            (tampered.to_vec(), None, Some(err.kind())),
+        ]
+    );
+}
+
+#[test]
+fn test_add_at_cert_unknown_aa() {
+    let mut service = setup_security_service();
+    let raw_at_cert =
+        std::fs::read(super::get_test_storage_path().join("assets/AT_0.cert")).unwrap();
+
+    // Local trust chain without AA certificate, the AT issuer is unknown.
+    let root_cert = service.store().own_chain().root_cert().clone();
+    *service.store_mut().own_chain_mut() = TrustChain::new(root_cert);
+
+    assert!(matches!(
+        service.add_at_cert(0, &raw_at_cert, valid_timestamp()),
+        Err(SecurityServiceError::InvalidCertificate(
+            CertificateError::UnknownSigner(_)
+        ))
+    ));
+    assert!(service.at_certs_stats().is_empty());
+}
+
+#[test]
+fn test_set_own_authorities() {
+    let mut service = setup_security_service();
+    let assets = super::get_test_storage_path().join("assets");
+    let raw_root_cert = std::fs::read(assets.join("RCA.cert")).unwrap();
+    let raw_aa_cert = std::fs::read(assets.join("AA.cert")).unwrap();
+    let raw_at_cert = std::fs::read(assets.join("AT_0.cert")).unwrap();
+    let aa_hash = service.store().own_chain().aa_cert().unwrap().hashed_id8();
+    let at_hash = service.at_hashed_id8().unwrap();
+
+    // AA certificate should be issued by the root certificate.
+    assert!(service
+        .set_own_authorities(&raw_aa_cert, &raw_aa_cert, valid_timestamp())
+        .is_err());
+    assert_eq!(service.at_hashed_id8().unwrap(), at_hash);
+
+    // AT certificates of the previous chain are dropped.
+    assert_eq!(
+        service
+            .set_own_authorities(&raw_root_cert, &raw_aa_cert, valid_timestamp())
+            .unwrap(),
+        aa_hash
+    );
+    assert!(service.at_certs_stats().is_empty());
+    assert!(service.at_hashed_id8().is_err());
+
+    assert_eq!(
+        service
+            .add_at_cert(0, &raw_at_cert, valid_timestamp())
+            .unwrap(),
+        at_hash
+    );
+    assert_eq!(
+        service.select_at_cert(at_hash, valid_timestamp()).unwrap(),
+        0
+    );
+}
@@ -0,0 +1,224 @@
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ec::{EcGroup, EcKey, EcPoint},
+    nid::Nid,
+    pkey::PKey,
+};
+use uom::si::angle::degree;
+
+use crate::{
+    common::PotiPosition,
+    security::{
+        backend::{BackendError, BackendTrait, PkiBackendTrait, Sm2Ciphertext},
+        certificate::{ExplicitCertificate, RootCertificate},
+        permission::Permission,
+        privacy::PrivacyStrategy,
+        secured_message::SecuredMessage,
+        service::SecurityService,
+        signature::{EcdsaSignature, EcdsaSignatureInner},
+        ssp::cam::CamSsp,
+        EcKeyType, EccPoint, EcdsaKey, SecurityBackend, UncompressedEccPoint,
+    },
+    time::Duration,
+    types::{Latitude, Longitude},
+};
+
+use super::{certificate::valid_timestamp, confidential::setup_chain, secured_message::GN_CAM};
+
+/// GB/T 32918.5 Annex A and Annex C key pair, used for both the signature and the encryption
+/// examples.
+const SM2_EXAMPLE_SECRET_KEY: &str =
+    "3945208F7B2144B13F36E38AC6D39F95889393692860B51A42FB81EF4DF7C5B8";
+const SM2_EXAMPLE_PUBLIC_KEY_X: &str =
+    "09F9DF311E5421A150DD7D161E4BC5C672179FAD1833FC076BB08FF356F35020";
+const SM2_EXAMPLE_PUBLIC_KEY_Y: &str =
+    "CCEA490CE26775A52DC6EA718CC1AA600AED05FBF35E084A6632F6072DA9AD13";
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_sm3_known_answer() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, backend) = super::setup_storage_and_crypto(base_path);
+
+    // GB/T 32905 Annex A.1 example.
+    assert_eq!(
+        backend.sm3(b"abc").unwrap().to_vec(),
+        hex("66C7F0F462EEEDD9D1F2D46BDC10E4E24167C4875CF2F7A2297DA02B8F4BA8E0")
+    );
+}
+
+#[test]
+fn test_sm2_signature_known_answer() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, backend) = super::setup_storage_and_crypto(base_path);
+
+    // GB/T 32918.5 Annex A example, with the default distinguishing identifier.
+    let key = EcdsaKey::Sm2(EccPoint::Uncompressed(UncompressedEccPoint {
+        x: hex(SM2_EXAMPLE_PUBLIC_KEY_X),
+        y: hex(SM2_EXAMPLE_PUBLIC_KEY_Y),
+    }));
+    let signature = EcdsaSignature::Sm2(EcdsaSignatureInner {
+        r: EccPoint::XCoordinateOnly(hex(
+            "F5A03B0648D2C4630EEAC513E1BB81A15944DA3827D5B74143AC7EACEEE720B3",
+        )),
+        s: hex("B1B6AA29DF212FD8763182BC0D421CA1BB9038FD1F7F42D4840B69C485BBC1AA"),
+    });
+
+    assert!(backend
+        .verify_signature(signature.clone(), key.clone(), b"message digest")
+        .unwrap());
+    assert!(!backend
+        .verify_signature(signature, key, b"message digesT")
+        .unwrap());
+}
+
+#[test]
+fn test_sm2_decryption_known_answer() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, backend) = super::setup_storage_and_crypto(base_path);
+
+    let group = EcGroup::from_curve_name(Nid::SM2).unwrap();
+    let ctx = BigNumContext::new().unwrap();
+    let d = BigNum::from_hex_str(SM2_EXAMPLE_SECRET_KEY).unwrap();
+    let mut point = EcPoint::new(&group).unwrap();
+    point.mul_generator(&group, &d, &ctx).unwrap();
+    let secret_key =
+        PKey::from_ec_key(EcKey::from_private_components(&group, &d, &point).unwrap()).unwrap();
+
+    // GB/T 32918.5 Annex C example.
+    let x =
+        BigNum::from_hex_str("04EBFC718E8D1798620432268E77FEB6415E2EDE0E073C0F4F640ECD2E149A73")
+            .unwrap();
+    let y =
+        BigNum::from_hex_str("E858F9D81E5430A57B36DAAB8F950A3C64E6EE6A63094D99283AFF767E124DF0")
+            .unwrap();
+    let mut ciphertext = Sm2Ciphertext {
+        ephemeral_key: PKey::from_ec_key(
+            EcKey::from_public_key_affine_coordinates(&group, &x, &y).unwrap(),
+        )
+        .unwrap(),
+        data: hex("21886CA989CA9C7D58087307CA93092D651EFA"),
+        tag: hex("59983C18F809E262923C53AEC295D30383B54E39D609D160AFCB1908D0BD8766"),
+    };
+
+    let res = backend.sm2_decrypt(&secret_key, &ciphertext).unwrap();
+    assert_eq!(res, b"encryption standard");
+
+    ciphertext.data[0] ^= 0x01;
+    let res = backend.sm2_decrypt(&secret_key, &ciphertext);
+    assert!(matches!(res, Err(BackendError::InvalidData)));
+}
+
+#[test]
+fn test_sm2_encrypt_decrypt() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, backend) = super::setup_storage_and_crypto(base_path);
+
+    let recipient = backend.generate_ephemeral_keypair(EcKeyType::Sm2).unwrap();
+    let key = backend.generate_aes128_key().unwrap();
+
+    let ciphertext = backend.sm2_encrypt(&recipient.public, &key).unwrap();
+    assert_eq!(ciphertext.data.len(), key.len());
+    assert_eq!(ciphertext.tag.len(), 32);
+
+    let res = backend.sm2_decrypt(&recipient.secret, &ciphertext).unwrap();
+    assert_eq!(res, key);
+
+    // Another key cannot decrypt.
+    let other = backend.generate_ephemeral_keypair(EcKeyType::Sm2).unwrap();
+    let res = backend.sm2_decrypt(&other.secret, &ciphertext);
+    assert!(matches!(res, Err(BackendError::InvalidData)));
+
+    // SM2 keys are not used for key agreement.
+    let res = backend.derive(&other.secret, &recipient.public);
+    assert!(matches!(res, Err(BackendError::UnsupportedKeyType)));
+}
+
+#[test]
+fn test_sm2_certificate_chain() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, mut backend) = super::setup_storage_and_crypto(base_path);
+
+    let chain = setup_chain(&mut backend, EcKeyType::Sm2);
+    let root = chain.root_cert();
+    let aa = chain.aa_cert().unwrap();
+    let at = chain.at_cert().unwrap().at_container();
+
+    assert!(matches!(
+        at.certificate().public_verification_key().unwrap(),
+        EcdsaKey::Sm2(_)
+    ));
+    assert!(matches!(
+        at.certificate().signature().unwrap(),
+        EcdsaSignature::Sm2(_)
+    ));
+
+    let timestamp = valid_timestamp();
+    assert!(root
+        .certificate()
+        .check(timestamp, &backend, |_| None::<RootCertificate>)
+        .unwrap());
+    assert!(aa
+        .certificate()
+        .check(timestamp, &backend, |h| {
+            (h == root.hashed_id8()).then(|| root.certificate().clone())
+        })
+        .unwrap());
+    assert!(at
+        .certificate()
+        .check(timestamp, &backend, |h| {
+            (h == aa.hashed_id8()).then(|| aa.certificate().clone())
+        })
+        .unwrap());
+
+    // AT is not issued by the Root.
+    let res = at
+        .certificate()
+        .check(timestamp, &backend, |_| Some(root.certificate().clone()));
+    assert!(!matches!(res, Ok(true)));
+}
+
+#[test]
+fn test_sm2_secured_message() {
+    let (base_path, _temp_dir) = super::create_temp_veloce_dir();
+    let (_, mut backend) = super::setup_storage_and_crypto(base_path);
+
+    let chain = setup_chain(&mut backend, EcKeyType::Sm2);
+    let mut service = SecurityService::new(
+        chain,
+        SecurityBackend::Openssl(backend),
+        PrivacyStrategy::NoStrategy,
+    );
+
+    let permissions = Permission::CAM(CamSsp::new_v1().into());
+    let position = PotiPosition {
+        latitude: Some(Latitude::new::<degree>(48.2764384)),
+        longitude: Some(Longitude::new::<degree>(-3.5519532)),
+        altitude: None,
+    };
+
+    let res = service
+        .encap_packet(GN_CAM.to_vec(), permissions, valid_timestamp(), position)
+        .unwrap();
+
+    let msg = SecuredMessage::from_bytes(&res).unwrap();
+    assert!(matches!(msg.signature().unwrap(), EcdsaSignature::Sm2(_)));
+
+    service
+        .decap_packet(&res, valid_timestamp() + Duration::from_millis(50))
+        .unwrap();
+
+    // A tampered message is rejected.
+    let mut tampered = res.clone();
+    let len = tampered.len();
+    tampered[len - 1] ^= 0x01;
+    assert!(service
+        .decap_packet(&tampered, valid_timestamp() + Duration::from_millis(50))
+        .is_err());
+}
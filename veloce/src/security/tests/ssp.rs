@@ -0,0 +1,198 @@
+use veloce_asn1::defs::etsi_102941_v221::ieee1609_dot2_base_types::{
+    Psid, PsidSsp as Etsi102941PsidSsp,
+};
+
+use crate::security::{
+    permission::{Permission, PermissionSspContainer, AID},
+    ssp::{
+        cpm::CpmSsp,
+        gn6::Gn6Ssp,
+        gpc::GpcSsp,
+        ivim::{IvimPermission, IvimSsp},
+        mapem::{MapemPermission, MapemSsp},
+        spatem::{SpatemPermission, SpatemSsp},
+        srem::{SremPermission, SremSsp},
+        ssem::SsemSsp,
+        vam::VamSsp,
+        SspError, SspTrait,
+    },
+};
+
+#[test]
+fn test_mapem_ssp() {
+    let mut ssp = MapemSsp::new();
+    assert!(!ssp.has_permission(MapemPermission::IntersectionGeometry));
+    assert!(!ssp.has_permission(MapemPermission::RoadSegment));
+
+    ssp.set_permission(MapemPermission::IntersectionGeometry);
+    assert!(ssp.has_permission(MapemPermission::IntersectionGeometry));
+    assert!(!ssp.has_permission(MapemPermission::RoadSegment));
+    assert_eq!(ssp.emit(), [0x01, 0x80]);
+
+    ssp.set_permission(MapemPermission::RoadSegment);
+    assert_eq!(ssp.emit(), [0x01, 0xc0]);
+    assert_eq!(MapemSsp::parse(&ssp.emit()), Ok(ssp));
+
+    let mut other = ssp;
+    other.clear_permission(MapemPermission::IntersectionGeometry);
+    assert!(!other.has_permission(MapemPermission::IntersectionGeometry));
+    assert!(ssp.contains_permissions_of(&other));
+    assert!(!other.contains_permissions_of(&ssp));
+
+    assert_eq!(MapemSsp::parse(&[0x01]), Err(SspError::Length));
+    assert_eq!(MapemSsp::parse(&[0x02, 0xc0]), Err(SspError::Version));
+}
+
+#[test]
+fn test_spatem_ssp() {
+    let mut ssp = SpatemSsp::new();
+    ssp.set_permission(SpatemPermission::EnabledLanes);
+    assert!(ssp.has_permission(SpatemPermission::EnabledLanes));
+    assert!(!ssp.has_permission(SpatemPermission::ManeuverAssist));
+    assert_eq!(ssp.emit(), [0x01, 0x40]);
+    assert_eq!(SpatemSsp::parse(&ssp.emit()), Ok(ssp));
+
+    let full = SpatemSsp::from_raw_permissions(0xc0);
+    assert!(full.has_permission(SpatemPermission::ManeuverAssist));
+    assert!(full.contains_permissions_of(&ssp));
+    assert!(!ssp.contains_permissions_of(&full));
+
+    let mut cleared = full;
+    cleared.clear_permission(SpatemPermission::ManeuverAssist);
+    assert_eq!(cleared, ssp);
+
+    assert_eq!(SpatemSsp::parse(&[]), Err(SspError::Length));
+    assert_eq!(SpatemSsp::parse(&[0x00, 0x40]), Err(SspError::Version));
+}
+
+#[test]
+fn test_ivim_ssp() {
+    let permissions = [
+        (IvimPermission::ImmediateDangerWarning, 0x80),
+        (IvimPermission::Regulatory, 0x40),
+        (IvimPermission::TrafficRelatedInformation, 0x20),
+        (IvimPermission::Pollution, 0x10),
+        (IvimPermission::NotTrafficRelatedInformation, 0x08),
+    ];
+
+    let mut all = IvimSsp::new();
+    for (permission, bit) in permissions {
+        let mut ssp = IvimSsp::new();
+        ssp.set_permission(permission);
+        assert!(ssp.has_permission(permission));
+        assert_eq!(ssp.emit(), [0x01, bit]);
+        assert_eq!(IvimSsp::parse(&ssp.emit()), Ok(ssp));
+
+        all.set_permission(permission);
+        assert!(all.contains_permissions_of(&ssp));
+        assert!(!ssp.contains_permissions_of(&IvimSsp::from_raw_permissions(0xf8)));
+    }
+
+    assert_eq!(all, IvimSsp::from_raw_permissions(0xf8));
+
+    all.clear_permission(IvimPermission::Pollution);
+    assert!(!all.has_permission(IvimPermission::Pollution));
+    assert_eq!(all.emit(), [0x01, 0xe8]);
+
+    assert_eq!(IvimSsp::parse(&[0x01]), Err(SspError::Length));
+    assert_eq!(IvimSsp::parse(&[0x02, 0x80]), Err(SspError::Version));
+}
+
+#[test]
+fn test_srem_ssp() {
+    let permissions = [
+        (SremPermission::PublicTransport, 0x80),
+        (SremPermission::SpecialTransport, 0x40),
+        (SremPermission::DangerousGoods, 0x20),
+        (SremPermission::RoadWork, 0x10),
+        (SremPermission::RoadRescue, 0x08),
+        (SremPermission::Emergency, 0x04),
+        (SremPermission::SafetyCar, 0x02),
+    ];
+
+    let mut all = SremSsp::new();
+    for (permission, bit) in permissions {
+        let mut ssp = SremSsp::new();
+        ssp.set_permission(permission);
+        assert!(ssp.has_permission(permission));
+        assert_eq!(ssp.emit(), [0x01, bit]);
+        assert_eq!(SremSsp::parse(&ssp.emit()), Ok(ssp));
+
+        all.set_permission(permission);
+        assert!(all.contains_permissions_of(&ssp));
+    }
+
+    assert_eq!(all, SremSsp::from_raw_permissions(0xfe));
+
+    let mut emergency = SremSsp::new();
+    emergency.set_permission(SremPermission::Emergency);
+    emergency.set_permission(SremPermission::SafetyCar);
+    emergency.clear_permission(SremPermission::SafetyCar);
+    assert!(!emergency.has_permission(SremPermission::SafetyCar));
+    assert!(!emergency.contains_permissions_of(&all));
+
+    assert_eq!(SremSsp::parse(&[0x01]), Err(SspError::Length));
+    assert_eq!(SremSsp::parse(&[0x00, 0x04]), Err(SspError::Version));
+}
+
+#[test]
+fn test_version_ssp() {
+    assert_eq!(CpmSsp::buf_size(), 1);
+    assert_eq!(CpmSsp::new().emit(), [0x01]);
+    assert_eq!(CpmSsp::parse(&[0x01]), Ok(CpmSsp::new()));
+    // Trailing bytes are ignored.
+    assert_eq!(VamSsp::parse(&[0x01, 0xff]), Ok(VamSsp::new()));
+
+    assert_eq!(SsemSsp::parse(&[]), Err(SspError::Length));
+    assert_eq!(GpcSsp::parse(&[0x00]), Err(SspError::Version));
+    assert_eq!(Gn6Ssp::parse(&[0x02]), Err(SspError::Version));
+
+    assert!(CpmSsp::new().contains_permissions_of(&CpmSsp::default()));
+    assert!(VamSsp::new().has_permission(()));
+}
+
+#[test]
+fn test_permission_round_trip() {
+    let permissions = [
+        Permission::MAPEM(MapemSsp::from_raw_permissions(0xc0).into()),
+        Permission::SPATEM(SpatemSsp::from_raw_permissions(0x40).into()),
+        Permission::IVIM(IvimSsp::from_raw_permissions(0xa8).into()),
+        Permission::SREM(SremSsp::from_raw_permissions(0x06).into()),
+        Permission::SSEM(SsemSsp::new().into()),
+        Permission::CPM(CpmSsp::new().into()),
+        Permission::VAM(VamSsp::new().into()),
+        Permission::GPC(GpcSsp::new().into()),
+        Permission::GN6(Gn6Ssp::new().into()),
+        Permission::GnMgmt,
+        Permission::Unknown {
+            aid: 1234,
+            ssp: Some(vec![0x01, 0x02]),
+            mask: None,
+        },
+    ];
+
+    for permission in permissions {
+        let psid_ssp: Etsi102941PsidSsp = permission.clone().into();
+        let decoded = Permission::try_from(&psid_ssp).unwrap();
+
+        assert_eq!(decoded, permission);
+        assert_eq!(decoded.aid(), permission.aid());
+        assert!(decoded.contains_permissions_of(&permission));
+    }
+
+    // Version-only SSPs may be omitted.
+    let psid_ssp = Etsi102941PsidSsp::new(Psid(AID::CP.into()), None);
+    assert_eq!(
+        Permission::try_from(&psid_ssp),
+        Ok(Permission::CPM(PermissionSspContainer::from(CpmSsp::new())))
+    );
+
+    // Containment is only checked against the same kind of permission.
+    let mapem = Permission::MAPEM(MapemSsp::from_raw_permissions(0xc0).into());
+    let road_segment = Permission::MAPEM(MapemSsp::from_raw_permissions(0x40).into());
+    assert!(mapem.contains_permissions_of(&road_segment));
+    assert!(!road_segment.contains_permissions_of(&mapem));
+    assert!(!mapem.contains_permissions_of(&Permission::SPATEM(
+        SpatemSsp::from_raw_permissions(0xc0).into()
+    )));
+}
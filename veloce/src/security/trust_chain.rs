@@ -8,6 +8,8 @@ use std::collections::BTreeMap;
 
 use core::{fmt, hash::Hash};
 
+use crate::time::Duration;
+
 use super::{
     certificate::{
         AuthorizationAuthorityCertificate, AuthorizationTicketCertificate,
@@ -19,6 +21,16 @@ use super::{
 
 type Container<C> = CertificateWithHashContainer<C>;
 
+/// Usage of an AT certificate during a UTC day.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ATDailyUsage {
+    /// UTC day of the usage, as a number of days since the Unix epoch.
+    pub day: i64,
+    /// Duration the AT certificate has been used to sign messages during the day.
+    pub usage: Duration,
+}
+
 /// Container for the AT certificate.
 #[derive(Debug)]
 pub struct ATContainer {
@@ -26,13 +38,19 @@ pub struct ATContainer {
     at_cert: Container<AuthorizationTicketCertificate>,
     /// Number of times the AT has been elected to sign messages.
     elected: usize,
+    /// Usage of the AT during the last UTC day it has been used.
+    daily_usage: ATDailyUsage,
 }
 
 impl ATContainer {
     /// Create a new [ATContainer] from an [AuthorizationTicketCertificate] and a number of times
     /// it has been elected to sign messages.
     pub fn new(at_cert: Container<AuthorizationTicketCertificate>, elected: usize) -> Self {
-        Self { at_cert, elected }
+        Self {
+            at_cert,
+            elected,
+            daily_usage: ATDailyUsage::default(),
+        }
     }
 
     /// Return a reference on the [AuthorizationTicketCertificate].
@@ -49,6 +67,33 @@ impl ATContainer {
     pub fn notify_elected(&mut self) {
         self.elected += 1;
     }
+
+    /// Return the usage of this AT during the last UTC day it has been used.
+    pub fn daily_usage(&self) -> ATDailyUsage {
+        self.daily_usage
+    }
+
+    /// Set the usage of this AT during the last UTC day it has been used.
+    pub fn set_daily_usage(&mut self, daily_usage: ATDailyUsage) {
+        self.daily_usage = daily_usage;
+    }
+
+    /// Return the duration this AT has been used during the UTC `day`.
+    pub fn usage_on(&self, day: i64) -> Duration {
+        if self.daily_usage.day == day {
+            self.daily_usage.usage
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// Account `usage` to this AT during the UTC `day`.
+    pub fn add_usage(&mut self, day: i64, usage: Duration) {
+        self.daily_usage = ATDailyUsage {
+            day,
+            usage: self.usage_on(day) + usage,
+        };
+    }
 }
 
 /// Error returned when trying to set an index that does not exist.
@@ -121,11 +166,21 @@ impl TrustChain {
         self.at_certs.get(&index)
     }
 
+    /// Get the index of the current Authorization Ticket certificate, if any.
+    pub fn at_cert_index(&self) -> Option<usize> {
+        self.current_at_id
+    }
+
     /// Get a reference on all the Authorization Ticket certificates.
     pub fn at_certs(&self) -> &BTreeMap<usize, ATContainer> {
         &self.at_certs
     }
 
+    /// Get a mutable reference on the Authorization Ticket certificate at `index`, if any.
+    pub fn at_cert_mut(&mut self, index: usize) -> Option<&mut ATContainer> {
+        self.at_certs.get_mut(&index)
+    }
+
     /// Set the Enrollment Authority Certificate.
     pub fn set_ea_cert(&mut self, ea_cert: Container<EnrollmentAuthorityCertificate>) {
         self.ea_cert = Some(ea_cert);
@@ -179,4 +234,9 @@ impl TrustChain {
     pub fn is_revoked(&self, hash: HashedId8) -> bool {
         self.revoked_certs.contains(&hash)
     }
+
+    /// Get a reference to the revoked certificates list.
+    pub fn revoked_certs(&self) -> &[HashedId8] {
+        &self.revoked_certs
+    }
 }
@@ -1,5 +1,5 @@
 //! The trust store contains the local chain of trust certificates as well as "remote" trust chains,
-//! ie: other PKIs the local station trusts.
+//! ie: other PKIs the local station trusts, and the EA and AA certificates learned over the air.
 
 #[cfg(not(feature = "std"))]
 use alloc::collections::btree_map::BTreeMap;
@@ -9,15 +9,18 @@ use std::collections::BTreeMap;
 
 use super::{
     certificate::{
-        AuthorizationAuthorityCertificate, CertificateWithHashContainer, RootCertificate,
+        AuthorizationAuthorityCertificate, CertificateWithHashContainer,
+        EnrollmentAuthorityCertificate, RootCertificate,
     },
     trust_chain::TrustChain,
     HashedId8,
 };
 
+type Container<C> = CertificateWithHashContainer<C>;
+
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-/// Error returned by [`Store::set_remote_aa`].
+/// Error returned by [`Store::set_remote_aa`] and [`Store::set_remote_ea`].
 pub struct InexistentChainError;
 
 #[derive(Debug)]
@@ -28,6 +31,18 @@ pub struct Store {
     /// Other trusted certificate chains, containing other trusted PKI certs.
     /// Map key is the Root Certificate [HashedId8].
     remote_chains: BTreeMap<HashedId8, TrustChain>,
+    /// Remote EA certificates, ie: not belonging to the own chain.
+    /// Map key is the EA Certificate [HashedId8].
+    remote_ea: BTreeMap<HashedId8, Container<EnrollmentAuthorityCertificate>>,
+    /// Remote AA certificates, ie: not belonging to the own chain.
+    /// Map key is the AA Certificate [HashedId8].
+    remote_aa: BTreeMap<HashedId8, Container<AuthorizationAuthorityCertificate>>,
+    /// Root certificates listed in the currently trusted ECTL.
+    /// Only these can be added as the root of trust of a remote chain.
+    trusted_roots: Vec<HashedId8>,
+    /// Revoked certificates which do not belong to a trust chain revocation list,
+    /// ie: restored from a previous run.
+    revoked_certs: Vec<HashedId8>,
 }
 
 impl Store {
@@ -36,6 +51,10 @@ impl Store {
         Self {
             own_chain,
             remote_chains: BTreeMap::new(),
+            remote_ea: BTreeMap::new(),
+            remote_aa: BTreeMap::new(),
+            trusted_roots: Vec::new(),
+            revoked_certs: Vec::new(),
         }
     }
 
@@ -52,7 +71,7 @@ impl Store {
     /// Query whether the certificate identifier `hash` is in the revoked
     /// certificates list of any trust chain.
     pub fn is_revoked(&self, hash: HashedId8) -> bool {
-        if self.own_chain.is_revoked(hash) {
+        if self.own_chain.is_revoked(hash) || self.revoked_certs.contains(&hash) {
             return true;
         }
 
@@ -61,6 +80,46 @@ impl Store {
             .any(|(_, chain)| chain.is_revoked(hash))
     }
 
+    /// Add a certificate [HashedId8] to the revoked certificates list.
+    pub fn add_revoked_cert(&mut self, hash: HashedId8) {
+        if !self.revoked_certs.contains(&hash) {
+            self.revoked_certs.push(hash);
+        }
+    }
+
+    /// Get the identifiers of all the revoked certificates, from the revocation lists of the
+    /// trust chains and from the store own revoked certificates list, sorted and deduplicated.
+    pub fn revoked_certs(&self) -> Vec<HashedId8> {
+        let mut revoked: Vec<HashedId8> = self
+            .own_chain
+            .revoked_certs()
+            .iter()
+            .chain(self.revoked_certs.iter())
+            .chain(
+                self.remote_chains
+                    .values()
+                    .flat_map(|chain| chain.revoked_certs().iter()),
+            )
+            .copied()
+            .collect();
+
+        revoked.sort_unstable();
+        revoked.dedup();
+        revoked
+    }
+
+    /// Set the root certificates listed in the currently trusted ECTL, identified with their
+    /// [HashedId8]. Replaces any previously set list.
+    pub fn set_trusted_roots(&mut self, roots: Vec<HashedId8>) {
+        self.trusted_roots = roots;
+    }
+
+    /// Query whether the root certificate identified with `hash` is trusted, ie: it is the own
+    /// chain root certificate or it is listed in the currently trusted ECTL.
+    pub fn is_trusted_root(&self, hash: HashedId8) -> bool {
+        self.own_chain.root_cert().hashed_id8() == hash || self.trusted_roots.contains(&hash)
+    }
+
     /// Lookup into the own and the remote chains for a [RootCertificate]
     /// identified with `hash`.
     pub fn lookup_root(&self, hash: HashedId8) -> Option<RootCertificate> {
@@ -83,27 +142,121 @@ impl Store {
             _ => {}
         }
 
-        self.remote_chains
+        let remote = self
+            .remote_chains
             .iter()
             .find_map(|(_, chain)| match chain.aa_cert() {
                 Some(aa) if aa.hashed_id8() == hash => Some(aa.certificate().clone()),
                 _ => None,
             });
 
-        None
+        remote.or_else(|| self.remote_aa.get(&hash).map(|e| e.certificate().clone()))
     }
 
-    /// Set the [AuthorizationAuthorityCertificate] for the remote chain identified with `hash`.
+    /// Lookup into the own and the remote chains for an [EnrollmentAuthorityCertificate]
+    /// identified with `hash`.
+    pub fn lookup_ea(&self, hash: HashedId8) -> Option<EnrollmentAuthorityCertificate> {
+        match self.own_chain.ea_cert() {
+            Some(own_ea) if own_ea.hashed_id8() == hash => {
+                return Some(own_ea.certificate().clone())
+            }
+            _ => {}
+        }
+
+        let remote = self
+            .remote_chains
+            .iter()
+            .find_map(|(_, chain)| match chain.ea_cert() {
+                Some(ea) if ea.hashed_id8() == hash => Some(ea.certificate().clone()),
+                _ => None,
+            });
+
+        remote.or_else(|| self.remote_ea.get(&hash).map(|e| e.certificate().clone()))
+    }
+
+    /// Query whether the root certificate identified with `hash` is known, ie: it is the own
+    /// chain root certificate or the root certificate of a remote chain.
+    pub fn is_known_root(&self, hash: HashedId8) -> bool {
+        self.own_chain.root_cert().hashed_id8() == hash || self.remote_chains.contains_key(&hash)
+    }
+
+    /// Add a remote chain with `cert` as root of trust. Returns `false` if the chain
+    /// already exists or if `cert` is not a trusted root, see [Store::is_trusted_root].
+    pub fn add_remote_root(&mut self, cert: Container<RootCertificate>) -> bool {
+        let hash = cert.hashed_id8();
+        if self.is_known_root(hash) || !self.is_trusted_root(hash) {
+            return false;
+        }
+
+        self.remote_chains.insert(hash, TrustChain::new(cert));
+        true
+    }
+
+    /// Remove the remote chain whose root certificate is identified with `hash`.
+    pub fn remove_remote_root(&mut self, hash: HashedId8) -> Option<Container<RootCertificate>> {
+        self.remote_chains
+            .remove(&hash)
+            .map(|chain| chain.root_cert().clone())
+    }
+
+    /// Set a remote [EnrollmentAuthorityCertificate], issued by the root certificate identified with `hash`.
+    pub fn set_remote_ea(
+        &mut self,
+        hash: HashedId8,
+        cert: Container<EnrollmentAuthorityCertificate>,
+    ) -> Result<(), InexistentChainError> {
+        if !self.is_known_root(hash) {
+            return Err(InexistentChainError);
+        }
+
+        self.remote_ea.insert(cert.hashed_id8(), cert);
+        Ok(())
+    }
+
+    /// Remove the remote [EnrollmentAuthorityCertificate] identified with `hash`.
+    pub fn remove_remote_ea(
+        &mut self,
+        hash: HashedId8,
+    ) -> Option<Container<EnrollmentAuthorityCertificate>> {
+        self.remote_ea.remove(&hash)
+    }
+
+    /// Set a remote [AuthorizationAuthorityCertificate], issued by the root certificate identified with `hash`.
     pub fn set_remote_aa(
         &mut self,
         hash: HashedId8,
-        cert: CertificateWithHashContainer<AuthorizationAuthorityCertificate>,
+        cert: Container<AuthorizationAuthorityCertificate>,
     ) -> Result<(), InexistentChainError> {
-        self.remote_chains
-            .get_mut(&hash)
-            .ok_or(InexistentChainError)?
-            .set_aa_cert(cert);
+        if !self.is_known_root(hash) {
+            return Err(InexistentChainError);
+        }
 
+        self.remote_aa.insert(cert.hashed_id8(), cert);
         Ok(())
     }
+
+    /// Remove the remote [AuthorizationAuthorityCertificate] identified with `hash`.
+    pub fn remove_remote_aa(
+        &mut self,
+        hash: HashedId8,
+    ) -> Option<Container<AuthorizationAuthorityCertificate>> {
+        self.remote_aa.remove(&hash)
+    }
+
+    /// Get an iterator over the remote root certificates.
+    pub fn remote_roots(&self) -> impl Iterator<Item = &Container<RootCertificate>> {
+        self.remote_chains.values().map(|chain| chain.root_cert())
+    }
+
+    /// Get an iterator over the remote EA certificates.
+    pub fn remote_eas(&self) -> impl Iterator<Item = &Container<EnrollmentAuthorityCertificate>> {
+        self.remote_ea.values()
+    }
+
+    /// Get an iterator over the remote AA certificates.
+    pub fn remote_aas(
+        &self,
+    ) -> impl Iterator<Item = &Container<AuthorizationAuthorityCertificate>> {
+        self.remote_aa.values()
+    }
 }
@@ -40,6 +40,12 @@ pub struct Request {
     #[cfg(feature = "proto-security")]
     /// ITS Application Identifier.
     pub its_aid: Permission,
+    #[cfg(feature = "proto-security")]
+    /// Encrypt the packet for its destination. Only applies to unicast
+    /// transport, and requires the destination certificate to be known.
+    /// Forwarders cannot decrypt the packet, so it is refused when the
+    /// destination is not a neighbour.
+    pub confidential: bool,
     /// Maximum lifetime of the packet.
     pub max_lifetime: Duration,
     /// Maximum hop limit of the packet.
@@ -55,6 +61,8 @@ impl Default for Request {
             ali_id: Default::default(),
             #[cfg(feature = "proto-security")]
             its_aid: Default::default(),
+            #[cfg(feature = "proto-security")]
+            confidential: false,
             max_lifetime: config::GN_DEFAULT_PACKET_LIFETIME,
             max_hop_limit: config::GN_DEFAULT_HOP_LIMIT,
             traffic_class: config::GN_DEFAULT_TRAFFIC_CLASS,
@@ -82,6 +90,9 @@ pub struct Indication {
     #[cfg(feature = "proto-security")]
     /// Certificate ID.
     pub cert_id: HashedId8,
+    #[cfg(feature = "proto-security")]
+    /// Packet was received encrypted.
+    pub confidential: bool,
     /// Remaining lifetime of the packet.
     pub rem_lifetime: Duration,
     /// Remaining hop limit of the packet.
@@ -510,6 +510,7 @@ impl<'a> Socket<'a> {
                 packet_meta.endpoint.addr,
                 (),
                 req.its_aid.clone(),
+                req.confidential,
                 req.max_lifetime,
                 req.max_hop_limit,
                 req.traffic_class,
@@ -6,16 +6,21 @@ use crate::iface::packet::GeonetPacket;
 use crate::iface::{Congestion, Context, ContextMeta};
 use crate::network::{GnCore, Transport};
 
+#[cfg(feature = "proto-security")]
+use crate::common::geo_area::GeoPosition;
 #[cfg(feature = "proto-security")]
 use crate::security::{
+    misbehavior::MisbehaviorVerdict,
     permission::{Permission, AID},
     ssp::{
         cam::{CamPermission, CamSsp},
         SspTrait,
     },
 };
-use crate::socket::{self, btp::SocketB as BtpBSocket, PollAt};
+use crate::socket::{self, btp::SocketB as BtpBSocket, MessageStats, PollAt};
 use crate::time::{Duration, Instant, TAI2004};
+#[cfg(feature = "proto-security")]
+use crate::types::{tenth_of_microdegree, Latitude, Longitude};
 use crate::types::{Heading, Pseudonym, Speed};
 use crate::wire::{self, ports, GnTrafficClass};
 
@@ -91,9 +96,56 @@ impl fmt::Display for TxPeriodOverride {
     }
 }
 
+/// Vehicle state included in the transmitted CAMs, ie: the vehicle data
+/// not provided by the positioning system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleState {
+    /// Drive direction of the vehicle.
+    pub drive_direction: cdd::DriveDirection,
+    /// Curvature of the vehicle trajectory, if available.
+    pub curvature: Option<cdd::CurvatureValue>,
+    /// Yaw rate of the vehicle, if available.
+    pub yaw_rate: Option<cdd::YawRateValue>,
+    /// Status of the vehicle controls, if available.
+    pub acceleration_control: Option<cdd::AccelerationControl>,
+    /// Status of the exterior lights.
+    pub exterior_lights: cdd::ExteriorLights,
+    /// Role of the vehicle. Determines the special vehicle container content.
+    pub vehicle_role: cdd::VehicleRole,
+    /// Whether the passenger embarkation is ongoing, for a public transport vehicle.
+    pub embarkation_status: bool,
+    /// Public transport activation data, for a public transport vehicle.
+    pub pt_activation: Option<cdd::PtActivation>,
+    /// Type of the dangerous goods carried, for a dangerous goods vehicle.
+    pub dangerous_goods: Option<cdd::DangerousGoodsBasic>,
+    /// Status of the light bar and siren, for a special vehicle.
+    pub light_bar_siren: cdd::LightBarSirenInUse,
+}
+
+impl Default for VehicleState {
+    fn default() -> Self {
+        VehicleState {
+            drive_direction: cdd::DriveDirection::unavailable,
+            curvature: None,
+            yaw_rate: None,
+            acceleration_control: None,
+            exterior_lights: cdd::ExteriorLights(Default::default()),
+            vehicle_role: cdd::VehicleRole::default,
+            embarkation_status: false,
+            pt_activation: None,
+            dangerous_goods: None,
+            light_bar_siren: cdd::LightBarSirenInUse(Default::default()),
+        }
+    }
+}
+
 /// Rx/Tx callback type.
 type RxTxCallback = Box<dyn FnMut(&[u8], &cam::CAM)>;
 
+#[cfg(feature = "proto-security")]
+/// Implausible CAM reception callback type.
+type ImplausibleCallback = Box<dyn FnMut(&[u8], &cam::CAM, &MisbehaviorVerdict)>;
+
 /// An ETSI CAM type socket.
 ///
 /// A CAM socket executes the Cooperative Awareness protocol,
@@ -124,12 +176,19 @@ pub struct Socket<'a> {
     n_gen_cam: u8,
     /// CAM transmission period override parameters.
     generation_override: Option<TxPeriodOverride>,
+    /// Vehicle state included in the transmitted CAMs.
+    vehicle_state: VehicleState,
+    /// CAM messages counters.
+    stats: MessageStats,
     /// Function to call when a CAM message is successfully received.
     rx_callback: Option<RxTxCallback>,
     /// Function to call when a CAM message is successfully transmitted to the lower layer.
     /// Keep in mind some mechanisms, like congestion control, may silently drop the message
     /// at a lower layer before any transmission occur.
     tx_callback: Option<RxTxCallback>,
+    #[cfg(feature = "proto-security")]
+    /// Function to call when a received CAM is flagged as implausible by the misbehavior detection.
+    implausible_callback: Option<ImplausibleCallback>,
 }
 
 impl fmt::Debug for Socket<'_> {
@@ -173,8 +232,12 @@ impl<'a> Socket<'a> {
             prev_pos: PotiPathPoint::default(),
             n_gen_cam: 0,
             generation_override: None,
+            vehicle_state: VehicleState::default(),
+            stats: MessageStats::default(),
             rx_callback: None,
             tx_callback: None,
+            #[cfg(feature = "proto-security")]
+            implausible_callback: None,
         }
     }
 
@@ -194,6 +257,19 @@ impl<'a> Socket<'a> {
         self.tx_callback = Some(Box::new(tx_cb));
     }
 
+    #[cfg(feature = "proto-security")]
+    /// Register a callback for an implausible CAM reception event, ie: a CAM flagged by the
+    /// misbehavior detection. Flagged CAMs are also delivered to the reception callback.
+    /// First callback parameter contains the CAM message serialized as UPER.
+    /// Second callback parameter contains the raw CAM message struct.
+    /// Third callback parameter contains the misbehavior detection verdict.
+    pub fn register_implausible_callback(
+        &mut self,
+        cb: impl FnMut(&[u8], &cam::CAM, &MisbehaviorVerdict) + 'static,
+    ) {
+        self.implausible_callback = Some(Box::new(cb));
+    }
+
     /// Override the transmission `period` of CAM messages for a number of generations `num_tx`.
     /// Enables ITS applications to increase the CAM generation frequency for a limited time.
     /// This value takes precedence over motion triggering, but not over the DCC rate limiting.
@@ -228,6 +304,28 @@ impl<'a> Socket<'a> {
         Ok(())
     }
 
+    /// Return the vehicle state included in the transmitted CAMs.
+    pub fn vehicle_state(&self) -> &VehicleState {
+        &self.vehicle_state
+    }
+
+    /// Get the CAM messages counters.
+    pub fn stats(&self) -> MessageStats {
+        self.stats
+    }
+
+    /// Set the vehicle state included in the transmitted CAMs.
+    /// The new state is included starting from the next transmitted CAM.
+    pub fn set_vehicle_state(&mut self, state: VehicleState) {
+        self.vehicle_state = state;
+    }
+
+    /// Notify the socket the local station pseudonym has changed.
+    /// Next CAM includes the low frequency container, as for the first CAM of a station.
+    pub(crate) fn notify_pseudonym_change(&mut self) {
+        self.prev_low_dynamic_at = Instant::ZERO;
+    }
+
     /// Query whether the CAM socket accepts the segment.
     #[must_use]
     pub(crate) fn accepts(
@@ -244,7 +342,7 @@ impl<'a> Socket<'a> {
     pub(crate) fn process(
         &mut self,
         cx: &mut Context,
-        srv: &ContextMeta,
+        srv: &mut ContextMeta,
         indication: Indication,
         payload: &[u8],
     ) {
@@ -258,6 +356,7 @@ impl<'a> Socket<'a> {
             Ok(d) => d,
             Err(e) => {
                 net_warn!("Cannot process CAM: {}", e);
+                self.stats.rx_discarded += 1;
                 return;
             }
         };
@@ -266,6 +365,7 @@ impl<'a> Socket<'a> {
             Ok(d) => d,
             Err(e) => {
                 net_warn!("Cannot process CAM: {}", e);
+                self.stats.rx_discarded += 1;
                 return;
             }
         };
@@ -279,15 +379,36 @@ impl<'a> Socket<'a> {
                     "Cannot process CAM - unexpected permission type. Got {:?}",
                     _ind.its_aid
                 );
+                self.stats.rx_discarded += 1;
                 return;
             };
 
             if !authorized {
                 net_warn!("Cannot process CAM - not authorized");
+                self.stats.rx_discarded += 1;
                 return;
             }
         }
 
+        #[cfg(feature = "proto-security")]
+        {
+            let (position, speed, heading) = Self::mobility(&decoded);
+            if let Some(verdict) = srv.check_misbehavior(position, speed, heading) {
+                if verdict.should_drop() {
+                    net_debug!("Cannot process CAM - implausible: {:?}", verdict.detections);
+                    self.stats.rx_discarded += 1;
+                    return;
+                }
+
+                if verdict.is_flagged() {
+                    if let Some(cb) = &mut self.implausible_callback {
+                        cb(buf, &decoded, &verdict);
+                    }
+                }
+            }
+        }
+
+        self.stats.rx += 1;
         if let Some(rx_cb) = &mut self.rx_callback {
             rx_cb(buf, &decoded);
         };
@@ -324,7 +445,7 @@ impl<'a> Socket<'a> {
 
         let ego_station_type = srv.core.station_type();
         let (ego_position, ego_position_history) = srv.core.position_and_history();
-        let now_tai2004 = TAI2004::from_unix_instant(now);
+        let now_tai2004 = srv.core.tai2004();
         let diff_now_pos = now_tai2004 - ego_position.timestamp;
 
         if let PotiMode::NoFix | PotiMode::Lost = ego_position.mode {
@@ -359,16 +480,16 @@ impl<'a> Socket<'a> {
         }
 
         let elapsed = now - self.prev_cam_at;
+        // T_GenCam_DCC, provided by DCC_FAC.
         let gen_cam_dcc = srv
             .congestion_control
-            .controller
-            .inner()
-            .tx_interval()
+            .gen_interval(Self::traffic_class().access_category())
             .clamp(CAM_GEN_CAM_MIN, CAM_GEN_CAM_MAX);
 
         // Rate limited by DCC.
         if elapsed < gen_cam_dcc {
             net_debug!("CAM cannot be sent: DCC rate limited");
+            self.retransmit_at = self.prev_cam_at + gen_cam_dcc;
             return Ok(());
         }
 
@@ -504,6 +625,7 @@ impl<'a> Socket<'a> {
                 }
             }
 
+            self.stats.tx += 1;
             if let Some(tx_cb) = &mut self.tx_callback {
                 tx_cb(&raw_cam, &cam);
             };
@@ -557,7 +679,6 @@ impl<'a> Socket<'a> {
     ) -> cam::CAM {
         use cam::*;
         use cdd::*;
-        use rasn::types::FixedBitString;
         use wire::geonet::StationType as VeloceStationType;
 
         let header = ItsPduHeader::new(OrdinalNumber1B(2), MessageId(2), StationId(pseudo.0));
@@ -595,7 +716,7 @@ impl<'a> Socket<'a> {
                         speed_value: fix.motion.speed_value(),
                         speed_confidence: fix.confidence.speed_confidence(),
                     },
-                    drive_direction: DriveDirection::unavailable,
+                    drive_direction: self.vehicle_state.drive_direction,
                     vehicle_length: VehicleLength {
                         vehicle_length_value: VehicleLengthValue(1023),
                         vehicle_length_confidence_indication:
@@ -607,15 +728,23 @@ impl<'a> Socket<'a> {
                         confidence: AccelerationConfidence(102),
                     },
                     curvature: Curvature {
-                        curvature_value: CurvatureValue(1023),
+                        curvature_value: self
+                            .vehicle_state
+                            .curvature
+                            .clone()
+                            .unwrap_or(CurvatureValue(1023)),
                         curvature_confidence: CurvatureConfidence::unavailable,
                     },
                     curvature_calculation_mode: CurvatureCalculationMode::unavailable,
                     yaw_rate: YawRate {
-                        yaw_rate_value: YawRateValue(32767),
+                        yaw_rate_value: self
+                            .vehicle_state
+                            .yaw_rate
+                            .clone()
+                            .unwrap_or(YawRateValue(32767)),
                         yaw_rate_confidence: YawRateConfidence::unavailable,
                     },
-                    acceleration_control: None,
+                    acceleration_control: self.vehicle_state.acceleration_control.clone(),
                     lane_position: None,
                     steering_wheel_angle: None,
                     lateral_acceleration: None,
@@ -634,7 +763,6 @@ impl<'a> Socket<'a> {
             _ if TAI2004::from_unix_instant(self.prev_low_dynamic_at) - timestamp
                 >= CAM_LF_RETRANSMIT_DELAY =>
             {
-                let ext_lights = FixedBitString::default();
                 let mut path_history = history
                     .as_etsi_path(&fix)
                     .unwrap_or_else(|_| cdd::Path(SequenceOf::new()));
@@ -644,8 +772,8 @@ impl<'a> Socket<'a> {
                 path_history.0.truncate(CAM_TRACE_MAX_POINTS);
 
                 let vehicle_lf = BasicVehicleContainerLowFrequency {
-                    vehicle_role: VehicleRole::default,
-                    exterior_lights: ExteriorLights(ext_lights),
+                    vehicle_role: self.vehicle_state.vehicle_role,
+                    exterior_lights: self.vehicle_state.exterior_lights.clone(),
                     path_history,
                 };
 
@@ -656,7 +784,17 @@ impl<'a> Socket<'a> {
             _ => None,
         };
 
-        let cam_params = CamParameters::new(basic_container, hf_container, lf_container, None);
+        // Special vehicle container, sent along with the low frequency container.
+        let special_container = lf_container
+            .as_ref()
+            .and_then(|_| self.special_vehicle_container());
+
+        let cam_params = CamParameters::new(
+            basic_container,
+            hf_container,
+            lf_container,
+            special_container,
+        );
 
         // Generation Delta Time is calculated differently for a RSU station.
         let gen_time = if let VeloceStationType::RoadSideUnit = station_type {
@@ -670,12 +808,75 @@ impl<'a> Socket<'a> {
         cam::CAM::new(header, coop_awareness)
     }
 
+    /// Build the special vehicle container matching the vehicle role.
+    /// Returns [None] if the role has no special vehicle container or if the
+    /// vehicle state lacks mandatory content.
+    fn special_vehicle_container(&self) -> Option<cam::SpecialVehicleContainer> {
+        use cam::*;
+        use cdd::*;
+
+        let state = &self.vehicle_state;
+        let lbs = state.light_bar_siren.clone();
+
+        let container = match state.vehicle_role {
+            VehicleRole::publicTransport => {
+                SpecialVehicleContainer::publicTransportContainer(PublicTransportContainer::new(
+                    EmbarkationStatus(state.embarkation_status),
+                    state.pt_activation.clone(),
+                ))
+            }
+            VehicleRole::dangerousGoods => SpecialVehicleContainer::dangerousGoodsContainer(
+                DangerousGoodsContainer::new(state.dangerous_goods?),
+            ),
+            VehicleRole::roadWork => SpecialVehicleContainer::roadWorksContainerBasic(
+                RoadWorksContainerBasic::new(None, lbs, None),
+            ),
+            VehicleRole::rescue => {
+                SpecialVehicleContainer::rescueContainer(RescueContainer::new(lbs))
+            }
+            VehicleRole::emergency => SpecialVehicleContainer::emergencyContainer(
+                EmergencyContainer::new(lbs, None, None),
+            ),
+            VehicleRole::safetyCar => SpecialVehicleContainer::safetyCarContainer(
+                SafetyCarContainer::new(lbs, None, None, None),
+            ),
+            _ => return None,
+        };
+
+        Some(container)
+    }
+
     /// Check if the CAM contains a low dynamic container.
     fn has_low_dynamic_container(&self, cam: &cam::CAM) -> bool {
         cam.cam.cam_parameters.low_frequency_container.is_some()
             || cam.cam.cam_parameters.special_vehicle_container.is_some()
     }
 
+    #[cfg(feature = "proto-security")]
+    /// Extract the position, speed and heading of the sender from `cam`,
+    /// for the misbehavior detection.
+    fn mobility(cam: &cam::CAM) -> (Option<GeoPosition>, Option<Speed>, Option<Heading>) {
+        let ref_pos = &cam.cam.cam_parameters.basic_container.reference_position;
+        let position = (ref_pos.latitude.0 != 900_000_001 && ref_pos.longitude.0 != 1_800_000_001)
+            .then(|| GeoPosition {
+                latitude: Latitude::new::<tenth_of_microdegree>(ref_pos.latitude.0.into()),
+                longitude: Longitude::new::<tenth_of_microdegree>(ref_pos.longitude.0.into()),
+            });
+
+        let cam::HighFrequencyContainer::basicVehicleContainerHighFrequency(hfc) =
+            &cam.cam.cam_parameters.high_frequency_container
+        else {
+            return (position, None, None);
+        };
+
+        let speed = (hfc.speed.speed_value.0 != 16383)
+            .then(|| Speed::new::<meter_per_second>(f64::from(hfc.speed.speed_value.0) / 100.0));
+        let heading = (hfc.heading.heading_value.0 != 3601)
+            .then(|| Heading::new::<degree>(f64::from(hfc.heading.heading_value.0) / 10.0));
+
+        (position, speed, heading)
+    }
+
     #[cfg(feature = "proto-security")]
     /// Check if the CAM content is authorized vs `permission`.
     fn check_permissions(cam: &cam::CAM, permission: &CamSsp) -> bool {
@@ -756,3 +957,203 @@ impl<'a> Socket<'a> {
         permission.contains_permissions_of(&expected)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use uom::si::length::centimeter;
+    use uom::si::velocity::centimeter_per_second;
+
+    use super::*;
+    use crate::common::{PotiConfidence, PotiMotion, PotiPosition, PotiPositionConfidence};
+    use crate::types::{decidegree, Latitude, Longitude};
+
+    fn station_pos_fix(timestamp: TAI2004) -> PotiFix {
+        PotiFix {
+            mode: PotiMode::Fix3d,
+            timestamp,
+            position: PotiPosition {
+                latitude: Some(Latitude::new::<degree>(48.2764384)),
+                longitude: Some(Longitude::new::<degree>(-3.5519532)),
+                altitude: Some(Length::new::<meter>(120.23)),
+            },
+            motion: PotiMotion {
+                speed: Some(Speed::new::<meter_per_second>(10.0)),
+                vertical_speed: None,
+                heading: Some(Heading::new::<degree>(140.0)),
+            },
+            confidence: PotiConfidence {
+                position: PotiPositionConfidence {
+                    semi_major: Some(Length::new::<centimeter>(123.0)),
+                    semi_minor: Some(Length::new::<centimeter>(123.0)),
+                    semi_major_orientation: Some(Heading::new::<decidegree>(10.0)),
+                },
+                altitude: Some(Length::new::<meter>(3.7)),
+                speed: Some(Speed::new::<centimeter_per_second>(5.0)),
+                heading: Some(Heading::new::<decidegree>(10.0)),
+            },
+        }
+    }
+
+    fn fill(socket: &Socket, station_type: StationType) -> cam::CamParameters {
+        let timestamp = TAI2004::from_unix_instant(Instant::from_secs(1_000));
+
+        socket
+            .fill_cam(
+                timestamp,
+                station_type,
+                station_pos_fix(timestamp),
+                PotiPositionHistory::default(),
+                Pseudonym(0xcafe),
+            )
+            .cam
+            .cam_parameters
+    }
+
+    fn bit_string<const N: usize>(bits: &[usize]) -> rasn::types::FixedBitString<N> {
+        let mut res = rasn::types::FixedBitString::<N>::default();
+        for bit in bits {
+            res.set(*bit, true);
+        }
+        res
+    }
+
+    #[test]
+    fn test_fill_cam_default_vehicle_state() {
+        let socket = Socket::new();
+        let params = fill(&socket, StationType::PassengerCar);
+
+        let cam::HighFrequencyContainer::basicVehicleContainerHighFrequency(hf) =
+            params.high_frequency_container
+        else {
+            panic!("Should be a basic vehicle high frequency container");
+        };
+        assert_eq!(hf.drive_direction, cdd::DriveDirection::unavailable);
+        assert_eq!(hf.curvature.curvature_value, cdd::CurvatureValue(1023));
+        assert_eq!(hf.yaw_rate.yaw_rate_value, cdd::YawRateValue(32767));
+        assert_eq!(hf.acceleration_control, None);
+
+        let Some(cam::LowFrequencyContainer::basicVehicleContainerLowFrequency(lf)) =
+            params.low_frequency_container
+        else {
+            panic!("Should be a basic vehicle low frequency container");
+        };
+        assert_eq!(lf.vehicle_role, cdd::VehicleRole::default);
+        assert_eq!(lf.exterior_lights, cdd::ExteriorLights(Default::default()));
+        assert_eq!(params.special_vehicle_container, None);
+    }
+
+    #[test]
+    fn test_fill_cam_vehicle_state() {
+        let mut socket = Socket::new();
+        let state = VehicleState {
+            drive_direction: cdd::DriveDirection::backward,
+            curvature: Some(cdd::CurvatureValue(-500)),
+            yaw_rate: Some(cdd::YawRateValue(500)),
+            acceleration_control: Some(cdd::AccelerationControl(bit_string(&[0, 2]))),
+            exterior_lights: cdd::ExteriorLights(bit_string(&[0, 7])),
+            vehicle_role: cdd::VehicleRole::emergency,
+            light_bar_siren: cdd::LightBarSirenInUse(bit_string(&[0, 1])),
+            ..Default::default()
+        };
+        socket.set_vehicle_state(state.clone());
+        assert_eq!(socket.vehicle_state(), &state);
+
+        let params = fill(&socket, StationType::PassengerCar);
+
+        let cam::HighFrequencyContainer::basicVehicleContainerHighFrequency(hf) =
+            params.high_frequency_container
+        else {
+            panic!("Should be a basic vehicle high frequency container");
+        };
+        assert_eq!(hf.drive_direction, state.drive_direction);
+        assert_eq!(Some(hf.curvature.curvature_value), state.curvature);
+        assert_eq!(Some(hf.yaw_rate.yaw_rate_value), state.yaw_rate);
+        assert_eq!(hf.acceleration_control, state.acceleration_control);
+
+        let Some(cam::LowFrequencyContainer::basicVehicleContainerLowFrequency(lf)) =
+            params.low_frequency_container
+        else {
+            panic!("Should be a basic vehicle low frequency container");
+        };
+        assert_eq!(lf.vehicle_role, state.vehicle_role);
+        assert_eq!(lf.exterior_lights, state.exterior_lights);
+        assert_eq!(
+            params.special_vehicle_container,
+            Some(cam::SpecialVehicleContainer::emergencyContainer(
+                cam::EmergencyContainer::new(state.light_bar_siren.clone(), None, None)
+            ))
+        );
+
+        // Road side units do not send the vehicle containers.
+        let params = fill(&socket, StationType::RoadSideUnit);
+        assert_eq!(params.low_frequency_container, None);
+        assert_eq!(params.special_vehicle_container, None);
+    }
+
+    #[test]
+    fn test_special_vehicle_container() {
+        let mut socket = Socket::new();
+        let lbs = cdd::LightBarSirenInUse(bit_string(&[1]));
+        let pt_activation = cdd::PtActivation::new(
+            cdd::PtActivationType(2),
+            cdd::PtActivationData(vec![0xca, 0xfe].into()),
+        );
+
+        let mut state = VehicleState {
+            light_bar_siren: lbs.clone(),
+            embarkation_status: true,
+            pt_activation: Some(pt_activation.clone()),
+            ..Default::default()
+        };
+
+        let expected = [
+            (cdd::VehicleRole::default, None),
+            (cdd::VehicleRole::specialTransport, None),
+            (
+                cdd::VehicleRole::publicTransport,
+                Some(cam::SpecialVehicleContainer::publicTransportContainer(
+                    cam::PublicTransportContainer::new(
+                        cdd::EmbarkationStatus(true),
+                        Some(pt_activation),
+                    ),
+                )),
+            ),
+            // Dangerous goods type is mandatory.
+            (cdd::VehicleRole::dangerousGoods, None),
+            (
+                cdd::VehicleRole::roadWork,
+                Some(cam::SpecialVehicleContainer::roadWorksContainerBasic(
+                    cam::RoadWorksContainerBasic::new(None, lbs.clone(), None),
+                )),
+            ),
+            (
+                cdd::VehicleRole::rescue,
+                Some(cam::SpecialVehicleContainer::rescueContainer(
+                    cam::RescueContainer::new(lbs.clone()),
+                )),
+            ),
+            (
+                cdd::VehicleRole::safetyCar,
+                Some(cam::SpecialVehicleContainer::safetyCarContainer(
+                    cam::SafetyCarContainer::new(lbs.clone(), None, None, None),
+                )),
+            ),
+        ];
+
+        for (role, container) in expected {
+            state.vehicle_role = role;
+            socket.set_vehicle_state(state.clone());
+            assert_eq!(socket.special_vehicle_container(), container);
+        }
+
+        state.vehicle_role = cdd::VehicleRole::dangerousGoods;
+        state.dangerous_goods = Some(cdd::DangerousGoodsBasic::explosives3);
+        socket.set_vehicle_state(state);
+        assert_eq!(
+            socket.special_vehicle_container(),
+            Some(cam::SpecialVehicleContainer::dangerousGoodsContainer(
+                cam::DangerousGoodsContainer::new(cdd::DangerousGoodsBasic::explosives3)
+            ))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::collections::BTreeSet;
 use core::fmt;
 
 use crate::common::geo_area::GeoArea;
@@ -6,6 +7,8 @@ use crate::iface::packet::GeonetPacket;
 use crate::iface::{Congestion, Context, ContextMeta};
 use crate::network::{GnCore, Transport};
 
+#[cfg(feature = "proto-security")]
+use crate::common::geo_area::GeoPosition;
 #[cfg(feature = "proto-security")]
 use crate::security::{
     permission::{Permission, AID},
@@ -14,9 +17,11 @@ use crate::security::{
         SspTrait,
     },
 };
-use crate::socket::{self, btp::SocketB as BtpBSocket, PollAt};
+use crate::socket::{self, btp::SocketB as BtpBSocket, MessageStats, PollAt};
 use crate::time::{Duration, Instant, TAI2004};
 use crate::types::Pseudonym;
+#[cfg(feature = "proto-security")]
+use crate::types::{tenth_of_microdegree, Latitude, Longitude};
 use crate::wire::{self, ports, EthernetAddress, GnTrafficClass};
 
 use crate::storage::PacketBuffer;
@@ -85,6 +90,9 @@ pub struct PollProcessInfo {
     pub action_id: ActionId,
     /// Full DENM message.
     pub msg: denm::DENM,
+    #[cfg(feature = "proto-security")]
+    /// DENM has been flagged as implausible by the misbehavior detection.
+    pub implausible: bool,
 }
 
 /// Error returned by [`Socket::trigger`], [`Socket::update`] and [`Socket::cancel`].
@@ -197,12 +205,24 @@ pub struct ReceivedDenm {
     detection_time: TAI2004,
     /// Reference time of the received DENM.
     reference_time: TAI2004,
+    /// Validity duration of the received DENM.
+    validity_duration: Duration,
+    /// Event position of the received DENM.
+    position: cdd::ReferencePosition,
+    /// Event awareness of the received DENM.
+    awareness: EventAwareness,
+    /// Destination area of the received DENM. [None] if the DENM was not
+    /// received with a Geo Broadcast transport.
+    geo_area: Option<GeoArea>,
+    /// Geonetworking traffic class of the received DENM.
+    traffic_class: GnTrafficClass,
 }
 
 /// State for a DENM across its lifetime. DENM is valid
 /// until its state is [EventState::Expired].
-#[derive(Debug, PartialEq, Eq)]
-enum EventState {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EventState {
     /// DENM is active.
     Active,
     /// DENM is cancelled.
@@ -240,12 +260,46 @@ struct Event {
 }
 
 /// Retransmission metadata for a DENM.
-#[derive(Debug)]
-struct RetransmissionMeta {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RetransmissionMeta {
     /// Retransmission delay.
-    retransmit_delay: Duration,
+    pub retransmit_delay: Duration,
     /// Instant at which retransmission ends.
-    retransmit_end: Instant,
+    pub retransmit_end: Instant,
+}
+
+/// Snapshot of the originating message table of a DENM [Socket], to persist the events
+/// originated by the local station across restarts. See [Socket::originating_table] and
+/// [Socket::restore_originating_table].
+#[derive(Debug, Clone, Default)]
+pub struct OriginatingTable {
+    /// Sequence number of the next Action Id.
+    pub seq_num: u16,
+    /// Events originated by the local station, still disseminated.
+    pub events: Vec<OriginatingEvent>,
+}
+
+/// Persistent state of a DENM originated by the local station.
+#[derive(Debug, Clone)]
+pub struct OriginatingEvent {
+    /// Action Id of the DENM.
+    pub action_id: ActionId,
+    /// State of the DENM. Never [EventState::Expired].
+    pub state: EventState,
+    /// Dissemination geographical area.
+    pub geo_area: GeoArea,
+    /// Geonetworking Traffic class associated to this DENM.
+    pub traffic_class: GnTrafficClass,
+    /// Instant at which the DENM expires.
+    pub expires_at: Instant,
+    /// UPER serialized DENM, containing the detection and reference times,
+    /// the validity duration and the keep-alive transmission interval.
+    pub encoded: Vec<u8>,
+    /// Next retransmission instant. [None] if retransmission has ended.
+    pub retransmit_at: Option<Instant>,
+    /// DENM retransmission metadata (if any).
+    pub retransmission: Option<RetransmissionMeta>,
 }
 
 /// Utility enum for internal processing.
@@ -373,6 +427,8 @@ pub struct Socket<'a> {
     orig_msg_table: ManagedSlice<'a, Option<OriginatedDenm>>,
     /// Receiving Message Table.
     recv_msg_table: ManagedSlice<'a, Option<ReceivedDenm>>,
+    /// DENM messages counters.
+    stats: MessageStats,
     /// Function to call when a DENM message is successfully received by the DENM socket,
     /// ie: whose content is valid and not expired, including repeated messages.
     rx_callback: Option<RxTxCallback>,
@@ -423,6 +479,7 @@ impl<'a> Socket<'a> {
             seq_num: 0,
             orig_msg_table: orig_table_storage.into(),
             recv_msg_table: recv_table_storage.into(),
+            stats: MessageStats::default(),
             rx_callback: None,
             tx_callback: None,
             dispatch_event: None,
@@ -446,6 +503,11 @@ impl<'a> Socket<'a> {
         self.tx_callback = Some(Box::new(tx_cb));
     }
 
+    /// Get the DENM messages counters.
+    pub fn stats(&self) -> MessageStats {
+        self.stats
+    }
+
     /// Trigger a DENM for transmission.
     pub fn trigger(
         &mut self,
@@ -552,6 +614,109 @@ impl<'a> Socket<'a> {
         Ok(handle)
     }
 
+    /// Cancel all the active DENMs originated by the local station, ie: on station shutdown.
+    /// Cancellation DENMs are transmitted once, with the position, destination area and
+    /// validity of the cancelled event.
+    /// Returns the number of cancelled DENMs.
+    pub fn cancel_all(&mut self, core: &GnCore) -> usize {
+        let events: Vec<(EventHandle, EventParameters)> = self
+            .orig_msg_table
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, d)| {
+                let d = d.as_ref().filter(|d| d.state == EventState::Active)?;
+                let management = &d.inner.denm_msg.denm.management;
+                let handle = EventHandle {
+                    idx,
+                    action_id: d.inner.action_id,
+                };
+                let params = EventParameters {
+                    detection_time: TAI2004::from(management.detection_time.clone()),
+                    validity_duration: Some(Duration::from_secs(
+                        management.validity_duration.0.into(),
+                    )),
+                    position: management.event_position.clone(),
+                    awareness: EventAwareness {
+                        distance: management.awareness_distance.clone(),
+                        traffic_direction: management.traffic_direction.clone(),
+                    },
+                    geo_area: d.inner.geo_area,
+                    repetition: None,
+                    keep_alive: None,
+                    traffic_class: d.inner.traffic_class,
+                    situation_container: None,
+                    location_container: None,
+                    alacarte_container: None,
+                };
+                Some((handle, params))
+            })
+            .collect();
+
+        events
+            .into_iter()
+            .filter(|(handle, params)| {
+                self.cancel(core, *handle, params.clone())
+                    .inspect_err(|e| {
+                        net_debug!("Cannot cancel DENM {}: {}", handle.action_id, e);
+                    })
+                    .is_ok()
+            })
+            .count()
+    }
+
+    /// Negate all the active DENMs originated by the local station but no longer in its
+    /// originating table, ie: events of a previous run or pseudonym, received back with a
+    /// Geo Broadcast transport from the stations forwarding them. Events originated by
+    /// other stations are never negated.
+    /// Negation DENMs are transmitted once, with the position, destination area and
+    /// validity of the negated event.
+    /// Returns the number of negated DENMs.
+    pub fn negate_all(&mut self, core: &GnCore) -> usize {
+        // Station IDs used by the local station to originate events.
+        let own_ids: BTreeSet<u32> = self
+            .orig_msg_table
+            .iter()
+            .flatten()
+            .map(|d| d.inner.action_id.station_id)
+            .chain([core.pseudonym.0])
+            .collect();
+
+        let events: Vec<(ActionId, EventParameters)> = self
+            .recv_msg_table
+            .iter()
+            .flatten()
+            .filter(|d| d.state == EventState::Active && d.expires_at >= core.now)
+            .filter(|d| own_ids.contains(&d.action_id.station_id))
+            .filter_map(|d| {
+                let params = EventParameters {
+                    detection_time: d.detection_time,
+                    validity_duration: Some(d.validity_duration),
+                    position: d.position.clone(),
+                    awareness: d.awareness.clone(),
+                    geo_area: d.geo_area?,
+                    repetition: None,
+                    keep_alive: None,
+                    traffic_class: d.traffic_class,
+                    situation_container: None,
+                    location_container: None,
+                    alacarte_container: None,
+                };
+                Some((d.action_id, params))
+            })
+            .collect();
+
+        events
+            .into_iter()
+            .filter(|(action_id, params)| {
+                self.negate(core, *action_id, params.clone())
+                    .inspect_err(|e| {
+                        net_debug!("Cannot negate DENM {}: {}", action_id, e);
+                    })
+                    .is_ok()
+            })
+            .count()
+    }
+
     fn api_inner(
         &mut self,
         core: &GnCore,
@@ -559,7 +724,7 @@ impl<'a> Socket<'a> {
         event: EventParameters,
         kind: Kind,
     ) -> Result<EventHandle, ApiError> {
-        let now_tai = TAI2004::from_unix_instant(core.now);
+        let now_tai = core.tai2004();
 
         if event.detection_time > now_tai {
             return Err(ApiError::InvalidDetectionTime);
@@ -610,60 +775,7 @@ impl<'a> Socket<'a> {
         };
 
         #[cfg(feature = "proto-security")]
-        let permission = if let Some(sec) = &core.security {
-            // Check if we have permission to send this DENM.
-            let sign_permissions = sec
-                .application_permissions()
-                .map_err(|_| ApiError::Unauthorized)?;
-
-            let mut denm_ssps: Vec<DenmSsp> = sign_permissions
-                .into_iter()
-                .filter_map(|p| {
-                    if p.aid() == AID::DEN {
-                        let ssp = *p.denm_or_panic();
-                        // Filter future versions
-                        (ssp.is_v1() || ssp.is_v2()).then_some(ssp)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            if denm_ssps.is_empty() {
-                // No DENM permission found, we cannot send it.
-                return Err(ApiError::Unauthorized);
-            }
-
-            // Sort permissions by descending version value.
-            denm_ssps.sort_by(|a, b| b.cmp(a));
-
-            let final_ssp = match &event.situation_container {
-                Some(situation) => {
-                    let cause_code = &situation.event_type.cc_and_scc;
-                    let requested_permission =
-                        DenmPermission::try_from(cause_code).map_err(|_| ApiError::Unauthorized)?;
-
-                    let denm_ssp = denm_ssps
-                        .iter()
-                        .find(|ssp| ssp.has_permission(requested_permission))
-                        .ok_or(ApiError::Unauthorized)?;
-
-                    let mut ssp = if denm_ssp.is_v1() {
-                        DenmSsp::new_v1()
-                    } else {
-                        DenmSsp::new_v2()
-                    };
-
-                    ssp.set_permission(requested_permission);
-                    ssp
-                }
-                None => denm_ssps[0], // No situation container: use first available SSP
-            };
-
-            Permission::DENM(final_ssp.into())
-        } else {
-            Default::default()
-        };
+        let permission = Self::permission(core, event.situation_container.as_ref())?;
 
         // Assign/get actionId value.
         let (action_id, reference_time) = match kind {
@@ -741,6 +853,79 @@ impl<'a> Socket<'a> {
         Ok(EventHandle { idx, action_id })
     }
 
+    /// Extract the event position from `denm`, for the misbehavior detection.
+    #[cfg(feature = "proto-security")]
+    fn event_position(denm: &denm::DENM) -> Option<GeoPosition> {
+        let pos = &denm.denm.management.event_position;
+        (pos.latitude.0 != 900_000_001 && pos.longitude.0 != 1_800_000_001).then(|| GeoPosition {
+            latitude: Latitude::new::<tenth_of_microdegree>(pos.latitude.0.into()),
+            longitude: Longitude::new::<tenth_of_microdegree>(pos.longitude.0.into()),
+        })
+    }
+
+    /// Computes the permission to sign a DENM with the `situation` container, ie: the DENM SSP
+    /// allowing its cause code among the local station application permissions.
+    #[cfg(feature = "proto-security")]
+    fn permission(
+        core: &GnCore,
+        situation: Option<&denm::SituationContainer>,
+    ) -> Result<Permission, ApiError> {
+        if let Some(sec) = &core.security {
+            // Check if we have permission to send this DENM.
+            let sign_permissions = sec
+                .application_permissions()
+                .map_err(|_| ApiError::Unauthorized)?;
+
+            let mut denm_ssps: Vec<DenmSsp> = sign_permissions
+                .into_iter()
+                .filter_map(|p| {
+                    if p.aid() == AID::DEN {
+                        let ssp = *p.denm_or_panic();
+                        // Filter future versions
+                        (ssp.is_v1() || ssp.is_v2()).then_some(ssp)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            if denm_ssps.is_empty() {
+                // No DENM permission found, we cannot send it.
+                return Err(ApiError::Unauthorized);
+            }
+
+            // Sort permissions by descending version value.
+            denm_ssps.sort_by(|a, b| b.cmp(a));
+
+            let final_ssp = match situation {
+                Some(situation) => {
+                    let cause_code = &situation.event_type.cc_and_scc;
+                    let requested_permission =
+                        DenmPermission::try_from(cause_code).map_err(|_| ApiError::Unauthorized)?;
+
+                    let denm_ssp = denm_ssps
+                        .iter()
+                        .find(|ssp| ssp.has_permission(requested_permission))
+                        .ok_or(ApiError::Unauthorized)?;
+
+                    let mut ssp = if denm_ssp.is_v1() {
+                        DenmSsp::new_v1()
+                    } else {
+                        DenmSsp::new_v2()
+                    };
+
+                    ssp.set_permission(requested_permission);
+                    ssp
+                }
+                None => denm_ssps[0], // No situation container: use first available SSP
+            };
+
+            Ok(Permission::DENM(final_ssp.into()))
+        } else {
+            Ok(Default::default())
+        }
+    }
+
     /// Query whether the DENM socket accepts the segment.
     #[must_use]
     pub(crate) fn accepts(
@@ -757,7 +942,7 @@ impl<'a> Socket<'a> {
     pub(crate) fn process(
         &mut self,
         cx: &mut Context,
-        srv: &ContextMeta,
+        srv: &mut ContextMeta,
         indication: Indication,
         payload: &[u8],
     ) {
@@ -769,10 +954,11 @@ impl<'a> Socket<'a> {
             return;
         }
 
-        let (buf, _ind) = match self.inner.recv() {
+        let (buf, ind) = match self.inner.recv() {
             Ok(d) => d,
             Err(e) => {
                 net_debug!("Cannot process DENM: {}", e);
+                self.stats.rx_discarded += 1;
                 return;
             }
         };
@@ -781,6 +967,7 @@ impl<'a> Socket<'a> {
             Ok(d) => d,
             Err(e) => {
                 net_debug!("Cannot process DENM: {}", e);
+                self.stats.rx_discarded += 1;
                 return;
             }
         };
@@ -789,7 +976,7 @@ impl<'a> Socket<'a> {
 
         #[cfg(feature = "proto-security")]
         if srv.core.security.is_some() {
-            let authorized = match (&decoded.denm.situation, &_ind.its_aid) {
+            let authorized = match (&decoded.denm.situation, &ind.its_aid) {
                 (None, Permission::DENM(_)) => true,
                 (Some(s), Permission::DENM(p)) => {
                     match DenmPermission::try_from(&s.event_type.cc_and_scc) {
@@ -802,22 +989,50 @@ impl<'a> Socket<'a> {
                         "Cannot process DENM {} - unexpected permission type",
                         action_id
                     );
+                    self.stats.rx_discarded += 1;
                     return;
                 }
             };
 
             if !authorized {
                 net_debug!("Cannot process DENM {} - not authorized", action_id);
+                self.stats.rx_discarded += 1;
                 return;
             }
         }
 
+        // The sender position is the generation location of the secured message, the event
+        // position and detection time are checked against it.
+        #[cfg(feature = "proto-security")]
+        let implausible = match srv.check_denm_misbehavior(
+            Self::event_position(&decoded),
+            TAI2004::from(decoded.denm.management.detection_time.clone()),
+        ) {
+            Some(verdict) if verdict.should_drop() => {
+                net_debug!("Cannot process DENM {} - implausible", action_id);
+                self.stats.rx_discarded += 1;
+                return;
+            }
+            Some(verdict) => verdict.is_flagged(),
+            None => false,
+        };
+
         let now = srv.core.now;
         let termination = decoded.denm.management.termination;
         let detection_time = TAI2004::from(decoded.denm.management.detection_time.clone());
         let reference_time = TAI2004::from(decoded.denm.management.reference_time.clone());
-        let expires_at = detection_time.as_unix_instant()
-            + Duration::from_secs(decoded.denm.management.validity_duration.0.into());
+        let validity_duration =
+            Duration::from_secs(decoded.denm.management.validity_duration.0.into());
+        let expires_at = detection_time.as_unix_instant() + validity_duration;
+        let position = decoded.denm.management.event_position.clone();
+        let awareness = EventAwareness {
+            distance: decoded.denm.management.awareness_distance.clone(),
+            traffic_direction: decoded.denm.management.traffic_direction.clone(),
+        };
+        let geo_area = match ind.transport {
+            Transport::Broadcast(area) => Some(area),
+            _ => None,
+        };
 
         if expires_at < now {
             net_debug!(
@@ -826,6 +1041,7 @@ impl<'a> Socket<'a> {
                 expires_at,
                 now
             );
+            self.stats.rx_discarded += 1;
             return;
         }
 
@@ -834,6 +1050,7 @@ impl<'a> Socket<'a> {
             .iter()
             .position(|item| item.as_ref().is_some_and(|e| e.action_id == action_id));
 
+        self.stats.rx += 1;
         if let Some(rx_cb) = &mut self.rx_callback {
             rx_cb(buf, &decoded);
         };
@@ -841,6 +1058,8 @@ impl<'a> Socket<'a> {
         let info = PollProcessInfo {
             action_id,
             msg: decoded,
+            #[cfg(feature = "proto-security")]
+            implausible,
         };
 
         match handle_opt {
@@ -860,6 +1079,11 @@ impl<'a> Socket<'a> {
                 entry.expires_at = expires_at;
                 entry.reference_time = reference_time;
                 entry.detection_time = detection_time;
+                entry.validity_duration = validity_duration;
+                entry.position = position;
+                entry.awareness = awareness;
+                entry.geo_area = geo_area;
+                entry.traffic_class = ind.traffic_class;
                 entry.state = if let Some(term) = termination {
                     self.process_event = Some(match term {
                         denm::Termination::isCancellation => PollProcessEvent::RecvCancel(info),
@@ -887,6 +1111,11 @@ impl<'a> Socket<'a> {
                     expires_at,
                     detection_time,
                     reference_time,
+                    validity_duration,
+                    position,
+                    awareness,
+                    geo_area,
+                    traffic_class: ind.traffic_class,
                 });
 
                 self.process_event = Some(PollProcessEvent::RecvNew(info));
@@ -990,19 +1219,23 @@ impl<'a> Socket<'a> {
                 return Ok(());
             };
 
+            // Minimum repetition interval, provided by DCC_FAC.
+            let gen_denm_dcc = srv
+                .congestion_control
+                .gen_interval(event.traffic_class.access_category());
+
             self.inner.dispatch(cx, srv, emit).inspect(|_| {
+                self.stats.tx += 1;
                 if let Some(tx_cb) = &mut self.tx_callback {
                     tx_cb(&event.encoded, &event.denm_msg);
                 };
             })?;
 
             // Schedule for next retransmission.
-            match &event.retransmission {
-                Some(r) if (now + r.retransmit_delay) < r.retransmit_end => {
-                    event.retransmit_at = Some(now + r.retransmit_delay);
-                }
-                _ => event.retransmit_at = None,
-            }
+            event.retransmit_at = event.retransmission.as_ref().and_then(|r| {
+                let at = now + r.retransmit_delay.max(gen_denm_dcc);
+                (at < r.retransmit_end).then_some(at)
+            });
 
             return Ok(());
         }
@@ -1038,6 +1271,151 @@ impl<'a> Socket<'a> {
             .unwrap_or(PollAt::Ingress)
     }
 
+    /// Query whether an event originated by the local station is still disseminated at `timestamp`,
+    /// ie: is active, cancelled or negated, and not expired.
+    pub fn has_active_events(&self, timestamp: Instant) -> bool {
+        self.orig_msg_table
+            .iter()
+            .flatten()
+            .any(|d| d.state != EventState::Expired && d.inner.expires_at >= timestamp)
+    }
+
+    /// Get a snapshot of the originating message table, ie: the events originated by the
+    /// local station still disseminated at `timestamp`, and the Action Id sequence number.
+    pub fn originating_table(&self, timestamp: Instant) -> OriginatingTable {
+        let events = self
+            .orig_msg_table
+            .iter()
+            .flatten()
+            .filter(|d| d.state != EventState::Expired && d.inner.expires_at >= timestamp)
+            .map(|d| OriginatingEvent {
+                action_id: d.inner.action_id,
+                state: d.state,
+                geo_area: d.inner.geo_area,
+                traffic_class: d.inner.traffic_class,
+                expires_at: d.inner.expires_at,
+                encoded: d.inner.encoded.clone(),
+                retransmit_at: d.inner.retransmit_at,
+                retransmission: d.inner.retransmission,
+            })
+            .collect();
+
+        OriginatingTable {
+            seq_num: self.seq_num,
+            events,
+        }
+    }
+
+    /// Restore the originating message table from a `table` snapshot taken with
+    /// [Socket::originating_table], ie: after a restart of the local station. Restored events
+    /// keep their Action Id and repetition schedule, retransmissions missed while the station
+    /// was down are sent on the next dispatch. Expired, invalid or already known events are ignored.
+    /// Returns the number of restored events.
+    pub fn restore_originating_table(&mut self, core: &GnCore, table: OriginatingTable) -> usize {
+        let mut restored = 0;
+
+        for evt in table.events {
+            if evt.state == EventState::Expired || evt.expires_at < core.now {
+                net_debug!("DENM {} not restored: expired", evt.action_id);
+                continue;
+            }
+
+            let known = self
+                .orig_msg_table
+                .iter()
+                .flatten()
+                .any(|d| d.state != EventState::Expired && d.inner.action_id == evt.action_id);
+
+            if known {
+                net_debug!(
+                    "DENM {} not restored: {}",
+                    evt.action_id,
+                    ApiError::ActionIdInOrigMsgtable
+                );
+                continue;
+            }
+
+            let mut denm_msg = match rasn::uper::decode::<denm::DENM>(&evt.encoded) {
+                Ok(msg) => Box::new(msg),
+                Err(e) => {
+                    net_debug!(
+                        "DENM {} not restored: invalid content: {}",
+                        evt.action_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            // Station may have restarted with a new pseudonym. The Action Id is kept so
+            // receivers still identify the event.
+            let mut encoded = evt.encoded;
+            if denm_msg.header.station_id.0 != core.pseudonym.0 {
+                denm_msg.header.station_id = cdd::StationId(core.pseudonym.0);
+                encoded = match rasn::uper::encode(&denm_msg) {
+                    Ok(enc) => enc,
+                    Err(e) => {
+                        net_debug!(
+                            "DENM {} not restored: invalid content: {}",
+                            evt.action_id,
+                            e
+                        );
+                        continue;
+                    }
+                };
+            }
+
+            #[cfg(feature = "proto-security")]
+            let permission = match Self::permission(core, denm_msg.denm.situation.as_ref()) {
+                Ok(p) => p,
+                Err(e) => {
+                    net_debug!("DENM {} not restored: {}", evt.action_id, e);
+                    continue;
+                }
+            };
+
+            let Some(idx) = self.find_free_orig_table() else {
+                net_debug!(
+                    "DENM {} not restored: {}",
+                    evt.action_id,
+                    ApiError::NoFreeSlot
+                );
+                break;
+            };
+
+            self.orig_msg_table[idx] = Some(OriginatedDenm {
+                state: evt.state,
+                inner: Event {
+                    action_id: evt.action_id,
+                    geo_area: evt.geo_area,
+                    traffic_class: evt.traffic_class,
+                    expires_at: evt.expires_at,
+                    denm_msg,
+                    encoded,
+                    retransmit_at: evt.retransmit_at,
+                    retransmission: evt.retransmission,
+                    #[cfg(feature = "proto-security")]
+                    permission,
+                },
+            });
+
+            restored += 1;
+        }
+
+        self.seq_num = table.seq_num;
+        restored
+    }
+
+    /// Notify the socket the local station pseudonym has changed.
+    /// Action Id sequence number is reset so it cannot link the previous and new pseudonyms,
+    /// unless some originated events are still disseminated, as their Action Id could collide
+    /// with the new ones.
+    pub(crate) fn notify_pseudonym_change(&mut self, timestamp: Instant) {
+        if !self.has_active_events(timestamp) {
+            self.seq_num = 0;
+        }
+    }
+
     /// Returns the next Action Id value to assign to a new DENM.
     /// This function increments the [Socket::seq_num] value.
     fn next_action_id(&mut self, pseudo: Pseudonym) -> ActionId {
@@ -1312,6 +1690,125 @@ mod ipc {
         }
     }
 
+    impl From<GeoArea> for ipc_denm::GeoArea {
+        fn from(value: GeoArea) -> Self {
+            let meters = |d: Distance| d.get::<meter>().round() as u32;
+            let shape = match value.shape {
+                Shape::Circle(c) => ipc_denm::geo_area::Shape::Circle(ipc_denm::CircleShape {
+                    radius: meters(c.radius),
+                }),
+                Shape::Rectangle(r) => {
+                    ipc_denm::geo_area::Shape::Rectangle(ipc_denm::RectangleShape {
+                        distance_a: meters(r.a),
+                        distance_b: meters(r.b),
+                    })
+                }
+                Shape::Ellipse(e) => ipc_denm::geo_area::Shape::Ellipse(ipc_denm::EllipseShape {
+                    distance_a: meters(e.a),
+                    distance_b: meters(e.b),
+                }),
+            };
+
+            ipc_denm::GeoArea {
+                latitude: value.position.latitude.get::<degree>(),
+                longitude: value.position.longitude.get::<degree>(),
+                shape: Some(shape),
+                angle: value.angle.get::<degree>().round() as u32,
+            }
+        }
+    }
+
+    impl From<OriginatingTable> for ipc_denm::OriginatingTable {
+        fn from(value: OriginatingTable) -> Self {
+            ipc_denm::OriginatingTable {
+                sequence_number: value.seq_num.into(),
+                events: value.events.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl TryFrom<ipc_denm::OriginatingTable> for OriginatingTable {
+        type Error = ipc_denm::ApiResultCode;
+
+        fn try_from(value: ipc_denm::OriginatingTable) -> Result<Self, Self::Error> {
+            Ok(OriginatingTable {
+                seq_num: value
+                    .sequence_number
+                    .try_into()
+                    .map_err(|_| ipc_denm::ApiResultCode::Malformed)?,
+                events: value
+                    .events
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+    }
+
+    impl From<OriginatingEvent> for ipc_denm::OriginatingEvent {
+        fn from(value: OriginatingEvent) -> Self {
+            let state = match value.state {
+                EventState::Cancelled => ipc_denm::OriginatingEventState::Cancelled,
+                EventState::Negated => ipc_denm::OriginatingEventState::Negated,
+                EventState::Active | EventState::Expired => ipc_denm::OriginatingEventState::Active,
+            };
+
+            ipc_denm::OriginatingEvent {
+                action_id: Some(ipc_denm::ActionId {
+                    station_id: value.action_id.station_id,
+                    sequence_number: value.action_id.seq_num.into(),
+                }),
+                state: state.into(),
+                geo_area: Some(value.geo_area.into()),
+                traffic_class: (*value.traffic_class.as_byte()).into(),
+                expires_at: value.expires_at.total_millis() as u64,
+                message: value.encoded,
+                retransmit_at: value.retransmit_at.map(|at| at.total_millis() as u64),
+                repetition: value
+                    .retransmission
+                    .map(|r| ipc_denm::OriginatingRepetition {
+                        interval: r.retransmit_delay.total_millis() as u32,
+                        end: r.retransmit_end.total_millis() as u64,
+                    }),
+            }
+        }
+    }
+
+    impl TryFrom<ipc_denm::OriginatingEvent> for OriginatingEvent {
+        type Error = ipc_denm::ApiResultCode;
+
+        fn try_from(value: ipc_denm::OriginatingEvent) -> Result<Self, Self::Error> {
+            let state = match ipc_denm::OriginatingEventState::try_from(value.state) {
+                Ok(ipc_denm::OriginatingEventState::Active) => EventState::Active,
+                Ok(ipc_denm::OriginatingEventState::Cancelled) => EventState::Cancelled,
+                Ok(ipc_denm::OriginatingEventState::Negated) => EventState::Negated,
+                Err(_) => return Err(ipc_denm::ApiResultCode::Malformed),
+            };
+
+            Ok(OriginatingEvent {
+                action_id: value
+                    .action_id
+                    .ok_or(ipc_denm::ApiResultCode::MalformedActionId)?
+                    .try_into()?,
+                state,
+                geo_area: value
+                    .geo_area
+                    .ok_or(ipc_denm::ApiResultCode::Malformed)?
+                    .try_into()?,
+                traffic_class: GnTrafficClass::from_byte(&(value.traffic_class as u8)),
+                expires_at: Instant::from_millis_const(value.expires_at as i64),
+                encoded: value.message,
+                retransmit_at: value
+                    .retransmit_at
+                    .map(|at| Instant::from_millis_const(at as i64)),
+                retransmission: value.repetition.map(|r| RetransmissionMeta {
+                    retransmit_delay: Duration::from_millis(r.interval.into()),
+                    retransmit_end: Instant::from_millis_const(r.end as i64),
+                }),
+            })
+        }
+    }
+
     impl TryFrom<ipc_denm::Handle> for EventHandle {
         type Error = ipc_denm::ApiResultCode;
 
@@ -1385,7 +1882,7 @@ mod test {
 
         let payload = rasn::uper::encode(&denm_repr).unwrap();
 
-        let srv = ContextMeta {
+        let mut srv = ContextMeta {
             core: &mut s.core,
             ls: &mut s.iface.location_service,
             congestion_control: &mut s.iface.congestion_control,
@@ -1398,7 +1895,7 @@ mod test {
         };
 
         s.socket
-            .process(&mut s.iface.inner, &srv, indication, &payload)
+            .process(&mut s.iface.inner, &mut srv, indication, &payload)
     }
 
     fn send(s: &mut TestSocket, timestamp: Instant) -> Option<denm::DENM> {
@@ -1627,6 +2124,8 @@ mod test {
             its_aid: Default::default(),
             #[cfg(feature = "proto-security")]
             cert_id: Default::default(),
+            #[cfg(feature = "proto-security")]
+            confidential: false,
             rem_lifetime: Duration::from_secs(1),
             rem_hop_limit: 9,
             traffic_class: GnTrafficClass::new(false, 10),
@@ -1816,6 +2315,39 @@ mod test {
         assert!(send(&mut s, now).is_none());
     }
 
+    #[test]
+    fn test_active_events() {
+        let mut s = socket(Medium::Ethernet);
+
+        let mut now = Instant::now();
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+        assert!(!s.socket.has_active_events(now));
+
+        let mut params = evt_params(now);
+        params.repetition = None;
+        let handle = s.socket.trigger(&s.core, params.clone()).unwrap();
+        assert_eq!(handle.action_id.seq_num, 0);
+        assert!(s.socket.has_active_events(now));
+
+        // Sequence number is kept while the event is disseminated.
+        #[cfg(feature = "proto-security")]
+        {
+            s.socket.notify_pseudonym_change(now);
+            assert_eq!(s.socket.seq_num, handle.action_id.seq_num + 1);
+        }
+
+        // Jump after the expiration of the event.
+        now += params.validity_duration.unwrap_or(DEFAULT_VALIDITY) + Duration::from_secs(1);
+        assert!(!s.socket.has_active_events(now));
+
+        #[cfg(feature = "proto-security")]
+        {
+            s.socket.notify_pseudonym_change(now);
+            assert_eq!(s.socket.seq_num, 0);
+        }
+    }
+
     #[test]
     fn test_repetition() {
         let mut s = socket(Medium::Ethernet);
@@ -2018,6 +2550,150 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_cancel_all() {
+        let mut s = socket(Medium::Ethernet);
+
+        let mut now = Instant::now();
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+
+        let mut params = evt_params(now);
+        params.repetition = None;
+        let first = s.socket.trigger(&s.core, params.clone()).unwrap();
+        assert!(send(&mut s, now).is_some());
+        let second = s.socket.trigger(&s.core, params.clone()).unwrap();
+        assert!(send(&mut s, now).is_some());
+
+        // Jump 10 secs in the future and cancel both DENMs.
+        now += Duration::from_secs(10);
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+
+        assert_eq!(s.socket.cancel_all(&s.core), 2);
+
+        for handle in [first, second] {
+            let msg = send(&mut s, now).unwrap();
+            check_fields(s.core.pseudonym, &params, &handle.action_id, &msg);
+            assert_eq!(
+                msg.denm.management.termination,
+                Some(denm::Termination::isCancellation)
+            );
+        }
+
+        // Cancellations are sent once, and are not cancelled again.
+        assert!(send(&mut s, now).is_none());
+        assert_eq!(s.socket.cancel_all(&s.core), 0);
+    }
+
+    #[test]
+    fn test_restore_originating_table() {
+        let mut s = socket(Medium::Ethernet);
+
+        let mut now = Instant::now();
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+
+        let params = evt_params(now);
+        let repet = params.repetition.unwrap();
+        let handle = s.socket.trigger(&s.core, params.clone()).unwrap();
+        assert!(send(&mut s, now).is_some());
+
+        let table = s.socket.originating_table(now);
+        assert_eq!(table.seq_num, handle.action_id.seq_num + 1);
+        assert_eq!(table.events.len(), 1);
+
+        // Restart with an empty socket and a new pseudonym, 2 secs later.
+        now += Duration::from_secs(2);
+        s.core.now = now;
+        s.core.pseudonym = Pseudonym(s.core.pseudonym.0.wrapping_add(1));
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+        s.socket = Socket::new(vec![], vec![]);
+
+        assert_eq!(
+            s.socket.restore_originating_table(&s.core, table.clone()),
+            1
+        );
+        assert_eq!(s.socket.seq_num, table.seq_num);
+        assert!(s.socket.has_active_events(now));
+
+        // Missed retransmission is sent right away, with the same Action Id and
+        // the new station Id.
+        let msg = send(&mut s, now).unwrap();
+        check_fields(s.core.pseudonym, &params, &handle.action_id, &msg);
+
+        // Event is already known.
+        assert_eq!(
+            s.socket.restore_originating_table(&s.core, table.clone()),
+            0
+        );
+
+        // Restored event can be cancelled with its handle.
+        let mut cancel_params = params.clone();
+        cancel_params.repetition = None;
+        s.socket.cancel(&s.core, handle, cancel_params).unwrap();
+        let msg = send(&mut s, now).unwrap();
+        assert_eq!(
+            msg.denm.management.termination,
+            Some(denm::Termination::isCancellation)
+        );
+
+        // Expired events are not restored.
+        s.socket = Socket::new(vec![], vec![]);
+        s.core.now = table.events[0].expires_at + repet.interval;
+        assert_eq!(s.socket.restore_originating_table(&s.core, table), 0);
+        assert!(!s.socket.has_active_events(s.core.now));
+    }
+
+    #[test]
+    fn test_negate_all() {
+        let mut s = socket(Medium::Ethernet);
+
+        let mut now = Instant::now();
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+
+        // Event originated by another station.
+        let foreign_evt = denm_evt(now);
+        recv(&mut s, now, (new_ind(), foreign_evt));
+
+        // Event originated by the local station in a previous run.
+        let mut denm_evt = denm_evt(now);
+        let own_id = cdd::StationId(s.core.pseudonym.0);
+        denm_evt.header.station_id = own_id.clone();
+        denm_evt.denm.management.action_id.originating_station_id = own_id;
+        recv(&mut s, now, (new_ind(), denm_evt.clone()));
+
+        now += Duration::from_secs(1);
+        s.core.now = now;
+        s.core.set_position(station_pos_fix(now), now).unwrap();
+
+        // Only the event of the local station is negated.
+        assert_eq!(s.socket.negate_all(&s.core), 1);
+
+        let msg = send(&mut s, now).unwrap();
+        assert_eq!(
+            msg.denm.management.action_id,
+            denm_evt.denm.management.action_id
+        );
+        assert_eq!(
+            msg.denm.management.reference_time,
+            denm_evt.denm.management.reference_time
+        );
+        assert_eq!(
+            msg.denm.management.event_position,
+            denm_evt.denm.management.event_position
+        );
+        assert_eq!(
+            msg.denm.management.termination,
+            Some(denm::Termination::isNegation)
+        );
+
+        // Negations are sent once, and are not negated again.
+        assert!(send(&mut s, now).is_none());
+        assert_eq!(s.socket.negate_all(&s.core), 0);
+    }
+
     #[test]
     fn test_receive() {
         let mut s = socket(Medium::Ethernet);
@@ -320,6 +320,8 @@ impl<'a> Socket<'a> {
                         (),
                         #[cfg(feature = "proto-security")]
                         req.its_aid.clone(),
+                        #[cfg(feature = "proto-security")]
+                        req.confidential,
                         req.max_lifetime,
                         req.max_hop_limit,
                         req.traffic_class,
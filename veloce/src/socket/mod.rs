@@ -32,6 +32,19 @@ mod waker;
 #[cfg(feature = "async")]
 pub(crate) use self::waker::WakerRegistration;
 
+/// Message counters of a facilities layer socket.
+#[cfg(any(feature = "socket-cam", feature = "socket-denm"))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MessageStats {
+    /// Messages transmitted to the lower layer.
+    pub tx: u64,
+    /// Messages received and delivered to the application.
+    pub rx: u64,
+    /// Received messages discarded, ie: undecodable, unauthorized or implausible.
+    pub rx_discarded: u64,
+}
+
 /// Gives an indication on the next time the socket should be polled.
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -30,7 +30,7 @@ pub(crate) fn setup<'a>(medium: Medium) -> (GnCore, Interface, SocketSet<'a>, Te
 use heapless::Deque;
 use heapless::Vec;
 
-use crate::phy::{self, Device, DeviceCapabilities, Medium};
+use crate::phy::{self, ChannelBusyRatio, Device, DeviceCapabilities, Medium};
 use crate::time::Instant;
 
 use super::network::GnCore;
@@ -42,6 +42,8 @@ pub struct TestingDevice {
     pub(crate) queue: Deque<Vec<u8, 1514, usize>, 4>,
     max_transmission_unit: usize,
     medium: Medium,
+    /// Channel Busy Ratio reported by the device.
+    pub(crate) cbr: ChannelBusyRatio,
 }
 
 #[allow(clippy::new_without_default)]
@@ -62,6 +64,7 @@ impl TestingDevice {
                 Medium::PC5 => 1500,
             },
             medium,
+            cbr: ChannelBusyRatio::from_ratio(0.0),
         }
     }
 }
@@ -93,6 +96,10 @@ impl Device for TestingDevice {
             queue: &mut self.queue,
         })
     }
+
+    fn channel_busy_ratio(&self) -> ChannelBusyRatio {
+        self.cbr
+    }
 }
 
 #[doc(hidden)]
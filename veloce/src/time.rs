@@ -7,10 +7,12 @@
 ///  - [TAI2004] is used to represent a TAI 2004 absolute time.
 ///  - [Instant] is used to represent absolute time.
 ///  - [Duration] is used to represent relative time.
+///  - [Clock] is used to estimate the accuracy of the system clock.
 ///
 /// [TAI2004]: struct.TAI2004.html
 /// [Instant]: struct.Instant.html
 /// [Duration]: struct.Duration.html
+/// [Clock]: struct.Clock.html
 ///
 use core::{fmt, ops};
 
@@ -19,6 +21,12 @@ use veloce_asn1::defs::etsi_messages_r2::etsi__its__cdd::TimestampIts;
 
 use chrono::DateTime;
 
+mod clock;
+mod leap_seconds;
+
+pub use clock::{Clock, Config as ClockConfig};
+pub use leap_seconds::{LeapSeconds, LeapSecondsError};
+
 /// A representation of an absolute TAI time value.
 /// Clock zero date is 01-01-2004 at 00:00:00 UTC.
 /// Also, leap seconds must be considered when generating a
@@ -26,6 +34,8 @@ use chrono::DateTime;
 /// The official number of leap seconds can be obtained from
 /// https://hpiers.obspm.fr/eop-pc/index.php
 /// or https://hpiers.obspm.fr/iers/bul/bulc/ntp/leap-seconds.list
+/// Conversions use [TAI2004::LEAP_SECONDS_DEFAULT] unless an explicit TAI - UTC
+/// offset is given, ie: the one maintained by the [Clock] from a [LeapSeconds] table.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TAI2004 {
     micros: i64,
@@ -36,12 +46,20 @@ impl TAI2004 {
     pub const ZERO: TAI2004 = TAI2004::from_micros_const(0);
     /// Time difference between 01-01-1970 00:00:00 UTC and 01-01-2004 00:00:00 UTC.
     pub const DIFF_1970_2004: Duration = Duration::from_secs(1_072_915_200);
-    const LEAP_SECONDS_2004: u64 = 32;
-    const LEAP_SECONDS_NOW: u64 = 37;
+    const LEAP_SECONDS_2004: u32 = 32;
+    /// TAI - UTC offset used when no leap seconds table has been loaded.
+    pub const LEAP_SECONDS_DEFAULT: u32 = 37;
 
     /// Return the number of TAI leap seconds since 01-01-2004.
     pub const fn leap_seconds_since_2004() -> Duration {
-        Duration::from_secs(Self::LEAP_SECONDS_NOW - Self::LEAP_SECONDS_2004)
+        Self::leap_seconds_since_2004_with_offset(Self::LEAP_SECONDS_DEFAULT)
+    }
+
+    /// Return the number of TAI leap seconds since 01-01-2004, for
+    /// the given `tai_utc_offset`, in seconds.
+    const fn leap_seconds_since_2004_with_offset(tai_utc_offset: u32) -> Duration {
+        let offset = tai_utc_offset.saturating_sub(Self::LEAP_SECONDS_2004);
+        Duration::from_secs(offset as u64)
     }
 
     /// Create a new `TAI2004` from a number of microseconds.
@@ -79,13 +97,26 @@ impl TAI2004 {
 
     /// Create a new `TAI2004` from an unix `Instant`
     pub fn from_unix_instant(unix: Instant) -> TAI2004 {
-        let adjusted = unix - Self::DIFF_1970_2004 + Self::leap_seconds_since_2004();
+        Self::from_unix_instant_with_offset(unix, Self::LEAP_SECONDS_DEFAULT)
+    }
+
+    /// Create a new `TAI2004` from an unix `Instant`, using the
+    /// given `tai_utc_offset`, in seconds.
+    pub fn from_unix_instant_with_offset(unix: Instant, tai_utc_offset: u32) -> TAI2004 {
+        let adjusted =
+            unix - Self::DIFF_1970_2004 + Self::leap_seconds_since_2004_with_offset(tai_utc_offset);
         Self::from_micros_const(adjusted.total_micros())
     }
 
     /// Return as an Unix epoch `Instant`.
     pub fn as_unix_instant(&self) -> Instant {
-        let unix = *self - Self::leap_seconds_since_2004() + Self::DIFF_1970_2004;
+        self.as_unix_instant_with_offset(Self::LEAP_SECONDS_DEFAULT)
+    }
+
+    /// Return as an Unix epoch `Instant`, using the given `tai_utc_offset`, in seconds.
+    pub fn as_unix_instant_with_offset(&self, tai_utc_offset: u32) -> Instant {
+        let unix = *self - Self::leap_seconds_since_2004_with_offset(tai_utc_offset)
+            + Self::DIFF_1970_2004;
         Instant::from_micros_const(unix.total_micros())
     }
 
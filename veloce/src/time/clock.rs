@@ -0,0 +1,328 @@
+/*! Clock service.
+
+The clock service keeps track of the TAI - UTC offset using a [LeapSeconds] table,
+and estimates the offset between the system clock and the GNSS time from the position
+fixes. The system clock corrected with this offset is the time reference of the stack,
+and its accuracy is required by ETSI TS 103 097 to generate secured messages.
+
+The offset is filtered with an exponentially weighted moving average. Offset variations
+larger than [Config::step_threshold] are considered as system clock steps, and reset the
+estimation. Without GNSS time, the uncertainty grows with the system clock drift until
+[Config::max_holdover] is reached, after which the accuracy is unknown.
+*/
+
+use super::{Duration, Instant, LeapSeconds, TAI2004};
+
+/// Clock service configuration.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// Gain of the offset filter, between 0.0 and 1.0 inclusive.
+    pub gain: f64,
+    /// Maximum latency between the GNSS fix time and its reception by the stack.
+    pub max_latency: Duration,
+    /// Maximum drift of the system clock, in parts per million.
+    pub drift_ppm: f64,
+    /// Offset variation above which the system clock is considered as stepped.
+    pub step_threshold: Duration,
+    /// Maximum duration without GNSS time after which the accuracy is unknown.
+    pub max_holdover: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            gain: 0.1,
+            max_latency: Duration::from_millis(200),
+            drift_ppm: 50.0,
+            step_threshold: Duration::from_secs(1),
+            max_holdover: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Clock service.
+#[derive(Debug)]
+pub struct Clock {
+    /// Clock configuration.
+    config: Config,
+    /// Leap seconds table.
+    leap_seconds: Option<LeapSeconds>,
+    /// Current TAI - UTC offset, in seconds.
+    tai_utc_offset: u32,
+    /// Estimated offset between the GNSS time and the system clock, in microseconds.
+    offset: Option<i64>,
+    /// Estimated jitter of the offset, in microseconds.
+    jitter: u64,
+    /// Instant of the last GNSS time sample.
+    synced_at: Option<Instant>,
+    /// Whether the leap seconds table expiration has been reported.
+    expiration_reported: bool,
+}
+
+impl Clock {
+    /// Constructs a new [Clock] with the given `config`.
+    pub fn new(config: Config) -> Self {
+        Clock {
+            config,
+            leap_seconds: None,
+            tai_utc_offset: TAI2004::LEAP_SECONDS_DEFAULT,
+            offset: None,
+            jitter: 0,
+            synced_at: None,
+            expiration_reported: false,
+        }
+    }
+
+    /// Returns the clock configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the leap seconds table, if any.
+    pub fn leap_seconds(&self) -> Option<&LeapSeconds> {
+        self.leap_seconds.as_ref()
+    }
+
+    /// Returns the current TAI - UTC offset, in seconds.
+    pub fn tai_utc_offset(&self) -> u32 {
+        self.tai_utc_offset
+    }
+
+    /// Set the leap seconds `table`. The TAI - UTC offset applicable at `timestamp`
+    /// is applied immediately.
+    pub fn set_leap_seconds(&mut self, table: LeapSeconds, timestamp: Instant) {
+        self.leap_seconds = Some(table);
+        self.expiration_reported = false;
+        self.poll(timestamp);
+    }
+
+    /// Notify the clock service about a `gnss` time sample, received at `timestamp`.
+    pub fn notify_gnss_time(&mut self, gnss: TAI2004, timestamp: Instant) {
+        let sample = gnss.total_micros() - self.utc_to_tai(timestamp).total_micros();
+
+        match self.offset {
+            Some(offset)
+                if (sample - offset).unsigned_abs() < self.config.step_threshold.total_micros() =>
+            {
+                let error = sample - offset;
+                let deviation = error.unsigned_abs() as f64 - self.jitter as f64;
+                self.offset = Some(offset + (error as f64 * self.config.gain) as i64);
+                self.jitter = (self.jitter as f64 + deviation * self.config.gain) as u64;
+            }
+            Some(offset) => {
+                net_debug!(
+                    "clock stepped: offset {}us -> {}us, resetting estimation",
+                    offset,
+                    sample
+                );
+                self.offset = Some(sample);
+                self.jitter = 0;
+            }
+            None => {
+                net_debug!("clock synced: offset {}us", sample);
+                self.offset = Some(sample);
+                self.jitter = 0;
+            }
+        }
+
+        self.synced_at = Some(timestamp);
+    }
+
+    /// Returns the estimated offset between the GNSS time and the system clock,
+    /// in microseconds. A positive value means the system clock is late.
+    pub fn offset_micros(&self) -> Option<i64> {
+        self.offset
+    }
+
+    /// Returns the uncertainty of the estimated offset at `timestamp`, or [None] if
+    /// the clock has never been synced or has been in holdover for too long.
+    pub fn uncertainty(&self, timestamp: Instant) -> Option<Duration> {
+        let synced_at = self.synced_at?;
+        let age = if timestamp > synced_at {
+            timestamp - synced_at
+        } else {
+            Duration::ZERO
+        };
+
+        if age > self.config.max_holdover {
+            return None;
+        }
+
+        let drift = (age.total_micros() as f64 * self.config.drift_ppm / 1_000_000.0) as u64;
+        Some(Duration::from_micros(self.jitter + drift) + self.config.max_latency)
+    }
+
+    /// Returns the accuracy of the corrected clock, see [Clock::now], at `timestamp`, ie: the
+    /// bound of the error between the corrected time and the GNSS time, or [None] if unknown.
+    /// The estimated offset itself is corrected, only its uncertainty is accounted.
+    pub fn accuracy(&self, timestamp: Instant) -> Option<Duration> {
+        self.offset?;
+        self.uncertainty(timestamp)
+    }
+
+    /// Returns the system clock `timestamp` corrected with the estimated offset.
+    pub fn now(&self, timestamp: Instant) -> Instant {
+        match self.offset {
+            Some(offset) if offset >= 0 => timestamp + Duration::from_micros(offset.unsigned_abs()),
+            Some(offset) => timestamp - Duration::from_micros(offset.unsigned_abs()),
+            None => timestamp,
+        }
+    }
+
+    /// Returns the system clock `timestamp` corrected with the estimated offset,
+    /// as a TAI time.
+    pub fn tai2004(&self, timestamp: Instant) -> TAI2004 {
+        self.utc_to_tai(self.now(timestamp))
+    }
+
+    /// Converts the UTC `timestamp` to a TAI time, using the current TAI - UTC offset.
+    fn utc_to_tai(&self, timestamp: Instant) -> TAI2004 {
+        TAI2004::from_unix_instant_with_offset(timestamp, self.tai_utc_offset)
+    }
+
+    /// Return the instant at which the clock service should be polled next, ie: the
+    /// next leap second announced in the table.
+    pub fn poll_at(&self, timestamp: Instant) -> Option<Instant> {
+        self.leap_seconds
+            .as_ref()
+            .and_then(|t| t.next_change(timestamp))
+    }
+
+    /// Poll the clock service. Applies the TAI - UTC offset of the leap seconds table
+    /// at `timestamp`.
+    pub fn poll(&mut self, timestamp: Instant) {
+        let Some(table) = &self.leap_seconds else {
+            return;
+        };
+
+        if let Some(offset) = table.offset_at(timestamp) {
+            if offset != self.tai_utc_offset {
+                net_debug!("TAI - UTC offset set to {}s", offset);
+                self.tai_utc_offset = offset;
+            }
+        }
+
+        if !self.expiration_reported && table.is_expired(timestamp) {
+            net_warn!("leap seconds table is expired");
+            self.expiration_reported = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static LEAP_SECONDS_LIST: &str = "\
+#@	3960057600
+3644697600	36	# 1 Jul 2015
+3692217600	37	# 1 Jan 2017
+";
+
+    /// 01-01-2020 00:00:00 UTC.
+    const NOW: Instant = Instant::from_micros_const(1_577_836_800_000_000);
+
+    #[test]
+    fn test_offset_estimation() {
+        let mut clock = Clock::new(Config::default());
+        assert_eq!(clock.accuracy(NOW), None);
+        assert_eq!(clock.now(NOW), NOW);
+
+        // System clock is 40 ms late.
+        let mut timestamp = NOW;
+        for _ in 0..20 {
+            let gnss = TAI2004::from_unix_instant(timestamp + Duration::from_millis(40));
+            clock.notify_gnss_time(gnss, timestamp);
+            timestamp += Duration::from_secs(1);
+        }
+
+        assert_eq!(clock.offset_micros(), Some(40_000));
+        let accuracy = clock.accuracy(timestamp).unwrap();
+        assert!(accuracy >= Duration::from_millis(200));
+        assert!(accuracy < Duration::from_millis(201));
+        assert_eq!(clock.now(timestamp), timestamp + Duration::from_millis(40));
+
+        // Noisy sample.
+        let gnss = TAI2004::from_unix_instant(timestamp + Duration::from_millis(140));
+        clock.notify_gnss_time(gnss, timestamp);
+        assert_eq!(clock.offset_micros(), Some(50_000));
+        assert_eq!(
+            clock.uncertainty(timestamp),
+            Some(Duration::from_millis(210))
+        );
+
+        // System clock stepped.
+        let gnss = TAI2004::from_unix_instant(timestamp - Duration::from_secs(5));
+        clock.notify_gnss_time(gnss, timestamp);
+        assert_eq!(clock.offset_micros(), Some(-5_000_000));
+        assert_eq!(
+            clock.uncertainty(timestamp),
+            Some(Duration::from_millis(200))
+        );
+
+        // Corrected time is accurate, despite the offset.
+        assert_eq!(clock.accuracy(timestamp), Some(Duration::from_millis(200)));
+        assert_eq!(clock.now(timestamp), timestamp - Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_holdover() {
+        let mut clock = Clock::new(Config::default());
+        clock.notify_gnss_time(TAI2004::from_unix_instant(NOW), NOW);
+
+        // 50 ppm drift during 100 seconds.
+        let later = NOW + Duration::from_secs(100);
+        assert_eq!(clock.uncertainty(later), Some(Duration::from_millis(205)));
+
+        let expired = NOW + Duration::from_secs(601);
+        assert_eq!(clock.uncertainty(expired), None);
+        assert_eq!(clock.accuracy(expired), None);
+    }
+
+    #[test]
+    fn test_leap_seconds() {
+        let mut clock = Clock::new(Config::default());
+        let table = LeapSeconds::parse(LEAP_SECONDS_LIST).unwrap();
+
+        assert_eq!(clock.tai_utc_offset(), 37);
+        clock.set_leap_seconds(table.clone(), NOW);
+        assert_eq!(clock.tai_utc_offset(), 37);
+        assert_eq!(clock.poll_at(NOW), None);
+        assert!(!clock.expiration_reported);
+
+        // 01-07-2015.
+        let y2015 = Instant::from_secs(1_435_708_800);
+        assert_eq!(
+            clock.poll_at(y2015),
+            Some(Instant::from_secs(1_483_228_800))
+        );
+
+        // 28-06-2025.
+        clock.poll(Instant::from_secs(1_751_068_800));
+        assert!(clock.expiration_reported);
+
+        // Each clock keeps its own offset. 01-01-2016.
+        let y2016 = Instant::from_secs(1_451_606_400);
+        let mut other = Clock::new(Config::default());
+        other.set_leap_seconds(table, y2016);
+        assert_eq!(other.tai_utc_offset(), 36);
+        assert_eq!(clock.tai_utc_offset(), 37);
+        assert_eq!(
+            clock.tai2004(y2016) - other.tai2004(y2016),
+            Duration::from_secs(1)
+        );
+        assert_eq!(other.tai2004(y2016).as_unix_instant_with_offset(36), y2016);
+    }
+
+    #[test]
+    fn test_tai2004() {
+        let mut clock = Clock::new(Config::default());
+        assert_eq!(clock.tai2004(NOW), TAI2004::from_unix_instant(NOW));
+
+        // System clock is 40 ms late.
+        let gnss = TAI2004::from_unix_instant(NOW + Duration::from_millis(40));
+        clock.notify_gnss_time(gnss, NOW);
+        assert_eq!(clock.tai2004(NOW), gnss);
+    }
+}
@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::Instant;
+
+/// Time difference between 01-01-1900 00:00:00 UTC (NTP epoch) and 01-01-1970 00:00:00 UTC.
+const NTP_UNIX_DIFF: i64 = 2_208_988_800;
+
+/// Error returned when parsing a leap seconds table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LeapSecondsError {
+    /// Malformed line, at the given line number.
+    Malformed(usize),
+    /// Table does not contain any leap second.
+    Empty,
+}
+
+impl fmt::Display for LeapSecondsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeapSecondsError::Malformed(line) => write!(f, "malformed line {}", line),
+            LeapSecondsError::Empty => write!(f, "no leap second"),
+        }
+    }
+}
+
+/// A leap second table entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    /// Unix instant from which the offset applies.
+    since: Instant,
+    /// TAI - UTC offset, in seconds.
+    offset: u32,
+}
+
+/// Leap seconds table, ie: the history of the TAI - UTC offset, as published by the IERS in
+/// the `leap-seconds.list` file, available at https://hpiers.obspm.fr/iers/bul/bulc/ntp/leap-seconds.list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeapSeconds {
+    /// Table entries, sorted by date.
+    entries: Vec<Entry>,
+    /// Expiration date of the table.
+    expires_at: Option<Instant>,
+}
+
+impl LeapSeconds {
+    /// Parses the content of a `leap-seconds.list` file.
+    pub fn parse(content: &str) -> Result<LeapSeconds, LeapSecondsError> {
+        let mut entries = Vec::new();
+        let mut expires_at = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_num = index + 1;
+            let line = line.trim();
+
+            if let Some(expiration) = line.strip_prefix("#@") {
+                let ntp = expiration
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| LeapSecondsError::Malformed(line_num))?;
+                expires_at = Some(Instant::from_secs(ntp - NTP_UNIX_DIFF));
+                continue;
+            }
+
+            // Strip comments.
+            let data = line.split('#').next().unwrap_or_default();
+            let mut fields = data.split_whitespace();
+            let (Some(ntp), Some(offset)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+
+            let (Ok(ntp), Ok(offset)) = (ntp.parse::<i64>(), offset.parse::<u32>()) else {
+                return Err(LeapSecondsError::Malformed(line_num));
+            };
+
+            entries.push(Entry {
+                since: Instant::from_secs(ntp - NTP_UNIX_DIFF),
+                offset,
+            });
+        }
+
+        if entries.is_empty() {
+            return Err(LeapSecondsError::Empty);
+        }
+
+        entries.sort_by_key(|e| e.since);
+
+        Ok(LeapSeconds {
+            entries,
+            expires_at,
+        })
+    }
+
+    /// Return the TAI - UTC offset in seconds applicable at the `unix` instant.
+    /// Returns [None] if `unix` is before the first leap second of the table.
+    pub fn offset_at(&self, unix: Instant) -> Option<u32> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.since <= unix)
+            .map(|e| e.offset)
+    }
+
+    /// Return the instant of the next offset change after the `unix` instant, if announced.
+    pub fn next_change(&self, unix: Instant) -> Option<Instant> {
+        self.entries
+            .iter()
+            .find(|e| e.since > unix)
+            .map(|e| e.since)
+    }
+
+    /// Return the expiration date of the table, if any.
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.expires_at
+    }
+
+    /// Query whether the table is expired at the `unix` instant, ie: a leap second may
+    /// have been announced after its publication.
+    pub fn is_expired(&self, unix: Instant) -> bool {
+        self.expires_at.is_some_and(|exp| unix >= exp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Duration;
+
+    static LEAP_SECONDS_LIST: &str = "\
+#	Updated through IERS Bulletin C 68
+#	File expires on:  28 June 2025
+#
+#$	 3913697179
+#@	3960057600
+#
+2272060800	10	# 1 Jan 1972
+2287785600	11	# 1 Jul 1972
+3124137600	32	# 1 Jan 1999
+3345062400	33	# 1 Jan 2006
+3439756800	34	# 1 Jan 2009
+3550089600	35	# 1 Jul 2012
+3644697600	36	# 1 Jul 2015
+3692217600	37	# 1 Jan 2017
+#
+#h	16edd0f0 3666784f 37db6bdd e74ced87 59af48f1
+";
+
+    #[test]
+    fn test_parse() {
+        let table = LeapSeconds::parse(LEAP_SECONDS_LIST).unwrap();
+
+        // 01-01-2004.
+        let y2004 = Instant::from_secs(1_072_915_200);
+        assert_eq!(table.offset_at(y2004), Some(32));
+        assert_eq!(
+            table.next_change(y2004),
+            Some(Instant::from_secs(1_136_073_600))
+        );
+
+        // 01-01-2017, right before and at the leap second.
+        let y2017 = Instant::from_secs(1_483_228_800);
+        assert_eq!(table.offset_at(y2017 - Duration::from_secs(1)), Some(36));
+        assert_eq!(table.offset_at(y2017), Some(37));
+        assert_eq!(table.next_change(y2017), None);
+
+        assert_eq!(table.offset_at(Instant::ZERO), None);
+
+        // 28-06-2025.
+        assert_eq!(table.expires_at(), Some(Instant::from_secs(1_751_068_800)));
+        assert!(!table.is_expired(y2017));
+        assert!(table.is_expired(Instant::from_secs(1_751_068_800)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            LeapSeconds::parse("# only comments\n"),
+            Err(LeapSecondsError::Empty)
+        );
+        assert_eq!(
+            LeapSeconds::parse("2272060800\t10\n2287785600\televen\n"),
+            Err(LeapSecondsError::Malformed(2))
+        );
+    }
+}
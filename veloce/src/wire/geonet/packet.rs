@@ -15,6 +15,8 @@ use crate::wire::{
 
 #[cfg(feature = "proto-security")]
 use crate::security::{permission::Permission, secured_message::SecuredMessage};
+#[cfg(feature = "proto-security")]
+use crate::wire::BHNextHeader;
 
 use super::{Address, SequenceNumber, TrafficClass};
 
@@ -59,8 +61,13 @@ pub enum Repr<T: PacketBufferMeta> {
     /// along with its secured emitted representation (which does not contain the basic header).
     Secured { repr: T, encapsulated: Vec<u8> },
     #[cfg(feature = "proto-security")]
-    /// Packet to be secured (has to be signed),
-    ToSecure { repr: T, permission: Permission },
+    /// Packet to be secured (has to be signed), and encrypted
+    /// for its destination if `confidential` is set.
+    ToSecure {
+        repr: T,
+        permission: Permission,
+        confidential: bool,
+    },
 }
 
 impl<T: PacketBufferMeta> Repr<T> {
@@ -424,6 +431,21 @@ impl Variant {
         }
     }
 
+    /// Sets the next header field of the Basic Header.
+    #[cfg(feature = "proto-security")]
+    pub(crate) fn set_next_header(&mut self, next_header: BHNextHeader) {
+        match self {
+            Self::Beacon(repr) => repr.basic_header.next_header = next_header,
+            Self::Unicast(repr) => repr.basic_header.next_header = next_header,
+            Self::Anycast(repr) => repr.basic_header.next_header = next_header,
+            Self::Broadcast(repr) => repr.basic_header.next_header = next_header,
+            Self::SingleHopBroadcast(repr) => repr.basic_header.next_header = next_header,
+            Self::TopoBroadcast(repr) => repr.basic_header.next_header = next_header,
+            Self::LocationServiceRequest(repr) => repr.basic_header.next_header = next_header,
+            Self::LocationServiceReply(repr) => repr.basic_header.next_header = next_header,
+        }
+    }
+
     /// Return the traffic class field of the packet.
     pub const fn traffic_class(&self) -> TrafficClass {
         match self {
@@ -739,9 +761,11 @@ pub(crate) fn unicast_to_variant_repr(unicast: &Repr<GeonetUnicast>) -> Repr<Var
         Repr::ToSecure {
             repr: u,
             permission,
+            confidential,
         } => Repr::ToSecure {
             repr: Variant::Unicast(u.to_owned()),
             permission: permission.to_owned(),
+            confidential: *confidential,
         },
     }
 }
@@ -0,0 +1,302 @@
+use byteorder::{ByteOrder, NetworkEndian};
+
+mod field {
+    use crate::wire::field::*;
+
+    /// UtCamChangeCurvature fields.
+    /// Curvature, in 1/10000 m-1.
+    pub const CURVATURE: Field = 0..2;
+
+    /// UtCamChangeSpeed fields.
+    /// Speed variation, in 0.01 m/s.
+    pub const SPEED_VARIATION: Field = 0..2;
+
+    /// UtCamSetAccelerationControlStatus fields.
+    /// Acceleration control bit string.
+    pub const ACCELERATION_CONTROL: usize = 0;
+
+    /// UtCamSetExteriorLightsStatus fields.
+    /// Exterior lights bit string.
+    pub const EXTERIOR_LIGHTS: usize = 0;
+
+    /// UtCamChangeHeading fields.
+    /// Heading variation, in 0.1 degree.
+    pub const HEADING_VARIATION: Field = 0..2;
+
+    /// UtCamSetDriveDirection fields.
+    /// Drive direction.
+    pub const DRIVE_DIRECTION: usize = 0;
+
+    /// UtCamChangeYawRate fields.
+    /// Yaw rate, in 0.01 degree/s.
+    pub const YAW_RATE: Field = 0..2;
+
+    /// UtCamSetStationType fields.
+    /// Station type.
+    pub const STATION_TYPE: usize = 0;
+
+    /// UtCamSetVehicleRole fields.
+    /// Vehicle role.
+    pub const VEHICLE_ROLE: usize = 0;
+
+    /// UtCamSetEmbarkationStatus fields.
+    /// Embarkation status.
+    pub const EMBARKATION_STATUS: usize = 0;
+
+    /// UtCamSetPtActivation fields.
+    /// Public transport activation type.
+    pub const PT_ACTIVATION_TYPE: usize = 0;
+    /// Length of the public transport activation data.
+    pub const PT_ACTIVATION_LEN: usize = 1;
+    /// Public transport activation data.
+    pub const PT_ACTIVATION_DATA: Rest = 2..;
+
+    /// UtCamSetDangerousGoods fields.
+    /// Dangerous goods type.
+    pub const DANGEROUS_GOODS: usize = 0;
+
+    /// UtCamSetLightBarSiren fields.
+    /// Light bar and siren bit string.
+    pub const LIGHT_BAR_SIREN: usize = 0;
+
+    /// UtCamEventInd fields.
+    /// Length of 'Packet' field.
+    pub const IND_PAYLOAD_LEN: Field = 0..2;
+    /// Packet Payload.
+    pub const IND_PAYLOAD: Rest = 2..;
+}
+
+/// A read/write wrapper around a UtCamTrigger packet.
+/// The content of the packet depends on the message type, hence the accessor
+/// to use. Bit strings are encoded with their first bit as the most significant
+/// bit of the octet.
+#[derive(Debug, PartialEq)]
+pub struct UtCamTrigger<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtCamTrigger<T> {
+    /// Create a raw octet buffer with a UtCamTrigger packet structure.
+    pub fn new(buffer: T) -> UtCamTrigger<T> {
+        UtCamTrigger { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Return the curvature field of a UtCamChangeCurvature packet.
+    #[inline]
+    pub fn curvature(&self) -> i16 {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_i16(&data[field::CURVATURE])
+    }
+
+    /// Return the speed variation field of a UtCamChangeSpeed packet.
+    #[inline]
+    pub fn speed_variation(&self) -> i16 {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_i16(&data[field::SPEED_VARIATION])
+    }
+
+    /// Return the acceleration control field of a UtCamSetAccelerationControlStatus packet.
+    #[inline]
+    pub fn acceleration_control(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::ACCELERATION_CONTROL]
+    }
+
+    /// Return the exterior lights field of a UtCamSetExteriorLightsStatus packet.
+    #[inline]
+    pub fn exterior_lights(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::EXTERIOR_LIGHTS]
+    }
+
+    /// Return the heading variation field of a UtCamChangeHeading packet.
+    #[inline]
+    pub fn heading_variation(&self) -> i16 {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_i16(&data[field::HEADING_VARIATION])
+    }
+
+    /// Return the drive direction field of a UtCamSetDriveDirection packet.
+    #[inline]
+    pub fn drive_direction(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::DRIVE_DIRECTION]
+    }
+
+    /// Return the yaw rate field of a UtCamChangeYawRate packet.
+    #[inline]
+    pub fn yaw_rate(&self) -> i16 {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_i16(&data[field::YAW_RATE])
+    }
+
+    /// Return the station type field of a UtCamSetStationType packet.
+    #[inline]
+    pub fn station_type(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::STATION_TYPE]
+    }
+
+    /// Return the vehicle role field of a UtCamSetVehicleRole packet.
+    #[inline]
+    pub fn vehicle_role(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::VEHICLE_ROLE]
+    }
+
+    /// Return the embarkation status field of a UtCamSetEmbarkationStatus packet.
+    #[inline]
+    pub fn embarkation_status(&self) -> bool {
+        let data = self.buffer.as_ref();
+        data[field::EMBARKATION_STATUS] != 0
+    }
+
+    /// Return the activation type field of a UtCamSetPtActivation packet.
+    #[inline]
+    pub fn pt_activation_type(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::PT_ACTIVATION_TYPE]
+    }
+
+    /// Return the activation data length field of a UtCamSetPtActivation packet.
+    #[inline]
+    pub fn pt_activation_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        data[field::PT_ACTIVATION_LEN].into()
+    }
+
+    /// Return the activation data field of a UtCamSetPtActivation packet.
+    #[inline]
+    pub fn pt_activation_data(&self) -> &[u8] {
+        let data = self.buffer.as_ref();
+        &data[field::PT_ACTIVATION_DATA][..self.pt_activation_len()]
+    }
+
+    /// Return the dangerous goods field of a UtCamSetDangerousGoods packet.
+    #[inline]
+    pub fn dangerous_goods(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::DANGEROUS_GOODS]
+    }
+
+    /// Return the light bar and siren field of a UtCamSetLightBarSiren packet.
+    #[inline]
+    pub fn light_bar_siren(&self) -> u8 {
+        let data = self.buffer.as_ref();
+        data[field::LIGHT_BAR_SIREN]
+    }
+}
+
+/// A read/write wrapper around a UtCamEventInd packet.
+#[derive(Debug, PartialEq)]
+pub struct UtCamEventInd<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtCamEventInd<T> {
+    /// Create a raw octet buffer with a UtCamEventInd packet structure.
+    pub fn new(buffer: T) -> UtCamEventInd<T> {
+        UtCamEventInd { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Return the payload length field.
+    #[inline]
+    pub fn payload_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_u16(&data[field::IND_PAYLOAD_LEN]).into()
+    }
+
+    /// Return the payload.
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        let data = self.buffer.as_ref();
+        &data[field::IND_PAYLOAD][..self.payload_len()]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> UtCamEventInd<T> {
+    /// Set the payload length field.
+    #[inline]
+    pub fn set_payload_len(&mut self, value: usize) {
+        let data = self.buffer.as_mut();
+        NetworkEndian::write_u16(&mut data[field::IND_PAYLOAD_LEN], value as u16);
+    }
+
+    /// Return a mutable pointer to the payload.
+    #[inline]
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let data = self.buffer.as_mut();
+        &mut data[field::IND_PAYLOAD]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_signed_fields() {
+        // UtCamChangeCurvature, UtCamChangeSpeed, UtCamChangeHeading and UtCamChangeYawRate
+        // payloads share the same layout.
+        let trigger = UtCamTrigger::new([0xfe, 0x0c]);
+        assert_eq!(trigger.curvature(), -500);
+        assert_eq!(trigger.speed_variation(), -500);
+        assert_eq!(trigger.heading_variation(), -500);
+        assert_eq!(trigger.yaw_rate(), -500);
+
+        let trigger = UtCamTrigger::new([0x01, 0xf4]);
+        assert_eq!(trigger.curvature(), 500);
+        assert_eq!(trigger.yaw_rate(), 500);
+    }
+
+    #[test]
+    fn test_octet_fields() {
+        let trigger = UtCamTrigger::new([0xa0]);
+        assert_eq!(trigger.acceleration_control(), 0xa0);
+        assert_eq!(trigger.exterior_lights(), 0xa0);
+        assert_eq!(trigger.drive_direction(), 0xa0);
+        assert_eq!(trigger.station_type(), 0xa0);
+        assert_eq!(trigger.vehicle_role(), 0xa0);
+        assert_eq!(trigger.dangerous_goods(), 0xa0);
+        assert_eq!(trigger.light_bar_siren(), 0xa0);
+        assert!(trigger.embarkation_status());
+        assert!(!UtCamTrigger::new([0x00]).embarkation_status());
+    }
+
+    #[test]
+    fn test_pt_activation() {
+        let trigger = UtCamTrigger::new([0x01, 0x03, 0xca, 0xfe, 0xde, 0xff]);
+        assert_eq!(trigger.pt_activation_type(), 0x01);
+        assert_eq!(trigger.pt_activation_len(), 3);
+        // Trailing octets are not part of the activation data.
+        assert_eq!(trigger.pt_activation_data(), &[0xca, 0xfe, 0xde]);
+        assert_eq!(trigger.into_inner()[5], 0xff);
+    }
+
+    #[test]
+    fn test_event_ind_round_trip() {
+        let payload = [0x02, 0x02, 0x00, 0x00, 0x00, 0x01];
+        let mut buf = vec![0u8; 2 + payload.len()];
+
+        let mut ind = UtCamEventInd::new(&mut buf);
+        ind.set_payload_len(payload.len());
+        ind.payload_mut().copy_from_slice(&payload);
+
+        assert_eq!(buf[..2], [0x00, 0x06]);
+        assert_eq!(buf[2..], payload);
+
+        let ind = UtCamEventInd::new(&buf);
+        assert_eq!(ind.payload_len(), payload.len());
+        assert_eq!(ind.payload(), &payload);
+    }
+}
@@ -4,8 +4,10 @@ use uom::si::length::centimeter;
 use crate::types::{tenth_of_microdegree, Distance, Latitude, Longitude};
 
 pub mod btp;
+pub mod cam;
 pub mod denm;
 pub mod geonet;
+pub mod security;
 
 /// Uppertester result values.
 pub enum Result {
@@ -26,6 +28,23 @@ enum_with_unknown! {
       UtChangePseudonym = 0x04,
       UtChangePseudonymResult = 0x05,
 
+      // CAM types.
+      UtCamTriggerResult = 0x21,
+      UtCamEventInd = 0x23,
+      UtCamChangeCurvature = 0x30,
+      UtCamChangeSpeed = 0x31,
+      UtCamSetAccelerationControlStatus = 0x32,
+      UtCamSetExteriorLightsStatus = 0x33,
+      UtCamChangeHeading = 0x34,
+      UtCamSetDriveDirection = 0x35,
+      UtCamChangeYawRate = 0x36,
+      UtCamSetStationType = 0x39,
+      UtCamSetVehicleRole = 0x3a,
+      UtCamSetEmbarkationStatus = 0x3b,
+      UtCamSetPtActivation = 0x3c,
+      UtCamSetDangerousGoods = 0x3d,
+      UtCamSetLightBarSiren = 0x3f,
+
       // DENM types.
       UtDenmTrigger = 0x10,
       UtDenmTriggerResult = 0x11,
@@ -44,6 +63,19 @@ enum_with_unknown! {
       UtGnTriggerTsb = 0x54,
       UtGnEventInd = 0x55,
 
+      // Security types.
+      // Veloce extensions, not defined by ETSI TR 103 099 nor used by the ETSI TS 103 096
+      // test suites, which select the AT certificate with UtInitialize and observe the
+      // verification results through the indications of the other primitives. Codes are
+      // taken in a range left unused by ETSI TR 103 099.
+      UtSecChangeAtCertificate = 0x90,
+      UtSecChangeAtCertificateResult = 0x91,
+      UtSecTrigger = 0x92,
+      UtSecTriggerResult = 0x93,
+      UtSecSetItsTime = 0x94,
+      UtSecSetItsTimeResult = 0x95,
+      UtSecEventInd = 0x96,
+
       // BTP types.
       UtBtpTriggerA = 0x70,
       UtBtpTriggerResult = 0x71,
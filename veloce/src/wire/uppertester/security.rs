@@ -0,0 +1,207 @@
+use byteorder::{ByteOrder, NetworkEndian};
+
+use crate::time::TAI2004;
+
+enum_with_unknown! {
+   /// Signer identifier type of the messages triggered with UtSecTrigger.
+   pub enum SignerType(u8) {
+      /// Messages are sent without security envelope.
+      Unsecured = 0x00,
+      /// Messages are signed and carry the AT certificate digest.
+      Digest = 0x01,
+      /// Messages are signed and carry the full AT certificate.
+      Certificate = 0x02,
+   }
+}
+
+enum_with_unknown! {
+   /// Verification result of a received secured packet, reported with UtSecEventInd.
+   pub enum VerificationResult(u8) {
+      Success = 0x00,
+      InvalidContent = 0x01,
+      FalseSignature = 0x02,
+      InvalidCertificate = 0x03,
+      RevokedCertificate = 0x04,
+      InsufficientPermissions = 0x05,
+      InconsistentChain = 0x06,
+      InvalidTimestamp = 0x07,
+      OffValidityPeriod = 0x08,
+      DuplicateMessage = 0x09,
+      InvalidMobilityData = 0x0a,
+      UnsignedMessage = 0x0b,
+      SignerCertificateNotFound = 0x0c,
+      SignerCertificateFalseSignature = 0x0d,
+      DecryptionError = 0x0e,
+      BlacklistedSigner = 0x0f,
+      Other = 0xff,
+   }
+}
+
+mod field {
+    use crate::wire::field::*;
+
+    /// UtSecChangeAtCertificate fields.
+    /// HashedId8 of the AT certificate to use.
+    pub const AT_HASHED_ID8: Field = 0..8;
+
+    /// UtSecTrigger fields.
+    /// Signer identifier type.
+    pub const TRIG_SIGNER: usize = 0;
+    /// Length of 'Payload' field.
+    pub const TRIG_PAYLOAD_LEN: Field = 1..3;
+    /// Packet Payload.
+    pub const TRIG_PAYLOAD: Rest = 3..;
+
+    /// UtSecSetItsTime fields.
+    /// ITS time, in milliseconds since 2004-01-01 00:00:00 UTC.
+    pub const ITS_TIME: Field = 0..8;
+
+    /// UtSecEventInd fields.
+    /// Verification result code.
+    pub const IND_RESULT: usize = 0;
+    /// HashedId8 of the signer certificate.
+    pub const IND_SIGNER: Field = 1..9;
+    /// Length of 'Packet' field.
+    pub const IND_PAYLOAD_LEN: Field = 9..11;
+    /// Packet Payload.
+    pub const IND_PAYLOAD: Rest = 11..;
+}
+
+/// A read/write wrapper around an UtSecChangeAtCertificate packet.
+#[derive(Debug, PartialEq)]
+pub struct UtSecChangeAtCertificate<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtSecChangeAtCertificate<T> {
+    /// Create a raw octet buffer with an UtSecChangeAtCertificate packet structure.
+    pub fn new(buffer: T) -> UtSecChangeAtCertificate<T> {
+        UtSecChangeAtCertificate { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Return the hashedId8 field.
+    #[inline]
+    pub fn hashed_id8(&self) -> &[u8] {
+        let data = self.buffer.as_ref();
+        &data[field::AT_HASHED_ID8]
+    }
+}
+
+/// A read/write wrapper around an UtSecTrigger packet.
+#[derive(Debug, PartialEq)]
+pub struct UtSecTrigger<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtSecTrigger<T> {
+    /// Create a raw octet buffer with an UtSecTrigger packet structure.
+    pub fn new(buffer: T) -> UtSecTrigger<T> {
+        UtSecTrigger { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Return the signer identifier type field.
+    #[inline]
+    pub fn signer_type(&self) -> SignerType {
+        let data = self.buffer.as_ref();
+        SignerType::from(data[field::TRIG_SIGNER])
+    }
+
+    /// Return the payload length field.
+    #[inline]
+    pub fn payload_len(&self) -> usize {
+        let data = self.buffer.as_ref();
+        NetworkEndian::read_u16(&data[field::TRIG_PAYLOAD_LEN]).into()
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> UtSecTrigger<&'a T> {
+    /// Return a pointer to the payload.
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        let data = self.buffer.as_ref();
+        &data[field::TRIG_PAYLOAD]
+    }
+}
+
+/// A read/write wrapper around an UtSecSetItsTime packet.
+#[derive(Debug, PartialEq)]
+pub struct UtSecSetItsTime<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtSecSetItsTime<T> {
+    /// Create a raw octet buffer with an UtSecSetItsTime packet structure.
+    pub fn new(buffer: T) -> UtSecSetItsTime<T> {
+        UtSecSetItsTime { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Return the ITS time field.
+    #[inline]
+    pub fn its_time(&self) -> TAI2004 {
+        let data = self.buffer.as_ref();
+        TAI2004::from_millis(NetworkEndian::read_i64(&data[field::ITS_TIME]))
+    }
+}
+
+/// A read/write wrapper around an UtSecEventInd packet.
+#[derive(Debug, PartialEq)]
+pub struct UtSecEventInd<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> UtSecEventInd<T> {
+    /// Create a raw octet buffer with an UtSecEventInd packet structure.
+    pub fn new(buffer: T) -> UtSecEventInd<T> {
+        UtSecEventInd { buffer }
+    }
+
+    /// Consume the header, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> UtSecEventInd<T> {
+    /// Set the verification result field.
+    #[inline]
+    pub fn set_result(&mut self, value: VerificationResult) {
+        let data = self.buffer.as_mut();
+        data[field::IND_RESULT] = value.into();
+    }
+
+    /// Set the signer hashedId8 field.
+    #[inline]
+    pub fn set_signer(&mut self, value: &[u8]) {
+        let data = self.buffer.as_mut();
+        data[field::IND_SIGNER].copy_from_slice(value);
+    }
+
+    /// Set the payload length field.
+    #[inline]
+    pub fn set_payload_len(&mut self, value: usize) {
+        let data = self.buffer.as_mut();
+        NetworkEndian::write_u16(&mut data[field::IND_PAYLOAD_LEN], value as u16);
+    }
+
+    /// Return a mutable pointer to the payload.
+    #[inline]
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        let data = self.buffer.as_mut();
+        &mut data[field::IND_PAYLOAD]
+    }
+}